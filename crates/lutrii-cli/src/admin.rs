@@ -0,0 +1,461 @@
+use anyhow::Result;
+use lutrii_merchant_registry::VerificationTier;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_program;
+
+use crate::client::{self, Client};
+
+pub fn init_platform(
+    client: &Client,
+    daily_volume_limit: u64,
+    fee_basis_points: u16,
+) -> Result<()> {
+    let signature = client.send(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::InitializePlatform {
+            platform_state: client::platform_state(),
+            authority: client.signer(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::InitializePlatform {
+            daily_volume_limit,
+            fee_basis_points,
+        },
+    )?;
+
+    println!("Platform initialized: {}", signature);
+    Ok(())
+}
+
+pub fn init_config(
+    client: &Client,
+    fee_wallet_usdc: Pubkey,
+    fee_wallet_usd1: Pubkey,
+    usdc_mint: Pubkey,
+    usd1_mint: Pubkey,
+) -> Result<()> {
+    let signature = client.send(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::InitializeConfig {
+            platform_state: client::platform_state(),
+            authority: client.signer(),
+            fee_wallet_usdc,
+            fee_wallet_usd1,
+            usdc_mint,
+            usd1_mint,
+            token_program: client.owner(&usdc_mint)?,
+        },
+        lutrii_recurring::instruction::InitializeConfig {},
+    )?;
+
+    println!("Platform fee wallets initialized: {}", signature);
+    Ok(())
+}
+
+pub fn update_config(
+    client: &Client,
+    usdc_mint: Pubkey,
+    usd1_mint: Pubkey,
+    new_authority: Option<Pubkey>,
+) -> Result<()> {
+    let signature = client.send(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::UpdateConfig {
+            platform_state: client::platform_state(),
+            authority: client.signer(),
+            usdc_mint,
+            usd1_mint,
+            token_program: client.owner(&usdc_mint)?,
+            audit_log: client.existing(client::admin_audit_log()),
+        },
+        lutrii_recurring::instruction::UpdateConfig { new_authority },
+    )?;
+
+    println!("Platform config updated: {}", signature);
+    Ok(())
+}
+
+pub fn schedule_config_change(
+    client: &Client,
+    fee_basis_points: Option<u16>,
+    daily_volume_limit: Option<u64>,
+    fee_wallet_usdc: Option<Pubkey>,
+    fee_wallet_usd1: Option<Pubkey>,
+    config_change_delay_seconds: Option<i64>,
+) -> Result<()> {
+    let signature = client.send(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::ScheduleConfigChange {
+            platform_state: client::platform_state(),
+            pending_config_change: client::pending_config_change(),
+            authority: client.signer(),
+            new_fee_wallet_usdc: fee_wallet_usdc,
+            new_fee_wallet_usd1: fee_wallet_usd1,
+            system_program: system_program::ID,
+            audit_log: client.existing(client::admin_audit_log()),
+        },
+        lutrii_recurring::instruction::ScheduleConfigChange {
+            fee_basis_points,
+            daily_volume_limit,
+            config_change_delay_seconds,
+        },
+    )?;
+
+    let pending: lutrii_recurring::PendingConfigChange =
+        client.account(&client::pending_config_change())?;
+    println!(
+        "Config change scheduled, executable at {}: {}",
+        pending.executable_at, signature
+    );
+    Ok(())
+}
+
+pub fn update_platform_params(
+    client: &Client,
+    fee_basis_points: u16,
+    min_fee: u64,
+    max_fee: u64,
+) -> Result<()> {
+    let signature = client.send(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::UpdatePlatformParams {
+            platform_state: client::platform_state(),
+            pending_config_change: client::pending_config_change(),
+            authority: client.signer(),
+            system_program: system_program::ID,
+            audit_log: client.existing(client::admin_audit_log()),
+        },
+        lutrii_recurring::instruction::UpdatePlatformParams {
+            fee_basis_points,
+            min_fee,
+            max_fee,
+        },
+    )?;
+
+    let pending: lutrii_recurring::PendingConfigChange =
+        client.account(&client::pending_config_change())?;
+    println!(
+        "Fee parameter change scheduled, executable at {}: {}",
+        pending.executable_at, signature
+    );
+    Ok(())
+}
+
+pub fn execute_config_change(client: &Client) -> Result<()> {
+    let signature = client.send(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::ExecuteConfigChange {
+            platform_state: client::platform_state(),
+            pending_config_change: client::pending_config_change(),
+        },
+        lutrii_recurring::instruction::ExecuteConfigChange {},
+    )?;
+
+    println!("Config change executed: {}", signature);
+    Ok(())
+}
+
+pub fn set_paused(client: &Client, pause_reason: Option<String>) -> Result<()> {
+    let accounts = lutrii_recurring::accounts::AdminAction {
+        platform_state: client::platform_state(),
+        authority: client.signer(),
+        audit_log: client.existing(client::admin_audit_log()),
+    };
+
+    let paused = pause_reason.is_some();
+    let signature = if let Some(reason) = pause_reason {
+        client.send(
+            lutrii_recurring::ID,
+            accounts,
+            lutrii_recurring::instruction::EmergencyPause { reason },
+        )?
+    } else {
+        client.send(
+            lutrii_recurring::ID,
+            accounts,
+            lutrii_recurring::instruction::EmergencyUnpause {},
+        )?
+    };
+
+    println!(
+        "Platform {}: {}",
+        if paused { "paused" } else { "unpaused" },
+        signature
+    );
+    Ok(())
+}
+
+pub fn set_token_program_pause(client: &Client, token_program: Pubkey, paused: bool) -> Result<()> {
+    let signature = client.send(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::AdminAction {
+            platform_state: client::platform_state(),
+            authority: client.signer(),
+            audit_log: client.existing(client::admin_audit_log()),
+        },
+        lutrii_recurring::instruction::SetTokenProgramPause {
+            token_program,
+            paused,
+        },
+    )?;
+
+    println!(
+        "Payments through {} {}: {}",
+        token_program,
+        if paused { "paused" } else { "resumed" },
+        signature
+    );
+    Ok(())
+}
+
+pub fn set_merchant_policy(client: &Client, allow_unverified_merchants: bool) -> Result<()> {
+    let signature = client.send(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::AdminAction {
+            platform_state: client::platform_state(),
+            authority: client.signer(),
+            audit_log: client.existing(client::admin_audit_log()),
+        },
+        lutrii_recurring::instruction::SetMerchantVerificationPolicy {
+            allow_unverified_merchants,
+        },
+    )?;
+
+    println!(
+        "Unverified merchants {}: {}",
+        if allow_unverified_merchants { "allowed" } else { "rejected" },
+        signature
+    );
+    Ok(())
+}
+
+pub fn set_caps(client: &Client, max_active: u64, max_daily_new: u32) -> Result<()> {
+    let signature = client.send(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::AdminAction {
+            platform_state: client::platform_state(),
+            authority: client.signer(),
+            audit_log: client.existing(client::admin_audit_log()),
+        },
+        lutrii_recurring::instruction::SetSubscriptionCaps {
+            max_active_subscriptions: max_active,
+            max_daily_new_subscriptions: max_daily_new,
+        },
+    )?;
+
+    println!(
+        "Subscription caps set ({} active, {} new per day): {}",
+        max_active, max_daily_new, signature
+    );
+    Ok(())
+}
+
+pub fn set_collection_window(client: &Client, seconds: i64) -> Result<()> {
+    let signature = client.send(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::AdminAction {
+            platform_state: client::platform_state(),
+            authority: client.signer(),
+            audit_log: client.existing(client::admin_audit_log()),
+        },
+        lutrii_recurring::instruction::SetCollectionWindow {
+            collection_window_seconds: seconds,
+        },
+    )?;
+
+    println!("Collection window set ({}s): {}", seconds, signature);
+    Ok(())
+}
+
+pub fn set_priority_reserve(client: &Client, bps: u16) -> Result<()> {
+    let signature = client.send(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::AdminAction {
+            platform_state: client::platform_state(),
+            authority: client.signer(),
+            audit_log: client.existing(client::admin_audit_log()),
+        },
+        lutrii_recurring::instruction::SetPriorityReserve {
+            priority_reserve_bps: bps,
+        },
+    )?;
+
+    println!("Priority volume reserve set ({} bps): {}", bps, signature);
+    Ok(())
+}
+
+pub fn deny_wallet(client: &Client, wallet: Pubkey) -> Result<()> {
+    let signature = client.send(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::AddToDenylist {
+            denylist_entry: client::denylist_entry(&wallet),
+            platform_state: client::platform_state(),
+            authority: client.signer(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::AddToDenylist { wallet },
+    )?;
+
+    println!("Wallet {} denylisted: {}", wallet, signature);
+    Ok(())
+}
+
+pub fn undeny_wallet(client: &Client, wallet: Pubkey) -> Result<()> {
+    let signature = client.send(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::RemoveFromDenylist {
+            denylist_entry: client::denylist_entry(&wallet),
+            platform_state: client::platform_state(),
+            authority: client.signer(),
+        },
+        lutrii_recurring::instruction::RemoveFromDenylist {},
+    )?;
+
+    println!("Wallet {} removed from denylist: {}", wallet, signature);
+    Ok(())
+}
+
+pub fn migrate_platform(client: &Client) -> Result<()> {
+    // Merge and close the legacy fee wallet config if it still exists
+    let legacy_config = client::platform_config();
+    let platform_config = client
+        .rpc
+        .get_account(&legacy_config)
+        .ok()
+        .map(|_| legacy_config);
+
+    let signature = client.send(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::MigratePlatformState {
+            platform_state: client::platform_state(),
+            platform_config,
+            authority: client.signer(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::MigratePlatformState {},
+    )?;
+
+    println!("Platform state migrated: {}", signature);
+    Ok(())
+}
+
+pub fn init_registry(client: &Client) -> Result<()> {
+    let signature = client.send(
+        lutrii_merchant_registry::ID,
+        lutrii_merchant_registry::accounts::InitializeRegistry {
+            registry_state: client::registry_state(),
+            authority: client.signer(),
+            system_program: system_program::ID,
+        },
+        lutrii_merchant_registry::instruction::InitializeRegistry {},
+    )?;
+
+    println!("Merchant registry initialized: {}", signature);
+    Ok(())
+}
+
+pub fn migrate_registry(client: &Client) -> Result<()> {
+    let signature = client.send(
+        lutrii_merchant_registry::ID,
+        lutrii_merchant_registry::accounts::MigrateRegistry {
+            registry_state: client::registry_state(),
+            authority: client.signer(),
+            system_program: system_program::ID,
+        },
+        lutrii_merchant_registry::instruction::MigrateRegistry {},
+    )?;
+
+    println!("Merchant registry migrated: {}", signature);
+    Ok(())
+}
+
+pub fn set_review_thresholds(
+    client: &Client,
+    min_payments: u32,
+    min_total_paid: u64,
+    min_subscription_age: i64,
+) -> Result<()> {
+    let signature = client.send(
+        lutrii_merchant_registry::ID,
+        lutrii_merchant_registry::accounts::UpdateReviewThresholds {
+            registry_state: client::registry_state(),
+            authority: client.signer(),
+            audit_log: client.existing(client::registry_admin_audit_log()),
+        },
+        lutrii_merchant_registry::instruction::UpdateReviewThresholds {
+            min_payments,
+            min_total_paid,
+            min_subscription_age,
+        },
+    )?;
+
+    println!("Review thresholds updated: {}", signature);
+    Ok(())
+}
+
+/// Open the admin audit logs of both programs, skipping any already open
+pub fn open_audit_logs(client: &Client) -> Result<()> {
+    if client.existing(client::admin_audit_log()).is_none() {
+        let signature = client.send(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::OpenAdminAuditLog {
+                platform_state: client::platform_state(),
+                audit_log: client::admin_audit_log(),
+                authority: client.signer(),
+                system_program: system_program::ID,
+            },
+            lutrii_recurring::instruction::OpenAdminAuditLog {},
+        )?;
+        println!("Platform audit log opened: {}", signature);
+    }
+
+    if client.existing(client::registry_admin_audit_log()).is_none() {
+        let signature = client.send(
+            lutrii_merchant_registry::ID,
+            lutrii_merchant_registry::accounts::OpenAdminAuditLog {
+                registry_state: client::registry_state(),
+                audit_log: client::registry_admin_audit_log(),
+                authority: client.signer(),
+                system_program: system_program::ID,
+            },
+            lutrii_merchant_registry::instruction::OpenAdminAuditLog {},
+        )?;
+        println!("Registry audit log opened: {}", signature);
+    }
+
+    Ok(())
+}
+
+pub fn approve_merchant(client: &Client, owner: Pubkey, tier: VerificationTier) -> Result<()> {
+    let signature = client.send(
+        lutrii_merchant_registry::ID,
+        admin_merchant_accounts(client, &owner),
+        lutrii_merchant_registry::instruction::ApproveMerchant { tier },
+    )?;
+
+    println!("Merchant {} set to {:?}: {}", owner, tier, signature);
+    Ok(())
+}
+
+pub fn suspend_merchant(client: &Client, owner: Pubkey, reason: String) -> Result<()> {
+    let signature = client.send(
+        lutrii_merchant_registry::ID,
+        admin_merchant_accounts(client, &owner),
+        lutrii_merchant_registry::instruction::SuspendMerchant { reason },
+    )?;
+
+    println!("Merchant {} suspended: {}", owner, signature);
+    Ok(())
+}
+
+fn admin_merchant_accounts(
+    client: &Client,
+    owner: &Pubkey,
+) -> lutrii_merchant_registry::accounts::AdminMerchantAction {
+    lutrii_merchant_registry::accounts::AdminMerchantAction {
+        merchant: client::merchant(owner),
+        registry_state: client::registry_state(),
+        authority: client.signer(),
+        audit_log: client.existing(client::registry_admin_audit_log()),
+    }
+}
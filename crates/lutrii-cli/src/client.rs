@@ -0,0 +1,122 @@
+use std::path::Path;
+
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use anyhow::{anyhow, Context, Result};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+
+/// RPC connection plus the signing keypair
+pub struct Client {
+    pub rpc: RpcClient,
+    pub payer: Keypair,
+}
+
+impl Client {
+    pub fn new(rpc_url: &str, keypair: &Path) -> Result<Self> {
+        let path = expand_home(keypair);
+        let payer = read_keypair_file(&path)
+            .map_err(|e| anyhow!("failed to read keypair {}: {}", path, e))?;
+
+        Ok(Self {
+            rpc: RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed()),
+            payer,
+        })
+    }
+
+    pub fn signer(&self) -> Pubkey {
+        self.payer.pubkey()
+    }
+
+    /// Fetch and deserialize an Anchor account
+    pub fn account<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<T> {
+        let account = self
+            .rpc
+            .get_account(address)
+            .with_context(|| format!("account {} not found", address))?;
+        Ok(T::try_deserialize(&mut account.data.as_slice())?)
+    }
+
+    /// `address` if an account exists there (for optional accounts)
+    pub fn existing(&self, address: Pubkey) -> Option<Pubkey> {
+        self.rpc.get_account(&address).ok().map(|_| address)
+    }
+
+    /// Owning program of an account (used to pick the token program for a mint)
+    pub fn owner(&self, address: &Pubkey) -> Result<Pubkey> {
+        Ok(self.rpc.get_account(address)?.owner)
+    }
+
+    /// Build, sign and confirm a single Anchor instruction
+    pub fn send(
+        &self,
+        program_id: Pubkey,
+        accounts: impl ToAccountMetas,
+        data: impl InstructionData,
+    ) -> Result<Signature> {
+        let ix = Instruction {
+            program_id,
+            accounts: accounts.to_account_metas(None),
+            data: data.data(),
+        };
+
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&self.payer.pubkey()),
+            &[&self.payer],
+            blockhash,
+        );
+        Ok(self.rpc.send_and_confirm_transaction(&tx)?)
+    }
+}
+
+fn expand_home(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{}/{}", home, rest),
+        _ => path.into_owned(),
+    }
+}
+
+// PDA helpers
+
+pub fn platform_state() -> Pubkey {
+    Pubkey::find_program_address(&[b"platform"], &lutrii_recurring::ID).0
+}
+
+/// Legacy fee wallet config, superseded by platform state v2
+pub fn platform_config() -> Pubkey {
+    Pubkey::find_program_address(&[b"platform_config"], &lutrii_recurring::ID).0
+}
+
+pub fn denylist_entry(wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"denylist", wallet.as_ref()], &lutrii_recurring::ID).0
+}
+
+pub fn admin_audit_log() -> Pubkey {
+    Pubkey::find_program_address(&[b"admin_audit_log"], &lutrii_recurring::ID).0
+}
+
+pub fn pending_config_change() -> Pubkey {
+    Pubkey::find_program_address(&[b"pending_config_change"], &lutrii_recurring::ID).0
+}
+
+pub fn registry_admin_audit_log() -> Pubkey {
+    Pubkey::find_program_address(&[b"admin_audit_log"], &lutrii_merchant_registry::ID).0
+}
+
+pub fn registry_state() -> Pubkey {
+    Pubkey::find_program_address(&[b"registry"], &lutrii_merchant_registry::ID).0
+}
+
+pub fn merchant(owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"merchant", owner.as_ref()],
+        &lutrii_merchant_registry::ID,
+    )
+    .0
+}
@@ -0,0 +1,244 @@
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anyhow::Result;
+use lutrii_common::Subscription;
+use lutrii_merchant_registry::Merchant;
+use lutrii_recurring::PlatformState;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::client::{self, Client};
+
+/// Byte offsets of the leading pubkeys in a serialized Subscription
+const SUBSCRIPTION_USER_OFFSET: usize = 8;
+const SUBSCRIPTION_MERCHANT_OFFSET: usize = 8 + 32;
+
+pub fn show_platform(client: &Client) -> Result<()> {
+    let address = client::platform_state();
+    let platform: PlatformState = client.account(&address)?;
+
+    println!("Platform state: {}", address);
+    println!("  Layout version:       {}", platform.version);
+    println!("  Authority:            {}", platform.authority);
+    println!("  Emergency pause:      {}", platform.emergency_pause);
+    if platform.emergency_pause {
+        println!("  Pause reason:         {}", platform.pause_reason());
+        println!("  Pause expires at:     {}", platform.pause_expires_at);
+    }
+    println!(
+        "  Fee:                  {} bps (min {}, max {})",
+        platform.fee_basis_points, platform.min_fee, platform.max_fee
+    );
+    println!("  SPL Token paused:     {}", platform.spl_token_paused);
+    println!("  Token-2022 paused:    {}", platform.token_2022_paused);
+    println!("  Unverified merchants: {}", platform.allow_unverified_merchants);
+    println!("  Daily volume limit:   {}", platform.daily_volume_limit);
+    println!("  Priority reserve:     {} bps", platform.priority_reserve_bps);
+    println!("  Volume (24h):         {}", platform.total_volume_24h);
+    println!("  Last volume reset:    {}", platform.last_volume_reset);
+    println!("  Failed tx count:      {}", platform.failed_tx_count);
+    println!("  Active subscriptions: {}", platform.total_subscriptions);
+    println!("  Total transactions:   {}", platform.total_transactions);
+    println!(
+        "  Subscription caps:    {} active, {} new per day (0 = unlimited)",
+        platform.max_active_subscriptions, platform.max_daily_new_subscriptions
+    );
+    println!("  New subs (window):    {}", platform.new_subscriptions_today);
+    println!(
+        "  Collection window:    {}s (0 = indefinitely)",
+        platform.collection_window_seconds
+    );
+    if platform.fee_wallets_configured() {
+        println!("  USDC fee wallet:      {}", platform.fee_wallet_usdc);
+        println!("  USD1 fee wallet:      {}", platform.fee_wallet_usd1);
+    } else {
+        println!("  Fee wallets:          not initialized");
+    }
+
+    Ok(())
+}
+
+pub fn show_merchant(client: &Client, owner: Pubkey) -> Result<()> {
+    let address = client::merchant(&owner);
+    let merchant: Merchant = client.account(&address)?;
+
+    println!("Merchant: {}", address);
+    println!("  Owner:                {}", merchant.owner);
+    println!("  Business name:        {}", merchant.business_name);
+    println!("  Category:             {}", merchant.category);
+    println!("  Webhook URL:          {}", merchant.webhook_url);
+    println!("  Tier:                 {:?}", merchant.verification_tier);
+    println!("  Community score:      {}", merchant.community_score);
+    println!(
+        "  Transactions:         {} ({} failed)",
+        merchant.total_transactions, merchant.failed_transactions
+    );
+    println!("  Volume:               {}", merchant.total_volume);
+    println!(
+        "  Premium badge:        {} (expires {})",
+        merchant.premium_badge_active, merchant.premium_badge_expires
+    );
+    println!("  Settlement token:     {}", merchant.settlement_token);
+    for token in &merchant.accepted_tokens[..merchant.accepted_tokens_count as usize] {
+        println!("  Accepted token:       {}", token);
+    }
+
+    Ok(())
+}
+
+/// Print both programs' admin audit logs, oldest entry first
+pub fn show_audit_log(client: &Client) -> Result<()> {
+    let platform: lutrii_recurring::AdminAuditLog = client.account(&client::admin_audit_log())?;
+    let registry: lutrii_merchant_registry::AdminAuditLog =
+        client.account(&client::registry_admin_audit_log())?;
+
+    for (name, trail) in [("Platform", &platform.trail), ("Registry", &registry.trail)] {
+        println!("{} admin actions: {} recorded", name, trail.total_actions);
+        for entry in trail.chronological() {
+            println!("  {:>12}  {:<20} {}", entry.timestamp, format!("{:?}", entry.action), entry.actor);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn show_subscription(client: &Client, address: Pubkey) -> Result<()> {
+    let subscription: Subscription = client.account(&address)?;
+    print_subscription(&address, &subscription);
+    Ok(())
+}
+
+pub fn list_subscriptions(
+    client: &Client,
+    user: Option<Pubkey>,
+    merchant: Option<Pubkey>,
+) -> Result<()> {
+    let mut filters = vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+        0,
+        Subscription::DISCRIMINATOR.to_vec(),
+    ))];
+    if let Some(user) = user {
+        filters.push(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+            SUBSCRIPTION_USER_OFFSET,
+            user.to_bytes().to_vec(),
+        )));
+    }
+    if let Some(merchant) = merchant {
+        filters.push(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+            SUBSCRIPTION_MERCHANT_OFFSET,
+            merchant.to_bytes().to_vec(),
+        )));
+    }
+
+    let accounts = client.rpc.get_program_accounts_with_config(
+        &lutrii_recurring::ID,
+        RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        },
+    )?;
+
+    let mut count = 0;
+    for (address, account) in accounts {
+        match Subscription::try_deserialize(&mut account.data.as_slice()) {
+            Ok(subscription) => {
+                print_subscription(&address, &subscription);
+                count += 1;
+            }
+            Err(e) => eprintln!("Skipping undecodable account {}: {}", address, e),
+        }
+    }
+
+    println!("{} subscriptions", count);
+    Ok(())
+}
+
+fn print_subscription(address: &Pubkey, subscription: &Subscription) {
+    let status = if subscription.force_cancelled {
+        "force-cancelled"
+    } else if !subscription.is_active {
+        "cancelled"
+    } else if subscription.is_paused {
+        "paused"
+    } else if subscription.delegation_deferred {
+        "trial"
+    } else {
+        "active"
+    };
+
+    println!("Subscription: {} [{}]", address, status);
+    println!("  Merchant name:        {}", subscription.merchant_name);
+    println!("  User:                 {}", subscription.user);
+    println!("  Merchant:             {}", subscription.merchant);
+    println!("  Mint:                 {}", subscription.mint);
+    println!(
+        "  Amount:               {} (original {})",
+        subscription.amount, subscription.original_amount
+    );
+    println!(
+        "  Frequency:            {}s",
+        subscription.frequency_seconds
+    );
+    println!("  Next payment:         {}", subscription.next_payment);
+    if subscription.billing_day != 0 {
+        println!(
+            "  Billing day:          {} (UTC offset {}s)",
+            subscription.billing_day, subscription.utc_offset_seconds
+        );
+    }
+    if subscription.billing_weekday != 0 {
+        println!(
+            "  Billing weekday:      {} (ISO, UTC offset {}s)",
+            subscription.billing_weekday, subscription.utc_offset_seconds
+        );
+    }
+    println!("  Missed payments:      {:?}", subscription.missed_payment_policy);
+    println!("  Schedule unit:        {:?}", subscription.schedule_unit);
+    println!("  Layout version:       {}", subscription.layout_version);
+    if subscription.max_slippage_bps != 0 {
+        println!("  Swap slippage cap:    {} bps", subscription.max_slippage_bps);
+    }
+    if subscription.claim_expires_slot != 0 {
+        println!(
+            "  Execution claim:      {} until slot {}",
+            subscription.execution_claimant, subscription.claim_expires_slot
+        );
+    }
+    println!("  Last payment:         {}", subscription.last_payment);
+    println!(
+        "  Payments:             {} (total {})",
+        subscription.payment_count, subscription.total_paid
+    );
+    println!(
+        "  Caps:                 {} per tx, {} lifetime",
+        subscription.max_per_transaction, subscription.lifetime_cap
+    );
+    println!("  Delegation healthy:   {}", subscription.delegation_healthy);
+    if subscription.escrow_funded {
+        println!("  Escrow-funded:        yes");
+    }
+    if subscription.setup_fee != 0 {
+        println!("  Setup fee:            {}", subscription.setup_fee);
+    }
+    if subscription.pending_proration != 0 {
+        println!("  Pending proration:    {}", subscription.pending_proration);
+    }
+    if subscription.discount_payments_remaining > 0 {
+        println!(
+            "  Promo discount:       {} off the next {} payments",
+            subscription.discount_amount, subscription.discount_payments_remaining
+        );
+    }
+    if subscription.retry_count > 0 {
+        println!(
+            "  Failed collections:   {} (next retry {})",
+            subscription.retry_count, subscription.next_retry_at
+        );
+    }
+    println!("  Created at:           {}", subscription.created_at);
+}
@@ -0,0 +1,319 @@
+//! Lutrii CLI
+//!
+//! Operator tooling for the Lutrii programs:
+//! - Platform and config initialization
+//! - Fee wallet rotation and authority transfer
+//! - Emergency pause/unpause
+//! - Compliance denylist management
+//! - Platform state layout migration
+//! - Merchant approval and suspension
+//! - Admin audit logs
+//! - Inspection of platform, subscription and merchant accounts
+
+mod admin;
+mod client;
+mod inspect;
+
+use std::path::PathBuf;
+
+use anyhow::{bail, Result};
+use clap::{Parser, Subcommand};
+use lutrii_merchant_registry::VerificationTier;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::client::Client;
+
+#[derive(Parser, Debug)]
+#[command(name = "lutrii", version, about = "Lutrii platform administration")]
+struct Cli {
+    /// Solana JSON-RPC endpoint
+    #[arg(
+        long,
+        global = true,
+        env = "LUTRII_RPC_URL",
+        default_value = "http://127.0.0.1:8899"
+    )]
+    rpc_url: String,
+
+    /// Signing keypair (platform or registry authority for admin commands)
+    #[arg(
+        long,
+        global = true,
+        env = "LUTRII_KEYPAIR",
+        default_value = "~/.config/solana/id.json"
+    )]
+    keypair: PathBuf,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Initialize global platform state (one-time)
+    InitPlatform {
+        /// Daily volume limit in base units
+        #[arg(long)]
+        daily_volume_limit: u64,
+        /// Platform fee in basis points (1-500)
+        #[arg(long)]
+        fee_basis_points: u16,
+    },
+
+    /// Initialize fee wallet config (one-time)
+    InitConfig {
+        #[arg(long)]
+        fee_wallet_usdc: Pubkey,
+        #[arg(long)]
+        fee_wallet_usd1: Pubkey,
+        #[arg(long)]
+        usdc_mint: Pubkey,
+        #[arg(long)]
+        usd1_mint: Pubkey,
+    },
+
+    /// Record settlement mints and/or transfer platform authority
+    UpdateConfig {
+        #[arg(long)]
+        usdc_mint: Pubkey,
+        #[arg(long)]
+        usd1_mint: Pubkey,
+        #[arg(long)]
+        new_authority: Option<Pubkey>,
+    },
+
+    /// Schedule a fee, volume limit or fee wallet change behind the timelock
+    ScheduleConfigChange {
+        #[arg(long)]
+        fee_basis_points: Option<u16>,
+        #[arg(long)]
+        daily_volume_limit: Option<u64>,
+        #[arg(long)]
+        fee_wallet_usdc: Option<Pubkey>,
+        #[arg(long)]
+        fee_wallet_usd1: Option<Pubkey>,
+        /// New delay for later changes, in seconds
+        #[arg(long)]
+        delay_seconds: Option<i64>,
+    },
+
+    /// Schedule a new platform fee rate and per-payment fee bounds
+    UpdatePlatformParams {
+        #[arg(long)]
+        fee_basis_points: u16,
+        #[arg(long)]
+        min_fee: u64,
+        #[arg(long)]
+        max_fee: u64,
+    },
+
+    /// Apply the scheduled config change once its delay has passed
+    ExecuteConfigChange,
+
+    /// Halt all payments system-wide until the pause duration lapses
+    Pause {
+        /// Why payments are halted, stored on the platform state
+        reason: String,
+    },
+
+    /// Resume payments after an emergency pause
+    Unpause,
+
+    /// Pause or resume payments through one token program (SPL Token or Token-2022)
+    SetTokenProgramPause {
+        token_program: Pubkey,
+        #[arg(long, action = clap::ArgAction::Set)]
+        paused: bool,
+    },
+
+    /// Allow or reject new subscriptions to unverified merchants
+    SetMerchantPolicy {
+        #[arg(long, action = clap::ArgAction::Set)]
+        allow_unverified: bool,
+    },
+
+    /// Set global subscription caps (0 = unlimited)
+    SetCaps {
+        #[arg(long, default_value_t = 0)]
+        max_active: u64,
+        #[arg(long, default_value_t = 0)]
+        max_daily_new: u32,
+    },
+
+    /// Set how long a due payment stays collectible (0 = indefinitely)
+    SetCollectionWindow {
+        #[arg(long)]
+        seconds: i64,
+    },
+
+    /// Reserve a share of the daily volume limit for Community-tier merchants
+    SetPriorityReserve {
+        #[arg(long)]
+        bps: u16,
+    },
+
+    /// Add a wallet to the compliance denylist
+    Deny { wallet: Pubkey },
+
+    /// Remove a wallet from the compliance denylist
+    Undeny { wallet: Pubkey },
+
+    /// Migrate platform state to the current account layout
+    MigratePlatform,
+
+    /// Initialize the merchant registry (one-time)
+    InitRegistry,
+
+    /// Migrate the merchant registry to the current account layout
+    MigrateRegistry,
+
+    /// Set the payment history and age a subscription needs before its owner may review
+    SetReviewThresholds {
+        #[arg(long)]
+        min_payments: u32,
+        /// Total paid, in token base units
+        #[arg(long)]
+        min_total_paid: u64,
+        /// Subscription age, in seconds
+        #[arg(long)]
+        min_age: i64,
+    },
+
+    /// Open the platform and registry admin audit logs (one-time)
+    OpenAuditLogs,
+
+    /// Approve a merchant at the given tier
+    ApproveMerchant {
+        /// Merchant owner wallet
+        owner: Pubkey,
+        /// unverified | verified | suspended
+        #[arg(long, default_value = "verified")]
+        tier: String,
+    },
+
+    /// Suspend a merchant
+    SuspendMerchant {
+        /// Merchant owner wallet
+        owner: Pubkey,
+        #[arg(long)]
+        reason: String,
+    },
+
+    /// Show platform state and fee config
+    ShowPlatform,
+
+    /// Show recorded admin actions of both programs
+    ShowAuditLog,
+
+    /// Show a merchant by owner wallet
+    ShowMerchant { owner: Pubkey },
+
+    /// Show a subscription by address
+    ShowSubscription { address: Pubkey },
+
+    /// List subscriptions, optionally filtered by user or merchant
+    ListSubscriptions {
+        #[arg(long)]
+        user: Option<Pubkey>,
+        #[arg(long)]
+        merchant: Option<Pubkey>,
+    },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let client = Client::new(&cli.rpc_url, &cli.keypair)?;
+
+    match cli.command {
+        Command::InitPlatform {
+            daily_volume_limit,
+            fee_basis_points,
+        } => admin::init_platform(&client, daily_volume_limit, fee_basis_points),
+        Command::InitConfig {
+            fee_wallet_usdc,
+            fee_wallet_usd1,
+            usdc_mint,
+            usd1_mint,
+        } => admin::init_config(
+            &client,
+            fee_wallet_usdc,
+            fee_wallet_usd1,
+            usdc_mint,
+            usd1_mint,
+        ),
+        Command::UpdateConfig {
+            usdc_mint,
+            usd1_mint,
+            new_authority,
+        } => admin::update_config(&client, usdc_mint, usd1_mint, new_authority),
+        Command::ScheduleConfigChange {
+            fee_basis_points,
+            daily_volume_limit,
+            fee_wallet_usdc,
+            fee_wallet_usd1,
+            delay_seconds,
+        } => admin::schedule_config_change(
+            &client,
+            fee_basis_points,
+            daily_volume_limit,
+            fee_wallet_usdc,
+            fee_wallet_usd1,
+            delay_seconds,
+        ),
+        Command::UpdatePlatformParams {
+            fee_basis_points,
+            min_fee,
+            max_fee,
+        } => admin::update_platform_params(&client, fee_basis_points, min_fee, max_fee),
+        Command::ExecuteConfigChange => admin::execute_config_change(&client),
+        Command::Pause { reason } => admin::set_paused(&client, Some(reason)),
+        Command::Unpause => admin::set_paused(&client, None),
+        Command::SetTokenProgramPause {
+            token_program,
+            paused,
+        } => admin::set_token_program_pause(&client, token_program, paused),
+        Command::SetMerchantPolicy { allow_unverified } => {
+            admin::set_merchant_policy(&client, allow_unverified)
+        }
+        Command::SetCaps {
+            max_active,
+            max_daily_new,
+        } => admin::set_caps(&client, max_active, max_daily_new),
+        Command::SetCollectionWindow { seconds } => admin::set_collection_window(&client, seconds),
+        Command::SetPriorityReserve { bps } => admin::set_priority_reserve(&client, bps),
+        Command::Deny { wallet } => admin::deny_wallet(&client, wallet),
+        Command::Undeny { wallet } => admin::undeny_wallet(&client, wallet),
+        Command::MigratePlatform => admin::migrate_platform(&client),
+        Command::InitRegistry => admin::init_registry(&client),
+        Command::MigrateRegistry => admin::migrate_registry(&client),
+        Command::SetReviewThresholds {
+            min_payments,
+            min_total_paid,
+            min_age,
+        } => admin::set_review_thresholds(&client, min_payments, min_total_paid, min_age),
+        Command::OpenAuditLogs => admin::open_audit_logs(&client),
+        Command::ApproveMerchant { owner, tier } => {
+            admin::approve_merchant(&client, owner, parse_tier(&tier)?)
+        }
+        Command::SuspendMerchant { owner, reason } => {
+            admin::suspend_merchant(&client, owner, reason)
+        }
+        Command::ShowPlatform => inspect::show_platform(&client),
+        Command::ShowAuditLog => inspect::show_audit_log(&client),
+        Command::ShowMerchant { owner } => inspect::show_merchant(&client, owner),
+        Command::ShowSubscription { address } => inspect::show_subscription(&client, address),
+        Command::ListSubscriptions { user, merchant } => {
+            inspect::list_subscriptions(&client, user, merchant)
+        }
+    }
+}
+
+fn parse_tier(tier: &str) -> Result<VerificationTier> {
+    match tier.to_ascii_lowercase().as_str() {
+        "unverified" => Ok(VerificationTier::Unverified),
+        "verified" => Ok(VerificationTier::Verified),
+        "suspended" => Ok(VerificationTier::Suspended),
+        "community" => bail!("Community tier is auto-earned and cannot be set manually"),
+        other => bail!("unknown tier '{}'", other),
+    }
+}
@@ -0,0 +1,581 @@
+//! Lutrii Shared Account Types
+//!
+//! Single source of truth for account layouts that more than one program
+//! reads. lutrii-recurring owns and writes these accounts; lutrii-merchant-registry
+//! deserializes them for review eligibility checks.
+//!
+//! Also the interface for third-party programs gating on a subscription:
+//! `verify_active_subscription` CPIs into lutrii-recurring and decodes its answer.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::clock::DEFAULT_MS_PER_SLOT;
+
+// `#[account]` resolves the owning program through `crate::ID`, so this crate
+// declares the lutrii-recurring program ID. Both IDs must stay in sync.
+declare_id!("146BGDDLG4yRYXfNCCDdRRmCAYTrGddCgY14n4ekxJyF");
+
+// Constants
+pub const MAX_MERCHANT_NAME_LEN: usize = 32;
+
+// ============================================================================
+// Event Schema
+// ============================================================================
+
+/// Layout version carried as the first field of every Lutrii event
+///
+/// Evolution policy, so indexers keep decoding across upgrades:
+/// - Fields are only ever appended to the end of an event
+/// - Every layout change bumps this version, once per release
+/// - Fields are never removed, reordered or retyped; that needs a new event
+///
+/// Readers decode `schema_version` first, then the fields that existed at
+/// that version, and ignore any trailing bytes from newer versions.
+///
+/// History:
+/// - 1: `schema_version` added to every event
+/// - 2: `PaymentRetryScheduled.failure`
+/// - 3: `MerchantPolicyUpdated.billing_anchor`
+/// - 4: `utc_offset_seconds` on events carrying a `next_payment`
+/// - 5: `PaymentExecuted.settlement_mint`
+/// - 6: `MerchantPolicyUpdated.swap_fallback`
+/// - 7: `PaymentSwapped.min_amount_out`
+/// - 8: `PaymentExecuted.promotional`
+/// - 9: `MerchantPolicyUpdated.fee_bearer`
+/// - 10: `gross_amount` on `PaymentExecuted` and `SetupFeeCharged`,
+///   `PaymentExecuted.fee_bearer`
+/// - 11: `SettlementSwept.held_in_reserve`
+/// - 12: `EmergencyPauseActivated.expires_at`
+/// - 13: `subscription` and `plan` on `ReviewSubmitted`
+pub const EVENT_SCHEMA_VERSION: u8 = 13;
+
+// ============================================================================
+// Account Structures
+// ============================================================================
+
+#[account]
+pub struct Subscription {
+    pub user: Pubkey,                      // 32
+    pub merchant: Pubkey,                  // 32
+    pub user_token_account: Pubkey,        // 32
+    pub merchant_token_account: Pubkey,    // 32
+    pub amount: u64,                       // 8
+    pub original_amount: u64,              // 8 - for variance check
+    pub frequency_seconds: i64,            // 8
+    pub last_payment: i64,                 // 8
+    pub next_payment: i64,                 // 8
+    pub total_paid: u64,                   // 8
+    pub payment_count: u32,                // 4
+    pub is_active: bool,                   // 1
+    pub is_paused: bool,                   // 1
+    pub payment_in_progress: bool,         // 1 - REENTRANCY GUARD
+    pub max_per_transaction: u64,          // 8
+    pub lifetime_cap: u64,                 // 8
+    pub merchant_name: String,             // 4 + 32
+    pub created_at: i64,                   // 8
+    pub bump: u8,                          // 1
+    pub delegation_healthy: bool,          // 1 - last check_delegation result
+    pub snoozed: bool,                     // 1 - snooze used this cycle
+    pub pending_proration: i64,            // 8 - applied to next payment (+charge / -credit)
+    pub plan: Pubkey,                      // 32 - merchant plan (default = custom terms)
+    pub retry_count: u8,                   // 1 - consecutive failed collections
+    pub next_retry_at: i64,                // 8 - backoff: no attempts before this
+    pub force_cancelled: bool,             // 1 - deactivated by the platform (compliance)
+    pub trial_ends_at: i64,                // 8 - first charge of a trial (0 = no trial)
+    pub delegation_deferred: bool,         // 1 - trial not yet converted, nothing approved
+    pub setup_fee: u64,                    // 8 - one-time fee charged at creation (in total_paid)
+    pub billing_day: u8,                   // 1 - day of month payments fall due (0 = every frequency_seconds)
+    pub utc_offset_seconds: i32,           // 4 - local time calendar due dates are computed in
+    pub missed_payment_policy: MissedPaymentPolicy, // 2 - handling of cycles not collected on time
+    pub layout_version: u8,                // 1 - account layout written at creation or migration
+    pub schedule_unit: ScheduleUnit,       // 1 - clock the schedule fields are measured on
+    pub execution_claimant: Pubkey,        // 32 - keeper holding the execution claim
+    pub claim_expires_slot: u64,           // 8 - claim lapses at this slot (0 = unclaimed)
+    pub max_slippage_bps: u16,             // 2 - swap slippage cap over the oracle price (0 = swap asset's)
+    pub mint: Pubkey,                      // 32 - token payments are taken in (default = migrated, not yet pinned)
+    pub fee_dust: u64,                     // 8 - platform fee dust carried to later payments
+    pub fee_exempt: bool,                  // 1 - no platform fee (set by the admin)
+    pub fee_bearer: FeeBearer,             // 1 - who pays the platform fee (merchant policy at creation)
+    pub last_failure_at: i64,              // 8 - unix time of the latest failed collection (0 = none)
+    pub pending_amount: u64,               // 8 - amount proposed by the merchant, awaiting the user (0 = none)
+    pub pending_amount_expires: i64,       // 8 - unix time the proposal lapses
+    pub nonce: u32,                        // 4 - tells apart a user's subscriptions to one merchant
+    pub discount_amount: u64,              // 8 - taken off each discounted payment (promo code)
+    pub discount_payments_remaining: u16,  // 2 - payments still discounted
+    pub escrow_funded: bool,               // 1 - prepaid through deposit_escrow; collections draw from the escrow
+    pub billing_weekday: u8,               // 1 - ISO day of week payments fall due (0 = none)
+}
+
+impl Subscription {
+    pub const MAX_NAME_LEN: usize = MAX_MERCHANT_NAME_LEN;
+    pub const SPACE: usize = 8 + // discriminator
+        32 + 32 + 32 + 32 + // pubkeys
+        8 + 8 + 8 + 8 + 8 + 8 + // u64/i64 fields
+        4 + 1 + 1 + 1 + 8 + 8 + // counters and bools (added +1 for payment_in_progress)
+        (4 + Self::MAX_NAME_LEN) + // string
+        8 + 1 + // created_at + bump
+        1 + 1 + // delegation_healthy + snoozed
+        8 + // pending_proration
+        32 + // plan
+        1 + 8 + // retry_count + next_retry_at
+        1 + // force_cancelled
+        8 + 1 + // trial_ends_at + delegation_deferred
+        8 + // setup_fee
+        1 + // billing_day
+        4 + // utc_offset_seconds
+        MissedPaymentPolicy::SPACE + // missed_payment_policy
+        1 + // layout_version
+        1 + // schedule_unit
+        32 + 8 + // execution_claimant + claim_expires_slot
+        2 + // max_slippage_bps
+        32 + // mint
+        8 + // fee_dust
+        1 + // fee_exempt
+        1 + // fee_bearer
+        8 + // last_failure_at
+        8 + 8 + // pending_amount + pending_amount_expires
+        4 + // nonce
+        8 + 2 + // discount_amount + discount_payments_remaining
+        1 + // escrow_funded
+        1; // billing_weekday
+
+    /// Account layout written by this build
+    ///
+    /// Bumped by releases that change how stored fields are read. Payments
+    /// refuse accounts on an older layout until `migrate_subscription` brings
+    /// them forward; accounts created before versioning read as 0.
+    ///
+    /// History:
+    /// - 1: `layout_version` added
+    /// - 2: `schedule_unit`
+    /// - 3: `execution_claimant`, `claim_expires_slot`
+    /// - 4: `max_slippage_bps`
+    /// - 5: `mint`
+    /// - 6: `fee_dust`
+    /// - 7: `fee_exempt`
+    /// - 8: `fee_bearer`
+    /// - 9: `last_failure_at`
+    /// - 10: `pending_amount`, `pending_amount_expires`
+    /// - 11: `nonce`
+    /// - 12: `discount_amount`, `discount_payments_remaining`
+    /// - 13: `escrow_funded`
+    /// - 14: `billing_weekday`
+    pub const CURRENT_VERSION: u8 = 14;
+
+    /// Last PDA seed of the subscription with `nonce`
+    ///
+    /// Empty for nonce 0, so a pair's first subscription keeps the
+    /// `[b"subscription", user, merchant]` address it had before nonces.
+    pub fn nonce_seed(nonce: u32) -> Vec<u8> {
+        match nonce {
+            0 => Vec::new(),
+            nonce => nonce.to_le_bytes().to_vec(),
+        }
+    }
+
+    /// Time the due date after a collection at `now` is computed from
+    ///
+    /// Accumulating subscriptions step from the cycle just collected, so a
+    /// backlog drains one cycle per execution; others restart from `now`.
+    pub fn next_cycle_base(&self, now: i64) -> i64 {
+        match self.missed_payment_policy {
+            MissedPaymentPolicy::Accumulate { .. } => self.next_payment,
+            MissedPaymentPolicy::Skip | MissedPaymentPolicy::AutoCancel => now,
+        }
+    }
+
+    /// Slippage a swap-settled payment may take, within the swap asset's `asset_max_bps`
+    pub fn swap_slippage_bps(&self, asset_max_bps: u16) -> u16 {
+        match self.max_slippage_bps {
+            0 => asset_max_bps,
+            bps => bps.min(asset_max_bps),
+        }
+    }
+
+    /// Whether payments may be taken in `mint`
+    ///
+    /// Subscriptions migrated from before the mint was stored pin the first
+    /// mint they are paid in, which the token account constraints already tie
+    /// to `user_token_account`.
+    pub fn pin_mint(&mut self, mint: &Pubkey) -> bool {
+        if self.mint == Pubkey::default() {
+            self.mint = *mint;
+        }
+        self.mint == *mint
+    }
+
+    /// Next payment as `(amount, due_at)` for revenue forecasts, if one is expected
+    ///
+    /// Paused, cancelled and slot-scheduled subscriptions have none.
+    pub fn forecast_entry(&self) -> Option<(u64, i64)> {
+        (self.is_active && !self.is_paused && self.schedule_unit == ScheduleUnit::Seconds)
+            .then_some((self.amount, self.next_payment))
+    }
+
+    /// Whether the free trial is still running at `now` (in `schedule_unit`)
+    pub fn is_in_trial(&self, now: i64) -> bool {
+        now < self.trial_ends_at
+    }
+
+    /// Amount change the user may still accept at `now` (unix time)
+    pub fn pending_amount_change(&self, now: i64) -> Option<u64> {
+        (self.pending_amount != 0 && now < self.pending_amount_expires).then_some(self.pending_amount)
+    }
+
+    /// `charge` less the promo discount, while discounted payments remain
+    pub fn discounted(&self, charge: u64) -> u64 {
+        if self.discount_payments_remaining > 0 {
+            charge.saturating_sub(self.discount_amount)
+        } else {
+            charge
+        }
+    }
+
+    /// Whether a keeper other than `executor` holds a live execution claim at `slot`
+    pub fn claimed_by_other(&self, executor: &Pubkey, slot: u64) -> bool {
+        slot < self.claim_expires_slot && self.execution_claimant != *executor
+    }
+}
+
+/// Clock a subscription's schedule is measured on
+///
+/// `frequency_seconds`, `next_payment`, `next_retry_at` and `trial_ends_at`
+/// count in this unit; `created_at` and `last_payment` are always unix time.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScheduleUnit {
+    /// Wall-clock unix seconds
+    #[default]
+    Seconds,
+    /// Slots, for deterministic on-chain timing (e.g. epoch-aligned billing)
+    Slots,
+}
+
+impl ScheduleUnit {
+    /// Current time on this clock
+    pub fn now(&self, clock: &Clock) -> i64 {
+        match self {
+            Self::Seconds => clock.unix_timestamp,
+            Self::Slots => clock.slot as i64,
+        }
+    }
+
+    /// A wall-clock duration expressed on this clock, at the target slot time
+    pub fn span(&self, seconds: i64) -> i64 {
+        match self {
+            Self::Seconds => seconds,
+            Self::Slots => seconds.saturating_mul(1_000) / DEFAULT_MS_PER_SLOT as i64,
+        }
+    }
+}
+
+/// Who pays the platform fee on a subscription's payments
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FeeBearer {
+    /// The fee comes out of the payment; the merchant receives amount − fee
+    #[default]
+    Merchant,
+    /// The fee is charged to the user on top; the merchant nets the full amount
+    User,
+}
+
+impl FeeBearer {
+    /// Amount taken from the user for a `charge` carrying a platform `fee`
+    pub fn user_outflow(&self, charge: u64, fee: u64) -> Option<u64> {
+        match self {
+            Self::Merchant => Some(charge),
+            Self::User => charge.checked_add(fee),
+        }
+    }
+
+    /// Amount the merchant receives for a `charge` carrying a platform `fee`
+    pub fn merchant_amount(&self, charge: u64, fee: u64) -> Option<u64> {
+        match self {
+            Self::Merchant => charge.checked_sub(fee),
+            Self::User => Some(charge),
+        }
+    }
+}
+
+/// How a subscription treats cycles the keeper did not collect on time
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MissedPaymentPolicy {
+    /// Skip cycles once the platform collection window closes
+    #[default]
+    Skip,
+    /// Collect missed cycles one execution at a time, keeping at most `max_cycles`
+    Accumulate { max_cycles: u8 },
+    /// Cancel the subscription once a cycle's collection window closes
+    AutoCancel,
+}
+
+impl MissedPaymentPolicy {
+    /// Serialized size (variant tag + largest payload)
+    pub const SPACE: usize = 1 + 1;
+
+    /// Largest backlog an accumulating subscription may keep
+    pub const MAX_ACCUMULATED_CYCLES: u8 = 12;
+
+    /// Whether the policy's parameters are within bounds
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::Accumulate { max_cycles } => {
+                (1..=Self::MAX_ACCUMULATED_CYCLES).contains(max_cycles)
+            }
+            Self::Skip | Self::AutoCancel => true,
+        }
+    }
+}
+
+// ============================================================================
+// Admin Audit Trail
+// ============================================================================
+
+/// Entries an admin audit log keeps before overwriting the oldest
+pub const ADMIN_AUDIT_LOG_CAPACITY: usize = 32;
+
+/// Kind of admin or governance action recorded in an audit log
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AdminAuditAction {
+    /// Unused slot
+    #[default]
+    None,
+    EmergencyPause,
+    EmergencyUnpause,
+    /// Emergency pause by the governance pause guardian
+    GuardianPause,
+    /// Payments through one token program paused or resumed
+    TokenProgramPause,
+    PauseDurationChange,
+    /// Platform fee policy or a merchant's negotiated fee schedule changed
+    FeeChange,
+    /// Subscription caps, collection window, priority reserve or
+    /// verification policy changed
+    LimitChange,
+    /// Fee wallets or settlement mints changed
+    ConfigUpdate,
+    /// Admin authority handed to a new key
+    AuthorityRotation,
+    /// Admin authority handed to an SPL Governance realm
+    GovernanceEnabled,
+    MerchantApproval,
+    MerchantSuspension,
+    /// Pauses and platform fees put under an M-of-N admin council
+    CouncilEnabled,
+}
+
+/// One recorded admin action
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AdminAuditEntry {
+    /// Key that signed the action
+    pub actor: Pubkey,
+    pub action: AdminAuditAction,
+    pub timestamp: i64,
+}
+
+impl AdminAuditEntry {
+    /// Serialized size
+    pub const SPACE: usize = 32 + 1 + 8;
+}
+
+/// Append-only ring buffer of admin actions
+///
+/// Each program keeps its own in an audit log PDA. Once full, each entry
+/// replaces the oldest; `total_actions` keeps counting, so a reader can
+/// tell how many entries were overwritten.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct AdminAuditTrail {
+    /// Slot the next entry is written to
+    pub next_index: u16,
+    /// Actions recorded since the log was opened
+    pub total_actions: u64,
+    pub entries: [AdminAuditEntry; ADMIN_AUDIT_LOG_CAPACITY],
+}
+
+impl AdminAuditTrail {
+    /// Serialized size
+    pub const SPACE: usize = 2 + 8 + AdminAuditEntry::SPACE * ADMIN_AUDIT_LOG_CAPACITY;
+
+    /// Append an entry, overwriting the oldest once full
+    pub fn record(&mut self, actor: Pubkey, action: AdminAuditAction, timestamp: i64) {
+        let index = self.next_index as usize % ADMIN_AUDIT_LOG_CAPACITY;
+        self.entries[index] = AdminAuditEntry {
+            actor,
+            action,
+            timestamp,
+        };
+        self.next_index = ((index + 1) % ADMIN_AUDIT_LOG_CAPACITY) as u16;
+        self.total_actions = self.total_actions.saturating_add(1);
+    }
+
+    /// Recorded entries, oldest first
+    pub fn chronological(&self) -> impl Iterator<Item = &AdminAuditEntry> {
+        let recorded = (self.total_actions as usize).min(ADMIN_AUDIT_LOG_CAPACITY);
+        let start = if recorded < ADMIN_AUDIT_LOG_CAPACITY {
+            0
+        } else {
+            self.next_index as usize
+        };
+        (0..recorded).map(move |offset| &self.entries[(start + offset) % ADMIN_AUDIT_LOG_CAPACITY])
+    }
+}
+
+// ============================================================================
+// Access Gating
+// ============================================================================
+
+/// Standing of a wallet's subscription to a merchant, from `verify_active_subscription`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessStatus {
+    /// Active and paid up through `paid_through`
+    Active,
+    /// In a free trial that has not ended
+    Trialing,
+    /// A payment is due and has not been collected yet
+    PaymentDue,
+    /// Paused by the user
+    Paused,
+    /// Cancelled by the user or the platform
+    Cancelled,
+    /// On a different plan than the one asked about
+    PlanMismatch,
+    /// The wallet has no subscription to the merchant
+    NotSubscribed,
+}
+
+impl AccessStatus {
+    /// Whether the subscriber should be let in
+    pub fn grants_access(&self) -> bool {
+        matches!(self, Self::Active | Self::Trialing)
+    }
+}
+
+/// Result of `verify_active_subscription`, returned through return data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SubscriptionAccess {
+    pub status: AccessStatus,
+    /// `status.grants_access()`, for callers that only need a yes or no
+    pub has_access: bool,
+    /// Subscription PDA of the wallet and merchant
+    pub subscription: Pubkey,
+    /// Merchant plan subscribed to (default = custom terms)
+    pub plan: Pubkey,
+    /// Token payments are taken in
+    pub mint: Pubkey,
+    /// Next due date, through which the subscription is paid (in `schedule_unit`)
+    pub paid_through: i64,
+    pub schedule_unit: ScheduleUnit,
+    pub total_paid: u64,
+    pub payment_count: u32,
+}
+
+impl SubscriptionAccess {
+    /// Serialized size; return data drops trailing zero bytes, so readers pad to it
+    pub const SPACE: usize = 1 + 1 + 32 + 32 + 32 + 8 + 1 + 8 + 4;
+
+    /// Answer for a wallet without a subscription at `subscription`
+    pub fn not_subscribed(subscription: Pubkey) -> Self {
+        Self {
+            status: AccessStatus::NotSubscribed,
+            has_access: false,
+            subscription,
+            plan: Pubkey::default(),
+            mint: Pubkey::default(),
+            paid_through: 0,
+            schedule_unit: ScheduleUnit::Seconds,
+            total_paid: 0,
+            payment_count: 0,
+        }
+    }
+}
+
+impl Subscription {
+    /// Access this subscription (at `address`) grants at `now`, on its own
+    /// clock, optionally only to subscribers of `plan`
+    pub fn access(&self, address: Pubkey, plan: Option<&Pubkey>, now: i64) -> SubscriptionAccess {
+        let status = if !self.is_active {
+            AccessStatus::Cancelled
+        } else if self.is_paused {
+            AccessStatus::Paused
+        } else if plan.is_some_and(|plan| *plan != self.plan) {
+            AccessStatus::PlanMismatch
+        } else if self.is_in_trial(now) {
+            AccessStatus::Trialing
+        } else if now < self.next_payment {
+            AccessStatus::Active
+        } else {
+            AccessStatus::PaymentDue
+        };
+        SubscriptionAccess {
+            status,
+            has_access: status.grants_access(),
+            subscription: address,
+            plan: self.plan,
+            mint: self.mint,
+            paid_through: self.next_payment,
+            schedule_unit: self.schedule_unit,
+            total_paid: self.total_paid,
+            payment_count: self.payment_count,
+        }
+    }
+}
+
+/// Subscription PDA of `user` and `merchant` (the pair's first subscription)
+pub fn subscription_address(user: &Pubkey, merchant: &Pubkey) -> Pubkey {
+    subscription_address_with_nonce(user, merchant, 0)
+}
+
+/// Subscription PDA of `user` and `merchant` created with `nonce`
+pub fn subscription_address_with_nonce(user: &Pubkey, merchant: &Pubkey, nonce: u32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"subscription", user.as_ref(), merchant.as_ref(), &Subscription::nonce_seed(nonce)],
+        &ID,
+    )
+    .0
+}
+
+/// `verify_active_subscription` instruction, for simulation or CPI
+pub fn verify_active_subscription_ix(
+    user: &Pubkey,
+    merchant: &Pubkey,
+    nonce: u32,
+    plan: Option<Pubkey>,
+) -> anchor_lang::solana_program::instruction::Instruction {
+    let preimage = "global:verify_active_subscription";
+    let mut data = anchor_lang::solana_program::hash::hash(preimage.as_bytes()).to_bytes()[..8].to_vec();
+    // Args are borsh fields in order, the same as a tuple
+    (*user, *merchant, nonce, plan)
+        .serialize(&mut data)
+        .expect("writing to a Vec cannot fail");
+    anchor_lang::solana_program::instruction::Instruction {
+        program_id: ID,
+        accounts: vec![AccountMeta::new_readonly(
+            subscription_address_with_nonce(user, merchant, nonce),
+            false,
+        )],
+        data,
+    }
+}
+
+/// Ask lutrii-recurring whether `user` holds an active, paid-up subscription
+/// to `merchant` (and to `plan`, if given)
+///
+/// For programs gating their own instructions on a subscription. Pass the
+/// account at `subscription_address_with_nonce(user, merchant, nonce)`,
+/// which need not exist, and the lutrii-recurring program account.
+pub fn verify_active_subscription<'info>(
+    subscription: &AccountInfo<'info>,
+    lutrii_program: &AccountInfo<'info>,
+    user: &Pubkey,
+    merchant: &Pubkey,
+    nonce: u32,
+    plan: Option<Pubkey>,
+) -> Result<SubscriptionAccess> {
+    let ix = verify_active_subscription_ix(user, merchant, nonce, plan);
+    anchor_lang::solana_program::program::invoke(&ix, &[subscription.clone(), lutrii_program.clone()])?;
+    let (program_id, mut data) = anchor_lang::solana_program::program::get_return_data()
+        .ok_or(ProgramError::InvalidAccountData)?;
+    if program_id != ID {
+        return Err(ProgramError::IncorrectProgramId.into());
+    }
+    data.resize(SubscriptionAccess::SPACE, 0);
+    SubscriptionAccess::deserialize(&mut &data[..]).map_err(|_| ProgramError::InvalidAccountData.into())
+}
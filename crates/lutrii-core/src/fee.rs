@@ -0,0 +1,199 @@
+use crate::{CoreError, CoreResult, BASIS_POINTS_DIVISOR};
+
+/// How a basis-point fee is rounded to whole base units
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rounding {
+    /// Round down; fractions of a base unit stay with the merchant
+    #[default]
+    Floor,
+    /// Round to the nearest base unit, halves up
+    HalfUp,
+}
+
+/// What happens to a fee below the dust threshold
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DustPolicy {
+    /// Not charged at all
+    #[default]
+    Waive,
+    /// Carried forward and charged once the carried total reaches the threshold
+    Accumulate,
+}
+
+/// Calculate platform fee with min/max capping, rounding down
+///
+/// Uses u128 for intermediate calculations to prevent overflow,
+/// then safely converts back to u64. The minimum is applied before the
+/// maximum, so `max_fee` always wins when the two conflict.
+pub fn calculate_fee(amount: u64, basis_points: u16, min_fee: u64, max_fee: u64) -> CoreResult<u64> {
+    calculate_fee_rounded(amount, basis_points, min_fee, max_fee, Rounding::Floor)
+}
+
+/// Calculate platform fee with min/max capping and the given rounding
+pub fn calculate_fee_rounded(
+    amount: u64,
+    basis_points: u16,
+    min_fee: u64,
+    max_fee: u64,
+    rounding: Rounding,
+) -> CoreResult<u64> {
+    let product = (amount as u128)
+        .checked_mul(basis_points as u128)
+        .ok_or(CoreError::Overflow)?;
+    let fee = match rounding {
+        Rounding::Floor => product / BASIS_POINTS_DIVISOR,
+        Rounding::HalfUp => (product + BASIS_POINTS_DIVISOR / 2) / BASIS_POINTS_DIVISOR,
+    };
+
+    let fee_u64 = u64::try_from(fee).map_err(|_| CoreError::Overflow)?;
+
+    Ok(fee_u64.max(min_fee).min(max_fee))
+}
+
+/// Fee charged on a payment of `amount` once the dust threshold is applied
+///
+/// `carried` is dust accumulated by earlier payments. Returns the fee to
+/// charge now and the dust to carry forward: fees below `threshold` are
+/// waived or carried, and a carried total that reaches the threshold is
+/// charged in full, up to `amount`.
+pub fn apply_dust_threshold(
+    amount: u64,
+    fee: u64,
+    carried: u64,
+    threshold: u64,
+    policy: DustPolicy,
+) -> CoreResult<(u64, u64)> {
+    let total = match policy {
+        DustPolicy::Waive => fee,
+        DustPolicy::Accumulate => fee.checked_add(carried).ok_or(CoreError::Overflow)?,
+    };
+    if total < threshold {
+        let carried = match policy {
+            DustPolicy::Waive => 0,
+            DustPolicy::Accumulate => total,
+        };
+        return Ok((0, carried));
+    }
+    let charged = total.min(amount);
+    Ok((charged, total - charged))
+}
+
+/// Amount the merchant receives after the fee is deducted
+pub fn merchant_amount(amount: u64, fee: u64) -> CoreResult<u64> {
+    amount.checked_sub(fee).ok_or(CoreError::InsufficientAmount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_fee_basis_points() {
+        // 2.5% of 10 USDC
+        assert_eq!(calculate_fee(10_000_000, 250, 0, u64::MAX), Ok(250_000));
+    }
+
+    #[test]
+    fn test_fee_min_and_max_capping() {
+        assert_eq!(calculate_fee(100_000, 250, 10_000, 500_000), Ok(10_000));
+        assert_eq!(calculate_fee(1_000_000_000, 250, 10_000, 500_000), Ok(500_000));
+    }
+
+    #[test]
+    fn test_fee_max_wins_over_min() {
+        assert_eq!(calculate_fee(1_000, 250, 10_000, 5_000), Ok(5_000));
+    }
+
+    #[test]
+    fn test_fee_large_amount_does_not_overflow() {
+        assert_eq!(
+            calculate_fee(u64::MAX, 500, 0, u64::MAX),
+            Ok(((u64::MAX as u128) * 500 / 10_000) as u64)
+        );
+    }
+
+    #[test]
+    fn test_fee_rounding() {
+        // 2.5% of 0.00002 USDC is 0.5 base units
+        assert_eq!(calculate_fee_rounded(20, 250, 0, u64::MAX, Rounding::Floor), Ok(0));
+        assert_eq!(calculate_fee_rounded(20, 250, 0, u64::MAX, Rounding::HalfUp), Ok(1));
+        // Below half rounds down either way
+        assert_eq!(calculate_fee_rounded(19, 250, 0, u64::MAX, Rounding::HalfUp), Ok(0));
+        assert_eq!(calculate_fee(20, 250, 0, u64::MAX), Ok(0));
+    }
+
+    #[test]
+    fn test_dust_waived() {
+        assert_eq!(apply_dust_threshold(1_000, 5, 0, 10, DustPolicy::Waive), Ok((0, 0)));
+        assert_eq!(apply_dust_threshold(1_000, 10, 0, 10, DustPolicy::Waive), Ok((10, 0)));
+        // Zero threshold charges every fee
+        assert_eq!(apply_dust_threshold(1_000, 1, 0, 0, DustPolicy::Waive), Ok((1, 0)));
+    }
+
+    #[test]
+    fn test_dust_accumulated() {
+        assert_eq!(apply_dust_threshold(1_000, 4, 0, 10, DustPolicy::Accumulate), Ok((0, 4)));
+        assert_eq!(apply_dust_threshold(1_000, 4, 4, 10, DustPolicy::Accumulate), Ok((0, 8)));
+        assert_eq!(apply_dust_threshold(1_000, 4, 8, 10, DustPolicy::Accumulate), Ok((12, 0)));
+        // Never charges more than the payment; the rest stays carried
+        assert_eq!(apply_dust_threshold(5, 4, 8, 10, DustPolicy::Accumulate), Ok((5, 7)));
+    }
+
+    #[test]
+    fn test_merchant_amount_insufficient() {
+        assert_eq!(merchant_amount(5_000, 10_000), Err(CoreError::InsufficientAmount));
+        assert_eq!(merchant_amount(10_000, 10_000), Ok(0));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_fee_within_bounds(
+            amount in any::<u64>(),
+            bps in 0u16..=10_000,
+            min_fee in 0u64..1_000_000,
+            spread in 0u64..1_000_000,
+        ) {
+            let max_fee = min_fee + spread;
+            let fee = calculate_fee(amount, bps, min_fee, max_fee).unwrap();
+            prop_assert!(fee >= min_fee && fee <= max_fee);
+        }
+
+        #[test]
+        fn prop_uncapped_fee_never_exceeds_amount(amount in any::<u64>(), bps in 0u16..=10_000) {
+            let fee = calculate_fee(amount, bps, 0, u64::MAX).unwrap();
+            prop_assert!(fee <= amount);
+            prop_assert_eq!(merchant_amount(amount, fee).unwrap() + fee, amount);
+        }
+
+        #[test]
+        fn prop_half_up_within_one_unit_of_floor(amount in any::<u64>(), bps in 0u16..=10_000) {
+            let floor = calculate_fee_rounded(amount, bps, 0, u64::MAX, Rounding::Floor).unwrap();
+            let half_up = calculate_fee_rounded(amount, bps, 0, u64::MAX, Rounding::HalfUp).unwrap();
+            prop_assert!(half_up == floor || half_up == floor + 1);
+            prop_assert!(half_up <= amount);
+        }
+
+        #[test]
+        fn prop_dust_conserved(
+            amount in any::<u64>(),
+            fee in 0u64..1_000_000,
+            carried in 0u64..1_000_000,
+            threshold in 0u64..1_000_000,
+        ) {
+            let (charged, left) =
+                apply_dust_threshold(amount, fee, carried, threshold, DustPolicy::Accumulate).unwrap();
+            prop_assert!(charged <= amount);
+            prop_assert_eq!(charged + left, fee + carried);
+        }
+
+        #[test]
+        fn prop_fee_monotonic_in_amount(a in any::<u64>(), b in any::<u64>(), bps in 0u16..=10_000) {
+            let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+            prop_assert!(
+                calculate_fee(lo, bps, 10_000, 500_000).unwrap()
+                    <= calculate_fee(hi, bps, 10_000, 500_000).unwrap()
+            );
+        }
+    }
+}
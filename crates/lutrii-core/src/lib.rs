@@ -0,0 +1,40 @@
+//! Lutrii Core
+//!
+//! Pure, allocation-free billing math used by both on-chain programs and the
+//! off-chain keeper/CLI, so every consumer charges exactly the same amounts:
+//! - Fee calculation with min/max capping, rounding and dust thresholds
+//! - Payment scheduling
+//! - Lifetime cap accounting
+//! - Time-based proration
+//! - Price variance checks
+//! - Oracle price parsing and swap bounds
+//!
+//! All arithmetic is checked; intermediate products use u128.
+
+#![cfg_attr(not(test), no_std)]
+
+pub mod fee;
+pub mod limits;
+pub mod oracle;
+pub mod proration;
+pub mod schedule;
+pub mod variance;
+
+// Constants
+pub const SECONDS_PER_DAY: i64 = 86_400;
+pub const BASIS_POINTS_DIVISOR: u128 = 10_000;
+
+/// Errors produced by core math
+///
+/// Programs map these onto their own error codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoreError {
+    /// Arithmetic overflowed or an intermediate value did not fit
+    Overflow,
+    /// Amount too small to cover the deduction taken from it
+    InsufficientAmount,
+    /// Oracle account is malformed, not trading or non-positive
+    InvalidPrice,
+}
+
+pub type CoreResult<T> = core::result::Result<T, CoreError>;
@@ -0,0 +1,84 @@
+use crate::{CoreError, CoreResult, BASIS_POINTS_DIVISOR};
+
+/// Total paid after one more payment of `amount`
+pub fn total_after_payment(total_paid: u64, amount: u64) -> CoreResult<u64> {
+    total_paid.checked_add(amount).ok_or(CoreError::Overflow)
+}
+
+/// Whether one more payment of `amount` stays within `lifetime_cap`
+pub fn within_lifetime_cap(total_paid: u64, amount: u64, lifetime_cap: u64) -> bool {
+    total_after_payment(total_paid, amount).is_ok_and(|total| total <= lifetime_cap)
+}
+
+/// Allowance left under the lifetime cap
+pub fn remaining_allowance(lifetime_cap: u64, total_paid: u64) -> u64 {
+    lifetime_cap.saturating_sub(total_paid)
+}
+
+/// Number of full payments of `amount` the remaining allowance still covers
+pub fn payments_remaining(lifetime_cap: u64, total_paid: u64, amount: u64) -> u64 {
+    remaining_allowance(lifetime_cap, total_paid)
+        .checked_div(amount)
+        .unwrap_or(0)
+}
+
+/// Daily volume a velocity class may fill
+///
+/// The top `priority_reserve_bps` of `daily_limit` is held back for priority
+/// merchants, so standard traffic runs out first when the limit is tight.
+pub fn class_volume_limit(daily_limit: u64, priority_reserve_bps: u16, priority: bool) -> u64 {
+    if priority {
+        return daily_limit;
+    }
+    let reserve = daily_limit as u128 * (priority_reserve_bps as u128).min(BASIS_POINTS_DIVISOR)
+        / BASIS_POINTS_DIVISOR;
+    daily_limit - reserve as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_lifetime_cap_boundary() {
+        assert!(within_lifetime_cap(90, 10, 100));
+        assert!(!within_lifetime_cap(91, 10, 100));
+        assert!(!within_lifetime_cap(u64::MAX, 1, u64::MAX));
+    }
+
+    #[test]
+    fn test_payments_remaining() {
+        assert_eq!(payments_remaining(100, 60, 10), 4);
+        assert_eq!(payments_remaining(100, 60, 0), 0);
+        assert_eq!(payments_remaining(50, 60, 10), 0);
+    }
+
+    #[test]
+    fn test_class_volume_limit() {
+        assert_eq!(class_volume_limit(1_000, 2_000, false), 800);
+        assert_eq!(class_volume_limit(1_000, 2_000, true), 1_000);
+        assert_eq!(class_volume_limit(1_000, 0, false), 1_000);
+        assert_eq!(class_volume_limit(1_000, u16::MAX, false), 0);
+        assert_eq!(class_volume_limit(u64::MAX, 5_000, false), u64::MAX - u64::MAX / 2);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_payments_remaining_all_fit_under_cap(
+            cap in 0u64..1_000_000_000_000,
+            paid in 0u64..1_000_000_000_000,
+            amount in 1u64..1_000_000_000,
+        ) {
+            let n = payments_remaining(cap, paid, amount);
+            let mut total = paid;
+            for _ in 0..n.min(64) {
+                prop_assert!(within_lifetime_cap(total, amount, cap));
+                total += amount;
+            }
+            if n < 64 {
+                prop_assert!(!within_lifetime_cap(total, amount, cap));
+            }
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use crate::{CoreError, CoreResult, BASIS_POINTS_DIVISOR};
+
+/// Pyth price account magic number
+pub const PYTH_MAGIC: u32 = 0xa1b2_c3d4;
+
+/// Pyth account type of a price account
+const PYTH_PRICE_ACCOUNT_TYPE: u32 = 3;
+
+/// Pyth aggregate status meaning the price is currently trading
+const PYTH_STATUS_TRADING: u32 = 1;
+
+// Byte offsets in a Pyth v2 price account
+const MAGIC_OFFSET: usize = 0;
+const ACCOUNT_TYPE_OFFSET: usize = 8;
+const EXPO_OFFSET: usize = 20;
+const TIMESTAMP_OFFSET: usize = 96;
+const AGG_PRICE_OFFSET: usize = 208;
+const AGG_CONF_OFFSET: usize = 216;
+const AGG_STATUS_OFFSET: usize = 224;
+
+/// Aggregate price read from an oracle account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OraclePrice {
+    /// Price mantissa; the USD price is `price * 10^expo`
+    pub price: i64,
+    /// Confidence interval in the same units as `price`
+    pub conf: u64,
+    pub expo: i32,
+    /// Unix time the aggregate was published
+    pub publish_time: i64,
+}
+
+impl OraclePrice {
+    /// Whether the confidence interval is at most `max_conf_bps` of the price
+    ///
+    /// A wide interval means publishers disagree, so the aggregate is no
+    /// sound basis for a swap bound.
+    pub fn confidence_within(&self, max_conf_bps: u16) -> bool {
+        (self.conf as u128) * BASIS_POINTS_DIVISOR
+            <= (self.price.unsigned_abs() as u128) * max_conf_bps as u128
+    }
+}
+
+/// Parse the aggregate price from a Pyth v2 price account
+///
+/// Rejects accounts that are not price accounts, prices that are not
+/// currently trading and non-positive prices.
+pub fn parse_pyth_price(data: &[u8]) -> CoreResult<OraclePrice> {
+    if read_u32(data, MAGIC_OFFSET)? != PYTH_MAGIC
+        || read_u32(data, ACCOUNT_TYPE_OFFSET)? != PYTH_PRICE_ACCOUNT_TYPE
+        || read_u32(data, AGG_STATUS_OFFSET)? != PYTH_STATUS_TRADING
+    {
+        return Err(CoreError::InvalidPrice);
+    }
+
+    let price = read_i64(data, AGG_PRICE_OFFSET)?;
+    if price <= 0 {
+        return Err(CoreError::InvalidPrice);
+    }
+
+    Ok(OraclePrice {
+        price,
+        conf: read_i64(data, AGG_CONF_OFFSET)? as u64,
+        expo: read_u32(data, EXPO_OFFSET)? as i32,
+        publish_time: read_i64(data, TIMESTAMP_OFFSET)?,
+    })
+}
+
+/// Most input tokens that may be sold for `amount_out` of a USD stablecoin
+///
+/// Converts at the oracle price of the input token and adds
+/// `slippage_bps`, rounding up. Both amounts are in base units.
+pub fn max_input_for_output(
+    amount_out: u64,
+    price: &OraclePrice,
+    input_decimals: u8,
+    output_decimals: u8,
+    slippage_bps: u16,
+) -> CoreResult<u64> {
+    // input = amount_out * 10^(input_decimals - output_decimals - expo) / price
+    let exponent = input_decimals as i32 - output_decimals as i32 - price.expo;
+    let mut numerator = (amount_out as u128)
+        .checked_mul(BASIS_POINTS_DIVISOR + slippage_bps as u128)
+        .ok_or(CoreError::Overflow)?;
+    let mut denominator = (price.price as u128)
+        .checked_mul(BASIS_POINTS_DIVISOR)
+        .ok_or(CoreError::Overflow)?;
+
+    let scale = 10u128
+        .checked_pow(exponent.unsigned_abs())
+        .ok_or(CoreError::Overflow)?;
+    if exponent >= 0 {
+        numerator = numerator.checked_mul(scale).ok_or(CoreError::Overflow)?;
+    } else {
+        denominator = denominator.checked_mul(scale).ok_or(CoreError::Overflow)?;
+    }
+
+    let input = numerator.div_ceil(denominator);
+    u64::try_from(input).map_err(|_| CoreError::Overflow)
+}
+
+/// Least USD stablecoin output acceptable for selling `amount_in` input tokens
+///
+/// Converts at the oracle price of the input token and deducts
+/// `slippage_bps`, rounding down. Both amounts are in base units.
+pub fn min_output_for_input(
+    amount_in: u64,
+    price: &OraclePrice,
+    input_decimals: u8,
+    output_decimals: u8,
+    slippage_bps: u16,
+) -> CoreResult<u64> {
+    // output = amount_in * price * 10^(output_decimals - input_decimals + expo)
+    let exponent = output_decimals as i32 - input_decimals as i32 + price.expo;
+    let mut numerator = (amount_in as u128)
+        .checked_mul(price.price as u128)
+        .and_then(|n| n.checked_mul(BASIS_POINTS_DIVISOR.saturating_sub(slippage_bps as u128)))
+        .ok_or(CoreError::Overflow)?;
+    let mut denominator = BASIS_POINTS_DIVISOR;
+
+    let scale = 10u128
+        .checked_pow(exponent.unsigned_abs())
+        .ok_or(CoreError::Overflow)?;
+    if exponent >= 0 {
+        numerator = numerator.checked_mul(scale).ok_or(CoreError::Overflow)?;
+    } else {
+        denominator = denominator.checked_mul(scale).ok_or(CoreError::Overflow)?;
+    }
+
+    u64::try_from(numerator / denominator).map_err(|_| CoreError::Overflow)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> CoreResult<u32> {
+    let bytes = data.get(offset..offset + 4).ok_or(CoreError::InvalidPrice)?;
+    Ok(u32::from_le_bytes(bytes.try_into().map_err(|_| CoreError::InvalidPrice)?))
+}
+
+fn read_i64(data: &[u8], offset: usize) -> CoreResult<i64> {
+    let bytes = data.get(offset..offset + 8).ok_or(CoreError::InvalidPrice)?;
+    Ok(i64::from_le_bytes(bytes.try_into().map_err(|_| CoreError::InvalidPrice)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price_account(price: i64, expo: i32, status: u32) -> Vec<u8> {
+        let mut data = vec![0u8; 240];
+        data[MAGIC_OFFSET..4].copy_from_slice(&PYTH_MAGIC.to_le_bytes());
+        data[ACCOUNT_TYPE_OFFSET..12].copy_from_slice(&PYTH_PRICE_ACCOUNT_TYPE.to_le_bytes());
+        data[EXPO_OFFSET..24].copy_from_slice(&expo.to_le_bytes());
+        data[TIMESTAMP_OFFSET..104].copy_from_slice(&1_700_000_000i64.to_le_bytes());
+        data[AGG_PRICE_OFFSET..216].copy_from_slice(&price.to_le_bytes());
+        data[AGG_CONF_OFFSET..224].copy_from_slice(&5u64.to_le_bytes());
+        data[AGG_STATUS_OFFSET..228].copy_from_slice(&status.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_parse_pyth_price() {
+        let parsed = parse_pyth_price(&price_account(15_000_000_000, -8, 1)).unwrap();
+        assert_eq!(parsed.price, 15_000_000_000);
+        assert_eq!(parsed.expo, -8);
+        assert_eq!(parsed.conf, 5);
+        assert_eq!(parsed.publish_time, 1_700_000_000);
+
+        // Halted, non-positive or truncated prices are rejected
+        assert!(parse_pyth_price(&price_account(15_000_000_000, -8, 0)).is_err());
+        assert!(parse_pyth_price(&price_account(0, -8, 1)).is_err());
+        assert!(parse_pyth_price(&[0u8; 64]).is_err());
+    }
+
+    #[test]
+    fn test_confidence_within() {
+        // $150.00000000 +/- $1.50000000 is a 1% interval
+        let price = OraclePrice {
+            price: 15_000_000_000,
+            conf: 150_000_000,
+            expo: -8,
+            publish_time: 0,
+        };
+        assert!(price.confidence_within(100));
+        assert!(price.confidence_within(200));
+        assert!(!price.confidence_within(99));
+        assert!(!OraclePrice { conf: 1, ..price }.confidence_within(0));
+    }
+
+    #[test]
+    fn test_max_input_for_output() {
+        // LST at $150.00000000, 9 decimals; 15 USDC (6 decimals) buys 0.1 LST
+        let price = OraclePrice {
+            price: 15_000_000_000,
+            conf: 0,
+            expo: -8,
+            publish_time: 0,
+        };
+        assert_eq!(max_input_for_output(15_000_000, &price, 9, 6, 0), Ok(100_000_000));
+
+        // 1% slippage allows 1% more input
+        assert_eq!(max_input_for_output(15_000_000, &price, 9, 6, 100), Ok(101_000_000));
+
+        // Rounds up so dust amounts still get a non-zero bound
+        assert_eq!(max_input_for_output(1, &price, 6, 6, 0), Ok(1));
+    }
+
+    #[test]
+    fn test_min_output_for_input() {
+        let price = OraclePrice {
+            price: 15_000_000_000,
+            conf: 0,
+            expo: -8,
+            publish_time: 0,
+        };
+        // 0.1 LST is worth 15 USDC; 1% slippage accepts 14.85
+        assert_eq!(min_output_for_input(100_000_000, &price, 9, 6, 0), Ok(15_000_000));
+        assert_eq!(min_output_for_input(100_000_000, &price, 9, 6, 100), Ok(14_850_000));
+
+        // Inverse of the input bound at zero slippage
+        let input = max_input_for_output(15_000_000, &price, 9, 6, 0).unwrap();
+        assert_eq!(min_output_for_input(input, &price, 9, 6, 0), Ok(15_000_000));
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{CoreError, CoreResult};
+
+/// Portion of `amount` covering `elapsed_seconds` of a `period_seconds` period
+///
+/// Rounds down (in the payer's favour). `elapsed_seconds` is clamped to the period.
+pub fn prorate(amount: u64, elapsed_seconds: i64, period_seconds: i64) -> CoreResult<u64> {
+    if period_seconds <= 0 {
+        return Err(CoreError::Overflow);
+    }
+    let elapsed = elapsed_seconds.clamp(0, period_seconds);
+
+    let prorated = (amount as u128)
+        .checked_mul(elapsed as u128)
+        .ok_or(CoreError::Overflow)?
+        .checked_div(period_seconds as u128)
+        .ok_or(CoreError::Overflow)?;
+
+    u64::try_from(prorated).map_err(|_| CoreError::Overflow)
+}
+
+/// Net adjustment when switching from `old_amount` to `new_amount` with
+/// `remaining_seconds` left in a `period_seconds` cycle that was paid at `old_amount`
+///
+/// Positive: the payer owes the difference. Negative: the payer is owed a credit.
+pub fn plan_change_adjustment(
+    old_amount: u64,
+    new_amount: u64,
+    remaining_seconds: i64,
+    period_seconds: i64,
+) -> CoreResult<i128> {
+    let unused_credit = prorate(old_amount, remaining_seconds, period_seconds)?;
+    let new_charge = prorate(new_amount, remaining_seconds, period_seconds)?;
+    Ok(new_charge as i128 - unused_credit as i128)
+}
+
+/// Charge for a payment of `amount` after applying a pending `adjustment`
+///
+/// Returns `(charge, carried)`. A charge is added in full; a credit larger
+/// than the payment zeroes it and the rest carries to the next payment.
+pub fn apply_adjustment(amount: u64, adjustment: i64) -> CoreResult<(u64, i64)> {
+    if adjustment >= 0 {
+        let charge = amount
+            .checked_add(adjustment as u64)
+            .ok_or(CoreError::Overflow)?;
+        return Ok((charge, 0));
+    }
+
+    let credit = adjustment.unsigned_abs();
+    if credit >= amount {
+        let carried = i64::try_from(credit - amount).map_err(|_| CoreError::Overflow)?;
+        Ok((0, -carried))
+    } else {
+        Ok((amount - credit, 0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_prorate_half_period() {
+        assert_eq!(prorate(10_000_000, 15, 30), Ok(5_000_000));
+    }
+
+    #[test]
+    fn test_prorate_clamps_and_rejects_bad_period() {
+        assert_eq!(prorate(100, 60, 30), Ok(100));
+        assert_eq!(prorate(100, -5, 30), Ok(0));
+        assert_eq!(prorate(100, 5, 0), Err(CoreError::Overflow));
+    }
+
+    #[test]
+    fn test_plan_change_upgrade_and_downgrade() {
+        // Half a cycle left: upgrade 10 -> 20 owes 5, downgrade owes -5
+        assert_eq!(plan_change_adjustment(10, 20, 15, 30), Ok(5));
+        assert_eq!(plan_change_adjustment(20, 10, 15, 30), Ok(-5));
+    }
+
+    #[test]
+    fn test_apply_adjustment_charge_and_credit() {
+        assert_eq!(apply_adjustment(100, 0), Ok((100, 0)));
+        assert_eq!(apply_adjustment(100, 25), Ok((125, 0)));
+        assert_eq!(apply_adjustment(100, -25), Ok((75, 0)));
+        // Credit beyond one payment carries forward
+        assert_eq!(apply_adjustment(100, -130), Ok((0, -30)));
+        assert_eq!(apply_adjustment(u64::MAX, 1), Err(CoreError::Overflow));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_prorate_bounded_by_amount(
+            amount in any::<u64>(),
+            elapsed in any::<i64>(),
+            period in 1i64..=31_536_000,
+        ) {
+            prop_assert!(prorate(amount, elapsed, period).unwrap() <= amount);
+        }
+
+        #[test]
+        fn prop_prorate_monotonic_in_elapsed(
+            amount in any::<u64>(),
+            a in 0i64..=31_536_000,
+            b in 0i64..=31_536_000,
+            period in 1i64..=31_536_000,
+        ) {
+            let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+            prop_assert!(prorate(amount, lo, period).unwrap() <= prorate(amount, hi, period).unwrap());
+        }
+
+        #[test]
+        fn prop_same_plan_has_no_adjustment(
+            amount in any::<u64>(),
+            remaining in 0i64..=31_536_000,
+            period in 1i64..=31_536_000,
+        ) {
+            prop_assert_eq!(plan_change_adjustment(amount, amount, remaining, period).unwrap(), 0);
+        }
+    }
+}
@@ -0,0 +1,574 @@
+use crate::{CoreError, CoreResult, SECONDS_PER_DAY};
+
+/// Delay before the first retry of a failed collection
+pub const RETRY_BASE_DELAY_SECONDS: i64 = 3_600;
+
+/// Longest delay between retries
+pub const RETRY_MAX_DELAY_SECONDS: i64 = SECONDS_PER_DAY;
+
+/// Shortest first-retry delay a merchant may configure
+pub const MIN_RETRY_BASE_DELAY_SECONDS: i64 = 900;
+
+/// Latest day of the month a billing day may name; shorter months clamp it
+pub const MAX_BILLING_DAY: u8 = 31;
+
+/// Latest ISO weekday a billing weekday may name (1 = Monday, 7 = Sunday)
+pub const MAX_BILLING_WEEKDAY: u8 = 7;
+
+const SECONDS_PER_WEEK: i64 = 7 * SECONDS_PER_DAY;
+
+/// Westernmost UTC offset in use (UTC-12:00)
+pub const MIN_UTC_OFFSET_SECONDS: i32 = -12 * 3_600;
+
+/// Easternmost UTC offset in use (UTC+14:00)
+pub const MAX_UTC_OFFSET_SECONDS: i32 = 14 * 3_600;
+
+/// Timestamp one period after `from`
+pub fn next_payment(from: i64, frequency_seconds: i64) -> CoreResult<i64> {
+    from.checked_add(frequency_seconds).ok_or(CoreError::Overflow)
+}
+
+/// First billing date after `now` on the grid `anchor + k * frequency_seconds`
+///
+/// Aligns subscriptions to a merchant's fixed billing day. `now` itself
+/// never qualifies, so a signup exactly on an anchor bills one full period.
+pub fn next_anchor(now: i64, anchor: i64, frequency_seconds: i64) -> CoreResult<i64> {
+    if frequency_seconds <= 0 {
+        return Err(CoreError::Overflow);
+    }
+    let offset = now
+        .checked_sub(anchor)
+        .ok_or(CoreError::Overflow)?
+        .rem_euclid(frequency_seconds);
+    (now - offset)
+        .checked_add(frequency_seconds)
+        .ok_or(CoreError::Overflow)
+}
+
+/// Whether `frequency_seconds` is a month long (28 to 31 days)
+///
+/// Only monthly subscriptions may bill on a fixed day of the month.
+pub fn is_monthly(frequency_seconds: i64) -> bool {
+    (28 * SECONDS_PER_DAY..=31 * SECONDS_PER_DAY).contains(&frequency_seconds)
+}
+
+/// Whether `frequency_seconds` is a whole number of weeks
+///
+/// Only such subscriptions may bill on a fixed day of the week.
+pub fn is_weekly(frequency_seconds: i64) -> bool {
+    frequency_seconds > 0 && frequency_seconds % SECONDS_PER_WEEK == 0
+}
+
+/// First occurrence of ISO `weekday` (1 = Monday .. 7 = Sunday) after `now`
+///
+/// Local midnight at `utc_offset_seconds` east of UTC. As with
+/// `next_anchor`, `now` itself never qualifies.
+pub fn next_weekday(now: i64, weekday: u8, utc_offset_seconds: i32) -> CoreResult<i64> {
+    if weekday == 0 || weekday > MAX_BILLING_WEEKDAY {
+        return Err(CoreError::Overflow);
+    }
+    // 1970-01-01 was a Thursday, ISO weekday 4
+    let anchor = (weekday as i64 - 4) * SECONDS_PER_DAY - utc_offset_seconds as i64;
+    next_anchor(now, anchor, SECONDS_PER_WEEK)
+}
+
+/// Occurrences of `billing_day` around `now` as `(previous, next)`
+///
+/// Both are local midnight at `utc_offset_seconds` east of UTC, returned as
+/// unix timestamps with `previous <= now < next`. In months shorter than
+/// `billing_day` the last day of the month is used, so the 31st bills on
+/// 30 April and 28 (or 29) February.
+pub fn billing_day_bounds(
+    now: i64,
+    billing_day: u8,
+    utc_offset_seconds: i32,
+) -> CoreResult<(i64, i64)> {
+    if billing_day == 0 || billing_day > MAX_BILLING_DAY {
+        return Err(CoreError::Overflow);
+    }
+    let offset = utc_offset_seconds as i64;
+    let local_now = now.checked_add(offset).ok_or(CoreError::Overflow)?;
+    let (year, month, _) = civil_from_days(local_now.div_euclid(SECONDS_PER_DAY));
+    let (previous_year, previous_month) = add_months(year, month, -1);
+    let (next_year, next_month) = add_months(year, month, 1);
+
+    let this_month = month_day(year, month, billing_day)?;
+    let (previous, next) = if this_month > local_now {
+        (month_day(previous_year, previous_month, billing_day)?, this_month)
+    } else {
+        (this_month, month_day(next_year, next_month, billing_day)?)
+    };
+    Ok((previous - offset, next - offset))
+}
+
+/// Due date following a payment collected at `now`
+///
+/// One period later, or the next `billing_day` of the month when set
+/// (0 = none). A `billing_weekday` (0 = none) moves it back to the last
+/// such weekday within the period, so late collections do not drift the
+/// schedule. Calendar due dates fall at local midnight for
+/// `utc_offset_seconds`.
+pub fn next_due(
+    now: i64,
+    frequency_seconds: i64,
+    billing_day: u8,
+    billing_weekday: u8,
+    utc_offset_seconds: i32,
+) -> CoreResult<i64> {
+    if billing_day != 0 {
+        return Ok(billing_day_bounds(now, billing_day, utc_offset_seconds)?.1);
+    }
+    if billing_weekday != 0 {
+        let week_before = next_payment(now, frequency_seconds - SECONDS_PER_WEEK)?;
+        return next_weekday(week_before, billing_weekday, utc_offset_seconds);
+    }
+    next_payment(now, frequency_seconds)
+}
+
+/// UTC calendar `(year, month)` containing `timestamp`
+pub fn utc_year_month(timestamp: i64) -> (i64, u32) {
+    let (year, month, _) = civil_from_days(timestamp.div_euclid(SECONDS_PER_DAY));
+    (year, month)
+}
+
+/// First and last-exclusive second of a UTC calendar month
+pub fn utc_month_bounds(year: i64, month: u32) -> CoreResult<(i64, i64)> {
+    if !(1..=12).contains(&month) {
+        return Err(CoreError::Overflow);
+    }
+    let (next_year, next_month) = add_months(year, month, 1);
+    Ok((month_day(year, month, 1)?, month_day(next_year, next_month, 1)?))
+}
+
+/// Midnight UTC of `day`, clamped to the length of `month`
+fn month_day(year: i64, month: u32, day: u8) -> CoreResult<i64> {
+    let day = (day as u32).min(days_in_month(year, month));
+    days_from_civil(year, month, day)
+        .checked_mul(SECONDS_PER_DAY)
+        .ok_or(CoreError::Overflow)
+}
+
+/// `(year, month)` shifted by `months`
+fn add_months(year: i64, month: u32, months: i64) -> (i64, u32) {
+    let index = year * 12 + (month as i64 - 1) + months;
+    (index.div_euclid(12), (index.rem_euclid(12) + 1) as u32)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date (H. Hinnant's algorithm)
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Proleptic Gregorian `(year, month, day)` of a day count since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 } as u32;
+    let year = year_of_era + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+/// Whether a payment scheduled at `next_payment` may be collected at `now`
+pub fn is_due(now: i64, next_payment: i64) -> bool {
+    now >= next_payment
+}
+
+/// Seconds until `next_payment` (zero once due)
+pub fn seconds_until_due(now: i64, next_payment: i64) -> i64 {
+    next_payment.saturating_sub(now).max(0)
+}
+
+/// Number of full periods that have elapsed since `next_payment` became due
+///
+/// Zero while not due or within the first period after the due date.
+pub fn periods_overdue(now: i64, next_payment: i64, frequency_seconds: i64) -> u64 {
+    if frequency_seconds <= 0 || !is_due(now, next_payment) {
+        return 0;
+    }
+    (now.saturating_sub(next_payment) / frequency_seconds) as u64
+}
+
+/// Whether a payment due at `next_payment` is past its collection window
+///
+/// A zero `collection_window` keeps payments collectible indefinitely.
+pub fn collection_closed(now: i64, next_payment: i64, collection_window: i64) -> bool {
+    collection_window > 0 && now > next_payment.saturating_add(collection_window)
+}
+
+/// Skip all but the latest `max_cycles` cycles due at `now`
+///
+/// Subscriptions that accumulate missed cycles collect them one execution at
+/// a time; this bounds how many a keeper outage can stack. Returns the oldest
+/// due date kept and the number of cycles skipped.
+pub fn cap_backlog(
+    now: i64,
+    next_payment: i64,
+    max_cycles: u8,
+    frequency_seconds: i64,
+    billing_day: u8,
+    utc_offset_seconds: i32,
+) -> CoreResult<(i64, u32)> {
+    if !is_due(now, next_payment) {
+        return Ok((next_payment, 0));
+    }
+    if frequency_seconds <= 0 {
+        return Err(CoreError::Overflow);
+    }
+
+    if billing_day == 0 {
+        let due = (now - next_payment) / frequency_seconds + 1;
+        let skipped = due.saturating_sub(max_cycles as i64).max(0);
+        let next = skipped
+            .checked_mul(frequency_seconds)
+            .and_then(|elapsed| next_payment.checked_add(elapsed))
+            .ok_or(CoreError::Overflow)?;
+        return Ok((next, u32::try_from(skipped).unwrap_or(u32::MAX)));
+    }
+
+    let mut due = 0u32;
+    let mut cursor = next_payment;
+    while is_due(now, cursor) {
+        cursor = billing_day_bounds(cursor, billing_day, utc_offset_seconds)?.1;
+        due = due.saturating_add(1);
+    }
+    let skipped = due.saturating_sub(max_cycles as u32);
+    let mut next = next_payment;
+    for _ in 0..skipped {
+        next = billing_day_bounds(next, billing_day, utc_offset_seconds)?.1;
+    }
+    Ok((next, skipped))
+}
+
+/// Skip cycles whose collection window has closed
+///
+/// A payment due at `next_payment` stays collectible for `collection_window`
+/// seconds (0 = indefinitely). Once that passes, the cycle is skipped, along
+/// with any later cycle whose window has also closed, so a keeper outage
+/// never stacks charges. Returns the due date to bill next and the number of
+/// cycles skipped (zero, with `next_payment` unchanged, when none expired).
+pub fn skip_expired_cycles(
+    now: i64,
+    next_payment: i64,
+    collection_window: i64,
+    frequency_seconds: i64,
+    billing_day: u8,
+    utc_offset_seconds: i32,
+) -> CoreResult<(i64, u32)> {
+    if !collection_closed(now, next_payment, collection_window) {
+        return Ok((next_payment, 0));
+    }
+    if frequency_seconds <= 0 {
+        return Err(CoreError::Overflow);
+    }
+    // Oldest due date still inside its window
+    let cutoff = now - collection_window;
+
+    if billing_day == 0 {
+        let cycles = (cutoff - next_payment)
+            .checked_add(frequency_seconds - 1)
+            .ok_or(CoreError::Overflow)?
+            / frequency_seconds;
+        let next = cycles
+            .checked_mul(frequency_seconds)
+            .and_then(|elapsed| next_payment.checked_add(elapsed))
+            .ok_or(CoreError::Overflow)?;
+        return Ok((next, u32::try_from(cycles).unwrap_or(u32::MAX)));
+    }
+
+    let mut next = next_payment;
+    let mut cycles = 0u32;
+    while next < cutoff {
+        next = billing_day_bounds(next, billing_day, utc_offset_seconds)?.1;
+        cycles = cycles.saturating_add(1);
+    }
+    Ok((next, cycles))
+}
+
+/// Backoff delay after `retry_count` consecutive failed collections
+///
+/// Doubles from `RETRY_BASE_DELAY_SECONDS` and caps at `RETRY_MAX_DELAY_SECONDS`.
+pub fn retry_delay(retry_count: u8) -> i64 {
+    backoff_delay(RETRY_BASE_DELAY_SECONDS, retry_count)
+}
+
+/// Backoff delay after `retry_count` failures, doubling from `base_delay`
+///
+/// Caps at `RETRY_MAX_DELAY_SECONDS`, or at `base_delay` if that is longer.
+pub fn backoff_delay(base_delay: i64, retry_count: u8) -> i64 {
+    let doublings = retry_count.saturating_sub(1).min(62) as u32;
+    let max_delay = base_delay.max(RETRY_MAX_DELAY_SECONDS);
+    base_delay
+        .checked_mul(1i64 << doublings)
+        .unwrap_or(max_delay)
+        .min(max_delay)
+}
+
+/// Earliest time to retry after `retry_count` consecutive failures at `now`
+pub fn next_retry_at(now: i64, retry_count: u8) -> CoreResult<i64> {
+    now.checked_add(retry_delay(retry_count))
+        .ok_or(CoreError::Overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_next_payment_overflow() {
+        assert_eq!(next_payment(i64::MAX, 1), Err(CoreError::Overflow));
+        assert_eq!(next_payment(1_000, 3_600), Ok(4_600));
+    }
+
+    #[test]
+    fn test_next_anchor() {
+        assert_eq!(next_anchor(1_050, 1_000, 100), Ok(1_100));
+        assert_eq!(next_anchor(1_000, 1_000, 100), Ok(1_100));
+        // Anchors in the future align backwards along the same grid
+        assert_eq!(next_anchor(1_000, 5_030, 100), Ok(1_030));
+        assert_eq!(next_anchor(1_000, 0, 0), Err(CoreError::Overflow));
+    }
+
+    // 2024-01-31T12:00:00Z, 2024-02-29T00:00:00Z, 2024-03-31T00:00:00Z
+    const JAN_31_NOON: i64 = 1_706_702_400;
+    const FEB_29: i64 = 1_709_164_800;
+    const MAR_31: i64 = 1_711_843_200;
+
+    #[test]
+    fn test_civil_round_trip() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(FEB_29 / SECONDS_PER_DAY), (2024, 2, 29));
+        assert_eq!(days_from_civil(2024, 3, 31) * SECONDS_PER_DAY, MAR_31);
+        assert_eq!(days_in_month(2023, 2), 28);
+        assert_eq!(days_in_month(2000, 2), 29);
+        assert_eq!(days_in_month(2100, 2), 28);
+    }
+
+    #[test]
+    fn test_utc_year_month() {
+        assert_eq!(utc_year_month(0), (1970, 1));
+        assert_eq!(utc_year_month(-1), (1969, 12));
+        assert_eq!(utc_year_month(JAN_31_NOON), (2024, 1));
+        assert_eq!(utc_year_month(MAR_31 - 1), (2024, 3));
+    }
+
+    #[test]
+    fn test_utc_month_bounds() {
+        let (start, end) = utc_month_bounds(2024, 2).unwrap();
+        assert_eq!(utc_year_month(start), (2024, 2));
+        assert_eq!(utc_year_month(end - 1), (2024, 2));
+        assert_eq!(utc_year_month(end), (2024, 3));
+        assert_eq!(end - start, 29 * SECONDS_PER_DAY);
+        assert_eq!(utc_month_bounds(2024, 12).unwrap().1, days_from_civil(2025, 1, 1) * SECONDS_PER_DAY);
+        assert_eq!(utc_month_bounds(2024, 13), Err(CoreError::Overflow));
+    }
+
+    #[test]
+    fn test_billing_day_clamps_to_short_months() {
+        let jan_31 = JAN_31_NOON - SECONDS_PER_DAY / 2;
+        assert_eq!(billing_day_bounds(JAN_31_NOON, 31, 0), Ok((jan_31, FEB_29)));
+        assert_eq!(billing_day_bounds(FEB_29, 31, 0), Ok((FEB_29, MAR_31)));
+        assert_eq!(next_due(FEB_29 - 1, 30 * SECONDS_PER_DAY, 31, 0, 0), Ok(FEB_29));
+        assert_eq!(next_due(100, 3_600, 0, 0, 0), Ok(3_700));
+        assert_eq!(billing_day_bounds(FEB_29, 0, 0), Err(CoreError::Overflow));
+        assert_eq!(billing_day_bounds(FEB_29, 32, 0), Err(CoreError::Overflow));
+    }
+
+    #[test]
+    fn test_skip_expired_cycles() {
+        let day = SECONDS_PER_DAY;
+        // Disabled, or still inside the window
+        assert_eq!(skip_expired_cycles(100 * day, day, 0, 30 * day, 0, 0), Ok((day, 0)));
+        assert_eq!(skip_expired_cycles(4 * day, day, 3 * day, 30 * day, 0, 0), Ok((day, 0)));
+
+        // One second late skips the cycle
+        assert_eq!(
+            skip_expired_cycles(4 * day + 1, day, 3 * day, 30 * day, 0, 0),
+            Ok((31 * day, 1))
+        );
+        // A long outage skips every closed cycle; the latest one stays collectible
+        assert_eq!(
+            skip_expired_cycles(93 * day, day, 3 * day, 30 * day, 0, 0),
+            Ok((91 * day, 3))
+        );
+
+        // Billing days walk the calendar, clamping short months
+        let jan_31 = JAN_31_NOON - day / 2;
+        assert_eq!(
+            skip_expired_cycles(MAR_31 - day, jan_31, 3 * day, 30 * day, 31, 0),
+            Ok((MAR_31, 2))
+        );
+        assert_eq!(skip_expired_cycles(day, 0, 1, 0, 0, 0), Err(CoreError::Overflow));
+    }
+
+    #[test]
+    fn test_cap_backlog() {
+        let day = SECONDS_PER_DAY;
+        assert!(!collection_closed(100 * day, day, 0));
+        assert!(collection_closed(4 * day + 1, day, 3 * day));
+
+        // Not due yet, or within the backlog
+        assert_eq!(cap_backlog(0, day, 2, 30 * day, 0, 0), Ok((day, 0)));
+        assert_eq!(cap_backlog(31 * day, day, 2, 30 * day, 0, 0), Ok((day, 0)));
+
+        // Four cycles due (1, 31, 61, 91): the oldest two are skipped
+        assert_eq!(cap_backlog(95 * day, day, 2, 30 * day, 0, 0), Ok((61 * day, 2)));
+
+        // Billing days: 31 Jan, 29 Feb and 31 Mar are due, one is kept
+        let jan_31 = JAN_31_NOON - day / 2;
+        assert_eq!(cap_backlog(MAR_31, jan_31, 1, 30 * day, 31, 0), Ok((MAR_31, 2)));
+    }
+
+    #[test]
+    fn test_billing_day_at_local_midnight() {
+        // 09:00 on 29 Feb in Tokyo (UTC+9): the 1st starts at 15:00 UTC the day before
+        let tokyo = 9 * 3_600;
+        let feb_1_tokyo = 1_706_745_600 - tokyo as i64;
+        let mar_1_tokyo = FEB_29 + SECONDS_PER_DAY - tokyo as i64;
+        assert_eq!(billing_day_bounds(FEB_29, 1, tokyo), Ok((feb_1_tokyo, mar_1_tokyo)));
+
+        // 19:00 on 28 Feb in New York (UTC-5) is still February there
+        let new_york = -5 * 3_600;
+        assert_eq!(
+            next_due(FEB_29, 30 * SECONDS_PER_DAY, 29, 0, new_york),
+            Ok(FEB_29 + 5 * 3_600)
+        );
+    }
+
+    #[test]
+    fn test_billing_weekday() {
+        // 29 Feb 2024 was a Thursday; the next Monday is 4 March
+        let day = SECONDS_PER_DAY;
+        let mar_4 = FEB_29 + 4 * day;
+        assert_eq!(next_weekday(FEB_29, 1, 0), Ok(mar_4));
+        assert_eq!(next_weekday(mar_4, 1, 0), Ok(mar_4 + 7 * day));
+        assert_eq!(next_weekday(FEB_29, 4, 0), Ok(FEB_29 + 7 * day));
+
+        // Monday in Tokyo (UTC+9) starts at 15:00 UTC on Sunday
+        let tokyo = 9 * 3_600;
+        assert_eq!(next_weekday(FEB_29, 1, tokyo), Ok(mar_4 - tokyo as i64));
+
+        // Collections on time or late keep the schedule on the weekday
+        assert_eq!(next_due(mar_4, 7 * day, 0, 1, 0), Ok(mar_4 + 7 * day));
+        assert_eq!(next_due(mar_4 + day / 2, 14 * day, 0, 1, 0), Ok(mar_4 + 14 * day));
+        assert_eq!(next_due(mar_4 + 6 * day, 7 * day, 0, 1, 0), Ok(mar_4 + 7 * day));
+
+        assert_eq!(next_weekday(FEB_29, 0, 0), Err(CoreError::Overflow));
+        assert_eq!(next_weekday(FEB_29, 8, 0), Err(CoreError::Overflow));
+    }
+
+    #[test]
+    fn test_is_weekly() {
+        assert!(is_weekly(7 * SECONDS_PER_DAY));
+        assert!(is_weekly(14 * SECONDS_PER_DAY));
+        assert!(!is_weekly(10 * SECONDS_PER_DAY));
+        assert!(!is_weekly(0));
+    }
+
+    #[test]
+    fn test_is_monthly() {
+        assert!(is_monthly(30 * SECONDS_PER_DAY));
+        assert!(!is_monthly(7 * SECONDS_PER_DAY));
+        assert!(!is_monthly(32 * SECONDS_PER_DAY));
+    }
+
+    #[test]
+    fn test_is_due_boundary() {
+        assert!(!is_due(99, 100));
+        assert!(is_due(100, 100));
+        assert!(is_due(101, 100));
+    }
+
+    #[test]
+    fn test_periods_overdue() {
+        assert_eq!(periods_overdue(50, 100, 10), 0);
+        assert_eq!(periods_overdue(109, 100, 10), 0);
+        assert_eq!(periods_overdue(110, 100, 10), 1);
+        assert_eq!(periods_overdue(135, 100, 10), 3);
+        assert_eq!(periods_overdue(135, 100, 0), 0);
+    }
+
+    #[test]
+    fn test_retry_backoff_doubles_then_caps() {
+        assert_eq!(retry_delay(1), 3_600);
+        assert_eq!(retry_delay(2), 7_200);
+        assert_eq!(retry_delay(5), 57_600);
+        assert_eq!(retry_delay(6), SECONDS_PER_DAY);
+        assert_eq!(retry_delay(u8::MAX), SECONDS_PER_DAY);
+        assert_eq!(next_retry_at(1_000, 1), Ok(4_600));
+    }
+
+    #[test]
+    fn test_backoff_delay_from_custom_base() {
+        assert_eq!(backoff_delay(900, 1), 900);
+        assert_eq!(backoff_delay(900, 3), 3_600);
+        assert_eq!(backoff_delay(900, 10), SECONDS_PER_DAY);
+        assert_eq!(backoff_delay(2 * SECONDS_PER_DAY, 4), 2 * SECONDS_PER_DAY);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_next_payment_is_due_exactly_one_period_later(
+            from in 0i64..4_000_000_000,
+            frequency in 3_600i64..=31_536_000,
+        ) {
+            let next = next_payment(from, frequency).unwrap();
+            prop_assert!(!is_due(next - 1, next));
+            prop_assert!(is_due(next, next));
+            prop_assert_eq!(seconds_until_due(from, next), frequency);
+        }
+
+        #[test]
+        fn prop_next_anchor_within_one_period(
+            now in 0i64..4_000_000_000,
+            anchor in 0i64..4_000_000_000,
+            frequency in 3_600i64..=31_536_000,
+        ) {
+            let next = next_anchor(now, anchor, frequency).unwrap();
+            prop_assert!(next > now && next - now <= frequency);
+            prop_assert_eq!((next - anchor).rem_euclid(frequency), 0);
+        }
+
+        #[test]
+        fn prop_billing_day_brackets_now(
+            now in 0i64..4_000_000_000,
+            billing_day in 1u8..=MAX_BILLING_DAY,
+            utc_offset in MIN_UTC_OFFSET_SECONDS..=MAX_UTC_OFFSET_SECONDS,
+        ) {
+            let (previous, next) = billing_day_bounds(now, billing_day, utc_offset).unwrap();
+            prop_assert!(previous <= now && now < next);
+            prop_assert!(is_monthly(next - previous));
+            let local_next = next + utc_offset as i64;
+            let (_, _, day) = civil_from_days(local_next / SECONDS_PER_DAY);
+            prop_assert!(day <= billing_day as u32 && local_next % SECONDS_PER_DAY == 0);
+        }
+
+        #[test]
+        fn prop_seconds_until_due_never_negative(now in any::<i64>(), next in any::<i64>()) {
+            let remaining = seconds_until_due(now, next);
+            prop_assert!(remaining >= 0);
+            prop_assert_eq!(remaining == 0, is_due(now, next));
+        }
+    }
+}
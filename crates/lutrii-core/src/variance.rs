@@ -0,0 +1,45 @@
+use crate::BASIS_POINTS_DIVISOR;
+
+/// Maximum allowed change from the original subscription amount (10%)
+pub const MAX_PRICE_VARIANCE_BPS: u16 = 1_000;
+
+/// Largest absolute deviation from `original_amount` allowed at `max_variance_bps`
+pub fn max_variance(original_amount: u64, max_variance_bps: u16) -> u64 {
+    // original * bps / 10_000 <= original, so the result always fits in u64
+    ((original_amount as u128) * (max_variance_bps as u128) / BASIS_POINTS_DIVISOR) as u64
+}
+
+/// Whether `amount` is within `max_variance_bps` of `original_amount`
+pub fn within_variance(amount: u64, original_amount: u64, max_variance_bps: u16) -> bool {
+    amount.abs_diff(original_amount) <= max_variance(original_amount, max_variance_bps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_ten_percent_boundary() {
+        assert!(within_variance(110, 100, MAX_PRICE_VARIANCE_BPS));
+        assert!(within_variance(90, 100, MAX_PRICE_VARIANCE_BPS));
+        assert!(!within_variance(111, 100, MAX_PRICE_VARIANCE_BPS));
+        assert!(!within_variance(89, 100, MAX_PRICE_VARIANCE_BPS));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_matches_integer_tenth(amount in any::<u64>(), original in any::<u64>()) {
+            // Historical on-chain rule: |amount - original| <= original / 10
+            prop_assert_eq!(
+                within_variance(amount, original, MAX_PRICE_VARIANCE_BPS),
+                amount.abs_diff(original) <= original / 10
+            );
+        }
+
+        #[test]
+        fn prop_original_always_within(original in any::<u64>(), bps in any::<u16>()) {
+            prop_assert!(within_variance(original, original, bps));
+        }
+    }
+}
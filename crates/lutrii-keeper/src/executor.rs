@@ -0,0 +1,557 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use anchor_lang::prelude::Clock;
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use anyhow::{anyhow, bail, Context, Result};
+use lutrii_common::{ScheduleUnit, Subscription};
+use lutrii_core::schedule;
+use lutrii_recurring::{DueBucket, FeeTreasury, PlatformState, SettlementVault};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+
+use crate::metrics::Metrics;
+use crate::scanner::{due_bucket, DueSubscription};
+
+/// Base delay between send attempts; doubled on every retry
+const RETRY_BASE_DELAY_MS: u64 = 500;
+
+/// SPL Associated Token Account program
+const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+pub struct ExecutorConfig {
+    pub batch_size: usize,
+    pub priority_fee_micro_lamports: u64,
+    pub compute_units_per_payment: u32,
+    pub max_retries: u32,
+    /// Reserve payments with claim_execution before executing them
+    pub claim_executions: bool,
+}
+
+/// Builds and sends execute_payment transactions
+pub struct Executor<'a> {
+    rpc: &'a RpcClient,
+    payer: &'a Keypair,
+    metrics: Arc<Metrics>,
+    config: ExecutorConfig,
+}
+
+/// Accounts resolved once per cycle and shared by every execution
+struct CycleContext {
+    platform_state: Pubkey,
+    /// Fee wallet keyed by mint
+    fee_wallets: HashMap<Pubkey, Pubkey>,
+    /// Enabled fee treasury and its vault keyed by mint
+    fee_treasuries: HashMap<Pubkey, (Pubkey, Pubkey)>,
+}
+
+impl<'a> Executor<'a> {
+    pub fn new(
+        rpc: &'a RpcClient,
+        payer: &'a Keypair,
+        metrics: Arc<Metrics>,
+        config: ExecutorConfig,
+    ) -> Self {
+        Self {
+            rpc,
+            payer,
+            metrics,
+            config,
+        }
+    }
+
+    /// Execute every due subscription in batches
+    ///
+    /// A batch that still fails after all retries is split into single
+    /// executions so one broken subscription cannot block its neighbours.
+    pub fn execute_all(&self, due: &[DueSubscription], clock: &Clock) -> Result<()> {
+        let cycle = self.load_cycle_context()?;
+
+        // Payments another keeper has claimed would only fail
+        let keeper = self.payer.pubkey();
+        let mut due: Vec<&DueSubscription> = due
+            .iter()
+            .filter(|s| !s.account.claimed_by_other(&keeper, clock.slot))
+            .collect();
+        if self.config.claim_executions {
+            due = self.claim_all(&cycle, due);
+        }
+
+        let mut instructions = Vec::with_capacity(due.len());
+        for subscription in due {
+            match self.build_execute_ix(&cycle, subscription, clock) {
+                Ok(ix) => instructions.push((subscription.address, ix)),
+                Err(e) => {
+                    log::warn!("Skipping {}: {:#}", subscription.address, e);
+                    self.metrics.payments_failed.inc();
+                }
+            }
+        }
+
+        for batch in instructions.chunks(self.config.batch_size) {
+            if self.send_with_retries(batch).is_ok() {
+                self.metrics.payments_executed.inc_by(batch.len() as u64);
+                continue;
+            }
+
+            if batch.len() == 1 {
+                log::error!("Execution failed for {}", batch[0].0);
+                self.metrics.payments_failed.inc();
+                continue;
+            }
+
+            log::warn!("Batch of {} failed, isolating executions", batch.len());
+            for single in batch.chunks(1) {
+                match self.send_with_retries(single) {
+                    Ok(()) => self.metrics.payments_executed.inc(),
+                    Err(e) => {
+                        log::error!("Execution failed for {}: {:#}", single[0].0, e);
+                        self.metrics.payments_failed.inc();
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Claim `due` for this keeper, keeping only the payments it now holds
+    ///
+    /// Claims are cheap, so a failed batch is retried claim by claim; a
+    /// payment claimed by a faster keeper in the meantime drops out.
+    fn claim_all<'d>(
+        &self,
+        cycle: &CycleContext,
+        due: Vec<&'d DueSubscription>,
+    ) -> Vec<&'d DueSubscription> {
+        let mut claimed = Vec::with_capacity(due.len());
+        for batch in due.chunks(self.config.batch_size) {
+            if self.send_claims(cycle, batch).is_ok() {
+                claimed.extend_from_slice(batch);
+                continue;
+            }
+            for single in batch.chunks(1) {
+                match self.send_claims(cycle, single) {
+                    Ok(_) => claimed.push(single[0]),
+                    Err(e) => log::debug!("Could not claim {}: {:#}", single[0].address, e),
+                }
+            }
+        }
+        claimed
+    }
+
+    fn send_claims(&self, cycle: &CycleContext, batch: &[&DueSubscription]) -> Result<Signature> {
+        let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_price(
+            self.config.priority_fee_micro_lamports,
+        )];
+        instructions.extend(batch.iter().map(|due| Instruction {
+            program_id: lutrii_recurring::ID,
+            accounts: lutrii_recurring::accounts::ClaimExecution {
+                subscription: due.address,
+                platform_state: cycle.platform_state,
+                keeper: self.payer.pubkey(),
+            }
+            .to_account_metas(None),
+            data: lutrii_recurring::instruction::ClaimExecution {}.data(),
+        }));
+        self.send(&instructions)
+    }
+
+    fn load_cycle_context(&self) -> Result<CycleContext> {
+        let program_id = lutrii_recurring::ID;
+        let (platform_state, _) = Pubkey::find_program_address(&[b"platform"], &program_id);
+
+        let platform_account = self
+            .rpc
+            .get_account(&platform_state)
+            .context("platform state not found")?;
+        let platform = PlatformState::try_deserialize(&mut platform_account.data.as_slice())
+            .context("platform state not migrated to the current layout")?;
+        if !platform.fee_wallets_configured() {
+            return Err(anyhow!("platform fee wallets not configured"));
+        }
+
+        let mut fee_wallets = HashMap::new();
+        let mut fee_treasuries = HashMap::new();
+        for wallet in [platform.fee_wallet_usdc, platform.fee_wallet_usd1] {
+            let account = self.rpc.get_account(&wallet)?;
+            let mint = token_account_mint(&account.data)?;
+            fee_wallets.insert(mint, wallet);
+            if let Some(treasury) = self.fee_treasury(&mint) {
+                fee_treasuries.insert(mint, treasury);
+            }
+        }
+
+        Ok(CycleContext {
+            platform_state,
+            fee_wallets,
+            fee_treasuries,
+        })
+    }
+
+    fn build_execute_ix(
+        &self,
+        cycle: &CycleContext,
+        due: &DueSubscription,
+        clock: &Clock,
+    ) -> Result<Instruction> {
+        let subscription = &due.account;
+
+        // Mint and token program come from the user's token account
+        let user_token = self.rpc.get_account(&subscription.user_token_account)?;
+        let mint = token_account_mint(&user_token.data)?;
+
+        // Swap-settled subscriptions need a Jupiter route, which this keeper
+        // does not quote; leave them to a route-aware executor
+        let merchant_token = self.rpc.get_account(&subscription.merchant_token_account)?;
+        let settlement_mint = token_account_mint(&merchant_token.data)?;
+        if settlement_mint != mint {
+            bail!(
+                "pays in {} but settles in {}; needs execute_payment_with_swap",
+                mint,
+                settlement_mint
+            );
+        }
+
+        let platform_fee_account = *cycle
+            .fee_wallets
+            .get(&mint)
+            .ok_or_else(|| anyhow!("no fee wallet configured for mint {}", mint))?;
+
+        // Merchant wallet (for the denylist check) is the settlement account owner
+        let merchant_wallet = token_account_owner(&merchant_token.data)?;
+
+        // Merchants with batched settlement enabled accrue into their vault
+        let (settlement, settlement_vault) = match self.settlement(&subscription.merchant, &mint) {
+            Some((address, vault)) => (Some(address), Some(vault)),
+            None => (None, None),
+        };
+        let (fee_treasury, fee_treasury_vault) = match cycle.fee_treasuries.get(&mint) {
+            Some((address, vault)) => (Some(*address), Some(*vault)),
+            None => (None, None),
+        };
+
+        // The merchant's trackers are always passed, for the current reporting
+        // year and statement month; the program skips those not yet opened
+        let (year, month) = schedule::utc_year_month(clock.unix_timestamp);
+        let (year, month) = (year as u16, month as u8);
+
+        // Keep indexed subscriptions indexed under their next due day
+        let (due_bucket, next_due_bucket) = self.due_buckets(subscription, clock);
+
+        // Prepaid subscriptions pay out of their escrow vault
+        let (escrow, escrow_vault) = if subscription.escrow_funded {
+            let (address, vault) = escrow(&due.address);
+            (Some(address), Some(vault))
+        } else {
+            (None, None)
+        };
+
+        // Claim the executor reward into the payer's token account, if it has one
+        let reward_account = associated_token_account(&self.payer.pubkey(), &user_token.owner, &mint);
+        let (executor, executor_token_account) = match self.rpc.get_account(&reward_account) {
+            Ok(_) => (Some(self.payer.pubkey()), Some(reward_account)),
+            Err(_) => (None, None),
+        };
+
+        let accounts = lutrii_recurring::accounts::ExecutePayment {
+            subscription: due.address,
+            platform_state: cycle.platform_state,
+            user: subscription.user,
+            user_token_account: subscription.user_token_account,
+            user_delegate: user_delegate(&subscription.user_token_account),
+            merchant_token_account: subscription.merchant_token_account,
+            platform_fee_account,
+            user_denylist_entry: denylist_entry(&subscription.user),
+            merchant_denylist_entry: denylist_entry(&merchant_wallet),
+            blocklist_entry: blocklist_entry(&subscription.merchant, &subscription.user),
+            mint,
+            token_program: user_token.owner,
+            user_stats: user_stats(&subscription.user),
+            settlement,
+            settlement_vault,
+            fee_treasury,
+            fee_treasury_vault,
+            due_bucket,
+            next_due_bucket,
+            merchant_policy: merchant_policy(&subscription.merchant),
+            claimant: Some(self.payer.pubkey()),
+            merchant: subscription.merchant,
+            merchant_registry_program: lutrii_merchant_registry::ID,
+            instructions: solana_sdk::sysvar::instructions::ID,
+            merchant_volume: merchant_volume(&subscription.merchant, &mint),
+            fee_holidays: fee_holidays(),
+            merchant_report: merchant_report(&subscription.merchant, &mint, year),
+            merchant_statement: merchant_statement(&subscription.merchant, &mint, year, month),
+            refund_liability: refund_liability(&subscription.merchant, &mint),
+            revenue_forecast: revenue_forecast(&subscription.merchant, &mint),
+            executor,
+            executor_token_account,
+            // Plan-bound subscriptions charge the plan's current price
+            plan: (subscription.plan != Pubkey::default()).then_some(subscription.plan),
+            escrow,
+            escrow_vault,
+        };
+
+        Ok(Instruction {
+            program_id: lutrii_recurring::ID,
+            accounts: accounts.to_account_metas(None),
+            data: lutrii_recurring::instruction::ExecutePayment {}.data(),
+        })
+    }
+
+    /// Current and next due-date buckets for an execution at `clock`
+    ///
+    /// Only subscriptions already listed in a bucket are re-indexed; the next
+    /// bucket is opened (at the keeper's expense, refunded on close) when
+    /// missing. Buckets index unix days, so slot schedules are never listed.
+    fn due_buckets(&self, subscription: &Subscription, clock: &Clock) -> (Option<Pubkey>, Option<Pubkey>) {
+        if subscription.schedule_unit == ScheduleUnit::Slots {
+            return (None, None);
+        }
+        let current = due_bucket(
+            &subscription.merchant,
+            DueBucket::day_of(subscription.next_payment),
+        );
+        if self.rpc.get_account(&current).is_err() {
+            return (None, None);
+        }
+
+        let Ok(next_payment) = schedule::next_due(
+            subscription.next_cycle_base(clock.unix_timestamp),
+            subscription.frequency_seconds,
+            subscription.billing_day,
+            subscription.billing_weekday,
+            subscription.utc_offset_seconds,
+        ) else {
+            return (Some(current), None);
+        };
+        let day = DueBucket::day_of(next_payment);
+        let next = due_bucket(&subscription.merchant, day);
+        if self.rpc.get_account(&next).is_ok() {
+            return (Some(current), Some(next));
+        }
+        match self.open_due_bucket(&subscription.merchant, day) {
+            Ok(_) => (Some(current), Some(next)),
+            Err(e) => {
+                log::warn!("Could not open due bucket {}: {:#}", next, e);
+                (Some(current), None)
+            }
+        }
+    }
+
+    fn open_due_bucket(&self, merchant: &Pubkey, day: i64) -> Result<Signature> {
+        let accounts = lutrii_recurring::accounts::OpenDueBucket {
+            due_bucket: due_bucket(merchant, day),
+            merchant: *merchant,
+            payer: self.payer.pubkey(),
+            system_program: solana_sdk::system_program::ID,
+        };
+        self.send(&[Instruction {
+            program_id: lutrii_recurring::ID,
+            accounts: accounts.to_account_metas(None),
+            data: lutrii_recurring::instruction::OpenDueBucket { day }.data(),
+        }])
+    }
+
+    /// Enabled settlement PDA and its vault for `merchant` and `mint`, if any
+    fn settlement(&self, merchant: &Pubkey, mint: &Pubkey) -> Option<(Pubkey, Pubkey)> {
+        let (address, _) = Pubkey::find_program_address(
+            &[b"settlement", merchant.as_ref(), mint.as_ref()],
+            &lutrii_recurring::ID,
+        );
+        let account = self.rpc.get_account(&address).ok()?;
+        let settlement = SettlementVault::try_deserialize(&mut account.data.as_slice()).ok()?;
+        settlement.enabled.then_some((address, settlement.vault))
+    }
+
+    /// Enabled fee treasury PDA and its vault for `mint`, if any
+    fn fee_treasury(&self, mint: &Pubkey) -> Option<(Pubkey, Pubkey)> {
+        let (address, _) =
+            Pubkey::find_program_address(&[b"fee_treasury", mint.as_ref()], &lutrii_recurring::ID);
+        let account = self.rpc.get_account(&address).ok()?;
+        let treasury = FeeTreasury::try_deserialize(&mut account.data.as_slice()).ok()?;
+        treasury.enabled.then_some((address, treasury.vault))
+    }
+
+    fn send_with_retries(&self, batch: &[(Pubkey, Instruction)]) -> Result<()> {
+        let compute_units = self
+            .config
+            .compute_units_per_payment
+            .saturating_mul(batch.len() as u32);
+
+        let mut instructions = vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(compute_units),
+            ComputeBudgetInstruction::set_compute_unit_price(
+                self.config.priority_fee_micro_lamports,
+            ),
+        ];
+        instructions.extend(batch.iter().map(|(_, ix)| ix.clone()));
+
+        let mut last_error = None;
+        for attempt in 0..self.config.max_retries {
+            if attempt > 0 {
+                self.metrics.retries.inc();
+                thread::sleep(Duration::from_millis(RETRY_BASE_DELAY_MS << (attempt - 1)));
+            }
+
+            match self.send(&instructions) {
+                Ok(signature) => {
+                    self.metrics.transactions_sent.inc();
+                    log::info!("Executed {} payments: {}", batch.len(), signature);
+                    return Ok(());
+                }
+                Err(e) => {
+                    log::debug!("Attempt {} failed: {}", attempt + 1, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow!("no send attempts made")))
+    }
+
+    fn send(&self, instructions: &[Instruction]) -> Result<Signature> {
+        // Fresh blockhash on every attempt so expiry is never the failure cause
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &[self.payer],
+            blockhash,
+        );
+        Ok(self.rpc.send_and_confirm_transaction(&tx)?)
+    }
+}
+
+/// Mint of an SPL Token or Token-2022 account (first 32 bytes of both layouts)
+fn token_account_mint(data: &[u8]) -> Result<Pubkey> {
+    token_account_field(data, 0)
+}
+
+/// Owner of an SPL Token or Token-2022 account (bytes 32..64 of both layouts)
+fn token_account_owner(data: &[u8]) -> Result<Pubkey> {
+    token_account_field(data, 32)
+}
+
+fn token_account_field(data: &[u8], offset: usize) -> Result<Pubkey> {
+    let bytes: [u8; 32] = data
+        .get(offset..offset + 32)
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| anyhow!("account data too short for a token account"))?;
+    Ok(Pubkey::new_from_array(bytes))
+}
+
+/// Shared delegate PDA for a user token account
+fn user_delegate(token_account: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"user_delegate", token_account.as_ref()],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
+
+/// Spending analytics PDA for `user`
+fn user_stats(user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"user_stats", user.as_ref()], &lutrii_recurring::ID).0
+}
+
+/// Merchant volume PDA, counted toward fee rebates when it exists
+fn merchant_volume(merchant: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"merchant_volume", merchant.as_ref(), mint.as_ref()],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
+
+/// Merchant reporting PDA for a calendar year, recorded to when it exists
+fn merchant_report(merchant: &Pubkey, mint: &Pubkey, year: u16) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"merchant_report", merchant.as_ref(), mint.as_ref(), &year.to_le_bytes()],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
+
+/// Merchant statement PDA for a calendar month, accrued to when it exists
+fn merchant_statement(merchant: &Pubkey, mint: &Pubkey, year: u16, month: u8) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"merchant_statement",
+            merchant.as_ref(),
+            mint.as_ref(),
+            &year.to_le_bytes(),
+            &[month],
+        ],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
+
+/// Refund liability PDA, accruing the merchant's refundable exposure when it exists
+fn refund_liability(merchant: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"refund_liability", merchant.as_ref(), mint.as_ref()],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
+
+/// Revenue forecast PDA, tracking the merchant's upcoming payments when it exists
+fn revenue_forecast(merchant: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"revenue_forecast", merchant.as_ref(), mint.as_ref()],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
+
+/// Fee holiday schedule PDA, zeroing the platform fee inside a window
+fn fee_holidays() -> Pubkey {
+    Pubkey::find_program_address(&[b"fee_holidays"], &lutrii_recurring::ID).0
+}
+
+/// Escrow PDA of a prepaid `subscription` and its vault, which pays instead of the delegation
+fn escrow(subscription: &Pubkey) -> (Pubkey, Pubkey) {
+    let escrow =
+        Pubkey::find_program_address(&[b"escrow", subscription.as_ref()], &lutrii_recurring::ID).0;
+    let vault =
+        Pubkey::find_program_address(&[b"escrow_vault", escrow.as_ref()], &lutrii_recurring::ID).0;
+    (escrow, vault)
+}
+
+/// Associated token account of `owner` for `mint`, where executor rewards go
+fn associated_token_account(owner: &Pubkey, token_program: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Merchant policy PDA, passed so auto-cancellations can free the slot
+fn merchant_policy(merchant: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"merchant_policy", merchant.as_ref()], &lutrii_recurring::ID).0
+}
+
+/// Compliance denylist PDA for `wallet`
+fn denylist_entry(wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"denylist", wallet.as_ref()], &lutrii_recurring::ID).0
+}
+
+/// Merchant blocklist PDA for `user`
+fn blocklist_entry(merchant: &Pubkey, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"blocklist", merchant.as_ref(), user.as_ref()],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
@@ -0,0 +1,158 @@
+//! Lutrii Keeper
+//!
+//! Reference crank for the lutrii-recurring program:
+//! - Scans for due subscriptions with filtered getProgramAccounts, or only
+//!   the due-date buckets of selected merchants
+//! - Batches execute_payment instructions with priority fees
+//! - Retries failed batches with backoff, then isolates bad subscriptions
+//! - Exposes Prometheus metrics over HTTP
+
+mod executor;
+mod metrics;
+mod scanner;
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use anchor_lang::prelude::Clock;
+use anyhow::{anyhow, Result};
+use clap::Parser;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::read_keypair_file;
+
+use crate::executor::{Executor, ExecutorConfig};
+use crate::metrics::Metrics;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "lutrii-keeper",
+    version,
+    about = "Executes due Lutrii subscription payments"
+)]
+struct Args {
+    /// Solana JSON-RPC endpoint
+    #[arg(long, env = "LUTRII_RPC_URL", default_value = "http://127.0.0.1:8899")]
+    rpc_url: String,
+
+    /// Keypair paying transaction fees for executions
+    #[arg(long, env = "LUTRII_KEEPER_KEYPAIR")]
+    keypair: PathBuf,
+
+    /// Seconds between scans
+    #[arg(long, default_value_t = 30)]
+    interval_secs: u64,
+
+    /// execute_payment instructions packed into one transaction
+    #[arg(long, default_value_t = 4)]
+    batch_size: usize,
+
+    /// Priority fee in micro-lamports per compute unit
+    #[arg(long, default_value_t = 10_000)]
+    priority_fee_micro_lamports: u64,
+
+    /// Compute unit budget requested per execute_payment instruction
+    #[arg(long, default_value_t = lutrii_recurring::EXECUTE_PAYMENT_COMPUTE_UNITS)]
+    compute_units_per_payment: u32,
+
+    /// Send attempts per batch before falling back to single executions
+    #[arg(long, default_value_t = 3)]
+    max_retries: u32,
+
+    /// Address for the Prometheus metrics endpoint
+    #[arg(long, default_value = "0.0.0.0:9464")]
+    metrics_addr: SocketAddr,
+
+    /// Run a single scan and exit
+    #[arg(long)]
+    once: bool,
+
+    /// Scan only these merchants' due-date buckets instead of every subscription
+    #[arg(long = "merchant")]
+    merchants: Vec<Pubkey>,
+
+    /// Days of overdue buckets read in merchant scans
+    #[arg(long, default_value_t = 7)]
+    bucket_lookback_days: i64,
+
+    /// Reserve due payments with claim_execution before executing them
+    #[arg(long)]
+    claim_executions: bool,
+}
+
+fn main() -> Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let args = Args::parse();
+
+    let payer = read_keypair_file(&args.keypair)
+        .map_err(|e| anyhow!("failed to read keypair {}: {}", args.keypair.display(), e))?;
+    let rpc = RpcClient::new_with_commitment(args.rpc_url.clone(), CommitmentConfig::confirmed());
+
+    let metrics = Arc::new(Metrics::new()?);
+    if !args.once {
+        metrics::serve(metrics.clone(), args.metrics_addr)?;
+    }
+
+    let executor = Executor::new(
+        &rpc,
+        &payer,
+        metrics.clone(),
+        ExecutorConfig {
+            batch_size: args.batch_size.max(1),
+            priority_fee_micro_lamports: args.priority_fee_micro_lamports,
+            compute_units_per_payment: args.compute_units_per_payment,
+            max_retries: args.max_retries.max(1),
+            claim_executions: args.claim_executions,
+        },
+    );
+
+    log::info!(
+        "Lutrii keeper started - program {}, rpc {}",
+        lutrii_recurring::ID,
+        args.rpc_url
+    );
+
+    loop {
+        if let Err(e) = run_cycle(&rpc, &executor, &metrics, &args) {
+            log::error!("Scan cycle failed: {:#}", e);
+        }
+
+        if args.once {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_secs(args.interval_secs));
+    }
+}
+
+/// One scan + execute pass
+fn run_cycle(rpc: &RpcClient, executor: &Executor, metrics: &Metrics, args: &Args) -> Result<()> {
+    // Subscriptions are scheduled in unix seconds or in slots
+    let slot = rpc.get_slot()?;
+    let now = rpc.get_block_time(slot)?;
+    let clock = Clock {
+        slot,
+        unix_timestamp: now,
+        ..Clock::default()
+    };
+    let due = if args.merchants.is_empty() {
+        scanner::fetch_due_subscriptions(rpc, &clock)?
+    } else {
+        scanner::fetch_indexed_due_subscriptions(rpc, &args.merchants, &clock, args.bucket_lookback_days)?
+    };
+
+    metrics.scans.inc();
+    metrics.due_subscriptions.set(due.len() as i64);
+    metrics.last_scan_timestamp.set(now);
+
+    if due.is_empty() {
+        log::debug!("No subscriptions due");
+        return Ok(());
+    }
+
+    log::info!("{} subscriptions due", due.len());
+    executor.execute_all(&due, &clock)
+}
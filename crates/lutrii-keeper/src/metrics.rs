@@ -0,0 +1,107 @@
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+
+use anyhow::Result;
+use prometheus::{Encoder, IntCounter, IntGauge, Registry, TextEncoder};
+
+/// Keeper metrics exported in Prometheus text format
+pub struct Metrics {
+    registry: Registry,
+    pub scans: IntCounter,
+    pub due_subscriptions: IntGauge,
+    pub last_scan_timestamp: IntGauge,
+    pub transactions_sent: IntCounter,
+    pub payments_executed: IntCounter,
+    pub payments_failed: IntCounter,
+    pub retries: IntCounter,
+}
+
+impl Metrics {
+    pub fn new() -> Result<Self> {
+        let registry = Registry::new_custom(Some("lutrii_keeper".to_string()), None)?;
+
+        let scans = IntCounter::new("scans_total", "Completed subscription scans")?;
+        let due_subscriptions =
+            IntGauge::new("due_subscriptions", "Subscriptions due in the latest scan")?;
+        let last_scan_timestamp = IntGauge::new(
+            "last_scan_timestamp",
+            "Cluster unix time of the latest scan",
+        )?;
+        let transactions_sent = IntCounter::new(
+            "transactions_sent_total",
+            "Execution transactions confirmed",
+        )?;
+        let payments_executed =
+            IntCounter::new("payments_executed_total", "Payments executed successfully")?;
+        let payments_failed = IntCounter::new(
+            "payments_failed_total",
+            "Payments that failed after all retries",
+        )?;
+        let retries = IntCounter::new("retries_total", "Transaction send retries")?;
+
+        registry.register(Box::new(scans.clone()))?;
+        registry.register(Box::new(due_subscriptions.clone()))?;
+        registry.register(Box::new(last_scan_timestamp.clone()))?;
+        registry.register(Box::new(transactions_sent.clone()))?;
+        registry.register(Box::new(payments_executed.clone()))?;
+        registry.register(Box::new(payments_failed.clone()))?;
+        registry.register(Box::new(retries.clone()))?;
+
+        Ok(Self {
+            registry,
+            scans,
+            due_subscriptions,
+            last_scan_timestamp,
+            transactions_sent,
+            payments_executed,
+            payments_failed,
+            retries,
+        })
+    }
+
+    fn render(&self) -> Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(buffer)
+    }
+}
+
+/// Serve metrics on `addr` from a background thread
+///
+/// Every request receives the full metrics page regardless of path.
+pub fn serve(metrics: Arc<Metrics>, addr: SocketAddr) -> Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    log::info!("Metrics listening on http://{}/metrics", addr);
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    if let Err(e) = respond(&metrics, stream) {
+                        log::debug!("Metrics request failed: {}", e);
+                    }
+                }
+                Err(e) => log::debug!("Metrics connection failed: {}", e),
+            }
+        }
+    });
+
+    Ok(())
+}
+
+fn respond(metrics: &Metrics, mut stream: TcpStream) -> Result<()> {
+    // Drain the request head; the response does not depend on it
+    let mut request = [0u8; 1024];
+    let _ = stream.read(&mut request)?;
+
+    let body = metrics.render()?;
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )?;
+    stream.write_all(&body)?;
+    Ok(())
+}
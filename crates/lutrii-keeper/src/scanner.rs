@@ -0,0 +1,146 @@
+use anchor_lang::prelude::Clock;
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anyhow::Result;
+use lutrii_common::Subscription;
+use lutrii_core::{limits, proration, schedule};
+use lutrii_recurring::DueBucket;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_client::rpc_request::MAX_MULTIPLE_ACCOUNTS;
+use solana_sdk::pubkey::Pubkey;
+
+/// Byte offset of `is_active` in a serialized Subscription
+///
+/// discriminator (8) + 4 pubkeys (128) + 6 u64/i64 fields (48) + payment_count (4).
+/// `is_paused` immediately follows, so one memcmp selects active, unpaused accounts.
+const IS_ACTIVE_OFFSET: usize = 8 + 32 * 4 + 8 * 6 + 4;
+
+/// A subscription whose payment can be executed now
+pub struct DueSubscription {
+    pub address: Pubkey,
+    pub account: Subscription,
+}
+
+/// Fetch every active, unpaused subscription whose next payment is due at `clock`
+///
+/// Results are ordered oldest-due first so backlogs drain fairly.
+pub fn fetch_due_subscriptions(rpc: &RpcClient, clock: &Clock) -> Result<Vec<DueSubscription>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                0,
+                Subscription::DISCRIMINATOR.to_vec(),
+            )),
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(IS_ACTIVE_OFFSET, vec![1, 0])),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let accounts = rpc.get_program_accounts_with_config(&lutrii_recurring::ID, config)?;
+
+    let mut due: Vec<DueSubscription> = accounts
+        .into_iter()
+        .filter_map(|(address, account)| {
+            match Subscription::try_deserialize(&mut account.data.as_slice()) {
+                Ok(subscription) => Some(DueSubscription {
+                    address,
+                    account: subscription,
+                }),
+                Err(e) => {
+                    log::warn!("Skipping undecodable subscription {}: {}", address, e);
+                    None
+                }
+            }
+        })
+        .filter(|s| is_due(&s.account, clock))
+        .collect();
+
+    due.sort_by_key(|s| s.account.next_payment);
+    Ok(due)
+}
+
+/// Fetch due subscriptions of `merchants` from their due-date buckets
+///
+/// Reads the buckets for today and the previous `lookback_days` days, then
+/// only the subscriptions they list - no program-wide scan. Subscriptions
+/// that were never indexed (including every slot-scheduled one), or are
+/// overdue beyond the lookback, are missed.
+pub fn fetch_indexed_due_subscriptions(
+    rpc: &RpcClient,
+    merchants: &[Pubkey],
+    clock: &Clock,
+    lookback_days: i64,
+) -> Result<Vec<DueSubscription>> {
+    let today = DueBucket::day_of(clock.unix_timestamp);
+    let buckets: Vec<Pubkey> = merchants
+        .iter()
+        .flat_map(|merchant| (today - lookback_days..=today).map(|day| due_bucket(merchant, day)))
+        .collect();
+
+    let mut listed = Vec::new();
+    for chunk in buckets.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        for account in rpc.get_multiple_accounts(chunk)?.into_iter().flatten() {
+            if let Ok(bucket) = DueBucket::try_deserialize(&mut account.data.as_slice()) {
+                listed.extend(bucket.subscriptions);
+            }
+        }
+    }
+
+    let mut due = Vec::with_capacity(listed.len());
+    for chunk in listed.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let accounts = rpc.get_multiple_accounts(chunk)?;
+        for (address, account) in chunk.iter().zip(accounts) {
+            // Closed since it was indexed
+            let Some(account) = account else {
+                continue;
+            };
+            match Subscription::try_deserialize(&mut account.data.as_slice()) {
+                Ok(subscription) if is_due(&subscription, clock) => due.push(DueSubscription {
+                    address: *address,
+                    account: subscription,
+                }),
+                Ok(_) => {}
+                Err(e) => log::warn!("Skipping undecodable subscription {}: {}", address, e),
+            }
+        }
+    }
+
+    due.sort_by_key(|s| s.account.next_payment);
+    Ok(due)
+}
+
+/// Due-date bucket PDA for `merchant` and unix `day`
+pub fn due_bucket(merchant: &Pubkey, day: i64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"due_bucket", merchant.as_ref(), &day.to_le_bytes()],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
+
+/// Mirror of the on-chain execute_payment preconditions that depend only on the account
+fn is_due(subscription: &Subscription, clock: &Clock) -> bool {
+    let now = subscription.schedule_unit.now(clock);
+    let Ok((charge, _)) =
+        proration::apply_adjustment(subscription.amount, subscription.pending_proration)
+    else {
+        return false;
+    };
+    let charge = subscription.discounted(charge);
+
+    subscription.is_active
+        && !subscription.is_paused
+        && !subscription.payment_in_progress
+        && !subscription.delegation_deferred
+        && subscription.layout_version == Subscription::CURRENT_VERSION
+        && schedule::is_due(now, subscription.next_payment)
+        && now >= subscription.next_retry_at
+        && charge <= subscription.max_per_transaction
+        && limits::within_lifetime_cap(subscription.total_paid, charge, subscription.lifetime_cap)
+}
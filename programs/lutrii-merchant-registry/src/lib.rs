@@ -1,7 +1,10 @@
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
 use anchor_spl::token_interface::{
     transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
 };
+use lutrii_common::{AdminAuditAction, AdminAuditTrail, EVENT_SCHEMA_VERSION};
+use lutrii_core::{schedule, SECONDS_PER_DAY};
 
 declare_id!("3RkcL88V6dyHRCJFyGZ54R1u1KcHqeYB24MA38894Eex");
 
@@ -12,7 +15,17 @@ const MAX_CATEGORY_LEN: usize = 32;
 const MAX_REVIEW_COMMENT_LEN: usize = 256;
 const PREMIUM_BADGE_DURATION_DAYS: i64 = 30;
 const PREMIUM_BADGE_PRICE: u64 = 50_000_000; // 50 USDC
-const SECONDS_PER_DAY: i64 = 86_400;
+
+// Review sybil resistance defaults, tunable with update_review_thresholds
+const DEFAULT_MIN_REVIEW_PAYMENTS: u32 = 3;
+const DEFAULT_MIN_REVIEW_TOTAL_PAID: u64 = 1_000_000; // 1 USDC
+const DEFAULT_MIN_REVIEW_SUBSCRIPTION_AGE: i64 = 7 * SECONDS_PER_DAY;
+const MAX_REVIEW_SUBSCRIPTION_AGE: i64 = 365 * SECONDS_PER_DAY;
+
+// Discovery tags
+const MAX_TAG_LEN: usize = 24;
+const MAX_MERCHANT_TAGS: usize = 5;
+const MAX_TAG_INDEX_MERCHANTS: usize = 250;
 
 /// Program version
 #[constant]
@@ -39,11 +52,113 @@ pub mod lutrii_merchant_registry {
         registry.verified_merchants = 0;
         registry.premium_badge_price = PREMIUM_BADGE_PRICE;
         registry.bump = ctx.bumps.registry_state;
+        registry.set_default_review_thresholds();
 
         msg!("Lutrii merchant registry initialized - version {}", VERSION);
         Ok(())
     }
 
+    /// Grow a registry created before review thresholds were stored (admin only)
+    ///
+    /// The shorter account no longer deserializes, so it is checked by hand,
+    /// reallocated in place and given the previously hard-coded thresholds.
+    pub fn migrate_registry(ctx: Context<MigrateRegistry>) -> Result<()> {
+        let registry_info = ctx.accounts.registry_state.to_account_info();
+        {
+            let data = registry_info.try_borrow_data()?;
+            require!(
+                data.len() >= RegistryState::LEGACY_SPACE && data[..8] == RegistryState::DISCRIMINATOR,
+                ErrorCode::InvalidRegistryState
+            );
+            require!(data.len() < RegistryState::SPACE, ErrorCode::RegistryAlreadyMigrated);
+            require!(
+                data[8..40] == ctx.accounts.authority.key().to_bytes(),
+                ErrorCode::UnauthorizedAdmin
+            );
+        }
+
+        let required_lamports = Rent::get()?.minimum_balance(RegistryState::SPACE);
+        let shortfall = required_lamports.saturating_sub(registry_info.lamports());
+        if shortfall > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: registry_info.clone(),
+                    },
+                ),
+                shortfall,
+            )?;
+        }
+        registry_info.realloc(RegistryState::SPACE, true)?;
+
+        let mut registry = RegistryState::try_deserialize(&mut &registry_info.try_borrow_data()?[..])?;
+        registry.set_default_review_thresholds();
+        registry.try_serialize(&mut &mut registry_info.try_borrow_mut_data()?[..])?;
+
+        msg!("Registry migrated to {} bytes", RegistryState::SPACE);
+        Ok(())
+    }
+
+    /// Tune the review sybil-resistance thresholds (admin only)
+    ///
+    /// # Arguments
+    /// * `min_payments` - Payments a subscription must have made (at least 1)
+    /// * `min_total_paid` - Token units it must have paid in total
+    /// * `min_subscription_age` - Seconds since it was created (up to a year)
+    pub fn update_review_thresholds(
+        ctx: Context<UpdateReviewThresholds>,
+        min_payments: u32,
+        min_total_paid: u64,
+        min_subscription_age: i64,
+    ) -> Result<()> {
+        require!(
+            min_payments > 0 && (0..=MAX_REVIEW_SUBSCRIPTION_AGE).contains(&min_subscription_age),
+            ErrorCode::InvalidReviewThresholds
+        );
+
+        let registry = &mut ctx.accounts.registry_state;
+        registry.min_review_payments = min_payments;
+        registry.min_review_total_paid = min_total_paid;
+        registry.min_review_subscription_age = min_subscription_age;
+
+        let now = Clock::get()?.unix_timestamp;
+        if let Some(audit_log) = ctx.accounts.audit_log.as_mut() {
+            audit_log
+                .trail
+                .record(ctx.accounts.authority.key(), AdminAuditAction::ConfigUpdate, now);
+        }
+
+        emit!(ReviewThresholdsUpdated {
+            schema_version: EVENT_SCHEMA_VERSION,
+            min_payments,
+            min_total_paid,
+            min_subscription_age,
+            timestamp: now,
+        });
+
+        msg!(
+            "Review thresholds: {} payments, {} paid, {}s old",
+            min_payments,
+            min_total_paid,
+            min_subscription_age
+        );
+        Ok(())
+    }
+
+    /// Open the registry's admin audit log (admin only)
+    ///
+    /// Merchant approvals and suspensions passed the log append their
+    /// signer, action and time to it, overwriting the oldest entry once full.
+    pub fn open_admin_audit_log(ctx: Context<OpenAdminAuditLog>) -> Result<()> {
+        // The trail starts empty: every slot of the new account is zeroed
+        ctx.accounts.audit_log.bump = ctx.bumps.audit_log;
+
+        msg!("Registry admin audit log opened");
+        Ok(())
+    }
+
     /// Apply for merchant verification
     ///
     /// Creates a merchant account and submits application for review.
@@ -101,6 +216,7 @@ pub mod lutrii_merchant_registry {
             .ok_or(ErrorCode::Overflow)?;
 
         emit!(MerchantApplicationSubmitted {
+            schema_version: EVENT_SCHEMA_VERSION,
             merchant: merchant.key(),
             owner: merchant.owner,
             business_name,
@@ -142,11 +258,14 @@ pub mod lutrii_merchant_registry {
         }
 
         emit!(MerchantVerified {
+            schema_version: EVENT_SCHEMA_VERSION,
             merchant: merchant.key(),
             tier,
             timestamp: merchant.last_updated,
         });
 
+        ctx.accounts.audit(AdminAuditAction::MerchantApproval)?;
+
         msg!("Merchant approved: {:?}", tier);
         Ok(())
     }
@@ -184,11 +303,15 @@ pub mod lutrii_merchant_registry {
 
         // Activate premium badge for 30 days
         merchant.premium_badge_active = true;
-        merchant.premium_badge_expires =
-            clock.unix_timestamp + (PREMIUM_BADGE_DURATION_DAYS * SECONDS_PER_DAY);
+        merchant.premium_badge_expires = schedule::next_payment(
+            clock.unix_timestamp,
+            PREMIUM_BADGE_DURATION_DAYS * SECONDS_PER_DAY,
+        )
+        .map_err(|_| error!(ErrorCode::Overflow))?;
         merchant.last_updated = clock.unix_timestamp;
 
         emit!(PremiumBadgeActivated {
+            schema_version: EVENT_SCHEMA_VERSION,
             merchant: merchant.key(),
             expires_at: merchant.premium_badge_expires,
         });
@@ -200,7 +323,9 @@ pub mod lutrii_merchant_registry {
     /// Record a transaction (ONLY callable by lutrii-recurring program)
     ///
     /// Updates merchant stats when payments are executed.
-    /// Enforces strict CPI caller validation for security.
+    /// Enforces strict CPI caller validation for security: the
+    /// lutrii-recurring platform PDA must sign, which only that program
+    /// can do, and the instruction may not run at the top level.
     pub fn record_transaction(
         ctx: Context<RecordTransaction>,
         amount: u64,
@@ -211,24 +336,14 @@ pub mod lutrii_merchant_registry {
         // ============================================================================
         use anchor_lang::solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
 
+        // The instructions sysvar lists top-level instructions only; the one
+        // executing now is the caller's, never this program's, under a CPI
         let ixs = &ctx.accounts.instructions.to_account_info();
         let current_index = load_current_index_checked(ixs)
             .map_err(|_| error!(ErrorCode::MustBeCalledViaCpi))?;
-
-        // Verify this instruction is being called via CPI (current_index > 0)
-        require!(current_index > 0, ErrorCode::MustBeCalledViaCpi);
-
-        // Load the parent instruction (the one that called this via CPI)
-        let parent_ix = load_instruction_at_checked(
-            (current_index - 1) as usize,
-            ixs
-        ).map_err(|_| error!(ErrorCode::MustBeCalledViaCpi))?;
-
-        // Verify the parent instruction is from lutrii-recurring program
-        require!(
-            parent_ix.program_id == lutrii_recurring::ID,
-            ErrorCode::UnauthorizedCpiCaller
-        );
+        let top_level_ix = load_instruction_at_checked(current_index as usize, ixs)
+            .map_err(|_| error!(ErrorCode::MustBeCalledViaCpi))?;
+        require!(top_level_ix.program_id != crate::ID, ErrorCode::MustBeCalledViaCpi);
 
         msg!("✅ CPI validation passed - called from lutrii-recurring program");
 
@@ -275,6 +390,7 @@ pub mod lutrii_merchant_registry {
             merchant.verification_tier = VerificationTier::Community;
 
             emit!(MerchantUpgraded {
+                schema_version: EVENT_SCHEMA_VERSION,
                 merchant: merchant.key(),
                 new_tier: VerificationTier::Community,
                 auto_upgraded: true,
@@ -289,6 +405,7 @@ pub mod lutrii_merchant_registry {
             merchant.premium_badge_active = false;
 
             emit!(MerchantSuspended {
+                schema_version: EVENT_SCHEMA_VERSION,
                 merchant: merchant.key(),
                 reason: "Community score below -100".to_string(),
                 score: merchant.community_score,
@@ -304,6 +421,10 @@ pub mod lutrii_merchant_registry {
     ///
     /// Users can only review merchants they have active subscriptions with.
     /// This prevents sybil attacks where fake wallets spam reviews.
+    ///
+    /// Each review belongs to one subscription and the plan it is on, so a
+    /// subscriber may review every plan they hold once and ratings can be
+    /// shown per product.
     pub fn submit_review(
         ctx: Context<SubmitReview>,
         rating: u8,
@@ -322,16 +443,23 @@ pub mod lutrii_merchant_registry {
         let review = &mut ctx.accounts.review;
         let merchant = &mut ctx.accounts.merchant;
         let subscription = &ctx.accounts.subscription;
+        let registry = &ctx.accounts.registry_state;
         let clock = Clock::get()?;
 
         // ============================================================================
-        // SYBIL RESISTANCE - Verify subscription age >= 7 days
+        // SYBIL RESISTANCE - Verify payment history and subscription age
         // ============================================================================
-        const MIN_SUBSCRIPTION_AGE_SECONDS: i64 = 7 * SECONDS_PER_DAY; // 7 days
+        require!(
+            subscription.payment_count >= registry.min_review_payments,
+            ErrorCode::InsufficientPaymentHistory
+        );
+        require!(
+            subscription.total_paid >= registry.min_review_total_paid,
+            ErrorCode::InsufficientTotalPaid
+        );
         let subscription_age = clock.unix_timestamp - subscription.created_at;
-
         require!(
-            subscription_age >= MIN_SUBSCRIPTION_AGE_SECONDS,
+            subscription_age >= registry.min_review_subscription_age,
             ErrorCode::SubscriptionTooNew
         );
 
@@ -344,6 +472,8 @@ pub mod lutrii_merchant_registry {
 
         review.merchant = merchant.key();
         review.reviewer = ctx.accounts.reviewer.key();
+        review.subscription = subscription.key();
+        review.plan = subscription.plan;
         review.rating = rating;
         review.comment = comment;
         review.timestamp = clock.unix_timestamp;
@@ -373,10 +503,13 @@ pub mod lutrii_merchant_registry {
         merchant.last_updated = clock.unix_timestamp;
 
         emit!(ReviewSubmitted {
+            schema_version: EVENT_SCHEMA_VERSION,
             merchant: merchant.key(),
             reviewer: review.reviewer,
             rating,
             new_score: merchant.community_score,
+            subscription: review.subscription,
+            plan: review.plan,
         });
 
         msg!("Review submitted: {} stars", rating);
@@ -402,11 +535,14 @@ pub mod lutrii_merchant_registry {
         merchant.last_updated = Clock::get()?.unix_timestamp;
 
         emit!(MerchantSuspended {
+            schema_version: EVENT_SCHEMA_VERSION,
             merchant: merchant.key(),
             reason,
             score: merchant.community_score,
         });
 
+        ctx.accounts.audit(AdminAuditAction::MerchantSuspension)?;
+
         msg!("Merchant suspended by admin");
         Ok(())
     }
@@ -466,6 +602,10 @@ pub mod lutrii_merchant_registry {
     /// - Settlement token must be in accepted_tokens list
     /// - Can accept 1-4 different tokens
     /// - Merchant must be verified (not Unverified or Suspended)
+    ///
+    /// lutrii-recurring only creates subscriptions that settle into a token
+    /// account of `settlement_token`, swapping when the subscriber pays with
+    /// another token. Existing subscriptions keep their settlement account.
     pub fn update_merchant_tokens(
         ctx: Context<UpdateMerchantTokens>,
         settlement_token: Pubkey,
@@ -530,6 +670,7 @@ pub mod lutrii_merchant_registry {
         merchant.last_updated = Clock::get()?.unix_timestamp;
 
         emit!(MerchantTokensUpdated {
+            schema_version: EVENT_SCHEMA_VERSION,
             merchant: merchant.key(),
             settlement_token,
             accepted_tokens_count: merchant.accepted_tokens_count,
@@ -541,6 +682,101 @@ pub mod lutrii_merchant_registry {
 
         Ok(())
     }
+
+    /// Attach a discovery tag to the merchant
+    ///
+    /// Adds the merchant to the tag's index PDA, so marketplaces can list
+    /// every merchant under a tag by reading `[b"tag_index", tag]` instead
+    /// of scanning all merchants. Tags are 1-24 characters of lowercase
+    /// letters, digits and `-`; a merchant holds up to 5.
+    ///
+    /// # Requirements
+    /// - Merchant must be verified (not Unverified or Suspended), which
+    ///   keeps spam out of the bounded indexes
+    pub fn add_merchant_tag(ctx: Context<AddMerchantTag>, tag: String) -> Result<()> {
+        validate_tag(&tag)?;
+        let merchant = &mut ctx.accounts.merchant;
+        require!(
+            merchant.verification_tier != VerificationTier::Unverified,
+            ErrorCode::MerchantNotVerified
+        );
+        require!(
+            merchant.verification_tier != VerificationTier::Suspended,
+            ErrorCode::MerchantSuspended
+        );
+
+        let tags = &mut ctx.accounts.merchant_tags;
+        require!(!tags.tags.contains(&tag), ErrorCode::DuplicateTag);
+        require!(tags.tags.len() < MAX_MERCHANT_TAGS, ErrorCode::TooManyTags);
+        tags.merchant = merchant.key();
+        tags.bump = ctx.bumps.merchant_tags;
+        tags.tags.push(tag.clone());
+
+        let index = &mut ctx.accounts.tag_index;
+        require!(
+            index.merchants.len() < MAX_TAG_INDEX_MERCHANTS,
+            ErrorCode::TagIndexFull
+        );
+        index.tag = tag.clone();
+        index.bump = ctx.bumps.tag_index;
+        index.merchants.push(merchant.key());
+
+        merchant.last_updated = Clock::get()?.unix_timestamp;
+
+        emit!(MerchantTagAdded {
+            schema_version: EVENT_SCHEMA_VERSION,
+            merchant: merchant.key(),
+            tag,
+            tag_merchants: index.merchants.len() as u16,
+        });
+
+        Ok(())
+    }
+
+    /// Detach a discovery tag from the merchant and drop it from the tag's index
+    pub fn remove_merchant_tag(ctx: Context<RemoveMerchantTag>, tag: String) -> Result<()> {
+        let merchant = &mut ctx.accounts.merchant;
+
+        let tags = &mut ctx.accounts.merchant_tags;
+        let position = tags
+            .tags
+            .iter()
+            .position(|t| *t == tag)
+            .ok_or(ErrorCode::TagNotFound)?;
+        tags.tags.remove(position);
+
+        let index = &mut ctx.accounts.tag_index;
+        let position = index
+            .merchants
+            .iter()
+            .position(|m| *m == merchant.key())
+            .ok_or(ErrorCode::TagNotFound)?;
+        index.merchants.swap_remove(position);
+
+        merchant.last_updated = Clock::get()?.unix_timestamp;
+
+        emit!(MerchantTagRemoved {
+            schema_version: EVENT_SCHEMA_VERSION,
+            merchant: merchant.key(),
+            tag,
+            tag_merchants: index.merchants.len() as u16,
+        });
+
+        Ok(())
+    }
+}
+
+/// Tags are lowercase so lookups by seed are unambiguous
+fn validate_tag(tag: &str) -> Result<()> {
+    require!(
+        !tag.is_empty()
+            && tag.len() <= MAX_TAG_LEN
+            && tag
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-'),
+        ErrorCode::InvalidTag
+    );
+    Ok(())
 }
 
 // ============================================================================
@@ -554,10 +790,24 @@ pub struct RegistryState {
     pub verified_merchants: u64,        // 8
     pub premium_badge_price: u64,       // 8
     pub bump: u8,                       // 1
+    /// Payments a subscription needs before its owner may review
+    pub min_review_payments: u32,       // 4
+    /// Total a subscription must have paid before its owner may review
+    pub min_review_total_paid: u64,     // 8
+    /// Seconds a subscription must have existed before its owner may review
+    pub min_review_subscription_age: i64, // 8
 }
 
 impl RegistryState {
-    pub const SPACE: usize = 8 + 32 + 8 + 8 + 8 + 1;
+    /// Layout before review thresholds were stored
+    pub const LEGACY_SPACE: usize = 8 + 32 + 8 + 8 + 8 + 1;
+    pub const SPACE: usize = Self::LEGACY_SPACE + 4 + 8 + 8;
+
+    fn set_default_review_thresholds(&mut self) {
+        self.min_review_payments = DEFAULT_MIN_REVIEW_PAYMENTS;
+        self.min_review_total_paid = DEFAULT_MIN_REVIEW_TOTAL_PAID;
+        self.min_review_subscription_age = DEFAULT_MIN_REVIEW_SUBSCRIPTION_AGE;
+    }
 }
 
 #[account]
@@ -620,10 +870,53 @@ pub struct Review {
     pub comment: String,                // 4 + 256
     pub timestamp: i64,                 // 8
     pub bump: u8,                       // 1
+    pub subscription: Pubkey,           // 32
+    pub plan: Pubkey,                   // 32 - default = custom terms
+}
+
+/// Ring buffer of the registry's admin actions
+///
+/// PDA: `[b"admin_audit_log"]`
+#[account]
+pub struct AdminAuditLog {
+    pub trail: AdminAuditTrail,         // 2 + 8 + 41 * 32
+    pub bump: u8,                       // 1
+}
+
+impl AdminAuditLog {
+    pub const SPACE: usize = 8 + AdminAuditTrail::SPACE + 1;
+}
+
+/// Discovery tags a merchant has attached
+///
+/// PDA: `[b"merchant_tags", merchant]`
+#[account]
+pub struct MerchantTags {
+    pub merchant: Pubkey,               // 32
+    pub tags: Vec<String>,              // 4 + 5 * (4 + 24)
+    pub bump: u8,                       // 1
+}
+
+impl MerchantTags {
+    pub const SPACE: usize = 8 + 32 + (4 + MAX_MERCHANT_TAGS * (4 + MAX_TAG_LEN)) + 1;
+}
+
+/// Every merchant carrying one tag, in no particular order
+///
+/// PDA: `[b"tag_index", tag]`
+#[account]
+pub struct TagIndex {
+    pub tag: String,                    // 4 + 24
+    pub merchants: Vec<Pubkey>,         // 4 + 32 * 250
+    pub bump: u8,                       // 1
+}
+
+impl TagIndex {
+    pub const SPACE: usize = 8 + (4 + MAX_TAG_LEN) + (4 + 32 * MAX_TAG_INDEX_MERCHANTS) + 1;
 }
 
 impl Review {
-    pub const SPACE: usize = 8 + 32 + 32 + 1 + (4 + MAX_REVIEW_COMMENT_LEN) + 8 + 1;
+    pub const SPACE: usize = 8 + 32 + 32 + 1 + (4 + MAX_REVIEW_COMMENT_LEN) + 8 + 1 + 32 + 32;
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
@@ -655,6 +948,68 @@ pub struct InitializeRegistry<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct OpenAdminAuditLog<'info> {
+    #[account(
+        seeds = [b"registry"],
+        bump = registry_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub registry_state: Account<'info, RegistryState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = AdminAuditLog::SPACE,
+        seeds = [b"admin_audit_log"],
+        bump
+    )]
+    pub audit_log: Box<Account<'info, AdminAuditLog>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateRegistry<'info> {
+    /// CHECK: Legacy layouts cannot be deserialized; validated in the handler
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump,
+        owner = crate::ID
+    )]
+    pub registry_state: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateReviewThresholds<'info> {
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump = registry_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub registry_state: Account<'info, RegistryState>,
+
+    pub authority: Signer<'info>,
+
+    /// Admin audit log, appended to when passed
+    #[account(
+        mut,
+        seeds = [b"admin_audit_log"],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Option<Box<Account<'info, AdminAuditLog>>>,
+}
+
 #[derive(Accounts)]
 pub struct ApplyForVerification<'info> {
     #[account(
@@ -697,6 +1052,25 @@ pub struct AdminMerchantAction<'info> {
     pub registry_state: Account<'info, RegistryState>,
 
     pub authority: Signer<'info>,
+
+    /// Admin audit log, appended to when passed
+    #[account(
+        mut,
+        seeds = [b"admin_audit_log"],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Option<Box<Account<'info, AdminAuditLog>>>,
+}
+
+impl AdminMerchantAction<'_> {
+    /// Record `action` by the authority in the audit log, if passed
+    fn audit(&mut self, action: AdminAuditAction) -> Result<()> {
+        if let Some(audit_log) = self.audit_log.as_mut() {
+            let now = Clock::get()?.unix_timestamp;
+            audit_log.trail.record(self.authority.key(), action, now);
+        }
+        Ok(())
+    }
 }
 
 #[derive(Accounts)]
@@ -738,9 +1112,13 @@ pub struct RecordTransaction<'info> {
     )]
     pub merchant: Account<'info, Merchant>,
 
-    /// CHECK: Validated via instruction introspection in record_transaction
-    /// Must be lutrii-recurring program calling via CPI
-    pub recurring_program: UncheckedAccount<'info>,
+    /// lutrii-recurring platform PDA, signing for the calling program
+    #[account(
+        seeds = [b"platform"],
+        bump,
+        seeds::program = lutrii_recurring::ID
+    )]
+    pub recurring_authority: Signer<'info>,
 
     /// CHECK: Solana instructions sysvar for CPI validation
     #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
@@ -755,8 +1133,8 @@ pub struct SubmitReview<'info> {
         space = Review::SPACE,
         seeds = [
             b"review",
-            merchant.key().as_ref(),
-            reviewer.key().as_ref()
+            subscription.key().as_ref(),
+            subscription.plan.as_ref()
         ],
         bump
     )]
@@ -769,25 +1147,21 @@ pub struct SubmitReview<'info> {
     )]
     pub merchant: Account<'info, Merchant>,
 
-    /// Verified subscription - ensures user has active subscription with sybil resistance
-    /// Requirements:
-    /// - Subscription must be active
-    /// - At least 3 successful payments
-    /// - At least 1 USDC total paid (prevents spam with tiny amounts)
+    /// Verified subscription - ensures user has an active subscription; its
+    /// payment history and age are held to the registry's review thresholds
     #[account(
-        seeds = [
-            b"subscription",
-            reviewer.key().as_ref(),
-            merchant.owner.as_ref(),
-        ],
-        bump = subscription.bump,
-        constraint = subscription.is_active @ ErrorCode::NoActiveSubscription,
-        constraint = subscription.payment_count >= 3 @ ErrorCode::InsufficientPaymentHistory,
-        constraint = subscription.total_paid >= 1_000_000 @ ErrorCode::InsufficientTotalPaid,
-        seeds::program = lutrii_recurring::ID
+        constraint = subscription.user == reviewer.key() @ ErrorCode::SubscriptionMismatch,
+        constraint = subscription.merchant == merchant.key() @ ErrorCode::SubscriptionMismatch,
+        constraint = subscription.is_active @ ErrorCode::NoActiveSubscription
     )]
     pub subscription: Account<'info, lutrii_recurring::Subscription>,
 
+    #[account(
+        seeds = [b"registry"],
+        bump = registry_state.bump
+    )]
+    pub registry_state: Account<'info, RegistryState>,
+
     #[account(mut)]
     pub reviewer: Signer<'info>,
 
@@ -828,12 +1202,76 @@ pub struct UpdateMerchantTokens<'info> {
     pub usd1_mint: AccountInfo<'info>,
 }
 
+#[derive(Accounts)]
+#[instruction(tag: String)]
+pub struct AddMerchantTag<'info> {
+    #[account(
+        mut,
+        seeds = [b"merchant", owner.key().as_ref()],
+        bump = merchant.bump,
+        has_one = owner @ ErrorCode::UnauthorizedMerchantOwner
+    )]
+    pub merchant: Account<'info, Merchant>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = MerchantTags::SPACE,
+        seeds = [b"merchant_tags", merchant.key().as_ref()],
+        bump
+    )]
+    pub merchant_tags: Account<'info, MerchantTags>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = TagIndex::SPACE,
+        seeds = [b"tag_index", tag.as_bytes()],
+        bump
+    )]
+    pub tag_index: Account<'info, TagIndex>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(tag: String)]
+pub struct RemoveMerchantTag<'info> {
+    #[account(
+        mut,
+        seeds = [b"merchant", owner.key().as_ref()],
+        bump = merchant.bump,
+        has_one = owner @ ErrorCode::UnauthorizedMerchantOwner
+    )]
+    pub merchant: Account<'info, Merchant>,
+
+    #[account(
+        mut,
+        seeds = [b"merchant_tags", merchant.key().as_ref()],
+        bump = merchant_tags.bump
+    )]
+    pub merchant_tags: Account<'info, MerchantTags>,
+
+    #[account(
+        mut,
+        seeds = [b"tag_index", tag.as_bytes()],
+        bump = tag_index.bump
+    )]
+    pub tag_index: Account<'info, TagIndex>,
+
+    pub owner: Signer<'info>,
+}
+
 // ============================================================================
 // Events
 // ============================================================================
 
 #[event]
 pub struct MerchantApplicationSubmitted {
+    pub schema_version: u8,
     pub merchant: Pubkey,
     pub owner: Pubkey,
     pub business_name: String,
@@ -842,6 +1280,7 @@ pub struct MerchantApplicationSubmitted {
 
 #[event]
 pub struct MerchantVerified {
+    pub schema_version: u8,
     pub merchant: Pubkey,
     pub tier: VerificationTier,
     pub timestamp: i64,
@@ -849,6 +1288,7 @@ pub struct MerchantVerified {
 
 #[event]
 pub struct MerchantUpgraded {
+    pub schema_version: u8,
     pub merchant: Pubkey,
     pub new_tier: VerificationTier,
     pub auto_upgraded: bool,
@@ -856,6 +1296,7 @@ pub struct MerchantUpgraded {
 
 #[event]
 pub struct MerchantSuspended {
+    pub schema_version: u8,
     pub merchant: Pubkey,
     pub reason: String,
     pub score: i32,
@@ -863,25 +1304,55 @@ pub struct MerchantSuspended {
 
 #[event]
 pub struct PremiumBadgeActivated {
+    pub schema_version: u8,
     pub merchant: Pubkey,
     pub expires_at: i64,
 }
 
 #[event]
 pub struct ReviewSubmitted {
+    pub schema_version: u8,
     pub merchant: Pubkey,
     pub reviewer: Pubkey,
     pub rating: u8,
     pub new_score: i32,
+    pub subscription: Pubkey,
+    pub plan: Pubkey,
+}
+
+#[event]
+pub struct ReviewThresholdsUpdated {
+    pub schema_version: u8,
+    pub min_payments: u32,
+    pub min_total_paid: u64,
+    pub min_subscription_age: i64,
+    pub timestamp: i64,
 }
 
 #[event]
 pub struct MerchantTokensUpdated {
+    pub schema_version: u8,
     pub merchant: Pubkey,
     pub settlement_token: Pubkey,
     pub accepted_tokens_count: u8,
 }
 
+#[event]
+pub struct MerchantTagAdded {
+    pub schema_version: u8,
+    pub merchant: Pubkey,
+    pub tag: String,
+    pub tag_merchants: u16,
+}
+
+#[event]
+pub struct MerchantTagRemoved {
+    pub schema_version: u8,
+    pub merchant: Pubkey,
+    pub tag: String,
+    pub tag_merchants: u16,
+}
+
 // ============================================================================
 // Errors
 // ============================================================================
@@ -927,13 +1398,13 @@ pub enum ErrorCode {
     #[msg("Must have made at least one payment to submit review")]
     NoPaymentHistory,
 
-    #[msg("Must have made at least 3 payments to submit review (sybil resistance)")]
+    #[msg("Too few payments made to submit review (sybil resistance)")]
     InsufficientPaymentHistory,
 
-    #[msg("Must have paid at least 1 USDC total to submit review (sybil resistance)")]
+    #[msg("Too little paid in total to submit review (sybil resistance)")]
     InsufficientTotalPaid,
 
-    #[msg("Subscription must be at least 7 days old to submit review (sybil resistance)")]
+    #[msg("Subscription is too new to submit review (sybil resistance)")]
     SubscriptionTooNew,
 
     #[msg("Suspension reason must be 1-256 characters")]
@@ -962,6 +1433,33 @@ pub enum ErrorCode {
 
     #[msg("Merchant suspended - cannot configure tokens")]
     MerchantSuspended,
+
+    #[msg("Review thresholds need at least one payment and an age of at most a year")]
+    InvalidReviewThresholds,
+
+    #[msg("Account is not a registry state")]
+    InvalidRegistryState,
+
+    #[msg("Registry state is already on the current layout")]
+    RegistryAlreadyMigrated,
+
+    #[msg("Subscription does not belong to this reviewer and merchant")]
+    SubscriptionMismatch,
+
+    #[msg("Tag must be 1-24 lowercase letters, digits or dashes")]
+    InvalidTag,
+
+    #[msg("Merchant already has this tag")]
+    DuplicateTag,
+
+    #[msg("Merchant has the maximum number of tags")]
+    TooManyTags,
+
+    #[msg("Tag index is full")]
+    TagIndexFull,
+
+    #[msg("Merchant does not have this tag")]
+    TagNotFound,
 }
 
 // ============================================================================
@@ -970,30 +1468,7 @@ pub enum ErrorCode {
 
 /// Reference to lutrii-recurring program for CPI validation and cross-program queries
 pub mod lutrii_recurring {
-    use super::*;
-
-    // This would normally import from the actual program, but for now we define the interface
-    declare_id!("146BGDDLG4yRYXfNCCDdRRmCAYTrGddCgY14n4ekxJyF");
-
-    #[account]
-    pub struct Subscription {
-        pub user: Pubkey,
-        pub merchant: Pubkey,
-        pub user_token_account: Pubkey,
-        pub merchant_token_account: Pubkey,
-        pub amount: u64,
-        pub original_amount: u64,
-        pub frequency_seconds: i64,
-        pub last_payment: i64,
-        pub next_payment: i64,
-        pub total_paid: u64,
-        pub payment_count: u32,
-        pub is_active: bool,
-        pub is_paused: bool,
-        pub max_per_transaction: u64,
-        pub lifetime_cap: u64,
-        pub merchant_name: String,
-        pub created_at: i64,
-        pub bump: u8,
-    }
+    // Account layouts live in lutrii-common so they cannot drift from the
+    // program that writes them
+    pub use lutrii_common::{Subscription, ID};
 }
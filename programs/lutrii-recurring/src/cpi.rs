@@ -0,0 +1,68 @@
+//! Pass-through CPIs to allowlisted external programs (lending, swaps), and
+//! the merchant registry's payment bookkeeping
+//!
+//! Pass-through callers check the target program against an allowlist and
+//! verify the resulting token balance changes themselves.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+
+/// Forward an instruction to an external program, signing as the PDA `authority`
+///
+/// `accounts` are passed through in order; `authority` is marked as a
+/// signer wherever it appears.
+pub(crate) fn forward_signed_cpi<'info>(
+    program: &AccountInfo<'info>,
+    accounts: &[AccountInfo<'info>],
+    authority: &Pubkey,
+    data: Vec<u8>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let metas = accounts
+        .iter()
+        .map(|account| AccountMeta {
+            pubkey: *account.key,
+            is_signer: account.is_signer || account.key == authority,
+            is_writable: account.is_writable,
+        })
+        .collect();
+    let instruction = Instruction {
+        program_id: *program.key,
+        accounts: metas,
+        data,
+    };
+
+    let mut infos = accounts.to_vec();
+    infos.push(program.clone());
+    invoke_signed(&instruction, &infos, signer_seeds)?;
+    Ok(())
+}
+
+/// Record a collected payment of `amount` in the merchant registry
+///
+/// The registry updates the merchant's volume, transaction count and
+/// community score, and may auto-upgrade its tier. The platform PDA signs
+/// to prove the call comes from this program.
+pub(crate) fn record_merchant_payment<'info>(
+    registry_program: &AccountInfo<'info>,
+    merchant: &AccountInfo<'info>,
+    platform_state: &Account<'info, crate::PlatformState>,
+    instructions: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let seeds: &[&[u8]] = &[b"platform", &[platform_state.bump]];
+    lutrii_merchant_registry::cpi::record_transaction(
+        CpiContext::new_with_signer(
+            registry_program.clone(),
+            lutrii_merchant_registry::cpi::accounts::RecordTransaction {
+                merchant: merchant.clone(),
+                recurring_authority: platform_state.to_account_info(),
+                instructions: instructions.clone(),
+            },
+            &[seeds],
+        ),
+        amount,
+        true,
+    )
+}
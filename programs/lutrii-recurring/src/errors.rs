@@ -8,6 +8,9 @@ pub enum ErrorCode {
     #[msg("System is currently paused for emergency maintenance")]
     SystemPaused,
 
+    #[msg("Payments through this token program are paused")]
+    TokenProgramPaused,
+
     #[msg("Payment already in progress - reentrancy protection")]
     PaymentInProgress,
 
@@ -26,6 +29,9 @@ pub enum ErrorCode {
     #[msg("Payment is not yet due - too early to execute")]
     PaymentNotDue,
 
+    #[msg("Previous collection failed - retry backoff has not elapsed")]
+    RetryBackoffActive,
+
     #[msg("Subscription is already paused")]
     AlreadyPaused,
 
@@ -35,6 +41,30 @@ pub enum ErrorCode {
     #[msg("Subscription must be inactive before closing")]
     SubscriptionStillActive,
 
+    #[msg("Payment has already been snoozed this cycle")]
+    AlreadySnoozed,
+
+    #[msg("Snooze must be between 1 day and the merchant's snooze limit")]
+    SnoozeLimitExceeded,
+
+    #[msg("Trial has not been converted - no token delegation yet")]
+    TrialNotConverted,
+
+    #[msg("Subscription has no trial awaiting conversion")]
+    NoTrialToConvert,
+
+    #[msg("Trial period must be between 1 hour and 1 year")]
+    InvalidTrialPeriod,
+
+    #[msg("Trials must pay in the merchant's settlement mint")]
+    TrialSwapUnsupported,
+
+    #[msg("Subscription uses an older account layout; run migrate_subscription")]
+    SubscriptionMigrationRequired,
+
+    #[msg("Payment is claimed by another keeper until the claim expires")]
+    ExecutionClaimed,
+
     // ========================================================================
     // Spending Limits and Safety Errors
     // ========================================================================
@@ -44,9 +74,18 @@ pub enum ErrorCode {
     #[msg("Total paid would exceed lifetime safety cap")]
     ExceedsLifetimeCap,
 
+    #[msg("Lifetime cap fully used - nothing left to delegate")]
+    LifetimeCapExhausted,
+
     #[msg("Daily volume limit exceeded - try again tomorrow")]
     VelocityExceeded,
 
+    #[msg("Platform active subscription cap reached")]
+    PlatformSubscriptionCapReached,
+
+    #[msg("Daily new subscription cap reached - try again tomorrow")]
+    DailyOnboardingCapReached,
+
     #[msg("Price changed more than 10% from original - safety check failed")]
     PriceVarianceExceeded,
 
@@ -62,6 +101,24 @@ pub enum ErrorCode {
     #[msg("Fee cannot exceed 5% (500 basis points)")]
     FeeTooHigh,
 
+    #[msg("Token account is not delegated to the user delegate")]
+    DelegateMismatch,
+
+    #[msg("Delegated amount does not cover the payment")]
+    DelegatedAmountInsufficient,
+
+    #[msg("Token account balance does not cover the payment")]
+    UserBalanceInsufficient,
+
+    #[msg("Token account is frozen")]
+    TokenAccountFrozen,
+
+    #[msg("Charging at creation requires paying in the merchant's settlement mint")]
+    FirstChargeSwapUnsupported,
+
+    #[msg("Fees charged on top of the payment require paying in the merchant's settlement mint")]
+    FeeOnTopSwapUnsupported,
+
     // ========================================================================
     // Validation Errors
     // ========================================================================
@@ -86,6 +143,60 @@ pub enum ErrorCode {
     #[msg("Invalid token account provided")]
     InvalidTokenAccount,
 
+    #[msg("Account is not a subscription of this user")]
+    InvalidSubscriptionAccount,
+
+    #[msg("Billing day must be 1-31 and only applies to monthly subscriptions")]
+    InvalidBillingDay,
+
+    #[msg("UTC offset must be between -12:00 and +14:00")]
+    InvalidUtcOffset,
+
+    #[msg("Collection window cannot be negative")]
+    InvalidCollectionWindow,
+
+    #[msg("Token program must be SPL Token or Token-2022")]
+    InvalidTokenProgram,
+
+    #[msg("Accumulating subscriptions must keep 1-12 missed cycles")]
+    InvalidMissedPaymentPolicy,
+
+    #[msg("Priority reserve cannot exceed 10000 basis points")]
+    InvalidPriorityReserve,
+
+    #[msg("Merchant token account is not in the merchant's settlement currency")]
+    SettlementCurrencyMismatch,
+
+    #[msg("Mint is not accepted for payments")]
+    MintNotAccepted,
+
+    #[msg("Amount is below the mint's minimum")]
+    BelowMintMinimum,
+
+    #[msg("Invalid accepted mint configuration")]
+    InvalidAcceptedMintConfig,
+
+    #[msg("Mint decimals do not match the expected value")]
+    InvalidMintDecimals,
+
+    #[msg("Mint has no circulating supply")]
+    InvalidMintSupply,
+
+    #[msg("Merchants must settle in the configured USDC or USD1 mint")]
+    UnsupportedSettlementMint,
+
+    #[msg("Fee dust threshold cannot exceed the maximum fee")]
+    InvalidFeeDustThreshold,
+
+    #[msg("Pass exactly one subscription or merchant policy")]
+    InvalidFeeExemptionTarget,
+
+    #[msg("Fee holidays need start before end, at most 4 windows")]
+    InvalidFeeHoliday,
+
+    #[msg("Fee schedule minimum exceeds its maximum")]
+    InvalidFeeSchedule,
+
     // ========================================================================
     // Merchant Errors
     // ========================================================================
@@ -98,6 +209,147 @@ pub enum ErrorCode {
     #[msg("Invalid merchant account - PDA validation failed")]
     InvalidMerchantAccount,
 
+    #[msg("User is blocked by this merchant")]
+    UserBlockedByMerchant,
+
+    #[msg("Merchant only accepts allowlisted subscribers")]
+    NotOnMerchantAllowlist,
+
+    #[msg("Merchant has reached its maximum number of active subscribers")]
+    MerchantAtCapacity,
+
+    #[msg("Snooze limit cannot exceed 30 days")]
+    InvalidSnoozeLimit,
+
+    #[msg("Merchant policy account required to cancel the subscription")]
+    MerchantPolicyRequired,
+
+    // ========================================================================
+    // Plan Errors
+    // ========================================================================
+    #[msg("Plan name must be 1-32 characters")]
+    InvalidPlanName,
+
+    #[msg("Plan belongs to a different merchant")]
+    PlanMerchantMismatch,
+
+    #[msg("Plan is not accepting subscriptions")]
+    PlanInactive,
+
+    #[msg("Subscription is already on this plan")]
+    SamePlan,
+
+    #[msg("Plan is not the one the subscription is bound to")]
+    PlanSubscriptionMismatch,
+
+    #[msg("Plan is billed in a different mint")]
+    PlanMintMismatch,
+
+    // ========================================================================
+    // Offer Errors
+    // ========================================================================
+    #[msg("Offer has expired")]
+    OfferExpired,
+
+    #[msg("Offer expiry must be in the future")]
+    InvalidOfferExpiry,
+
+    #[msg("Offer has no inventory left")]
+    OfferSoldOut,
+
+    #[msg("Published offer inventory must be positive")]
+    InvalidOfferInventory,
+
+    #[msg("Offer has not expired")]
+    OfferNotExpired,
+
+    // ========================================================================
+    // Invoice Errors
+    // ========================================================================
+    #[msg("Invoice must have 1-10 line items with non-zero quantity and price")]
+    InvalidLineItems,
+
+    #[msg("Invoice is not open")]
+    InvoiceNotOpen,
+
+    #[msg("Subscription does not belong to the invoiced user and merchant")]
+    InvoiceSubscriptionMismatch,
+
+    #[msg("Invoice is not linked to this subscription")]
+    InvoiceNotLinked,
+
+    // ========================================================================
+    // Due Bucket Errors
+    // ========================================================================
+    #[msg("Due bucket does not cover this merchant and due date")]
+    InvalidDueBucket,
+
+    #[msg("Due bucket is full")]
+    DueBucketFull,
+
+    #[msg("Due bucket still lists subscriptions or has not passed")]
+    DueBucketInUse,
+
+    // ========================================================================
+    // Settlement Errors
+    // ========================================================================
+    #[msg("Batched settlement is disabled for this merchant and mint")]
+    SettlementDisabled,
+
+    #[msg("Settlement vault does not match the settlement account")]
+    InvalidSettlementVault,
+
+    #[msg("Settlement vault is empty")]
+    NothingToSweep,
+
+    #[msg("Fee treasury is disabled for this mint")]
+    FeeTreasuryDisabled,
+
+    #[msg("Fee treasury vault does not match the fee treasury")]
+    InvalidFeeTreasuryVault,
+
+    #[msg("Fee split must have 1-3 distinct recipients summing to 10,000 basis points")]
+    InvalidFeeSplit,
+
+    #[msg("Sweep interval must be positive")]
+    InvalidSweepInterval,
+
+    #[msg("Fee recipient accounts do not match the configured split")]
+    InvalidFeeRecipient,
+
+    #[msg("Fee sweep interval has not elapsed")]
+    SweepTooEarly,
+
+    #[msg("Rebate schedule must have at most 3 tiers, ascending in volume and rebate, each at most 10,000 basis points")]
+    InvalidRebateTiers,
+
+    #[msg("No rebate is due for the last finished period")]
+    NoRebateDue,
+
+    #[msg("Fee treasury has not accrued enough fees to pay the rebate")]
+    InsufficientAccruedFees,
+
+    // ========================================================================
+    // Yield Errors
+    // ========================================================================
+    #[msg("Lending program is not on the yield allowlist")]
+    LendingProgramNotAllowed,
+
+    #[msg("Deposits of held funds are disabled")]
+    YieldDisabled,
+
+    #[msg("Yield config allows at most 4 lending programs and 10,000 basis points")]
+    InvalidYieldConfig,
+
+    #[msg("Vault balance changed by an unexpected amount")]
+    UnexpectedVaultBalance,
+
+    // ========================================================================
+    // Compliance Errors
+    // ========================================================================
+    #[msg("Wallet is on the compliance denylist")]
+    WalletDenylisted,
+
     // ========================================================================
     // Authorization Errors
     // ========================================================================
@@ -113,12 +365,24 @@ pub enum ErrorCode {
     #[msg("Invalid fee wallet mint - must be USDC or USD1 token account")]
     InvalidFeeWalletMint,
 
+    #[msg("Fee account does not match a configured platform fee wallet")]
+    InvalidFeeWallet,
+
     #[msg("No update provided - must specify at least one field to update")]
     NoUpdateProvided,
 
     #[msg("Platform config not initialized")]
     ConfigNotInitialized,
 
+    #[msg("Platform fee wallets are already configured - use schedule_config_change")]
+    ConfigAlreadyInitialized,
+
+    #[msg("Platform state is already at the current version")]
+    AlreadyMigrated,
+
+    #[msg("Account data does not match the expected layout")]
+    InvalidAccountData,
+
     // ========================================================================
     // Multi-Token Errors (Phase 1)
     // ========================================================================
@@ -143,9 +407,184 @@ pub enum ErrorCode {
     #[msg("Slippage exceeded - received amount below minimum")]
     SlippageExceeded,
 
-    #[msg("Invalid Jupiter program provided")]
+    #[msg("Swap program is not on the swap allowlist")]
     InvalidJupiterProgram,
 
     #[msg("Swap route not found for token pair")]
     SwapRouteNotFound,
+
+    #[msg("Oracle price account is invalid or not trading")]
+    InvalidOraclePrice,
+
+    #[msg("Oracle price is too old")]
+    StaleOraclePrice,
+
+    #[msg("Payments from this token are not enabled")]
+    SwapAssetDisabled,
+
+    #[msg("Price feed does not match the swap asset")]
+    InvalidPriceFeed,
+
+    #[msg("Invalid swap asset configuration")]
+    InvalidSwapAssetConfig,
+
+    #[msg("Subscription slippage cap cannot exceed 1,000 basis points")]
+    InvalidSlippage,
+
+    #[msg("Swap config allows at most 4 swap programs and 8 blocked mints")]
+    InvalidSwapConfig,
+
+    #[msg("Swap route must spend from the user's account into the swap output and nothing else the delegate owns")]
+    InvalidSwapRoute,
+
+    #[msg("Swap route passes through a disallowed mint")]
+    DisallowedRouteMint,
+
+    #[msg("Swap route has more accounts than MAX_SWAP_ROUTE_ACCOUNTS")]
+    SwapRouteTooLarge,
+
+    #[msg("Compute budget left is below SWAP_PAYMENT_COMPUTE_UNITS")]
+    InsufficientComputeBudget,
+
+    #[msg("Merchant report is for a different calendar year")]
+    ReportingYearMismatch,
+
+    #[msg("Statement month must be between 1 and 12")]
+    InvalidStatementPeriod,
+
+    #[msg("Merchant statement is for a different month")]
+    StatementPeriodMismatch,
+
+    #[msg("Merchant statement is already closed")]
+    StatementClosed,
+
+    #[msg("Merchant statement's month has not ended yet")]
+    StatementPeriodOpen,
+
+    #[msg("Refund window must be 1 to 31 days and the reserve at most 10,000 basis points")]
+    InvalidRefundLiability,
+
+    #[msg("Governance account must belong to the realm under the given governance program")]
+    InvalidGovernance,
+
+    #[msg("Only the governance pause guardian can do this")]
+    UnauthorizedGuardian,
+
+    #[msg("Proposal must be an executing proposal of the platform's governance")]
+    InvalidProposal,
+
+    #[msg("Spend would exceed the governance cap per proposal")]
+    ProposalSpendCapExceeded,
+
+    #[msg("Emergency pause reason must be non-empty and at most 64 bytes")]
+    InvalidPauseReason,
+
+    #[msg("Emergency pause duration must be between 1 hour and 7 days")]
+    InvalidPauseDuration,
+
+    #[msg("Retry base delay must be between 15 minutes and 7 days")]
+    InvalidRetryPolicy,
+
+    #[msg("Executor reward cannot exceed 100% of the charge")]
+    InvalidExecutorReward,
+
+    #[msg("Executor reward needs both the executor and its token account")]
+    InvalidExecutorRewardAccount,
+
+    #[msg("Proposed amount must be non-zero and differ from the current amount")]
+    InvalidAmountChange,
+
+    #[msg("No amount change is pending, or it has expired")]
+    NoPendingAmountChange,
+
+    #[msg("Accepted amount does not match the merchant's proposal")]
+    AmountChangeMismatch,
+
+    #[msg("Admin council needs 1 to 10 distinct members and a threshold they can reach")]
+    InvalidCouncil,
+
+    #[msg("Signer is not a member of the admin council")]
+    NotCouncilMember,
+
+    #[msg("This change needs admin council approval")]
+    CouncilApprovalRequired,
+
+    #[msg("Member already approved this proposal")]
+    AlreadyApproved,
+
+    #[msg("Council proposal was already executed or has expired")]
+    CouncilProposalClosed,
+
+    #[msg("Council proposal does not have enough approvals")]
+    CouncilThresholdNotMet,
+
+    #[msg("Daily volume limit must be greater than zero")]
+    InvalidDailyVolumeLimit,
+
+    #[msg("Config change delay must be between 1 hour and 30 days")]
+    InvalidConfigChangeDelay,
+
+    #[msg("No config change is scheduled")]
+    NoPendingConfigChange,
+
+    #[msg("Scheduled config change is still in its timelock")]
+    ConfigChangeTimelocked,
+
+    #[msg("Escrow vault does not belong to the escrow")]
+    InvalidEscrowVault,
+
+    #[msg("Escrow balance does not cover the payment")]
+    EscrowBalanceInsufficient,
+
+    #[msg("Metered charge exceeds the subscription's usage cap for this period")]
+    UsageCapExceeded,
+
+    #[msg("Invalid promo code: code 1-16 bytes, a real discount over at least one payment, future expiry")]
+    InvalidPromo,
+
+    #[msg("Promo code has expired")]
+    PromoExpired,
+
+    #[msg("Promo code has no redemptions left")]
+    PromoExhausted,
+
+    #[msg("Accounts passed do not match the council action")]
+    CouncilActionAccountsMismatch,
+
+    #[msg("Subscription is prepaid; pass its escrow and escrow vault")]
+    EscrowRequired,
+
+    #[msg("Prepaid subscriptions cannot be paid through a swap")]
+    EscrowSwapUnsupported,
+
+    #[msg("Subscription is bound to a plan; pass the plan")]
+    PlanRequired,
+
+    #[msg("Token account already pays its subscriptions the other way, directly or through swaps")]
+    PaymentModeMismatch,
+
+    #[msg("Oracle confidence interval is too wide relative to the price")]
+    OraclePriceUncertain,
+
+    #[msg("Plan-bound subscriptions charge the plan price; amounts are changed through the plan")]
+    PlanBoundAmount,
+
+    #[msg("Invoice is linked to a subscription; pass the subscription")]
+    InvoiceSubscriptionRequired,
+
+    #[msg("Billing weekday must be 1-7 and only applies to whole-week subscriptions without a billing day")]
+    InvalidBillingWeekday,
+
+    #[msg("A config change is already scheduled; execute it or replace it through schedule_config_change")]
+    ConfigChangePending,
+}
+
+impl From<lutrii_core::CoreError> for ErrorCode {
+    fn from(error: lutrii_core::CoreError) -> Self {
+        match error {
+            lutrii_core::CoreError::Overflow => ErrorCode::Overflow,
+            lutrii_core::CoreError::InsufficientAmount => ErrorCode::InsufficientAmount,
+            lutrii_core::CoreError::InvalidPrice => ErrorCode::InvalidOraclePrice,
+        }
+    }
 }
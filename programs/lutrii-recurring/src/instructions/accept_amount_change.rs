@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+use lutrii_common::{EVENT_SCHEMA_VERSION, Subscription};
+use lutrii_core::proration;
+use crate::errors::ErrorCode;
+use crate::state::RevenueForecast;
+use crate::AmountChangeAccepted;
+
+/// Accept the amount change a merchant proposed (user only)
+///
+/// From the next payment the subscription charges the new amount, which
+/// also replaces `original_amount` as the baseline the 10% price variance
+/// check measures against. The current cycle is not prorated. Plan-bound
+/// subscriptions are refused, since payments reset them to the plan price.
+///
+/// # Arguments
+/// * `new_amount` - Amount the user agrees to; must equal the pending
+///   proposal, so a proposal replaced after the user reviewed it is not
+///   accepted by mistake
+///
+/// # Security
+/// - Only the subscription owner can accept (has_one + signer)
+/// - New amount, with any pending proration, must fit the user's
+///   per-transaction cap
+#[derive(Accounts)]
+pub struct AcceptAmountChange<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        bump = subscription.bump,
+        has_one = user @ ErrorCode::UnauthorizedUser
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    pub user: Signer<'info>,
+
+    /// Merchant's revenue forecast in the subscription's mint, when kept
+    #[account(
+        mut,
+        seeds = [b"revenue_forecast", subscription.merchant.as_ref(), subscription.mint.as_ref()],
+        bump = revenue_forecast.bump
+    )]
+    pub revenue_forecast: Option<Box<Account<'info, RevenueForecast>>>,
+}
+
+pub fn handler(ctx: Context<AcceptAmountChange>, new_amount: u64) -> Result<()> {
+    let subscription = &mut ctx.accounts.subscription;
+    let now = Clock::get()?.unix_timestamp;
+
+    // ============================================================================
+    // CHECKS
+    // ============================================================================
+
+    require!(subscription.is_active, ErrorCode::SubscriptionInactive);
+    require!(subscription.plan == Pubkey::default(), ErrorCode::PlanBoundAmount);
+    let pending = subscription
+        .pending_amount_change(now)
+        .ok_or(ErrorCode::NoPendingAmountChange)?;
+    require!(pending == new_amount, ErrorCode::AmountChangeMismatch);
+
+    let (next_charge, _) = proration::apply_adjustment(new_amount, subscription.pending_proration)
+        .map_err(ErrorCode::from)?;
+    require!(
+        next_charge <= subscription.max_per_transaction,
+        ErrorCode::ExceedsTransactionCap
+    );
+
+    // ============================================================================
+    // EFFECTS
+    // ============================================================================
+
+    let old_amount = subscription.amount;
+    let forecast_entry = subscription.forecast_entry();
+
+    subscription.amount = new_amount;
+    subscription.original_amount = new_amount; // variance is measured against the accepted amount
+    subscription.pending_amount = 0;
+    subscription.pending_amount_expires = 0;
+    if let Some(revenue_forecast) = ctx.accounts.revenue_forecast.as_mut() {
+        revenue_forecast.reschedule(forecast_entry, subscription.forecast_entry(), now)?;
+    }
+
+    emit!(AmountChangeAccepted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        subscription: subscription.key(),
+        user: subscription.user,
+        merchant: subscription.merchant,
+        old_amount,
+        new_amount,
+        timestamp: now,
+    });
+
+    msg!("Amount change accepted: {} -> {}", old_amount, new_amount);
+    Ok(())
+}
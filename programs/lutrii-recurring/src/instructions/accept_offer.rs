@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::SubscriptionOffer;
+use crate::{CreateOptions, CreateSubscription, CreateSubscriptionBumps, OfferAccepted};
+// Client modules generated for the nested create_subscription accounts
+use crate::{__client_accounts_create_subscription, __cpi_client_accounts_create_subscription};
+
+/// Accept a merchant offer, creating the subscription on its terms (user only)
+///
+/// Takes every create_subscription account plus the offer; the amount,
+/// frequency, caps and name come from the offer rather than the client.
+/// Takes one unit of inventory; the last acceptance closes the offer and
+/// returns its rent to the merchant owner.
+///
+/// # Security
+/// - Offer must belong to the merchant and be addressed to the signer, or
+///   published
+/// - Settlement account must be the one named in the offer
+#[derive(Accounts)]
+pub struct AcceptOffer<'info> {
+    #[account(
+        mut,
+        constraint = offer.merchant == create.merchant.key() @ ErrorCode::InvalidMerchantAccount,
+        constraint = offer.is_open_to(&create.user.key()) @ ErrorCode::UnauthorizedUser,
+        constraint = offer.merchant_token_account == create.merchant_token_account.key() @ ErrorCode::InvalidTokenAccount
+    )]
+    pub offer: Account<'info, SubscriptionOffer>,
+
+    /// CHECK: Merchant owner wallet that funded the offer; receives its rent
+    #[account(mut, address = create.merchant.owner)]
+    pub merchant_owner: UncheckedAccount<'info>,
+
+    pub create: CreateSubscription<'info>,
+}
+
+pub fn handler(ctx: Context<AcceptOffer>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let offer = &ctx.accounts.offer;
+    require!(!offer.is_expired(now), ErrorCode::OfferExpired);
+    require!(offer.remaining() > 0, ErrorCode::OfferSoldOut);
+
+    let terms = offer.terms.clone();
+    ctx.accounts.create.create(
+        &ctx.bumps.create,
+        terms.amount,
+        terms.frequency_seconds,
+        terms.max_per_transaction,
+        terms.lifetime_cap,
+        terms.merchant_name,
+        CreateOptions::default(),
+    )?;
+
+    let offer = &mut ctx.accounts.offer;
+    offer.acceptances = offer.acceptances.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+    emit!(OfferAccepted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        offer: offer.key(),
+        subscription: ctx.accounts.create.subscription.key(),
+        merchant: offer.merchant,
+        user: ctx.accounts.create.user.key(),
+        timestamp: now,
+    });
+
+    msg!("Offer accepted: {} remaining", offer.remaining());
+    if offer.remaining() == 0 {
+        offer.close(ctx.accounts.merchant_owner.to_account_info())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::state::DenylistEntry;
+use crate::errors::ErrorCode;
+use crate::{PlatformState, WalletDenylisted};
+
+/// Add a wallet to the compliance denylist (admin only)
+///
+/// Denied wallets cannot create subscriptions or receive payments, whether
+/// they act as the subscriber or the merchant.
+///
+/// # Arguments
+/// * `wallet` - Wallet to deny
+///
+/// # Security
+/// - Only the platform authority can call this
+/// - init constraint rejects wallets that are already denied
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct AddToDenylist<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = DenylistEntry::LEN,
+        seeds = [b"denylist", wallet.as_ref()],
+        bump
+    )]
+    pub denylist_entry: Account<'info, DenylistEntry>,
+
+    #[account(
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<AddToDenylist>, wallet: Pubkey) -> Result<()> {
+    let entry = &mut ctx.accounts.denylist_entry;
+    let clock = Clock::get()?;
+
+    entry.wallet = wallet;
+    entry.added_by = ctx.accounts.authority.key();
+    entry.added_at = clock.unix_timestamp;
+    entry.bump = ctx.bumps.denylist_entry;
+
+    emit!(WalletDenylisted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        wallet,
+        denied: true,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("⛔ Wallet added to denylist: {}", wallet);
+    Ok(())
+}
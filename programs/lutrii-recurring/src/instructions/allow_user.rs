@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::state::AllowlistEntry;
+use crate::AllowlistUpdated;
+
+/// Pre-approve a wallet to subscribe to an allowlist-only merchant (merchant owner only)
+///
+/// # Arguments
+/// * `user` - Subscriber wallet to approve
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct AllowUser<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = AllowlistEntry::LEN,
+        seeds = [b"allowlist", merchant.key().as_ref(), user.as_ref()],
+        bump
+    )]
+    pub allowlist_entry: Account<'info, AllowlistEntry>,
+
+    #[account(
+        seeds = [b"merchant", owner.key().as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<AllowUser>, user: Pubkey) -> Result<()> {
+    let entry = &mut ctx.accounts.allowlist_entry;
+    let clock = Clock::get()?;
+
+    entry.merchant = ctx.accounts.merchant.key();
+    entry.user = user;
+    entry.added_at = clock.unix_timestamp;
+    entry.bump = ctx.bumps.allowlist_entry;
+
+    emit!(AllowlistUpdated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        merchant: entry.merchant,
+        user,
+        allowed: true,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("✅ User {} allowlisted by merchant {}", user, entry.merchant);
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::{AdminCouncil, CouncilProposal};
+use crate::CouncilActionApproved;
+
+/// Approve an open council proposal (council member only)
+#[derive(Accounts)]
+pub struct ApproveCouncilAction<'info> {
+    #[account(
+        seeds = [b"admin_council"],
+        bump = admin_council.bump
+    )]
+    pub admin_council: Account<'info, AdminCouncil>,
+
+    #[account(
+        mut,
+        seeds = [b"council_proposal", proposal.proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, CouncilProposal>,
+
+    pub member: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<ApproveCouncilAction>) -> Result<()> {
+    let council = &ctx.accounts.admin_council;
+    let index = council
+        .member_index(&ctx.accounts.member.key())
+        .ok_or(ErrorCode::NotCouncilMember)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let proposal = &mut ctx.accounts.proposal;
+    require!(!proposal.executed, ErrorCode::CouncilProposalClosed);
+    require!(now < proposal.expires_at, ErrorCode::CouncilProposalClosed);
+    require!(proposal.approve(index), ErrorCode::AlreadyApproved);
+
+    emit!(CouncilActionApproved {
+        schema_version: EVENT_SCHEMA_VERSION,
+        proposal: proposal.key(),
+        member: ctx.accounts.member.key(),
+        approvals: proposal.approval_count(),
+        threshold: council.threshold,
+        timestamp: now,
+    });
+
+    msg!(
+        "Council proposal {}: {} of {} approvals",
+        proposal.proposal_id,
+        proposal.approval_count(),
+        council.threshold
+    );
+    Ok(())
+}
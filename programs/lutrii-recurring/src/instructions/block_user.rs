@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::state::BlocklistEntry;
+use crate::UserBlockUpdated;
+
+/// Block a subscriber wallet from a merchant (merchant owner only)
+///
+/// Used for abuse and chargebacks. The user cannot create new subscriptions
+/// with this merchant, and payments on their existing subscription are
+/// refused until it is cancelled.
+///
+/// # Arguments
+/// * `user` - Subscriber wallet to block
+///
+/// # Security
+/// - Merchant PDA is derived from the signing owner, so only the merchant
+///   owner can manage its blocklist
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct BlockUser<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = BlocklistEntry::LEN,
+        seeds = [b"blocklist", merchant.key().as_ref(), user.as_ref()],
+        bump
+    )]
+    pub blocklist_entry: Account<'info, BlocklistEntry>,
+
+    #[account(
+        seeds = [b"merchant", owner.key().as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<BlockUser>, user: Pubkey) -> Result<()> {
+    let entry = &mut ctx.accounts.blocklist_entry;
+    let clock = Clock::get()?;
+
+    entry.merchant = ctx.accounts.merchant.key();
+    entry.user = user;
+    entry.blocked_at = clock.unix_timestamp;
+    entry.bump = ctx.bumps.blocklist_entry;
+
+    emit!(UserBlockUpdated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        merchant: entry.merchant,
+        user,
+        blocked: true,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("⛔ User {} blocked by merchant {}", user, entry.merchant);
+    Ok(())
+}
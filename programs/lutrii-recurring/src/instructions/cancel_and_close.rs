@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{TokenAccount, TokenInterface};
+use lutrii_common::{Subscription, EVENT_SCHEMA_VERSION};
+use crate::errors::ErrorCode;
+use crate::state::{DueBucket, MerchantPolicy, RevenueForecast, UserDelegate, UserStats};
+use crate::{PlatformState, SubscriptionCancelled};
+
+/// Cancel a subscription and reclaim its rent in one instruction
+///
+/// Does everything `cancel_subscription` does (releases the delegation
+/// share, frees the merchant slot, emits `SubscriptionCancelled`) and then
+/// closes the subscription PDA to the user, so no rent is left behind by a
+/// forgotten `close_subscription`.
+///
+/// # Security
+/// - Only the subscription owner can cancel (has_one + signer)
+#[derive(Accounts)]
+pub struct CancelAndClose<'info> {
+    #[account(
+        mut,
+        close = user,
+        seeds = [
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        bump = subscription.bump,
+        has_one = user @ ErrorCode::UnauthorizedUser
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform_state.bump
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// CHECK: Merchant policy PDA; may be uninitialized for older subscriptions
+    #[account(
+        mut,
+        seeds = [b"merchant_policy", subscription.merchant.as_ref()],
+        bump
+    )]
+    pub merchant_policy: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.key() == subscription.user_token_account @ ErrorCode::InvalidTokenAccount
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user_delegate", subscription.user_token_account.as_ref()],
+        bump = user_delegate.bump
+    )]
+    pub user_delegate: Account<'info, UserDelegate>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Spending analytics for the user, when they created it
+    #[account(
+        mut,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump = user_stats.bump
+    )]
+    pub user_stats: Option<Account<'info, UserStats>>,
+
+    /// Due-date bucket listing the subscription, when indexed
+    #[account(
+        mut,
+        constraint = due_bucket.covers(&subscription.merchant, subscription.next_payment) @ ErrorCode::InvalidDueBucket
+    )]
+    pub due_bucket: Option<Account<'info, DueBucket>>,
+
+    /// Merchant's revenue forecast in the subscription's mint, when kept
+    #[account(
+        mut,
+        seeds = [b"revenue_forecast", subscription.merchant.as_ref(), subscription.mint.as_ref()],
+        bump = revenue_forecast.bump
+    )]
+    pub revenue_forecast: Option<Box<Account<'info, RevenueForecast>>>,
+}
+
+pub fn handler(ctx: Context<CancelAndClose>) -> Result<()> {
+    let subscription = &ctx.accounts.subscription;
+    require!(subscription.is_active, ErrorCode::SubscriptionInactive);
+
+    // Give back this subscription's share of the delegation
+    let user_delegate = &mut ctx.accounts.user_delegate;
+    user_delegate.remove_subscription(subscription);
+    user_delegate.sync_approval(
+        user_delegate.to_account_info(),
+        ctx.accounts.user_token_account.to_account_info(),
+        ctx.accounts.user.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+    )?;
+
+    let platform = &mut ctx.accounts.platform_state;
+    platform.total_subscriptions = platform.total_subscriptions.saturating_sub(1);
+    MerchantPolicy::release_slot(&ctx.accounts.merchant_policy)?;
+    if let Some(user_stats) = ctx.accounts.user_stats.as_mut() {
+        user_stats.subscription_removed();
+    }
+    if let Some(due_bucket) = ctx.accounts.due_bucket.as_mut() {
+        due_bucket.remove(&subscription.key());
+    }
+    let now = Clock::get()?.unix_timestamp;
+    if let Some(revenue_forecast) = ctx.accounts.revenue_forecast.as_mut() {
+        revenue_forecast.reschedule(subscription.forecast_entry(), None, now)?;
+    }
+
+    emit!(SubscriptionCancelled {
+        schema_version: EVENT_SCHEMA_VERSION,
+        subscription: subscription.key(),
+        user: subscription.user,
+        total_paid: subscription.total_paid,
+        payment_count: subscription.payment_count,
+        timestamp: now,
+    });
+
+    msg!("Subscription cancelled and closed, rent reclaimed");
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::SubscriptionOffer;
+use crate::OfferCancelled;
+
+/// Withdraw an offer and reclaim its rent (merchant owner only)
+///
+/// Works for targeted and published offers; remaining inventory is dropped.
+#[derive(Accounts)]
+pub struct CancelOffer<'info> {
+    #[account(
+        mut,
+        close = owner,
+        has_one = merchant @ ErrorCode::InvalidMerchantAccount
+    )]
+    pub offer: Account<'info, SubscriptionOffer>,
+
+    #[account(
+        seeds = [b"merchant", owner.key().as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<CancelOffer>) -> Result<()> {
+    let offer = &ctx.accounts.offer;
+
+    emit!(OfferCancelled {
+        schema_version: EVENT_SCHEMA_VERSION,
+        offer: offer.key(),
+        merchant: offer.merchant,
+        user: offer.user,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Offer cancelled");
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use lutrii_common::{EVENT_SCHEMA_VERSION, Subscription};
+use lutrii_core::{proration, schedule};
+use crate::errors::ErrorCode;
+use crate::state::{AcceptedMint, Plan, RevenueForecast};
+use crate::PlanChanged;
+
+/// Move a subscription to another plan of the same merchant (user only)
+///
+/// The subscription keeps its PDA, payment_count, total_paid and created_at,
+/// so review eligibility built on that history survives upgrades and
+/// downgrades. The current cycle still ends at `next_payment`; if it was
+/// already paid, the price difference for its remaining time is queued as a
+/// proration charge or credit on the next payment.
+///
+/// # Security
+/// - Only the subscription owner can change plans (has_one + signer)
+/// - Target plan must belong to the subscription's merchant and be active
+/// - New price must fit the user's per-transaction cap and meet the
+///   minimum of the mint the subscription is billed in
+#[derive(Accounts)]
+pub struct ChangePlan<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        bump = subscription.bump,
+        has_one = user @ ErrorCode::UnauthorizedUser
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(
+        constraint = new_plan.merchant == subscription.merchant @ ErrorCode::PlanMerchantMismatch,
+        constraint = new_plan.is_active @ ErrorCode::PlanInactive
+    )]
+    pub new_plan: Account<'info, Plan>,
+
+    /// Merchant's token account; its mint is the one the plan price is billed in
+    #[account(
+        constraint = merchant_token_account.key() == subscription.merchant_token_account @ ErrorCode::InvalidTokenAccount
+    )]
+    pub merchant_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Registry entry for the billing mint
+    #[account(
+        seeds = [b"accepted_mint", merchant_token_account.mint.as_ref()],
+        bump = accepted_mint.bump
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+
+    pub user: Signer<'info>,
+
+    /// Merchant's revenue forecast in the subscription's mint, when kept
+    #[account(
+        mut,
+        seeds = [b"revenue_forecast", subscription.merchant.as_ref(), subscription.mint.as_ref()],
+        bump = revenue_forecast.bump
+    )]
+    pub revenue_forecast: Option<Box<Account<'info, RevenueForecast>>>,
+}
+
+pub fn handler(ctx: Context<ChangePlan>) -> Result<()> {
+    let new_plan = &ctx.accounts.new_plan;
+    let subscription = &mut ctx.accounts.subscription;
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+    // Plans price wall-clock periods; slot schedules bill the same span in slots
+    let unit = subscription.schedule_unit;
+    let new_frequency = unit.span(new_plan.frequency_seconds);
+
+    // ============================================================================
+    // CHECKS
+    // ============================================================================
+
+    require!(subscription.is_active, ErrorCode::SubscriptionInactive);
+    require!(subscription.plan != new_plan.key(), ErrorCode::SamePlan);
+    require!(
+        new_plan.price >= ctx.accounts.accepted_mint.min_amount,
+        ErrorCode::BelowMintMinimum
+    );
+    require!(
+        subscription.billing_day == 0 || schedule::is_monthly(new_plan.frequency_seconds),
+        ErrorCode::InvalidBillingDay
+    );
+    require!(
+        subscription.billing_weekday == 0 || schedule::is_weekly(new_plan.frequency_seconds),
+        ErrorCode::InvalidBillingWeekday
+    );
+
+    // Prorate the rest of the current cycle only if it was paid at the old price
+    let adjustment = if subscription.payment_count > 0 && !subscription.is_paused {
+        let adjustment = proration::plan_change_adjustment(
+            subscription.amount,
+            new_plan.price,
+            subscription.next_payment.saturating_sub(unit.now(&clock)),
+            subscription.frequency_seconds,
+        )
+        .map_err(ErrorCode::from)?;
+        i64::try_from(adjustment).map_err(|_| error!(ErrorCode::Overflow))?
+    } else {
+        0
+    };
+    let pending_proration = subscription
+        .pending_proration
+        .checked_add(adjustment)
+        .ok_or(ErrorCode::Overflow)?;
+
+    let (next_charge, _) = proration::apply_adjustment(new_plan.price, pending_proration)
+        .map_err(ErrorCode::from)?;
+    require!(
+        next_charge <= subscription.max_per_transaction,
+        ErrorCode::ExceedsTransactionCap
+    );
+
+    // ============================================================================
+    // EFFECTS
+    // ============================================================================
+
+    let old_plan = subscription.plan;
+    let old_amount = subscription.amount;
+    let forecast_entry = subscription.forecast_entry();
+
+    subscription.plan = new_plan.key();
+    subscription.amount = new_plan.price;
+    subscription.original_amount = new_plan.price; // variance is measured against the new plan
+    subscription.frequency_seconds = new_frequency;
+    subscription.pending_proration = pending_proration;
+    if let Some(revenue_forecast) = ctx.accounts.revenue_forecast.as_mut() {
+        revenue_forecast.reschedule(forecast_entry, subscription.forecast_entry(), now)?;
+    }
+
+    emit!(PlanChanged {
+        schema_version: EVENT_SCHEMA_VERSION,
+        subscription: subscription.key(),
+        user: subscription.user,
+        old_plan,
+        new_plan: subscription.plan,
+        old_amount,
+        new_amount: subscription.amount,
+        proration_adjustment: adjustment,
+        timestamp: now,
+    });
+
+    msg!(
+        "Plan changed: {} -> {} (proration {})",
+        old_amount,
+        subscription.amount,
+        adjustment
+    );
+    Ok(())
+}
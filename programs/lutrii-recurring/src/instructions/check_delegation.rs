@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token_interface::TokenAccount;
+use lutrii_common::{EVENT_SCHEMA_VERSION, Subscription};
+use lutrii_core::proration;
+use crate::errors::ErrorCode;
+use crate::state::{MerchantPolicy, UserDelegate};
+use crate::{DelegationChecked, PlatformState};
+
+/// Record whether a subscription's delegation still covers its next payment
+///
+/// Permissionless: cranks and dashboards call this to triage subscriptions
+/// before attempting payments. Another dApp approving a different delegate
+/// than the shared user delegate, or spent-down allowance, flips
+/// `delegation_healthy` to false. A fee the user bears on top counts
+/// toward the amount the delegation must cover.
+#[derive(Accounts)]
+pub struct CheckDelegation<'info> {
+    #[account(mut)]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(seeds = [b"platform"], bump = platform_state.bump)]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        constraint = user_token_account.key() == subscription.user_token_account @ ErrorCode::InvalidTokenAccount
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Shared delegate for the user's token account
+    #[account(
+        seeds = [b"user_delegate", subscription.user_token_account.as_ref()],
+        bump = user_delegate.bump
+    )]
+    pub user_delegate: Account<'info, UserDelegate>,
+
+    /// CHECK: Merchant policy PDA, for a negotiated fee schedule; may be
+    /// uninitialized. Required so the check prices the fee as executions do
+    #[account(
+        seeds = [b"merchant_policy", subscription.merchant.as_ref()],
+        bump
+    )]
+    pub merchant_policy: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<CheckDelegation>) -> Result<()> {
+    let user_token_account = &ctx.accounts.user_token_account;
+    let subscription = &mut ctx.accounts.subscription;
+
+    let delegate_matches =
+        user_token_account.delegate == COption::Some(ctx.accounts.user_delegate.key());
+    let delegated_amount = if delegate_matches {
+        user_token_account.delegated_amount
+    } else {
+        0
+    };
+    let (charge, _) =
+        proration::apply_adjustment(subscription.amount, subscription.pending_proration)
+            .map_err(ErrorCode::from)?;
+    let charge = subscription.discounted(charge);
+    let custom_fee = MerchantPolicy::fee_schedule_of(&ctx.accounts.merchant_policy)?;
+    let required_amount = ctx
+        .accounts
+        .platform_state
+        .payment_outflow(subscription.fee_bearer, charge, custom_fee)?;
+    let healthy = delegate_matches
+        && delegated_amount >= ctx.accounts.user_delegate.to_token_units(required_amount)?;
+
+    subscription.delegation_healthy = healthy;
+
+    emit!(DelegationChecked {
+        schema_version: EVENT_SCHEMA_VERSION,
+        subscription: subscription.key(),
+        user: subscription.user,
+        delegate_matches,
+        delegated_amount,
+        required_amount,
+        healthy,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    if healthy {
+        msg!("Delegation healthy: {} delegated", delegated_amount);
+    } else {
+        msg!(
+            "⚠️ Delegation unhealthy: {} delegated, {} required",
+            delegated_amount,
+            required_amount
+        );
+    }
+
+    Ok(())
+}
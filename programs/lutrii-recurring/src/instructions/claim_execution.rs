@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use lutrii_common::{Subscription, EVENT_SCHEMA_VERSION};
+use lutrii_core::schedule;
+use crate::errors::ErrorCode;
+use crate::{ExecutionClaimed, PlatformState, EXECUTION_CLAIM_SLOTS};
+
+/// Reserve a due payment for the calling keeper (permissionless)
+///
+/// Keepers claim before executing so competitors skip the payment instead
+/// of racing for it with ever higher priority fees. While the claim is live
+/// `execute_payment` only accepts the claimant; it lapses after
+/// `EXECUTION_CLAIM_SLOTS`, so a keeper that claims and walks away delays
+/// the payment by at most one window. Executing clears the claim.
+///
+/// # Security
+/// - Only payments `execute_payment` would accept now can be claimed
+/// - A live claim cannot be taken over; the holder may extend it
+#[derive(Accounts)]
+pub struct ClaimExecution<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        bump = subscription.bump
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(
+        seeds = [b"platform"],
+        bump = platform_state.bump
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    pub keeper: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<ClaimExecution>) -> Result<()> {
+    let subscription = &mut ctx.accounts.subscription;
+    let keeper = ctx.accounts.keeper.key();
+    let clock = Clock::get()?;
+    let now = subscription.schedule_unit.now(&clock);
+
+    require!(
+        !ctx.accounts.platform_state.is_paused(clock.unix_timestamp),
+        ErrorCode::SystemPaused
+    );
+    require!(subscription.is_active, ErrorCode::SubscriptionInactive);
+    require!(!subscription.is_paused, ErrorCode::SubscriptionPaused);
+    require!(!subscription.delegation_deferred, ErrorCode::TrialNotConverted);
+    require!(
+        subscription.layout_version == Subscription::CURRENT_VERSION,
+        ErrorCode::SubscriptionMigrationRequired
+    );
+    require!(
+        schedule::is_due(now, subscription.next_payment),
+        ErrorCode::PaymentNotDue
+    );
+    require!(
+        now >= subscription.next_retry_at,
+        ErrorCode::RetryBackoffActive
+    );
+    require!(
+        !subscription.claimed_by_other(&keeper, clock.slot),
+        ErrorCode::ExecutionClaimed
+    );
+
+    subscription.execution_claimant = keeper;
+    subscription.claim_expires_slot = clock
+        .slot
+        .checked_add(EXECUTION_CLAIM_SLOTS)
+        .ok_or(ErrorCode::Overflow)?;
+
+    emit!(ExecutionClaimed {
+        schema_version: EVENT_SCHEMA_VERSION,
+        subscription: subscription.key(),
+        claimant: keeper,
+        expires_slot: subscription.claim_expires_slot,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Execution claimed until slot {}", subscription.claim_expires_slot);
+    Ok(())
+}
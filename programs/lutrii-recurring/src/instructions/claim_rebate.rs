@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::transfer_checked;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use crate::errors::ErrorCode;
+use crate::state::{FeeTreasury, MerchantVolume};
+use crate::RebateClaimed;
+
+/// Pay a merchant's fee rebate for the last finished period (merchant only)
+///
+/// The rebate is the share of that period's platform fees set by the
+/// highest tier of the fee treasury's schedule the period's volume reached.
+/// It comes out of fees accrued since the last sweep, so a claim made right
+/// after a sweep can fail until enough fees accrue again.
+///
+/// # Security
+/// - Signed by the merchant owner; paid to a token account the owner chooses
+/// - One claim per period
+#[derive(Accounts)]
+pub struct ClaimRebate<'info> {
+    #[account(
+        seeds = [b"merchant", owner.key().as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"merchant_volume", merchant.key().as_ref(), mint.key().as_ref()],
+        bump = merchant_volume.bump
+    )]
+    pub merchant_volume: Account<'info, MerchantVolume>,
+
+    #[account(
+        mut,
+        seeds = [b"fee_treasury", mint.key().as_ref()],
+        bump = fee_treasury.bump,
+        has_one = vault @ ErrorCode::InvalidFeeTreasuryVault,
+        has_one = mint
+    )]
+    pub fee_treasury: Account<'info, FeeTreasury>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    pub owner: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<ClaimRebate>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    let merchant_volume = &mut ctx.accounts.merchant_volume;
+    merchant_volume.roll(now);
+    require!(merchant_volume.rebate_claimable(), ErrorCode::NoRebateDue);
+
+    let (rebate_bps, rebate) = ctx
+        .accounts
+        .fee_treasury
+        .rebate(merchant_volume.closed_volume, merchant_volume.closed_fees);
+    require!(rebate > 0, ErrorCode::NoRebateDue);
+    require!(
+        rebate <= ctx.accounts.fee_treasury.accrued,
+        ErrorCode::InsufficientAccruedFees
+    );
+
+    // ============================================================================
+    // EFFECTS
+    // ============================================================================
+
+    merchant_volume.claimed_period = merchant_volume.closed_period;
+    let period = merchant_volume.closed_period;
+    let volume = merchant_volume.closed_volume;
+
+    let treasury = &mut ctx.accounts.fee_treasury;
+    treasury.accrued -= rebate;
+
+    // ============================================================================
+    // INTERACTIONS
+    // ============================================================================
+
+    let seeds = &[b"fee_treasury", treasury.mint.as_ref(), &[treasury.bump]];
+    let signer = &[&seeds[..]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: treasury.to_account_info(),
+            },
+            signer,
+        ),
+        rebate,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    emit!(RebateClaimed {
+        schema_version: EVENT_SCHEMA_VERSION,
+        merchant: ctx.accounts.merchant.key(),
+        mint: treasury.mint,
+        period,
+        volume,
+        rebate_bps,
+        amount: rebate,
+        timestamp: now,
+    });
+
+    msg!("✅ Rebate claimed for period {}: {}", period, rebate);
+    Ok(())
+}
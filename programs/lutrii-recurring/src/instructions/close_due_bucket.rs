@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::state::DueBucket;
+
+/// Close an empty, past due-date bucket and refund its payer (permissionless)
+#[derive(Accounts)]
+pub struct CloseDueBucket<'info> {
+    #[account(
+        mut,
+        close = payer,
+        has_one = payer,
+        seeds = [b"due_bucket", due_bucket.merchant.as_ref(), due_bucket.day.to_le_bytes().as_ref()],
+        bump = due_bucket.bump
+    )]
+    pub due_bucket: Account<'info, DueBucket>,
+
+    /// CHECK: Receives the rent; must be the account that opened the bucket
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<CloseDueBucket>) -> Result<()> {
+    let due_bucket = &ctx.accounts.due_bucket;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        due_bucket.subscriptions.is_empty() && due_bucket.day < DueBucket::day_of(now),
+        ErrorCode::DueBucketInUse
+    );
+
+    msg!("Due bucket for day {} closed", due_bucket.day);
+    Ok(())
+}
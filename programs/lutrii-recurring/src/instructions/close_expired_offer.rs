@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::SubscriptionOffer;
+use crate::OfferCancelled;
+
+/// Close a lapsed offer and refund its rent to the merchant owner (permissionless)
+#[derive(Accounts)]
+pub struct CloseExpiredOffer<'info> {
+    #[account(
+        mut,
+        close = merchant_owner,
+        has_one = merchant @ ErrorCode::InvalidMerchantAccount
+    )]
+    pub offer: Account<'info, SubscriptionOffer>,
+
+    /// Merchant account from merchant registry
+    #[account(
+        seeds = [b"merchant", merchant.owner.as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    /// CHECK: Merchant owner wallet that funded the offer; receives its rent
+    #[account(mut, address = merchant.owner)]
+    pub merchant_owner: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<CloseExpiredOffer>) -> Result<()> {
+    let offer = &ctx.accounts.offer;
+    let now = Clock::get()?.unix_timestamp;
+    require!(offer.is_expired(now), ErrorCode::OfferNotExpired);
+
+    emit!(OfferCancelled {
+        schema_version: EVENT_SCHEMA_VERSION,
+        offer: offer.key(),
+        merchant: offer.merchant,
+        user: offer.user,
+        timestamp: now,
+    });
+
+    msg!("Expired offer closed");
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::state::MerchantStatement;
+use crate::SettlementPeriodClosed;
+
+/// Close a merchant's statement once its month has ended (permissionless)
+///
+/// Freezes the period's totals, which later payments cannot change, and
+/// publishes them as a `SettlementPeriodClosed` event.
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseMerchantStatement<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"merchant_statement",
+            merchant_statement.merchant.as_ref(),
+            merchant_statement.mint.as_ref(),
+            &merchant_statement.year.to_le_bytes(),
+            &[merchant_statement.month],
+        ],
+        bump = merchant_statement.bump
+    )]
+    pub merchant_statement: Account<'info, MerchantStatement>,
+}
+
+pub fn handler(ctx: Context<CloseMerchantStatement>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let statement = &mut ctx.accounts.merchant_statement;
+    statement.close_books(now)?;
+    let (period_start, period_end) = statement.period_bounds()?;
+
+    emit_cpi!(SettlementPeriodClosed {
+        schema_version: EVENT_SCHEMA_VERSION,
+        statement: statement.key(),
+        merchant: statement.merchant,
+        mint: statement.mint,
+        year: statement.year,
+        month: statement.month,
+        period_start,
+        period_end,
+        payment_count: statement.payment_count,
+        gross_volume: statement.gross_volume,
+        refunds: statement.refunds,
+        fees: statement.fees,
+        net_settlement: statement.net_settlement,
+        timestamp: now,
+    });
+
+    msg!(
+        "Merchant statement {}-{:02} closed: {} payments, net {}",
+        statement.year,
+        statement.month,
+        statement.payment_count,
+        statement.net_settlement
+    );
+    Ok(())
+}
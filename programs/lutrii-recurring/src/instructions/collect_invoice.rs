@@ -0,0 +1,391 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::transfer_checked;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+use lutrii_common::{EVENT_SCHEMA_VERSION, Subscription};
+use lutrii_core::{fee, limits, SECONDS_PER_DAY};
+use lutrii_merchant_registry::{program::LutriiMerchantRegistry, Merchant as MerchantAccount};
+use crate::cpi::record_merchant_payment;
+use crate::errors::ErrorCode;
+use crate::state::{check_spendable, FeeTreatment, Invoice, InvoiceStatus, MerchantPolicy, MerchantReport, MerchantStatement, RefundLiability, SubscriptionEscrow, UserDelegate};
+use crate::{InvoicePaid, PlatformState};
+
+/// Collect an open invoice through the linked subscription's delegation
+///
+/// Permissionless, like `execute_payment`. The invoice total counts against
+/// the subscription's per-transaction and lifetime caps and the platform's
+/// daily volume limit, so itemized overages can never pull more than the
+/// user approved for the subscription. Escrow-funded subscriptions pay the
+/// invoice from their escrow vault instead. The payment counts toward the
+/// merchant's registry stats.
+///
+/// # Security
+/// - Invoice must be linked to this subscription at issue time
+/// - Caps, velocity limit, compliance denylist and merchant blocklist apply
+///   as for payments
+/// - Fees only go to a configured platform fee wallet, with the fee
+///   exemptions and promotional fee holidays payments get
+#[derive(Accounts)]
+pub struct CollectInvoice<'info> {
+    #[account(
+        mut,
+        has_one = subscription @ ErrorCode::InvoiceNotLinked,
+        has_one = merchant_token_account @ ErrorCode::InvalidTokenAccount,
+        has_one = mint @ ErrorCode::InvalidMint
+    )]
+    pub invoice: Box<Account<'info, Invoice>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        bump = subscription.bump
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform_state.bump
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.key() == subscription.user_token_account @ ErrorCode::InvalidTokenAccount
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user_delegate", subscription.user_token_account.as_ref()],
+        bump = user_delegate.bump
+    )]
+    pub user_delegate: Account<'info, UserDelegate>,
+
+    #[account(mut)]
+    pub merchant_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = platform_state.is_fee_wallet(&platform_fee_account.key()) @ ErrorCode::InvalidFeeWallet,
+        constraint = platform_fee_account.mint == mint.key() @ ErrorCode::InvalidFeeWalletMint
+    )]
+    pub platform_fee_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Denylist PDA for the user; must not exist
+    #[account(
+        seeds = [b"denylist", subscription.user.as_ref()],
+        bump,
+        constraint = user_denylist_entry.data_is_empty() @ ErrorCode::WalletDenylisted
+    )]
+    pub user_denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: Denylist PDA for the merchant wallet (owner of the settlement account); must not exist
+    #[account(
+        seeds = [b"denylist", merchant_token_account.owner.as_ref()],
+        bump,
+        constraint = merchant_denylist_entry.data_is_empty() @ ErrorCode::WalletDenylisted
+    )]
+    pub merchant_denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant blocklist PDA for this user; must not exist
+    #[account(
+        seeds = [b"blocklist", subscription.merchant.as_ref(), subscription.user.as_ref()],
+        bump,
+        constraint = blocklist_entry.data_is_empty() @ ErrorCode::UserBlockedByMerchant
+    )]
+    pub blocklist_entry: UncheckedAccount<'info>,
+
+    /// Merchant registry account; has the payment recorded in its stats
+    #[account(
+        mut,
+        constraint = merchant.key() == invoice.merchant @ ErrorCode::InvalidMerchantAccount
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: Merchant policy PDA, for a negotiated fee schedule and fee
+    /// exemption; may be uninitialized
+    #[account(
+        seeds = [b"merchant_policy", invoice.merchant.as_ref()],
+        bump
+    )]
+    pub merchant_policy: UncheckedAccount<'info>,
+
+    /// CHECK: Platform's promotional fee windows PDA; may be uninitialized
+    #[account(seeds = [b"fee_holidays"], bump)]
+    pub fee_holidays: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant's reporting totals PDA for the current year; may be
+    /// uninitialized
+    #[account(
+        mut,
+        seeds = [
+            b"merchant_report",
+            invoice.merchant.as_ref(),
+            mint.key().as_ref(),
+            &MerchantReport::year_seed(Clock::get()?.unix_timestamp),
+        ],
+        bump
+    )]
+    pub merchant_report: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant's statement PDA for the current month; may be
+    /// uninitialized
+    #[account(
+        mut,
+        seeds = [
+            b"merchant_statement",
+            invoice.merchant.as_ref(),
+            mint.key().as_ref(),
+            &MerchantReport::year_seed(Clock::get()?.unix_timestamp),
+            &MerchantStatement::month_seed(Clock::get()?.unix_timestamp),
+        ],
+        bump
+    )]
+    pub merchant_statement: UncheckedAccount<'info>,
+    /// CHECK: Merchant's refund exposure PDA; may be uninitialized
+    #[account(
+        mut,
+        seeds = [b"refund_liability", invoice.merchant.as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub refund_liability: UncheckedAccount<'info>,
+
+    /// Subscription's prepaid escrow; required once the subscription is
+    /// escrow-funded
+    #[account(
+        mut,
+        seeds = [b"escrow", subscription.key().as_ref()],
+        bump = escrow.bump
+    )]
+    pub escrow: Option<Box<Account<'info, SubscriptionEscrow>>>,
+
+    /// Must be `escrow.vault`; required with `escrow`
+    #[account(mut)]
+    pub escrow_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub merchant_registry_program: Program<'info, LutriiMerchantRegistry>,
+
+    /// CHECK: Instructions sysvar, read by the registry to check it runs under a CPI
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<CollectInvoice>) -> Result<()> {
+    let invoice = &mut ctx.accounts.invoice;
+    let subscription = &mut ctx.accounts.subscription;
+    let platform = &mut ctx.accounts.platform_state;
+    let clock = Clock::get()?;
+
+    // ============================================================================
+    // CHECKS
+    // ============================================================================
+
+    require!(
+        !subscription.payment_in_progress,
+        ErrorCode::PaymentInProgress
+    );
+
+    if clock.unix_timestamp >= platform.last_volume_reset + SECONDS_PER_DAY {
+        platform.total_volume_24h = 0;
+        platform.last_volume_reset = clock.unix_timestamp;
+        msg!("Daily volume reset");
+    }
+
+    require!(!platform.is_paused(clock.unix_timestamp), ErrorCode::SystemPaused);
+    require!(
+        !platform.token_program_paused(&ctx.accounts.token_program.key()),
+        ErrorCode::TokenProgramPaused
+    );
+    require!(
+        invoice.status == InvoiceStatus::Open,
+        ErrorCode::InvoiceNotOpen
+    );
+    require!(subscription.is_active, ErrorCode::SubscriptionInactive);
+    require!(!subscription.is_paused, ErrorCode::SubscriptionPaused);
+    require!(!subscription.delegation_deferred, ErrorCode::TrialNotConverted);
+    require!(
+        subscription.layout_version == Subscription::CURRENT_VERSION,
+        ErrorCode::SubscriptionMigrationRequired
+    );
+    require!(
+        subscription.pin_mint(&ctx.accounts.mint.key()),
+        ErrorCode::InvalidMint
+    );
+    require!(
+        invoice.total <= subscription.max_per_transaction,
+        ErrorCode::ExceedsTransactionCap
+    );
+
+    let new_total = limits::total_after_payment(subscription.total_paid, invoice.total)
+        .map_err(ErrorCode::from)?;
+    require!(
+        new_total <= subscription.lifetime_cap,
+        ErrorCode::ExceedsLifetimeCap
+    );
+
+    let new_volume = platform
+        .total_volume_24h
+        .checked_add(invoice.total)
+        .ok_or(ErrorCode::Overflow)?;
+    require!(
+        new_volume <= platform.daily_volume_limit,
+        ErrorCode::VelocityExceeded
+    );
+
+    let custom_fee = MerchantPolicy::fee_schedule_of(&ctx.accounts.merchant_policy)?;
+    let fee_treatment = FeeTreatment::of(
+        subscription,
+        invoice.total,
+        &ctx.accounts.merchant_policy,
+        &ctx.accounts.fee_holidays,
+        false,
+        &ctx.accounts.mint.key(),
+        clock.unix_timestamp,
+    )?;
+    let (fee, waived_fee) = match platform.platform_fee(invoice.total, custom_fee)? {
+        waived if fee_treatment.waives_fee() => (0, waived),
+        fee => (fee, 0),
+    };
+    let merchant_amount = fee::merchant_amount(invoice.total, fee).map_err(ErrorCode::from)?;
+
+    // Prepaid subscriptions pay from their escrow vault instead
+    match (ctx.accounts.escrow.as_ref(), ctx.accounts.escrow_vault.as_ref()) {
+        (Some(escrow), Some(vault)) => {
+            require_keys_eq!(vault.key(), escrow.vault, ErrorCode::InvalidEscrowVault);
+            require!(vault.amount >= invoice.total, ErrorCode::EscrowBalanceInsufficient);
+        }
+        (None, None) => {
+            require!(!subscription.escrow_funded, ErrorCode::EscrowRequired);
+            check_spendable(
+                &ctx.accounts.user_token_account,
+                &ctx.accounts.user_delegate.key(),
+                invoice.total,
+            )?;
+        }
+        _ => return err!(ErrorCode::InvalidEscrowVault),
+    }
+
+    // ============================================================================
+    // EFFECTS
+    // ============================================================================
+
+    invoice.status = InvoiceStatus::Paid;
+    invoice.paid_at = clock.unix_timestamp;
+
+    subscription.total_paid = new_total;
+
+    let user_delegate = &mut ctx.accounts.user_delegate;
+    if let Some(escrow) = ctx.accounts.escrow.as_mut() {
+        escrow.spent = escrow.spent.checked_add(invoice.total).ok_or(ErrorCode::Overflow)?;
+    } else {
+        user_delegate.release(invoice.total);
+    }
+
+    platform.total_volume_24h = new_volume;
+    platform.total_transactions = platform
+        .total_transactions
+        .checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+    if fee_treatment == FeeTreatment::Exempt {
+        platform.record_fee_exemption(waived_fee);
+    }
+
+    let now = clock.unix_timestamp;
+    MerchantReport::update_in(&ctx.accounts.merchant_report, |report| report.record(invoice.total, fee, now))?;
+    MerchantStatement::update_in(&ctx.accounts.merchant_statement, |statement| {
+        statement.record(invoice.total, fee, merchant_amount, now)
+    })?;
+    RefundLiability::update_in(&ctx.accounts.refund_liability, |liability| liability.record(merchant_amount, now))?;
+
+    // ============================================================================
+    // INTERACTIONS
+    // ============================================================================
+
+    // Pay from the escrow vault as the escrow PDA, or from the user's
+    // token account as its delegate
+    let (source, authority, seeds): (AccountInfo, AccountInfo, [&[u8]; 3]) =
+        match (ctx.accounts.escrow.as_ref(), ctx.accounts.escrow_vault.as_ref()) {
+            (Some(escrow), Some(vault)) => (
+                vault.to_account_info(),
+                escrow.to_account_info(),
+                [b"escrow", escrow.subscription.as_ref(), std::slice::from_ref(&escrow.bump)],
+            ),
+            _ => (
+                ctx.accounts.user_token_account.to_account_info(),
+                user_delegate.to_account_info(),
+                [
+                    b"user_delegate",
+                    user_delegate.token_account.as_ref(),
+                    std::slice::from_ref(&user_delegate.bump),
+                ],
+            ),
+        };
+    let signer = &[&seeds[..]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: source.clone(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.merchant_token_account.to_account_info(),
+                authority: authority.clone(),
+            },
+            signer,
+        ),
+        merchant_amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    if fee > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: source,
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.platform_fee_account.to_account_info(),
+                    authority,
+                },
+                signer,
+            ),
+            fee,
+            ctx.accounts.mint.decimals,
+        )?;
+    }
+
+    // Merchant stats and automatic tier upgrades in the registry
+    record_merchant_payment(
+        &ctx.accounts.merchant_registry_program.to_account_info(),
+        &ctx.accounts.merchant.to_account_info(),
+        &ctx.accounts.platform_state,
+        &ctx.accounts.instructions,
+        invoice.total,
+    )?;
+
+    emit!(InvoicePaid {
+        schema_version: EVENT_SCHEMA_VERSION,
+        invoice: invoice.key(),
+        merchant: invoice.merchant,
+        user: invoice.user,
+        total: invoice.total,
+        fee,
+        via_delegation: true,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "✅ Invoice {} collected via delegation: {}",
+        invoice.invoice_id,
+        invoice.total
+    );
+    Ok(())
+}
@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::AcceptedMint;
+use crate::{AcceptedMintConfigured, PlatformState};
+
+/// List, update or disable a mint accepted for payments (admin only)
+///
+/// # Arguments
+/// * `price_feed` - Pyth USD price account for the mint (default = none)
+/// * `min_amount` - Smallest subscription amount billed in the mint
+/// * `enabled` - Accept the mint for new subscriptions
+#[derive(Accounts)]
+pub struct ConfigureAcceptedMint<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = AcceptedMint::LEN,
+        seeds = [b"accepted_mint", mint.key().as_ref()],
+        bump
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<ConfigureAcceptedMint>,
+    price_feed: Pubkey,
+    min_amount: u64,
+    enabled: bool,
+) -> Result<()> {
+    require!(min_amount > 0, ErrorCode::InvalidAcceptedMintConfig);
+
+    let accepted_mint = &mut ctx.accounts.accepted_mint;
+    accepted_mint.mint = ctx.accounts.mint.key();
+    accepted_mint.decimals = ctx.accounts.mint.decimals;
+    accepted_mint.price_feed = price_feed;
+    accepted_mint.min_amount = min_amount;
+    accepted_mint.enabled = enabled;
+    accepted_mint.bump = ctx.bumps.accepted_mint;
+
+    emit!(AcceptedMintConfigured {
+        schema_version: EVENT_SCHEMA_VERSION,
+        mint: accepted_mint.mint,
+        decimals: accepted_mint.decimals,
+        price_feed,
+        min_amount,
+        enabled,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Accepted mint configured: min {} base units, enabled: {}",
+        min_amount,
+        enabled
+    );
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::{FeeHoliday, FeeHolidays, MAX_FEE_HOLIDAYS};
+use crate::{FeeHolidaysConfigured, PlatformState};
+
+/// Schedule the platform's promotional zero-fee windows (admin only)
+///
+/// Replaces every scheduled window; pass none to cancel them all. Refused
+/// once an admin council exists.
+///
+/// # Arguments
+/// * `holidays` - Windows with `start < end`, optionally limited to a mint (max 4)
+#[derive(Accounts)]
+pub struct ConfigureFeeHolidays<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = FeeHolidays::LEN,
+        seeds = [b"fee_holidays"],
+        bump
+    )]
+    pub fee_holidays: Account<'info, FeeHolidays>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<ConfigureFeeHolidays>, holidays: Vec<FeeHoliday>) -> Result<()> {
+    require!(
+        !ctx.accounts.platform_state.council_enabled,
+        ErrorCode::CouncilApprovalRequired
+    );
+    require!(
+        holidays.len() <= MAX_FEE_HOLIDAYS
+            && holidays.iter().all(|holiday| holiday.start < holiday.end),
+        ErrorCode::InvalidFeeHoliday
+    );
+
+    let config = &mut ctx.accounts.fee_holidays;
+    config.holidays = [FeeHoliday::default(); MAX_FEE_HOLIDAYS];
+    config.holidays[..holidays.len()].copy_from_slice(&holidays);
+    config.holiday_count = holidays.len() as u8;
+    config.bump = ctx.bumps.fee_holidays;
+
+    emit!(FeeHolidaysConfigured {
+        schema_version: EVENT_SCHEMA_VERSION,
+        holidays,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Fee holidays scheduled: {}", config.holiday_count);
+    Ok(())
+}
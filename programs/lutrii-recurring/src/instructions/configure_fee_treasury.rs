@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::{FeeShare, FeeTreasury, MAX_FEE_RECIPIENTS};
+use crate::{FeeTreasuryConfigured, PlatformState};
+
+/// Create or update the fee treasury for a mint (admin only)
+///
+/// # Arguments
+/// * `shares` - Distribution split (1-3 recipients, basis points summing to 10,000)
+/// * `sweep_interval` - Minimum seconds between sweeps
+/// * `enabled` - Route execution fees into the treasury vault
+///
+/// Disabling stops new accruals; fees already in the vault can still be swept.
+#[derive(Accounts)]
+pub struct ConfigureFeeTreasury<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = FeeTreasury::LEN,
+        seeds = [b"fee_treasury", mint.key().as_ref()],
+        bump
+    )]
+    pub fee_treasury: Account<'info, FeeTreasury>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        seeds = [b"fee_treasury_vault", fee_treasury.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = fee_treasury,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<ConfigureFeeTreasury>,
+    shares: Vec<FeeShare>,
+    sweep_interval: i64,
+    enabled: bool,
+) -> Result<()> {
+    FeeTreasury::validate_shares(&shares)?;
+    require!(sweep_interval > 0, ErrorCode::InvalidSweepInterval);
+
+    let treasury = &mut ctx.accounts.fee_treasury;
+    treasury.mint = ctx.accounts.mint.key();
+    treasury.vault = ctx.accounts.vault.key();
+    treasury.shares = [FeeShare::default(); MAX_FEE_RECIPIENTS];
+    treasury.shares[..shares.len()].copy_from_slice(&shares);
+    treasury.share_count = shares.len() as u8;
+    treasury.sweep_interval = sweep_interval;
+    treasury.enabled = enabled;
+    treasury.bump = ctx.bumps.fee_treasury;
+    treasury.vault_bump = ctx.bumps.vault;
+
+    emit!(FeeTreasuryConfigured {
+        schema_version: EVENT_SCHEMA_VERSION,
+        mint: treasury.mint,
+        shares,
+        sweep_interval,
+        enabled,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Fee treasury configured: {} recipients", treasury.share_count);
+    Ok(())
+}
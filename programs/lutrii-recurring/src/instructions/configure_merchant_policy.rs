@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use lutrii_common::{FeeBearer, EVENT_SCHEMA_VERSION};
+use crate::errors::ErrorCode;
+use crate::state::{MerchantPolicy, SwapFallback};
+use crate::MerchantPolicyUpdated;
+
+/// Create or update a merchant's subscription policy (merchant owner only)
+///
+/// # Arguments
+/// * `allowlist_only` - Restrict new subscriptions to allowlisted wallets
+/// * `max_active_subscribers` - Cap on concurrently active subscriptions (0 = unlimited)
+/// * `max_snooze_days` - Days subscribers may defer a due payment (0 = disabled)
+/// * `billing_anchor` - Billing date new subscriptions align to, with a
+///   prorated first charge at signup (0 = bill from signup)
+/// * `swap_fallback` - What swap-settled payments do when no route exists
+/// * `fee_bearer` - Who pays the platform fee on new subscriptions
+///
+/// # Security
+/// - Merchant PDA is derived from the signing owner, so only the merchant
+///   owner can configure its policy
+/// - Existing subscriptions are unaffected by enabling allowlist-only mode,
+///   lowering the cap below the current count or moving the billing anchor;
+///   subscriptions keep the fee bearer they were created with
+#[derive(Accounts)]
+pub struct ConfigureMerchantPolicy<'info> {
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = MerchantPolicy::LEN,
+        seeds = [b"merchant_policy", merchant.key().as_ref()],
+        bump
+    )]
+    pub merchant_policy: Account<'info, MerchantPolicy>,
+
+    #[account(
+        seeds = [b"merchant", owner.key().as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<ConfigureMerchantPolicy>,
+    allowlist_only: bool,
+    max_active_subscribers: u32,
+    max_snooze_days: u8,
+    billing_anchor: i64,
+    swap_fallback: SwapFallback,
+    fee_bearer: FeeBearer,
+) -> Result<()> {
+    require!(
+        max_snooze_days <= MerchantPolicy::MAX_SNOOZE_DAYS,
+        ErrorCode::InvalidSnoozeLimit
+    );
+
+    let policy = &mut ctx.accounts.merchant_policy;
+
+    policy.merchant = ctx.accounts.merchant.key();
+    policy.allowlist_only = allowlist_only;
+    policy.max_active_subscribers = max_active_subscribers;
+    policy.max_snooze_days = max_snooze_days;
+    policy.billing_anchor = billing_anchor;
+    policy.swap_fallback = swap_fallback;
+    policy.fee_bearer = fee_bearer;
+    policy.bump = ctx.bumps.merchant_policy;
+
+    emit!(MerchantPolicyUpdated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        merchant: policy.merchant,
+        allowlist_only,
+        max_active_subscribers,
+        max_snooze_days,
+        active_subscribers: policy.active_subscribers,
+        timestamp: Clock::get()?.unix_timestamp,
+        billing_anchor,
+        swap_fallback,
+        fee_bearer,
+    });
+
+    msg!(
+        "Merchant policy updated: allowlist-only {}, cap {} ({} active), snooze {} days",
+        if allowlist_only { "enabled" } else { "disabled" },
+        max_active_subscribers,
+        policy.active_subscribers,
+        max_snooze_days
+    );
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::{FeeTreasury, RebateTier, MAX_REBATE_TIERS};
+use crate::{PlatformState, RebatesConfigured};
+
+/// Set the merchant fee rebate schedule of a mint's fee treasury (admin only)
+///
+/// # Arguments
+/// * `tiers` - Up to 3 `(min_volume, rebate_bps)` tiers, ascending; empty disables rebates
+///
+/// Changes apply to claims made after this call, including claims for a
+/// period that has already finished.
+#[derive(Accounts)]
+pub struct ConfigureRebates<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        mut,
+        seeds = [b"fee_treasury", fee_treasury.mint.as_ref()],
+        bump = fee_treasury.bump
+    )]
+    pub fee_treasury: Account<'info, FeeTreasury>,
+
+    pub authority: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<ConfigureRebates>, tiers: Vec<RebateTier>) -> Result<()> {
+    FeeTreasury::validate_rebate_tiers(&tiers)?;
+
+    let treasury = &mut ctx.accounts.fee_treasury;
+    treasury.rebate_tiers = [RebateTier::default(); MAX_REBATE_TIERS];
+    treasury.rebate_tiers[..tiers.len()].copy_from_slice(&tiers);
+    treasury.rebate_tier_count = tiers.len() as u8;
+
+    emit!(RebatesConfigured {
+        schema_version: EVENT_SCHEMA_VERSION,
+        mint: treasury.mint,
+        tiers,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Rebate schedule set: {} tiers", treasury.rebate_tier_count);
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use crate::errors::ErrorCode;
+use crate::state::{RefundLiability, MAX_REFUND_WINDOW_DAYS};
+use crate::{PlatformState, RefundLiabilityConfigured};
+
+/// Track a merchant's refund exposure and size their reserve from it (admin only)
+///
+/// # Arguments
+/// * `window_days` - Days a payment stays refundable (1 to `MAX_REFUND_WINDOW_DAYS`)
+/// * `reserve_bps` - Share of the exposure settlement sweeps hold back
+#[derive(Accounts)]
+pub struct ConfigureRefundLiability<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = RefundLiability::LEN,
+        seeds = [b"refund_liability", merchant.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub refund_liability: Account<'info, RefundLiability>,
+
+    /// Merchant account from merchant registry
+    #[account(
+        seeds = [b"merchant", merchant.owner.as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<ConfigureRefundLiability>,
+    window_days: u16,
+    reserve_bps: u16,
+) -> Result<()> {
+    require!(
+        (1..=MAX_REFUND_WINDOW_DAYS).contains(&window_days) && reserve_bps <= 10_000,
+        ErrorCode::InvalidRefundLiability
+    );
+
+    let refund_liability = &mut ctx.accounts.refund_liability;
+    refund_liability.merchant = ctx.accounts.merchant.key();
+    refund_liability.mint = ctx.accounts.mint.key();
+    refund_liability.window_days = window_days;
+    refund_liability.reserve_bps = reserve_bps;
+    refund_liability.bump = ctx.bumps.refund_liability;
+
+    emit!(RefundLiabilityConfigured {
+        schema_version: EVENT_SCHEMA_VERSION,
+        merchant: refund_liability.merchant,
+        mint: refund_liability.mint,
+        window_days,
+        reserve_bps,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Refund liability configured: {} day window, {} bps reserve",
+        window_days,
+        reserve_bps
+    );
+    Ok(())
+}
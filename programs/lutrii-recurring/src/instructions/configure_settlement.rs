@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::SettlementVault;
+use crate::SettlementConfigured;
+
+/// Enable or disable batched settlement for one mint (merchant owner only)
+///
+/// Creates the settlement PDA and its vault token account on first use.
+/// Disabling stops new accruals; funds already in the vault can still be
+/// swept.
+///
+/// # Arguments
+/// * `enabled` - Route merchant legs of executions into the vault
+///
+/// # Security
+/// - Merchant PDA is derived from the signing owner
+/// - Sweeps only ever go to `destination`, which must be owned by the
+///   merchant owner
+#[derive(Accounts)]
+pub struct ConfigureSettlement<'info> {
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = SettlementVault::LEN,
+        seeds = [b"settlement", merchant.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub settlement: Account<'info, SettlementVault>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        seeds = [b"settlement_vault", settlement.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = settlement,
+        token::token_program = token_program
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = destination.owner == owner.key() @ ErrorCode::InvalidTokenAccountOwner,
+        constraint = destination.mint == mint.key() @ ErrorCode::InvalidMint
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"merchant", owner.key().as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<ConfigureSettlement>, enabled: bool) -> Result<()> {
+    let settlement = &mut ctx.accounts.settlement;
+
+    settlement.merchant = ctx.accounts.merchant.key();
+    settlement.mint = ctx.accounts.mint.key();
+    settlement.vault = ctx.accounts.vault.key();
+    settlement.destination = ctx.accounts.destination.key();
+    settlement.enabled = enabled;
+    settlement.bump = ctx.bumps.settlement;
+    settlement.vault_bump = ctx.bumps.vault;
+
+    emit!(SettlementConfigured {
+        schema_version: EVENT_SCHEMA_VERSION,
+        merchant: settlement.merchant,
+        mint: settlement.mint,
+        destination: settlement.destination,
+        enabled,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Batched settlement {}", if enabled { "enabled" } else { "disabled" });
+    Ok(())
+}
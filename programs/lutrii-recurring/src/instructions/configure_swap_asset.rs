@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::{SwapAsset, MAX_SWAP_CONFIDENCE_BPS, MAX_SWAP_SLIPPAGE_BPS};
+use crate::{PlatformState, SwapAssetConfigured};
+
+/// Allow or update a token users may pay with through swaps (admin only)
+///
+/// # Arguments
+/// * `price_feed` - Pyth USD price account for the mint
+/// * `max_slippage_bps` - Slippage allowed over the oracle price (max 1,000)
+/// * `max_price_age` - Oldest accepted oracle price in seconds
+/// * `max_confidence_bps` - Widest accepted oracle confidence interval as
+///   bps of the price (1 to 500)
+/// * `enabled` - Accept the asset for new subscriptions and executions
+#[derive(Accounts)]
+pub struct ConfigureSwapAsset<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = SwapAsset::LEN,
+        seeds = [b"swap_asset", mint.key().as_ref()],
+        bump
+    )]
+    pub swap_asset: Account<'info, SwapAsset>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<ConfigureSwapAsset>,
+    price_feed: Pubkey,
+    max_slippage_bps: u16,
+    max_price_age: i64,
+    max_confidence_bps: u16,
+    enabled: bool,
+) -> Result<()> {
+    require!(
+        max_slippage_bps <= MAX_SWAP_SLIPPAGE_BPS,
+        ErrorCode::InvalidSwapAssetConfig
+    );
+    require!(max_price_age > 0, ErrorCode::InvalidSwapAssetConfig);
+    require!(
+        max_confidence_bps > 0 && max_confidence_bps <= MAX_SWAP_CONFIDENCE_BPS,
+        ErrorCode::InvalidSwapAssetConfig
+    );
+
+    let swap_asset = &mut ctx.accounts.swap_asset;
+    swap_asset.mint = ctx.accounts.mint.key();
+    swap_asset.price_feed = price_feed;
+    swap_asset.max_slippage_bps = max_slippage_bps;
+    swap_asset.max_price_age = max_price_age;
+    swap_asset.max_confidence_bps = max_confidence_bps;
+    swap_asset.enabled = enabled;
+    swap_asset.bump = ctx.bumps.swap_asset;
+
+    emit!(SwapAssetConfigured {
+        schema_version: EVENT_SCHEMA_VERSION,
+        mint: swap_asset.mint,
+        price_feed,
+        max_slippage_bps,
+        max_price_age,
+        max_confidence_bps,
+        enabled,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Swap asset configured: {} bps slippage, {}s price age",
+        max_slippage_bps,
+        max_price_age
+    );
+    Ok(())
+}
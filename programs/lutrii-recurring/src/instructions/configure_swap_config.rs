@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::{SwapConfig, MAX_BLOCKED_MINTS, MAX_SWAP_PROGRAMS};
+use crate::{PlatformState, SwapConfigUpdated};
+
+/// Set the swap program allowlist and blocked route mints (admin only)
+///
+/// Swap-settled payments fail until a swap config exists.
+///
+/// # Arguments
+/// * `swap_programs` - Programs `execute_payment_with_swap` may route through (max 4)
+/// * `blocked_mints` - Mints a route may not pass through (max 8)
+#[derive(Accounts)]
+pub struct ConfigureSwapConfig<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = SwapConfig::LEN,
+        seeds = [b"swap_config"],
+        bump
+    )]
+    pub swap_config: Account<'info, SwapConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<ConfigureSwapConfig>,
+    swap_programs: Vec<Pubkey>,
+    blocked_mints: Vec<Pubkey>,
+) -> Result<()> {
+    require!(
+        swap_programs.len() <= MAX_SWAP_PROGRAMS && blocked_mints.len() <= MAX_BLOCKED_MINTS,
+        ErrorCode::InvalidSwapConfig
+    );
+
+    let config = &mut ctx.accounts.swap_config;
+    config.swap_programs = [Pubkey::default(); MAX_SWAP_PROGRAMS];
+    config.swap_programs[..swap_programs.len()].copy_from_slice(&swap_programs);
+    config.swap_program_count = swap_programs.len() as u8;
+    config.blocked_mints = [Pubkey::default(); MAX_BLOCKED_MINTS];
+    config.blocked_mints[..blocked_mints.len()].copy_from_slice(&blocked_mints);
+    config.blocked_mint_count = blocked_mints.len() as u8;
+    config.bump = ctx.bumps.swap_config;
+
+    emit!(SwapConfigUpdated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        swap_programs,
+        blocked_mints,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Swap config updated: {} programs, {} blocked mints",
+        config.swap_program_count,
+        config.blocked_mint_count
+    );
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use lutrii_common::{EVENT_SCHEMA_VERSION, Subscription};
+use crate::errors::ErrorCode;
+use crate::state::UsageMeter;
+use crate::UsageCapConfigured;
+
+/// Set how much usage the merchant may bill per period (user only)
+///
+/// Creates the subscription's usage meter on first use, starting its first
+/// period now. Changing the cap later keeps the usage already billed in the
+/// current period; 0 turns metered billing off.
+///
+/// # Arguments
+/// * `period_cap` - Most `execute_metered_payment` may charge per period
+///
+/// # Security
+/// - Only the subscription owner can set the cap (has_one + signer)
+#[derive(Accounts)]
+pub struct ConfigureUsageCap<'info> {
+    #[account(
+        seeds = [
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        bump = subscription.bump,
+        has_one = user @ ErrorCode::UnauthorizedUser
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UsageMeter::LEN,
+        seeds = [b"usage_meter", subscription.key().as_ref()],
+        bump
+    )]
+    pub usage_meter: Account<'info, UsageMeter>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<ConfigureUsageCap>, period_cap: u64) -> Result<()> {
+    let subscription = &ctx.accounts.subscription;
+    require!(subscription.is_active, ErrorCode::SubscriptionInactive);
+
+    let meter = &mut ctx.accounts.usage_meter;
+    if meter.subscription == Pubkey::default() {
+        meter.subscription = subscription.key();
+        meter.period_start = subscription.schedule_unit.now(&Clock::get()?);
+        meter.bump = ctx.bumps.usage_meter;
+    }
+    meter.period_cap = period_cap;
+
+    emit!(UsageCapConfigured {
+        schema_version: EVENT_SCHEMA_VERSION,
+        subscription: meter.subscription,
+        period_cap,
+        period_start: meter.period_start,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Usage cap set to {} per period", period_cap);
+    Ok(())
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::{YieldConfig, MAX_LENDING_PROGRAMS};
+use crate::{PlatformState, YieldConfigUpdated};
+
+/// Set the lending allowlist and yield attribution (admin only)
+///
+/// # Arguments
+/// * `lending_programs` - Lending programs held funds may be deposited into (max 4)
+/// * `merchant_yield_bps` - Share of yield kept by the merchant (0-10,000)
+/// * `enabled` - Allow new deposits; withdrawals are always allowed
+#[derive(Accounts)]
+pub struct ConfigureYield<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = YieldConfig::LEN,
+        seeds = [b"yield_config"],
+        bump
+    )]
+    pub yield_config: Account<'info, YieldConfig>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<ConfigureYield>,
+    lending_programs: Vec<Pubkey>,
+    merchant_yield_bps: u16,
+    enabled: bool,
+) -> Result<()> {
+    require!(
+        lending_programs.len() <= MAX_LENDING_PROGRAMS,
+        ErrorCode::InvalidYieldConfig
+    );
+    require!(merchant_yield_bps <= 10_000, ErrorCode::InvalidYieldConfig);
+
+    let config = &mut ctx.accounts.yield_config;
+    config.lending_programs = [Pubkey::default(); MAX_LENDING_PROGRAMS];
+    config.lending_programs[..lending_programs.len()].copy_from_slice(&lending_programs);
+    config.lending_program_count = lending_programs.len() as u8;
+    config.merchant_yield_bps = merchant_yield_bps;
+    config.enabled = enabled;
+    config.bump = ctx.bumps.yield_config;
+
+    emit!(YieldConfigUpdated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        lending_programs,
+        merchant_yield_bps,
+        enabled,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Yield config updated: {} bps to merchants", merchant_yield_bps);
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{TokenAccount, TokenInterface};
+use lutrii_common::{Subscription, EVENT_SCHEMA_VERSION};
+use lutrii_core::limits;
+use crate::errors::ErrorCode;
+use crate::state::UserDelegate;
+use crate::TrialConverted;
+
+/// Approve a trial subscription's allowance and start billing (user only)
+///
+/// Trials created with `create_trial_subscription` approve nothing, so the
+/// user can sign up before funding the wallet. Converting adds the
+/// subscription's lifetime allowance to the shared user delegate and
+/// re-approves the token account. The first payment stays at the end of the
+/// trial; converting after it collects right away.
+///
+/// # Security
+/// - Only the subscription owner can approve (has_one + signer)
+/// - The approval grows by exactly `lifetime_cap - total_paid`
+#[derive(Accounts)]
+pub struct ConvertTrial<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        bump = subscription.bump,
+        has_one = user @ ErrorCode::UnauthorizedUser
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.key() == subscription.user_token_account @ ErrorCode::InvalidTokenAccount
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user_delegate", subscription.user_token_account.as_ref()],
+        bump = user_delegate.bump
+    )]
+    pub user_delegate: Account<'info, UserDelegate>,
+
+    pub user: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<ConvertTrial>) -> Result<()> {
+    let subscription = &mut ctx.accounts.subscription;
+    require!(subscription.is_active, ErrorCode::SubscriptionInactive);
+    require!(subscription.delegation_deferred, ErrorCode::NoTrialToConvert);
+
+    // A token account pays either directly or through swaps, never both;
+    // trials only settle directly
+    let user_delegate = &mut ctx.accounts.user_delegate;
+    require!(
+        user_delegate.active_subscriptions == 0 || user_delegate.approval_rate == 0,
+        ErrorCode::TrialSwapUnsupported
+    );
+    user_delegate.approval_rate = 0;
+    user_delegate.reserve(limits::remaining_allowance(
+        subscription.lifetime_cap,
+        subscription.total_paid,
+    ))?;
+    user_delegate.active_subscriptions = user_delegate
+        .active_subscriptions
+        .checked_add(1)
+        .ok_or(ErrorCode::Overflow)?;
+    user_delegate.sync_approval(
+        user_delegate.to_account_info(),
+        ctx.accounts.user_token_account.to_account_info(),
+        ctx.accounts.user.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+    )?;
+
+    subscription.delegation_deferred = false;
+    subscription.delegation_healthy = true;
+
+    emit!(TrialConverted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        subscription: subscription.key(),
+        user: subscription.user,
+        delegated_amount: user_delegate.approval_amount()?,
+        next_payment: subscription.next_payment,
+        timestamp: Clock::get()?.unix_timestamp,
+        utc_offset_seconds: subscription.utc_offset_seconds,
+    });
+
+    msg!("Trial converted: first payment at {}", subscription.next_payment);
+    Ok(())
+}
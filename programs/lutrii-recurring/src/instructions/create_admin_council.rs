@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use lutrii_common::{AdminAuditAction, EVENT_SCHEMA_VERSION};
+use crate::errors::ErrorCode;
+use crate::state::{record_admin_action, AdminAuditLog, AdminCouncil, PendingConfigChange};
+use crate::{AdminCouncilCreated, PlatformState};
+
+/// Put emergency pauses and platform fees under an M-of-N council (admin only)
+///
+/// From then on `emergency_pause`, `emergency_unpause`, `set_fee_policy`,
+/// `set_executor_reward`, `set_first_subscription_waiver`,
+/// `schedule_config_change` and `update_platform_params` refuse the
+/// authority alone; the council proposes, approves and executes those
+/// changes instead. `set_fee_exemption`, `set_merchant_fee_schedule` and
+/// `configure_fee_holidays` are refused. This is a one-way switch and
+/// membership is fixed. The pending config change slot is created here so
+/// council proposals can schedule into it.
+///
+/// # Arguments
+/// * `members` - 1 to `MAX_COUNCIL_MEMBERS` distinct keys
+/// * `threshold` - Approvals a proposal needs, at most the member count
+#[derive(Accounts)]
+pub struct CreateAdminCouncil<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = AdminCouncil::LEN,
+        seeds = [b"admin_council"],
+        bump
+    )]
+    pub admin_council: Account<'info, AdminCouncil>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = PendingConfigChange::LEN,
+        seeds = [b"pending_config_change"],
+        bump
+    )]
+    pub pending_config_change: Account<'info, PendingConfigChange>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Admin audit log, appended to when passed
+    #[account(
+        mut,
+        seeds = [b"admin_audit_log"],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Option<Box<Account<'info, AdminAuditLog>>>,
+}
+
+pub fn handler(ctx: Context<CreateAdminCouncil>, members: Vec<Pubkey>, threshold: u8) -> Result<()> {
+    require!(AdminCouncil::is_valid(&members, threshold), ErrorCode::InvalidCouncil);
+    let now = Clock::get()?.unix_timestamp;
+
+    let council = &mut ctx.accounts.admin_council;
+    council.members = members.clone();
+    council.threshold = threshold;
+    council.proposal_count = 0;
+    council.created_at = now;
+    council.bump = ctx.bumps.admin_council;
+    ctx.accounts.pending_config_change.bump = ctx.bumps.pending_config_change;
+
+    ctx.accounts.platform_state.council_enabled = true;
+
+    emit!(AdminCouncilCreated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        members,
+        threshold,
+        timestamp: now,
+    });
+
+    let actor = ctx.accounts.authority.key();
+    record_admin_action(ctx.accounts.audit_log.as_mut(), actor, AdminAuditAction::CouncilEnabled)?;
+
+    msg!("Admin council created: {} of {}", threshold, ctx.accounts.admin_council.members.len());
+    Ok(())
+}
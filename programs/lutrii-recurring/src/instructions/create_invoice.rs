@@ -0,0 +1,120 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use lutrii_common::{EVENT_SCHEMA_VERSION, Subscription};
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use crate::errors::ErrorCode;
+use crate::state::{Invoice, InvoiceStatus, LineItem};
+use crate::InvoiceCreated;
+
+/// Issue an itemized invoice to a user (merchant owner only)
+///
+/// # Arguments
+/// * `invoice_id` - Merchant-chosen identifier, unique per merchant
+/// * `user` - Billed wallet
+/// * `line_items` - 1-10 items with non-zero quantity and unit price
+///
+/// Passing the user's subscription links the invoice to it, which allows
+/// collection through the subscription's delegation.
+///
+/// # Security
+/// - Merchant PDA is derived from the signing owner, so only the merchant
+///   owner can issue its invoices
+/// - Settlement account must belong to the merchant owner
+#[derive(Accounts)]
+#[instruction(invoice_id: u64)]
+pub struct CreateInvoice<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = Invoice::LEN,
+        seeds = [b"invoice", merchant.key().as_ref(), invoice_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub invoice: Box<Account<'info, Invoice>>,
+
+    #[account(
+        seeds = [b"merchant", owner.key().as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    /// Subscription to bill alongside (optional)
+    #[account(
+        constraint = subscription.merchant == merchant.key() @ ErrorCode::InvoiceSubscriptionMismatch
+    )]
+    pub subscription: Option<Account<'info, Subscription>>,
+
+    #[account(
+        constraint = merchant_token_account.owner == owner.key() @ ErrorCode::InvalidTokenAccountOwner
+    )]
+    pub merchant_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<CreateInvoice>,
+    invoice_id: u64,
+    user: Pubkey,
+    line_items: Vec<LineItem>,
+) -> Result<()> {
+    require!(
+        !line_items.is_empty()
+            && line_items.len() <= Invoice::MAX_LINE_ITEMS
+            && line_items
+                .iter()
+                .all(|item| item.quantity > 0 && item.unit_price > 0),
+        ErrorCode::InvalidLineItems
+    );
+    let total = Invoice::compute_total(&line_items).ok_or(ErrorCode::Overflow)?;
+
+    let subscription = match &ctx.accounts.subscription {
+        Some(subscription) => {
+            require!(
+                subscription.user == user,
+                ErrorCode::InvoiceSubscriptionMismatch
+            );
+            subscription.key()
+        }
+        None => Pubkey::default(),
+    };
+
+    let invoice = &mut ctx.accounts.invoice;
+    let clock = Clock::get()?;
+
+    invoice.merchant = ctx.accounts.merchant.key();
+    invoice.user = user;
+    invoice.invoice_id = invoice_id;
+    invoice.subscription = subscription;
+    invoice.merchant_token_account = ctx.accounts.merchant_token_account.key();
+    invoice.mint = ctx.accounts.merchant_token_account.mint;
+    invoice.line_items = line_items;
+    invoice.total = total;
+    invoice.status = InvoiceStatus::Open;
+    invoice.created_at = clock.unix_timestamp;
+    invoice.paid_at = 0;
+    invoice.bump = ctx.bumps.invoice;
+
+    emit!(InvoiceCreated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        invoice: invoice.key(),
+        merchant: invoice.merchant,
+        user,
+        subscription,
+        line_item_count: invoice.line_items.len() as u8,
+        total,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!(
+        "Invoice {} issued: {} items, total {}",
+        invoice_id,
+        invoice.line_items.len(),
+        total
+    );
+    Ok(())
+}
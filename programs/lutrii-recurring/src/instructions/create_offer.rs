@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::{OfferTerms, SubscriptionOffer};
+use crate::OfferCreated;
+
+/// Offer a subscription to a user on fixed terms (merchant owner only)
+///
+/// The user turns it into a subscription with `accept_offer`. One offer per
+/// user is outstanding at a time; `cancel_offer` withdraws it.
+///
+/// # Arguments
+/// * `user` - Wallet allowed to accept
+/// * `terms` - Amount, frequency, caps and display name
+/// * `expires_at` - Unix timestamp the offer lapses at
+///
+/// # Security
+/// - Merchant PDA is derived from the signing owner
+/// - Settlement account must belong to the merchant owner
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct CreateOffer<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = SubscriptionOffer::LEN,
+        seeds = [b"offer", merchant.key().as_ref(), user.as_ref()],
+        bump
+    )]
+    pub offer: Account<'info, SubscriptionOffer>,
+
+    #[account(
+        seeds = [b"merchant", owner.key().as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    #[account(
+        constraint = merchant_token_account.owner == owner.key() @ ErrorCode::InvalidTokenAccountOwner
+    )]
+    pub merchant_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<CreateOffer>,
+    user: Pubkey,
+    terms: OfferTerms,
+    expires_at: i64,
+) -> Result<()> {
+    require!(user != Pubkey::default(), ErrorCode::UnauthorizedUser);
+    terms.validate()?;
+    let now = Clock::get()?.unix_timestamp;
+    require!(expires_at > now, ErrorCode::InvalidOfferExpiry);
+
+    let offer = &mut ctx.accounts.offer;
+    offer.merchant = ctx.accounts.merchant.key();
+    offer.user = user;
+    offer.merchant_token_account = ctx.accounts.merchant_token_account.key();
+    offer.terms = terms;
+    offer.expires_at = expires_at;
+    offer.created_at = now;
+    offer.bump = ctx.bumps.offer;
+    offer.offer_id = 0;
+    offer.max_acceptances = 1;
+    offer.acceptances = 0;
+
+    emit!(OfferCreated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        offer: offer.key(),
+        merchant: offer.merchant,
+        user,
+        amount: offer.terms.amount,
+        frequency_seconds: offer.terms.frequency_seconds,
+        expires_at,
+    });
+
+    msg!("Offer created: {} every {} seconds", offer.terms.amount, offer.terms.frequency_seconds);
+    Ok(())
+}
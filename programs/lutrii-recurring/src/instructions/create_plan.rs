@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::Plan;
+use crate::{PlanCreated, MAX_FREQUENCY_SECONDS, MIN_FREQUENCY_SECONDS};
+
+/// Create a subscription plan (merchant owner only)
+///
+/// # Arguments
+/// * `plan_id` - Merchant-chosen identifier, unique per merchant
+/// * `name` - Display name (1-32 characters)
+/// * `price` - Price per billing cycle
+/// * `frequency_seconds` - Billing cycle length
+/// * `mint` - Mint the plan is billed in (default = any accepted mint)
+/// * `trial_period_seconds` - Free trial for new subscribers (0 = none)
+///
+/// # Security
+/// - Merchant PDA is derived from the signing owner, so only the merchant
+///   owner can create its plans
+#[derive(Accounts)]
+#[instruction(plan_id: u64)]
+pub struct CreatePlan<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = Plan::LEN,
+        seeds = [b"plan", merchant.key().as_ref(), plan_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub plan: Account<'info, Plan>,
+
+    #[account(
+        seeds = [b"merchant", owner.key().as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<CreatePlan>,
+    plan_id: u64,
+    name: String,
+    price: u64,
+    frequency_seconds: i64,
+    mint: Pubkey,
+    trial_period_seconds: i64,
+) -> Result<()> {
+    require!(
+        !name.is_empty() && name.len() <= Plan::MAX_NAME_LEN,
+        ErrorCode::InvalidPlanName
+    );
+    require!(price > 0, ErrorCode::AmountTooLow);
+    require!(
+        frequency_seconds >= MIN_FREQUENCY_SECONDS,
+        ErrorCode::FrequencyTooShort
+    );
+    require!(
+        frequency_seconds <= MAX_FREQUENCY_SECONDS,
+        ErrorCode::FrequencyTooLong
+    );
+    require!(
+        trial_period_seconds == 0
+            || (MIN_FREQUENCY_SECONDS..=MAX_FREQUENCY_SECONDS).contains(&trial_period_seconds),
+        ErrorCode::InvalidTrialPeriod
+    );
+
+    let plan = &mut ctx.accounts.plan;
+    let clock = Clock::get()?;
+
+    plan.merchant = ctx.accounts.merchant.key();
+    plan.plan_id = plan_id;
+    plan.name = name;
+    plan.price = price;
+    plan.frequency_seconds = frequency_seconds;
+    plan.is_active = true;
+    plan.created_at = clock.unix_timestamp;
+    plan.bump = ctx.bumps.plan;
+    plan.mint = mint;
+    plan.trial_period_seconds = trial_period_seconds;
+
+    emit!(PlanCreated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        plan: plan.key(),
+        merchant: plan.merchant,
+        plan_id,
+        price,
+        frequency_seconds,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Plan {} created: {} every {} seconds", plan.name, price, frequency_seconds);
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::{Promo, PromoDiscount, MAX_PROMO_CODE_LEN};
+use crate::PromoCreated;
+
+/// Create a promo code for new subscribers (merchant owner only)
+///
+/// # Arguments
+/// * `code` - Code subscribers enter (1-16 bytes), unique per merchant
+/// * `discount` - Percent or fixed amount off each discounted payment
+/// * `discounted_payments` - Payments each redemption discounts (at least 1)
+/// * `max_redemptions` - Most subscriptions that may redeem it (0 = unlimited)
+/// * `expires_at` - Unix time it stops being accepted (0 = never)
+///
+/// # Security
+/// - Merchant PDA is derived from the signing owner, so only the merchant
+///   owner can create its promo codes
+#[derive(Accounts)]
+#[instruction(code: String)]
+pub struct CreatePromo<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = Promo::LEN,
+        seeds = [b"promo", merchant.key().as_ref(), code.as_bytes()],
+        bump
+    )]
+    pub promo: Account<'info, Promo>,
+
+    #[account(
+        seeds = [b"merchant", owner.key().as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<CreatePromo>,
+    code: String,
+    discount: PromoDiscount,
+    discounted_payments: u16,
+    max_redemptions: u32,
+    expires_at: i64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    require!(
+        !code.is_empty() && code.len() <= MAX_PROMO_CODE_LEN,
+        ErrorCode::InvalidPromo
+    );
+    require!(
+        discount.is_valid() && discounted_payments > 0,
+        ErrorCode::InvalidPromo
+    );
+    require!(
+        expires_at == 0 || expires_at > clock.unix_timestamp,
+        ErrorCode::InvalidPromo
+    );
+
+    let promo = &mut ctx.accounts.promo;
+    promo.merchant = ctx.accounts.merchant.key();
+    promo.code = code;
+    promo.discount = discount;
+    promo.discounted_payments = discounted_payments;
+    promo.max_redemptions = max_redemptions;
+    promo.redemptions = 0;
+    promo.expires_at = expires_at;
+    promo.created_at = clock.unix_timestamp;
+    promo.bump = ctx.bumps.promo;
+
+    emit!(PromoCreated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        promo: promo.key(),
+        merchant: promo.merchant,
+        code: promo.code.clone(),
+        discount,
+        discounted_payments,
+        max_redemptions,
+        expires_at,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("Promo {} created", promo.code);
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::state::Plan;
+use crate::{CreateOptions, CreateSubscription, CreateSubscriptionBumps};
+// Client modules generated for the nested create_subscription accounts
+use crate::{__client_accounts_create_subscription, __cpi_client_accounts_create_subscription};
+
+/// Subscribe to a merchant plan (user only)
+///
+/// Takes every create_subscription account plus the plan; the amount,
+/// frequency, trial and name come from the plan, while the spending caps
+/// stay the user's own. The subscription is bound to the plan, so its
+/// payments follow the plan's price within the variance window.
+///
+/// # Arguments
+/// * `max_per_transaction` - Most a single payment may take
+/// * `lifetime_cap` - Most the subscription may take in total
+///
+/// # Security
+/// - Plan must belong to the merchant, be active and be billed in the
+///   subscription's mint
+#[derive(Accounts)]
+pub struct CreateSubscriptionFromPlan<'info> {
+    #[account(
+        constraint = plan.merchant == create.merchant.key() @ ErrorCode::PlanMerchantMismatch,
+        constraint = plan.is_active @ ErrorCode::PlanInactive,
+        constraint = plan.accepts_mint(&create.mint.key()) @ ErrorCode::PlanMintMismatch
+    )]
+    pub plan: Account<'info, Plan>,
+
+    pub create: CreateSubscription<'info>,
+}
+
+pub fn handler(
+    ctx: Context<CreateSubscriptionFromPlan>,
+    max_per_transaction: u64,
+    lifetime_cap: u64,
+) -> Result<()> {
+    let plan = &ctx.accounts.plan;
+    ctx.accounts.create.create(
+        &ctx.bumps.create,
+        plan.price,
+        plan.frequency_seconds,
+        max_per_transaction,
+        lifetime_cap,
+        plan.name.clone(),
+        CreateOptions {
+            trial_seconds: plan.trial_period_seconds,
+            ..Default::default()
+        },
+    )?;
+    ctx.accounts.create.subscription.plan = plan.key();
+
+    msg!("Subscribed to plan {}", plan.name);
+    Ok(())
+}
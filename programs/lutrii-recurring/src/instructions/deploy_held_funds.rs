@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::cpi::forward_signed_cpi;
+use crate::state::{SettlementVault, YieldConfig};
+use crate::HeldFundsDeployed;
+
+/// Deposit part of a settlement vault into an allowlisted lending protocol (merchant owner only)
+///
+/// The lending program's deposit instruction is forwarded with the
+/// remaining accounts, signed by the settlement PDA.
+///
+/// # Arguments
+/// * `amount` - Tokens to deposit from the vault
+/// * `data` - Instruction data for the lending program's deposit
+///
+/// # Security
+/// - Only allowlisted lending programs can be invoked
+/// - The vault balance must drop by exactly `amount`
+#[derive(Accounts)]
+pub struct DeployHeldFunds<'info> {
+    #[account(
+        mut,
+        seeds = [b"settlement", merchant.key().as_ref(), settlement.mint.as_ref()],
+        bump = settlement.bump,
+        has_one = vault
+    )]
+    pub settlement: Account<'info, SettlementVault>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(seeds = [b"yield_config"], bump = yield_config.bump)]
+    pub yield_config: Account<'info, YieldConfig>,
+
+    #[account(
+        seeds = [b"merchant", owner.key().as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    pub owner: Signer<'info>,
+
+    /// CHECK: Must be on the yield allowlist
+    #[account(
+        executable,
+        constraint = yield_config.is_allowed(lending_program.key) @ ErrorCode::LendingProgramNotAllowed
+    )]
+    pub lending_program: UncheckedAccount<'info>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DeployHeldFunds<'info>>,
+    amount: u64,
+    data: Vec<u8>,
+) -> Result<()> {
+    require!(ctx.accounts.yield_config.enabled, ErrorCode::YieldDisabled);
+    require!(amount > 0, ErrorCode::AmountTooLow);
+    let before = ctx.accounts.vault.amount;
+    require!(amount <= before, ErrorCode::InsufficientAmount);
+
+    let settlement = &mut ctx.accounts.settlement;
+    settlement.deployed = settlement
+        .deployed
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    let seeds = &[
+        b"settlement",
+        settlement.merchant.as_ref(),
+        settlement.mint.as_ref(),
+        &[settlement.bump],
+    ];
+    forward_signed_cpi(
+        &ctx.accounts.lending_program.to_account_info(),
+        ctx.remaining_accounts,
+        &settlement.key(),
+        data,
+        &[&seeds[..]],
+    )?;
+
+    ctx.accounts.vault.reload()?;
+    require!(
+        before.checked_sub(ctx.accounts.vault.amount) == Some(amount),
+        ErrorCode::UnexpectedVaultBalance
+    );
+
+    emit!(HeldFundsDeployed {
+        schema_version: EVENT_SCHEMA_VERSION,
+        merchant: settlement.merchant,
+        mint: settlement.mint,
+        lending_program: ctx.accounts.lending_program.key(),
+        amount,
+        deployed: settlement.deployed,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Deployed {} held funds to lending", amount);
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::transfer_checked;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+use lutrii_common::{EVENT_SCHEMA_VERSION, Subscription};
+use crate::errors::ErrorCode;
+use crate::state::SubscriptionEscrow;
+use crate::EscrowDeposited;
+
+/// Prepay a subscription into its escrow vault (user only)
+///
+/// Creates the escrow PDA and its vault on first use and marks the
+/// subscription escrow-funded. From then on executions and invoice
+/// collections must pass the escrow and draw from it instead of the
+/// delegation.
+///
+/// # Arguments
+/// * `amount` - Token units to move from `source` into the vault
+///
+/// # Security
+/// - Only the subscription owner can deposit (has_one + signer)
+/// - The vault holds the subscription's own mint
+#[derive(Accounts)]
+pub struct DepositEscrow<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        bump = subscription.bump,
+        has_one = user @ ErrorCode::UnauthorizedUser,
+        constraint = subscription.mint == mint.key() @ ErrorCode::InvalidMint
+    )]
+    pub subscription: Box<Account<'info, Subscription>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = SubscriptionEscrow::LEN,
+        seeds = [b"escrow", subscription.key().as_ref()],
+        bump
+    )]
+    pub escrow: Box<Account<'info, SubscriptionEscrow>>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        seeds = [b"escrow_vault", escrow.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = escrow,
+        token::token_program = token_program
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = source.owner == user.key() @ ErrorCode::InvalidTokenAccountOwner,
+        constraint = source.mint == mint.key() @ ErrorCode::InvalidMint
+    )]
+    pub source: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<DepositEscrow>, amount: u64) -> Result<()> {
+    require!(ctx.accounts.subscription.is_active, ErrorCode::SubscriptionInactive);
+    require!(
+        ctx.accounts.subscription.layout_version == Subscription::CURRENT_VERSION,
+        ErrorCode::SubscriptionMigrationRequired
+    );
+    require!(amount > 0, ErrorCode::InsufficientAmount);
+
+    // ============================================================================
+    // EFFECTS
+    // ============================================================================
+
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.subscription = ctx.accounts.subscription.key();
+    escrow.user = ctx.accounts.user.key();
+    escrow.mint = ctx.accounts.mint.key();
+    escrow.vault = ctx.accounts.vault.key();
+    escrow.bump = ctx.bumps.escrow;
+    escrow.vault_bump = ctx.bumps.vault;
+    escrow.deposited = escrow.deposited.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+    ctx.accounts.subscription.escrow_funded = true;
+
+    // ============================================================================
+    // INTERACTIONS
+    // ============================================================================
+
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.source.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    let balance = ctx
+        .accounts
+        .vault
+        .amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+
+    emit!(EscrowDeposited {
+        schema_version: EVENT_SCHEMA_VERSION,
+        subscription: escrow.subscription,
+        user: escrow.user,
+        amount,
+        balance,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Escrow deposit of {}, balance {}", amount, balance);
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::state::AllowlistEntry;
+use crate::AllowlistUpdated;
+
+/// Remove a wallet from a merchant's allowlist (merchant owner only)
+///
+/// Only affects new subscriptions. Closes the entry and returns its rent to
+/// the merchant owner.
+#[derive(Accounts)]
+pub struct DisallowUser<'info> {
+    #[account(
+        mut,
+        seeds = [b"allowlist", merchant.key().as_ref(), allowlist_entry.user.as_ref()],
+        bump = allowlist_entry.bump,
+        close = owner
+    )]
+    pub allowlist_entry: Account<'info, AllowlistEntry>,
+
+    #[account(
+        seeds = [b"merchant", owner.key().as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<DisallowUser>) -> Result<()> {
+    let merchant = ctx.accounts.merchant.key();
+    let user = ctx.accounts.allowlist_entry.user;
+
+    emit!(AllowlistUpdated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        merchant,
+        user,
+        allowed: false,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("User {} removed from merchant {} allowlist", user, merchant);
+    Ok(())
+}
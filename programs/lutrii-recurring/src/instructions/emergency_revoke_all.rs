@@ -0,0 +1,152 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::{revoke, Revoke};
+use anchor_spl::token_interface::TokenInterface;
+use lutrii_common::{Subscription, EVENT_SCHEMA_VERSION};
+use crate::errors::ErrorCode;
+use crate::state::{MerchantPolicy, UserDelegate, UserStats};
+use crate::{EmergencyRevokeAllExecuted, PlatformState, SubscriptionCancelled};
+
+/// Remaining accounts passed per subscription
+const ACCOUNTS_PER_SUBSCRIPTION: usize = 4;
+
+/// Cancel every listed subscription and revoke their token approvals (user only)
+///
+/// The panic button for a compromised merchant. Remaining accounts come in
+/// groups of four per subscription:
+/// `[subscription, merchant_policy, user_delegate, user_token_account]`,
+/// all writable. Clients find the user's subscriptions with a
+/// `getProgramAccounts` filter on `Subscription.user`.
+///
+/// Every touched token account has its delegation revoked outright, even if
+/// other subscriptions on it were left out; those need `refresh_delegation`.
+/// Already inactive subscriptions are skipped but their token accounts are
+/// still revoked.
+///
+/// # Security
+/// - Each subscription must belong to the signer; duplicates are rejected
+/// - Policy and delegate accounts must be the subscription's PDAs
+#[derive(Accounts)]
+pub struct EmergencyRevokeAll<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform_state.bump
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    pub user: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Spending analytics for the user, when they created it
+    #[account(
+        mut,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump = user_stats.bump
+    )]
+    pub user_stats: Option<Account<'info, UserStats>>,
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, EmergencyRevokeAll<'info>>) -> Result<()> {
+    let user = ctx.accounts.user.key();
+    let now = Clock::get()?.unix_timestamp;
+    let groups = ctx.remaining_accounts.chunks_exact(ACCOUNTS_PER_SUBSCRIPTION);
+    require!(
+        !ctx.remaining_accounts.is_empty() && groups.remainder().is_empty(),
+        ErrorCode::InvalidSubscriptionAccount
+    );
+
+    let mut seen: Vec<Pubkey> = Vec::new();
+    let mut revoked: Vec<Pubkey> = Vec::new();
+    let mut cancelled: u32 = 0;
+
+    for group in groups {
+        let [subscription_info, policy_info, delegate_info, token_account_info] = group else {
+            return err!(ErrorCode::InvalidSubscriptionAccount);
+        };
+
+        require!(subscription_info.owner == &crate::ID, ErrorCode::InvalidSubscriptionAccount);
+        require!(!seen.contains(subscription_info.key), ErrorCode::InvalidSubscriptionAccount);
+        seen.push(*subscription_info.key);
+
+        let mut subscription =
+            Subscription::try_deserialize(&mut &subscription_info.try_borrow_data()?[..])?;
+        require!(subscription.user == user, ErrorCode::InvalidSubscriptionAccount);
+        require_keys_eq!(
+            *token_account_info.key,
+            subscription.user_token_account,
+            ErrorCode::InvalidTokenAccount
+        );
+
+        if subscription.is_active {
+            let (policy, _) = Pubkey::find_program_address(
+                &[b"merchant_policy", subscription.merchant.as_ref()],
+                &crate::ID,
+            );
+            require_keys_eq!(*policy_info.key, policy, ErrorCode::InvalidAccountData);
+
+            require!(delegate_info.owner == &crate::ID, ErrorCode::InvalidAccountData);
+            let mut user_delegate =
+                UserDelegate::try_deserialize(&mut &delegate_info.try_borrow_data()?[..])?;
+            let expected = Pubkey::create_program_address(
+                &[
+                    b"user_delegate",
+                    subscription.user_token_account.as_ref(),
+                    &[user_delegate.bump],
+                ],
+                &crate::ID,
+            )
+            .map_err(|_| ErrorCode::InvalidAccountData)?;
+            require_keys_eq!(*delegate_info.key, expected, ErrorCode::InvalidAccountData);
+
+            user_delegate.remove_subscription(&subscription);
+            user_delegate.try_serialize(&mut &mut delegate_info.try_borrow_mut_data()?[..])?;
+
+            subscription.is_active = false;
+            subscription.is_paused = false;
+            subscription.try_serialize(&mut &mut subscription_info.try_borrow_mut_data()?[..])?;
+
+            MerchantPolicy::release_slot(policy_info)?;
+            let platform = &mut ctx.accounts.platform_state;
+            platform.total_subscriptions = platform.total_subscriptions.saturating_sub(1);
+            if let Some(user_stats) = ctx.accounts.user_stats.as_mut() {
+                user_stats.subscription_removed();
+            }
+            cancelled += 1;
+
+            emit!(SubscriptionCancelled {
+                schema_version: EVENT_SCHEMA_VERSION,
+                subscription: *subscription_info.key,
+                user,
+                total_paid: subscription.total_paid,
+                payment_count: subscription.payment_count,
+                timestamp: now,
+            });
+        }
+
+        if !revoked.contains(token_account_info.key) {
+            revoke(CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Revoke {
+                    source: token_account_info.clone(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ))?;
+            revoked.push(*token_account_info.key);
+        }
+    }
+
+    emit!(EmergencyRevokeAllExecuted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        user,
+        subscriptions_cancelled: cancelled,
+        token_accounts_revoked: revoked.len() as u32,
+        timestamp: now,
+    });
+
+    msg!(
+        "🚨 Revoked {} token accounts, cancelled {} subscriptions",
+        revoked.len(),
+        cancelled
+    );
+    Ok(())
+}
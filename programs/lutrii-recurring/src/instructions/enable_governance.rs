@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use lutrii_common::{AdminAuditAction, EVENT_SCHEMA_VERSION};
+use crate::errors::ErrorCode;
+use crate::state::{record_admin_action, AdminAuditLog, GovernanceConfig, GOVERNANCE_REALM_OFFSET};
+use crate::{GovernanceEnabled, PlatformState};
+
+/// Hand the platform to an SPL Governance realm (admin only)
+///
+/// The platform authority becomes the governance's native treasury, so
+/// every admin instruction afterwards is executed from a passed proposal.
+/// This is a one-way switch: only a proposal can rotate the authority again
+/// (through `update_config`).
+///
+/// # Arguments
+/// * `pause_guardian` - Key allowed to pause payments without a vote (default = none)
+#[derive(Accounts)]
+pub struct EnableGovernance<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = GovernanceConfig::LEN,
+        seeds = [b"governance"],
+        bump
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+
+    /// CHECK: Governance account; its owner must be `governance_program` and
+    /// its realm `realm`
+    #[account(owner = governance_program.key() @ ErrorCode::InvalidGovernance)]
+    pub governance: UncheckedAccount<'info>,
+
+    /// CHECK: Realm account owned by `governance_program`
+    #[account(owner = governance_program.key() @ ErrorCode::InvalidGovernance)]
+    pub realm: UncheckedAccount<'info>,
+
+    /// CHECK: SPL Governance program instance
+    #[account(executable)]
+    pub governance_program: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Admin audit log, appended to when passed
+    #[account(
+        mut,
+        seeds = [b"admin_audit_log"],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Option<Box<Account<'info, AdminAuditLog>>>,
+}
+
+pub fn handler(ctx: Context<EnableGovernance>, pause_guardian: Pubkey) -> Result<()> {
+    let realm = ctx.accounts.realm.key();
+    {
+        let data = ctx.accounts.governance.try_borrow_data()?;
+        let realm_bytes = data
+            .get(GOVERNANCE_REALM_OFFSET..GOVERNANCE_REALM_OFFSET + 32)
+            .ok_or(ErrorCode::InvalidGovernance)?;
+        require!(realm_bytes == realm.as_ref(), ErrorCode::InvalidGovernance);
+    }
+
+    let governance = ctx.accounts.governance.key();
+    let governance_program = ctx.accounts.governance_program.key();
+    let native_treasury = GovernanceConfig::native_treasury_of(&governance, &governance_program);
+    let now = Clock::get()?.unix_timestamp;
+
+    let config = &mut ctx.accounts.governance_config;
+    config.governance_program = governance_program;
+    config.realm = realm;
+    config.governance = governance;
+    config.native_treasury = native_treasury;
+    config.pause_guardian = pause_guardian;
+    config.enabled_at = now;
+    config.bump = ctx.bumps.governance_config;
+
+    let platform = &mut ctx.accounts.platform_state;
+    let previous_authority = platform.authority;
+    platform.authority = native_treasury;
+
+    emit!(GovernanceEnabled {
+        schema_version: EVENT_SCHEMA_VERSION,
+        realm,
+        governance,
+        native_treasury,
+        previous_authority,
+        pause_guardian,
+        timestamp: now,
+    });
+
+    let actor = ctx.accounts.authority.key();
+    record_admin_action(ctx.accounts.audit_log.as_mut(), actor, AdminAuditAction::GovernanceEnabled)?;
+
+    msg!("Platform authority handed to governance treasury {}", native_treasury);
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::PendingConfigChange;
+use crate::{ConfigChangeExecuted, PlatformParamsUpdated, PlatformState};
+
+/// Apply a scheduled config change whose delay has passed (permissionless)
+///
+/// The change is checked again against the current platform state, as the
+/// fee policy may have moved since it was scheduled.
+#[derive(Accounts)]
+pub struct ExecuteConfigChange<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform_state.bump
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        mut,
+        seeds = [b"pending_config_change"],
+        bump = pending_config_change.bump
+    )]
+    pub pending_config_change: Account<'info, PendingConfigChange>,
+}
+
+pub fn handler(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let pending = &mut ctx.accounts.pending_config_change;
+    require!(pending.pending, ErrorCode::NoPendingConfigChange);
+    require!(now >= pending.executable_at, ErrorCode::ConfigChangeTimelocked);
+    pending.pending = false;
+
+    let change = pending.change;
+    let platform = &mut ctx.accounts.platform_state;
+    change.validate(platform)?;
+    if let Some(fee_basis_points) = change.fee_basis_points {
+        platform.fee_basis_points = fee_basis_points;
+    }
+    if let Some(min_fee) = change.min_fee {
+        platform.min_fee = min_fee;
+    }
+    if let Some(max_fee) = change.max_fee {
+        platform.max_fee = max_fee;
+    }
+    if let Some(limit) = change.daily_volume_limit {
+        platform.daily_volume_limit = limit;
+    }
+    if let Some(wallet) = change.fee_wallet_usdc {
+        platform.fee_wallet_usdc = wallet;
+    }
+    if let Some(wallet) = change.fee_wallet_usd1 {
+        platform.fee_wallet_usd1 = wallet;
+    }
+    if let Some(delay) = change.config_change_delay_seconds {
+        platform.config_change_delay_seconds = delay;
+    }
+
+    if change.changes_fee_params() {
+        emit!(PlatformParamsUpdated {
+            schema_version: EVENT_SCHEMA_VERSION,
+            fee_basis_points: platform.fee_basis_points,
+            min_fee: platform.min_fee,
+            max_fee: platform.max_fee,
+            timestamp: now,
+        });
+    }
+
+    emit!(ConfigChangeExecuted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        change,
+        scheduled_at: pending.scheduled_at,
+        timestamp: now,
+    });
+
+    msg!("Config change scheduled at {} executed", pending.scheduled_at);
+    Ok(())
+}
@@ -0,0 +1,200 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::instructions::schedule_config_change::schedule;
+use crate::state::{
+    record_admin_action, AdminAuditLog, AdminCouncil, CouncilAction, CouncilProposal,
+    PendingConfigChange,
+};
+use crate::{
+    CouncilActionExecuted, EmergencyPauseActivated, ExecutorRewardUpdated, FeePolicyUpdated,
+    FirstSubscriptionWaiverUpdated, PlatformState,
+};
+
+/// Run a council proposal that reached its threshold (permissionless)
+///
+/// Each proposal runs once and only before it expires. The audit log
+/// records the change with the proposal as the actor. A config change is
+/// scheduled into `pending_config_change` and its new fee wallets must be
+/// passed.
+#[derive(Accounts)]
+pub struct ExecuteCouncilAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform_state.bump
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        seeds = [b"admin_council"],
+        bump = admin_council.bump
+    )]
+    pub admin_council: Account<'info, AdminCouncil>,
+
+    #[account(
+        mut,
+        seeds = [b"council_proposal", proposal.proposal_id.to_le_bytes().as_ref()],
+        bump = proposal.bump
+    )]
+    pub proposal: Account<'info, CouncilProposal>,
+
+    /// Config change slot, for `CouncilAction::ScheduleConfigChange`
+    #[account(
+        mut,
+        seeds = [b"pending_config_change"],
+        bump = pending_config_change.bump
+    )]
+    pub pending_config_change: Option<Account<'info, PendingConfigChange>>,
+
+    /// USDC fee wallet the config change rotates to
+    #[account(
+        constraint = new_fee_wallet_usdc.mint == platform_state.usdc_mint @ ErrorCode::InvalidFeeWalletMint
+    )]
+    pub new_fee_wallet_usdc: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// USD1 fee wallet the config change rotates to
+    #[account(
+        constraint = new_fee_wallet_usd1.mint == platform_state.usd1_mint @ ErrorCode::InvalidFeeWalletMint
+    )]
+    pub new_fee_wallet_usd1: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Admin audit log, appended to when passed
+    #[account(
+        mut,
+        seeds = [b"admin_audit_log"],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Option<Box<Account<'info, AdminAuditLog>>>,
+}
+
+pub fn handler(ctx: Context<ExecuteCouncilAction>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let proposal = &mut ctx.accounts.proposal;
+    require!(!proposal.executed, ErrorCode::CouncilProposalClosed);
+    require!(now < proposal.expires_at, ErrorCode::CouncilProposalClosed);
+    require!(
+        proposal.approval_count() >= ctx.accounts.admin_council.threshold,
+        ErrorCode::CouncilThresholdNotMet
+    );
+    proposal.executed = true;
+
+    if let CouncilAction::ScheduleConfigChange { change } = &proposal.action {
+        let usdc = ctx.accounts.new_fee_wallet_usdc.as_ref().map(|wallet| wallet.key());
+        let usd1 = ctx.accounts.new_fee_wallet_usd1.as_ref().map(|wallet| wallet.key());
+        require!(
+            change.fee_wallet_usdc == usdc && change.fee_wallet_usd1 == usd1,
+            ErrorCode::CouncilActionAccountsMismatch
+        );
+    }
+    apply_admin_action(
+        &mut ctx.accounts.platform_state,
+        ctx.accounts.pending_config_change.as_deref_mut(),
+        &proposal.action,
+    )?;
+
+    emit!(CouncilActionExecuted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        proposal: proposal.key(),
+        proposal_id: proposal.proposal_id,
+        approvals: proposal.approval_count(),
+        timestamp: now,
+    });
+
+    let actor = proposal.key();
+    let action = proposal.action.audit_action();
+    record_admin_action(ctx.accounts.audit_log.as_mut(), actor, action)?;
+
+    msg!("Council proposal {} executed", proposal.proposal_id);
+    Ok(())
+}
+
+/// Make the platform change `action` describes, emitting its usual event
+///
+/// Shared by the council and the single-key admin instructions, which only
+/// platforms without a council may still use. Scheduling a config change
+/// needs `pending_config_change`.
+pub(crate) fn apply_admin_action(
+    platform: &mut PlatformState,
+    pending_config_change: Option<&mut PendingConfigChange>,
+    action: &CouncilAction,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    match action {
+        CouncilAction::EmergencyPause { reason } => {
+            let expires_at = platform.activate_pause(reason, now)?;
+
+            emit!(EmergencyPauseActivated {
+                schema_version: EVENT_SCHEMA_VERSION,
+                timestamp: now,
+                reason: reason.clone(),
+                expires_at,
+            });
+
+            msg!("⚠️ EMERGENCY PAUSE ACTIVATED");
+        }
+        CouncilAction::EmergencyUnpause => {
+            platform.clear_pause();
+            platform.total_volume_24h = 0;
+            platform.last_volume_reset = now;
+            platform.failed_tx_count = 0;
+
+            msg!("✅ System unpaused, counters reset");
+        }
+        &CouncilAction::SetFeePolicy { fee_rounding, fee_dust_policy, fee_dust_threshold } => {
+            require!(
+                fee_dust_threshold <= platform.max_fee,
+                ErrorCode::InvalidFeeDustThreshold
+            );
+            platform.fee_rounding = fee_rounding;
+            platform.fee_dust_policy = fee_dust_policy;
+            platform.fee_dust_threshold = fee_dust_threshold;
+
+            emit!(FeePolicyUpdated {
+                schema_version: EVENT_SCHEMA_VERSION,
+                fee_rounding,
+                fee_dust_policy,
+                fee_dust_threshold,
+                timestamp: now,
+            });
+
+            msg!(
+                "Fee policy updated: {:?} rounding, {:?} dust below {}",
+                fee_rounding,
+                fee_dust_policy,
+                fee_dust_threshold
+            );
+        }
+        &CouncilAction::SetExecutorReward { reward_bps, flat_reward } => {
+            require!(reward_bps <= 10_000, ErrorCode::InvalidExecutorReward);
+            platform.executor_reward_bps = reward_bps;
+            platform.executor_reward_flat = flat_reward;
+
+            emit!(ExecutorRewardUpdated {
+                schema_version: EVENT_SCHEMA_VERSION,
+                reward_bps,
+                flat_reward,
+                timestamp: now,
+            });
+
+            msg!("Executor reward: {} bps + {} flat", reward_bps, flat_reward);
+        }
+        &CouncilAction::SetFirstSubscriptionWaiver { free_payments } => {
+            platform.first_subscription_free_payments = free_payments;
+
+            emit!(FirstSubscriptionWaiverUpdated {
+                schema_version: EVENT_SCHEMA_VERSION,
+                free_payments,
+                timestamp: now,
+            });
+
+            msg!("First subscription fee waiver: {} payments", free_payments);
+        }
+        &CouncilAction::ScheduleConfigChange { change } => {
+            let pending = pending_config_change.ok_or(ErrorCode::CouncilActionAccountsMismatch)?;
+            schedule(pending, platform, change)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,386 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::transfer_checked;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+use lutrii_merchant_registry::{program::LutriiMerchantRegistry, Merchant as MerchantAccount};
+use lutrii_common::{EVENT_SCHEMA_VERSION, Subscription};
+use lutrii_core::limits;
+use crate::cpi::record_merchant_payment;
+use crate::errors::ErrorCode;
+use crate::payment::PaymentAccounts;
+use crate::state::{check_spendable, MerchantReport, MerchantStatement, SubscriptionEscrow, UsageMeter, UserDelegate};
+use crate::{MeteredPaymentExecuted, PlatformState};
+
+/// Bill actual usage through the subscription's delegation (merchant owner only)
+///
+/// For API and utility merchants whose charges vary. Each charge must fit
+/// the subscription's `max_per_transaction`, the usage billed in the period
+/// must stay within the cap the user set with `configure_usage_cap`, and
+/// everything counts against the lifetime cap and the platform's daily
+/// volume limit. The fixed-amount schedule is not affected.
+///
+/// Charges run the same guards and keep the same records as scheduled
+/// payments (user stats, merchant volume, reports, statements, refund
+/// exposure and the registry's stats). The platform fee is treated the same
+/// way: exemptions, fee holidays, dust and the subscription's fee bearer
+/// apply. The first-subscription waiver counts scheduled payments only.
+/// Escrow-funded subscriptions are charged from their escrow vault.
+///
+/// # Arguments
+/// * `amount` - Usage charge in the subscription's mint
+///
+/// # Security
+/// - Merchant PDA is derived from the signing owner and must be the
+///   subscription's merchant
+/// - Only billable once the user has set a usage cap
+/// - Caps, velocity limit, compliance denylist and merchant blocklist apply
+///   as for payments
+#[derive(Accounts)]
+pub struct ExecuteMeteredPayment<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        bump = subscription.bump,
+        constraint = subscription.merchant == merchant.key() @ ErrorCode::InvalidMerchantAccount
+    )]
+    pub subscription: Box<Account<'info, Subscription>>,
+
+    #[account(
+        mut,
+        seeds = [b"usage_meter", subscription.key().as_ref()],
+        bump = usage_meter.bump
+    )]
+    pub usage_meter: Box<Account<'info, UsageMeter>>,
+
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform_state.bump
+    )]
+    pub platform_state: Box<Account<'info, PlatformState>>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.key() == subscription.user_token_account @ ErrorCode::InvalidTokenAccount
+    )]
+    pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_delegate", subscription.user_token_account.as_ref()],
+        bump = user_delegate.bump
+    )]
+    pub user_delegate: Box<Account<'info, UserDelegate>>,
+
+    #[account(
+        mut,
+        constraint = merchant_token_account.key() == subscription.merchant_token_account @ ErrorCode::InvalidTokenAccount
+    )]
+    pub merchant_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = platform_state.is_fee_wallet(&platform_fee_account.key()) @ ErrorCode::InvalidFeeWallet,
+        constraint = platform_fee_account.mint == mint.key() @ ErrorCode::InvalidFeeWalletMint
+    )]
+    pub platform_fee_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Denylist PDA for the user; must not exist
+    #[account(
+        seeds = [b"denylist", subscription.user.as_ref()],
+        bump,
+        constraint = user_denylist_entry.data_is_empty() @ ErrorCode::WalletDenylisted
+    )]
+    pub user_denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: Denylist PDA for the merchant wallet (owner of the settlement account); must not exist
+    #[account(
+        seeds = [b"denylist", merchant_token_account.owner.as_ref()],
+        bump,
+        constraint = merchant_denylist_entry.data_is_empty() @ ErrorCode::WalletDenylisted
+    )]
+    pub merchant_denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant blocklist PDA for this user; must not exist
+    #[account(
+        seeds = [b"blocklist", subscription.merchant.as_ref(), subscription.user.as_ref()],
+        bump,
+        constraint = blocklist_entry.data_is_empty() @ ErrorCode::UserBlockedByMerchant
+    )]
+    pub blocklist_entry: UncheckedAccount<'info>,
+
+    /// Merchant registry account; has the charge recorded in its stats
+    #[account(
+        mut,
+        seeds = [b"merchant", owner.key().as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    pub owner: Signer<'info>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: Merchant policy PDA, for a negotiated fee schedule and fee
+    /// exemption; may be uninitialized
+    #[account(
+        seeds = [b"merchant_policy", subscription.merchant.as_ref()],
+        bump
+    )]
+    pub merchant_policy: UncheckedAccount<'info>,
+
+    /// CHECK: Platform fee holidays PDA; may be uninitialized
+    #[account(
+        seeds = [b"fee_holidays"],
+        bump
+    )]
+    pub fee_holidays: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant's reporting totals PDA for the current year; may be
+    /// uninitialized
+    #[account(
+        mut,
+        seeds = [
+            b"merchant_report",
+            subscription.merchant.as_ref(),
+            mint.key().as_ref(),
+            &MerchantReport::year_seed(Clock::get()?.unix_timestamp),
+        ],
+        bump
+    )]
+    pub merchant_report: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant's statement PDA for the current month; may be
+    /// uninitialized
+    #[account(
+        mut,
+        seeds = [
+            b"merchant_statement",
+            subscription.merchant.as_ref(),
+            mint.key().as_ref(),
+            &MerchantReport::year_seed(Clock::get()?.unix_timestamp),
+            &MerchantStatement::month_seed(Clock::get()?.unix_timestamp),
+        ],
+        bump
+    )]
+    pub merchant_statement: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant's refund exposure PDA; may be uninitialized
+    #[account(
+        mut,
+        seeds = [b"refund_liability", subscription.merchant.as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub refund_liability: UncheckedAccount<'info>,
+
+    /// CHECK: User's spending analytics PDA; may be uninitialized
+    #[account(
+        mut,
+        seeds = [b"user_stats", subscription.user.as_ref()],
+        bump
+    )]
+    pub user_stats: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant's monthly volume PDA in the mint, tracked for fee
+    /// rebates; may be uninitialized
+    #[account(
+        mut,
+        seeds = [b"merchant_volume", subscription.merchant.as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub merchant_volume: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant's revenue forecast PDA in the mint; may be uninitialized
+    #[account(
+        mut,
+        seeds = [b"revenue_forecast", subscription.merchant.as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub revenue_forecast: UncheckedAccount<'info>,
+
+    pub merchant_registry_program: Program<'info, LutriiMerchantRegistry>,
+
+    /// CHECK: Instructions sysvar, read by the registry to check it runs under a CPI
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    /// Subscription's prepaid escrow; the charge is drawn from its vault.
+    /// Required once the subscription is escrow-funded
+    #[account(
+        mut,
+        seeds = [b"escrow", subscription.key().as_ref()],
+        bump = escrow.bump
+    )]
+    pub escrow: Option<Box<Account<'info, SubscriptionEscrow>>>,
+
+    /// Must be `escrow.vault`; required with `escrow`
+    #[account(mut)]
+    pub escrow_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+}
+
+pub fn handler(ctx: Context<ExecuteMeteredPayment>, amount: u64) -> Result<()> {
+    let clock = Clock::get()?;
+    let accounts = &mut *ctx.accounts;
+    let mut payment = PaymentAccounts {
+        subscription: &mut accounts.subscription,
+        platform: &mut accounts.platform_state,
+        user_delegate: &mut accounts.user_delegate,
+        merchant: &accounts.merchant,
+        merchant_policy: &accounts.merchant_policy,
+        claimant: Pubkey::default(),
+        plan: None,
+        fee_holidays: &accounts.fee_holidays,
+        user_stats: &accounts.user_stats,
+        due_bucket: None,
+        next_due_bucket: None,
+        revenue_forecast: &accounts.revenue_forecast,
+        merchant_volume: &accounts.merchant_volume,
+        merchant_report: &accounts.merchant_report,
+        merchant_statement: &accounts.merchant_statement,
+        refund_liability: &accounts.refund_liability,
+    };
+
+    // ============================================================================
+    // CHECKS
+    // ============================================================================
+
+    let due = payment.begin_usage(
+        &accounts.token_program.key(),
+        &accounts.mint.key(),
+        amount,
+        &clock,
+    )?;
+
+    // Prepaid subscriptions draw from their escrow vault instead
+    match (accounts.escrow.as_ref(), accounts.escrow_vault.as_ref()) {
+        (Some(escrow), Some(vault)) => {
+            require_keys_eq!(vault.key(), escrow.vault, ErrorCode::InvalidEscrowVault);
+            require!(vault.amount >= due.outflow, ErrorCode::EscrowBalanceInsufficient);
+        }
+        (None, None) => {
+            require!(!payment.subscription.escrow_funded, ErrorCode::EscrowRequired);
+            check_spendable(
+                &accounts.user_token_account,
+                &payment.user_delegate.key(),
+                due.outflow,
+            )?;
+        }
+        _ => return err!(ErrorCode::InvalidEscrowVault),
+    }
+
+    // ============================================================================
+    // EFFECTS
+    // ============================================================================
+
+    let meter = &mut accounts.usage_meter;
+    meter.record(
+        amount,
+        payment.subscription.frequency_seconds,
+        payment.subscription.schedule_unit.now(&clock),
+    )?;
+
+    payment.record_usage(&due, clock.unix_timestamp)?;
+    payment.record_settlement(&due, due.merchant_amount, clock.unix_timestamp)?;
+    if let Some(escrow) = accounts.escrow.as_mut() {
+        escrow.spent = escrow.spent.checked_add(due.outflow).ok_or(ErrorCode::Overflow)?;
+    }
+
+    // ============================================================================
+    // INTERACTIONS
+    // ============================================================================
+
+    // Pay from the escrow vault as the escrow PDA, or from the user's
+    // token account as its delegate
+    let user_delegate = &*payment.user_delegate;
+    let (source, authority, seeds): (AccountInfo, AccountInfo, [&[u8]; 3]) =
+        match (accounts.escrow.as_ref(), accounts.escrow_vault.as_ref()) {
+            (Some(escrow), Some(vault)) => (
+                vault.to_account_info(),
+                escrow.to_account_info(),
+                [b"escrow", escrow.subscription.as_ref(), std::slice::from_ref(&escrow.bump)],
+            ),
+            _ => (
+                accounts.user_token_account.to_account_info(),
+                user_delegate.to_account_info(),
+                [
+                    b"user_delegate",
+                    user_delegate.token_account.as_ref(),
+                    std::slice::from_ref(&user_delegate.bump),
+                ],
+            ),
+        };
+    let signer = &[&seeds[..]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: source.clone(),
+                mint: accounts.mint.to_account_info(),
+                to: accounts.merchant_token_account.to_account_info(),
+                authority: authority.clone(),
+            },
+            signer,
+        ),
+        due.merchant_amount,
+        accounts.mint.decimals,
+    )?;
+
+    if due.fee > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: source,
+                    mint: accounts.mint.to_account_info(),
+                    to: accounts.platform_fee_account.to_account_info(),
+                    authority,
+                },
+                signer,
+            ),
+            due.fee,
+            accounts.mint.decimals,
+        )?;
+    }
+
+    // Merchant stats and automatic tier upgrades in the registry
+    record_merchant_payment(
+        &accounts.merchant_registry_program.to_account_info(),
+        &payment.merchant.to_account_info(),
+        payment.platform,
+        &accounts.instructions,
+        due.charge,
+    )?;
+
+    let subscription = &*payment.subscription;
+    emit!(MeteredPaymentExecuted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        subscription: subscription.key(),
+        merchant: subscription.merchant,
+        amount,
+        fee: due.fee,
+        period_usage: meter.period_usage,
+        period_cap: meter.period_cap,
+        remaining_lifetime_allowance: limits::remaining_allowance(
+            subscription.lifetime_cap,
+            subscription.total_paid,
+        ),
+        timestamp: clock.unix_timestamp,
+    });
+
+    // MeteredPaymentExecuted carries the same data; logs are for local debugging
+    #[cfg(feature = "verbose-logs")]
+    msg!(
+        "✅ Metered payment of {} ({} of {} this period)",
+        amount,
+        meter.period_usage,
+        meter.period_cap
+    );
+    Ok(())
+}
@@ -0,0 +1,508 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token_2022::transfer_checked;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+use lutrii_common::{Subscription, EVENT_SCHEMA_VERSION};
+use lutrii_merchant_registry::{program::LutriiMerchantRegistry, Merchant as MerchantAccount};
+use crate::cpi::record_merchant_payment;
+use crate::errors::ErrorCode;
+use crate::payment::PaymentAccounts;
+use crate::state::{
+    check_spendable, DueBucket, FeeTreasury, MerchantReport, MerchantStatement, Plan,
+    SettlementVault, SubscriptionEscrow, UserDelegate,
+};
+use crate::{ExecutorRewardPaid, PlatformState};
+
+/// Execute a scheduled payment
+///
+/// Can be called by anyone once a payment is due. Uses delegated authority
+/// from the user delegate PDA to transfer tokens from user to merchant, or
+/// draws from the subscription's prepaid escrow when it has one.
+#[derive(Accounts)]
+pub struct ExecutePayment<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        bump = subscription.bump
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform_state.bump
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// CHECK: User doesn't need to sign for automated payments
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.key() == subscription.user_token_account @ ErrorCode::InvalidTokenAccount
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user_delegate", subscription.user_token_account.as_ref()],
+        bump = user_delegate.bump
+    )]
+    pub user_delegate: Account<'info, UserDelegate>,
+
+    #[account(
+        mut,
+        constraint = merchant_token_account.key() == subscription.merchant_token_account @ ErrorCode::InvalidTokenAccount
+    )]
+    pub merchant_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: One of the configured fee wallets; `transfer_checked` rejects
+    /// a wallet for another mint, so it is not unpacked here
+    #[account(
+        mut,
+        constraint = platform_state.is_fee_wallet(&platform_fee_account.key()) @ ErrorCode::InvalidFeeWallet
+    )]
+    pub platform_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Denylist PDA for the user; must not exist
+    #[account(
+        seeds = [b"denylist", subscription.user.as_ref()],
+        bump,
+        constraint = user_denylist_entry.data_is_empty() @ ErrorCode::WalletDenylisted
+    )]
+    pub user_denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: Denylist PDA for the merchant wallet (owner of the settlement account); must not exist
+    #[account(
+        seeds = [b"denylist", merchant_token_account.owner.as_ref()],
+        bump,
+        constraint = merchant_denylist_entry.data_is_empty() @ ErrorCode::WalletDenylisted
+    )]
+    pub merchant_denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant blocklist PDA for this user; must not exist
+    #[account(
+        seeds = [b"blocklist", subscription.merchant.as_ref(), subscription.user.as_ref()],
+        bump,
+        constraint = blocklist_entry.data_is_empty() @ ErrorCode::UserBlockedByMerchant
+    )]
+    pub blocklist_entry: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: User's spending analytics PDA, for the first-subscription fee
+    /// waiver and spend totals; may be uninitialized
+    #[account(
+        mut,
+        seeds = [b"user_stats", subscription.user.as_ref()],
+        bump
+    )]
+    pub user_stats: UncheckedAccount<'info>,
+
+    /// Batched settlement for the merchant and mint, when enabled
+    #[account(
+        mut,
+        seeds = [b"settlement", subscription.merchant.as_ref(), mint.key().as_ref()],
+        bump = settlement.bump,
+        constraint = settlement.enabled @ ErrorCode::SettlementDisabled
+    )]
+    pub settlement: Option<Account<'info, SettlementVault>>,
+
+    /// CHECK: Must be `settlement.vault`; required with `settlement`
+    #[account(mut)]
+    pub settlement_vault: Option<UncheckedAccount<'info>>,
+
+    /// Fee treasury for the mint, when enabled
+    #[account(
+        mut,
+        seeds = [b"fee_treasury", mint.key().as_ref()],
+        bump = fee_treasury.bump,
+        constraint = fee_treasury.enabled @ ErrorCode::FeeTreasuryDisabled
+    )]
+    pub fee_treasury: Option<Account<'info, FeeTreasury>>,
+
+    /// CHECK: Must be `fee_treasury.vault`; required with `fee_treasury`
+    #[account(mut)]
+    pub fee_treasury_vault: Option<UncheckedAccount<'info>>,
+
+    /// Due-date bucket listing the subscription, when indexed
+    #[account(
+        mut,
+        constraint = due_bucket.covers(&subscription.merchant, subscription.next_payment) @ ErrorCode::InvalidDueBucket
+    )]
+    pub due_bucket: Option<Account<'info, DueBucket>>,
+
+    /// Due-date bucket for the rescheduled payment, when indexed
+    #[account(mut)]
+    pub next_due_bucket: Option<Account<'info, DueBucket>>,
+
+    /// CHECK: Merchant policy PDA, freed a slot when a missed payment
+    /// auto-cancels the subscription and read for the merchant's fee
+    /// exemption, negotiated fee schedule and retry policy; may be uninitialized
+    #[account(
+        mut,
+        seeds = [b"merchant_policy", subscription.merchant.as_ref()],
+        bump
+    )]
+    pub merchant_policy: UncheckedAccount<'info>,
+
+    /// Keeper holding the execution claim; required while one is live
+    pub claimant: Option<Signer<'info>>,
+
+    /// Merchant registry account; places the payment in its velocity class
+    /// and has the payment recorded in its stats
+    #[account(
+        mut,
+        constraint = merchant.key() == subscription.merchant @ ErrorCode::InvalidMerchantAccount
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    pub merchant_registry_program: Program<'info, LutriiMerchantRegistry>,
+
+    /// CHECK: Instructions sysvar, read by the registry to check it runs under a CPI
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant's monthly volume PDA in the mint, tracked for fee
+    /// rebates; may be uninitialized
+    #[account(
+        mut,
+        seeds = [b"merchant_volume", subscription.merchant.as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub merchant_volume: UncheckedAccount<'info>,
+
+    /// CHECK: Platform's promotional fee windows PDA; may be uninitialized
+    #[account(seeds = [b"fee_holidays"], bump)]
+    pub fee_holidays: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant's reporting totals PDA for the current year; may be
+    /// uninitialized
+    #[account(
+        mut,
+        seeds = [
+            b"merchant_report",
+            subscription.merchant.as_ref(),
+            mint.key().as_ref(),
+            &MerchantReport::year_seed(Clock::get()?.unix_timestamp),
+        ],
+        bump
+    )]
+    pub merchant_report: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant's statement PDA for the current month; may be
+    /// uninitialized
+    #[account(
+        mut,
+        seeds = [
+            b"merchant_statement",
+            subscription.merchant.as_ref(),
+            mint.key().as_ref(),
+            &MerchantReport::year_seed(Clock::get()?.unix_timestamp),
+            &MerchantStatement::month_seed(Clock::get()?.unix_timestamp),
+        ],
+        bump
+    )]
+    pub merchant_statement: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant's refund exposure PDA; may be uninitialized
+    #[account(
+        mut,
+        seeds = [b"refund_liability", subscription.merchant.as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub refund_liability: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant's revenue forecast PDA in the mint; may be uninitialized
+    #[account(
+        mut,
+        seeds = [b"revenue_forecast", subscription.merchant.as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub revenue_forecast: UncheckedAccount<'info>,
+
+    /// Keeper claiming the executor reward, when the platform pays one
+    pub executor: Option<Signer<'info>>,
+
+    /// Executor's token account in the payment mint; receives the reward
+    #[account(
+        mut,
+        constraint = executor_token_account.mint == mint.key() @ ErrorCode::InvalidMint
+    )]
+    pub executor_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Plan the subscription is bound to, whose current price is charged;
+    /// required for plan-bound subscriptions and refused for others
+    #[account(
+        constraint = plan.key() == subscription.plan @ ErrorCode::PlanSubscriptionMismatch
+    )]
+    pub plan: Option<Box<Account<'info, Plan>>>,
+
+    /// Subscription's prepaid escrow; the payment is drawn from its vault.
+    /// Required once the subscription is escrow-funded
+    #[account(
+        mut,
+        seeds = [b"escrow", subscription.key().as_ref()],
+        bump = escrow.bump
+    )]
+    pub escrow: Option<Box<Account<'info, SubscriptionEscrow>>>,
+
+    /// Must be `escrow.vault`; required with `escrow`
+    #[account(mut)]
+    pub escrow_vault: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+}
+
+pub fn handler(ctx: Context<ExecutePayment>) -> Result<()> {
+    let clock = Clock::get()?;
+    let accounts = &mut *ctx.accounts;
+    let mut payment = PaymentAccounts {
+        subscription: &mut accounts.subscription,
+        platform: &mut accounts.platform_state,
+        user_delegate: &mut accounts.user_delegate,
+        merchant: &accounts.merchant,
+        merchant_policy: &accounts.merchant_policy,
+        claimant: accounts.claimant.as_ref().map(|c| c.key()).unwrap_or_default(),
+        plan: accounts.plan.as_deref(),
+        fee_holidays: &accounts.fee_holidays,
+        user_stats: &accounts.user_stats,
+        due_bucket: accounts.due_bucket.as_mut(),
+        next_due_bucket: accounts.next_due_bucket.as_mut(),
+        revenue_forecast: &accounts.revenue_forecast,
+        merchant_volume: &accounts.merchant_volume,
+        merchant_report: &accounts.merchant_report,
+        merchant_statement: &accounts.merchant_statement,
+        refund_liability: &accounts.refund_liability,
+    };
+
+    // ============================================================================
+    // CHECKS - All validation logic
+    // ============================================================================
+
+    let Some(due) = payment.begin(&accounts.token_program.key(), &accounts.mint.key(), &clock)? else {
+        return Ok(());
+    };
+
+    // An executor that passes its token account earns the reward out of the fee
+    let executor_reward = match (accounts.executor.as_ref(), accounts.executor_token_account.as_ref()) {
+        (Some(executor), Some(token_account)) => {
+            require_keys_eq!(
+                token_account.owner,
+                executor.key(),
+                ErrorCode::InvalidExecutorRewardAccount
+            );
+            payment.platform.executor_reward(due.charge, due.fee)
+        }
+        (None, None) => 0,
+        _ => return err!(ErrorCode::InvalidExecutorRewardAccount),
+    };
+    let platform_fee = due.fee - executor_reward;
+
+    // Delinquency: record the failed attempt and back off instead of
+    // reverting, so keepers stop retrying an empty wallet every block
+    let user_token_account = &accounts.user_token_account;
+    let user_delegate_key = payment.user_delegate.key();
+    let delegation_covers = user_token_account.delegate == COption::Some(user_delegate_key)
+        && user_token_account.delegated_amount >= due.outflow;
+
+    // Prepaid subscriptions draw from their escrow vault instead
+    let escrow_vault = match (accounts.escrow.as_ref(), accounts.escrow_vault.as_ref()) {
+        (Some(escrow), Some(vault)) => {
+            require_keys_eq!(vault.key(), escrow.vault, ErrorCode::InvalidEscrowVault);
+            Some(vault)
+        }
+        (None, None) => {
+            require!(!payment.subscription.escrow_funded, ErrorCode::EscrowRequired);
+            None
+        }
+        _ => return err!(ErrorCode::InvalidEscrowVault),
+    };
+    let failure = match escrow_vault {
+        _ if due.outflow == 0 => None,
+        Some(vault) => (vault.amount < due.outflow).then_some(ErrorCode::EscrowBalanceInsufficient),
+        None => check_spendable(user_token_account, &user_delegate_key, due.outflow).err(),
+    };
+    if let Some(failure) = failure {
+        return payment.fail(&due, failure, delegation_covers, &clock);
+    }
+    // The transfers below spend the outflow from the shared approval,
+    // unless the escrow pays
+    let remaining_delegation = if escrow_vault.is_some() {
+        if user_token_account.delegate == COption::Some(user_delegate_key) {
+            user_token_account.delegated_amount
+        } else {
+            0
+        }
+    } else if delegation_covers {
+        user_token_account.delegated_amount - due.outflow
+    } else {
+        0
+    };
+
+    // ============================================================================
+    // EFFECTS - Update state BEFORE external calls (CEI pattern)
+    // ============================================================================
+
+    payment.record(&due, clock.unix_timestamp)?;
+    payment.record_settlement(&due, due.merchant_amount, clock.unix_timestamp)?;
+    if let Some(escrow) = accounts.escrow.as_mut() {
+        escrow.spent = escrow.spent.checked_add(due.outflow).ok_or(ErrorCode::Overflow)?;
+    }
+
+    // Batched settlement accrues the merchant leg in the vault
+    let merchant_destination = match (accounts.settlement.as_mut(), accounts.settlement_vault.as_ref()) {
+        (Some(settlement), Some(vault)) => {
+            require_keys_eq!(vault.key(), settlement.vault, ErrorCode::InvalidSettlementVault);
+            settlement.accrued = settlement
+                .accrued
+                .checked_add(due.merchant_amount)
+                .ok_or(ErrorCode::Overflow)?;
+            vault.to_account_info()
+        }
+        (None, None) => accounts.merchant_token_account.to_account_info(),
+        _ => return err!(ErrorCode::InvalidSettlementVault),
+    };
+    let batched = accounts.settlement.is_some();
+
+    // Fee treasury accrues the fee for a periodic split
+    let fee_destination = match (accounts.fee_treasury.as_mut(), accounts.fee_treasury_vault.as_ref()) {
+        (Some(treasury), Some(vault)) => {
+            require_keys_eq!(vault.key(), treasury.vault, ErrorCode::InvalidFeeTreasuryVault);
+            treasury.accrued = treasury
+                .accrued
+                .checked_add(platform_fee)
+                .ok_or(ErrorCode::Overflow)?;
+            vault.to_account_info()
+        }
+        (None, None) => accounts.platform_fee_account.to_account_info(),
+        _ => return err!(ErrorCode::InvalidFeeTreasuryVault),
+    };
+
+    payment.platform.executor_rewards_paid = payment
+        .platform
+        .executor_rewards_paid
+        .checked_add(executor_reward)
+        .ok_or(ErrorCode::Overflow)?;
+
+    // ============================================================================
+    // INTERACTIONS - External calls AFTER state updates (CEI pattern)
+    // ============================================================================
+
+    // Pay from the escrow vault as the escrow PDA, or from the user's
+    // token account as its delegate
+    let user_delegate = &*payment.user_delegate;
+    let (source, authority, seeds): (AccountInfo, AccountInfo, [&[u8]; 3]) =
+        match (accounts.escrow.as_ref(), accounts.escrow_vault.as_ref()) {
+            (Some(escrow), Some(vault)) => (
+                vault.to_account_info(),
+                escrow.to_account_info(),
+                [b"escrow", escrow.subscription.as_ref(), std::slice::from_ref(&escrow.bump)],
+            ),
+            _ => (
+                accounts.user_token_account.to_account_info(),
+                user_delegate.to_account_info(),
+                [
+                    b"user_delegate",
+                    user_delegate.token_account.as_ref(),
+                    std::slice::from_ref(&user_delegate.bump),
+                ],
+            ),
+        };
+    let signer = &[&seeds[..]];
+
+    // Transfer to merchant
+    transfer_checked(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: source.clone(),
+                mint: accounts.mint.to_account_info(),
+                to: merchant_destination,
+                authority: authority.clone(),
+            },
+            signer,
+        ),
+        due.merchant_amount,
+        accounts.mint.decimals,
+    )?;
+
+    // Transfer platform fee
+    if platform_fee > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: source.clone(),
+                    mint: accounts.mint.to_account_info(),
+                    to: fee_destination,
+                    authority: authority.clone(),
+                },
+                signer,
+            ),
+            platform_fee,
+            accounts.mint.decimals,
+        )?;
+    }
+
+    // Transfer the executor's share of the fee
+    if let (Some(executor), Some(executor_token_account)) =
+        (accounts.executor.as_ref(), accounts.executor_token_account.as_ref())
+    {
+        if executor_reward > 0 {
+            transfer_checked(
+                CpiContext::new_with_signer(
+                    accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: source,
+                        mint: accounts.mint.to_account_info(),
+                        to: executor_token_account.to_account_info(),
+                        authority,
+                    },
+                    signer,
+                ),
+                executor_reward,
+                accounts.mint.decimals,
+            )?;
+
+            emit!(ExecutorRewardPaid {
+                schema_version: EVENT_SCHEMA_VERSION,
+                subscription: payment.subscription.key(),
+                executor: executor.key(),
+                reward: executor_reward,
+                timestamp: clock.unix_timestamp,
+            });
+        }
+    }
+
+    // Merchant stats and automatic tier upgrades in the registry
+    record_merchant_payment(
+        &accounts.merchant_registry_program.to_account_info(),
+        &payment.merchant.to_account_info(),
+        payment.platform,
+        &accounts.instructions,
+        due.charge,
+    )?;
+
+    payment.finish(
+        &due,
+        due.merchant_amount,
+        remaining_delegation,
+        batched,
+        accounts.mint.key(),
+        clock.unix_timestamp,
+    )?;
+
+    // PaymentExecuted carries the same data; logs are for local debugging
+    #[cfg(feature = "verbose-logs")]
+    msg!(
+        "✅ Payment executed: {} to merchant (fee: {})",
+        due.merchant_amount,
+        due.fee
+    );
+    Ok(())
+}
@@ -0,0 +1,638 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::compute_units::sol_remaining_compute_units;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token_2022::transfer_checked;
+use anchor_spl::token_interface::{
+    close_account, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+use lutrii_common::{FeeBearer, Subscription, EVENT_SCHEMA_VERSION};
+use lutrii_merchant_registry::{program::LutriiMerchantRegistry, Merchant as MerchantAccount};
+use crate::cpi::{forward_signed_cpi, record_merchant_payment};
+use crate::errors::ErrorCode;
+use crate::payment::PaymentAccounts;
+use crate::state::{
+    check_spendable, DueBucket, MerchantPolicy, MerchantReport, MerchantStatement, Plan, SwapAsset, SwapConfig, SwapFallback, UserDelegate,
+};
+use crate::{
+    PaymentSwapped, PlatformState, SwapFallbackSettled, MAX_SWAP_ROUTE_ACCOUNTS,
+    SWAP_PAYMENT_COMPUTE_UNITS,
+};
+
+/// Execute a scheduled payment by swapping the user's token into the merchant's settlement mint
+///
+/// For subscriptions paid from an enabled swap asset (e.g. mSOL, jitoSOL).
+/// The keeper supplies a Jupiter route as `data` plus its accounts as
+/// remaining accounts; the user delegate signs as transfer authority. Output
+/// lands in a transient `swap_output` account owned by the user delegate,
+/// is split between merchant and platform fee wallet, and the account is
+/// closed back to the keeper.
+///
+/// An empty `data` reports that no swap route is available; the merchant's
+/// `SwapFallback` then decides: refuse (default), pay the merchant in the
+/// subscriber's token at the oracle price, or back the cycle off like a
+/// failed collection. Amounts in events stay in the settlement currency.
+/// Escrow-funded subscriptions are refused; they pay through
+/// `execute_payment`.
+///
+/// # Compute and account budget
+/// Multi-hop routes do not fit the default 200k compute units. Keepers set
+/// a compute unit limit of `SWAP_PAYMENT_COMPUTE_UNITS` plus the route's
+/// simulated cost, and the route may pass at most `MAX_SWAP_ROUTE_ACCOUNTS`
+/// accounts. Only `keeper` (and `claimant`) sign, so every other account,
+/// including the route's, can be loaded from an address lookup table; the
+/// global ones (`platform_state`, `swap_config`, `swap_asset`, `price_feed`,
+/// mints, fee account and programs) are the same for every payment of an
+/// asset and belong in a shared table.
+///
+/// # Arguments
+/// * `data` - Jupiter route instruction data (empty = no route)
+///
+/// # Security
+/// - Only allowlisted swap programs (`SwapConfig`) can be invoked
+/// - The route must spend from the user's token account into `swap_output`,
+///   touch no other account the user delegate owns and no blocked mint
+/// - Input spent is bounded by the oracle price plus the slippage allowance
+///   (the subscription's cap, within the asset's), and the output must be
+///   worth the input at the oracle price less that allowance and cover the
+///   charge; otherwise the payment fails with `SlippageExceeded`. No
+///   client-supplied minimum output is trusted
+/// - Oracle prices older than the asset's `max_price_age`, or whose
+///   confidence interval exceeds its `max_confidence_bps`, are rejected
+#[derive(Accounts)]
+pub struct ExecutePaymentWithSwap<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        bump = subscription.bump
+    )]
+    pub subscription: Box<Account<'info, Subscription>>,
+
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform_state.bump
+    )]
+    pub platform_state: Box<Account<'info, PlatformState>>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.key() == subscription.user_token_account @ ErrorCode::InvalidTokenAccount,
+        constraint = user_token_account.owner == subscription.user @ ErrorCode::InvalidTokenAccountOwner
+    )]
+    pub user_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_delegate", subscription.user_token_account.as_ref()],
+        bump = user_delegate.bump
+    )]
+    pub user_delegate: Box<Account<'info, UserDelegate>>,
+
+    #[account(
+        mut,
+        constraint = merchant_token_account.key() == subscription.merchant_token_account @ ErrorCode::InvalidTokenAccount
+    )]
+    pub merchant_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = platform_state.is_fee_wallet(&platform_fee_account.key()) @ ErrorCode::InvalidFeeWallet,
+        constraint = platform_fee_account.mint == settlement_mint.key() @ ErrorCode::InvalidFeeWalletMint
+    )]
+    pub platform_fee_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Denylist PDA for the user; must not exist
+    #[account(
+        seeds = [b"denylist", subscription.user.as_ref()],
+        bump,
+        constraint = user_denylist_entry.data_is_empty() @ ErrorCode::WalletDenylisted
+    )]
+    pub user_denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: Denylist PDA for the merchant wallet (owner of the settlement account); must not exist
+    #[account(
+        seeds = [b"denylist", merchant_token_account.owner.as_ref()],
+        bump,
+        constraint = merchant_denylist_entry.data_is_empty() @ ErrorCode::WalletDenylisted
+    )]
+    pub merchant_denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant blocklist PDA for this user; must not exist
+    #[account(
+        seeds = [b"blocklist", subscription.merchant.as_ref(), subscription.user.as_ref()],
+        bump,
+        constraint = blocklist_entry.data_is_empty() @ ErrorCode::UserBlockedByMerchant
+    )]
+    pub blocklist_entry: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"swap_asset", input_mint.key().as_ref()],
+        bump = swap_asset.bump,
+        constraint = swap_asset.enabled @ ErrorCode::SwapAssetDisabled
+    )]
+    pub swap_asset: Box<Account<'info, SwapAsset>>,
+
+    /// CHECK: Must match `swap_asset.price_feed`; parsed as a Pyth price account
+    pub price_feed: UncheckedAccount<'info>,
+
+    /// Transient account receiving swap output; closed at the end of the payment
+    #[account(
+        init_if_needed,
+        payer = keeper,
+        seeds = [b"swap_output", user_delegate.key().as_ref()],
+        bump,
+        token::mint = settlement_mint,
+        token::authority = user_delegate,
+        token::token_program = token_program
+    )]
+    pub swap_output: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(constraint = input_mint.key() == user_token_account.mint @ ErrorCode::InvalidMint)]
+    pub input_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(constraint = settlement_mint.key() == merchant_token_account.mint @ ErrorCode::InvalidMint)]
+    pub settlement_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [b"swap_config"],
+        bump = swap_config.bump
+    )]
+    pub swap_config: Box<Account<'info, SwapConfig>>,
+
+    /// CHECK: Swap aggregator; must be on the swap allowlist
+    #[account(
+        executable,
+        constraint = swap_config.is_allowed(&swap_program.key()) @ ErrorCode::InvalidJupiterProgram
+    )]
+    pub swap_program: UncheckedAccount<'info>,
+
+    /// Keeper fronting rent for `swap_output`; refunded when it closes
+    #[account(mut)]
+    pub keeper: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: User's spending analytics PDA, for the first-subscription fee
+    /// waiver and spend totals; may be uninitialized
+    #[account(
+        mut,
+        seeds = [b"user_stats", subscription.user.as_ref()],
+        bump
+    )]
+    pub user_stats: UncheckedAccount<'info>,
+
+    /// Due-date bucket listing the subscription, when indexed
+    #[account(
+        mut,
+        constraint = due_bucket.covers(&subscription.merchant, subscription.next_payment) @ ErrorCode::InvalidDueBucket
+    )]
+    pub due_bucket: Option<Account<'info, DueBucket>>,
+
+    /// Due-date bucket for the rescheduled payment, when indexed
+    #[account(mut)]
+    pub next_due_bucket: Option<Account<'info, DueBucket>>,
+
+    /// CHECK: Merchant policy PDA, freed a slot when a missed payment
+    /// auto-cancels the subscription and read for the merchant's fee
+    /// exemption, negotiated fee schedule and retry policy; may be uninitialized
+    #[account(
+        mut,
+        seeds = [b"merchant_policy", subscription.merchant.as_ref()],
+        bump
+    )]
+    pub merchant_policy: UncheckedAccount<'info>,
+
+    /// Keeper holding the execution claim; required while one is live
+    pub claimant: Option<Signer<'info>>,
+
+    /// Merchant registry account; places the payment in its velocity class
+    /// and has the payment recorded in its stats
+    #[account(
+        mut,
+        constraint = merchant.key() == subscription.merchant @ ErrorCode::InvalidMerchantAccount
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    pub merchant_registry_program: Program<'info, LutriiMerchantRegistry>,
+
+    /// CHECK: Instructions sysvar, read by the registry to check it runs under a CPI
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant's monthly volume PDA in the settlement mint, tracked
+    /// for fee rebates; may be uninitialized
+    #[account(
+        mut,
+        seeds = [b"merchant_volume", subscription.merchant.as_ref(), settlement_mint.key().as_ref()],
+        bump
+    )]
+    pub merchant_volume: UncheckedAccount<'info>,
+
+    /// Merchant's account in the subscriber's token; required for the
+    /// `SettleInPaymentToken` fallback
+    #[account(
+        mut,
+        constraint = fallback_merchant_token_account.owner == merchant_token_account.owner @ ErrorCode::InvalidTokenAccountOwner,
+        constraint = fallback_merchant_token_account.mint == input_mint.key() @ ErrorCode::InvalidMint
+    )]
+    pub fallback_merchant_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Fee wallet owner's account in the subscriber's token; required for
+    /// the `SettleInPaymentToken` fallback when a fee is due
+    #[account(
+        mut,
+        constraint = fallback_fee_account.owner == platform_fee_account.owner @ ErrorCode::InvalidFeeWallet,
+        constraint = fallback_fee_account.mint == input_mint.key() @ ErrorCode::InvalidMint
+    )]
+    pub fallback_fee_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: Platform's promotional fee windows PDA; may be uninitialized
+    #[account(seeds = [b"fee_holidays"], bump)]
+    pub fee_holidays: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant's reporting totals PDA for the current year; may be
+    /// uninitialized
+    #[account(
+        mut,
+        seeds = [
+            b"merchant_report",
+            subscription.merchant.as_ref(),
+            settlement_mint.key().as_ref(),
+            &MerchantReport::year_seed(Clock::get()?.unix_timestamp),
+        ],
+        bump
+    )]
+    pub merchant_report: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant's statement PDA for the current month; may be
+    /// uninitialized
+    #[account(
+        mut,
+        seeds = [
+            b"merchant_statement",
+            subscription.merchant.as_ref(),
+            settlement_mint.key().as_ref(),
+            &MerchantReport::year_seed(Clock::get()?.unix_timestamp),
+            &MerchantStatement::month_seed(Clock::get()?.unix_timestamp),
+        ],
+        bump
+    )]
+    pub merchant_statement: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant's refund exposure PDA; may be uninitialized
+    #[account(
+        mut,
+        seeds = [b"refund_liability", subscription.merchant.as_ref(), settlement_mint.key().as_ref()],
+        bump
+    )]
+    pub refund_liability: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant's revenue forecast PDA in the subscriber's token; may
+    /// be uninitialized
+    #[account(
+        mut,
+        seeds = [b"revenue_forecast", subscription.merchant.as_ref(), input_mint.key().as_ref()],
+        bump
+    )]
+    pub revenue_forecast: UncheckedAccount<'info>,
+
+    /// Plan the subscription is bound to, whose current price is charged;
+    /// required for plan-bound subscriptions and refused for others
+    #[account(
+        constraint = plan.key() == subscription.plan @ ErrorCode::PlanSubscriptionMismatch
+    )]
+    pub plan: Option<Box<Account<'info, Plan>>>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExecutePaymentWithSwap<'info>>,
+    data: Vec<u8>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let remaining_accounts = ctx.remaining_accounts;
+    let accounts = &mut *ctx.accounts;
+    let mut payment = PaymentAccounts {
+        subscription: &mut accounts.subscription,
+        platform: &mut accounts.platform_state,
+        user_delegate: &mut accounts.user_delegate,
+        merchant: &accounts.merchant,
+        merchant_policy: &accounts.merchant_policy,
+        claimant: accounts.claimant.as_ref().map(|c| c.key()).unwrap_or_default(),
+        plan: accounts.plan.as_deref(),
+        fee_holidays: &accounts.fee_holidays,
+        user_stats: &accounts.user_stats,
+        due_bucket: accounts.due_bucket.as_mut(),
+        next_due_bucket: accounts.next_due_bucket.as_mut(),
+        revenue_forecast: &accounts.revenue_forecast,
+        merchant_volume: &accounts.merchant_volume,
+        merchant_report: &accounts.merchant_report,
+        merchant_statement: &accounts.merchant_statement,
+        refund_liability: &accounts.refund_liability,
+    };
+
+    // ============================================================================
+    // CHECKS - Same guards as execute_payment, plus the oracle bound
+    // ============================================================================
+
+    // The fee is split out of the swap output, so the user cannot bear it on top
+    require!(
+        payment.subscription.fee_bearer == FeeBearer::Merchant,
+        ErrorCode::FeeOnTopSwapUnsupported
+    );
+    // Routes spend from the user's token account, never from an escrow
+    require!(!payment.subscription.escrow_funded, ErrorCode::EscrowSwapUnsupported);
+    let Some(due) = payment.begin(
+        &accounts.token_program.key(),
+        &accounts.input_mint.key(),
+        &clock,
+    )?
+    else {
+        return Ok(());
+    };
+    let (charge, fee) = (due.charge, due.fee);
+
+    // Most of the user's token the charge may cost at the oracle price,
+    // within the subscription's own slippage cap when it set one
+    let price = accounts
+        .swap_asset
+        .price(&accounts.price_feed, clock.unix_timestamp)?;
+    let slippage_bps = payment
+        .subscription
+        .swap_slippage_bps(accounts.swap_asset.max_slippage_bps);
+    let max_amount_in = accounts.swap_asset.max_input(
+        &price,
+        charge,
+        accounts.input_mint.decimals,
+        accounts.settlement_mint.decimals,
+        slippage_bps,
+    )?;
+
+    // Without a route the merchant's fallback applies; otherwise the route
+    // may only move the user's token into the swap output
+    let fallback = if charge > 0 && data.is_empty() {
+        match MerchantPolicy::swap_fallback_of(payment.merchant_policy)? {
+            SwapFallback::Fail => return err!(ErrorCode::SwapRouteNotFound),
+            fallback => Some(fallback),
+        }
+    } else {
+        if charge > 0 {
+            require!(
+                remaining_accounts.len() <= MAX_SWAP_ROUTE_ACCOUNTS as usize,
+                ErrorCode::SwapRouteTooLarge
+            );
+            accounts.swap_config.check_route(
+                remaining_accounts,
+                &accounts.user_token_account.key(),
+                &accounts.swap_output.key(),
+                &payment.user_delegate.key(),
+            )?;
+            // Fail before swapping rather than running out mid-settlement
+            require!(
+                sol_remaining_compute_units() >= SWAP_PAYMENT_COMPUTE_UNITS as u64,
+                ErrorCode::InsufficientComputeBudget
+            );
+        }
+        None
+    };
+
+    // Delinquency: back off instead of reverting, as in execute_payment
+    let user_token_account = &accounts.user_token_account;
+    let user_delegate_key = payment.user_delegate.key();
+    let delegation_covers = user_token_account.delegate == COption::Some(user_delegate_key)
+        && user_token_account.delegated_amount >= max_amount_in;
+    let failure = if charge == 0 {
+        None
+    } else if fallback == Some(SwapFallback::Delinquent) {
+        Some(ErrorCode::SwapRouteNotFound)
+    } else {
+        check_spendable(user_token_account, &user_delegate_key, max_amount_in).err()
+    };
+    if let Some(failure) = failure {
+        return payment.fail(&due, failure, delegation_covers, &clock);
+    }
+    let balance_before = user_token_account.amount;
+
+    // ============================================================================
+    // EFFECTS - Update state BEFORE external calls (CEI pattern)
+    // ============================================================================
+
+    payment.record(&due, clock.unix_timestamp)?;
+
+    // ============================================================================
+    // INTERACTIONS - Swap, then settle the output
+    // ============================================================================
+
+    let user_delegate = &*payment.user_delegate;
+    let seeds = &[
+        b"user_delegate",
+        user_delegate.token_account.as_ref(),
+        &[user_delegate.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let (amount_in, amount_out, min_amount_out, merchant_amount) = if charge > 0 && fallback.is_some() {
+        // Oracle conversion of the merchant and fee legs, without slippage
+        let to_input = |amount: u64| {
+            accounts.swap_asset.max_input(
+                &price,
+                amount,
+                accounts.input_mint.decimals,
+                accounts.settlement_mint.decimals,
+                0,
+            )
+        };
+        let total_in = to_input(charge)?;
+        let fee_in = to_input(fee)?.min(total_in);
+        let merchant_in = total_in - fee_in;
+        let merchant_account = accounts
+            .fallback_merchant_token_account
+            .as_ref()
+            .ok_or(ErrorCode::InvalidTokenAccount)?;
+        transfer_checked(
+            CpiContext::new_with_signer(
+                accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: accounts.user_token_account.to_account_info(),
+                    mint: accounts.input_mint.to_account_info(),
+                    to: merchant_account.to_account_info(),
+                    authority: user_delegate.to_account_info(),
+                },
+                signer,
+            ),
+            merchant_in,
+            accounts.input_mint.decimals,
+        )?;
+        if fee_in > 0 {
+            let fee_account = accounts
+                .fallback_fee_account
+                .as_ref()
+                .ok_or(ErrorCode::InvalidFeeWallet)?;
+            transfer_checked(
+                CpiContext::new_with_signer(
+                    accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: accounts.user_token_account.to_account_info(),
+                        mint: accounts.input_mint.to_account_info(),
+                        to: fee_account.to_account_info(),
+                        authority: user_delegate.to_account_info(),
+                    },
+                    signer,
+                ),
+                fee_in,
+                accounts.input_mint.decimals,
+            )?;
+        }
+        (total_in, 0, 0, due.merchant_amount)
+    } else if charge > 0 {
+        let output_before = accounts.swap_output.amount;
+        forward_signed_cpi(
+            &accounts.swap_program.to_account_info(),
+            remaining_accounts,
+            &user_delegate.key(),
+            data,
+            signer,
+        )?;
+
+        accounts.user_token_account.reload()?;
+        accounts.swap_output.reload()?;
+        let amount_in = balance_before
+            .checked_sub(accounts.user_token_account.amount)
+            .ok_or(ErrorCode::SwapFailed)?;
+        let amount_out = accounts
+            .swap_output
+            .amount
+            .checked_sub(output_before)
+            .ok_or(ErrorCode::SwapFailed)?;
+        require!(amount_in <= max_amount_in, ErrorCode::SlippageExceeded);
+
+        // Whatever the route spent must come back at the oracle price less
+        // slippage, and cover the charge
+        let min_amount_out = accounts
+            .swap_asset
+            .min_output(
+                &price,
+                amount_in,
+                accounts.input_mint.decimals,
+                accounts.settlement_mint.decimals,
+                slippage_bps,
+            )?
+            .max(charge);
+        require!(amount_out >= min_amount_out, ErrorCode::SlippageExceeded);
+
+        // Positive slippage goes to the merchant; the fee is charged on `charge`
+        let merchant_amount = accounts.swap_output.amount - fee;
+        transfer_checked(
+            CpiContext::new_with_signer(
+                accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: accounts.swap_output.to_account_info(),
+                    mint: accounts.settlement_mint.to_account_info(),
+                    to: accounts.merchant_token_account.to_account_info(),
+                    authority: user_delegate.to_account_info(),
+                },
+                signer,
+            ),
+            merchant_amount,
+            accounts.settlement_mint.decimals,
+        )?;
+
+        if fee > 0 {
+            transfer_checked(
+                CpiContext::new_with_signer(
+                    accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: accounts.swap_output.to_account_info(),
+                        mint: accounts.settlement_mint.to_account_info(),
+                        to: accounts.platform_fee_account.to_account_info(),
+                        authority: user_delegate.to_account_info(),
+                    },
+                    signer,
+                ),
+                fee,
+                accounts.settlement_mint.decimals,
+            )?;
+        }
+        (amount_in, amount_out, min_amount_out, merchant_amount)
+    } else {
+        (0, 0, 0, 0)
+    };
+
+    // Nothing is custodied between payments
+    close_account(CpiContext::new_with_signer(
+        accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: accounts.swap_output.to_account_info(),
+            destination: accounts.keeper.to_account_info(),
+            authority: user_delegate.to_account_info(),
+        },
+        signer,
+    ))?;
+
+    // Merchant stats and automatic tier upgrades in the registry
+    record_merchant_payment(
+        &accounts.merchant_registry_program.to_account_info(),
+        &payment.merchant.to_account_info(),
+        payment.platform,
+        &accounts.instructions,
+        charge,
+    )?;
+
+    // Booked at what the merchant actually received, slippage included
+    payment.record_settlement(&due, merchant_amount, clock.unix_timestamp)?;
+
+    let user_token_account = &accounts.user_token_account;
+    let remaining_delegation = if user_token_account.delegate == COption::Some(user_delegate_key) {
+        user_token_account.delegated_amount
+    } else {
+        0
+    };
+    payment.finish(
+        &due,
+        merchant_amount,
+        remaining_delegation,
+        false,
+        accounts.settlement_mint.key(),
+        clock.unix_timestamp,
+    )?;
+
+    if fallback.is_some() {
+        emit!(SwapFallbackSettled {
+            schema_version: EVENT_SCHEMA_VERSION,
+            subscription: payment.subscription.key(),
+            input_mint: accounts.input_mint.key(),
+            amount_in,
+            oracle_price: price.price,
+            oracle_expo: price.expo,
+            timestamp: clock.unix_timestamp,
+        });
+    } else {
+        emit!(PaymentSwapped {
+            schema_version: EVENT_SCHEMA_VERSION,
+            subscription: payment.subscription.key(),
+            input_mint: accounts.input_mint.key(),
+            output_mint: accounts.settlement_mint.key(),
+            amount_in,
+            max_amount_in,
+            amount_out,
+            oracle_price: price.price,
+            oracle_expo: price.expo,
+            timestamp: clock.unix_timestamp,
+            min_amount_out,
+        });
+    }
+
+    #[cfg(feature = "verbose-logs")]
+    msg!(
+        "✅ Swap payment executed: {} in for {} out (max in {})",
+        amount_in,
+        amount_out,
+        max_amount_in
+    );
+    Ok(())
+}
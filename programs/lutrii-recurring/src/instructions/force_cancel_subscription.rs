@@ -0,0 +1,129 @@
+use anchor_lang::prelude::*;
+use lutrii_common::{Subscription, EVENT_SCHEMA_VERSION};
+use crate::errors::ErrorCode;
+use crate::state::{DueBucket, MerchantPolicy, RevenueForecast, UserDelegate, UserStats};
+use crate::{PlatformState, SubscriptionForceCancelled};
+
+/// Why the platform deactivated a subscription
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ForceCancelReason {
+    /// Merchant or user became subject to sanctions
+    Sanctions,
+    /// Court order or regulator request
+    LegalOrder,
+    /// Confirmed fraud
+    Fraud,
+    Other,
+}
+
+/// Deactivate a subscription for compliance (admin or governance authority only)
+///
+/// Stops executions immediately. The subscription's share of the shared
+/// delegation is released from the bookkeeping, but the token approval
+/// itself can only be revoked by the user; the program never spends it for
+/// an inactive subscription, and the user can still close the account.
+///
+/// # Arguments
+/// * `reason` - Recorded in `SubscriptionForceCancelled`
+#[derive(Accounts)]
+pub struct ForceCancelSubscription<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        bump = subscription.bump
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(
+        mut,
+        seeds = [b"user_delegate", subscription.user_token_account.as_ref()],
+        bump = user_delegate.bump
+    )]
+    pub user_delegate: Account<'info, UserDelegate>,
+
+    /// CHECK: Merchant policy PDA; may be uninitialized for older subscriptions
+    #[account(
+        mut,
+        seeds = [b"merchant_policy", subscription.merchant.as_ref()],
+        bump
+    )]
+    pub merchant_policy: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+
+    /// Spending analytics for the user, when they created it
+    #[account(
+        mut,
+        seeds = [b"user_stats", subscription.user.as_ref()],
+        bump = user_stats.bump
+    )]
+    pub user_stats: Option<Account<'info, UserStats>>,
+
+    /// Due-date bucket listing the subscription, when indexed
+    #[account(
+        mut,
+        constraint = due_bucket.covers(&subscription.merchant, subscription.next_payment) @ ErrorCode::InvalidDueBucket
+    )]
+    pub due_bucket: Option<Account<'info, DueBucket>>,
+
+    /// Merchant's revenue forecast in the subscription's mint, when kept
+    #[account(
+        mut,
+        seeds = [b"revenue_forecast", subscription.merchant.as_ref(), subscription.mint.as_ref()],
+        bump = revenue_forecast.bump
+    )]
+    pub revenue_forecast: Option<Box<Account<'info, RevenueForecast>>>,
+}
+
+pub fn handler(ctx: Context<ForceCancelSubscription>, reason: ForceCancelReason) -> Result<()> {
+    let subscription = &mut ctx.accounts.subscription;
+    require!(subscription.is_active, ErrorCode::SubscriptionInactive);
+    let now = Clock::get()?.unix_timestamp;
+
+    let forecast_entry = subscription.forecast_entry();
+    subscription.is_active = false;
+    subscription.is_paused = false;
+    subscription.force_cancelled = true;
+
+    let user_delegate = &mut ctx.accounts.user_delegate;
+    user_delegate.remove_subscription(subscription);
+
+    let platform = &mut ctx.accounts.platform_state;
+    platform.total_subscriptions = platform.total_subscriptions.saturating_sub(1);
+    MerchantPolicy::release_slot(&ctx.accounts.merchant_policy)?;
+    if let Some(user_stats) = ctx.accounts.user_stats.as_mut() {
+        user_stats.subscription_removed();
+    }
+    if let Some(due_bucket) = ctx.accounts.due_bucket.as_mut() {
+        due_bucket.remove(&subscription.key());
+    }
+    if let Some(revenue_forecast) = ctx.accounts.revenue_forecast.as_mut() {
+        revenue_forecast.reschedule(forecast_entry, None, now)?;
+    }
+
+    emit!(SubscriptionForceCancelled {
+        schema_version: EVENT_SCHEMA_VERSION,
+        subscription: subscription.key(),
+        user: subscription.user,
+        merchant: subscription.merchant,
+        authority: ctx.accounts.authority.key(),
+        reason,
+        timestamp: now,
+    });
+
+    msg!("⚠️ Subscription force-cancelled: {:?}", reason);
+    Ok(())
+}
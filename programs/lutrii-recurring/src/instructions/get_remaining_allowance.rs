@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token_interface::TokenAccount;
+use lutrii_common::Subscription;
+use lutrii_core::limits;
+use crate::errors::ErrorCode;
+use crate::state::UserDelegate;
+
+/// Allowance snapshot returned by `get_remaining_allowance`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RemainingAllowance {
+    /// Allowance left under the subscription's lifetime cap
+    pub remaining_lifetime_allowance: u64,
+    /// Full payments the lifetime cap still covers
+    pub payments_remaining: u64,
+    /// Tokens the shared user delegate may still spend from the user's account
+    pub remaining_delegation: u64,
+    /// Full payments the current delegation covers
+    pub delegated_payments_remaining: u64,
+}
+
+/// Read-only query of how much a subscription may still collect
+#[derive(Accounts)]
+pub struct GetRemainingAllowance<'info> {
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(
+        constraint = user_token_account.key() == subscription.user_token_account @ ErrorCode::InvalidTokenAccount
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Shared delegate for the user's token account
+    #[account(
+        seeds = [b"user_delegate", subscription.user_token_account.as_ref()],
+        bump = user_delegate.bump
+    )]
+    pub user_delegate: Account<'info, UserDelegate>,
+}
+
+pub fn handler(ctx: Context<GetRemainingAllowance>) -> Result<RemainingAllowance> {
+    let subscription = &ctx.accounts.subscription;
+    let user_token_account = &ctx.accounts.user_token_account;
+
+    let remaining_delegation =
+        if user_token_account.delegate == COption::Some(ctx.accounts.user_delegate.key()) {
+            user_token_account.delegated_amount
+        } else {
+            0
+        };
+
+    Ok(RemainingAllowance {
+        remaining_lifetime_allowance: limits::remaining_allowance(
+            subscription.lifetime_cap,
+            subscription.total_paid,
+        ),
+        payments_remaining: limits::payments_remaining(
+            subscription.lifetime_cap,
+            subscription.total_paid,
+            subscription.amount,
+        ),
+        remaining_delegation,
+        delegated_payments_remaining: remaining_delegation
+            .checked_div(subscription.amount)
+            .unwrap_or(0),
+    })
+}
@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token_interface::TokenAccount;
+use lutrii_common::Subscription;
+use lutrii_core::{limits, proration, schedule};
+use crate::errors::ErrorCode;
+use crate::state::UserDelegate;
+
+/// Payment health of a subscription at the time of the query
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubscriptionHealth {
+    /// Next payment is not yet due
+    Current,
+    /// Payment is due and can be collected
+    Due,
+    /// At least one full period has passed since the due date, but the payment can still be collected
+    Overdue,
+    /// Payment is due but cannot be collected (delegation, balance or lifetime cap exhausted)
+    Delinquent,
+    /// Subscription is paused by the user
+    Paused,
+    /// Subscription has been cancelled
+    Cancelled,
+    /// Subscription was deactivated by the platform (compliance)
+    ForceCancelled,
+    /// Trial has ended but the user has not approved billing with `convert_trial`
+    TrialUnconverted,
+}
+
+/// Snapshot returned by `get_subscription_status`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SubscriptionStatus {
+    pub health: SubscriptionHealth,
+    /// Amount that will be charged on the next payment, including pending proration
+    pub next_charge_amount: u64,
+    /// Timestamp of the next payment
+    pub next_payment: i64,
+    /// Seconds (slots, for slot schedules) until the next payment (zero once due)
+    pub seconds_until_due: i64,
+    /// Full periods elapsed since the next payment became due
+    pub periods_overdue: u64,
+    /// Allowance left under the lifetime cap
+    pub remaining_lifetime_allowance: u64,
+    /// Full payments the lifetime cap still covers
+    pub payments_remaining: u64,
+    /// Tokens the shared user delegate may still spend from the user's account
+    pub remaining_delegation: u64,
+    /// Current balance of the user's token account
+    pub user_balance: u64,
+    /// Whether the next charge would pass the delegation, balance and lifetime cap checks
+    pub can_collect: bool,
+    /// Consecutive failed collections
+    pub retry_count: u8,
+    /// Earliest time of the next collection attempt after a failure
+    pub next_retry_at: i64,
+}
+
+/// Read-only query of a subscription's payment health
+///
+/// Returns a `SubscriptionStatus` through Anchor return data so clients can
+/// call it via transaction simulation without signing or paying fees.
+#[derive(Accounts)]
+pub struct GetSubscriptionStatus<'info> {
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(
+        constraint = user_token_account.key() == subscription.user_token_account @ ErrorCode::InvalidTokenAccount
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Shared delegate for the user's token account
+    #[account(
+        seeds = [b"user_delegate", subscription.user_token_account.as_ref()],
+        bump = user_delegate.bump
+    )]
+    pub user_delegate: Account<'info, UserDelegate>,
+}
+
+pub fn handler(ctx: Context<GetSubscriptionStatus>) -> Result<SubscriptionStatus> {
+    let subscription = &ctx.accounts.subscription;
+    let user_token_account = &ctx.accounts.user_token_account;
+    let now = subscription.schedule_unit.now(&Clock::get()?);
+
+    let remaining_delegation =
+        if user_token_account.delegate == COption::Some(ctx.accounts.user_delegate.key()) {
+            user_token_account.delegated_amount
+        } else {
+            0
+        };
+
+    let (next_charge_amount, _) =
+        proration::apply_adjustment(subscription.amount, subscription.pending_proration)
+            .map_err(ErrorCode::from)?;
+    let next_charge_amount = subscription.discounted(next_charge_amount);
+
+    let can_collect = remaining_delegation >= next_charge_amount
+        && user_token_account.amount >= next_charge_amount
+        && limits::within_lifetime_cap(
+            subscription.total_paid,
+            next_charge_amount,
+            subscription.lifetime_cap,
+        );
+
+    let periods_overdue = schedule::periods_overdue(
+        now,
+        subscription.next_payment,
+        subscription.frequency_seconds,
+    );
+
+    let health = if subscription.force_cancelled {
+        SubscriptionHealth::ForceCancelled
+    } else if !subscription.is_active {
+        SubscriptionHealth::Cancelled
+    } else if subscription.is_paused {
+        SubscriptionHealth::Paused
+    } else if !schedule::is_due(now, subscription.next_payment) {
+        SubscriptionHealth::Current
+    } else if subscription.delegation_deferred {
+        SubscriptionHealth::TrialUnconverted
+    } else if !can_collect {
+        SubscriptionHealth::Delinquent
+    } else if periods_overdue > 0 {
+        SubscriptionHealth::Overdue
+    } else {
+        SubscriptionHealth::Due
+    };
+
+    Ok(SubscriptionStatus {
+        health,
+        next_charge_amount,
+        next_payment: subscription.next_payment,
+        seconds_until_due: schedule::seconds_until_due(now, subscription.next_payment),
+        periods_overdue,
+        remaining_lifetime_allowance: limits::remaining_allowance(
+            subscription.lifetime_cap,
+            subscription.total_paid,
+        ),
+        payments_remaining: limits::payments_remaining(
+            subscription.lifetime_cap,
+            subscription.total_paid,
+            subscription.amount,
+        ),
+        remaining_delegation,
+        user_balance: user_token_account.amount,
+        can_collect,
+        retry_count: subscription.retry_count,
+        next_retry_at: subscription.next_retry_at,
+    })
+}
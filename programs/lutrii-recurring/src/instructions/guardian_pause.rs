@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use lutrii_common::{AdminAuditAction, EVENT_SCHEMA_VERSION};
+use crate::errors::ErrorCode;
+use crate::state::{record_admin_action, AdminAuditLog, GovernanceConfig};
+use crate::{EmergencyPauseActivated, PlatformState};
+
+/// Emergency pause by the governance pause guardian (guardian only)
+///
+/// Lets a governed platform stop payments without waiting for a vote.
+/// The pause lapses like an admin pause; resuming earlier still takes a
+/// proposal calling `emergency_unpause`.
+#[derive(Accounts)]
+pub struct GuardianPause<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform_state.bump
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        seeds = [b"governance"],
+        bump = governance_config.bump,
+        constraint = governance_config.is_pause_guardian(&guardian.key()) @ ErrorCode::UnauthorizedGuardian
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+
+    pub guardian: Signer<'info>,
+
+    /// Admin audit log, appended to when passed
+    #[account(
+        mut,
+        seeds = [b"admin_audit_log"],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Option<Box<Account<'info, AdminAuditLog>>>,
+}
+
+pub fn handler(ctx: Context<GuardianPause>, reason: String) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let expires_at = ctx.accounts.platform_state.activate_pause(&reason, now)?;
+
+    emit!(EmergencyPauseActivated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        timestamp: now,
+        reason,
+        expires_at,
+    });
+
+    let actor = ctx.accounts.guardian.key();
+    record_admin_action(ctx.accounts.audit_log.as_mut(), actor, AdminAuditAction::GuardianPause)?;
+
+    msg!("⚠️ EMERGENCY PAUSE ACTIVATED by guardian {}", ctx.accounts.guardian.key());
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use lutrii_common::{EVENT_SCHEMA_VERSION, Subscription};
+use lutrii_core::SECONDS_PER_DAY;
+use crate::errors::ErrorCode;
+use crate::state::{UserStats, SPEND_WINDOW_DAYS};
+use crate::UserStatsInitialized;
+
+/// Create the caller's spending analytics account
+///
+/// The user's existing active subscriptions may be passed as remaining
+/// accounts to seed `active_subscriptions`; later creations and
+/// cancellations keep it current as long as the stats account is passed.
+/// The first of them takes the first-subscription fee waiver.
+///
+/// # Security
+/// - Each remaining account must be an active subscription owned by this
+///   program and belonging to the signer; duplicates are rejected
+#[derive(Accounts)]
+pub struct InitUserStats<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = UserStats::LEN,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitUserStats>) -> Result<()> {
+    let user = ctx.accounts.user.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    let mut seen: Vec<Pubkey> = Vec::with_capacity(ctx.remaining_accounts.len());
+    for account in ctx.remaining_accounts {
+        require!(account.owner == &crate::ID, ErrorCode::InvalidSubscriptionAccount);
+        require!(!seen.contains(account.key), ErrorCode::InvalidSubscriptionAccount);
+
+        let data = account.try_borrow_data()?;
+        let subscription = Subscription::try_deserialize(&mut &data[..])?;
+        require!(subscription.user == user, ErrorCode::InvalidSubscriptionAccount);
+        require!(subscription.is_active, ErrorCode::SubscriptionInactive);
+        seen.push(*account.key);
+    }
+
+    let user_stats = &mut ctx.accounts.user_stats;
+    user_stats.user = user;
+    user_stats.active_subscriptions = seen.len() as u32;
+    user_stats.payment_count = 0;
+    user_stats.lifetime_spend = 0;
+    user_stats.month_start = now;
+    user_stats.month_spend = 0;
+    user_stats.previous_month_spend = 0;
+    user_stats.daily_spend = [0; SPEND_WINDOW_DAYS];
+    user_stats.last_spend_day = now.div_euclid(SECONDS_PER_DAY);
+    user_stats.created_at = now;
+    user_stats.bump = ctx.bumps.user_stats;
+    user_stats.first_subscription = seen.first().copied().unwrap_or_default();
+
+    emit!(UserStatsInitialized {
+        schema_version: EVENT_SCHEMA_VERSION,
+        user,
+        active_subscriptions: user_stats.active_subscriptions,
+        timestamp: now,
+    });
+
+    msg!("User stats initialized: {} active subscriptions", seen.len());
+    Ok(())
+}
@@ -1,33 +1,32 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
-use crate::state::PlatformConfig;
+use crate::PlatformState;
 use crate::errors::ErrorCode;
 
-/// Initialize the platform configuration
+/// Initialize the platform fee wallets
 ///
 /// This instruction can only be called once to set up the fee collection wallets.
-/// The authority will be able to update the config later via update_config.
+/// The authority can rotate them later via schedule_config_change.
+/// The USDC and USD1 mints are recorded as the only settlement mints.
 ///
 /// # Arguments
 /// * `fee_wallet_usdc` - Token account to receive USDC fees
 /// * `fee_wallet_usd1` - Token account to receive USD1 fees
 ///
 /// # Security
-/// - Can only be called once (init constraint)
-/// - Authority becomes admin with update permissions
+/// - Only the platform authority can call this
+/// - Can only be called once (fails if fee wallets are already set)
 /// - Fee wallets must be valid token accounts for USDC/USD1 mints
 #[derive(Accounts)]
 pub struct InitializeConfig<'info> {
     #[account(
-        init,
-        payer = authority,
-        space = PlatformConfig::LEN,
-        seeds = [b"platform_config"],
-        bump
+        mut,
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
     )]
-    pub config: Account<'info, PlatformConfig>,
+    pub platform_state: Account<'info, PlatformState>,
 
-    #[account(mut)]
     pub authority: Signer<'info>,
 
     /// Fee wallet for USDC (must be valid USDC token account)
@@ -48,30 +47,27 @@ pub struct InitializeConfig<'info> {
     /// USD1 mint (for validation)
     pub usd1_mint: InterfaceAccount<'info, Mint>,
 
-    pub system_program: Program<'info, System>,
     pub token_program: Interface<'info, TokenInterface>,
 }
 
 pub fn handler(ctx: Context<InitializeConfig>) -> Result<()> {
-    let config = &mut ctx.accounts.config;
+    let platform = &mut ctx.accounts.platform_state;
 
-    // Set core config
-    config.authority = ctx.accounts.authority.key();
-    config.fee_wallet_usdc = ctx.accounts.fee_wallet_usdc.key();
-    config.fee_wallet_usd1 = ctx.accounts.fee_wallet_usd1.key();
-    config.bump = ctx.bumps.config;
+    require!(
+        platform.fee_wallet_usdc == Pubkey::default()
+            && platform.fee_wallet_usd1 == Pubkey::default(),
+        ErrorCode::ConfigAlreadyInitialized
+    );
 
-    // Initialize reserved fields to default (ready for Phase 3)
-    config.reserved1 = Pubkey::default();
-    config.reserved2 = Pubkey::default();
-    config.reserved3 = Pubkey::default();
-    config.reserved4 = 0;
-    config.reserved5 = [0; 63];
+    platform.fee_wallet_usdc = ctx.accounts.fee_wallet_usdc.key();
+    platform.fee_wallet_usd1 = ctx.accounts.fee_wallet_usd1.key();
+    platform.usdc_mint = ctx.accounts.usdc_mint.key();
+    platform.usd1_mint = ctx.accounts.usd1_mint.key();
 
-    msg!("✅ Platform config initialized");
-    msg!("Authority: {}", config.authority);
-    msg!("USDC fee wallet: {}", config.fee_wallet_usdc);
-    msg!("USD1 fee wallet: {}", config.fee_wallet_usd1);
+    msg!("✅ Platform fee wallets initialized");
+    msg!("Authority: {}", platform.authority);
+    msg!("USDC fee wallet: {}", platform.fee_wallet_usdc);
+    msg!("USD1 fee wallet: {}", platform.fee_wallet_usd1);
 
     Ok(())
 }
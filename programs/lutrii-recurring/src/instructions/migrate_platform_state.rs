@@ -0,0 +1,153 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::{AccountsClose, Discriminator};
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::PlatformConfig;
+use crate::{PlatformState, PlatformStateMigrated};
+
+/// Migrate the platform state PDA to the current layout (admin only)
+///
+/// Older deployments store a shorter PlatformState that no longer
+/// deserializes into the current struct, so the account is taken unchecked
+/// and validated by hand before being reallocated in place.
+///
+/// # Security
+/// - PDA seeds and program ownership are enforced by constraints
+/// - Discriminator is checked before any bytes are trusted
+/// - Only the authority stored in the account can migrate it
+/// - Legacy config must share the same authority before it is merged
+/// - Authority pays any additional rent
+#[derive(Accounts)]
+pub struct MigratePlatformState<'info> {
+    /// CHECK: Validated manually in the handler; legacy layouts cannot be
+    /// deserialized as `Account<PlatformState>`
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump,
+        owner = crate::ID
+    )]
+    pub platform_state: UncheckedAccount<'info>,
+
+    /// Legacy fee wallet config, merged into platform state and closed
+    #[account(
+        mut,
+        seeds = [b"platform_config"],
+        bump = platform_config.bump
+    )]
+    pub platform_config: Option<Account<'info, PlatformConfig>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<MigratePlatformState>) -> Result<()> {
+    let platform_info = ctx.accounts.platform_state.to_account_info();
+
+    // ============================================================================
+    // CHECKS - Validate raw account data
+    // ============================================================================
+
+    let from_version = {
+        let data = platform_info.try_borrow_data()?;
+        require!(
+            data.len() >= PlatformState::LEGACY_SPACE
+                && data[..8] == PlatformState::DISCRIMINATOR,
+            ErrorCode::InvalidAccountData
+        );
+
+        let authority = Pubkey::try_from(&data[8..40])
+            .map_err(|_| error!(ErrorCode::InvalidAccountData))?;
+        require!(
+            authority == ctx.accounts.authority.key(),
+            ErrorCode::UnauthorizedAdmin
+        );
+
+        if data.len() > PlatformState::VERSION_OFFSET {
+            data[PlatformState::VERSION_OFFSET]
+        } else {
+            0
+        }
+    };
+
+    if let Some(config) = &ctx.accounts.platform_config {
+        require!(
+            config.authority == ctx.accounts.authority.key(),
+            ErrorCode::UnauthorizedAdmin
+        );
+    }
+
+    require!(
+        from_version < PlatformState::CURRENT_VERSION || ctx.accounts.platform_config.is_some(),
+        ErrorCode::AlreadyMigrated
+    );
+
+    // ============================================================================
+    // EFFECTS - Top up rent, grow the account and stamp the new version
+    // ============================================================================
+
+    let new_size = PlatformState::SPACE.max(platform_info.data_len());
+    let required_lamports = Rent::get()?.minimum_balance(new_size);
+    let shortfall = required_lamports.saturating_sub(platform_info.lamports());
+
+    if shortfall > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: platform_info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+
+    // New bytes are zeroed, and fields added since v1 occupy previously
+    // zeroed padding, so every appended field starts at its default
+    platform_info.realloc(new_size, true)?;
+    platform_info.try_borrow_mut_data()?[PlatformState::VERSION_OFFSET] =
+        PlatformState::CURRENT_VERSION;
+
+    // Merge the legacy config (v2) - platform state wins if already set
+    if let Some(config) = &ctx.accounts.platform_config {
+        let mut platform =
+            PlatformState::try_deserialize(&mut &platform_info.try_borrow_data()?[..])?;
+
+        if !platform.fee_wallets_configured() {
+            platform.fee_wallet_usdc = config.fee_wallet_usdc;
+            platform.fee_wallet_usd1 = config.fee_wallet_usd1;
+            msg!("Fee wallets merged from legacy platform config");
+        }
+
+        platform.try_serialize(&mut &mut platform_info.try_borrow_mut_data()?[..])?;
+    }
+
+    // ============================================================================
+    // INTERACTIONS - Close the legacy config
+    // ============================================================================
+
+    if let Some(config) = &ctx.accounts.platform_config {
+        config.close(ctx.accounts.authority.to_account_info())?;
+        msg!("Legacy platform config closed");
+    }
+
+    emit!(PlatformStateMigrated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        from_version,
+        to_version: PlatformState::CURRENT_VERSION,
+        new_size: new_size as u64,
+    });
+
+    msg!(
+        "✅ Platform state migrated: v{} -> v{} ({} bytes)",
+        from_version,
+        PlatformState::CURRENT_VERSION,
+        new_size
+    );
+
+    Ok(())
+}
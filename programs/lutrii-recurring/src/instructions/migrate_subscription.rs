@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+use anchor_lang::Discriminator;
+use lutrii_common::{Subscription, EVENT_SCHEMA_VERSION};
+use crate::errors::ErrorCode;
+use crate::SubscriptionMigrated;
+
+/// Bring a subscription forward to the current account layout (permissionless)
+///
+/// Payments refuse subscriptions whose `layout_version` predates this build,
+/// so a stale layout is never silently misread after an upgrade. Fields are
+/// only ever appended, so migrating grows the account in place, leaves the
+/// new fields at their zeroed defaults and stamps the current version.
+/// `escrow_funded` is the exception: it is set when the subscription
+/// already has an escrow.
+///
+/// # Security
+/// - Program ownership is enforced by constraint and the discriminator is
+///   checked before any bytes are trusted; the subscription PDA is checked
+///   once the account decodes, since its nonce seed is only readable then
+/// - Only adds zeroed fields; terms and limits are untouched, so anyone
+///   (usually the keeper) may migrate and pay any additional rent
+#[derive(Accounts)]
+pub struct MigrateSubscription<'info> {
+    /// CHECK: Validated manually in the handler; older layouts may not
+    /// deserialize as `Account<Subscription>`
+    #[account(mut, owner = crate::ID)]
+    pub subscription: UncheckedAccount<'info>,
+
+    /// CHECK: Subscription's escrow PDA; only whether it exists is read
+    #[account(
+        seeds = [b"escrow", subscription.key().as_ref()],
+        bump
+    )]
+    pub escrow: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<MigrateSubscription>) -> Result<()> {
+    let subscription_info = ctx.accounts.subscription.to_account_info();
+
+    // ============================================================================
+    // CHECKS - Validate raw account data
+    // ============================================================================
+
+    {
+        let data = subscription_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 + 64 && data[..8] == Subscription::DISCRIMINATOR,
+            ErrorCode::InvalidAccountData
+        );
+    }
+
+    // ============================================================================
+    // EFFECTS - Top up rent, grow the account and stamp the current version
+    // ============================================================================
+
+    let new_size = Subscription::SPACE.max(subscription_info.data_len());
+    let required_lamports = Rent::get()?.minimum_balance(new_size);
+    let shortfall = required_lamports.saturating_sub(subscription_info.lamports());
+
+    if shortfall > 0 {
+        transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: subscription_info.clone(),
+                },
+            ),
+            shortfall,
+        )?;
+    }
+
+    // Appended fields read from the new zeroed bytes
+    subscription_info.realloc(new_size, true)?;
+    let mut subscription =
+        Subscription::try_deserialize(&mut &subscription_info.try_borrow_data()?[..])?;
+    // Layouts before nonces read nonce 0, the original address
+    let (expected, _) = Pubkey::find_program_address(
+        &[
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        &crate::ID,
+    );
+    require_keys_eq!(
+        expected,
+        subscription_info.key(),
+        ErrorCode::InvalidSubscriptionAccount
+    );
+    let from_version = subscription.layout_version;
+    require!(
+        from_version < Subscription::CURRENT_VERSION,
+        ErrorCode::AlreadyMigrated
+    );
+
+    // Escrows opened before the flag existed keep being drawn from
+    if from_version < 13 {
+        subscription.escrow_funded = !ctx.accounts.escrow.data_is_empty();
+    }
+    subscription.layout_version = Subscription::CURRENT_VERSION;
+    subscription.try_serialize(&mut &mut subscription_info.try_borrow_mut_data()?[..])?;
+
+    emit!(SubscriptionMigrated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        subscription: subscription_info.key(),
+        from_version,
+        to_version: Subscription::CURRENT_VERSION,
+        new_size: new_size as u64,
+    });
+
+    msg!(
+        "Subscription migrated: v{} -> v{}",
+        from_version,
+        Subscription::CURRENT_VERSION
+    );
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{TokenAccount, TokenInterface};
+use lutrii_common::{EVENT_SCHEMA_VERSION, Subscription};
+use lutrii_core::limits;
+use crate::errors::ErrorCode;
+use crate::state::UserDelegate;
+use crate::TokenAccountMigrated;
+
+/// Move a subscription to another token account of the same user and mint
+///
+/// The subscription's remaining lifetime allowance moves from the old token
+/// account's shared delegate to the new one's: the old approval shrinks by
+/// that amount (and is revoked once nothing is left on it), the new one
+/// grows by it, and `user_token_account` is repointed, all in one
+/// instruction. Trials awaiting `convert_trial` reserve nothing and only
+/// repoint the subscription.
+///
+/// # Security
+/// - Only the subscription owner can migrate (has_one + signer)
+/// - The new token account must be owned by the user and hold the same mint
+#[derive(Accounts)]
+pub struct MigrateTokenAccount<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        bump = subscription.bump,
+        has_one = user @ ErrorCode::UnauthorizedUser
+    )]
+    pub subscription: Box<Account<'info, Subscription>>,
+
+    #[account(
+        mut,
+        constraint = old_token_account.key() == subscription.user_token_account @ ErrorCode::InvalidTokenAccount
+    )]
+    pub old_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [b"user_delegate", subscription.user_token_account.as_ref()],
+        bump = old_user_delegate.bump
+    )]
+    pub old_user_delegate: Box<Account<'info, UserDelegate>>,
+
+    #[account(
+        mut,
+        constraint = new_token_account.key() != old_token_account.key() @ ErrorCode::InvalidTokenAccount,
+        constraint = new_token_account.owner == user.key() @ ErrorCode::InvalidTokenAccountOwner,
+        constraint = new_token_account.mint == old_token_account.mint @ ErrorCode::InvalidMint
+    )]
+    pub new_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Shared delegate for the new token account; created if it has none yet
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserDelegate::LEN,
+        seeds = [b"user_delegate", new_token_account.key().as_ref()],
+        bump
+    )]
+    pub new_user_delegate: Box<Account<'info, UserDelegate>>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<MigrateTokenAccount>) -> Result<()> {
+    let subscription = &mut ctx.accounts.subscription;
+    require!(subscription.is_active, ErrorCode::SubscriptionInactive);
+
+    let new_delegate = &mut ctx.accounts.new_user_delegate;
+    if new_delegate.token_account == Pubkey::default() {
+        new_delegate.user = ctx.accounts.user.key();
+        new_delegate.token_account = ctx.accounts.new_token_account.key();
+        new_delegate.bump = ctx.bumps.new_user_delegate;
+    }
+
+    if !subscription.delegation_deferred {
+        let old_delegate = &mut ctx.accounts.old_user_delegate;
+
+        // A token account pays either directly or through swaps, never both
+        require!(
+            new_delegate.active_subscriptions == 0
+                || (new_delegate.approval_rate == 0) == (old_delegate.approval_rate == 0),
+            ErrorCode::PaymentModeMismatch
+        );
+        if new_delegate.active_subscriptions == 0 {
+            new_delegate.approval_rate = old_delegate.approval_rate;
+        }
+
+        // ============================================================================
+        // EFFECTS - Move the remaining allowance between the shared delegates
+        // ============================================================================
+
+        old_delegate.remove_subscription(subscription);
+        new_delegate.reserve(limits::remaining_allowance(
+            subscription.lifetime_cap,
+            subscription.total_paid,
+        ))?;
+        new_delegate.active_subscriptions = new_delegate
+            .active_subscriptions
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        // ============================================================================
+        // INTERACTIONS - Shrink (or revoke) the old approval, grow the new one
+        // ============================================================================
+
+        old_delegate.sync_approval(
+            old_delegate.to_account_info(),
+            ctx.accounts.old_token_account.to_account_info(),
+            ctx.accounts.user.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+        )?;
+        new_delegate.sync_approval(
+            new_delegate.to_account_info(),
+            ctx.accounts.new_token_account.to_account_info(),
+            ctx.accounts.user.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+        )?;
+
+        subscription.delegation_healthy = new_delegate.outstanding_allowance >= subscription.amount;
+    }
+
+    let old_token_account = subscription.user_token_account;
+    subscription.user_token_account = ctx.accounts.new_token_account.key();
+
+    emit!(TokenAccountMigrated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        subscription: subscription.key(),
+        user: subscription.user,
+        old_token_account,
+        new_token_account: subscription.user_token_account,
+        delegated_amount: new_delegate.approval_amount()?,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Subscription moved to token account {}",
+        subscription.user_token_account
+    );
+    Ok(())
+}
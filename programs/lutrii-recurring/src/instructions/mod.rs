@@ -1,5 +1,179 @@
 pub mod initialize_config;
 pub mod update_config;
+pub mod get_subscription_status;
+pub mod migrate_platform_state;
+pub mod add_to_denylist;
+pub mod remove_from_denylist;
+pub mod block_user;
+pub mod unblock_user;
+pub mod configure_merchant_policy;
+pub mod allow_user;
+pub mod disallow_user;
+pub mod check_delegation;
+pub mod refresh_delegation;
+pub mod migrate_token_account;
+pub mod deposit_escrow;
+pub mod withdraw_escrow;
+pub mod configure_usage_cap;
+pub mod execute_metered_payment;
+pub mod create_promo;
+pub mod snooze_payment;
+pub mod create_plan;
+pub mod update_plan;
+pub mod create_subscription_from_plan;
+pub mod change_plan;
+pub mod propose_amount_change;
+pub mod accept_amount_change;
+pub mod create_invoice;
+pub mod void_invoice;
+pub mod pay_invoice;
+pub mod collect_invoice;
+pub mod preview_payment;
+pub mod get_remaining_allowance;
+pub mod verify_active_subscription;
+pub mod init_user_stats;
+pub mod configure_settlement;
+pub mod sweep_settlement;
+pub mod configure_fee_treasury;
+pub mod sweep_fees;
+pub mod configure_yield;
+pub mod deploy_held_funds;
+pub mod withdraw_held_funds;
+pub mod configure_swap_asset;
+pub mod execute_payment;
+pub mod execute_payment_with_swap;
+pub mod cancel_and_close;
+pub mod force_cancel_subscription;
+pub mod emergency_revoke_all;
+pub mod open_due_bucket;
+pub mod close_due_bucket;
+pub mod create_offer;
+pub mod accept_offer;
+pub mod cancel_offer;
+pub mod publish_offer;
+pub mod close_expired_offer;
+pub mod convert_trial;
+pub mod set_missed_payment_policy;
+pub mod set_retry_policy;
+pub mod migrate_subscription;
+pub mod claim_execution;
+pub mod open_merchant_volume;
+pub mod open_merchant_report;
+pub mod open_merchant_statement;
+pub mod close_merchant_statement;
+pub mod configure_refund_liability;
+pub mod open_revenue_forecast;
+pub mod open_subscription_counter;
+pub mod refresh_revenue_forecast;
+pub mod enable_governance;
+pub mod guardian_pause;
+pub mod set_pause_guardian;
+pub mod set_treasury_spend_cap;
+pub mod withdraw_treasury;
+pub mod treasury_buyback;
+pub mod open_admin_audit_log;
+pub mod create_admin_council;
+pub mod propose_council_action;
+pub mod approve_council_action;
+pub mod execute_council_action;
+pub mod schedule_config_change;
+pub mod update_platform_params;
+pub mod execute_config_change;
+pub mod configure_rebates;
+pub mod claim_rebate;
+pub mod set_swap_slippage;
+pub mod configure_swap_config;
+pub mod configure_accepted_mint;
+pub mod set_fee_exemption;
+pub mod configure_fee_holidays;
+pub mod set_merchant_fee_schedule;
 
 pub use initialize_config::*;
 pub use update_config::*;
+pub use get_subscription_status::*;
+pub use migrate_platform_state::*;
+pub use add_to_denylist::*;
+pub use remove_from_denylist::*;
+pub use block_user::*;
+pub use unblock_user::*;
+pub use configure_merchant_policy::*;
+pub use allow_user::*;
+pub use disallow_user::*;
+pub use check_delegation::*;
+pub use refresh_delegation::*;
+pub use migrate_token_account::*;
+pub use deposit_escrow::*;
+pub use withdraw_escrow::*;
+pub use configure_usage_cap::*;
+pub use execute_metered_payment::*;
+pub use create_promo::*;
+pub use snooze_payment::*;
+pub use create_plan::*;
+pub use update_plan::*;
+pub use create_subscription_from_plan::*;
+pub use change_plan::*;
+pub use propose_amount_change::*;
+pub use accept_amount_change::*;
+pub use create_invoice::*;
+pub use void_invoice::*;
+pub use pay_invoice::*;
+pub use collect_invoice::*;
+pub use preview_payment::*;
+pub use get_remaining_allowance::*;
+pub use verify_active_subscription::*;
+pub use init_user_stats::*;
+pub use configure_settlement::*;
+pub use sweep_settlement::*;
+pub use configure_fee_treasury::*;
+pub use sweep_fees::*;
+pub use configure_yield::*;
+pub use deploy_held_funds::*;
+pub use withdraw_held_funds::*;
+pub use configure_swap_asset::*;
+pub use execute_payment::*;
+pub use execute_payment_with_swap::*;
+pub use cancel_and_close::*;
+pub use force_cancel_subscription::*;
+pub use emergency_revoke_all::*;
+pub use open_due_bucket::*;
+pub use close_due_bucket::*;
+pub use create_offer::*;
+pub use accept_offer::*;
+pub use cancel_offer::*;
+pub use publish_offer::*;
+pub use close_expired_offer::*;
+pub use convert_trial::*;
+pub use set_missed_payment_policy::*;
+pub use set_retry_policy::*;
+pub use migrate_subscription::*;
+pub use claim_execution::*;
+pub use open_merchant_volume::*;
+pub use open_merchant_report::*;
+pub use open_merchant_statement::*;
+pub use close_merchant_statement::*;
+pub use configure_refund_liability::*;
+pub use open_revenue_forecast::*;
+pub use open_subscription_counter::*;
+pub use refresh_revenue_forecast::*;
+pub use enable_governance::*;
+pub use guardian_pause::*;
+pub use set_pause_guardian::*;
+pub use set_treasury_spend_cap::*;
+pub use withdraw_treasury::*;
+pub use treasury_buyback::*;
+pub use open_admin_audit_log::*;
+pub use create_admin_council::*;
+pub use propose_council_action::*;
+pub use approve_council_action::*;
+pub use execute_council_action::*;
+pub use schedule_config_change::*;
+pub use update_platform_params::*;
+pub use execute_config_change::*;
+pub use configure_rebates::*;
+pub use claim_rebate::*;
+pub use set_swap_slippage::*;
+pub use configure_swap_config::*;
+pub use configure_accepted_mint::*;
+pub use set_fee_exemption::*;
+pub use configure_fee_holidays::*;
+pub use set_merchant_fee_schedule::*;
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use lutrii_common::ADMIN_AUDIT_LOG_CAPACITY;
+use crate::errors::ErrorCode;
+use crate::state::AdminAuditLog;
+use crate::PlatformState;
+
+/// Open the platform's admin audit log (admin only)
+///
+/// From then on admin instructions that are passed the log record who did
+/// what, and when.
+#[derive(Accounts)]
+pub struct OpenAdminAuditLog<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = AdminAuditLog::LEN,
+        seeds = [b"admin_audit_log"],
+        bump
+    )]
+    pub audit_log: Box<Account<'info, AdminAuditLog>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<OpenAdminAuditLog>) -> Result<()> {
+    // The trail starts empty: every slot of the new account is zeroed
+    ctx.accounts.audit_log.bump = ctx.bumps.audit_log;
+
+    msg!("Admin audit log opened ({} entries)", ADMIN_AUDIT_LOG_CAPACITY);
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use crate::errors::ErrorCode;
+use crate::state::DueBucket;
+use crate::DueBucketOpened;
+
+/// Open a merchant's due-date bucket for `day` (permissionless)
+///
+/// Usually called by keepers ahead of the payments they will re-index. The
+/// payer gets the rent back through `close_due_bucket` once the day has
+/// passed and the bucket is empty.
+///
+/// # Arguments
+/// * `day` - Unix day (`timestamp / 86400`); must not be in the past
+#[derive(Accounts)]
+#[instruction(day: i64)]
+pub struct OpenDueBucket<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = DueBucket::LEN,
+        seeds = [b"due_bucket", merchant.key().as_ref(), day.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub due_bucket: Account<'info, DueBucket>,
+
+    /// Merchant account from merchant registry
+    #[account(
+        seeds = [b"merchant", merchant.owner.as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<OpenDueBucket>, day: i64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    require!(day >= DueBucket::day_of(now), ErrorCode::InvalidDueBucket);
+
+    let due_bucket = &mut ctx.accounts.due_bucket;
+    due_bucket.merchant = ctx.accounts.merchant.key();
+    due_bucket.day = day;
+    due_bucket.payer = ctx.accounts.payer.key();
+    due_bucket.subscriptions = Vec::new();
+    due_bucket.bump = ctx.bumps.due_bucket;
+
+    emit!(DueBucketOpened {
+        schema_version: EVENT_SCHEMA_VERSION,
+        due_bucket: due_bucket.key(),
+        merchant: due_bucket.merchant,
+        day,
+        payer: due_bucket.payer,
+    });
+
+    msg!("Due bucket opened for day {}", day);
+    Ok(())
+}
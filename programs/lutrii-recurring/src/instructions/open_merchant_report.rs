@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use crate::state::MerchantReport;
+use crate::MerchantReportOpened;
+
+/// Start a merchant's reporting totals for a calendar year in a mint (permissionless)
+///
+/// Usually called by the merchant before the year begins; payments only
+/// count once the account exists, and from then on every payment and
+/// invoice in the year must pass it.
+#[derive(Accounts)]
+#[instruction(year: u16)]
+pub struct OpenMerchantReport<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = MerchantReport::LEN,
+        seeds = [
+            b"merchant_report",
+            merchant.key().as_ref(),
+            mint.key().as_ref(),
+            &year.to_le_bytes(),
+        ],
+        bump
+    )]
+    pub merchant_report: Account<'info, MerchantReport>,
+
+    /// Merchant account from merchant registry
+    #[account(
+        seeds = [b"merchant", merchant.owner.as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<OpenMerchantReport>, year: u16) -> Result<()> {
+    let merchant_report = &mut ctx.accounts.merchant_report;
+    merchant_report.merchant = ctx.accounts.merchant.key();
+    merchant_report.mint = ctx.accounts.mint.key();
+    merchant_report.year = year;
+    merchant_report.bump = ctx.bumps.merchant_report;
+
+    emit!(MerchantReportOpened {
+        schema_version: EVENT_SCHEMA_VERSION,
+        merchant: merchant_report.merchant,
+        mint: merchant_report.mint,
+        year,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Merchant reporting opened for {}", year);
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use crate::errors::ErrorCode;
+use crate::state::MerchantStatement;
+use crate::MerchantStatementOpened;
+
+/// Open a merchant's statement for a calendar month in a mint (permissionless)
+///
+/// Cranks open the coming month's statement before it starts; payments
+/// only accrue into statements that exist, and every payment and invoice
+/// in the month must pass it.
+#[derive(Accounts)]
+#[instruction(year: u16, month: u8)]
+pub struct OpenMerchantStatement<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = MerchantStatement::LEN,
+        seeds = [
+            b"merchant_statement",
+            merchant.key().as_ref(),
+            mint.key().as_ref(),
+            &year.to_le_bytes(),
+            &[month],
+        ],
+        bump
+    )]
+    pub merchant_statement: Account<'info, MerchantStatement>,
+
+    /// Merchant account from merchant registry
+    #[account(
+        seeds = [b"merchant", merchant.owner.as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<OpenMerchantStatement>, year: u16, month: u8) -> Result<()> {
+    require!((1..=12).contains(&month), ErrorCode::InvalidStatementPeriod);
+
+    let statement = &mut ctx.accounts.merchant_statement;
+    statement.merchant = ctx.accounts.merchant.key();
+    statement.mint = ctx.accounts.mint.key();
+    statement.year = year;
+    statement.month = month;
+    statement.bump = ctx.bumps.merchant_statement;
+
+    emit!(MerchantStatementOpened {
+        schema_version: EVENT_SCHEMA_VERSION,
+        merchant: statement.merchant,
+        mint: statement.mint,
+        year,
+        month,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Merchant statement opened for {}-{:02}", year, month);
+    Ok(())
+}
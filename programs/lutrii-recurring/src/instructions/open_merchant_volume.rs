@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use crate::state::MerchantVolume;
+use crate::MerchantVolumeOpened;
+
+/// Start tracking a merchant's monthly volume in a mint (permissionless)
+///
+/// Usually called by the merchant ahead of the first period they want a
+/// rebate for; payments only count once the account exists, and from then
+/// on every execution must pass it.
+#[derive(Accounts)]
+pub struct OpenMerchantVolume<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = MerchantVolume::LEN,
+        seeds = [b"merchant_volume", merchant.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub merchant_volume: Account<'info, MerchantVolume>,
+
+    /// Merchant account from merchant registry
+    #[account(
+        seeds = [b"merchant", merchant.owner.as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<OpenMerchantVolume>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    let merchant_volume = &mut ctx.accounts.merchant_volume;
+    merchant_volume.open(
+        ctx.accounts.merchant.key(),
+        ctx.accounts.mint.key(),
+        now,
+        ctx.bumps.merchant_volume,
+    );
+
+    emit!(MerchantVolumeOpened {
+        schema_version: EVENT_SCHEMA_VERSION,
+        merchant: merchant_volume.merchant,
+        mint: merchant_volume.mint,
+        period: merchant_volume.period,
+        timestamp: now,
+    });
+
+    msg!("Merchant volume tracking opened for period {}", merchant_volume.period);
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use crate::state::{RevenueForecast, FORECAST_RING_DAYS};
+use crate::RevenueForecastOpened;
+
+/// Start a merchant's revenue forecast in a mint (permissionless)
+///
+/// Subscriptions enter the forecast as they are next created, paid or
+/// rescheduled with it passed along.
+#[derive(Accounts)]
+pub struct OpenRevenueForecast<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = RevenueForecast::LEN,
+        seeds = [b"revenue_forecast", merchant.key().as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub revenue_forecast: Box<Account<'info, RevenueForecast>>,
+
+    /// Merchant account from merchant registry
+    #[account(
+        seeds = [b"merchant", merchant.owner.as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<OpenRevenueForecast>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let forecast = &mut ctx.accounts.revenue_forecast;
+    forecast.merchant = ctx.accounts.merchant.key();
+    forecast.mint = ctx.accounts.mint.key();
+    forecast.buckets = vec![0; FORECAST_RING_DAYS];
+    forecast.bump = ctx.bumps.revenue_forecast;
+    forecast.refresh(now);
+
+    emit!(RevenueForecastOpened {
+        schema_version: EVENT_SCHEMA_VERSION,
+        merchant: forecast.merchant,
+        mint: forecast.mint,
+        timestamp: now,
+    });
+
+    msg!("Revenue forecast opened");
+    Ok(())
+}
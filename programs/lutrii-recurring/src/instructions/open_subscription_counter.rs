@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::state::SubscriptionCounter;
+
+/// Open the nonce allocator for a pair subscribed before it existed (user only)
+///
+/// A pair's first `create_subscription` normally creates the counter at
+/// nonce 0, which is the address subscriptions had before nonces. Pairs
+/// whose subscription already sits there open the counter here instead,
+/// starting at nonce 1, so their next subscription does not collide with it.
+///
+/// # Security
+/// - `subscription` must be a program-owned account at the pair's nonce-0
+///   address; it may be on any layout
+#[derive(Accounts)]
+pub struct OpenSubscriptionCounter<'info> {
+    #[account(
+        init,
+        payer = user,
+        space = SubscriptionCounter::LEN,
+        seeds = [b"subscription_counter", user.key().as_ref(), merchant.key().as_ref()],
+        bump
+    )]
+    pub subscription_counter: Account<'info, SubscriptionCounter>,
+
+    /// CHECK: The pair's existing nonce-0 subscription; only its address
+    /// and owner are checked, so older layouts qualify
+    #[account(
+        seeds = [b"subscription", user.key().as_ref(), merchant.key().as_ref()],
+        bump,
+        owner = crate::ID @ ErrorCode::InvalidSubscriptionAccount,
+        constraint = !subscription.data_is_empty() @ ErrorCode::InvalidSubscriptionAccount
+    )]
+    pub subscription: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant PDA (lutrii-merchant-registry); only used as a seed
+    pub merchant: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<OpenSubscriptionCounter>) -> Result<()> {
+    let counter = &mut ctx.accounts.subscription_counter;
+    counter.user = ctx.accounts.user.key();
+    counter.merchant = ctx.accounts.merchant.key();
+    counter.next_nonce = 1;
+    counter.bump = ctx.bumps.subscription_counter;
+
+    msg!("Subscription counter opened at nonce {}", counter.next_nonce);
+    Ok(())
+}
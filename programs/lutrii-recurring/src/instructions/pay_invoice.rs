@@ -0,0 +1,249 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::transfer_checked;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+use lutrii_core::fee;
+use lutrii_merchant_registry::{program::LutriiMerchantRegistry, Merchant as MerchantAccount};
+use crate::cpi::record_merchant_payment;
+use lutrii_common::{EVENT_SCHEMA_VERSION, Subscription};
+use crate::errors::ErrorCode;
+use crate::state::{FeeTreatment, Invoice, InvoiceStatus, MerchantPolicy, MerchantReport, MerchantStatement, RefundLiability};
+use crate::{InvoicePaid, PlatformState};
+
+/// Pay an open invoice directly from the user's wallet (user only)
+///
+/// The platform fee is deducted exactly as for subscription payments,
+/// including fee exemptions and promotional fee holidays. The
+/// first-subscription waiver covers scheduled payments only. The payment
+/// counts toward the merchant's registry stats.
+///
+/// # Security
+/// - Only the billed user can pay (signer must match `invoice.user`)
+/// - Settlement account and mint are pinned by the invoice
+/// - Fees only go to a configured platform fee wallet
+/// - A linked invoice must be paid with its subscription, whose fee
+///   exemption applies
+#[derive(Accounts)]
+pub struct PayInvoice<'info> {
+    #[account(
+        mut,
+        has_one = user @ ErrorCode::UnauthorizedUser,
+        has_one = merchant_token_account @ ErrorCode::InvalidTokenAccount,
+        has_one = mint @ ErrorCode::InvalidMint
+    )]
+    pub invoice: Box<Account<'info, Invoice>>,
+
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform_state.bump
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// Subscription the invoice is linked to; required for linked invoices
+    #[account(constraint = subscription.key() == invoice.subscription @ ErrorCode::InvoiceNotLinked)]
+    pub subscription: Option<Box<Account<'info, Subscription>>>,
+
+    pub user: Signer<'info>,
+
+    /// Merchant registry account; has the payment recorded in its stats
+    #[account(
+        mut,
+        constraint = merchant.key() == invoice.merchant @ ErrorCode::InvalidMerchantAccount
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::InvalidTokenAccountOwner,
+        constraint = user_token_account.mint == mint.key() @ ErrorCode::InvalidMint
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub merchant_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = platform_state.is_fee_wallet(&platform_fee_account.key()) @ ErrorCode::InvalidFeeWallet,
+        constraint = platform_fee_account.mint == mint.key() @ ErrorCode::InvalidFeeWalletMint
+    )]
+    pub platform_fee_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: Merchant policy PDA, for a negotiated fee schedule and fee
+    /// exemption; may be uninitialized. Required so the payer cannot choose
+    /// the fee schedule
+    #[account(
+        seeds = [b"merchant_policy", invoice.merchant.as_ref()],
+        bump
+    )]
+    pub merchant_policy: UncheckedAccount<'info>,
+
+    /// CHECK: Platform's promotional fee windows PDA; may be uninitialized
+    #[account(seeds = [b"fee_holidays"], bump)]
+    pub fee_holidays: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant's reporting totals PDA for the current year; may be
+    /// uninitialized
+    #[account(
+        mut,
+        seeds = [
+            b"merchant_report",
+            invoice.merchant.as_ref(),
+            mint.key().as_ref(),
+            &MerchantReport::year_seed(Clock::get()?.unix_timestamp),
+        ],
+        bump
+    )]
+    pub merchant_report: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant's statement PDA for the current month; may be
+    /// uninitialized
+    #[account(
+        mut,
+        seeds = [
+            b"merchant_statement",
+            invoice.merchant.as_ref(),
+            mint.key().as_ref(),
+            &MerchantReport::year_seed(Clock::get()?.unix_timestamp),
+            &MerchantStatement::month_seed(Clock::get()?.unix_timestamp),
+        ],
+        bump
+    )]
+    pub merchant_statement: UncheckedAccount<'info>,
+    /// CHECK: Merchant's refund exposure PDA; may be uninitialized
+    #[account(
+        mut,
+        seeds = [b"refund_liability", invoice.merchant.as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub refund_liability: UncheckedAccount<'info>,
+
+    pub merchant_registry_program: Program<'info, LutriiMerchantRegistry>,
+
+    /// CHECK: Instructions sysvar, read by the registry to check it runs under a CPI
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<PayInvoice>) -> Result<()> {
+    let platform = &mut ctx.accounts.platform_state;
+    let invoice = &mut ctx.accounts.invoice;
+    let clock = Clock::get()?;
+
+    // ============================================================================
+    // CHECKS
+    // ============================================================================
+
+    require!(!platform.is_paused(clock.unix_timestamp), ErrorCode::SystemPaused);
+    require!(
+        !platform.token_program_paused(&ctx.accounts.token_program.key()),
+        ErrorCode::TokenProgramPaused
+    );
+    require!(
+        invoice.status == InvoiceStatus::Open,
+        ErrorCode::InvoiceNotOpen
+    );
+
+    let subscription_exempt = match ctx.accounts.subscription.as_ref() {
+        Some(subscription) => subscription.fee_exempt,
+        None => {
+            require!(
+                invoice.subscription == Pubkey::default(),
+                ErrorCode::InvoiceSubscriptionRequired
+            );
+            false
+        }
+    };
+    let fee_exempt = subscription_exempt || MerchantPolicy::fee_exempt_of(&ctx.accounts.merchant_policy)?;
+    let fee_treatment = FeeTreatment::for_charge(
+        invoice.total,
+        fee_exempt,
+        &ctx.accounts.fee_holidays,
+        false,
+        &ctx.accounts.mint.key(),
+        clock.unix_timestamp,
+    )?;
+    let custom_fee = MerchantPolicy::fee_schedule_of(&ctx.accounts.merchant_policy)?;
+    let (fee, waived_fee) = match platform.platform_fee(invoice.total, custom_fee)? {
+        waived if fee_treatment.waives_fee() => (0, waived),
+        fee => (fee, 0),
+    };
+    let merchant_amount = fee::merchant_amount(invoice.total, fee).map_err(ErrorCode::from)?;
+
+    // ============================================================================
+    // EFFECTS
+    // ============================================================================
+
+    invoice.status = InvoiceStatus::Paid;
+    invoice.paid_at = clock.unix_timestamp;
+    if fee_treatment == FeeTreatment::Exempt {
+        platform.record_fee_exemption(waived_fee);
+    }
+
+    let now = clock.unix_timestamp;
+    MerchantReport::update_in(&ctx.accounts.merchant_report, |report| report.record(invoice.total, fee, now))?;
+    MerchantStatement::update_in(&ctx.accounts.merchant_statement, |statement| {
+        statement.record(invoice.total, fee, merchant_amount, now)
+    })?;
+    RefundLiability::update_in(&ctx.accounts.refund_liability, |liability| liability.record(merchant_amount, now))?;
+
+    // ============================================================================
+    // INTERACTIONS
+    // ============================================================================
+
+    transfer_checked(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.user_token_account.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.merchant_token_account.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        ),
+        merchant_amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    if fee > 0 {
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.platform_fee_account.to_account_info(),
+                    authority: ctx.accounts.user.to_account_info(),
+                },
+            ),
+            fee,
+            ctx.accounts.mint.decimals,
+        )?;
+    }
+
+    // Merchant stats and automatic tier upgrades in the registry
+    record_merchant_payment(
+        &ctx.accounts.merchant_registry_program.to_account_info(),
+        &ctx.accounts.merchant.to_account_info(),
+        &ctx.accounts.platform_state,
+        &ctx.accounts.instructions,
+        invoice.total,
+    )?;
+
+    emit!(InvoicePaid {
+        schema_version: EVENT_SCHEMA_VERSION,
+        invoice: invoice.key(),
+        merchant: invoice.merchant,
+        user: invoice.user,
+        total: invoice.total,
+        fee,
+        via_delegation: false,
+        timestamp: clock.unix_timestamp,
+    });
+
+    msg!("✅ Invoice {} paid: {}", invoice.invoice_id, invoice.total);
+    Ok(())
+}
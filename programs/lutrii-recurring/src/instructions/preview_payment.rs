@@ -0,0 +1,208 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token_interface::TokenAccount;
+use lutrii_common::Subscription;
+use lutrii_core::{limits, proration, schedule, variance, SECONDS_PER_DAY};
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use crate::errors::ErrorCode;
+use crate::state::{FeeTreatment, MerchantPolicy, UserDelegate, UserStats};
+use crate::PlatformState;
+
+/// Breakdown of the next payment returned by `preview_payment`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PaymentPreview {
+    /// Subscription amount before adjustments
+    pub base_amount: u64,
+    /// Pending proration added to this payment
+    pub surcharge: u64,
+    /// Pending proration credit applied to this payment
+    pub discount: u64,
+    /// Tax collected on this payment (the program does not collect tax)
+    pub tax: u64,
+    /// Total taken from the user's token account, including a fee the user bears
+    pub charge: u64,
+    /// Platform fee, out of the charge or on top of it per the fee bearer
+    pub platform_fee: u64,
+    /// Reward paid out of the fee to an executor passing its token account
+    pub keeper_reward: u64,
+    /// Amount the merchant receives
+    pub merchant_amount: u64,
+    /// Credit left over for the following payment
+    pub carried_proration: i64,
+
+    // Guards, in the order execute_payment evaluates them
+    pub system_active: bool,
+    pub subscription_active: bool,
+    pub not_paused: bool,
+    pub is_due: bool,
+    pub retry_ready: bool,
+    pub within_transaction_cap: bool,
+    pub within_lifetime_cap: bool,
+    pub within_velocity_limit: bool,
+    pub within_price_variance: bool,
+    pub delegation_covers: bool,
+    pub balance_covers: bool,
+
+    /// Whether executing now would move funds (every guard passes)
+    pub will_collect: bool,
+}
+
+/// Read-only simulation of the next `execute_payment`
+///
+/// Returns a `PaymentPreview` through Anchor return data so wallets can
+/// show the exact upcoming charge via transaction simulation.
+#[derive(Accounts)]
+pub struct PreviewPayment<'info> {
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(seeds = [b"platform"], bump = platform_state.bump)]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        constraint = user_token_account.key() == subscription.user_token_account @ ErrorCode::InvalidTokenAccount
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Shared delegate for the user's token account
+    #[account(
+        seeds = [b"user_delegate", subscription.user_token_account.as_ref()],
+        bump = user_delegate.bump
+    )]
+    pub user_delegate: Account<'info, UserDelegate>,
+
+    /// Merchant registry account; places the payment in its velocity class
+    #[account(
+        constraint = merchant.key() == subscription.merchant @ ErrorCode::InvalidMerchantAccount
+    )]
+    pub merchant: Option<Box<Account<'info, MerchantAccount>>>,
+
+    /// CHECK: Merchant policy PDA, for the merchant's fee exemption and
+    /// negotiated fee schedule; may be uninitialized
+    #[account(
+        seeds = [b"merchant_policy", subscription.merchant.as_ref()],
+        bump
+    )]
+    pub merchant_policy: UncheckedAccount<'info>,
+
+    /// CHECK: Platform's promotional fee windows PDA; may be uninitialized
+    #[account(seeds = [b"fee_holidays"], bump)]
+    pub fee_holidays: UncheckedAccount<'info>,
+
+    /// CHECK: User's spending analytics PDA, for the first-subscription fee
+    /// waiver; may be uninitialized
+    #[account(
+        seeds = [b"user_stats", subscription.user.as_ref()],
+        bump
+    )]
+    pub user_stats: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<PreviewPayment>) -> Result<PaymentPreview> {
+    let subscription = &ctx.accounts.subscription;
+    let platform = &ctx.accounts.platform_state;
+    let user_token_account = &ctx.accounts.user_token_account;
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+    let schedule_now = subscription.schedule_unit.now(&clock);
+
+    let (charge, carried_proration) =
+        proration::apply_adjustment(subscription.amount, subscription.pending_proration)
+            .map_err(ErrorCode::from)?;
+    let charge = subscription.discounted(charge);
+    let (surcharge, discount) = if charge >= subscription.amount {
+        (charge - subscription.amount, 0)
+    } else {
+        (0, subscription.amount - charge)
+    };
+
+    let first_subscription = UserStats::first_subscription_fee_waived_in(
+        &ctx.accounts.user_stats,
+        &subscription.key(),
+        subscription.payment_count,
+        platform.first_subscription_free_payments,
+    )?;
+    let fee_treatment = FeeTreatment::of(
+        subscription,
+        charge,
+        &ctx.accounts.merchant_policy,
+        &ctx.accounts.fee_holidays,
+        first_subscription,
+        &subscription.mint,
+        now,
+    )?;
+    let custom_fee = MerchantPolicy::fee_schedule_of(&ctx.accounts.merchant_policy)?;
+    let platform_fee = if fee_treatment.waives_fee() {
+        0
+    } else {
+        platform.recurring_fee(charge, subscription.fee_dust, custom_fee)?.0
+    };
+    let fee_bearer = subscription.fee_bearer;
+    let outflow = fee_bearer
+        .user_outflow(charge, platform_fee)
+        .ok_or(ErrorCode::Overflow)?;
+    let merchant_amount = fee_bearer
+        .merchant_amount(charge, platform_fee)
+        .ok_or(ErrorCode::InsufficientAmount)?;
+
+    // Volume resets at the start of the execution once the window has passed
+    let volume_24h = if now >= platform.last_volume_reset + SECONDS_PER_DAY {
+        0
+    } else {
+        platform.total_volume_24h
+    };
+    let within_velocity_limit = volume_24h
+        .checked_add(charge)
+        .is_some_and(|volume| volume <= platform.volume_limit(ctx.accounts.merchant.as_ref().map(|m| &m.verification_tier)));
+
+    let within_price_variance = subscription.payment_count == 0
+        || variance::within_variance(
+            platform.payment_outflow(fee_bearer, subscription.amount, custom_fee)?,
+            platform.payment_outflow(fee_bearer, subscription.original_amount, custom_fee)?,
+            variance::MAX_PRICE_VARIANCE_BPS,
+        );
+
+    let delegation_covers = user_token_account.delegate
+        == COption::Some(ctx.accounts.user_delegate.key())
+        && user_token_account.delegated_amount >= outflow;
+
+    let mut preview = PaymentPreview {
+        base_amount: subscription.amount,
+        surcharge,
+        discount,
+        tax: 0,
+        charge: outflow,
+        platform_fee,
+        keeper_reward: platform.executor_reward(charge, platform_fee),
+        merchant_amount,
+        carried_proration,
+        system_active: !platform.is_paused(now),
+        subscription_active: subscription.is_active,
+        not_paused: !subscription.is_paused,
+        is_due: schedule::is_due(schedule_now, subscription.next_payment),
+        retry_ready: schedule_now >= subscription.next_retry_at,
+        within_transaction_cap: outflow <= subscription.max_per_transaction,
+        within_lifetime_cap: limits::within_lifetime_cap(
+            subscription.total_paid,
+            outflow,
+            subscription.lifetime_cap,
+        ),
+        within_velocity_limit,
+        within_price_variance,
+        delegation_covers: outflow == 0 || delegation_covers,
+        balance_covers: user_token_account.amount >= outflow,
+        will_collect: false,
+    };
+    preview.will_collect = preview.system_active
+        && preview.subscription_active
+        && preview.not_paused
+        && preview.is_due
+        && preview.retry_ready
+        && preview.within_transaction_cap
+        && preview.within_lifetime_cap
+        && preview.within_velocity_limit
+        && preview.within_price_variance
+        && preview.delegation_covers
+        && preview.balance_covers;
+
+    Ok(preview)
+}
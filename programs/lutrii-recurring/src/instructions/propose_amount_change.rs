@@ -0,0 +1,96 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use lutrii_common::{EVENT_SCHEMA_VERSION, Subscription};
+use crate::errors::ErrorCode;
+use crate::state::AcceptedMint;
+use crate::{AmountChangeProposed, AMOUNT_CHANGE_WINDOW_SECONDS};
+
+/// Propose a new amount for one of a merchant's subscriptions (merchant owner only)
+///
+/// Payments keep the current amount until the user accepts with
+/// `accept_amount_change`; a proposal not accepted within
+/// `AMOUNT_CHANGE_WINDOW_SECONDS` lapses. Proposing again replaces the
+/// pending proposal and restarts the window. Plan-bound subscriptions
+/// follow their plan's price instead and are refused.
+///
+/// # Arguments
+/// * `new_amount` - Amount to charge per payment once accepted
+///
+/// # Security
+/// - Merchant PDA is derived from the signing owner and must be the
+///   subscription's merchant
+/// - New amount must meet the minimum of the mint the subscription is
+///   billed in; the user's per-transaction cap is checked on acceptance
+#[derive(Accounts)]
+pub struct ProposeAmountChange<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        bump = subscription.bump,
+        constraint = merchant.key() == subscription.merchant @ ErrorCode::InvalidMerchantAccount
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(
+        seeds = [b"merchant", owner.key().as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    /// Merchant's token account; its mint is the one the amount is billed in
+    #[account(
+        constraint = merchant_token_account.key() == subscription.merchant_token_account @ ErrorCode::InvalidTokenAccount
+    )]
+    pub merchant_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Registry entry for the billing mint
+    #[account(
+        seeds = [b"accepted_mint", merchant_token_account.mint.as_ref()],
+        bump = accepted_mint.bump
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+
+    pub owner: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<ProposeAmountChange>, new_amount: u64) -> Result<()> {
+    let subscription = &mut ctx.accounts.subscription;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(subscription.is_active, ErrorCode::SubscriptionInactive);
+    require!(subscription.plan == Pubkey::default(), ErrorCode::PlanBoundAmount);
+    require!(
+        new_amount > 0 && new_amount != subscription.amount,
+        ErrorCode::InvalidAmountChange
+    );
+    require!(
+        new_amount >= ctx.accounts.accepted_mint.min_amount,
+        ErrorCode::BelowMintMinimum
+    );
+
+    subscription.pending_amount = new_amount;
+    subscription.pending_amount_expires = now
+        .checked_add(AMOUNT_CHANGE_WINDOW_SECONDS)
+        .ok_or(ErrorCode::Overflow)?;
+
+    emit!(AmountChangeProposed {
+        schema_version: EVENT_SCHEMA_VERSION,
+        subscription: subscription.key(),
+        user: subscription.user,
+        merchant: subscription.merchant,
+        old_amount: subscription.amount,
+        new_amount,
+        expires_at: subscription.pending_amount_expires,
+        timestamp: now,
+    });
+
+    msg!("Amount change proposed: {} -> {}", subscription.amount, new_amount);
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::{AdminCouncil, CouncilAction, CouncilProposal, COUNCIL_PROPOSAL_TTL_SECONDS};
+use crate::{CouncilActionProposed, MAX_PAUSE_REASON_LEN};
+
+/// Propose a council-governed platform change (council member only)
+///
+/// The proposer's approval is counted. The proposal expires after
+/// `COUNCIL_PROPOSAL_TTL_SECONDS`.
+///
+/// # Arguments
+/// * `action` - Change to make once `threshold` members approve
+#[derive(Accounts)]
+pub struct ProposeCouncilAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"admin_council"],
+        bump = admin_council.bump
+    )]
+    pub admin_council: Account<'info, AdminCouncil>,
+
+    #[account(
+        init,
+        payer = member,
+        space = CouncilProposal::LEN,
+        seeds = [b"council_proposal", admin_council.proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, CouncilProposal>,
+
+    #[account(mut)]
+    pub member: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<ProposeCouncilAction>, action: CouncilAction) -> Result<()> {
+    let council = &mut ctx.accounts.admin_council;
+    let index = council
+        .member_index(&ctx.accounts.member.key())
+        .ok_or(ErrorCode::NotCouncilMember)?;
+    if let CouncilAction::EmergencyPause { reason } = &action {
+        require!(
+            !reason.trim().is_empty() && reason.len() <= MAX_PAUSE_REASON_LEN,
+            ErrorCode::InvalidPauseReason
+        );
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let proposal_id = council.proposal_count;
+    council.proposal_count = proposal_id.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.proposal_id = proposal_id;
+    proposal.proposer = ctx.accounts.member.key();
+    proposal.action = action.clone();
+    proposal.approvals = 0;
+    proposal.approve(index);
+    proposal.created_at = now;
+    proposal.expires_at = now
+        .checked_add(COUNCIL_PROPOSAL_TTL_SECONDS)
+        .ok_or(ErrorCode::Overflow)?;
+    proposal.executed = false;
+    proposal.bump = ctx.bumps.proposal;
+
+    emit!(CouncilActionProposed {
+        schema_version: EVENT_SCHEMA_VERSION,
+        proposal: proposal.key(),
+        proposal_id,
+        proposer: proposal.proposer,
+        action,
+        expires_at: proposal.expires_at,
+        timestamp: now,
+    });
+
+    msg!("Council proposal {} created", proposal_id);
+    Ok(())
+}
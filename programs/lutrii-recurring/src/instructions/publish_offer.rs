@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::{OfferTerms, SubscriptionOffer};
+use crate::OfferPublished;
+
+/// Publish an offer any wallet can accept, with limited inventory (merchant owner only)
+///
+/// E.g. "first 100 subscribers at $5/mo". Each `accept_offer` takes one unit;
+/// the offer closes when the inventory runs out, and anyone can close it
+/// once expired with `close_expired_offer`.
+///
+/// # Arguments
+/// * `offer_id` - Merchant-chosen identifier, unique per merchant
+/// * `terms` - Amount, frequency, caps and display name
+/// * `expires_at` - Unix timestamp the offer lapses at
+/// * `max_acceptances` - Subscriptions the offer can create
+#[derive(Accounts)]
+#[instruction(offer_id: u64)]
+pub struct PublishOffer<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = SubscriptionOffer::LEN,
+        seeds = [b"offer", merchant.key().as_ref(), offer_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub offer: Account<'info, SubscriptionOffer>,
+
+    #[account(
+        seeds = [b"merchant", owner.key().as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    #[account(
+        constraint = merchant_token_account.owner == owner.key() @ ErrorCode::InvalidTokenAccountOwner
+    )]
+    pub merchant_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<PublishOffer>,
+    offer_id: u64,
+    terms: OfferTerms,
+    expires_at: i64,
+    max_acceptances: u32,
+) -> Result<()> {
+    terms.validate()?;
+    require!(max_acceptances > 0, ErrorCode::InvalidOfferInventory);
+    let now = Clock::get()?.unix_timestamp;
+    require!(expires_at > now, ErrorCode::InvalidOfferExpiry);
+
+    let offer = &mut ctx.accounts.offer;
+    offer.merchant = ctx.accounts.merchant.key();
+    offer.user = Pubkey::default();
+    offer.merchant_token_account = ctx.accounts.merchant_token_account.key();
+    offer.terms = terms;
+    offer.expires_at = expires_at;
+    offer.created_at = now;
+    offer.bump = ctx.bumps.offer;
+    offer.offer_id = offer_id;
+    offer.max_acceptances = max_acceptances;
+    offer.acceptances = 0;
+
+    emit!(OfferPublished {
+        schema_version: EVENT_SCHEMA_VERSION,
+        offer: offer.key(),
+        merchant: offer.merchant,
+        offer_id,
+        amount: offer.terms.amount,
+        frequency_seconds: offer.terms.frequency_seconds,
+        max_acceptances,
+        expires_at,
+    });
+
+    msg!("Offer {} published: {} available", offer_id, max_acceptances);
+    Ok(())
+}
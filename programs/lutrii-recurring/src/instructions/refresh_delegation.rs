@@ -0,0 +1,141 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use lutrii_common::{EVENT_SCHEMA_VERSION, Subscription};
+use crate::errors::ErrorCode;
+use crate::state::{SwapAsset, UserDelegate};
+use crate::DelegationRefreshed;
+
+/// Re-approve the shared user delegate for the remaining lifetime allowance
+///
+/// Restores a delegation that was spent down or replaced by another dApp's
+/// approval without touching the subscription's safety caps. The approval
+/// covers every active subscription on the token account, so one refresh
+/// repairs all of them.
+///
+/// Token accounts paying through swaps are re-priced at the current oracle
+/// rate, which restores an approval eroded by a falling asset price.
+///
+/// # Security
+/// - Only the subscription owner can re-approve (has_one + signer)
+/// - Approval never exceeds the sum of `lifetime_cap - total_paid` across
+///   the token account's active subscriptions
+#[derive(Accounts)]
+pub struct RefreshDelegation<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        bump = subscription.bump,
+        has_one = user @ ErrorCode::UnauthorizedUser
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.key() == subscription.user_token_account @ ErrorCode::InvalidTokenAccount
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user_delegate", subscription.user_token_account.as_ref()],
+        bump = user_delegate.bump
+    )]
+    pub user_delegate: Account<'info, UserDelegate>,
+
+    pub user: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Swap asset config for the user's mint; required when paying through swaps
+    #[account(
+        seeds = [b"swap_asset", user_token_account.mint.as_ref()],
+        bump = swap_asset.bump
+    )]
+    pub swap_asset: Option<Box<Account<'info, SwapAsset>>>,
+
+    /// CHECK: Must match `swap_asset.price_feed`; parsed as a Pyth price account
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    /// User's mint; required with `swap_asset`
+    #[account(constraint = mint.key() == user_token_account.mint @ ErrorCode::InvalidMint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Merchant's settlement mint; required with `swap_asset`
+    pub settlement_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        constraint = merchant_token_account.key() == subscription.merchant_token_account @ ErrorCode::InvalidTokenAccount
+    )]
+    pub merchant_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+}
+
+pub fn handler(ctx: Context<RefreshDelegation>) -> Result<()> {
+    let user_delegate = &mut ctx.accounts.user_delegate;
+    let subscription = &mut ctx.accounts.subscription;
+    require!(subscription.is_active, ErrorCode::SubscriptionInactive);
+    require!(!subscription.delegation_deferred, ErrorCode::TrialNotConverted);
+    require!(
+        user_delegate.outstanding_allowance > 0,
+        ErrorCode::LifetimeCapExhausted
+    );
+
+    if user_delegate.approval_rate != 0 {
+        let (
+            Some(swap_asset),
+            Some(price_feed),
+            Some(mint),
+            Some(settlement_mint),
+            Some(merchant_token_account),
+        ) = (
+            ctx.accounts.swap_asset.as_ref(),
+            ctx.accounts.price_feed.as_ref(),
+            ctx.accounts.mint.as_ref(),
+            ctx.accounts.settlement_mint.as_ref(),
+            ctx.accounts.merchant_token_account.as_ref(),
+        )
+        else {
+            return err!(ErrorCode::InvalidPriceFeed);
+        };
+        require!(swap_asset.enabled, ErrorCode::SwapAssetDisabled);
+        require_keys_eq!(
+            settlement_mint.key(),
+            merchant_token_account.mint,
+            ErrorCode::InvalidMint
+        );
+        let price = swap_asset.price(price_feed, Clock::get()?.unix_timestamp)?;
+        user_delegate.approval_rate = swap_asset.max_input(
+            &price,
+            UserDelegate::RATE_SCALE,
+            mint.decimals,
+            settlement_mint.decimals,
+            swap_asset.max_slippage_bps,
+        )?;
+    }
+
+    user_delegate.sync_approval(
+        user_delegate.to_account_info(),
+        ctx.accounts.user_token_account.to_account_info(),
+        ctx.accounts.user.to_account_info(),
+        ctx.accounts.token_program.to_account_info(),
+    )?;
+
+    subscription.delegation_healthy = user_delegate.outstanding_allowance >= subscription.amount;
+
+    emit!(DelegationRefreshed {
+        schema_version: EVENT_SCHEMA_VERSION,
+        subscription: subscription.key(),
+        user: subscription.user,
+        delegated_amount: user_delegate.approval_amount()?,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Delegation refreshed: {} approved",
+        user_delegate.outstanding_allowance
+    );
+    Ok(())
+}
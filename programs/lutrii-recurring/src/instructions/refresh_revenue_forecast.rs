@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+use crate::state::RevenueForecast;
+
+/// Bring a revenue forecast's projection up to date (permissionless)
+///
+/// Payments move in and out of the window as days pass; this recomputes
+/// `projected_revenue` for readers that need it current.
+#[derive(Accounts)]
+pub struct RefreshRevenueForecast<'info> {
+    #[account(
+        mut,
+        seeds = [b"revenue_forecast", revenue_forecast.merchant.as_ref(), revenue_forecast.mint.as_ref()],
+        bump = revenue_forecast.bump
+    )]
+    pub revenue_forecast: Box<Account<'info, RevenueForecast>>,
+}
+
+pub fn handler(ctx: Context<RefreshRevenueForecast>) -> Result<()> {
+    let forecast = &mut ctx.accounts.revenue_forecast;
+    forecast.refresh(Clock::get()?.unix_timestamp);
+
+    msg!("Projected 30-day revenue: {}", forecast.projected_revenue);
+    Ok(())
+}
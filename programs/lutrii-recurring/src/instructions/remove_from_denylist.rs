@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::state::DenylistEntry;
+use crate::errors::ErrorCode;
+use crate::{PlatformState, WalletDenylisted};
+
+/// Remove a wallet from the compliance denylist (admin only)
+///
+/// Closes the denylist entry and returns its rent to the authority.
+#[derive(Accounts)]
+pub struct RemoveFromDenylist<'info> {
+    #[account(
+        mut,
+        seeds = [b"denylist", denylist_entry.wallet.as_ref()],
+        bump = denylist_entry.bump,
+        close = authority
+    )]
+    pub denylist_entry: Account<'info, DenylistEntry>,
+
+    #[account(
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<RemoveFromDenylist>) -> Result<()> {
+    let wallet = ctx.accounts.denylist_entry.wallet;
+
+    emit!(WalletDenylisted {
+        schema_version: EVENT_SCHEMA_VERSION,
+        wallet,
+        denied: false,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✅ Wallet removed from denylist: {}", wallet);
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::TokenAccount;
+use lutrii_common::{AdminAuditAction, EVENT_SCHEMA_VERSION};
+use crate::errors::ErrorCode;
+use crate::state::{record_admin_action, AdminAuditLog, ConfigChange, PendingConfigChange};
+use crate::{ConfigChangeScheduled, PlatformState};
+
+/// Schedule a platform fee, volume limit or fee wallet change (admin only)
+///
+/// The change takes effect through `execute_config_change` once the
+/// platform's config change delay has passed, giving subscribers time to
+/// react. Scheduling again replaces a change still pending. Once an admin
+/// council exists the change needs its approval instead.
+///
+/// # Arguments
+/// * `change` - Parameters to set; the fee wallets come from the accounts
+#[derive(Accounts)]
+pub struct ScheduleConfigChange<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = PendingConfigChange::LEN,
+        seeds = [b"pending_config_change"],
+        bump
+    )]
+    pub pending_config_change: Account<'info, PendingConfigChange>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// New USDC fee wallet (optional)
+    #[account(
+        constraint = new_fee_wallet_usdc.mint == platform_state.usdc_mint @ ErrorCode::InvalidFeeWalletMint
+    )]
+    pub new_fee_wallet_usdc: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// New USD1 fee wallet (optional)
+    #[account(
+        constraint = new_fee_wallet_usd1.mint == platform_state.usd1_mint @ ErrorCode::InvalidFeeWalletMint
+    )]
+    pub new_fee_wallet_usd1: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Admin audit log, appended to when passed
+    #[account(
+        mut,
+        seeds = [b"admin_audit_log"],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Option<Box<Account<'info, AdminAuditLog>>>,
+}
+
+pub fn handler(ctx: Context<ScheduleConfigChange>, change: ConfigChange) -> Result<()> {
+    require!(
+        !ctx.accounts.platform_state.council_enabled,
+        ErrorCode::CouncilApprovalRequired
+    );
+    let change = ConfigChange {
+        fee_wallet_usdc: ctx.accounts.new_fee_wallet_usdc.as_ref().map(|wallet| wallet.key()),
+        fee_wallet_usd1: ctx.accounts.new_fee_wallet_usd1.as_ref().map(|wallet| wallet.key()),
+        ..change
+    };
+    ctx.accounts.pending_config_change.bump = ctx.bumps.pending_config_change;
+    schedule(&mut ctx.accounts.pending_config_change, &ctx.accounts.platform_state, change)?;
+
+    let actor = ctx.accounts.authority.key();
+    record_admin_action(ctx.accounts.audit_log.as_mut(), actor, AdminAuditAction::ConfigUpdate)
+}
+
+/// Write `change` into the pending slot, executable once `platform`'s
+/// config change delay has passed
+///
+/// Shared with council proposals that schedule a change.
+pub(crate) fn schedule(
+    pending: &mut PendingConfigChange,
+    platform: &PlatformState,
+    change: ConfigChange,
+) -> Result<()> {
+    change.validate(platform)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let executable_at = now
+        .checked_add(platform.config_change_delay())
+        .ok_or(ErrorCode::Overflow)?;
+
+    pending.change = change;
+    pending.pending = true;
+    pending.scheduled_at = now;
+    pending.executable_at = executable_at;
+
+    emit!(ConfigChangeScheduled {
+        schema_version: EVENT_SCHEMA_VERSION,
+        change,
+        executable_at,
+        timestamp: now,
+    });
+
+    msg!("Config change scheduled, executable at {}", executable_at);
+    Ok(())
+}
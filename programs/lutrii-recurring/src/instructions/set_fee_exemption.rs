@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+use lutrii_common::{Subscription, EVENT_SCHEMA_VERSION};
+use crate::errors::ErrorCode;
+use crate::state::MerchantPolicy;
+use crate::{FeeExemptionUpdated, PlatformState};
+
+/// Mark a subscription or a merchant fee-exempt, or clear it (admin only)
+///
+/// For strategic partners and internal billing. Exempt payments skip the
+/// platform fee transfer and are counted in `PlatformState::fee_exempt_payments`.
+/// Pass exactly one of `subscription` and `merchant_policy`. Refused once
+/// an admin council exists.
+///
+/// # Arguments
+/// * `fee_exempt` - Whether payments skip the platform fee
+#[derive(Accounts)]
+pub struct SetFeeExemption<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        bump = subscription.bump
+    )]
+    pub subscription: Option<Account<'info, Subscription>>,
+
+    #[account(
+        mut,
+        seeds = [b"merchant_policy", merchant_policy.merchant.as_ref()],
+        bump = merchant_policy.bump
+    )]
+    pub merchant_policy: Option<Account<'info, MerchantPolicy>>,
+}
+
+pub fn handler(ctx: Context<SetFeeExemption>, fee_exempt: bool) -> Result<()> {
+    require!(
+        !ctx.accounts.platform_state.council_enabled,
+        ErrorCode::CouncilApprovalRequired
+    );
+    let (subscription, merchant) = match (
+        ctx.accounts.subscription.as_mut(),
+        ctx.accounts.merchant_policy.as_mut(),
+    ) {
+        (Some(subscription), None) => {
+            subscription.fee_exempt = fee_exempt;
+            (subscription.key(), subscription.merchant)
+        }
+        (None, Some(policy)) => {
+            policy.fee_exempt = fee_exempt;
+            (Pubkey::default(), policy.merchant)
+        }
+        _ => return err!(ErrorCode::InvalidFeeExemptionTarget),
+    };
+
+    emit!(FeeExemptionUpdated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        subscription,
+        merchant,
+        fee_exempt,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Fee exemption set to {} for merchant {}", fee_exempt, merchant);
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use lutrii_common::{AdminAuditAction, EVENT_SCHEMA_VERSION};
+use crate::errors::ErrorCode;
+use crate::state::{record_admin_action, AdminAuditLog, FeeSchedule, MerchantPolicy};
+use crate::{MerchantFeeScheduleUpdated, PlatformState, MAX_FEE_BASIS_POINTS, MIN_FEE_BASIS_POINTS};
+
+/// Attach a negotiated fee schedule to a merchant, or clear it (admin only)
+///
+/// Payments and invoices to the merchant are charged under the schedule
+/// instead of the platform's fee parameters; rounding and dust handling
+/// still follow the platform fee policy. The merchant must have configured
+/// its policy first. Refused once an admin council exists.
+///
+/// # Arguments
+/// * `fee_schedule` - Basis points within the platform bounds and
+///   `min_fee <= max_fee`, or `None` to fall back to the platform defaults
+#[derive(Accounts)]
+pub struct SetMerchantFeeSchedule<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"merchant_policy", merchant_policy.merchant.as_ref()],
+        bump = merchant_policy.bump
+    )]
+    pub merchant_policy: Account<'info, MerchantPolicy>,
+
+    /// Admin audit log, appended to when passed
+    #[account(
+        mut,
+        seeds = [b"admin_audit_log"],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Option<Box<Account<'info, AdminAuditLog>>>,
+}
+
+pub fn handler(ctx: Context<SetMerchantFeeSchedule>, fee_schedule: Option<FeeSchedule>) -> Result<()> {
+    require!(
+        !ctx.accounts.platform_state.council_enabled,
+        ErrorCode::CouncilApprovalRequired
+    );
+    if let Some(schedule) = &fee_schedule {
+        require!(
+            schedule.fee_basis_points >= MIN_FEE_BASIS_POINTS,
+            ErrorCode::FeeTooLow
+        );
+        require!(
+            schedule.fee_basis_points <= MAX_FEE_BASIS_POINTS,
+            ErrorCode::FeeTooHigh
+        );
+        require!(
+            schedule.min_fee <= schedule.max_fee,
+            ErrorCode::InvalidFeeSchedule
+        );
+    }
+
+    let policy = &mut ctx.accounts.merchant_policy;
+    policy.custom_fee = fee_schedule.is_some();
+    policy.fee_schedule = fee_schedule.unwrap_or_default();
+
+    emit!(MerchantFeeScheduleUpdated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        merchant: policy.merchant,
+        fee_schedule,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    let actor = ctx.accounts.authority.key();
+    record_admin_action(ctx.accounts.audit_log.as_mut(), actor, AdminAuditAction::FeeChange)?;
+
+    msg!("Fee schedule for merchant {}: {:?}", policy.merchant, fee_schedule);
+    Ok(())
+}
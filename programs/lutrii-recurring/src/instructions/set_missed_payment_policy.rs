@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use lutrii_common::{MissedPaymentPolicy, Subscription, EVENT_SCHEMA_VERSION};
+use crate::errors::ErrorCode;
+use crate::MissedPaymentPolicyUpdated;
+
+/// Choose how cycles the keeper failed to collect on time are handled (user only)
+///
+/// - `Skip` (default): a cycle is skipped once the platform collection
+///   window closes
+/// - `Accumulate`: missed cycles stay collectible, one per execution, up to
+///   `max_cycles`; older ones are skipped
+/// - `AutoCancel`: the subscription is cancelled once a cycle's collection
+///   window closes
+///
+/// # Arguments
+/// * `policy` - New missed-payment policy
+#[derive(Accounts)]
+pub struct SetMissedPaymentPolicy<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        bump = subscription.bump,
+        has_one = user @ ErrorCode::UnauthorizedUser
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    pub user: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetMissedPaymentPolicy>, policy: MissedPaymentPolicy) -> Result<()> {
+    let subscription = &mut ctx.accounts.subscription;
+    require!(subscription.is_active, ErrorCode::SubscriptionInactive);
+    require!(policy.is_valid(), ErrorCode::InvalidMissedPaymentPolicy);
+
+    subscription.missed_payment_policy = policy;
+
+    emit!(MissedPaymentPolicyUpdated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        subscription: subscription.key(),
+        policy,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Missed payment policy set: {:?}", policy);
+    Ok(())
+}
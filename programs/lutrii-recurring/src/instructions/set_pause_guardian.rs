@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::GovernanceConfig;
+use crate::{PauseGuardianUpdated, PlatformState};
+
+/// Replace or remove the governance pause guardian (admin only)
+///
+/// In governance mode the authority is the realm's treasury, so this runs
+/// from a passed proposal.
+#[derive(Accounts)]
+pub struct SetPauseGuardian<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        mut,
+        seeds = [b"governance"],
+        bump = governance_config.bump
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetPauseGuardian>, pause_guardian: Pubkey) -> Result<()> {
+    ctx.accounts.governance_config.pause_guardian = pause_guardian;
+
+    emit!(PauseGuardianUpdated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        pause_guardian,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Pause guardian set to {}", pause_guardian);
+    Ok(())
+}
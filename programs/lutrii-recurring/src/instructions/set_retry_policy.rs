@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::{MerchantPolicy, RetryPolicy};
+use crate::RetryPolicyUpdated;
+
+/// Set how a merchant's failed collections are retried (merchant owner only)
+///
+/// # Arguments
+/// * `retry_policy` - Backoff base delay, failures allowed in a row and
+///   whether a delinquent subscription is paused or cancelled
+///
+/// # Security
+/// - Merchant PDA is derived from the signing owner, so only the merchant
+///   owner can set its policy
+/// - Applies from each subscription's next failure; failures already
+///   counted keep their backoff
+#[derive(Accounts)]
+pub struct SetRetryPolicy<'info> {
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = MerchantPolicy::LEN,
+        seeds = [b"merchant_policy", merchant.key().as_ref()],
+        bump
+    )]
+    pub merchant_policy: Account<'info, MerchantPolicy>,
+
+    #[account(
+        seeds = [b"merchant", owner.key().as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SetRetryPolicy>, retry_policy: RetryPolicy) -> Result<()> {
+    require!(retry_policy.is_valid(), ErrorCode::InvalidRetryPolicy);
+
+    let policy = &mut ctx.accounts.merchant_policy;
+    policy.merchant = ctx.accounts.merchant.key();
+    policy.retry_policy = retry_policy;
+    policy.bump = ctx.bumps.merchant_policy;
+
+    emit!(RetryPolicyUpdated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        merchant: policy.merchant,
+        retry_policy,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Retry policy updated: {} attempts, {:?} when delinquent",
+        retry_policy.max_failed_attempts,
+        retry_policy.delinquency_action
+    );
+    Ok(())
+}
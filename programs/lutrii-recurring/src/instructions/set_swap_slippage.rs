@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use lutrii_common::{Subscription, EVENT_SCHEMA_VERSION};
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use crate::errors::ErrorCode;
+use crate::state::MAX_SWAP_SLIPPAGE_BPS;
+use crate::SwapSlippageUpdated;
+
+/// Cap the slippage swap-settled payments may take (user or merchant)
+///
+/// Executions that would spend more of the user's token than the oracle
+/// conversion plus this allowance fail with `SlippageExceeded` instead of
+/// paying. The swap asset's own allowance still applies when it is lower.
+///
+/// # Arguments
+/// * `max_slippage_bps` - Allowance over the oracle price (0 = the swap asset's)
+///
+/// # Security
+/// - Signed by the subscriber, or by the merchant owner passing `merchant`
+#[derive(Accounts)]
+pub struct SetSwapSlippage<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        bump = subscription.bump
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    pub authority: Signer<'info>,
+
+    /// Merchant registry account; required when the merchant owner signs
+    #[account(
+        constraint = merchant.key() == subscription.merchant @ ErrorCode::InvalidMerchantAccount
+    )]
+    pub merchant: Option<Box<Account<'info, MerchantAccount>>>,
+}
+
+pub fn handler(ctx: Context<SetSwapSlippage>, max_slippage_bps: u16) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    let subscription = &mut ctx.accounts.subscription;
+    require!(
+        authority == subscription.user
+            || ctx.accounts.merchant.as_ref().is_some_and(|m| m.owner == authority),
+        ErrorCode::UnauthorizedUser
+    );
+    require!(subscription.is_active, ErrorCode::SubscriptionInactive);
+    require!(max_slippage_bps <= MAX_SWAP_SLIPPAGE_BPS, ErrorCode::InvalidSlippage);
+
+    subscription.max_slippage_bps = max_slippage_bps;
+
+    emit!(SwapSlippageUpdated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        subscription: subscription.key(),
+        max_slippage_bps,
+        updated_by: authority,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Swap slippage cap set: {} bps", max_slippage_bps);
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::GovernanceConfig;
+use crate::{PlatformState, TreasurySpendCapUpdated};
+
+/// Set how much one proposal may spend from the fee treasuries (admin only)
+///
+/// Runs from a passed proposal in governance mode; 0 disables treasury
+/// withdrawals and buybacks.
+#[derive(Accounts)]
+pub struct SetTreasurySpendCap<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        mut,
+        seeds = [b"governance"],
+        bump = governance_config.bump
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+
+    pub authority: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetTreasurySpendCap>, max_spend_per_proposal: u64) -> Result<()> {
+    ctx.accounts.governance_config.max_spend_per_proposal = max_spend_per_proposal;
+
+    emit!(TreasurySpendCapUpdated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        max_spend_per_proposal,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Treasury spend cap per proposal set to {}", max_spend_per_proposal);
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use anchor_lang::prelude::*;
+use lutrii_common::{EVENT_SCHEMA_VERSION, Subscription};
+use lutrii_core::{schedule, SECONDS_PER_DAY};
+use crate::errors::ErrorCode;
+use crate::state::{DueBucket, MerchantPolicy, RevenueForecast};
+use crate::PaymentSnoozed;
+
+/// Defer a due payment by up to the merchant's snooze limit (user only)
+///
+/// Unlike pausing or skipping, the payment is still collected - just later,
+/// e.g. aligned with payday. Allowed once per billing cycle; the next
+/// executed payment re-arms it.
+///
+/// # Arguments
+/// * `days` - Days to defer the due date by (1..=merchant `max_snooze_days`)
+#[derive(Accounts)]
+pub struct SnoozePayment<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        bump = subscription.bump,
+        has_one = user @ ErrorCode::UnauthorizedUser
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    /// Merchant policy holding the snooze limit
+    #[account(
+        seeds = [b"merchant_policy", subscription.merchant.as_ref()],
+        bump = merchant_policy.bump
+    )]
+    pub merchant_policy: Account<'info, MerchantPolicy>,
+
+    pub user: Signer<'info>,
+
+    /// Due-date bucket listing the subscription, when indexed
+    #[account(
+        mut,
+        constraint = due_bucket.covers(&subscription.merchant, subscription.next_payment) @ ErrorCode::InvalidDueBucket
+    )]
+    pub due_bucket: Option<Account<'info, DueBucket>>,
+
+    /// Due-date bucket for the rescheduled payment, when indexed
+    #[account(mut)]
+    pub next_due_bucket: Option<Account<'info, DueBucket>>,
+
+    /// Merchant's revenue forecast in the subscription's mint, when kept
+    #[account(
+        mut,
+        seeds = [b"revenue_forecast", subscription.merchant.as_ref(), subscription.mint.as_ref()],
+        bump = revenue_forecast.bump
+    )]
+    pub revenue_forecast: Option<Box<Account<'info, RevenueForecast>>>,
+}
+
+pub fn handler(ctx: Context<SnoozePayment>, days: u8) -> Result<()> {
+    let subscription = &mut ctx.accounts.subscription;
+    let clock = Clock::get()?;
+    let now = subscription.schedule_unit.now(&clock);
+
+    require!(subscription.is_active, ErrorCode::SubscriptionInactive);
+    require!(!subscription.is_paused, ErrorCode::SubscriptionPaused);
+    require!(
+        schedule::is_due(now, subscription.next_payment),
+        ErrorCode::PaymentNotDue
+    );
+    require!(!subscription.snoozed, ErrorCode::AlreadySnoozed);
+    require!(
+        days > 0 && days <= ctx.accounts.merchant_policy.max_snooze_days,
+        ErrorCode::SnoozeLimitExceeded
+    );
+
+    let forecast_entry = subscription.forecast_entry();
+    subscription.next_payment = subscription
+        .next_payment
+        .checked_add(subscription.schedule_unit.span(days as i64 * SECONDS_PER_DAY))
+        .ok_or(ErrorCode::Overflow)?;
+    subscription.snoozed = true;
+
+    if let Some(due_bucket) = ctx.accounts.due_bucket.as_mut() {
+        due_bucket.reschedule(&subscription.key(), &subscription.merchant, subscription.next_payment);
+    }
+    if let Some(next_due_bucket) = ctx.accounts.next_due_bucket.as_mut() {
+        next_due_bucket.insert(subscription.key(), &subscription.merchant, subscription.next_payment)?;
+    }
+    if let Some(revenue_forecast) = ctx.accounts.revenue_forecast.as_mut() {
+        revenue_forecast.reschedule(forecast_entry, subscription.forecast_entry(), clock.unix_timestamp)?;
+    }
+
+    emit!(PaymentSnoozed {
+        schema_version: EVENT_SCHEMA_VERSION,
+        subscription: subscription.key(),
+        user: subscription.user,
+        days,
+        next_payment: subscription.next_payment,
+        timestamp: clock.unix_timestamp,
+        utc_offset_seconds: subscription.utc_offset_seconds,
+    });
+
+    msg!("Payment snoozed {} days", days);
+    Ok(())
+}
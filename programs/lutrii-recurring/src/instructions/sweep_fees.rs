@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::transfer_checked;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::FeeTreasury;
+use crate::FeesSwept;
+
+/// Distribute a fee treasury's balance per its split (permissionless)
+///
+/// Recipient token accounts are passed as writable remaining accounts in
+/// the order of the configured shares.
+///
+/// # Security
+/// - Each remaining account must match the configured recipient
+/// - At most one sweep per `sweep_interval`
+#[derive(Accounts)]
+pub struct SweepFees<'info> {
+    #[account(
+        mut,
+        seeds = [b"fee_treasury", fee_treasury.mint.as_ref()],
+        bump = fee_treasury.bump,
+        has_one = vault,
+        has_one = mint
+    )]
+    pub fee_treasury: Account<'info, FeeTreasury>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, SweepFees<'info>>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let treasury = &ctx.accounts.fee_treasury;
+
+    require!(treasury.sweep_due(now), ErrorCode::SweepTooEarly);
+    let amount = ctx.accounts.vault.amount;
+    require!(amount > 0, ErrorCode::NothingToSweep);
+
+    let recipients = ctx.remaining_accounts;
+    require!(
+        recipients.len() == treasury.share_count as usize,
+        ErrorCode::InvalidFeeRecipient
+    );
+    for (account, share) in recipients.iter().zip(treasury.active_shares()) {
+        require_keys_eq!(account.key(), share.recipient, ErrorCode::InvalidFeeRecipient);
+    }
+    let amounts = treasury.split(amount);
+
+    // ============================================================================
+    // EFFECTS
+    // ============================================================================
+
+    let treasury = &mut ctx.accounts.fee_treasury;
+    treasury.accrued = 0;
+    treasury.total_distributed = treasury
+        .total_distributed
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+    treasury.last_sweep = now;
+
+    // ============================================================================
+    // INTERACTIONS
+    // ============================================================================
+
+    let seeds = &[b"fee_treasury", treasury.mint.as_ref(), &[treasury.bump]];
+    let signer = &[&seeds[..]];
+
+    for (recipient, share_amount) in recipients.iter().zip(amounts) {
+        if share_amount == 0 {
+            continue;
+        }
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: recipient.clone(),
+                    authority: treasury.to_account_info(),
+                },
+                signer,
+            ),
+            share_amount,
+            ctx.accounts.mint.decimals,
+        )?;
+    }
+
+    emit!(FeesSwept {
+        schema_version: EVENT_SCHEMA_VERSION,
+        mint: treasury.mint,
+        amount,
+        total_distributed: treasury.total_distributed,
+        timestamp: now,
+    });
+
+    msg!("✅ Fees swept: {} to {} recipients", amount, recipients.len());
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::transfer_checked;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::{RefundLiability, SettlementVault};
+use crate::SettlementSwept;
+
+/// Move everything accrued in a settlement vault to the merchant (permissionless)
+///
+/// When the merchant's refund liability is tracked, its required reserve
+/// stays in the vault until the payments behind it leave the refund window.
+///
+/// # Security
+/// - Funds can only reach the `destination` recorded by the merchant owner
+#[derive(Accounts)]
+pub struct SweepSettlement<'info> {
+    #[account(
+        mut,
+        seeds = [b"settlement", settlement.merchant.as_ref(), settlement.mint.as_ref()],
+        bump = settlement.bump,
+        has_one = vault,
+        has_one = destination,
+        has_one = mint
+    )]
+    pub settlement: Account<'info, SettlementVault>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Merchant's refund exposure, when tracked
+    #[account(
+        seeds = [b"refund_liability", settlement.merchant.as_ref(), settlement.mint.as_ref()],
+        bump = refund_liability.bump
+    )]
+    pub refund_liability: Option<Account<'info, RefundLiability>>,
+}
+
+pub fn handler(ctx: Context<SweepSettlement>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let held_in_reserve = ctx
+        .accounts
+        .refund_liability
+        .as_ref()
+        .map_or(0, |liability| liability.required_reserve(now))
+        .min(ctx.accounts.vault.amount);
+    let amount = ctx.accounts.vault.amount - held_in_reserve;
+    require!(amount > 0, ErrorCode::NothingToSweep);
+
+    // ============================================================================
+    // EFFECTS
+    // ============================================================================
+
+    let settlement = &mut ctx.accounts.settlement;
+    settlement.accrued = held_in_reserve;
+    settlement.total_swept = settlement
+        .total_swept
+        .checked_add(amount)
+        .ok_or(ErrorCode::Overflow)?;
+    settlement.last_sweep = now;
+
+    // ============================================================================
+    // INTERACTIONS
+    // ============================================================================
+
+    let seeds = &[
+        b"settlement",
+        settlement.merchant.as_ref(),
+        settlement.mint.as_ref(),
+        &[settlement.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: settlement.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    emit!(SettlementSwept {
+        schema_version: EVENT_SCHEMA_VERSION,
+        merchant: settlement.merchant,
+        mint: settlement.mint,
+        amount,
+        total_swept: settlement.total_swept,
+        timestamp: now,
+        held_in_reserve,
+    });
+
+    msg!("✅ Settlement swept: {} to merchant", amount);
+    Ok(())
+}
@@ -0,0 +1,168 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::cpi::forward_signed_cpi;
+use crate::errors::ErrorCode;
+use crate::state::{FeeTreasury, GovernanceConfig, SwapConfig, TreasurySpend};
+use crate::{PlatformState, TreasuryBuyback, MAX_SWAP_ROUTE_ACCOUNTS};
+
+/// Swap fee revenue into a buyback destination under a passed proposal (governance only)
+///
+/// The route runs through an allowlisted swap program with the fee
+/// treasury PDA signing for its vault, like swap payments do for the user
+/// delegate. Spending counts toward the proposal's cap.
+///
+/// # Arguments
+/// * `amount_in` - Most the vault may spend
+/// * `min_amount_out` - Least `buyback_destination` must receive
+/// * `data` - Swap program instruction data
+#[derive(Accounts)]
+pub struct TreasuryBuybackAccounts<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        seeds = [b"governance"],
+        bump = governance_config.bump,
+        constraint = governance_config.native_treasury == authority.key() @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub governance_config: Box<Account<'info, GovernanceConfig>>,
+
+    /// CHECK: SPL Governance proposal being executed, checked in the handler
+    pub proposal: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = TreasurySpend::LEN,
+        seeds = [b"treasury_spend", proposal.key().as_ref()],
+        bump
+    )]
+    pub treasury_spend: Box<Account<'info, TreasurySpend>>,
+
+    #[account(
+        seeds = [b"fee_treasury", fee_treasury.mint.as_ref()],
+        bump = fee_treasury.bump,
+        has_one = vault,
+        has_one = mint
+    )]
+    pub fee_treasury: Box<Account<'info, FeeTreasury>>,
+
+    #[account(mut)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token account receiving the bought tokens
+    #[account(mut)]
+    pub buyback_destination: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"swap_config"],
+        bump = swap_config.bump
+    )]
+    pub swap_config: Box<Account<'info, SwapConfig>>,
+
+    /// CHECK: Allowlisted swap program
+    #[account(
+        executable,
+        constraint = swap_config.is_allowed(&swap_program.key()) @ ErrorCode::InvalidJupiterProgram
+    )]
+    pub swap_program: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, TreasuryBuybackAccounts<'info>>,
+    amount_in: u64,
+    min_amount_out: u64,
+    data: Vec<u8>,
+) -> Result<()> {
+    let governance = &ctx.accounts.governance_config;
+    governance.check_executing_proposal(&ctx.accounts.proposal)?;
+    require!(
+        amount_in > 0 && amount_in <= ctx.accounts.vault.amount,
+        ErrorCode::InsufficientAmount
+    );
+    require!(
+        ctx.remaining_accounts.len() <= MAX_SWAP_ROUTE_ACCOUNTS as usize,
+        ErrorCode::SwapRouteTooLarge
+    );
+    // The treasury PDA owns nothing but its vault, whose outflow is
+    // bounded below, so no route account needs an owner check
+    ctx.accounts.swap_config.check_route(
+        ctx.remaining_accounts,
+        &ctx.accounts.vault.key(),
+        &ctx.accounts.buyback_destination.key(),
+        &Pubkey::default(),
+    )?;
+
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts
+        .treasury_spend
+        .record_spend(amount_in, governance.max_spend_per_proposal, now)?;
+
+    // ============================================================================
+    // INTERACTIONS
+    // ============================================================================
+
+    let vault_before = ctx.accounts.vault.amount;
+    let output_before = ctx.accounts.buyback_destination.amount;
+    let treasury = &ctx.accounts.fee_treasury;
+    let seeds = &[b"fee_treasury", treasury.mint.as_ref(), &[treasury.bump]];
+    forward_signed_cpi(
+        &ctx.accounts.swap_program.to_account_info(),
+        ctx.remaining_accounts,
+        &treasury.key(),
+        data,
+        &[&seeds[..]],
+    )?;
+
+    ctx.accounts.vault.reload()?;
+    ctx.accounts.buyback_destination.reload()?;
+    let spent = vault_before
+        .checked_sub(ctx.accounts.vault.amount)
+        .ok_or(ErrorCode::SwapFailed)?;
+    let received = ctx
+        .accounts
+        .buyback_destination
+        .amount
+        .checked_sub(output_before)
+        .ok_or(ErrorCode::SwapFailed)?;
+    require!(spent <= amount_in, ErrorCode::SlippageExceeded);
+    require!(received >= min_amount_out, ErrorCode::SlippageExceeded);
+
+    // ============================================================================
+    // EFFECTS - Log what the swap actually did
+    // ============================================================================
+
+    let spend = &mut ctx.accounts.treasury_spend;
+    spend.proposal = ctx.accounts.proposal.key();
+    spend.bump = ctx.bumps.treasury_spend;
+    spend.bought_back = spend.bought_back.checked_add(spent).ok_or(ErrorCode::Overflow)?;
+    spend.buyback_received = spend
+        .buyback_received
+        .checked_add(received)
+        .ok_or(ErrorCode::Overflow)?;
+
+    emit!(TreasuryBuyback {
+        schema_version: EVENT_SCHEMA_VERSION,
+        proposal: spend.proposal,
+        mint: ctx.accounts.fee_treasury.mint,
+        output_mint: ctx.accounts.buyback_destination.mint,
+        amount_in: spent,
+        amount_out: received,
+        proposal_total: spend.total_spent()?,
+        timestamp: now,
+    });
+
+    msg!("Treasury buyback: {} in, {} out", spent, received);
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::state::BlocklistEntry;
+use crate::UserBlockUpdated;
+
+/// Lift a merchant's block on a subscriber wallet (merchant owner only)
+///
+/// Closes the blocklist entry and returns its rent to the merchant owner.
+#[derive(Accounts)]
+pub struct UnblockUser<'info> {
+    #[account(
+        mut,
+        seeds = [b"blocklist", merchant.key().as_ref(), blocklist_entry.user.as_ref()],
+        bump = blocklist_entry.bump,
+        close = owner
+    )]
+    pub blocklist_entry: Account<'info, BlocklistEntry>,
+
+    #[account(
+        seeds = [b"merchant", owner.key().as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<UnblockUser>) -> Result<()> {
+    let merchant = ctx.accounts.merchant.key();
+    let user = ctx.accounts.blocklist_entry.user;
+
+    emit!(UserBlockUpdated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        merchant,
+        user,
+        blocked: false,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("✅ User {} unblocked by merchant {}", user, merchant);
+    Ok(())
+}
@@ -1,46 +1,34 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
-use crate::state::PlatformConfig;
+use anchor_spl::token_interface::{Mint, TokenInterface};
+use lutrii_common::AdminAuditAction;
+use crate::PlatformState;
 use crate::errors::ErrorCode;
+use crate::state::{record_admin_action, AdminAuditLog};
 
 /// Update the platform configuration (admin only)
 ///
-/// Allows the authority to update fee collection wallets if needed.
-/// This is critical for wallet rotation or migrating to new fee wallets.
+/// The passed USDC and USD1 mints become the accepted settlement mints.
+/// Fee wallets are rotated through `schedule_config_change` instead, so
+/// subscribers see the change coming.
 ///
 /// # Arguments
-/// * `new_fee_wallet_usdc` - Optional new USDC fee wallet
-/// * `new_fee_wallet_usd1` - Optional new USD1 fee wallet
 /// * `new_authority` - Optional new authority (for admin rotation)
 ///
 /// # Security
 /// - Only current authority can call this
 /// - has_one constraint enforces authority check
-/// - All new wallets validated as proper token accounts
 #[derive(Accounts)]
 pub struct UpdateConfig<'info> {
     #[account(
         mut,
-        seeds = [b"platform_config"],
-        bump = config.bump,
+        seeds = [b"platform"],
+        bump = platform_state.bump,
         has_one = authority @ ErrorCode::UnauthorizedAdmin
     )]
-    pub config: Account<'info, PlatformConfig>,
+    pub platform_state: Account<'info, PlatformState>,
 
     pub authority: Signer<'info>,
 
-    /// New USDC fee wallet (optional)
-    #[account(
-        constraint = new_fee_wallet_usdc.mint == usdc_mint.key() @ ErrorCode::InvalidFeeWalletMint
-    )]
-    pub new_fee_wallet_usdc: Option<InterfaceAccount<'info, TokenAccount>>,
-
-    /// New USD1 fee wallet (optional)
-    #[account(
-        constraint = new_fee_wallet_usd1.mint == usd1_mint.key() @ ErrorCode::InvalidFeeWalletMint
-    )]
-    pub new_fee_wallet_usd1: Option<InterfaceAccount<'info, TokenAccount>>,
-
     /// USDC mint (for validation)
     pub usdc_mint: InterfaceAccount<'info, Mint>,
 
@@ -48,48 +36,56 @@ pub struct UpdateConfig<'info> {
     pub usd1_mint: InterfaceAccount<'info, Mint>,
 
     pub token_program: Interface<'info, TokenInterface>,
+
+    /// Admin audit log, appended to when passed
+    #[account(
+        mut,
+        seeds = [b"admin_audit_log"],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Option<Box<Account<'info, AdminAuditLog>>>,
 }
 
 pub fn handler(
     ctx: Context<UpdateConfig>,
     new_authority: Option<Pubkey>,
 ) -> Result<()> {
-    let config = &mut ctx.accounts.config;
+    let platform = &mut ctx.accounts.platform_state;
 
     let mut updated = false;
 
-    // Update USDC fee wallet if provided
-    if let Some(new_usdc_wallet) = &ctx.accounts.new_fee_wallet_usdc {
-        let old_wallet = config.fee_wallet_usdc;
-        config.fee_wallet_usdc = new_usdc_wallet.key();
-        msg!("USDC fee wallet updated");
-        msg!("  Old: {}", old_wallet);
-        msg!("  New: {}", config.fee_wallet_usdc);
-        updated = true;
-    }
-
-    // Update USD1 fee wallet if provided
-    if let Some(new_usd1_wallet) = &ctx.accounts.new_fee_wallet_usd1 {
-        let old_wallet = config.fee_wallet_usd1;
-        config.fee_wallet_usd1 = new_usd1_wallet.key();
-        msg!("USD1 fee wallet updated");
-        msg!("  Old: {}", old_wallet);
-        msg!("  New: {}", config.fee_wallet_usd1);
+    // Record the stablecoin mints (platforms migrated to v3 start without them)
+    let usdc_mint = ctx.accounts.usdc_mint.key();
+    let usd1_mint = ctx.accounts.usd1_mint.key();
+    if platform.usdc_mint != usdc_mint || platform.usd1_mint != usd1_mint {
+        platform.usdc_mint = usdc_mint;
+        platform.usd1_mint = usd1_mint;
+        msg!("Stablecoin mints updated");
+        msg!("  USDC: {}", usdc_mint);
+        msg!("  USD1: {}", usd1_mint);
         updated = true;
     }
 
     // Update authority if provided
     if let Some(new_auth) = new_authority {
-        let old_authority = config.authority;
-        config.authority = new_auth;
+        let old_authority = platform.authority;
+        platform.authority = new_auth;
         msg!("Authority updated");
         msg!("  Old: {}", old_authority);
-        msg!("  New: {}", config.authority);
+        msg!("  New: {}", platform.authority);
         updated = true;
     }
 
     require!(updated, ErrorCode::NoUpdateProvided);
 
+    let action = if new_authority.is_some() {
+        AdminAuditAction::AuthorityRotation
+    } else {
+        AdminAuditAction::ConfigUpdate
+    };
+    let actor = ctx.accounts.authority.key();
+    record_admin_action(ctx.accounts.audit_log.as_mut(), actor, action)?;
+
     msg!("✅ Platform config updated successfully");
 
     Ok(())
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::Plan;
+use crate::PlanUpdated;
+
+/// Reprice a plan or stop taking subscribers (merchant owner only)
+///
+/// Bound subscriptions charge the new price from their next payment, as
+/// long as it stays within 10% of the amount each user agreed to; beyond
+/// that their payments fail with `PriceVarianceExceeded` until the user
+/// accepts the change through `accept_amount_change`. Deactivating a plan
+/// keeps existing subscribers billing.
+///
+/// # Arguments
+/// * `price` - New price per billing cycle
+/// * `is_active` - Whether new subscriptions may join the plan
+#[derive(Accounts)]
+pub struct UpdatePlan<'info> {
+    #[account(
+        mut,
+        seeds = [b"plan", merchant.key().as_ref(), plan.plan_id.to_le_bytes().as_ref()],
+        bump = plan.bump
+    )]
+    pub plan: Account<'info, Plan>,
+
+    #[account(
+        seeds = [b"merchant", owner.key().as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    pub owner: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<UpdatePlan>, price: u64, is_active: bool) -> Result<()> {
+    require!(price > 0, ErrorCode::AmountTooLow);
+
+    let plan = &mut ctx.accounts.plan;
+    let old_price = plan.price;
+    plan.price = price;
+    plan.is_active = is_active;
+
+    emit!(PlanUpdated {
+        schema_version: EVENT_SCHEMA_VERSION,
+        plan: plan.key(),
+        merchant: plan.merchant,
+        old_price,
+        price,
+        is_active,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Plan {} updated: {} -> {}", plan.name, old_price, price);
+    Ok(())
+}
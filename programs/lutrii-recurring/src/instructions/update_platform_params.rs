@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+use lutrii_common::AdminAuditAction;
+use crate::errors::ErrorCode;
+use crate::instructions::schedule_config_change::schedule;
+use crate::state::{record_admin_action, AdminAuditLog, ConfigChange, PendingConfigChange};
+use crate::PlatformState;
+
+/// Schedule a change to the platform fee rate and its bounds (admin only)
+///
+/// Only the fee parameters change; fee wallets rotate through
+/// `schedule_config_change`. Refused while another change is scheduled, so
+/// it never silently drops one; `schedule_config_change` replaces a pending
+/// change deliberately. Once an admin council exists the change needs its
+/// approval instead.
+///
+/// # Arguments
+/// * `fee_basis_points` - Within the platform bounds
+/// * `min_fee` - Smallest platform fee, at most `max_fee`
+/// * `max_fee` - Largest platform fee
+#[derive(Accounts)]
+pub struct UpdatePlatformParams<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = PendingConfigChange::LEN,
+        seeds = [b"pending_config_change"],
+        bump
+    )]
+    pub pending_config_change: Account<'info, PendingConfigChange>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Admin audit log, appended to when passed
+    #[account(
+        mut,
+        seeds = [b"admin_audit_log"],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Option<Box<Account<'info, AdminAuditLog>>>,
+}
+
+pub fn handler(
+    ctx: Context<UpdatePlatformParams>,
+    fee_basis_points: u16,
+    min_fee: u64,
+    max_fee: u64,
+) -> Result<()> {
+    require!(
+        !ctx.accounts.platform_state.council_enabled,
+        ErrorCode::CouncilApprovalRequired
+    );
+    require!(
+        !ctx.accounts.pending_config_change.pending,
+        ErrorCode::ConfigChangePending
+    );
+    let change = ConfigChange {
+        fee_basis_points: Some(fee_basis_points),
+        min_fee: Some(min_fee),
+        max_fee: Some(max_fee),
+        ..ConfigChange::default()
+    };
+    ctx.accounts.pending_config_change.bump = ctx.bumps.pending_config_change;
+    schedule(&mut ctx.accounts.pending_config_change, &ctx.accounts.platform_state, change)?;
+
+    let actor = ctx.accounts.authority.key();
+    record_admin_action(ctx.accounts.audit_log.as_mut(), actor, AdminAuditAction::ConfigUpdate)
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use lutrii_common::{Subscription, SubscriptionAccess};
+use crate::errors::ErrorCode;
+
+/// Read-only check of whether a wallet may access a merchant's service
+///
+/// Returns a `SubscriptionAccess` through return data. Other programs CPI
+/// it with `lutrii_common::verify_active_subscription`; clients simulate
+/// it. A missing or closed subscription answers `NotSubscribed` rather
+/// than failing. `nonce` picks which of the pair's subscriptions is read.
+#[derive(Accounts)]
+#[instruction(user: Pubkey, merchant: Pubkey, nonce: u32)]
+pub struct VerifyActiveSubscription<'info> {
+    /// CHECK: Subscription PDA of `user` and `merchant` created with
+    /// `nonce`; may not exist
+    #[account(
+        seeds = [
+            b"subscription",
+            user.as_ref(),
+            merchant.as_ref(),
+            &Subscription::nonce_seed(nonce),
+        ],
+        bump
+    )]
+    pub subscription: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<VerifyActiveSubscription>, plan: Option<Pubkey>) -> Result<SubscriptionAccess> {
+    let info = ctx.accounts.subscription.to_account_info();
+    if info.owner != &crate::ID || info.data_is_empty() {
+        return Ok(SubscriptionAccess::not_subscribed(info.key()));
+    }
+    // Layouts from before the latest migration do not decode
+    let subscription = Subscription::try_deserialize(&mut &info.try_borrow_data()?[..])
+        .map_err(|_| error!(ErrorCode::SubscriptionMigrationRequired))?;
+    let now = subscription.schedule_unit.now(&Clock::get()?);
+    Ok(subscription.access(info.key(), plan.as_ref(), now))
+}
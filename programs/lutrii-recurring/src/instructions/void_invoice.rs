@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::{Invoice, InvoiceStatus};
+use crate::InvoiceVoided;
+
+/// Void an open invoice (merchant owner only)
+///
+/// # Security
+/// - Merchant PDA is derived from the signing owner and must match the invoice
+#[derive(Accounts)]
+pub struct VoidInvoice<'info> {
+    #[account(
+        mut,
+        seeds = [b"invoice", merchant.key().as_ref(), invoice.invoice_id.to_le_bytes().as_ref()],
+        bump = invoice.bump
+    )]
+    pub invoice: Box<Account<'info, Invoice>>,
+
+    #[account(
+        seeds = [b"merchant", owner.key().as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    pub owner: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<VoidInvoice>) -> Result<()> {
+    let invoice = &mut ctx.accounts.invoice;
+    require!(
+        invoice.status == InvoiceStatus::Open,
+        ErrorCode::InvoiceNotOpen
+    );
+
+    invoice.status = InvoiceStatus::Void;
+
+    emit!(InvoiceVoided {
+        schema_version: EVENT_SCHEMA_VERSION,
+        invoice: invoice.key(),
+        merchant: invoice.merchant,
+        user: invoice.user,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Invoice {} voided", invoice.invoice_id);
+    Ok(())
+}
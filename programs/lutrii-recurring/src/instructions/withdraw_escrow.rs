@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::transfer_checked;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::SubscriptionEscrow;
+use crate::EscrowWithdrawn;
+
+/// Withdraw unspent prepaid funds from a subscription's escrow (user only)
+///
+/// Available at any time, including after the subscription is cancelled
+/// or closed; the escrow keeps its own record of the owner.
+///
+/// # Arguments
+/// * `amount` - Token units to move back to `destination`
+///
+/// # Security
+/// - Only the escrow's user can withdraw (has_one + signer)
+/// - Funds only go to a token account the user owns
+#[derive(Accounts)]
+pub struct WithdrawEscrow<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow", escrow.subscription.as_ref()],
+        bump = escrow.bump,
+        has_one = user @ ErrorCode::UnauthorizedUser,
+        has_one = vault,
+        has_one = mint
+    )]
+    pub escrow: Box<Account<'info, SubscriptionEscrow>>,
+
+    #[account(mut)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = destination.owner == user.key() @ ErrorCode::InvalidTokenAccountOwner,
+        constraint = destination.mint == mint.key() @ ErrorCode::InvalidMint
+    )]
+    pub destination: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub user: Signer<'info>,
+
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler(ctx: Context<WithdrawEscrow>, amount: u64) -> Result<()> {
+    require!(
+        amount > 0 && amount <= ctx.accounts.vault.amount,
+        ErrorCode::InsufficientAmount
+    );
+
+    // ============================================================================
+    // EFFECTS
+    // ============================================================================
+
+    let escrow = &mut ctx.accounts.escrow;
+    escrow.withdrawn = escrow.withdrawn.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+
+    // ============================================================================
+    // INTERACTIONS
+    // ============================================================================
+
+    let seeds = &[b"escrow", escrow.subscription.as_ref(), &[escrow.bump]];
+    let signer = &[&seeds[..]];
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: escrow.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    let balance = ctx.accounts.vault.amount - amount;
+
+    emit!(EscrowWithdrawn {
+        schema_version: EVENT_SCHEMA_VERSION,
+        subscription: escrow.subscription,
+        user: escrow.user,
+        amount,
+        balance,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Escrow withdrawal of {}, balance {}", amount, balance);
+    Ok(())
+}
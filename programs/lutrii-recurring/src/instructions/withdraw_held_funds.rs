@@ -0,0 +1,146 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::transfer_checked;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::cpi::forward_signed_cpi;
+use crate::state::{SettlementVault, YieldConfig};
+use crate::{HeldFundsWithdrawn, PlatformState};
+
+/// Withdraw held funds from a lending protocol back into the settlement vault (merchant owner only)
+///
+/// Whatever the vault receives first repays deployed principal; the excess
+/// is yield, split per `YieldConfig::merchant_yield_bps`. The platform's
+/// share is paid to the fee wallet, the merchant's share stays in the vault
+/// for the next sweep.
+///
+/// # Arguments
+/// * `data` - Instruction data for the lending program's withdrawal
+///
+/// # Security
+/// - Only allowlisted lending programs can be invoked
+/// - The vault balance may not decrease
+#[derive(Accounts)]
+pub struct WithdrawHeldFunds<'info> {
+    #[account(
+        mut,
+        seeds = [b"settlement", merchant.key().as_ref(), mint.key().as_ref()],
+        bump = settlement.bump,
+        has_one = vault
+    )]
+    pub settlement: Account<'info, SettlementVault>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(seeds = [b"yield_config"], bump = yield_config.bump)]
+    pub yield_config: Account<'info, YieldConfig>,
+
+    #[account(seeds = [b"platform"], bump = platform_state.bump)]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        mut,
+        constraint = platform_state.is_fee_wallet(&platform_fee_account.key()) @ ErrorCode::InvalidFeeWallet,
+        constraint = platform_fee_account.mint == mint.key() @ ErrorCode::InvalidFeeWalletMint
+    )]
+    pub platform_fee_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"merchant", owner.key().as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    pub owner: Signer<'info>,
+
+    /// CHECK: Must be on the yield allowlist
+    #[account(
+        executable,
+        constraint = yield_config.is_allowed(lending_program.key) @ ErrorCode::LendingProgramNotAllowed
+    )]
+    pub lending_program: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, WithdrawHeldFunds<'info>>,
+    data: Vec<u8>,
+) -> Result<()> {
+    let before = ctx.accounts.vault.amount;
+    let settlement = &mut ctx.accounts.settlement;
+    let (merchant, mint, bump) = (settlement.merchant, settlement.mint, settlement.bump);
+    let seeds = &[b"settlement", merchant.as_ref(), mint.as_ref(), &[bump]];
+    let signer = &[&seeds[..]];
+
+    forward_signed_cpi(
+        &ctx.accounts.lending_program.to_account_info(),
+        ctx.remaining_accounts,
+        &settlement.key(),
+        data,
+        signer,
+    )?;
+
+    ctx.accounts.vault.reload()?;
+    let received = ctx
+        .accounts
+        .vault
+        .amount
+        .checked_sub(before)
+        .ok_or(ErrorCode::UnexpectedVaultBalance)?;
+
+    // Principal first, the rest is yield
+    let principal = received.min(settlement.deployed);
+    let yield_amount = received - principal;
+    let (merchant_yield, platform_yield) = ctx.accounts.yield_config.split_yield(yield_amount);
+
+    settlement.deployed -= principal;
+    settlement.yield_earned = settlement
+        .yield_earned
+        .checked_add(merchant_yield)
+        .ok_or(ErrorCode::Overflow)?;
+    settlement.accrued = settlement
+        .accrued
+        .checked_add(merchant_yield)
+        .ok_or(ErrorCode::Overflow)?;
+
+    if platform_yield > 0 {
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.platform_fee_account.to_account_info(),
+                    authority: settlement.to_account_info(),
+                },
+                signer,
+            ),
+            platform_yield,
+            ctx.accounts.mint.decimals,
+        )?;
+    }
+
+    emit!(HeldFundsWithdrawn {
+        schema_version: EVENT_SCHEMA_VERSION,
+        merchant,
+        mint,
+        lending_program: ctx.accounts.lending_program.key(),
+        principal,
+        merchant_yield,
+        platform_yield,
+        deployed: settlement.deployed,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Withdrew {} principal and {} yield from lending",
+        principal,
+        yield_amount
+    );
+    Ok(())
+}
@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::transfer_checked;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+use lutrii_common::EVENT_SCHEMA_VERSION;
+use crate::errors::ErrorCode;
+use crate::state::{FeeTreasury, GovernanceConfig, TreasurySpend};
+use crate::{PlatformState, TreasuryWithdrawn};
+
+/// Withdraw from a fee treasury under a passed proposal (governance only)
+///
+/// Only available in governance mode: the signer must be the governance
+/// treasury executing `proposal`, and the proposal's spending is logged
+/// and capped at `max_spend_per_proposal`.
+///
+/// # Arguments
+/// * `amount` - Token units to move to `destination`
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    #[account(
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(
+        seeds = [b"governance"],
+        bump = governance_config.bump,
+        constraint = governance_config.native_treasury == authority.key() @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub governance_config: Account<'info, GovernanceConfig>,
+
+    /// CHECK: SPL Governance proposal being executed, checked in the handler
+    pub proposal: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = TreasurySpend::LEN,
+        seeds = [b"treasury_spend", proposal.key().as_ref()],
+        bump
+    )]
+    pub treasury_spend: Account<'info, TreasurySpend>,
+
+    #[account(
+        mut,
+        seeds = [b"fee_treasury", fee_treasury.mint.as_ref()],
+        bump = fee_treasury.bump,
+        has_one = vault,
+        has_one = mint
+    )]
+    pub fee_treasury: Account<'info, FeeTreasury>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = destination.mint == mint.key() @ ErrorCode::InvalidMint
+    )]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+    let governance = &ctx.accounts.governance_config;
+    governance.check_executing_proposal(&ctx.accounts.proposal)?;
+    require!(
+        amount > 0 && amount <= ctx.accounts.vault.amount,
+        ErrorCode::InsufficientAmount
+    );
+
+    // ============================================================================
+    // EFFECTS
+    // ============================================================================
+
+    let now = Clock::get()?.unix_timestamp;
+    let spend = &mut ctx.accounts.treasury_spend;
+    spend.proposal = ctx.accounts.proposal.key();
+    spend.bump = ctx.bumps.treasury_spend;
+    spend.record_spend(amount, governance.max_spend_per_proposal, now)?;
+    spend.withdrawn = spend.withdrawn.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+
+    let treasury = &mut ctx.accounts.fee_treasury;
+    treasury.accrued = treasury.accrued.saturating_sub(amount);
+
+    // ============================================================================
+    // INTERACTIONS
+    // ============================================================================
+
+    let seeds = &[b"fee_treasury", treasury.mint.as_ref(), &[treasury.bump]];
+    let signer = &[&seeds[..]];
+    transfer_checked(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            TransferChecked {
+                from: ctx.accounts.vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: treasury.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+        ctx.accounts.mint.decimals,
+    )?;
+
+    emit!(TreasuryWithdrawn {
+        schema_version: EVENT_SCHEMA_VERSION,
+        proposal: spend.proposal,
+        mint: treasury.mint,
+        destination: ctx.accounts.destination.key(),
+        amount,
+        proposal_total: spend.total_spent()?,
+        timestamp: now,
+    });
+
+    msg!("Treasury withdrawal of {} under proposal {}", amount, spend.proposal);
+    Ok(())
+}
@@ -1,15 +1,23 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token_2022::{approve, transfer_checked, revoke, Approve};
-use anchor_spl::token_interface::{
-    Mint, Revoke, TokenAccount, TokenInterface, TransferChecked,
+use anchor_spl::token_2022::transfer_checked;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+use lutrii_merchant_registry::{self, program::LutriiMerchantRegistry, Merchant as MerchantAccount, VerificationTier};
+
+use lutrii_common::MAX_MERCHANT_NAME_LEN;
+use lutrii_core::{fee, limits, proration, schedule, SECONDS_PER_DAY};
+
+pub use lutrii_common::{
+    AccessStatus, AdminAuditAction, AdminAuditEntry, FeeBearer, MissedPaymentPolicy, ScheduleUnit,
+    Subscription, SubscriptionAccess, EVENT_SCHEMA_VERSION,
 };
-use lutrii_merchant_registry::{self, Merchant as MerchantAccount, VerificationTier};
 
 // Import new modular structure
 mod state;
 mod instructions;
 mod errors;
+mod cpi;
+mod payment;
 
 pub use state::*;
 pub use instructions::*;
@@ -18,18 +26,75 @@ pub use errors::ErrorCode;
 declare_id!("146BGDDLG4yRYXfNCCDdRRmCAYTrGddCgY14n4ekxJyF");
 
 // Constants
-const SECONDS_PER_DAY: i64 = 86_400;
-const BASIS_POINTS_DIVISOR: u128 = 10_000;
 const MIN_FREQUENCY_SECONDS: i64 = 3_600; // 1 hour
 const MAX_FREQUENCY_SECONDS: i64 = 31_536_000; // 1 year
-const MAX_MERCHANT_NAME_LEN: usize = 32;
 const MAX_FEE_BASIS_POINTS: u16 = 500; // 5% max
 const MIN_FEE_BASIS_POINTS: u16 = 1; // 0.01% min
+const STABLECOIN_DECIMALS: u8 = 6; // USDC and USD1
+
+/// Jupiter v6 aggregator, the usual entry on the swap allowlist (`SwapConfig`)
+pub mod jupiter {
+    use anchor_lang::prelude::*;
+
+    declare_id!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
+}
 
 /// Program version for tracking upgrades
 #[constant]
 pub const VERSION: &str = "1.0.0";
 
+/// Compute units one `execute_payment` may consume, enforced by the
+/// lifecycle tests; keepers size their compute budget from it
+///
+/// Raised from 40_000 for the merchant registry `record_transaction` CPI.
+/// Not yet measured on the BPF runtime; `test_execute_payment_within_compute_budget`
+/// prints the units a payment uses, and the figure belongs here with any
+/// change to the budget.
+#[constant]
+pub const EXECUTE_PAYMENT_COMPUTE_UNITS: u32 = 55_000;
+
+/// Compute units `execute_payment_with_swap` needs besides the route itself;
+/// keepers request this plus the route's estimate
+///
+/// Raised from 90_000 for the same registry CPI; not yet measured either.
+#[constant]
+pub const SWAP_PAYMENT_COMPUTE_UNITS: u32 = 105_000;
+
+/// Most route accounts `execute_payment_with_swap` forwards, keeping the
+/// transaction within the 64 account locks alongside its own accounts
+#[constant]
+pub const MAX_SWAP_ROUTE_ACCOUNTS: u8 = 40;
+
+/// Slots a `claim_execution` reserves a due payment for (about a minute)
+#[constant]
+pub const EXECUTION_CLAIM_SLOTS: u64 = 150;
+
+/// How long an emergency pause lasts unless re-activated, when the platform
+/// has not configured a duration (72 hours)
+#[constant]
+pub const DEFAULT_PAUSE_DURATION_SECONDS: i64 = 72 * 3_600;
+
+/// Bounds for `set_pause_duration` (1 hour to 7 days)
+pub const MIN_PAUSE_DURATION_SECONDS: i64 = 3_600;
+pub const MAX_PAUSE_DURATION_SECONDS: i64 = 7 * 86_400;
+
+/// How long a scheduled config change waits before it may be executed, when
+/// the platform has not configured a delay (48 hours)
+#[constant]
+pub const DEFAULT_CONFIG_CHANGE_DELAY_SECONDS: i64 = 48 * 3_600;
+
+/// Bounds for the config change delay (1 hour to 30 days)
+pub const MIN_CONFIG_CHANGE_DELAY_SECONDS: i64 = 3_600;
+pub const MAX_CONFIG_CHANGE_DELAY_SECONDS: i64 = 30 * 86_400;
+
+/// How long a user has to accept a merchant's amount change (7 days)
+#[constant]
+pub const AMOUNT_CHANGE_WINDOW_SECONDS: i64 = 7 * 86_400;
+
+/// Most bytes an emergency pause reason may take
+#[constant]
+pub const MAX_PAUSE_REASON_LEN: usize = 64;
+
 /// Lutrii Recurring Payment Program
 ///
 /// Enables users to create non-custodial recurring subscriptions with:
@@ -76,8 +141,10 @@ pub mod lutrii_recurring {
         platform.total_subscriptions = 0;
         platform.total_transactions = 0;
         platform.bump = ctx.bumps.platform_state;
+        platform.version = PlatformState::CURRENT_VERSION;
 
         emit!(PlatformInitialized {
+            schema_version: EVENT_SCHEMA_VERSION,
             authority: platform.authority,
             fee_basis_points,
             daily_volume_limit,
@@ -88,17 +155,17 @@ pub mod lutrii_recurring {
         Ok(())
     }
 
-    /// Initialize platform configuration (Phase 1)
+    /// Initialize platform fee wallets (Phase 1, admin only, one-time)
     ///
-    /// Sets up fee collection wallets for USDC and USD1.
-    /// This is separate from platform_state and manages multi-token fee routing.
+    /// Sets the USDC and USD1 fee collection wallets on the platform state.
     pub fn initialize_config(ctx: Context<InitializeConfig>) -> Result<()> {
         instructions::initialize_config::handler(ctx)
     }
 
     /// Update platform configuration (Phase 1, admin only)
     ///
-    /// Allows updating fee wallets or transferring admin authority.
+    /// Records the settlement mints or transfers platform authority. Fee
+    /// wallets rotate through `schedule_config_change`.
     pub fn update_config(
         ctx: Context<UpdateConfig>,
         new_authority: Option<Pubkey>,
@@ -106,10 +173,55 @@ pub mod lutrii_recurring {
         instructions::update_config::handler(ctx, new_authority)
     }
 
+    /// Schedule a timelocked platform config change (admin only)
+    ///
+    /// Fee basis points, the daily volume limit, fee wallets and the delay
+    /// itself change only once the platform's config change delay has passed.
+    /// Needs council approval once an admin council exists.
+    pub fn schedule_config_change(
+        ctx: Context<ScheduleConfigChange>,
+        fee_basis_points: Option<u16>,
+        daily_volume_limit: Option<u64>,
+        config_change_delay_seconds: Option<i64>,
+    ) -> Result<()> {
+        let change = ConfigChange {
+            fee_basis_points,
+            daily_volume_limit,
+            config_change_delay_seconds,
+            ..ConfigChange::default()
+        };
+        instructions::schedule_config_change::handler(ctx, change)
+    }
+
+    /// Change the platform fee rate and its per-payment bounds (admin only)
+    ///
+    /// Same bounds as `initialize_platform`, with `min_fee <= max_fee`. The
+    /// change is scheduled behind the config change timelock like any other
+    /// and emits `PlatformParamsUpdated` when executed. Fee wallets are left
+    /// alone. Refused while another change is scheduled. Needs council
+    /// approval once an admin council exists.
+    pub fn update_platform_params(
+        ctx: Context<UpdatePlatformParams>,
+        fee_basis_points: u16,
+        min_fee: u64,
+        max_fee: u64,
+    ) -> Result<()> {
+        instructions::update_platform_params::handler(ctx, fee_basis_points, min_fee, max_fee)
+    }
+
+    /// Apply a scheduled config change after its delay (permissionless)
+    pub fn execute_config_change(ctx: Context<ExecuteConfigChange>) -> Result<()> {
+        instructions::execute_config_change::handler(ctx)
+    }
+
     /// Create a new subscription with token delegation
     ///
     /// User approves the subscription PDA to spend up to lifetime_cap on their behalf.
     /// This enables automated payments without requiring user signatures.
+    ///
+    /// `options` cover up-front charges, calendar billing, the schedule's
+    /// clock and free trials; see `CreateOptions`. The default bills one
+    /// period after creation, or on the merchant's billing anchor.
     pub fn create_subscription(
         ctx: Context<CreateSubscription>,
         amount: u64,
@@ -117,315 +229,53 @@ pub mod lutrii_recurring {
         max_per_transaction: u64,
         lifetime_cap: u64,
         merchant_name: String,
+        options: CreateOptions,
     ) -> Result<()> {
-        let platform = &ctx.accounts.platform_state;
-        require!(!platform.emergency_pause, ErrorCode::SystemPaused);
-
-        // ============================================================================
-        // MERCHANT VALIDATION - Verify merchant is registered and verified
-        // ============================================================================
-        let merchant_account = &ctx.accounts.merchant;
-
-        // Deserialize merchant account and validate
-        let merchant_data = MerchantAccount::try_deserialize(
-            &mut merchant_account.try_borrow_data()?.as_ref()
-        )?;
-
-        // Verify merchant is verified (not Unverified or Suspended)
-        require!(
-            merchant_data.verification_tier != VerificationTier::Unverified,
-            ErrorCode::MerchantNotVerified
-        );
-        require!(
-            merchant_data.verification_tier != VerificationTier::Suspended,
-            ErrorCode::MerchantSuspended
-        );
-
-        // Verify merchant PDA seeds match
-        let merchant_owner = merchant_data.owner;
-        let expected_merchant_key = Pubkey::create_program_address(
-            &[
-                b"merchant",
-                merchant_owner.as_ref(),
-                &[merchant_data.bump],
-            ],
-            &lutrii_merchant_registry::ID,
-        ).map_err(|_| error!(ErrorCode::InvalidMerchantAccount))?;
-
-        require!(
-            expected_merchant_key == merchant_account.key(),
-            ErrorCode::InvalidMerchantAccount
-        );
-
-        // Verify merchant_token_account owner matches merchant owner wallet
-        require!(
-            ctx.accounts.merchant_token_account.owner == merchant_owner,
-            ErrorCode::InvalidTokenAccountOwner
-        );
-
-        // Verify merchant_token_account mint matches
-        require!(
-            ctx.accounts.merchant_token_account.mint == ctx.accounts.mint.key(),
-            ErrorCode::InvalidMint
-        );
-
-        msg!(
-            "✅ Merchant validated: {} (tier: {:?})",
-            merchant_data.business_name,
-            merchant_data.verification_tier
-        );
-
-        // Validate inputs
-        require!(
-            frequency_seconds >= MIN_FREQUENCY_SECONDS,
-            ErrorCode::FrequencyTooShort
-        );
-        require!(
-            frequency_seconds <= MAX_FREQUENCY_SECONDS,
-            ErrorCode::FrequencyTooLong
-        );
-        require!(
-            !merchant_name.is_empty() && merchant_name.len() <= MAX_MERCHANT_NAME_LEN,
-            ErrorCode::InvalidMerchantName
-        );
-        require!(amount > 0, ErrorCode::AmountTooLow);
-        require!(
-            amount <= max_per_transaction,
-            ErrorCode::ExceedsTransactionCap
-        );
-        require!(amount <= lifetime_cap, ErrorCode::ExceedsLifetimeCap);
-
-        let subscription = &mut ctx.accounts.subscription;
-        let clock = Clock::get()?;
-
-        // Initialize subscription
-        subscription.user = ctx.accounts.user.key();
-        subscription.merchant = ctx.accounts.merchant.key();
-        subscription.user_token_account = ctx.accounts.user_token_account.key();
-        subscription.merchant_token_account = ctx.accounts.merchant_token_account.key();
-        subscription.amount = amount;
-        subscription.original_amount = amount; // Store for variance check
-        subscription.frequency_seconds = frequency_seconds;
-        subscription.last_payment = 0;
-        subscription.next_payment = clock.unix_timestamp + frequency_seconds;
-        subscription.total_paid = 0;
-        subscription.payment_count = 0;
-        subscription.is_active = true;
-        subscription.is_paused = false;
-        subscription.max_per_transaction = max_per_transaction;
-        subscription.lifetime_cap = lifetime_cap;
-        subscription.merchant_name = merchant_name.clone();
-        subscription.created_at = clock.unix_timestamp;
-        subscription.bump = ctx.bumps.subscription;
-
-        // Approve subscription PDA to spend user's tokens (delegation model)
-        // This allows the PDA to execute payments on user's behalf
-        approve(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Approve {
-                    to: ctx.accounts.user_token_account.to_account_info(),
-                    delegate: subscription.to_account_info(),
-                    authority: ctx.accounts.user.to_account_info(),
-                },
-            ),
+        ctx.accounts.create(
+            &ctx.bumps,
+            amount,
+            frequency_seconds,
+            max_per_transaction,
             lifetime_cap,
-        )?;
-
-        // Update platform stats
-        let platform_state = &mut ctx.accounts.platform_state;
-        platform_state.total_subscriptions = platform_state
-            .total_subscriptions
-            .checked_add(1)
-            .ok_or(ErrorCode::Overflow)?;
+            merchant_name,
+            options,
+        )
+    }
 
-        emit!(SubscriptionCreated {
-            subscription: subscription.key(),
-            user: subscription.user,
-            merchant: subscription.merchant,
+    /// Create a subscription that starts with a free trial
+    ///
+    /// Shorthand for `create_subscription` with `trial_seconds` and
+    /// `defer_delegation` set; see `CreateOptions` for the two kinds of trial.
+    pub fn create_trial_subscription(
+        ctx: Context<CreateSubscription>,
+        amount: u64,
+        frequency_seconds: i64,
+        max_per_transaction: u64,
+        lifetime_cap: u64,
+        merchant_name: String,
+        trial_seconds: i64,
+    ) -> Result<()> {
+        ctx.accounts.create(
+            &ctx.bumps,
             amount,
             frequency_seconds,
-            next_payment: subscription.next_payment,
-        });
-
-        msg!(
-            "Subscription created: {} USDC every {} seconds",
-            amount as f64 / 1_000_000.0,
-            frequency_seconds
-        );
-        Ok(())
+            max_per_transaction,
+            lifetime_cap,
+            merchant_name,
+            CreateOptions {
+                trial_seconds,
+                defer_delegation: true,
+                ..Default::default()
+            },
+        )
     }
 
     /// Execute a scheduled payment
     ///
     /// Can be called by anyone once a payment is due. Uses delegated authority
-    /// from subscription PDA to transfer tokens from user to merchant.
+    /// from the user delegate PDA to transfer tokens from user to merchant.
     pub fn execute_payment(ctx: Context<ExecutePayment>) -> Result<()> {
-        let subscription = &mut ctx.accounts.subscription;
-        let platform = &mut ctx.accounts.platform_state;
-        let clock = Clock::get()?;
-
-        // ============================================================================
-        // CHECKS - All validation logic
-        // ============================================================================
-
-        // REENTRANCY GUARD - Check payment not already in progress
-        require!(
-            !subscription.payment_in_progress,
-            ErrorCode::PaymentInProgress
-        );
-
-        // Auto-reset daily volume if 24h passed
-        if clock.unix_timestamp >= platform.last_volume_reset + SECONDS_PER_DAY {
-            platform.total_volume_24h = 0;
-            platform.last_volume_reset = clock.unix_timestamp;
-            msg!("Daily volume reset");
-        }
-
-        // Security checks
-        require!(!platform.emergency_pause, ErrorCode::SystemPaused);
-        require!(subscription.is_active, ErrorCode::SubscriptionInactive);
-        require!(!subscription.is_paused, ErrorCode::SubscriptionPaused);
-        require!(
-            clock.unix_timestamp >= subscription.next_payment,
-            ErrorCode::PaymentNotDue
-        );
-
-        // Check lifetime cap
-        let new_total = subscription
-            .total_paid
-            .checked_add(subscription.amount)
-            .ok_or(ErrorCode::Overflow)?;
-        require!(
-            new_total <= subscription.lifetime_cap,
-            ErrorCode::ExceedsLifetimeCap
-        );
-
-        // Check velocity limits
-        let new_volume = platform
-            .total_volume_24h
-            .checked_add(subscription.amount)
-            .ok_or(ErrorCode::Overflow)?;
-        require!(
-            new_volume <= platform.daily_volume_limit,
-            ErrorCode::VelocityExceeded
-        );
-
-        // Price variance protection (10% max change from original)
-        if subscription.payment_count > 0 {
-            let variance = subscription
-                .amount
-                .abs_diff(subscription.original_amount);
-            let max_variance = subscription
-                .original_amount
-                .checked_div(10)
-                .ok_or(ErrorCode::Overflow)?;
-            require!(
-                variance <= max_variance,
-                ErrorCode::PriceVarianceExceeded
-            );
-        }
-
-        // Calculate platform fee
-        let fee = calculate_fee(
-            subscription.amount,
-            platform.fee_basis_points,
-            platform.min_fee,
-            platform.max_fee,
-        )?;
-        let merchant_amount = subscription
-            .amount
-            .checked_sub(fee)
-            .ok_or(ErrorCode::InsufficientAmount)?;
-
-        // ============================================================================
-        // EFFECTS - Update state BEFORE external calls (CEI pattern)
-        // ============================================================================
-
-        // Set reentrancy guard
-        subscription.payment_in_progress = true;
-
-        // Update subscription state
-        subscription.last_payment = clock.unix_timestamp;
-        subscription.next_payment = clock.unix_timestamp + subscription.frequency_seconds;
-        subscription.total_paid = new_total;
-        subscription.payment_count = subscription
-            .payment_count
-            .checked_add(1)
-            .ok_or(ErrorCode::Overflow)?;
-
-        // Update platform stats
-        platform.total_volume_24h = new_volume;
-        platform.total_transactions = platform
-            .total_transactions
-            .checked_add(1)
-            .ok_or(ErrorCode::Overflow)?;
-
-        // ============================================================================
-        // INTERACTIONS - External calls AFTER state updates (CEI pattern)
-        // ============================================================================
-
-        // Generate PDA signer seeds
-        let seeds = &[
-            b"subscription",
-            subscription.user.as_ref(),
-            subscription.merchant.as_ref(),
-            &[subscription.bump],
-        ];
-        let signer = &[&seeds[..]];
-
-        // Transfer to merchant using delegated authority
-        transfer_checked(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                TransferChecked {
-                    from: ctx.accounts.user_token_account.to_account_info(),
-                    mint: ctx.accounts.mint.to_account_info(),
-                    to: ctx.accounts.merchant_token_account.to_account_info(),
-                    authority: subscription.to_account_info(), // PDA is delegate
-                },
-                signer,
-            ),
-            merchant_amount,
-            ctx.accounts.mint.decimals,
-        )?;
-
-        // Transfer platform fee
-        if fee > 0 {
-            transfer_checked(
-                CpiContext::new_with_signer(
-                    ctx.accounts.token_program.to_account_info(),
-                    TransferChecked {
-                        from: ctx.accounts.user_token_account.to_account_info(),
-                        mint: ctx.accounts.mint.to_account_info(),
-                        to: ctx.accounts.platform_fee_account.to_account_info(),
-                        authority: subscription.to_account_info(),
-                    },
-                    signer,
-                ),
-                fee,
-                ctx.accounts.mint.decimals,
-            )?;
-        }
-
-        // Clear reentrancy guard
-        subscription.payment_in_progress = false;
-
-        emit!(PaymentExecuted {
-            subscription: subscription.key(),
-            amount: subscription.amount,
-            fee,
-            merchant_received: merchant_amount,
-            payment_count: subscription.payment_count,
-            timestamp: clock.unix_timestamp,
-        });
-
-        msg!(
-            "✅ Payment executed: {} USDC to merchant (fee: {} USDC)",
-            merchant_amount as f64 / 1_000_000.0,
-            fee as f64 / 1_000_000.0
-        );
-        Ok(())
+        instructions::execute_payment::handler(ctx)
     }
 
     /// Pause a subscription
@@ -436,13 +286,22 @@ pub mod lutrii_recurring {
         let subscription = &mut ctx.accounts.subscription;
         require!(subscription.is_active, ErrorCode::SubscriptionInactive);
         require!(!subscription.is_paused, ErrorCode::AlreadyPaused);
+        let now = Clock::get()?.unix_timestamp;
 
+        let forecast_entry = subscription.forecast_entry();
         subscription.is_paused = true;
+        if let Some(due_bucket) = ctx.accounts.due_bucket.as_mut() {
+            due_bucket.remove(&subscription.key());
+        }
+        if let Some(revenue_forecast) = ctx.accounts.revenue_forecast.as_mut() {
+            revenue_forecast.reschedule(forecast_entry, None, now)?;
+        }
 
         emit!(SubscriptionPaused {
+            schema_version: EVENT_SCHEMA_VERSION,
             subscription: subscription.key(),
             user: subscription.user,
-            timestamp: Clock::get()?.unix_timestamp,
+            timestamp: now,
         });
 
         msg!("Subscription paused");
@@ -452,7 +311,8 @@ pub mod lutrii_recurring {
     /// Resume a paused subscription
     ///
     /// Resumes a paused subscription and schedules the next payment
-    /// based on the current time plus frequency.
+    /// based on the current time plus frequency, or on the next billing day
+    /// or weekday.
     pub fn resume_subscription(ctx: Context<ModifySubscription>) -> Result<()> {
         let subscription = &mut ctx.accounts.subscription;
         let clock = Clock::get()?;
@@ -461,13 +321,28 @@ pub mod lutrii_recurring {
         require!(subscription.is_paused, ErrorCode::NotPaused);
 
         subscription.is_paused = false;
-        subscription.next_payment = clock.unix_timestamp + subscription.frequency_seconds;
+        subscription.next_payment = schedule::next_due(
+            subscription.schedule_unit.now(&clock),
+            subscription.frequency_seconds,
+            subscription.billing_day,
+            subscription.billing_weekday,
+            subscription.utc_offset_seconds,
+        )
+        .map_err(ErrorCode::from)?;
+        if let Some(next_due_bucket) = ctx.accounts.next_due_bucket.as_mut() {
+            next_due_bucket.insert(subscription.key(), &subscription.merchant, subscription.next_payment)?;
+        }
+        if let Some(revenue_forecast) = ctx.accounts.revenue_forecast.as_mut() {
+            revenue_forecast.reschedule(None, subscription.forecast_entry(), clock.unix_timestamp)?;
+        }
 
         emit!(SubscriptionResumed {
+            schema_version: EVENT_SCHEMA_VERSION,
             subscription: subscription.key(),
             user: subscription.user,
             next_payment: subscription.next_payment,
             timestamp: clock.unix_timestamp,
+            utc_offset_seconds: subscription.utc_offset_seconds,
         });
 
         msg!("Subscription resumed");
@@ -476,34 +351,50 @@ pub mod lutrii_recurring {
 
     /// Cancel a subscription permanently
     ///
-    /// Revokes the token delegation and marks subscription as inactive.
-    /// User can close the account after cancellation to reclaim rent.
+    /// Removes the subscription's remaining allowance from the shared
+    /// delegation (revoking it once no subscriptions remain) and marks the
+    /// subscription as inactive. User can close the account after
+    /// cancellation to reclaim rent.
     pub fn cancel_subscription(ctx: Context<CancelSubscription>) -> Result<()> {
         let subscription = &mut ctx.accounts.subscription;
         require!(subscription.is_active, ErrorCode::SubscriptionInactive);
 
-        // Revoke delegation
-        revoke(CpiContext::new(
+        // Give back this subscription's share of the delegation
+        let user_delegate = &mut ctx.accounts.user_delegate;
+        user_delegate.remove_subscription(subscription);
+        user_delegate.sync_approval(
+            user_delegate.to_account_info(),
+            ctx.accounts.user_token_account.to_account_info(),
+            ctx.accounts.user.to_account_info(),
             ctx.accounts.token_program.to_account_info(),
-            Revoke {
-                source: ctx.accounts.user_token_account.to_account_info(),
-                authority: ctx.accounts.user.to_account_info(),
-            },
-        ))?;
+        )?;
 
+        let forecast_entry = subscription.forecast_entry();
         subscription.is_active = false;
         subscription.is_paused = false;
 
-        // Update platform stats
+        // Update platform stats and free the merchant slot
         let platform = &mut ctx.accounts.platform_state;
         platform.total_subscriptions = platform.total_subscriptions.saturating_sub(1);
+        MerchantPolicy::release_slot(&ctx.accounts.merchant_policy)?;
+        if let Some(user_stats) = ctx.accounts.user_stats.as_mut() {
+            user_stats.subscription_removed();
+        }
+        if let Some(due_bucket) = ctx.accounts.due_bucket.as_mut() {
+            due_bucket.remove(&subscription.key());
+        }
+        let now = Clock::get()?.unix_timestamp;
+        if let Some(revenue_forecast) = ctx.accounts.revenue_forecast.as_mut() {
+            revenue_forecast.reschedule(forecast_entry, None, now)?;
+        }
 
         emit!(SubscriptionCancelled {
+            schema_version: EVENT_SCHEMA_VERSION,
             subscription: subscription.key(),
             user: subscription.user,
             total_paid: subscription.total_paid,
             payment_count: subscription.payment_count,
-            timestamp: Clock::get()?.unix_timestamp,
+            timestamp: now,
         });
 
         msg!("Subscription cancelled");
@@ -547,25 +438,31 @@ pub mod lutrii_recurring {
                 ErrorCode::ExceedsLifetimeCap
             );
 
-            // Update delegation amount if increasing cap
-            if lifetime > subscription.lifetime_cap {
-                approve(
-                    CpiContext::new(
-                        ctx.accounts.token_program.to_account_info(),
-                        Approve {
-                            to: ctx.accounts.user_token_account.to_account_info(),
-                            delegate: subscription.to_account_info(),
-                            authority: ctx.accounts.user.to_account_info(),
-                        },
-                    ),
-                    lifetime,
+            // Swap this subscription's old allowance for the new one in the
+            // shared delegation; unconverted trials reserve at conversion
+            if !subscription.delegation_deferred {
+                let user_delegate = &mut ctx.accounts.user_delegate;
+                user_delegate.release(limits::remaining_allowance(
+                    subscription.lifetime_cap,
+                    subscription.total_paid,
+                ));
+                user_delegate
+                    .reserve(limits::remaining_allowance(lifetime, subscription.total_paid))?;
+                user_delegate.sync_approval(
+                    user_delegate.to_account_info(),
+                    ctx.accounts.user_token_account.to_account_info(),
+                    ctx.accounts.user.to_account_info(),
+                    ctx.accounts.token_program.to_account_info(),
                 )?;
+                subscription.delegation_healthy =
+                    user_delegate.outstanding_allowance >= subscription.amount;
             }
 
             subscription.lifetime_cap = lifetime;
         }
 
         emit!(LimitsUpdated {
+            schema_version: EVENT_SCHEMA_VERSION,
             subscription: subscription.key(),
             max_per_transaction: subscription.max_per_transaction,
             lifetime_cap: subscription.lifetime_cap,
@@ -578,405 +475,3500 @@ pub mod lutrii_recurring {
     /// Emergency pause (admin only)
     ///
     /// Immediately stops all payments system-wide. Should only be used
-    /// in case of detected exploit or critical bug.
-    pub fn emergency_pause(ctx: Context<AdminAction>) -> Result<()> {
+    /// in case of detected exploit or critical bug. The pause lapses on its
+    /// own after the platform's pause duration unless activated again, and
+    /// `reason` is stored on the platform state for anyone to read.
+    /// Needs council approval once an admin council exists.
+    pub fn emergency_pause(ctx: Context<AdminAction>, reason: String) -> Result<()> {
+        ctx.accounts.apply(CouncilAction::EmergencyPause { reason })
+    }
+
+    /// Pause or resume payments through one token program (admin only)
+    ///
+    /// Isolates an incident in the legacy SPL Token or the Token-2022
+    /// integration (e.g. a transfer hook exploit) without halting payments
+    /// in the other. Independent of `emergency_pause`.
+    pub fn set_token_program_pause(
+        ctx: Context<AdminAction>,
+        token_program: Pubkey,
+        paused: bool,
+    ) -> Result<()> {
         let platform = &mut ctx.accounts.platform_state;
-        platform.emergency_pause = true;
+        if token_program == anchor_spl::token::ID {
+            platform.spl_token_paused = paused;
+        } else if token_program == anchor_spl::token_2022::ID {
+            platform.token_2022_paused = paused;
+        } else {
+            return err!(ErrorCode::InvalidTokenProgram);
+        }
 
-        emit!(EmergencyPauseActivated {
+        emit!(TokenProgramPauseUpdated {
+            schema_version: EVENT_SCHEMA_VERSION,
+            token_program,
+            paused,
             timestamp: Clock::get()?.unix_timestamp,
-            reason: "Admin triggered emergency pause".to_string(),
         });
 
-        msg!("⚠️ EMERGENCY PAUSE ACTIVATED");
+        ctx.accounts.audit(AdminAuditAction::TokenProgramPause)?;
+
+        msg!(
+            "Payments through {} {}",
+            token_program,
+            if paused { "paused" } else { "resumed" }
+        );
         Ok(())
     }
 
-    /// Unpause system (admin only)
+    /// Hand platform administration to an SPL Governance realm (admin only)
     ///
-    /// Resumes normal operations after emergency pause. Resets volume counters.
-    pub fn emergency_unpause(ctx: Context<AdminAction>) -> Result<()> {
-        let platform = &mut ctx.accounts.platform_state;
-        let clock = Clock::get()?;
+    /// The authority becomes the governance's native treasury; admin
+    /// instructions then execute from passed proposals.
+    pub fn enable_governance(ctx: Context<EnableGovernance>, pause_guardian: Pubkey) -> Result<()> {
+        instructions::enable_governance::handler(ctx, pause_guardian)
+    }
 
-        platform.emergency_pause = false;
-        platform.total_volume_24h = 0;
-        platform.last_volume_reset = clock.unix_timestamp;
-        platform.failed_tx_count = 0;
+    /// Emergency pause by the governance pause guardian, without a vote
+    pub fn guardian_pause(ctx: Context<GuardianPause>, reason: String) -> Result<()> {
+        instructions::guardian_pause::handler(ctx, reason)
+    }
 
-        msg!("✅ System unpaused, counters reset");
-        Ok(())
+    /// Open the admin audit log (admin only)
+    ///
+    /// Admin instructions passed the log append their signer, action and
+    /// time to it, overwriting the oldest entry once full.
+    pub fn open_admin_audit_log(ctx: Context<OpenAdminAuditLog>) -> Result<()> {
+        instructions::open_admin_audit_log::handler(ctx)
     }
-}
 
-// ============================================================================
-// Account Structures
-// ============================================================================
+    /// Require M-of-N council approval for pauses and platform fees (admin only)
+    ///
+    /// One-way: the single-key instructions for those changes are refused
+    /// afterwards and the council's proposals make them instead.
+    pub fn create_admin_council(
+        ctx: Context<CreateAdminCouncil>,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        instructions::create_admin_council::handler(ctx, members, threshold)
+    }
 
-#[account]
-pub struct PlatformState {
-    pub authority: Pubkey,              // 32
-    pub daily_volume_limit: u64,        // 8
-    pub total_volume_24h: u64,          // 8
-    pub last_volume_reset: i64,         // 8
-    pub failed_tx_count: u16,           // 2
-    pub emergency_pause: bool,          // 1
+    /// Propose a pause, unpause or fee change to the admin council (member only)
+    pub fn propose_council_action(
+        ctx: Context<ProposeCouncilAction>,
+        action: CouncilAction,
+    ) -> Result<()> {
+        instructions::propose_council_action::handler(ctx, action)
+    }
+
+    /// Approve an open admin council proposal (member only)
+    pub fn approve_council_action(ctx: Context<ApproveCouncilAction>) -> Result<()> {
+        instructions::approve_council_action::handler(ctx)
+    }
+
+    /// Execute an admin council proposal with enough approvals (permissionless)
+    pub fn execute_council_action(ctx: Context<ExecuteCouncilAction>) -> Result<()> {
+        instructions::execute_council_action::handler(ctx)
+    }
+
+    /// Replace or remove the governance pause guardian (admin only)
+    pub fn set_pause_guardian(ctx: Context<SetPauseGuardian>, pause_guardian: Pubkey) -> Result<()> {
+        instructions::set_pause_guardian::handler(ctx, pause_guardian)
+    }
+
+    /// Cap what one governance proposal may spend from fee treasuries (admin only)
+    pub fn set_treasury_spend_cap(
+        ctx: Context<SetTreasurySpendCap>,
+        max_spend_per_proposal: u64,
+    ) -> Result<()> {
+        instructions::set_treasury_spend_cap::handler(ctx, max_spend_per_proposal)
+    }
+
+    /// Withdraw fee revenue under an executing proposal (governance only)
+    ///
+    /// The spend is logged per proposal and capped at the governance's
+    /// `max_spend_per_proposal`.
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        instructions::withdraw_treasury::handler(ctx, amount)
+    }
+
+    /// Buy back tokens with fee revenue under an executing proposal (governance only)
+    ///
+    /// Routes through an allowlisted swap program passed as remaining
+    /// accounts, with the same per-proposal log and cap as withdrawals.
+    pub fn treasury_buyback<'info>(
+        ctx: Context<'_, '_, 'info, 'info, TreasuryBuybackAccounts<'info>>,
+        amount_in: u64,
+        min_amount_out: u64,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::treasury_buyback::handler(ctx, amount_in, min_amount_out, data)
+    }
+
+    /// Unpause system (admin only)
+    ///
+    /// Resumes normal operations after emergency pause. Resets volume counters.
+    /// Needs council approval once an admin council exists.
+    pub fn emergency_unpause(ctx: Context<AdminAction>) -> Result<()> {
+        ctx.accounts.apply(CouncilAction::EmergencyUnpause)
+    }
+
+    /// Set global subscription caps (admin only)
+    ///
+    /// Launch-ramp risk control: limits total active subscriptions and new
+    /// subscriptions per rolling 24h window. Zero disables a cap.
+    pub fn set_subscription_caps(
+        ctx: Context<AdminAction>,
+        max_active_subscriptions: u64,
+        max_daily_new_subscriptions: u32,
+    ) -> Result<()> {
+        let platform = &mut ctx.accounts.platform_state;
+        platform.max_active_subscriptions = max_active_subscriptions;
+        platform.max_daily_new_subscriptions = max_daily_new_subscriptions;
+
+        emit!(SubscriptionCapsUpdated {
+            schema_version: EVENT_SCHEMA_VERSION,
+            max_active_subscriptions,
+            max_daily_new_subscriptions,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        ctx.accounts.audit(AdminAuditAction::LimitChange)?;
+
+        msg!(
+            "Subscription caps updated: {} active, {} new per day",
+            max_active_subscriptions,
+            max_daily_new_subscriptions
+        );
+        Ok(())
+    }
+
+    /// Set how long an emergency pause lasts before lapsing (admin only)
+    ///
+    /// Applies from the next activation; a pause already in force keeps
+    /// its expiry.
+    pub fn set_pause_duration(ctx: Context<AdminAction>, pause_duration_seconds: i64) -> Result<()> {
+        require!(
+            (MIN_PAUSE_DURATION_SECONDS..=MAX_PAUSE_DURATION_SECONDS).contains(&pause_duration_seconds),
+            ErrorCode::InvalidPauseDuration
+        );
+        let platform = &mut ctx.accounts.platform_state;
+        platform.pause_duration_seconds = pause_duration_seconds;
+
+        emit!(PauseDurationUpdated {
+            schema_version: EVENT_SCHEMA_VERSION,
+            pause_duration_seconds,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        ctx.accounts.audit(AdminAuditAction::PauseDurationChange)?;
+
+        msg!("Emergency pauses now last {}s", pause_duration_seconds);
+        Ok(())
+    }
+
+    /// Set how long a due payment stays collectible (admin only)
+    ///
+    /// A cycle still uncollected this many seconds after it fell due (keeper
+    /// outage) is not charged late: the next execution skips it, or cancels
+    /// the subscription under `MissedPaymentPolicy::AutoCancel`. Zero keeps
+    /// payments collectible indefinitely.
+    pub fn set_collection_window(
+        ctx: Context<AdminAction>,
+        collection_window_seconds: i64,
+    ) -> Result<()> {
+        require!(collection_window_seconds >= 0, ErrorCode::InvalidCollectionWindow);
+        let platform = &mut ctx.accounts.platform_state;
+        platform.collection_window_seconds = collection_window_seconds;
+
+        emit!(CollectionWindowUpdated {
+            schema_version: EVENT_SCHEMA_VERSION,
+            collection_window_seconds,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        ctx.accounts.audit(AdminAuditAction::LimitChange)?;
+
+        msg!("Collection window updated: {}s", collection_window_seconds);
+        Ok(())
+    }
+
+    /// Reserve a share of the daily volume limit for priority merchants (admin only)
+    ///
+    /// Once standard traffic has used all but `priority_reserve_bps` of
+    /// `daily_volume_limit`, only Community-tier merchants' payments are
+    /// collected until the daily reset. Zero shares the whole limit.
+    pub fn set_priority_reserve(
+        ctx: Context<AdminAction>,
+        priority_reserve_bps: u16,
+    ) -> Result<()> {
+        require!(priority_reserve_bps <= 10_000, ErrorCode::InvalidPriorityReserve);
+        let platform = &mut ctx.accounts.platform_state;
+        platform.priority_reserve_bps = priority_reserve_bps;
+
+        emit!(PriorityReserveUpdated {
+            schema_version: EVENT_SCHEMA_VERSION,
+            priority_reserve_bps,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        ctx.accounts.audit(AdminAuditAction::LimitChange)?;
+
+        msg!("Priority volume reserve updated: {} bps", priority_reserve_bps);
+        Ok(())
+    }
+
+    /// Set how platform fees are rounded and what happens to dust fees (admin only)
+    ///
+    /// Recurring fees below `fee_dust_threshold` are waived or carried on the
+    /// subscription until they add up to the threshold. The threshold may not
+    /// exceed `max_fee`. One-off charges (setup fees, invoices) only round.
+    /// Needs council approval once an admin council exists.
+    pub fn set_fee_policy(
+        ctx: Context<AdminAction>,
+        fee_rounding: FeeRounding,
+        fee_dust_policy: FeeDustPolicy,
+        fee_dust_threshold: u64,
+    ) -> Result<()> {
+        ctx.accounts.apply(CouncilAction::SetFeePolicy {
+            fee_rounding,
+            fee_dust_policy,
+            fee_dust_threshold,
+        })
+    }
+
+    /// Pay keepers a share of the platform fee for executing payments (admin only)
+    ///
+    /// Executors that sign `execute_payment` and pass a token account in the
+    /// payment mint receive `flat_reward` plus `reward_bps` of the charge,
+    /// capped at the fee. Both zero turns rewards off. Needs council
+    /// approval once an admin council exists.
+    pub fn set_executor_reward(
+        ctx: Context<AdminAction>,
+        reward_bps: u16,
+        flat_reward: u64,
+    ) -> Result<()> {
+        ctx.accounts.apply(CouncilAction::SetExecutorReward { reward_bps, flat_reward })
+    }
+
+    /// Waive the platform fee on a user's first subscription (admin only)
+    ///
+    /// The first `free_payments` payments of the first subscription recorded
+    /// in a user's stats account pay no platform fee; 0 turns the waiver off.
+    /// Needs council approval once an admin council exists.
+    pub fn set_first_subscription_waiver(
+        ctx: Context<AdminAction>,
+        free_payments: u8,
+    ) -> Result<()> {
+        ctx.accounts.apply(CouncilAction::SetFirstSubscriptionWaiver { free_payments })
+    }
+
+    /// Schedule the platform's promotional zero-fee windows (admin only)
+    pub fn configure_fee_holidays(
+        ctx: Context<ConfigureFeeHolidays>,
+        holidays: Vec<FeeHoliday>,
+    ) -> Result<()> {
+        instructions::configure_fee_holidays::handler(ctx, holidays)
+    }
+
+    /// Mark a subscription or a merchant fee-exempt, or clear it (admin only)
+    pub fn set_fee_exemption(ctx: Context<SetFeeExemption>, fee_exempt: bool) -> Result<()> {
+        instructions::set_fee_exemption::handler(ctx, fee_exempt)
+    }
+
+    /// Attach a negotiated fee schedule to a merchant, or clear it (admin only)
+    pub fn set_merchant_fee_schedule(
+        ctx: Context<SetMerchantFeeSchedule>,
+        fee_schedule: Option<FeeSchedule>,
+    ) -> Result<()> {
+        instructions::set_merchant_fee_schedule::handler(ctx, fee_schedule)
+    }
+
+    /// Set whether unverified merchants may accept new subscriptions (admin only)
+    ///
+    /// Disabled by default. Suspended merchants are always rejected.
+    pub fn set_merchant_verification_policy(
+        ctx: Context<AdminAction>,
+        allow_unverified_merchants: bool,
+    ) -> Result<()> {
+        let platform = &mut ctx.accounts.platform_state;
+        platform.allow_unverified_merchants = allow_unverified_merchants;
+
+        emit!(MerchantVerificationPolicyUpdated {
+            schema_version: EVENT_SCHEMA_VERSION,
+            allow_unverified_merchants,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        ctx.accounts.audit(AdminAuditAction::LimitChange)?;
+
+        msg!(
+            "Merchant verification policy updated: unverified merchants {}",
+            if allow_unverified_merchants { "allowed" } else { "rejected" }
+        );
+        Ok(())
+    }
+
+    /// Add a wallet to the compliance denylist (admin only)
+    ///
+    /// Denied wallets cannot create subscriptions or receive payments.
+    pub fn add_to_denylist(ctx: Context<AddToDenylist>, wallet: Pubkey) -> Result<()> {
+        instructions::add_to_denylist::handler(ctx, wallet)
+    }
+
+    /// Remove a wallet from the compliance denylist (admin only)
+    pub fn remove_from_denylist(ctx: Context<RemoveFromDenylist>) -> Result<()> {
+        instructions::remove_from_denylist::handler(ctx)
+    }
+
+    /// Block a subscriber wallet from a merchant (merchant owner only)
+    ///
+    /// Blocked users cannot subscribe, and payments on their existing
+    /// subscription are refused pending cancellation.
+    pub fn block_user(ctx: Context<BlockUser>, user: Pubkey) -> Result<()> {
+        instructions::block_user::handler(ctx, user)
+    }
+
+    /// Lift a merchant's block on a subscriber wallet (merchant owner only)
+    pub fn unblock_user(ctx: Context<UnblockUser>) -> Result<()> {
+        instructions::unblock_user::handler(ctx)
+    }
+
+    /// Create or update a merchant's subscription policy (merchant owner only)
+    ///
+    /// Enables allowlist-only mode for B2B contracts, private betas and
+    /// KYC-gated services, and caps active subscribers for capacity-constrained
+    /// offerings. Also sets how many days subscribers may snooze a due payment
+    /// and the billing anchor new subscriptions align to, how swap-settled
+    /// payments fall back when no swap route is available, and who pays the
+    /// platform fee on new subscriptions.
+    pub fn configure_merchant_policy(
+        ctx: Context<ConfigureMerchantPolicy>,
+        allowlist_only: bool,
+        max_active_subscribers: u32,
+        max_snooze_days: u8,
+        billing_anchor: i64,
+        swap_fallback: SwapFallback,
+        fee_bearer: FeeBearer,
+    ) -> Result<()> {
+        instructions::configure_merchant_policy::handler(
+            ctx,
+            allowlist_only,
+            max_active_subscribers,
+            max_snooze_days,
+            billing_anchor,
+            swap_fallback,
+            fee_bearer,
+        )
+    }
+
+    /// Pre-approve a wallet to subscribe to an allowlist-only merchant (merchant owner only)
+    pub fn allow_user(ctx: Context<AllowUser>, user: Pubkey) -> Result<()> {
+        instructions::allow_user::handler(ctx, user)
+    }
+
+    /// Remove a wallet from a merchant's allowlist (merchant owner only)
+    pub fn disallow_user(ctx: Context<DisallowUser>) -> Result<()> {
+        instructions::disallow_user::handler(ctx)
+    }
+
+    /// Migrate platform state to the current layout (admin only)
+    ///
+    /// Reallocates the platform PDA in place and bumps its version byte so
+    /// fields can be added without redeploying or re-initializing state.
+    /// If the legacy `platform_config` account is supplied, its fee wallets
+    /// are merged into the platform state and the account is closed.
+    pub fn migrate_platform_state(ctx: Context<MigratePlatformState>) -> Result<()> {
+        instructions::migrate_platform_state::handler(ctx)
+    }
+
+    /// Query subscription payment health (read-only)
+    ///
+    /// Returns due/overdue/delinquent status, remaining lifetime allowance,
+    /// remaining delegation and the next charge via return data. Intended to
+    /// be called through transaction simulation.
+    pub fn get_subscription_status(
+        ctx: Context<GetSubscriptionStatus>,
+    ) -> Result<SubscriptionStatus> {
+        instructions::get_subscription_status::handler(ctx)
+    }
+
+    /// Check a subscription's delegation health (permissionless)
+    ///
+    /// Compares the user's token account delegate and delegated amount
+    /// against the next payment and records the result on the subscription.
+    pub fn check_delegation(ctx: Context<CheckDelegation>) -> Result<()> {
+        instructions::check_delegation::handler(ctx)
+    }
+
+    /// Preview the next payment's breakdown and guards (read-only)
+    ///
+    /// Intended for simulation: returns the exact charge, fee and merchant
+    /// amount along with which execute_payment guards currently pass.
+    pub fn preview_payment(ctx: Context<PreviewPayment>) -> Result<PaymentPreview> {
+        instructions::preview_payment::handler(ctx)
+    }
+
+    /// Remaining lifetime allowance and delegation for a subscription (read-only)
+    pub fn get_remaining_allowance(
+        ctx: Context<GetRemainingAllowance>,
+    ) -> Result<RemainingAllowance> {
+        instructions::get_remaining_allowance::handler(ctx)
+    }
+
+    /// Whether `user` has an active, paid-up subscription to `merchant` (read-only)
+    ///
+    /// `nonce` selects which of the pair's subscriptions, optionally only on
+    /// `plan`. Returns a `SubscriptionAccess` via return data for other
+    /// programs to gate on through CPI, or clients through simulation.
+    pub fn verify_active_subscription(
+        ctx: Context<VerifyActiveSubscription>,
+        _user: Pubkey,
+        _merchant: Pubkey,
+        _nonce: u32,
+        plan: Option<Pubkey>,
+    ) -> Result<SubscriptionAccess> {
+        instructions::verify_active_subscription::handler(ctx, plan)
+    }
+
+    /// Open the subscription nonce allocator of a pair that already has a
+    /// subscription from before nonces (user only)
+    pub fn open_subscription_counter(ctx: Context<OpenSubscriptionCounter>) -> Result<()> {
+        instructions::open_subscription_counter::handler(ctx)
+    }
+
+    /// Create the caller's spending analytics account
+    ///
+    /// Existing active subscriptions may be passed as remaining accounts
+    /// to seed the active subscription count.
+    pub fn init_user_stats(ctx: Context<InitUserStats>) -> Result<()> {
+        instructions::init_user_stats::handler(ctx)
+    }
+
+    /// Enable or disable batched settlement for a mint (merchant owner only)
+    ///
+    /// While enabled, keepers route the merchant leg of each execution into
+    /// a program vault that `sweep_settlement` empties in one transfer.
+    pub fn configure_settlement(ctx: Context<ConfigureSettlement>, enabled: bool) -> Result<()> {
+        instructions::configure_settlement::handler(ctx, enabled)
+    }
+
+    /// Move a settlement vault's balance to the merchant (permissionless)
+    ///
+    /// A tracked refund liability keeps its reserve in the vault.
+    pub fn sweep_settlement(ctx: Context<SweepSettlement>) -> Result<()> {
+        instructions::sweep_settlement::handler(ctx)
+    }
+
+    /// Create or update the per-mint fee treasury and its split (admin only)
+    ///
+    /// While enabled, keepers pay execution fees into the treasury vault;
+    /// `sweep_fees` distributes them once per sweep interval.
+    pub fn configure_fee_treasury(
+        ctx: Context<ConfigureFeeTreasury>,
+        shares: Vec<FeeShare>,
+        sweep_interval: i64,
+        enabled: bool,
+    ) -> Result<()> {
+        instructions::configure_fee_treasury::handler(ctx, shares, sweep_interval, enabled)
+    }
+
+    /// Distribute accrued fees per the configured split (permissionless)
+    pub fn sweep_fees<'info>(ctx: Context<'_, '_, 'info, 'info, SweepFees<'info>>) -> Result<()> {
+        instructions::sweep_fees::handler(ctx)
+    }
+
+    /// Set the lending allowlist and yield attribution for held funds (admin only)
+    pub fn configure_yield(
+        ctx: Context<ConfigureYield>,
+        lending_programs: Vec<Pubkey>,
+        merchant_yield_bps: u16,
+        enabled: bool,
+    ) -> Result<()> {
+        instructions::configure_yield::handler(ctx, lending_programs, merchant_yield_bps, enabled)
+    }
+
+    /// Deposit settlement vault funds into an allowlisted lending protocol (merchant owner only)
+    pub fn deploy_held_funds<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DeployHeldFunds<'info>>,
+        amount: u64,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::deploy_held_funds::handler(ctx, amount, data)
+    }
+
+    /// Withdraw lent funds back into the settlement vault, splitting yield (merchant owner only)
+    pub fn withdraw_held_funds<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawHeldFunds<'info>>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::withdraw_held_funds::handler(ctx, data)
+    }
+
+    /// Allow or update a token users may pay with through swaps (admin only)
+    pub fn configure_swap_asset(
+        ctx: Context<ConfigureSwapAsset>,
+        price_feed: Pubkey,
+        max_slippage_bps: u16,
+        max_price_age: i64,
+        max_confidence_bps: u16,
+        enabled: bool,
+    ) -> Result<()> {
+        instructions::configure_swap_asset::handler(
+            ctx,
+            price_feed,
+            max_slippage_bps,
+            max_price_age,
+            max_confidence_bps,
+            enabled,
+        )
+    }
+
+    /// List, update or disable a mint accepted for payments (admin only)
+    pub fn configure_accepted_mint(
+        ctx: Context<ConfigureAcceptedMint>,
+        price_feed: Pubkey,
+        min_amount: u64,
+        enabled: bool,
+    ) -> Result<()> {
+        instructions::configure_accepted_mint::handler(ctx, price_feed, min_amount, enabled)
+    }
+
+    /// Set the swap program allowlist and blocked route mints (admin only)
+    pub fn configure_swap_config(
+        ctx: Context<ConfigureSwapConfig>,
+        swap_programs: Vec<Pubkey>,
+        blocked_mints: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::configure_swap_config::handler(ctx, swap_programs, blocked_mints)
+    }
+
+    /// Execute a payment from a swap asset, settling in the merchant's mint
+    ///
+    /// Permissionless like `execute_payment`; the caller supplies the Jupiter
+    /// route, whose input is bounded by the asset's oracle price. Without a
+    /// route (empty `data`) the merchant's swap fallback applies.
+    pub fn execute_payment_with_swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecutePaymentWithSwap<'info>>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        instructions::execute_payment_with_swap::handler(ctx, data)
+    }
+
+    /// Cancel a subscription and close it in one step (user only)
+    ///
+    /// Same effects as `cancel_subscription` followed by `close_subscription`.
+    pub fn cancel_and_close(ctx: Context<CancelAndClose>) -> Result<()> {
+        instructions::cancel_and_close::handler(ctx)
+    }
+
+    /// Deactivate a subscription for compliance (admin only)
+    ///
+    /// Stops executions immediately; the user still revokes the token
+    /// approval and closes the account themselves.
+    pub fn force_cancel_subscription(
+        ctx: Context<ForceCancelSubscription>,
+        reason: ForceCancelReason,
+    ) -> Result<()> {
+        instructions::force_cancel_subscription::handler(ctx, reason)
+    }
+
+    /// Cancel the listed subscriptions and revoke their approvals at once (user only)
+    ///
+    /// Panic button for a compromised merchant; subscriptions are passed as
+    /// remaining accounts.
+    pub fn emergency_revoke_all<'info>(
+        ctx: Context<'_, '_, 'info, 'info, EmergencyRevokeAll<'info>>,
+    ) -> Result<()> {
+        instructions::emergency_revoke_all::handler(ctx)
+    }
+
+    /// Open a merchant's due-date bucket for keeper scans (permissionless)
+    pub fn open_due_bucket(ctx: Context<OpenDueBucket>, day: i64) -> Result<()> {
+        instructions::open_due_bucket::handler(ctx, day)
+    }
+
+    /// Close an empty, past due-date bucket (permissionless)
+    pub fn close_due_bucket(ctx: Context<CloseDueBucket>) -> Result<()> {
+        instructions::close_due_bucket::handler(ctx)
+    }
+
+    /// Offer a subscription to a user on fixed terms (merchant owner only)
+    pub fn create_offer(
+        ctx: Context<CreateOffer>,
+        user: Pubkey,
+        terms: OfferTerms,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::create_offer::handler(ctx, user, terms, expires_at)
+    }
+
+    /// Accept a merchant offer, creating the subscription on its terms (user only)
+    pub fn accept_offer(ctx: Context<AcceptOffer>) -> Result<()> {
+        instructions::accept_offer::handler(ctx)
+    }
+
+    /// Withdraw an unaccepted offer (merchant owner only)
+    pub fn cancel_offer(ctx: Context<CancelOffer>) -> Result<()> {
+        instructions::cancel_offer::handler(ctx)
+    }
+
+    /// Publish an offer open to any wallet with limited inventory (merchant owner only)
+    pub fn publish_offer(
+        ctx: Context<PublishOffer>,
+        offer_id: u64,
+        terms: OfferTerms,
+        expires_at: i64,
+        max_acceptances: u32,
+    ) -> Result<()> {
+        instructions::publish_offer::handler(ctx, offer_id, terms, expires_at, max_acceptances)
+    }
+
+    /// Close a lapsed offer, refunding the merchant owner (permissionless)
+    pub fn close_expired_offer(ctx: Context<CloseExpiredOffer>) -> Result<()> {
+        instructions::close_expired_offer::handler(ctx)
+    }
+
+    /// End a trial by approving its allowance (user only)
+    ///
+    /// Billing starts at the end of the trial period; see `CreateOptions`
+    /// for the two kinds of trial.
+    pub fn convert_trial(ctx: Context<ConvertTrial>) -> Result<()> {
+        instructions::convert_trial::handler(ctx)
+    }
+
+    /// Choose how missed payment cycles are handled (user only)
+    ///
+    /// Skip them once the collection window closes (default), accumulate up
+    /// to a bound and catch up, or cancel the subscription.
+    pub fn set_missed_payment_policy(
+        ctx: Context<SetMissedPaymentPolicy>,
+        policy: MissedPaymentPolicy,
+    ) -> Result<()> {
+        instructions::set_missed_payment_policy::handler(ctx, policy)
+    }
+
+    /// Set how failed collections are retried for a merchant (merchant owner only)
+    ///
+    /// Failed collections back off from `base_delay_seconds`, doubling each
+    /// time; after `max_failed_attempts` in a row the subscription is paused
+    /// or cancelled per `delinquency_action`.
+    pub fn set_retry_policy(ctx: Context<SetRetryPolicy>, retry_policy: RetryPolicy) -> Result<()> {
+        instructions::set_retry_policy::handler(ctx, retry_policy)
+    }
+
+    /// Bring a subscription forward to the current account layout (permissionless)
+    ///
+    /// Required before payments resume on subscriptions created under an
+    /// older layout version.
+    pub fn migrate_subscription(ctx: Context<MigrateSubscription>) -> Result<()> {
+        instructions::migrate_subscription::handler(ctx)
+    }
+
+    /// Reserve a due payment for the calling keeper (permissionless)
+    ///
+    /// For `EXECUTION_CLAIM_SLOTS` only the claimant can execute it, so
+    /// competing keepers stop racing for the same payment.
+    pub fn claim_execution(ctx: Context<ClaimExecution>) -> Result<()> {
+        instructions::claim_execution::handler(ctx)
+    }
+
+    /// Start tracking a merchant's monthly volume in a mint (permissionless)
+    ///
+    /// Payments count toward fee rebates once this account exists.
+    pub fn open_merchant_volume(ctx: Context<OpenMerchantVolume>) -> Result<()> {
+        instructions::open_merchant_volume::handler(ctx)
+    }
+
+    /// Start a merchant's reporting totals for a calendar year (permissionless)
+    ///
+    /// Payments and invoices in the mint count toward the year once this
+    /// account exists and is passed along.
+    pub fn open_merchant_report(ctx: Context<OpenMerchantReport>, year: u16) -> Result<()> {
+        instructions::open_merchant_report::handler(ctx, year)
+    }
+
+    /// Open a merchant's statement for a calendar month (permissionless)
+    pub fn open_merchant_statement(
+        ctx: Context<OpenMerchantStatement>,
+        year: u16,
+        month: u8,
+    ) -> Result<()> {
+        instructions::open_merchant_statement::handler(ctx, year, month)
+    }
+
+    /// Close the books on a finished month's statement (permissionless)
+    pub fn close_merchant_statement(ctx: Context<CloseMerchantStatement>) -> Result<()> {
+        instructions::close_merchant_statement::handler(ctx)
+    }
+
+    /// Track a merchant's refund exposure and reserve against it (admin only)
+    pub fn configure_refund_liability(
+        ctx: Context<ConfigureRefundLiability>,
+        window_days: u16,
+        reserve_bps: u16,
+    ) -> Result<()> {
+        instructions::configure_refund_liability::handler(ctx, window_days, reserve_bps)
+    }
+
+    /// Start a merchant's 30-day revenue forecast in a mint (permissionless)
+    ///
+    /// Instructions that create, pay, pause or cancel a subscription move
+    /// its next payment in the forecast when it is passed along.
+    pub fn open_revenue_forecast(ctx: Context<OpenRevenueForecast>) -> Result<()> {
+        instructions::open_revenue_forecast::handler(ctx)
+    }
+
+    /// Recompute a revenue forecast's projection at the current time (permissionless)
+    pub fn refresh_revenue_forecast(ctx: Context<RefreshRevenueForecast>) -> Result<()> {
+        instructions::refresh_revenue_forecast::handler(ctx)
+    }
+
+    /// Set a mint's merchant fee rebate schedule (admin only)
+    pub fn configure_rebates(ctx: Context<ConfigureRebates>, tiers: Vec<RebateTier>) -> Result<()> {
+        instructions::configure_rebates::handler(ctx, tiers)
+    }
+
+    /// Claim the fee rebate for the last finished monthly period (merchant only)
+    ///
+    /// Merchants whose volume reached a rebate tier get that share of the
+    /// period's platform fees back from the fee treasury, once per period.
+    pub fn claim_rebate(ctx: Context<ClaimRebate>) -> Result<()> {
+        instructions::claim_rebate::handler(ctx)
+    }
+
+    /// Cap the swap slippage of a subscription's payments (user or merchant)
+    ///
+    /// Swap-settled payments needing more fail with `SlippageExceeded`; 0
+    /// falls back to the swap asset's allowance.
+    pub fn set_swap_slippage(ctx: Context<SetSwapSlippage>, max_slippage_bps: u16) -> Result<()> {
+        instructions::set_swap_slippage::handler(ctx, max_slippage_bps)
+    }
+
+    /// Re-approve the remaining lifetime allowance (user only)
+    ///
+    /// Fixes a delegation consumed or clobbered by another dApp without
+    /// changing the subscription's caps.
+    pub fn refresh_delegation(ctx: Context<RefreshDelegation>) -> Result<()> {
+        instructions::refresh_delegation::handler(ctx)
+    }
+
+    /// Move a subscription to another token account (user only)
+    ///
+    /// Revokes or shrinks the old approval, approves the new account and
+    /// repoints the subscription in one step, keeping its history and caps.
+    pub fn migrate_token_account(ctx: Context<MigrateTokenAccount>) -> Result<()> {
+        instructions::migrate_token_account::handler(ctx)
+    }
+
+    /// Prepay a subscription into its escrow vault (user only)
+    ///
+    /// Executions passed the escrow draw from it instead of the delegation.
+    pub fn deposit_escrow(ctx: Context<DepositEscrow>, amount: u64) -> Result<()> {
+        instructions::deposit_escrow::handler(ctx, amount)
+    }
+
+    /// Withdraw unspent prepaid funds from a subscription's escrow (user only)
+    pub fn withdraw_escrow(ctx: Context<WithdrawEscrow>, amount: u64) -> Result<()> {
+        instructions::withdraw_escrow::handler(ctx, amount)
+    }
+
+    /// Cap the usage the merchant may bill per period (user only)
+    ///
+    /// Enables `execute_metered_payment` on the subscription; 0 disables it.
+    pub fn configure_usage_cap(ctx: Context<ConfigureUsageCap>, period_cap: u64) -> Result<()> {
+        instructions::configure_usage_cap::handler(ctx, period_cap)
+    }
+
+    /// Bill actual usage within the user's caps (merchant owner only)
+    ///
+    /// Bounded by `max_per_transaction`, the per-period usage cap and the
+    /// lifetime cap, alongside the fixed-amount schedule.
+    pub fn execute_metered_payment(ctx: Context<ExecuteMeteredPayment>, amount: u64) -> Result<()> {
+        instructions::execute_metered_payment::handler(ctx, amount)
+    }
+
+    /// Defer a due payment by up to the merchant's snooze limit (user only)
+    ///
+    /// Once per cycle, for payday-aligned flexibility without skipping.
+    pub fn snooze_payment(ctx: Context<SnoozePayment>, days: u8) -> Result<()> {
+        instructions::snooze_payment::handler(ctx, days)
+    }
+
+    /// Create a subscription plan (merchant owner only)
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_plan(
+        ctx: Context<CreatePlan>,
+        plan_id: u64,
+        name: String,
+        price: u64,
+        frequency_seconds: i64,
+        mint: Pubkey,
+        trial_period_seconds: i64,
+    ) -> Result<()> {
+        instructions::create_plan::handler(
+            ctx,
+            plan_id,
+            name,
+            price,
+            frequency_seconds,
+            mint,
+            trial_period_seconds,
+        )
+    }
+
+    /// Create a promo code discounting new subscribers' first payments (merchant owner only)
+    ///
+    /// Redeemed by passing the promo to `create_subscription`, up to
+    /// `max_redemptions` times before `expires_at`.
+    pub fn create_promo(
+        ctx: Context<CreatePromo>,
+        code: String,
+        discount: PromoDiscount,
+        discounted_payments: u16,
+        max_redemptions: u32,
+        expires_at: i64,
+    ) -> Result<()> {
+        instructions::create_promo::handler(
+            ctx,
+            code,
+            discount,
+            discounted_payments,
+            max_redemptions,
+            expires_at,
+        )
+    }
+
+    /// Reprice or deactivate a plan (merchant owner only)
+    ///
+    /// Bound subscriptions follow the new price within the variance window.
+    pub fn update_plan(ctx: Context<UpdatePlan>, price: u64, is_active: bool) -> Result<()> {
+        instructions::update_plan::handler(ctx, price, is_active)
+    }
+
+    /// Subscribe on a plan's terms (user only)
+    ///
+    /// The user chooses only the spending caps; the subscription is bound
+    /// to the plan.
+    pub fn create_subscription_from_plan(
+        ctx: Context<CreateSubscriptionFromPlan>,
+        max_per_transaction: u64,
+        lifetime_cap: u64,
+    ) -> Result<()> {
+        instructions::create_subscription_from_plan::handler(ctx, max_per_transaction, lifetime_cap)
+    }
+
+    /// Move a subscription to another plan of the same merchant (user only)
+    ///
+    /// Preserves payment history and prorates the rest of a paid cycle onto
+    /// the next payment.
+    pub fn change_plan(ctx: Context<ChangePlan>) -> Result<()> {
+        instructions::change_plan::handler(ctx)
+    }
+
+    /// Propose a new amount for a subscription (merchant owner only)
+    ///
+    /// Takes effect only once the user accepts it, within
+    /// `AMOUNT_CHANGE_WINDOW_SECONDS`.
+    pub fn propose_amount_change(ctx: Context<ProposeAmountChange>, new_amount: u64) -> Result<()> {
+        instructions::propose_amount_change::handler(ctx, new_amount)
+    }
+
+    /// Accept the merchant's pending amount change (user only)
+    ///
+    /// The new amount also becomes the baseline for price variance checks.
+    pub fn accept_amount_change(ctx: Context<AcceptAmountChange>, new_amount: u64) -> Result<()> {
+        instructions::accept_amount_change::handler(ctx, new_amount)
+    }
+
+    /// Issue an itemized invoice to a user (merchant owner only)
+    pub fn create_invoice(
+        ctx: Context<CreateInvoice>,
+        invoice_id: u64,
+        user: Pubkey,
+        line_items: Vec<LineItem>,
+    ) -> Result<()> {
+        instructions::create_invoice::handler(ctx, invoice_id, user, line_items)
+    }
+
+    /// Void an open invoice (merchant owner only)
+    pub fn void_invoice(ctx: Context<VoidInvoice>) -> Result<()> {
+        instructions::void_invoice::handler(ctx)
+    }
+
+    /// Pay an open invoice directly (user only)
+    pub fn pay_invoice(ctx: Context<PayInvoice>) -> Result<()> {
+        instructions::pay_invoice::handler(ctx)
+    }
+
+    /// Collect an open invoice through its subscription's delegation (permissionless)
+    pub fn collect_invoice(ctx: Context<CollectInvoice>) -> Result<()> {
+        instructions::collect_invoice::handler(ctx)
+    }
+}
+
+// ============================================================================
+// Account Structures
+// ============================================================================
+
+#[account]
+pub struct PlatformState {
+    pub authority: Pubkey,              // 32
+    pub daily_volume_limit: u64,        // 8
+    pub total_volume_24h: u64,          // 8
+    pub last_volume_reset: i64,         // 8
+    pub failed_tx_count: u16,           // 2
+    pub emergency_pause: bool,          // 1
     pub fee_basis_points: u16,          // 2
     pub min_fee: u64,                   // 8
     pub max_fee: u64,                   // 8
     pub total_subscriptions: u64,       // 8
     pub total_transactions: u64,        // 8
     pub bump: u8,                       // 1
+    pub version: u8,                    // 1
+    // v2: fee wallets merged from the legacy PlatformConfig account
+    pub fee_wallet_usdc: Pubkey,        // 32
+    pub fee_wallet_usd1: Pubkey,        // 32
+    pub allow_unverified_merchants: bool, // 1
+    pub max_active_subscriptions: u64,  // 8
+    pub max_daily_new_subscriptions: u32, // 4
+    pub new_subscriptions_today: u32,   // 4
+    pub onboarding_window_start: i64,   // 8
+    pub collection_window_seconds: i64, // 8
+    pub spl_token_paused: bool,         // 1
+    pub token_2022_paused: bool,        // 1
+    pub priority_reserve_bps: u16,      // 2
+    // v3: stablecoin mints recorded by initialize_config / update_config
+    pub usdc_mint: Pubkey,              // 32
+    pub usd1_mint: Pubkey,              // 32
+    pub fee_rounding: FeeRounding,      // 1
+    pub fee_dust_policy: FeeDustPolicy, // 1
+    pub fee_dust_threshold: u64,        // 8 - recurring fees below this are dust (0 = none)
+    pub fee_exempt_payments: u64,       // 8 - payments collected without a fee by exemption
+    pub fees_waived: u64,               // 8 - platform fees those payments did not pay
+    pub first_subscription_free_payments: u8, // 1 - fee-free payments on a user's first subscription (0 = off)
+    // v4: emergency pause expiry
+    pub pause_duration_seconds: i64,    // 8 - how long a pause lasts (0 = DEFAULT_PAUSE_DURATION_SECONDS)
+    pub pause_expires_at: i64,          // 8 - when the active pause lapses (0 = never, pauses set before v4)
+    pub pause_reason: [u8; MAX_PAUSE_REASON_LEN], // 64 - UTF-8, zero padded
+    // v5: executor rewards
+    pub executor_reward_bps: u16,       // 2 - reward per payment in bps of the charge
+    pub executor_reward_flat: u64,      // 8 - flat reward per payment in token units
+    pub executor_rewards_paid: u64,     // 8 - total rewards paid out of platform fees
+    // v6: admin council
+    pub council_enabled: bool,          // 1 - pauses and platform fees need council approval
+    // v7: config change timelock
+    pub config_change_delay_seconds: i64, // 8 - wait before a scheduled change (0 = DEFAULT_CONFIG_CHANGE_DELAY_SECONDS)
+}
+
+impl PlatformState {
+    /// Layout version written by this build
+    ///
+    /// - v0: original layout
+    /// - v1: `version` byte and reserved padding
+    /// - v2: fee wallets (occupying v1's zeroed padding)
+    /// - v3: `usdc_mint` and `usd1_mint` (grows the account)
+    /// - v4: emergency pause duration, expiry and reason (grows the account)
+    /// - v5: executor reward settings and total (grows the account)
+    /// - v6: `council_enabled` (grows the account)
+    /// - v7: `config_change_delay_seconds` (grows the account)
+    ///
+    /// Fields carved out of `reserved` default to zero and need no migration.
+    pub const CURRENT_VERSION: u8 = 7;
+
+    /// Size of the original (version 0) layout, before `version` and `reserved`
+    pub const LEGACY_SPACE: usize = 8 + 32 + 8 + 8 + 8 + 2 + 1 + 2 + 8 + 8 + 8 + 8 + 1;
+
+    /// Byte offset of `version` (first field appended after the legacy layout)
+    pub const VERSION_OFFSET: usize = Self::LEGACY_SPACE;
+
+    pub const SPACE: usize =
+        Self::LEGACY_SPACE + 1 + 32 + 32 + 64 + 64 + 8 + 8 + MAX_PAUSE_REASON_LEN + 2 + 8 + 8 + 1 + 8;
+
+    /// Whether fee wallets have been configured
+    pub fn fee_wallets_configured(&self) -> bool {
+        self.fee_wallet_usdc != Pubkey::default() && self.fee_wallet_usd1 != Pubkey::default()
+    }
+
+    /// Whether payments are halted by an emergency pause at `now`
+    ///
+    /// A pause lapses at `pause_expires_at` without anyone unpausing;
+    /// pauses from before expiries existed have none.
+    pub fn is_paused(&self, now: i64) -> bool {
+        self.emergency_pause && (self.pause_expires_at == 0 || now < self.pause_expires_at)
+    }
+
+    /// How long a pause activated now would last
+    pub fn pause_duration(&self) -> i64 {
+        if self.pause_duration_seconds == 0 {
+            DEFAULT_PAUSE_DURATION_SECONDS
+        } else {
+            self.pause_duration_seconds
+        }
+    }
+
+    /// How long a config change scheduled now has to wait
+    pub fn config_change_delay(&self) -> i64 {
+        if self.config_change_delay_seconds == 0 {
+            DEFAULT_CONFIG_CHANGE_DELAY_SECONDS
+        } else {
+            self.config_change_delay_seconds
+        }
+    }
+
+    /// Pause payments for `reason` until one pause duration from `now`,
+    /// returning the expiry; activating again restarts the clock
+    pub fn activate_pause(&mut self, reason: &str, now: i64) -> Result<i64> {
+        require!(
+            !reason.trim().is_empty() && reason.len() <= MAX_PAUSE_REASON_LEN,
+            ErrorCode::InvalidPauseReason
+        );
+        let expires_at = now
+            .checked_add(self.pause_duration())
+            .ok_or(ErrorCode::Overflow)?;
+        self.emergency_pause = true;
+        self.pause_expires_at = expires_at;
+        self.pause_reason = [0; MAX_PAUSE_REASON_LEN];
+        self.pause_reason[..reason.len()].copy_from_slice(reason.as_bytes());
+        Ok(expires_at)
+    }
+
+    /// Lift the emergency pause and forget its reason
+    pub fn clear_pause(&mut self) {
+        self.emergency_pause = false;
+        self.pause_expires_at = 0;
+        self.pause_reason = [0; MAX_PAUSE_REASON_LEN];
+    }
+
+    /// Reason stored by the latest pause
+    pub fn pause_reason(&self) -> String {
+        let len = self.pause_reason.iter().position(|&b| b == 0).unwrap_or(MAX_PAUSE_REASON_LEN);
+        String::from_utf8_lossy(&self.pause_reason[..len]).into_owned()
+    }
+
+    /// Whether the 24h onboarding window has elapsed at `now`
+    pub fn onboarding_window_expired(&self, now: i64) -> bool {
+        now >= self.onboarding_window_start.saturating_add(SECONDS_PER_DAY)
+    }
+
+    /// New subscriptions counted against the daily cap at `now`
+    pub fn new_subscriptions_in_window(&self, now: i64) -> u32 {
+        if self.onboarding_window_expired(now) {
+            0
+        } else {
+            self.new_subscriptions_today
+        }
+    }
+
+    /// Whether payments routed through `token_program` are paused
+    pub fn token_program_paused(&self, token_program: &Pubkey) -> bool {
+        (*token_program == anchor_spl::token::ID && self.spl_token_paused)
+            || (*token_program == anchor_spl::token_2022::ID && self.token_2022_paused)
+    }
+
+    /// Daily volume payments to a merchant of `tier` may fill
+    ///
+    /// Community-tier merchants (the registry's earned top tier) are the
+    /// priority class and may use the `priority_reserve_bps` share held back
+    /// from everyone else, so a tight limit cuts standard traffic first.
+    /// Without the merchant account a payment counts as standard.
+    pub fn volume_limit(&self, tier: Option<&VerificationTier>) -> u64 {
+        let priority = tier == Some(&VerificationTier::Community);
+        limits::class_volume_limit(self.daily_volume_limit, self.priority_reserve_bps, priority)
+    }
+
+    /// Whether `account` is one of the configured fee wallets
+    pub fn is_fee_wallet(&self, account: &Pubkey) -> bool {
+        self.fee_wallets_configured()
+            && (*account == self.fee_wallet_usdc || *account == self.fee_wallet_usd1)
+    }
+
+    /// Fee schedule a merchant is charged under: its negotiated `custom`
+    /// schedule if it has one, else the platform defaults
+    pub fn fee_schedule(&self, custom: Option<FeeSchedule>) -> FeeSchedule {
+        custom.unwrap_or(FeeSchedule {
+            fee_basis_points: self.fee_basis_points,
+            min_fee: self.min_fee,
+            max_fee: self.max_fee,
+        })
+    }
+
+    /// Platform fee on a charge of `amount`, rounded per `fee_rounding`
+    ///
+    /// `custom` is the merchant's negotiated schedule, if any.
+    pub fn platform_fee(&self, amount: u64, custom: Option<FeeSchedule>) -> Result<u64> {
+        if amount == 0 {
+            return Ok(0);
+        }
+        let schedule = self.fee_schedule(custom);
+        Ok(fee::calculate_fee_rounded(
+            amount,
+            schedule.fee_basis_points,
+            schedule.min_fee,
+            schedule.max_fee,
+            self.fee_rounding.into(),
+        )
+        .map_err(ErrorCode::from)?)
+    }
+
+    /// Platform fee on a recurring charge, given the subscription's `fee_dust`
+    ///
+    /// Returns the fee to collect now and the dust to carry forward.
+    pub fn recurring_fee(
+        &self,
+        amount: u64,
+        fee_dust: u64,
+        custom: Option<FeeSchedule>,
+    ) -> Result<(u64, u64)> {
+        if amount == 0 {
+            return Ok((0, fee_dust));
+        }
+        Ok(fee::apply_dust_threshold(
+            amount,
+            self.platform_fee(amount, custom)?,
+            fee_dust,
+            self.fee_dust_threshold,
+            self.fee_dust_policy.into(),
+        )
+        .map_err(ErrorCode::from)?)
+    }
+
+    /// Reward for the keeper executing a payment of `charge` carrying `fee`
+    ///
+    /// Paid out of the platform fee, so it never exceeds it.
+    pub fn executor_reward(&self, charge: u64, fee: u64) -> u64 {
+        let share = (charge as u128 * self.executor_reward_bps as u128 / 10_000) as u64;
+        self.executor_reward_flat.saturating_add(share).min(fee)
+    }
+
+    /// Total a full payment of `amount` takes from the user under `fee_bearer`
+    ///
+    /// Ignores waivers, so caps and variance are held to the fee a payment
+    /// nominally carries.
+    pub fn payment_outflow(
+        &self,
+        fee_bearer: FeeBearer,
+        amount: u64,
+        custom: Option<FeeSchedule>,
+    ) -> Result<u64> {
+        let fee = match fee_bearer {
+            FeeBearer::Merchant => 0,
+            FeeBearer::User => self.platform_fee(amount, custom)?,
+        };
+        Ok(fee_bearer.user_outflow(amount, fee).ok_or(ErrorCode::Overflow)?)
+    }
+
+    /// Count a fee-exempt payment and the platform fee it did not pay
+    pub fn record_fee_exemption(&mut self, waived_fee: u64) {
+        self.fee_exempt_payments = self.fee_exempt_payments.saturating_add(1);
+        self.fees_waived = self.fees_waived.saturating_add(waived_fee);
+    }
+
+    /// Whether subscriptions may settle in `mint`
+    ///
+    /// Platforms migrated to v3 accept any mint until `update_config`
+    /// records the USDC and USD1 mint addresses.
+    pub fn is_settlement_stablecoin(&self, mint: &Pubkey) -> bool {
+        (self.usdc_mint == Pubkey::default() && self.usd1_mint == Pubkey::default())
+            || *mint == self.usdc_mint
+            || *mint == self.usd1_mint
+    }
+}
+
+// ============================================================================
+// Context Structures
+// ============================================================================
+
+#[derive(Accounts)]
+pub struct InitializePlatform<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = PlatformState::SPACE,
+        seeds = [b"platform"],
+        bump
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateSubscription<'info> {
+    /// Nonce allocator of the user and merchant; created by their first
+    /// subscription (ahead of `subscription`, whose seeds read it)
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = SubscriptionCounter::LEN,
+        seeds = [b"subscription_counter", user.key().as_ref(), merchant.key().as_ref()],
+        bump
+    )]
+    pub subscription_counter: Box<Account<'info, SubscriptionCounter>>,
+
+    #[account(
+        init,
+        payer = user,
+        space = Subscription::SPACE,
+        seeds = [
+            b"subscription",
+            user.key().as_ref(),
+            merchant.key().as_ref(),
+            &Subscription::nonce_seed(subscription_counter.next_nonce),
+        ],
+        bump
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform_state.bump
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Merchant account from merchant registry; has up-front charges
+    /// recorded in its stats
+    /// Boxed to keep the large Merchant account off the stack
+    #[account(
+        mut,
+        seeds = [b"merchant", merchant.owner.as_ref()],
+        bump = merchant.bump,
+        seeds::program = lutrii_merchant_registry::ID
+    )]
+    pub merchant: Box<Account<'info, MerchantAccount>>,
+
+    /// CHECK: Denylist PDA for the user; must not exist
+    #[account(
+        seeds = [b"denylist", user.key().as_ref()],
+        bump,
+        constraint = user_denylist_entry.data_is_empty() @ ErrorCode::WalletDenylisted
+    )]
+    pub user_denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: Denylist PDA for the merchant wallet; must not exist
+    #[account(
+        seeds = [b"denylist", merchant.owner.as_ref()],
+        bump,
+        constraint = merchant_denylist_entry.data_is_empty() @ ErrorCode::WalletDenylisted
+    )]
+    pub merchant_denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: Merchant blocklist PDA for this user; must not exist
+    #[account(
+        seeds = [b"blocklist", merchant.key().as_ref(), user.key().as_ref()],
+        bump,
+        constraint = blocklist_entry.data_is_empty() @ ErrorCode::UserBlockedByMerchant
+    )]
+    pub blocklist_entry: UncheckedAccount<'info>,
+
+    /// Merchant policy; created with no restrictions by the first subscriber
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = MerchantPolicy::LEN,
+        seeds = [b"merchant_policy", merchant.key().as_ref()],
+        bump
+    )]
+    pub merchant_policy: Box<Account<'info, MerchantPolicy>>,
+
+    /// CHECK: Merchant allowlist PDA for this user; required to exist in allowlist-only mode
+    #[account(
+        seeds = [b"allowlist", merchant.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub allowlist_entry: UncheckedAccount<'info>,
+
+    /// Shared delegate for the user's token account; created by its first subscription
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserDelegate::LEN,
+        seeds = [b"user_delegate", user_token_account.key().as_ref()],
+        bump
+    )]
+    pub user_delegate: Box<Account<'info, UserDelegate>>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::InvalidTokenAccountOwner,
+        constraint = user_token_account.mint == mint.key() @ ErrorCode::InvalidMint
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub merchant_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Registry entry for `mint`; unlisted or disabled mints are rejected
+    #[account(
+        seeds = [b"accepted_mint", mint.key().as_ref()],
+        bump = accepted_mint.bump,
+        constraint = accepted_mint.enabled @ ErrorCode::MintNotAccepted
+    )]
+    pub accepted_mint: Box<Account<'info, AcceptedMint>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+
+    /// Spending analytics for the user, when they created it
+    #[account(
+        mut,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump = user_stats.bump
+    )]
+    pub user_stats: Option<Account<'info, UserStats>>,
+
+    /// Swap asset config for `mint`; required when the merchant settles in another mint
+    #[account(
+        seeds = [b"swap_asset", mint.key().as_ref()],
+        bump = swap_asset.bump
+    )]
+    pub swap_asset: Option<Box<Account<'info, SwapAsset>>>,
+
+    /// CHECK: Must match `swap_asset.price_feed`; parsed as a Pyth price account
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    /// Merchant's settlement mint; required with `swap_asset`
+    pub settlement_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Registry entry for the settlement mint; required with `swap_asset`
+    #[account(
+        seeds = [b"accepted_mint", merchant_token_account.mint.as_ref()],
+        bump = settlement_accepted_mint.bump
+    )]
+    pub settlement_accepted_mint: Option<Box<Account<'info, AcceptedMint>>>,
+
+    /// Due-date bucket for the first payment, when indexed
+    #[account(mut)]
+    pub next_due_bucket: Option<Account<'info, DueBucket>>,
+
+    /// Merchant's revenue forecast in the mint, when kept
+    #[account(
+        mut,
+        seeds = [b"revenue_forecast", merchant.key().as_ref(), mint.key().as_ref()],
+        bump = revenue_forecast.bump
+    )]
+    pub revenue_forecast: Option<Box<Account<'info, RevenueForecast>>>,
+
+    /// CHECK: One of the configured fee wallets; required with `charge_immediately`
+    #[account(
+        mut,
+        constraint = platform_state.is_fee_wallet(&platform_fee_account.key()) @ ErrorCode::InvalidFeeWallet
+    )]
+    pub platform_fee_account: Option<UncheckedAccount<'info>>,
+
+    /// Merchant promo code the user redeems, discounting the first payments
+    #[account(
+        mut,
+        constraint = promo.merchant == merchant.key() @ ErrorCode::InvalidPromo
+    )]
+    pub promo: Option<Box<Account<'info, Promo>>>,
+
+    /// CHECK: Platform's promotional fee windows PDA, for up-front charges;
+    /// may be uninitialized
+    #[account(seeds = [b"fee_holidays"], bump)]
+    pub fee_holidays: UncheckedAccount<'info>,
+
+    pub merchant_registry_program: Program<'info, LutriiMerchantRegistry>,
+
+    /// CHECK: Instructions sysvar, read by the registry to check it runs under a CPI
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+/// How a new subscription starts; the default bills one period after creation
+/// or on the merchant's billing anchor
+///
+/// Up-front charges (`charge_immediately`, `setup_fee`, and the partial
+/// period a billing day, billing weekday or merchant billing anchor charges
+/// at signup) require `platform_fee_account`.
+///
+/// A nonzero `trial_seconds` starts a free trial: the first payment falls
+/// due when the trial ends, and no up-front charge, billing day or billing
+/// weekday may be combined with it. Trials come in two kinds:
+/// - By default the allowance is approved at signup, and billing starts
+///   on its own once the trial ends.
+/// - With `defer_delegation` nothing is approved, and nothing can be
+///   charged, until the user signs `convert_trial`, so wallets without
+///   funds can sign up. `create_trial_subscription` creates these.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CreateOptions {
+    /// Collect the first payment at creation ("pay now, renew monthly");
+    /// the next one falls due one frequency after it
+    pub charge_immediately: bool,
+    /// One-time fee collected at creation, counted against the lifetime cap
+    pub setup_fee: u64,
+    /// Day of the month a monthly subscription falls due, the last day of
+    /// shorter months (0 = every `frequency_seconds`)
+    pub billing_day: u8,
+    /// Seconds east of UTC whose midnight calendar due dates fall on
+    pub utc_offset_seconds: i32,
+    /// Clock `frequency_seconds` and `trial_seconds` count on; slots give
+    /// deterministic on-chain timing but take no billing day
+    pub schedule_unit: ScheduleUnit,
+    /// Free trial length before the first payment falls due (0 = no trial)
+    pub trial_seconds: i64,
+    /// Approve nothing until `convert_trial` (trials only)
+    pub defer_delegation: bool,
+    /// ISO day of the week (1 = Monday .. 7 = Sunday) a subscription billed
+    /// every whole number of weeks falls due (0 = every `frequency_seconds`
+    /// or the merchant's billing anchor)
+    pub billing_weekday: u8,
+}
+
+impl<'info> CreateSubscription<'info> {
+    /// Create the subscription on the given terms
+    ///
+    /// Shared by `create_subscription`, `create_trial_subscription` and
+    /// `accept_offer`, which differ only in where the terms come from and in
+    /// `options`. Up-front charges are signed by the user, not the delegate.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn create(
+        &mut self,
+        bumps: &CreateSubscriptionBumps,
+        amount: u64,
+        frequency_seconds: i64,
+        max_per_transaction: u64,
+        lifetime_cap: u64,
+        merchant_name: String,
+        options: CreateOptions,
+    ) -> Result<()> {
+        let platform = &self.platform_state;
+        let clock = Clock::get()?;
+        require!(!platform.is_paused(clock.unix_timestamp), ErrorCode::SystemPaused);
+
+        // Global launch-ramp caps (0 = unlimited)
+        require!(
+            platform.max_active_subscriptions == 0
+                || platform.total_subscriptions < platform.max_active_subscriptions,
+            ErrorCode::PlatformSubscriptionCapReached
+        );
+        require!(
+            platform.max_daily_new_subscriptions == 0
+                || platform.new_subscriptions_in_window(clock.unix_timestamp)
+                    < platform.max_daily_new_subscriptions,
+            ErrorCode::DailyOnboardingCapReached
+        );
+
+        // ============================================================================
+        // MERCHANT VALIDATION - Verify merchant is registered and verified
+        // ============================================================================
+        // Registry ownership and PDA seeds are enforced by the account constraints
+        let merchant_data = &self.merchant;
+
+        // Suspended merchants can never take new subscriptions; unverified
+        // merchants only when the platform explicitly allows it
+        require!(
+            merchant_data.verification_tier != VerificationTier::Suspended,
+            ErrorCode::MerchantSuspended
+        );
+        require!(
+            merchant_data.verification_tier != VerificationTier::Unverified
+                || platform.allow_unverified_merchants,
+            ErrorCode::MerchantNotVerified
+        );
+
+        let merchant_owner = merchant_data.owner;
+
+        // Merchant policy: allowlist-only mode and active subscriber cap
+        let policy = &mut self.merchant_policy;
+        if policy.merchant == Pubkey::default() {
+            policy.merchant = self.merchant.key();
+            policy.bump = bumps.merchant_policy;
+        }
+        require!(
+            !policy.allowlist_only || !self.allowlist_entry.data_is_empty(),
+            ErrorCode::NotOnMerchantAllowlist
+        );
+        require!(policy.has_capacity(), ErrorCode::MerchantAtCapacity);
+        policy.active_subscribers = policy
+            .active_subscribers
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        // Verify merchant_token_account owner matches merchant owner wallet
+        require!(
+            self.merchant_token_account.owner == merchant_owner,
+            ErrorCode::InvalidTokenAccountOwner
+        );
+
+        // Merchants that chose a settlement currency are paid in it, whatever
+        // the subscriber pays with
+        require!(
+            merchant_data.settlement_token == Pubkey::default()
+                || self.merchant_token_account.mint == merchant_data.settlement_token,
+            ErrorCode::SettlementCurrencyMismatch
+        );
+
+        // Verify merchant_token_account mint matches, unless the user pays
+        // from an enabled swap asset; the approval is then priced in the
+        // asset at the oracle rate plus slippage
+        let approval_rate = if self.merchant_token_account.mint == self.mint.key() {
+            0
+        } else {
+            let (Some(swap_asset), Some(price_feed), Some(settlement_mint)) = (
+                self.swap_asset.as_ref(),
+                self.price_feed.as_ref(),
+                self.settlement_mint.as_ref(),
+            ) else {
+                return err!(ErrorCode::InvalidMint);
+            };
+            require!(swap_asset.enabled, ErrorCode::SwapAssetDisabled);
+            require_keys_eq!(
+                settlement_mint.key(),
+                self.merchant_token_account.mint,
+                ErrorCode::InvalidMint
+            );
+            let price = swap_asset.price(price_feed, clock.unix_timestamp)?;
+            swap_asset.max_input(
+                &price,
+                UserDelegate::RATE_SCALE,
+                self.mint.decimals,
+                settlement_mint.decimals,
+                swap_asset.max_slippage_bps,
+            )?
+        };
+
+        // Mint sanity: the registry entry describes this mint, it is in
+        // circulation, and the merchant is paid in a configured stablecoin
+        require!(
+            self.mint.decimals == self.accepted_mint.decimals,
+            ErrorCode::InvalidMintDecimals
+        );
+        require!(self.mint.supply > 0, ErrorCode::InvalidMintSupply);
+        require!(
+            platform.is_settlement_stablecoin(&self.merchant_token_account.mint),
+            ErrorCode::UnsupportedSettlementMint
+        );
+        let settlement_decimals = match &self.settlement_mint {
+            Some(settlement_mint) if approval_rate != 0 => settlement_mint.decimals,
+            _ => self.mint.decimals,
+        };
+        require!(
+            settlement_decimals == STABLECOIN_DECIMALS,
+            ErrorCode::InvalidMintDecimals
+        );
+
+        msg!(
+            "✅ Merchant validated: {} (tier: {:?})",
+            merchant_data.business_name,
+            merchant_data.verification_tier
+        );
+
+        // Validate inputs; slot schedules get the same bounds at the
+        // target slot time
+        let unit = options.schedule_unit;
+        let min_frequency = unit.span(MIN_FREQUENCY_SECONDS);
+        let max_frequency = unit.span(MAX_FREQUENCY_SECONDS);
+        require!(
+            frequency_seconds >= min_frequency,
+            ErrorCode::FrequencyTooShort
+        );
+        require!(
+            frequency_seconds <= max_frequency,
+            ErrorCode::FrequencyTooLong
+        );
+        require!(
+            !merchant_name.is_empty() && merchant_name.len() <= MAX_MERCHANT_NAME_LEN,
+            ErrorCode::InvalidMerchantName
+        );
+        require!(amount > 0, ErrorCode::AmountTooLow);
+        // Minimums apply in the mint the subscription is billed in: the
+        // payment mint, or the settlement mint for swap subscriptions
+        let billing_mint = if approval_rate == 0 {
+            &self.accepted_mint
+        } else {
+            self.settlement_accepted_mint
+                .as_ref()
+                .ok_or(ErrorCode::MintNotAccepted)?
+        };
+        require!(amount >= billing_mint.min_amount, ErrorCode::BelowMintMinimum);
+
+        // Caps bound what a payment takes from the user, fee on top included
+        let fee_bearer = self.merchant_policy.fee_bearer;
+        let custom_fee = self.merchant_policy.custom_fee_schedule();
+        let payment_outflow = self
+            .platform_state
+            .payment_outflow(fee_bearer, amount, custom_fee)?;
+        require!(
+            payment_outflow <= max_per_transaction,
+            ErrorCode::ExceedsTransactionCap
+        );
+        require!(payment_outflow <= lifetime_cap, ErrorCode::ExceedsLifetimeCap);
+
+        // Deferred trials approve nothing up front and converting one
+        // prices the approval 1:1, so trials take the settlement mint only.
+        // The first payment is the trial's end, leaving nothing to charge
+        // at signup or anchor to a billing day.
+        let trial = options.trial_seconds != 0;
+        let deferred = options.defer_delegation;
+        require!(trial || !deferred, ErrorCode::InvalidTrialPeriod);
+        if trial {
+            require!(
+                (min_frequency..=max_frequency).contains(&options.trial_seconds),
+                ErrorCode::InvalidTrialPeriod
+            );
+            require!(approval_rate == 0, ErrorCode::TrialSwapUnsupported);
+            require!(
+                !options.charge_immediately && options.billing_day == 0 && options.billing_weekday == 0,
+                ErrorCode::InvalidTrialPeriod
+            );
+        }
+
+        // Swaps price the approval off the amount alone, leaving no room for a fee on top
+        require!(
+            fee_bearer == FeeBearer::Merchant || approval_rate == 0,
+            ErrorCode::FeeOnTopSwapUnsupported
+        );
+
+        require!(
+            options.billing_day == 0
+                || (options.billing_day <= schedule::MAX_BILLING_DAY
+                    && schedule::is_monthly(frequency_seconds)
+                    && unit == ScheduleUnit::Seconds),
+            ErrorCode::InvalidBillingDay
+        );
+        require!(
+            options.billing_weekday == 0
+                || (options.billing_weekday <= schedule::MAX_BILLING_WEEKDAY
+                    && options.billing_day == 0
+                    && schedule::is_weekly(frequency_seconds)
+                    && unit == ScheduleUnit::Seconds),
+            ErrorCode::InvalidBillingWeekday
+        );
+        require!(
+            (schedule::MIN_UTC_OFFSET_SECONDS..=schedule::MAX_UTC_OFFSET_SECONDS)
+                .contains(&options.utc_offset_seconds),
+            ErrorCode::InvalidUtcOffset
+        );
+
+        // A billing day, billing weekday or merchant billing anchor moves the
+        // first due date and charges the partial period up to it at signup.
+        // Anchors are wall-clock, so slot schedules ignore them.
+        let now = unit.now(&clock);
+        let billing_anchor = match unit {
+            ScheduleUnit::Seconds => self.merchant_policy.billing_anchor,
+            ScheduleUnit::Slots => 0,
+        };
+        let (next_payment, first_payment) = if trial {
+            let next = schedule::next_payment(now, options.trial_seconds);
+            (next.map_err(ErrorCode::from)?, 0)
+        } else if options.billing_day != 0 {
+            let (previous, next) =
+                schedule::billing_day_bounds(now, options.billing_day, options.utc_offset_seconds)
+                    .map_err(ErrorCode::from)?;
+            let charge = proration::prorate(amount, next - now, next - previous);
+            (next, charge.map_err(ErrorCode::from)?)
+        } else if options.billing_weekday != 0 {
+            let next = schedule::next_weekday(now, options.billing_weekday, options.utc_offset_seconds)
+                .map_err(ErrorCode::from)?;
+            let charge = proration::prorate(amount, next - now, frequency_seconds);
+            (next, charge.map_err(ErrorCode::from)?)
+        } else if billing_anchor != 0 {
+            let next = schedule::next_anchor(now, billing_anchor, frequency_seconds)
+                .map_err(ErrorCode::from)?;
+            let charge = proration::prorate(amount, next - now, frequency_seconds);
+            (next, charge.map_err(ErrorCode::from)?)
+        } else {
+            let next = schedule::next_payment(now, frequency_seconds);
+            let charge = if options.charge_immediately { amount } else { 0 };
+            (next.map_err(ErrorCode::from)?, charge)
+        };
+
+        // A promo code discounts the first payments, starting with any
+        // charged now
+        let (discount_amount, mut discount_payments) = match self.promo.as_mut() {
+            Some(promo) => promo.redeem(amount, clock.unix_timestamp)?,
+            None => (0, 0),
+        };
+        let first_payment = if first_payment > 0 && discount_payments > 0 {
+            discount_payments -= 1;
+            first_payment.saturating_sub(discount_amount)
+        } else {
+            first_payment
+        };
+
+        // Up-front charges: the first payment and any setup fee, under the
+        // same fee and velocity rules as execute_payment
+        let upfront = first_payment
+            .checked_add(options.setup_fee)
+            .ok_or(ErrorCode::Overflow)?;
+
+        // Claimed before `subscription_added` counts this subscription
+        if let Some(user_stats) = self.user_stats.as_mut() {
+            user_stats.claim_first_subscription(self.subscription.key());
+        }
+        let first_payment_waived = first_payment > 0
+            && self.user_stats.as_ref().is_some_and(|stats| {
+                stats.first_subscription_fee_waived(
+                    &self.subscription.key(),
+                    0,
+                    self.platform_state.first_subscription_free_payments,
+                )
+            });
+
+        let fee_exempt = self.merchant_policy.fee_exempt;
+        let payment_treatment = FeeTreatment::for_charge(
+            first_payment,
+            fee_exempt,
+            &self.fee_holidays,
+            first_payment_waived,
+            &self.mint.key(),
+            clock.unix_timestamp,
+        )?;
+        let setup_treatment = FeeTreatment::for_charge(
+            options.setup_fee,
+            fee_exempt,
+            &self.fee_holidays,
+            false,
+            &self.mint.key(),
+            clock.unix_timestamp,
+        )?;
+
+        let upfront_fees = if upfront > 0 {
+            require!(!trial, ErrorCode::InvalidTrialPeriod);
+            require!(approval_rate == 0, ErrorCode::FirstChargeSwapUnsupported);
+            require!(
+                !self.platform_state.token_program_paused(&self.token_program.key()),
+                ErrorCode::TokenProgramPaused
+            );
+            require!(
+                self.platform_fee_account.is_some(),
+                ErrorCode::InvalidFeeWallet
+            );
+
+            let platform = &mut self.platform_state;
+            if clock.unix_timestamp >= platform.last_volume_reset + SECONDS_PER_DAY {
+                platform.total_volume_24h = 0;
+                platform.last_volume_reset = clock.unix_timestamp;
+            }
+            let new_volume = platform
+                .total_volume_24h
+                .checked_add(upfront)
+                .ok_or(ErrorCode::Overflow)?;
+            require!(
+                new_volume <= platform.volume_limit(Some(&self.merchant.verification_tier)),
+                ErrorCode::VelocityExceeded
+            );
+            platform.total_volume_24h = new_volume;
+            platform.total_transactions = platform
+                .total_transactions
+                .checked_add(1)
+                .ok_or(ErrorCode::Overflow)?;
+
+            // Fees are itemized per charge, as if collected separately, each
+            // waived as a payment's would be
+            let mut itemized_fee = |charge: u64, treatment: FeeTreatment| -> Result<u64> {
+                let fee = platform.platform_fee(charge, custom_fee)?;
+                if !treatment.waives_fee() {
+                    return Ok(fee);
+                }
+                if treatment == FeeTreatment::Exempt {
+                    platform.record_fee_exemption(fee);
+                }
+                Ok(0)
+            };
+            let payment_fee = itemized_fee(first_payment, payment_treatment)?;
+            let setup_platform_fee = itemized_fee(options.setup_fee, setup_treatment)?;
+
+            // Each fee is at most its charge, so the sum cannot overflow
+            let outflow = fee_bearer
+                .user_outflow(upfront, payment_fee + setup_platform_fee)
+                .ok_or(ErrorCode::Overflow)?;
+            require!(outflow <= lifetime_cap, ErrorCode::ExceedsLifetimeCap);
+            require!(
+                self.user_token_account.amount >= outflow,
+                ErrorCode::UserBalanceInsufficient
+            );
+            Some((payment_fee, setup_platform_fee, outflow))
+        } else {
+            None
+        };
+        let upfront_outflow = upfront_fees.map_or(0, |(_, _, outflow)| outflow);
+
+        let subscription = &mut self.subscription;
+
+        // Initialize subscription
+        subscription.user = self.user.key();
+        subscription.merchant = self.merchant.key();
+        subscription.user_token_account = self.user_token_account.key();
+        subscription.mint = self.mint.key();
+        subscription.merchant_token_account = self.merchant_token_account.key();
+        subscription.amount = amount;
+        subscription.original_amount = amount; // Store for variance check
+        subscription.frequency_seconds = frequency_seconds;
+        subscription.last_payment = 0;
+        subscription.next_payment = next_payment;
+        subscription.total_paid = upfront_outflow;
+        subscription.payment_count = 0;
+        subscription.is_active = true;
+        subscription.is_paused = false;
+        subscription.max_per_transaction = max_per_transaction;
+        subscription.lifetime_cap = lifetime_cap;
+        subscription.merchant_name = merchant_name.clone();
+        subscription.created_at = clock.unix_timestamp;
+        subscription.bump = bumps.subscription;
+        subscription.nonce = self.subscription_counter.next_nonce;
+        subscription.delegation_healthy = !deferred; // approved below
+        subscription.trial_ends_at = if trial { subscription.next_payment } else { 0 };
+        subscription.delegation_deferred = deferred;
+        subscription.setup_fee = options.setup_fee;
+        subscription.billing_day = options.billing_day;
+        subscription.billing_weekday = options.billing_weekday;
+        subscription.utc_offset_seconds = options.utc_offset_seconds;
+        subscription.layout_version = Subscription::CURRENT_VERSION;
+        subscription.schedule_unit = unit;
+        subscription.fee_bearer = fee_bearer;
+        subscription.discount_amount = discount_amount;
+        subscription.discount_payments_remaining = discount_payments;
+        if first_payment > 0 {
+            subscription.last_payment = clock.unix_timestamp;
+            subscription.payment_count = 1;
+        }
+
+        // The pair's next subscription takes the next nonce
+        let counter = &mut self.subscription_counter;
+        counter.user = subscription.user;
+        counter.merchant = subscription.merchant;
+        counter.bump = bumps.subscription_counter;
+        counter.next_nonce = counter.next_nonce.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+        // Add this subscription's allowance to the shared user delegate
+        let user_delegate = &mut self.user_delegate;
+        if user_delegate.token_account == Pubkey::default() {
+            user_delegate.user = self.user.key();
+            user_delegate.token_account = self.user_token_account.key();
+            user_delegate.bump = bumps.user_delegate;
+        }
+        if !deferred {
+            // A token account pays either directly or through swaps, never both
+            require!(
+                user_delegate.active_subscriptions == 0
+                    || (user_delegate.approval_rate == 0) == (approval_rate == 0),
+                ErrorCode::PaymentModeMismatch
+            );
+            user_delegate.approval_rate = approval_rate;
+            user_delegate.reserve(limits::remaining_allowance(
+                lifetime_cap,
+                subscription.total_paid,
+            ))?;
+            user_delegate.active_subscriptions = user_delegate
+                .active_subscriptions
+                .checked_add(1)
+                .ok_or(ErrorCode::Overflow)?;
+
+            // Approve the user delegate PDA for every active subscription on this
+            // token account (delegation model)
+            user_delegate.sync_approval(
+                user_delegate.to_account_info(),
+                self.user_token_account.to_account_info(),
+                self.user.to_account_info(),
+                self.token_program.to_account_info(),
+            )?;
+        }
+
+        // Update platform stats
+        let platform_state = &mut self.platform_state;
+        platform_state.total_subscriptions = platform_state
+            .total_subscriptions
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        // Roll the onboarding window like the daily volume window
+        if platform_state.onboarding_window_expired(clock.unix_timestamp) {
+            platform_state.onboarding_window_start = clock.unix_timestamp;
+            platform_state.new_subscriptions_today = 0;
+        }
+        platform_state.new_subscriptions_today = platform_state
+            .new_subscriptions_today
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+
+        // Reverted transactions emit nothing, so signal the subscription that fills a cap
+        if platform_state.max_active_subscriptions != 0
+            && platform_state.total_subscriptions >= platform_state.max_active_subscriptions
+        {
+            emit!(SubscriptionCapReached {
+                schema_version: EVENT_SCHEMA_VERSION,
+                cap: SubscriptionCapKind::ActiveSubscriptions,
+                limit: platform_state.max_active_subscriptions,
+                timestamp: clock.unix_timestamp,
+            });
+        }
+        if platform_state.max_daily_new_subscriptions != 0
+            && platform_state.new_subscriptions_today >= platform_state.max_daily_new_subscriptions
+        {
+            emit!(SubscriptionCapReached {
+                schema_version: EVENT_SCHEMA_VERSION,
+                cap: SubscriptionCapKind::DailyOnboarding,
+                limit: platform_state.max_daily_new_subscriptions as u64,
+                timestamp: clock.unix_timestamp,
+            });
+        }
+
+        if let Some(user_stats) = self.user_stats.as_mut() {
+            user_stats.subscription_added()?;
+            if upfront_outflow > 0 {
+                user_stats.record_payment(upfront_outflow, clock.unix_timestamp)?;
+            }
+        }
+        if let Some(next_due_bucket) = self.next_due_bucket.as_mut() {
+            next_due_bucket.insert(subscription.key(), &subscription.merchant, subscription.next_payment)?;
+        }
+        if let Some(revenue_forecast) = self.revenue_forecast.as_mut() {
+            revenue_forecast.reschedule(None, subscription.forecast_entry(), clock.unix_timestamp)?;
+        }
+
+        // The user signs creation, so up-front charges need no delegation
+        if let (Some((payment_fee, setup_platform_fee, _)), Some(platform_fee_account)) =
+            (upfront_fees, self.platform_fee_account.as_ref())
+        {
+            // Each fee is at most its charge, so neither can underflow
+            let fee = payment_fee + setup_platform_fee;
+            let merchant_amount = fee_bearer
+                .merchant_amount(upfront, fee)
+                .ok_or(ErrorCode::InsufficientAmount)?;
+            transfer_checked(
+                CpiContext::new(
+                    self.token_program.to_account_info(),
+                    TransferChecked {
+                        from: self.user_token_account.to_account_info(),
+                        mint: self.mint.to_account_info(),
+                        to: self.merchant_token_account.to_account_info(),
+                        authority: self.user.to_account_info(),
+                    },
+                ),
+                merchant_amount,
+                self.mint.decimals,
+            )?;
+            if fee > 0 {
+                transfer_checked(
+                    CpiContext::new(
+                        self.token_program.to_account_info(),
+                        TransferChecked {
+                            from: self.user_token_account.to_account_info(),
+                            mint: self.mint.to_account_info(),
+                            to: platform_fee_account.to_account_info(),
+                            authority: self.user.to_account_info(),
+                        },
+                    ),
+                    fee,
+                    self.mint.decimals,
+                )?;
+            }
+
+            // Merchant stats and automatic tier upgrades in the registry
+            cpi::record_merchant_payment(
+                &self.merchant_registry_program.to_account_info(),
+                &self.merchant.to_account_info(),
+                &self.platform_state,
+                &self.instructions,
+                upfront,
+            )?;
+
+            if options.setup_fee > 0 {
+                emit!(SetupFeeCharged {
+                    schema_version: EVENT_SCHEMA_VERSION,
+                    subscription: subscription.key(),
+                    user: subscription.user,
+                    merchant: subscription.merchant,
+                    setup_fee: options.setup_fee,
+                    fee: setup_platform_fee,
+                    merchant_received: fee_bearer
+                        .merchant_amount(options.setup_fee, setup_platform_fee)
+                        .ok_or(ErrorCode::InsufficientAmount)?,
+                    timestamp: clock.unix_timestamp,
+                    gross_amount: fee_bearer
+                        .user_outflow(options.setup_fee, setup_platform_fee)
+                        .ok_or(ErrorCode::Overflow)?,
+                });
+            }
+            if first_payment > 0 {
+                // A prorated first period is itemized as a credit on `amount`
+                let proration_credit =
+                    i64::try_from(amount - first_payment).map_err(|_| ErrorCode::Overflow)?;
+                emit!(PaymentExecuted {
+                    schema_version: EVENT_SCHEMA_VERSION,
+                    subscription: subscription.key(),
+                    amount: first_payment,
+                    proration_adjustment: -proration_credit,
+                    fee: payment_fee,
+                    merchant_received: fee_bearer
+                        .merchant_amount(first_payment, payment_fee)
+                        .ok_or(ErrorCode::InsufficientAmount)?,
+                    payment_count: subscription.payment_count,
+                    remaining_lifetime_allowance: limits::remaining_allowance(
+                        lifetime_cap,
+                        subscription.total_paid,
+                    ),
+                    payments_remaining: limits::payments_remaining(
+                        lifetime_cap,
+                        subscription.total_paid,
+                        payment_outflow,
+                    ),
+                    remaining_delegation: self.user_delegate.approval_amount()?,
+                    batched: false,
+                    timestamp: clock.unix_timestamp,
+                    settlement_mint: self.mint.key(),
+                    promotional: payment_treatment.is_promotional(),
+                    gross_amount: fee_bearer
+                        .user_outflow(first_payment, payment_fee)
+                        .ok_or(ErrorCode::Overflow)?,
+                    fee_bearer,
+                });
+            }
+        }
+
+        emit!(SubscriptionCreated {
+            schema_version: EVENT_SCHEMA_VERSION,
+            subscription: subscription.key(),
+            user: subscription.user,
+            merchant: subscription.merchant,
+            amount,
+            frequency_seconds,
+            next_payment: subscription.next_payment,
+            utc_offset_seconds: subscription.utc_offset_seconds,
+        });
+
+        if let Some(promo) = self.promo.as_ref() {
+            emit!(PromoRedeemed {
+                schema_version: EVENT_SCHEMA_VERSION,
+                promo: promo.key(),
+                subscription: subscription.key(),
+                user: subscription.user,
+                discount_amount,
+                discounted_payments: promo.discounted_payments,
+                redemptions: promo.redemptions,
+                timestamp: clock.unix_timestamp,
+            });
+        }
+
+        msg!(
+            "Subscription created: {} USDC every {} seconds",
+            amount as f64 / 1_000_000.0,
+            frequency_seconds
+        );
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ModifySubscription<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        bump = subscription.bump,
+        has_one = user @ ErrorCode::UnauthorizedUser
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform_state.bump
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    pub user: Signer<'info>,
+
+    /// Due-date bucket listing the subscription, when indexed
+    #[account(
+        mut,
+        constraint = due_bucket.covers(&subscription.merchant, subscription.next_payment) @ ErrorCode::InvalidDueBucket
+    )]
+    pub due_bucket: Option<Account<'info, DueBucket>>,
+
+    /// Due-date bucket for the rescheduled payment, when indexed
+    #[account(mut)]
+    pub next_due_bucket: Option<Account<'info, DueBucket>>,
+
+    /// Merchant's revenue forecast in the subscription's mint, when kept
+    #[account(
+        mut,
+        seeds = [b"revenue_forecast", subscription.merchant.as_ref(), subscription.mint.as_ref()],
+        bump = revenue_forecast.bump
+    )]
+    pub revenue_forecast: Option<Box<Account<'info, RevenueForecast>>>,
+}
+
+#[derive(Accounts)]
+pub struct CancelSubscription<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        bump = subscription.bump,
+        has_one = user @ ErrorCode::UnauthorizedUser
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform_state.bump
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    /// CHECK: Merchant policy PDA; may be uninitialized for older subscriptions
+    #[account(
+        mut,
+        seeds = [b"merchant_policy", subscription.merchant.as_ref()],
+        bump
+    )]
+    pub merchant_policy: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.key() == subscription.user_token_account @ ErrorCode::InvalidTokenAccount
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user_delegate", subscription.user_token_account.as_ref()],
+        bump = user_delegate.bump
+    )]
+    pub user_delegate: Account<'info, UserDelegate>,
+
+    pub user: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// Spending analytics for the user, when they created it
+    #[account(
+        mut,
+        seeds = [b"user_stats", user.key().as_ref()],
+        bump = user_stats.bump
+    )]
+    pub user_stats: Option<Account<'info, UserStats>>,
+
+    /// Due-date bucket listing the subscription, when indexed
+    #[account(
+        mut,
+        constraint = due_bucket.covers(&subscription.merchant, subscription.next_payment) @ ErrorCode::InvalidDueBucket
+    )]
+    pub due_bucket: Option<Account<'info, DueBucket>>,
+
+    /// Merchant's revenue forecast in the subscription's mint, when kept
+    #[account(
+        mut,
+        seeds = [b"revenue_forecast", subscription.merchant.as_ref(), subscription.mint.as_ref()],
+        bump = revenue_forecast.bump
+    )]
+    pub revenue_forecast: Option<Box<Account<'info, RevenueForecast>>>,
+}
+
+#[derive(Accounts)]
+pub struct CloseSubscription<'info> {
+    #[account(
+        mut,
+        close = user,
+        seeds = [
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        bump = subscription.bump,
+        has_one = user @ ErrorCode::UnauthorizedUser,
+        constraint = !subscription.is_active @ ErrorCode::SubscriptionStillActive
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateLimits<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"subscription",
+            subscription.user.as_ref(),
+            subscription.merchant.as_ref(),
+            &Subscription::nonce_seed(subscription.nonce),
+        ],
+        bump = subscription.bump,
+        has_one = user @ ErrorCode::UnauthorizedUser
+    )]
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.key() == subscription.user_token_account @ ErrorCode::InvalidTokenAccount
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"user_delegate", subscription.user_token_account.as_ref()],
+        bump = user_delegate.bump
+    )]
+    pub user_delegate: Account<'info, UserDelegate>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub user: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct AdminAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform_state.bump,
+        has_one = authority @ ErrorCode::UnauthorizedAdmin
+    )]
+    pub platform_state: Account<'info, PlatformState>,
+
+    pub authority: Signer<'info>,
+
+    /// Admin audit log, appended to when passed
+    #[account(
+        mut,
+        seeds = [b"admin_audit_log"],
+        bump = audit_log.bump
+    )]
+    pub audit_log: Option<Box<Account<'info, AdminAuditLog>>>,
+}
+
+impl AdminAction<'_> {
+    /// Record `action` by the authority in the audit log, if passed
+    fn audit(&mut self, action: AdminAuditAction) -> Result<()> {
+        let actor = self.authority.key();
+        record_admin_action(self.audit_log.as_mut(), actor, action)
+    }
+
+    /// Make a council-governed change on the authority's key alone, which
+    /// only platforms without an admin council allow
+    fn apply(&mut self, action: CouncilAction) -> Result<()> {
+        require!(
+            !self.platform_state.council_enabled,
+            ErrorCode::CouncilApprovalRequired
+        );
+        instructions::execute_council_action::apply_admin_action(&mut self.platform_state, None, &action)?;
+        self.audit(action.audit_action())
+    }
+}
+
+// ============================================================================
+// Events
+// ============================================================================
+
+#[event]
+pub struct PlatformInitialized {
+    pub schema_version: u8,
+    pub authority: Pubkey,
+    pub fee_basis_points: u16,
+    pub daily_volume_limit: u64,
+    pub version: String,
+}
+
+#[event]
+pub struct SubscriptionCreated {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub user: Pubkey,
+    pub merchant: Pubkey,
+    pub amount: u64,
+    pub frequency_seconds: i64,
+    pub next_payment: i64,
+    /// Seconds east of UTC that calendar due dates follow
+    pub utc_offset_seconds: i32,
+}
+
+#[event]
+pub struct PaymentExecuted {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    /// Total charged, including `proration_adjustment`
+    pub amount: u64,
+    /// Plan-change proration applied to this payment (positive = charge, negative = credit)
+    pub proration_adjustment: i64,
+    pub fee: u64,
+    pub merchant_received: u64,
+    pub payment_count: u32,
+    /// Allowance left under the lifetime cap after this payment
+    pub remaining_lifetime_allowance: u64,
+    /// Full payments the lifetime cap still covers
+    pub payments_remaining: u64,
+    /// Tokens the shared user delegate may still spend after this payment
+    pub remaining_delegation: u64,
+    /// Merchant leg accrued in the settlement vault instead of paid directly
+    pub batched: bool,
+    pub timestamp: i64,
+    /// Mint `amount`, `fee` and `merchant_received` are denominated in: the
+    /// merchant's settlement currency, whatever the subscriber paid with
+    pub settlement_mint: Pubkey,
+    /// Fee waived by a platform promotion (fee holiday or first-subscription waiver)
+    pub promotional: bool,
+    /// Total taken from the user: `amount`, plus `fee` when the user bears it
+    pub gross_amount: u64,
+    /// Who paid `fee`; `merchant_received` is the net after it
+    pub fee_bearer: FeeBearer,
+}
+
+#[event]
+pub struct PaymentRetryScheduled {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub retry_count: u8,
+    pub next_retry_at: i64,
+    /// False when the delegation (rather than the balance) was insufficient
+    pub delegation_covers: bool,
+    pub timestamp: i64,
+    /// Error code of the cause (`DelegateMismatch`, `UserBalanceInsufficient`, ...)
+    pub failure: u32,
+}
+
+#[event]
+pub struct ExecutorRewardPaid {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub executor: Pubkey,
+    pub reward: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExecutorRewardUpdated {
+    pub schema_version: u8,
+    pub reward_bps: u16,
+    pub flat_reward: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SubscriptionDelinquent {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub user: Pubkey,
+    pub merchant: Pubkey,
+    /// Consecutive failed collections that exhausted the retry policy
+    pub failed_attempts: u8,
+    /// Whether the subscription was paused or cancelled
+    pub action: DelinquencyAction,
+    /// Error code of the last failure
+    pub failure: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PaymentCycleSkipped {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    /// Due date of the oldest cycle skipped
+    pub missed_payment: i64,
+    pub cycles_skipped: u32,
+    pub next_payment: i64,
+    pub timestamp: i64,
+    /// Seconds east of UTC that calendar due dates follow
+    pub utc_offset_seconds: i32,
+}
+
+#[event]
+pub struct SubscriptionAutoCancelled {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub user: Pubkey,
+    pub merchant: Pubkey,
+    /// Due date of the cycle whose collection window closed
+    pub missed_payment: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ExecutionClaimed {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub claimant: Pubkey,
+    pub expires_slot: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MissedPaymentPolicyUpdated {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub policy: MissedPaymentPolicy,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SwapSlippageUpdated {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub max_slippage_bps: u16,
+    pub updated_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SubscriptionPaused {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub user: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SubscriptionResumed {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub user: Pubkey,
+    pub next_payment: i64,
+    pub timestamp: i64,
+    /// Seconds east of UTC that calendar due dates follow
+    pub utc_offset_seconds: i32,
+}
+
+#[event]
+pub struct SubscriptionCancelled {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub user: Pubkey,
+    pub total_paid: u64,
+    pub payment_count: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LimitsUpdated {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub max_per_transaction: u64,
+    pub lifetime_cap: u64,
+}
+
+#[event]
+pub struct SubscriptionMigrated {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+    pub new_size: u64,
+}
+
+#[event]
+pub struct PlatformStateMigrated {
+    pub schema_version: u8,
+    pub from_version: u8,
+    pub to_version: u8,
+    pub new_size: u64,
+}
+
+#[event]
+pub struct InvoiceCreated {
+    pub schema_version: u8,
+    pub invoice: Pubkey,
+    pub merchant: Pubkey,
+    pub user: Pubkey,
+    pub subscription: Pubkey,
+    pub line_item_count: u8,
+    pub total: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InvoicePaid {
+    pub schema_version: u8,
+    pub invoice: Pubkey,
+    pub merchant: Pubkey,
+    pub user: Pubkey,
+    pub total: u64,
+    pub fee: u64,
+    /// Collected through the subscription delegation rather than paid directly
+    pub via_delegation: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InvoiceVoided {
+    pub schema_version: u8,
+    pub invoice: Pubkey,
+    pub merchant: Pubkey,
+    pub user: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UserStatsInitialized {
+    pub schema_version: u8,
+    pub user: Pubkey,
+    pub active_subscriptions: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SettlementConfigured {
+    pub schema_version: u8,
+    pub merchant: Pubkey,
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub enabled: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SettlementSwept {
+    pub schema_version: u8,
+    pub merchant: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub total_swept: u64,
+    pub timestamp: i64,
+    /// Balance held back in the vault against refund exposure
+    pub held_in_reserve: u64,
+}
+
+#[event]
+pub struct RefundLiabilityConfigured {
+    pub schema_version: u8,
+    pub merchant: Pubkey,
+    pub mint: Pubkey,
+    pub window_days: u16,
+    pub reserve_bps: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeTreasuryConfigured {
+    pub schema_version: u8,
+    pub mint: Pubkey,
+    pub shares: Vec<FeeShare>,
+    pub sweep_interval: i64,
+    pub enabled: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeesSwept {
+    pub schema_version: u8,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub total_distributed: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MerchantVolumeOpened {
+    pub schema_version: u8,
+    pub merchant: Pubkey,
+    pub mint: Pubkey,
+    pub period: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MerchantReportOpened {
+    pub schema_version: u8,
+    pub merchant: Pubkey,
+    pub mint: Pubkey,
+    pub year: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RevenueForecastOpened {
+    pub schema_version: u8,
+    pub merchant: Pubkey,
+    pub mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MerchantStatementOpened {
+    pub schema_version: u8,
+    pub merchant: Pubkey,
+    pub mint: Pubkey,
+    pub year: u16,
+    pub month: u8,
+    pub timestamp: i64,
+}
+
+/// Period summary for accounting systems, emitted through a self-CPI so
+/// it survives log truncation
+#[event]
+pub struct SettlementPeriodClosed {
+    pub schema_version: u8,
+    pub statement: Pubkey,
+    pub merchant: Pubkey,
+    pub mint: Pubkey,
+    pub year: u16,
+    pub month: u8,
+    pub period_start: i64,
+    pub period_end: i64,
+    pub payment_count: u32,
+    pub gross_volume: u64,
+    pub refunds: u64,
+    pub fees: u64,
+    pub net_settlement: u64,
+    pub timestamp: i64,
 }
 
-impl PlatformState {
-    pub const SPACE: usize = 8 + 32 + 8 + 8 + 8 + 2 + 1 + 2 + 8 + 8 + 8 + 8 + 1;
+#[event]
+pub struct RebatesConfigured {
+    pub schema_version: u8,
+    pub mint: Pubkey,
+    pub tiers: Vec<RebateTier>,
+    pub timestamp: i64,
 }
 
-#[account]
-pub struct Subscription {
-    pub user: Pubkey,                      // 32
-    pub merchant: Pubkey,                  // 32
-    pub user_token_account: Pubkey,        // 32
-    pub merchant_token_account: Pubkey,    // 32
-    pub amount: u64,                       // 8
-    pub original_amount: u64,              // 8 - for variance check
-    pub frequency_seconds: i64,            // 8
-    pub last_payment: i64,                 // 8
-    pub next_payment: i64,                 // 8
-    pub total_paid: u64,                   // 8
-    pub payment_count: u32,                // 4
-    pub is_active: bool,                   // 1
-    pub is_paused: bool,                   // 1
-    pub payment_in_progress: bool,         // 1 - REENTRANCY GUARD
-    pub max_per_transaction: u64,          // 8
-    pub lifetime_cap: u64,                 // 8
-    pub merchant_name: String,             // 4 + 32
-    pub created_at: i64,                   // 8
-    pub bump: u8,                          // 1
-}
-
-impl Subscription {
-    pub const MAX_NAME_LEN: usize = MAX_MERCHANT_NAME_LEN;
-    pub const SPACE: usize = 8 + // discriminator
-        32 + 32 + 32 + 32 + // pubkeys
-        8 + 8 + 8 + 8 + 8 + 8 + // u64/i64 fields
-        4 + 1 + 1 + 1 + 8 + 8 + // counters and bools (added +1 for payment_in_progress)
-        (4 + Self::MAX_NAME_LEN) + // string
-        8 + 1; // created_at + bump
+#[event]
+pub struct RebateClaimed {
+    pub schema_version: u8,
+    pub merchant: Pubkey,
+    pub mint: Pubkey,
+    pub period: i64,
+    pub volume: u64,
+    pub rebate_bps: u16,
+    pub amount: u64,
+    pub timestamp: i64,
 }
 
-// ============================================================================
-// Context Structures
-// ============================================================================
+#[event]
+pub struct YieldConfigUpdated {
+    pub schema_version: u8,
+    pub lending_programs: Vec<Pubkey>,
+    pub merchant_yield_bps: u16,
+    pub enabled: bool,
+    pub timestamp: i64,
+}
 
-#[derive(Accounts)]
-pub struct InitializePlatform<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = PlatformState::SPACE,
-        seeds = [b"platform"],
-        bump
-    )]
-    pub platform_state: Account<'info, PlatformState>,
+#[event]
+pub struct HeldFundsDeployed {
+    pub schema_version: u8,
+    pub merchant: Pubkey,
+    pub mint: Pubkey,
+    pub lending_program: Pubkey,
+    pub amount: u64,
+    pub deployed: u64,
+    pub timestamp: i64,
+}
 
-    #[account(mut)]
-    pub authority: Signer<'info>,
+#[event]
+pub struct HeldFundsWithdrawn {
+    pub schema_version: u8,
+    pub merchant: Pubkey,
+    pub mint: Pubkey,
+    pub lending_program: Pubkey,
+    pub principal: u64,
+    pub merchant_yield: u64,
+    pub platform_yield: u64,
+    pub deployed: u64,
+    pub timestamp: i64,
+}
 
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct SwapFallbackSettled {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub input_mint: Pubkey,
+    /// Subscriber's token spent, at the oracle price without slippage
+    pub amount_in: u64,
+    pub oracle_price: i64,
+    pub oracle_expo: i32,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct CreateSubscription<'info> {
-    #[account(
-        init,
-        payer = user,
-        space = Subscription::SPACE,
-        seeds = [
-            b"subscription",
-            user.key().as_ref(),
-            merchant.key().as_ref(),
-        ],
-        bump
-    )]
-    pub subscription: Account<'info, Subscription>,
+#[event]
+pub struct SwapConfigUpdated {
+    pub schema_version: u8,
+    pub swap_programs: Vec<Pubkey>,
+    pub blocked_mints: Vec<Pubkey>,
+    pub timestamp: i64,
+}
 
-    #[account(
-        mut,
-        seeds = [b"platform"],
-        bump = platform_state.bump
-    )]
-    pub platform_state: Account<'info, PlatformState>,
+#[event]
+pub struct SwapAssetConfigured {
+    pub schema_version: u8,
+    pub mint: Pubkey,
+    pub price_feed: Pubkey,
+    pub max_slippage_bps: u16,
+    pub max_price_age: i64,
+    pub max_confidence_bps: u16,
+    pub enabled: bool,
+    pub timestamp: i64,
+}
 
-    #[account(mut)]
-    pub user: Signer<'info>,
+#[event]
+pub struct AcceptedMintConfigured {
+    pub schema_version: u8,
+    pub mint: Pubkey,
+    pub decimals: u8,
+    pub price_feed: Pubkey,
+    pub min_amount: u64,
+    pub enabled: bool,
+    pub timestamp: i64,
+}
 
-    /// Merchant account from merchant registry
-    /// Validated manually in create_subscription to avoid stack overflow
-    /// CHECK: Validated against merchant registry in create_subscription function
-    pub merchant: AccountInfo<'info>,
+#[event]
+pub struct PaymentSwapped {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    pub max_amount_in: u64,
+    pub amount_out: u64,
+    pub oracle_price: i64,
+    pub oracle_expo: i32,
+    pub timestamp: i64,
+    /// Least output accepted: the oracle value of `amount_in` less slippage, at least the charge
+    pub min_amount_out: u64,
+}
 
-    #[account(
-        mut,
-        constraint = user_token_account.owner == user.key() @ ErrorCode::InvalidTokenAccountOwner,
-        constraint = user_token_account.mint == mint.key() @ ErrorCode::InvalidMint
-    )]
-    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+#[event]
+pub struct SubscriptionForceCancelled {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub user: Pubkey,
+    pub merchant: Pubkey,
+    pub authority: Pubkey,
+    pub reason: ForceCancelReason,
+    pub timestamp: i64,
+}
 
-    #[account(mut)]
-    pub merchant_token_account: InterfaceAccount<'info, TokenAccount>,
+#[event]
+pub struct EmergencyRevokeAllExecuted {
+    pub schema_version: u8,
+    pub user: Pubkey,
+    pub subscriptions_cancelled: u32,
+    pub token_accounts_revoked: u32,
+    pub timestamp: i64,
+}
 
-    pub mint: InterfaceAccount<'info, Mint>,
-    pub token_program: Interface<'info, TokenInterface>,
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct DueBucketOpened {
+    pub schema_version: u8,
+    pub due_bucket: Pubkey,
+    pub merchant: Pubkey,
+    pub day: i64,
+    pub payer: Pubkey,
 }
 
-#[derive(Accounts)]
-pub struct ExecutePayment<'info> {
-    #[account(
-        mut,
-        seeds = [
-            b"subscription",
-            subscription.user.as_ref(),
-            subscription.merchant.as_ref(),
-        ],
-        bump = subscription.bump
-    )]
-    pub subscription: Account<'info, Subscription>,
+#[event]
+pub struct OfferCreated {
+    pub schema_version: u8,
+    pub offer: Pubkey,
+    pub merchant: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub frequency_seconds: i64,
+    pub expires_at: i64,
+}
 
-    #[account(
-        mut,
-        seeds = [b"platform"],
-        bump = platform_state.bump
-    )]
-    pub platform_state: Account<'info, PlatformState>,
+#[event]
+pub struct OfferPublished {
+    pub schema_version: u8,
+    pub offer: Pubkey,
+    pub merchant: Pubkey,
+    pub offer_id: u64,
+    pub amount: u64,
+    pub frequency_seconds: i64,
+    pub max_acceptances: u32,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct OfferAccepted {
+    pub schema_version: u8,
+    pub offer: Pubkey,
+    pub subscription: Pubkey,
+    pub merchant: Pubkey,
+    pub user: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OfferCancelled {
+    pub schema_version: u8,
+    pub offer: Pubkey,
+    pub merchant: Pubkey,
+    pub user: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SetupFeeCharged {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub user: Pubkey,
+    pub merchant: Pubkey,
+    /// Charged to the user, counted against the lifetime cap
+    pub setup_fee: u64,
+    pub fee: u64,
+    pub merchant_received: u64,
+    pub timestamp: i64,
+    /// Total taken from the user: `setup_fee`, plus `fee` when the user bears it
+    pub gross_amount: u64,
+}
+
+#[event]
+pub struct TrialEnded {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub user: Pubkey,
+    pub merchant: Pubkey,
+    pub trial_ends_at: i64,
+    pub first_payment: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TrialConverted {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub user: Pubkey,
+    pub delegated_amount: u64,
+    pub next_payment: i64,
+    pub timestamp: i64,
+    /// Seconds east of UTC that calendar due dates follow
+    pub utc_offset_seconds: i32,
+}
+
+#[event]
+pub struct PromoCreated {
+    pub schema_version: u8,
+    pub promo: Pubkey,
+    pub merchant: Pubkey,
+    pub code: String,
+    pub discount: PromoDiscount,
+    pub discounted_payments: u16,
+    pub max_redemptions: u32,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PromoRedeemed {
+    pub schema_version: u8,
+    pub promo: Pubkey,
+    pub subscription: Pubkey,
+    pub user: Pubkey,
+    pub discount_amount: u64,
+    pub discounted_payments: u16,
+    pub redemptions: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PlanCreated {
+    pub schema_version: u8,
+    pub plan: Pubkey,
+    pub merchant: Pubkey,
+    pub plan_id: u64,
+    pub price: u64,
+    pub frequency_seconds: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PlanUpdated {
+    pub schema_version: u8,
+    pub plan: Pubkey,
+    pub merchant: Pubkey,
+    pub old_price: u64,
+    pub price: u64,
+    pub is_active: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PlanChanged {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub user: Pubkey,
+    pub old_plan: Pubkey,
+    pub new_plan: Pubkey,
+    pub old_amount: u64,
+    pub new_amount: u64,
+    /// Queued onto the next payment (positive = charge, negative = credit)
+    pub proration_adjustment: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AmountChangeProposed {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub user: Pubkey,
+    pub merchant: Pubkey,
+    pub old_amount: u64,
+    pub new_amount: u64,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AmountChangeAccepted {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub user: Pubkey,
+    pub merchant: Pubkey,
+    pub old_amount: u64,
+    pub new_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PaymentSnoozed {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub user: Pubkey,
+    pub days: u8,
+    pub next_payment: i64,
+    pub timestamp: i64,
+    /// Seconds east of UTC that calendar due dates follow
+    pub utc_offset_seconds: i32,
+}
+
+#[event]
+pub struct DelegationChecked {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub user: Pubkey,
+    pub delegate_matches: bool,
+    pub delegated_amount: u64,
+    pub required_amount: u64,
+    pub healthy: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DelegationRefreshed {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub user: Pubkey,
+    pub delegated_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowDeposited {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct EscrowWithdrawn {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub balance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct UsageCapConfigured {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub period_cap: u64,
+    pub period_start: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MeteredPaymentExecuted {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub merchant: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    pub period_usage: u64,
+    pub period_cap: u64,
+    pub remaining_lifetime_allowance: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokenAccountMigrated {
+    pub schema_version: u8,
+    pub subscription: Pubkey,
+    pub user: Pubkey,
+    pub old_token_account: Pubkey,
+    pub new_token_account: Pubkey,
+    pub delegated_amount: u64,
+    pub timestamp: i64,
+}
 
-    /// CHECK: User doesn't need to sign for automated payments
-    pub user: UncheckedAccount<'info>,
+/// Global cap reported by `SubscriptionCapReached`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SubscriptionCapKind {
+    ActiveSubscriptions,
+    DailyOnboarding,
+}
 
-    #[account(
-        mut,
-        constraint = user_token_account.key() == subscription.user_token_account @ ErrorCode::InvalidTokenAccount
-    )]
-    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+#[event]
+pub struct SubscriptionCapsUpdated {
+    pub schema_version: u8,
+    pub max_active_subscriptions: u64,
+    pub max_daily_new_subscriptions: u32,
+    pub timestamp: i64,
+}
 
-    #[account(
-        mut,
-        constraint = merchant_token_account.key() == subscription.merchant_token_account @ ErrorCode::InvalidTokenAccount
-    )]
-    pub merchant_token_account: InterfaceAccount<'info, TokenAccount>,
+#[event]
+pub struct SubscriptionCapReached {
+    pub schema_version: u8,
+    pub cap: SubscriptionCapKind,
+    pub limit: u64,
+    pub timestamp: i64,
+}
 
-    #[account(mut)]
-    pub platform_fee_account: InterfaceAccount<'info, TokenAccount>,
+#[event]
+pub struct TokenProgramPauseUpdated {
+    pub schema_version: u8,
+    pub token_program: Pubkey,
+    pub paused: bool,
+    pub timestamp: i64,
+}
 
-    pub mint: InterfaceAccount<'info, Mint>,
-    pub token_program: Interface<'info, TokenInterface>,
+#[event]
+pub struct CollectionWindowUpdated {
+    pub schema_version: u8,
+    pub collection_window_seconds: i64,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct ModifySubscription<'info> {
-    #[account(
-        mut,
-        seeds = [
-            b"subscription",
-            subscription.user.as_ref(),
-            subscription.merchant.as_ref(),
-        ],
-        bump = subscription.bump,
-        has_one = user @ ErrorCode::UnauthorizedUser
-    )]
-    pub subscription: Account<'info, Subscription>,
+#[event]
+pub struct PriorityReserveUpdated {
+    pub schema_version: u8,
+    pub priority_reserve_bps: u16,
+    pub timestamp: i64,
+}
 
-    #[account(
-        mut,
-        seeds = [b"platform"],
-        bump = platform_state.bump
-    )]
-    pub platform_state: Account<'info, PlatformState>,
+#[event]
+pub struct FeePolicyUpdated {
+    pub schema_version: u8,
+    pub fee_rounding: FeeRounding,
+    pub fee_dust_policy: FeeDustPolicy,
+    pub fee_dust_threshold: u64,
+    pub timestamp: i64,
+}
 
-    pub user: Signer<'info>,
+#[event]
+pub struct FirstSubscriptionWaiverUpdated {
+    pub schema_version: u8,
+    pub free_payments: u8,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct CancelSubscription<'info> {
-    #[account(
-        mut,
-        seeds = [
-            b"subscription",
-            subscription.user.as_ref(),
-            subscription.merchant.as_ref(),
-        ],
-        bump = subscription.bump,
-        has_one = user @ ErrorCode::UnauthorizedUser
-    )]
-    pub subscription: Account<'info, Subscription>,
+#[event]
+pub struct FeeHolidaysConfigured {
+    pub schema_version: u8,
+    pub holidays: Vec<FeeHoliday>,
+    pub timestamp: i64,
+}
 
-    #[account(
-        mut,
-        seeds = [b"platform"],
-        bump = platform_state.bump
-    )]
-    pub platform_state: Account<'info, PlatformState>,
+#[event]
+pub struct MerchantFeeScheduleUpdated {
+    pub schema_version: u8,
+    pub merchant: Pubkey,
+    /// Negotiated schedule (none = platform defaults)
+    pub fee_schedule: Option<FeeSchedule>,
+    pub timestamp: i64,
+}
 
-    #[account(
-        mut,
-        constraint = user_token_account.key() == subscription.user_token_account @ ErrorCode::InvalidTokenAccount
-    )]
-    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+#[event]
+pub struct FeeExemptionUpdated {
+    pub schema_version: u8,
+    /// Exempted subscription (default when the merchant was exempted)
+    pub subscription: Pubkey,
+    pub merchant: Pubkey,
+    pub fee_exempt: bool,
+    pub timestamp: i64,
+}
 
-    pub user: Signer<'info>,
-    pub token_program: Interface<'info, TokenInterface>,
+#[event]
+pub struct MerchantVerificationPolicyUpdated {
+    pub schema_version: u8,
+    pub allow_unverified_merchants: bool,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct CloseSubscription<'info> {
-    #[account(
-        mut,
-        close = user,
-        seeds = [
-            b"subscription",
-            subscription.user.as_ref(),
-            subscription.merchant.as_ref(),
-        ],
-        bump = subscription.bump,
-        has_one = user @ ErrorCode::UnauthorizedUser,
-        constraint = !subscription.is_active @ ErrorCode::SubscriptionStillActive
-    )]
-    pub subscription: Account<'info, Subscription>,
+#[event]
+pub struct WalletDenylisted {
+    pub schema_version: u8,
+    pub wallet: Pubkey,
+    /// true when added, false when removed
+    pub denied: bool,
+    pub timestamp: i64,
+}
 
-    #[account(mut)]
-    pub user: Signer<'info>,
+#[event]
+pub struct UserBlockUpdated {
+    pub schema_version: u8,
+    pub merchant: Pubkey,
+    pub user: Pubkey,
+    /// true when blocked, false when unblocked
+    pub blocked: bool,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct UpdateLimits<'info> {
-    #[account(
-        mut,
-        seeds = [
-            b"subscription",
-            subscription.user.as_ref(),
-            subscription.merchant.as_ref(),
-        ],
-        bump = subscription.bump,
-        has_one = user @ ErrorCode::UnauthorizedUser
-    )]
-    pub subscription: Account<'info, Subscription>,
+#[event]
+pub struct MerchantPolicyUpdated {
+    pub schema_version: u8,
+    pub merchant: Pubkey,
+    pub allowlist_only: bool,
+    pub max_active_subscribers: u32,
+    pub max_snooze_days: u8,
+    pub active_subscribers: u32,
+    pub timestamp: i64,
+    /// Billing date new subscriptions align to (0 = bill from signup)
+    pub billing_anchor: i64,
+    pub swap_fallback: SwapFallback,
+    pub fee_bearer: FeeBearer,
+}
 
-    #[account(
-        mut,
-        constraint = user_token_account.key() == subscription.user_token_account @ ErrorCode::InvalidTokenAccount
-    )]
-    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+#[event]
+pub struct RetryPolicyUpdated {
+    pub schema_version: u8,
+    pub merchant: Pubkey,
+    pub retry_policy: RetryPolicy,
+    pub timestamp: i64,
+}
 
-    pub mint: InterfaceAccount<'info, Mint>,
-    pub user: Signer<'info>,
-    pub token_program: Interface<'info, TokenInterface>,
+#[event]
+pub struct AllowlistUpdated {
+    pub schema_version: u8,
+    pub merchant: Pubkey,
+    pub user: Pubkey,
+    /// true when approved, false when removed
+    pub allowed: bool,
+    pub timestamp: i64,
 }
 
-#[derive(Accounts)]
-pub struct AdminAction<'info> {
-    #[account(
-        mut,
-        seeds = [b"platform"],
-        bump = platform_state.bump,
-        has_one = authority @ ErrorCode::UnauthorizedAdmin
-    )]
-    pub platform_state: Account<'info, PlatformState>,
+#[event]
+pub struct EmergencyPauseActivated {
+    pub schema_version: u8,
+    pub timestamp: i64,
+    pub reason: String,
+    pub expires_at: i64,
+}
 
-    pub authority: Signer<'info>,
+#[event]
+pub struct PauseDurationUpdated {
+    pub schema_version: u8,
+    pub pause_duration_seconds: i64,
+    pub timestamp: i64,
 }
 
-// ============================================================================
-// Events
-// ============================================================================
+#[event]
+pub struct GovernanceEnabled {
+    pub schema_version: u8,
+    pub realm: Pubkey,
+    pub governance: Pubkey,
+    pub native_treasury: Pubkey,
+    pub previous_authority: Pubkey,
+    pub pause_guardian: Pubkey,
+    pub timestamp: i64,
+}
 
 #[event]
-pub struct PlatformInitialized {
-    pub authority: Pubkey,
+pub struct ConfigChangeScheduled {
+    pub schema_version: u8,
+    pub change: ConfigChange,
+    pub executable_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PlatformParamsUpdated {
+    pub schema_version: u8,
     pub fee_basis_points: u16,
-    pub daily_volume_limit: u64,
-    pub version: String,
+    pub min_fee: u64,
+    pub max_fee: u64,
+    pub timestamp: i64,
 }
 
 #[event]
-pub struct SubscriptionCreated {
-    pub subscription: Pubkey,
-    pub user: Pubkey,
-    pub merchant: Pubkey,
-    pub amount: u64,
-    pub frequency_seconds: i64,
-    pub next_payment: i64,
+pub struct ConfigChangeExecuted {
+    pub schema_version: u8,
+    pub change: ConfigChange,
+    pub scheduled_at: i64,
+    pub timestamp: i64,
 }
 
 #[event]
-pub struct PaymentExecuted {
-    pub subscription: Pubkey,
-    pub amount: u64,
-    pub fee: u64,
-    pub merchant_received: u64,
-    pub payment_count: u32,
+pub struct AdminCouncilCreated {
+    pub schema_version: u8,
+    pub members: Vec<Pubkey>,
+    pub threshold: u8,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct SubscriptionPaused {
-    pub subscription: Pubkey,
-    pub user: Pubkey,
+pub struct CouncilActionProposed {
+    pub schema_version: u8,
+    pub proposal: Pubkey,
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub action: CouncilAction,
+    pub expires_at: i64,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct SubscriptionResumed {
-    pub subscription: Pubkey,
-    pub user: Pubkey,
-    pub next_payment: i64,
+pub struct CouncilActionApproved {
+    pub schema_version: u8,
+    pub proposal: Pubkey,
+    pub member: Pubkey,
+    pub approvals: u8,
+    pub threshold: u8,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct SubscriptionCancelled {
-    pub subscription: Pubkey,
-    pub user: Pubkey,
-    pub total_paid: u64,
-    pub payment_count: u32,
+pub struct CouncilActionExecuted {
+    pub schema_version: u8,
+    pub proposal: Pubkey,
+    pub proposal_id: u64,
+    pub approvals: u8,
     pub timestamp: i64,
 }
 
 #[event]
-pub struct LimitsUpdated {
-    pub subscription: Pubkey,
-    pub max_per_transaction: u64,
-    pub lifetime_cap: u64,
+pub struct PauseGuardianUpdated {
+    pub schema_version: u8,
+    pub pause_guardian: Pubkey,
+    pub timestamp: i64,
 }
 
 #[event]
-pub struct EmergencyPauseActivated {
+pub struct TreasurySpendCapUpdated {
+    pub schema_version: u8,
+    pub max_spend_per_proposal: u64,
     pub timestamp: i64,
-    pub reason: String,
 }
 
-// ============================================================================
-// Helper Functions
-// ============================================================================
+#[event]
+pub struct TreasuryWithdrawn {
+    pub schema_version: u8,
+    pub proposal: Pubkey,
+    pub mint: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub proposal_total: u64,
+    pub timestamp: i64,
+}
 
-/// Calculate platform fee with min/max capping
-///
-/// Uses u128 for intermediate calculations to prevent overflow,
-/// then safely converts back to u64.
-fn calculate_fee(
-    amount: u64,
-    basis_points: u16,
-    min_fee: u64,
-    max_fee: u64,
-) -> Result<u64> {
-    let fee = (amount as u128)
-        .checked_mul(basis_points as u128)
-        .ok_or(ErrorCode::Overflow)?
-        .checked_div(BASIS_POINTS_DIVISOR)
-        .ok_or(ErrorCode::Overflow)?;
-
-    let fee_u64 = u64::try_from(fee).map_err(|_| ErrorCode::Overflow)?;
-
-    Ok(fee_u64.max(min_fee).min(max_fee))
+#[event]
+pub struct TreasuryBuyback {
+    pub schema_version: u8,
+    pub proposal: Pubkey,
+    pub mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub proposal_total: u64,
+    pub timestamp: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_account_owner_matches_program_id() {
+        // lutrii-common resolves account ownership via its own declare_id!
+        assert_eq!(lutrii_common::ID, crate::ID);
+        assert_eq!(<Subscription as Owner>::owner(), crate::ID);
+    }
+
+    #[test]
+    fn test_verify_active_subscription_interface_matches_program() {
+        let (user, merchant, plan) = (Pubkey::new_unique(), Pubkey::new_unique(), Some(Pubkey::new_unique()));
+        let ix = lutrii_common::verify_active_subscription_ix(&user, &merchant, 0, plan);
+        let expected = instruction::VerifyActiveSubscription {
+            _user: user,
+            _merchant: merchant,
+            _nonce: 0,
+            plan,
+        };
+        assert_eq!(ix.data, anchor_lang::InstructionData::data(&expected));
+        assert_eq!(
+            ix.accounts[0].pubkey,
+            Pubkey::find_program_address(&[b"subscription", user.as_ref(), merchant.as_ref()], &crate::ID).0
+        );
+
+        // Later subscriptions of the pair are read at their own address
+        let ix = lutrii_common::verify_active_subscription_ix(&user, &merchant, 1, plan);
+        let expected = instruction::VerifyActiveSubscription {
+            _user: user,
+            _merchant: merchant,
+            _nonce: 1,
+            plan,
+        };
+        assert_eq!(ix.data, anchor_lang::InstructionData::data(&expected));
+        assert_eq!(
+            ix.accounts[0].pubkey,
+            Pubkey::find_program_address(
+                &[b"subscription", user.as_ref(), merchant.as_ref(), &1u32.to_le_bytes()],
+                &crate::ID
+            )
+            .0
+        );
+
+        let access = SubscriptionAccess::not_subscribed(ix.accounts[0].pubkey);
+        assert_eq!(access.try_to_vec().unwrap().len(), SubscriptionAccess::SPACE);
+    }
+
+    #[test]
+    fn test_platform_state_version_offset() {
+        // version must be the first byte after the legacy layout for in-place migration
+        let platform = PlatformState {
+            authority: Pubkey::new_unique(),
+            daily_volume_limit: 0,
+            total_volume_24h: 0,
+            last_volume_reset: 0,
+            failed_tx_count: 0,
+            emergency_pause: false,
+            fee_basis_points: 0,
+            min_fee: 0,
+            max_fee: 0,
+            total_subscriptions: 0,
+            total_transactions: 0,
+            bump: 0,
+            version: PlatformState::CURRENT_VERSION,
+            fee_wallet_usdc: Pubkey::default(),
+            fee_wallet_usd1: Pubkey::default(),
+            allow_unverified_merchants: false,
+            max_active_subscriptions: 0,
+            max_daily_new_subscriptions: 0,
+            new_subscriptions_today: 0,
+            onboarding_window_start: 0,
+            collection_window_seconds: 0,
+            spl_token_paused: false,
+            token_2022_paused: false,
+            priority_reserve_bps: 0,
+            usdc_mint: Pubkey::default(),
+            usd1_mint: Pubkey::default(),
+            fee_rounding: FeeRounding::Floor,
+            fee_dust_policy: FeeDustPolicy::Waive,
+            fee_dust_threshold: 0,
+            fee_exempt_payments: 0,
+            fees_waived: 0,
+            first_subscription_free_payments: 0,
+            pause_duration_seconds: 0,
+            pause_expires_at: 0,
+            pause_reason: [0; MAX_PAUSE_REASON_LEN],
+            executor_reward_bps: 0,
+            executor_reward_flat: 0,
+            executor_rewards_paid: 0,
+            council_enabled: false,
+            config_change_delay_seconds: 0,
+        };
+        let mut data = Vec::new();
+        platform.try_serialize(&mut data).unwrap();
+
+        assert_eq!(data.len(), PlatformState::SPACE);
+        assert_eq!(data[PlatformState::VERSION_OFFSET], PlatformState::CURRENT_VERSION);
+    }
+
+    #[test]
+    fn test_executor_reward_capped_at_fee() {
+        let mut data = vec![0u8; PlatformState::SPACE];
+        data[..8].copy_from_slice(&<PlatformState as anchor_lang::Discriminator>::DISCRIMINATOR);
+        let mut platform = PlatformState::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(platform.executor_reward(1_000_000, 25_000), 0);
+
+        platform.executor_reward_flat = 1_000;
+        platform.executor_reward_bps = 50;
+        assert_eq!(platform.executor_reward(1_000_000, 25_000), 6_000);
+
+        // Never more than the fee it comes out of
+        assert_eq!(platform.executor_reward(1_000_000, 4_000), 4_000);
+    }
+
+    #[test]
+    fn test_emergency_pause_expiry() {
+        let mut data = vec![0u8; PlatformState::SPACE];
+        data[..8].copy_from_slice(&<PlatformState as anchor_lang::Discriminator>::DISCRIMINATOR);
+        let mut platform = PlatformState::try_deserialize(&mut &data[..]).unwrap();
+
+        // Pauses set before v4 have no expiry
+        platform.emergency_pause = true;
+        assert!(platform.is_paused(i64::MAX));
+
+        let expires_at = platform.activate_pause("Exploit", 1_000).unwrap();
+        assert_eq!(expires_at, 1_000 + DEFAULT_PAUSE_DURATION_SECONDS);
+        assert!(platform.is_paused(expires_at - 1));
+        assert!(!platform.is_paused(expires_at));
+        assert_eq!(platform.pause_reason(), "Exploit");
+
+        assert!(platform.activate_pause("", 1_000).is_err());
+        assert!(platform.activate_pause(&"x".repeat(MAX_PAUSE_REASON_LEN + 1), 1_000).is_err());
+    }
+
+    #[test]
+    fn test_admin_audit_trail_overwrites_oldest() {
+        let mut data = vec![0u8; AdminAuditLog::LEN];
+        data[..8].copy_from_slice(&<AdminAuditLog as anchor_lang::Discriminator>::DISCRIMINATOR);
+        let mut log = AdminAuditLog::try_deserialize(&mut &data[..]).unwrap();
+        assert_eq!(log.trail.chronological().count(), 0);
+
+        let actor = Pubkey::new_unique();
+        let capacity = lutrii_common::ADMIN_AUDIT_LOG_CAPACITY as i64;
+        for timestamp in 0..capacity + 3 {
+            log.trail.record(actor, AdminAuditAction::FeeChange, timestamp);
+        }
+
+        // The three oldest entries were overwritten; order is preserved
+        let timestamps: Vec<i64> = log.trail.chronological().map(|entry| entry.timestamp).collect();
+        assert_eq!(timestamps, (3..capacity + 3).collect::<Vec<_>>());
+        assert_eq!(log.trail.total_actions, capacity as u64 + 3);
+    }
+
+    #[test]
+    fn test_settlement_stablecoins() {
+        let mut data = vec![0u8; PlatformState::SPACE];
+        data[..8].copy_from_slice(&<PlatformState as anchor_lang::Discriminator>::DISCRIMINATOR);
+        let mut platform = PlatformState::try_deserialize(&mut &data[..]).unwrap();
+        let other = Pubkey::new_unique();
+
+        // Migrated platforms accept any mint until the stablecoins are recorded
+        assert!(platform.is_settlement_stablecoin(&other));
+
+        platform.usdc_mint = Pubkey::new_unique();
+        platform.usd1_mint = Pubkey::new_unique();
+        assert!(platform.is_settlement_stablecoin(&platform.usdc_mint));
+        assert!(platform.is_settlement_stablecoin(&platform.usd1_mint));
+        assert!(!platform.is_settlement_stablecoin(&other));
+    }
+
+    #[test]
+    fn test_payment_outflow_by_fee_bearer() {
+        let mut data = vec![0u8; PlatformState::SPACE];
+        data[..8].copy_from_slice(&<PlatformState as anchor_lang::Discriminator>::DISCRIMINATOR);
+        let mut platform = PlatformState::try_deserialize(&mut &data[..]).unwrap();
+        platform.fee_basis_points = 250;
+        platform.max_fee = u64::MAX;
+
+        // 2.5% of 10 USDC rides on top only when the user bears it
+        assert_eq!(platform.payment_outflow(FeeBearer::Merchant, 10_000_000, None).unwrap(), 10_000_000);
+        assert_eq!(platform.payment_outflow(FeeBearer::User, 10_000_000, None).unwrap(), 10_250_000);
+        assert_eq!(FeeBearer::User.merchant_amount(10_000_000, 250_000), Some(10_000_000));
+        assert_eq!(FeeBearer::Merchant.merchant_amount(10_000_000, 250_000), Some(9_750_000));
+    }
 }
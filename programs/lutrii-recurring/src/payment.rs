@@ -0,0 +1,659 @@
+//! Checks, fee treatment and bookkeeping shared by the payment instructions
+//!
+//! `execute_payment` and `execute_payment_with_swap` differ only in where
+//! the tokens come from and how they reach the merchant. Both run the same
+//! guards and missed-cycle handling, charge under the same fee treatment and
+//! caps, back failed collections off the same way and keep the same
+//! subscription, platform and merchant records. `execute_metered_payment`
+//! shares the guards, pricing and records but not the schedule.
+
+use anchor_lang::prelude::*;
+use lutrii_common::{MissedPaymentPolicy, Subscription, EVENT_SCHEMA_VERSION};
+use lutrii_core::{limits, proration, schedule, variance, SECONDS_PER_DAY};
+use lutrii_merchant_registry::Merchant as MerchantAccount;
+use crate::errors::ErrorCode;
+use crate::state::{
+    DelinquencyAction, DueBucket, FeeSchedule, FeeTreatment, MerchantPolicy, MerchantReport,
+    MerchantStatement, MerchantVolume, Plan, RefundLiability, RevenueForecast,
+    UserDelegate, UserStats,
+};
+use crate::{
+    PaymentCycleSkipped, PaymentExecuted, PaymentRetryScheduled, PlatformState,
+    SubscriptionAutoCancelled, SubscriptionDelinquent, TrialEnded,
+};
+
+/// Accounts a payment checks and updates, however it settles
+pub(crate) struct PaymentAccounts<'a, 'info> {
+    pub subscription: &'a mut Account<'info, Subscription>,
+    pub platform: &'a mut Account<'info, PlatformState>,
+    pub user_delegate: &'a mut Account<'info, UserDelegate>,
+    /// Registry account placing the payment in its velocity class
+    pub merchant: &'a Account<'info, MerchantAccount>,
+    /// Merchant's policy PDA; may be uninitialized
+    pub merchant_policy: &'a UncheckedAccount<'info>,
+    /// Keeper signing as the execution claimant (default = none)
+    pub claimant: Pubkey,
+    pub plan: Option<&'a Account<'info, Plan>>,
+    /// Platform's fee holidays PDA; may be uninitialized
+    pub fee_holidays: &'a UncheckedAccount<'info>,
+    /// User's stats PDA; may be uninitialized
+    pub user_stats: &'a UncheckedAccount<'info>,
+    pub due_bucket: Option<&'a mut Account<'info, DueBucket>>,
+    pub next_due_bucket: Option<&'a mut Account<'info, DueBucket>>,
+    /// Merchant's tracking PDAs, each for the payment's mint and the
+    /// current period; may be uninitialized
+    pub revenue_forecast: &'a UncheckedAccount<'info>,
+    pub merchant_volume: &'a UncheckedAccount<'info>,
+    pub merchant_report: &'a UncheckedAccount<'info>,
+    pub merchant_statement: &'a UncheckedAccount<'info>,
+    pub refund_liability: &'a UncheckedAccount<'info>,
+}
+
+/// A payment that passed every check, and what it moves
+pub(crate) struct DuePayment {
+    /// Charged for the cycle, after proration and any promo discount, or
+    /// for the usage billed
+    pub charge: u64,
+    /// Platform fee collected (0 when waived)
+    pub fee: u64,
+    /// Leaves the user: the charge plus a fee the user bears
+    pub outflow: u64,
+    /// Nets to the merchant
+    pub merchant_amount: u64,
+    pub fee_treatment: FeeTreatment,
+    /// Fee the treatment waived
+    pub waived_fee: u64,
+    fee_dust: u64,
+    proration_adjustment: i64,
+    carried_proration: i64,
+    /// A full payment including any fee on top, before waivers
+    payment_outflow: u64,
+    new_total: u64,
+    new_volume: u64,
+    forecast_entry: Option<(u64, i64)>,
+    schedule_now: i64,
+    trial_ending: bool,
+}
+
+impl<'a, 'info> PaymentAccounts<'a, 'info> {
+    /// Check the subscription can be charged in `mint` now and price the payment
+    ///
+    /// Cycles missed past the collection window are skipped, or cancel the
+    /// subscription under `MissedPaymentPolicy::AutoCancel`; the execution
+    /// then ends there and `None` is returned.
+    pub fn begin(
+        &mut self,
+        token_program: &Pubkey,
+        mint: &Pubkey,
+        clock: &Clock,
+    ) -> Result<Option<DuePayment>> {
+        self.check_chargeable(token_program, mint, clock)?;
+        let subscription = &mut *self.subscription;
+        let platform = &*self.platform;
+        let forecast_entry = subscription.forecast_entry();
+
+        // A live claim_execution reserves the payment for its keeper. Every
+        // outcome that commits (payment, retry, skip, cancel) spends the claim.
+        require!(
+            !subscription.claimed_by_other(&self.claimant, clock.slot),
+            ErrorCode::ExecutionClaimed
+        );
+        subscription.execution_claimant = Pubkey::default();
+        subscription.claim_expires_slot = 0;
+
+        // Schedule fields count on the subscription's own clock (seconds or slots)
+        let unit = subscription.schedule_unit;
+        let schedule_now = unit.now(clock);
+
+        // Cycles left uncollected on time (keeper outage) follow the
+        // subscription's missed-payment policy instead of stacking up
+        if subscription.missed_payment_policy == MissedPaymentPolicy::AutoCancel
+            && schedule::collection_closed(
+                schedule_now,
+                subscription.next_payment,
+                unit.span(platform.collection_window_seconds),
+            )
+        {
+            self.auto_cancel(forecast_entry, clock.unix_timestamp)?;
+            return Ok(None);
+        }
+        if self.skip_missed_cycles(forecast_entry, schedule_now, clock.unix_timestamp)? {
+            return Ok(None);
+        }
+
+        let subscription = &mut *self.subscription;
+        let platform = &*self.platform;
+        require!(
+            schedule::is_due(schedule_now, subscription.next_payment),
+            ErrorCode::PaymentNotDue
+        );
+        require!(
+            schedule_now >= subscription.next_retry_at,
+            ErrorCode::RetryBackoffActive
+        );
+
+        // Plan-bound subscriptions charge the plan's current price, held to
+        // the variance window below
+        match self.plan {
+            Some(plan) => subscription.amount = plan.price,
+            None => require!(subscription.plan == Pubkey::default(), ErrorCode::PlanRequired),
+        }
+
+        // Apply any pending proration from a mid-cycle plan change
+        let (charge, carried_proration) =
+            proration::apply_adjustment(subscription.amount, subscription.pending_proration)
+                .map_err(ErrorCode::from)?;
+        let proration_adjustment = subscription.pending_proration - carried_proration;
+        // Less any promo code discount still running
+        let charge = subscription.discounted(charge);
+        require!(
+            charge <= subscription.max_per_transaction,
+            ErrorCode::ExceedsTransactionCap
+        );
+
+        // Merchants with a negotiated fee schedule are charged under it
+        let custom_fee = MerchantPolicy::fee_schedule_of(self.merchant_policy)?;
+
+        // Price variance protection (10% max change from original), on what
+        // the user pays including any fee on top
+        let payment_outflow =
+            platform.payment_outflow(subscription.fee_bearer, subscription.amount, custom_fee)?;
+        if subscription.payment_count > 0 {
+            require!(
+                variance::within_variance(
+                    payment_outflow,
+                    platform.payment_outflow(
+                        subscription.fee_bearer,
+                        subscription.original_amount,
+                        custom_fee,
+                    )?,
+                    variance::MAX_PRICE_VARIANCE_BPS,
+                ),
+                ErrorCode::PriceVarianceExceeded
+            );
+        }
+
+        let first_subscription = UserStats::first_subscription_fee_waived_in(
+            self.user_stats,
+            &subscription.key(),
+            subscription.payment_count,
+            platform.first_subscription_free_payments,
+        )?;
+        // The first collection of a trial subscription converts it to paid
+        let trial_ending = subscription.trial_ends_at != 0 && subscription.payment_count == 0;
+        let priced = self.price(charge, custom_fee, first_subscription, mint, clock)?;
+
+        Ok(Some(DuePayment {
+            proration_adjustment,
+            carried_proration,
+            payment_outflow,
+            forecast_entry,
+            trial_ending,
+            ..priced
+        }))
+    }
+
+    /// Check the subscription can be charged `amount` of usage in `mint` now
+    /// and price it
+    ///
+    /// The schedule is neither checked nor advanced, and the
+    /// first-subscription fee waiver does not apply.
+    pub fn begin_usage(
+        &mut self,
+        token_program: &Pubkey,
+        mint: &Pubkey,
+        amount: u64,
+        clock: &Clock,
+    ) -> Result<DuePayment> {
+        self.check_chargeable(token_program, mint, clock)?;
+        require!(amount > 0, ErrorCode::InsufficientAmount);
+        let custom_fee = MerchantPolicy::fee_schedule_of(self.merchant_policy)?;
+        self.price(amount, custom_fee, false, mint, clock)
+    }
+
+    /// Guards every charge on the subscription runs first
+    fn check_chargeable(&mut self, token_program: &Pubkey, mint: &Pubkey, clock: &Clock) -> Result<()> {
+        // REENTRANCY GUARD - Check payment not already in progress
+        require!(
+            !self.subscription.payment_in_progress,
+            ErrorCode::PaymentInProgress
+        );
+
+        // Auto-reset daily volume if 24h passed
+        let platform = &mut *self.platform;
+        if clock.unix_timestamp >= platform.last_volume_reset + SECONDS_PER_DAY {
+            platform.total_volume_24h = 0;
+            platform.last_volume_reset = clock.unix_timestamp;
+            #[cfg(feature = "verbose-logs")]
+            msg!("Daily volume reset");
+        }
+
+        // Security checks
+        let subscription = &mut *self.subscription;
+        require!(!platform.is_paused(clock.unix_timestamp), ErrorCode::SystemPaused);
+        require!(
+            !platform.token_program_paused(token_program),
+            ErrorCode::TokenProgramPaused
+        );
+        require!(subscription.is_active, ErrorCode::SubscriptionInactive);
+        require!(!subscription.is_paused, ErrorCode::SubscriptionPaused);
+        require!(!subscription.delegation_deferred, ErrorCode::TrialNotConverted);
+        require!(
+            subscription.layout_version == Subscription::CURRENT_VERSION,
+            ErrorCode::SubscriptionMigrationRequired
+        );
+        require!(subscription.pin_mint(mint), ErrorCode::InvalidMint);
+        Ok(())
+    }
+
+    /// Fee, outflow and caps of a `charge` on the subscription
+    ///
+    /// `first_subscription` is set when the user's first-subscription
+    /// waiver covers the charge. Schedule fields are left as they stand.
+    fn price(
+        &self,
+        charge: u64,
+        custom_fee: Option<FeeSchedule>,
+        first_subscription: bool,
+        mint: &Pubkey,
+        clock: &Clock,
+    ) -> Result<DuePayment> {
+        let subscription = &*self.subscription;
+        let platform = &*self.platform;
+
+        // Check velocity limits
+        let new_volume = platform
+            .total_volume_24h
+            .checked_add(charge)
+            .ok_or(ErrorCode::Overflow)?;
+        require!(
+            new_volume <= platform.volume_limit(Some(&self.merchant.verification_tier)),
+            ErrorCode::VelocityExceeded
+        );
+
+        // Calculate platform fee (a fully credited payment moves no tokens)
+        let (fee, fee_dust) = platform.recurring_fee(charge, subscription.fee_dust, custom_fee)?;
+
+        // Fee-exempt and promotional payments pay no platform fee and
+        // accrue no dust
+        let fee_treatment = FeeTreatment::of(
+            subscription,
+            charge,
+            self.merchant_policy,
+            self.fee_holidays,
+            first_subscription,
+            mint,
+            clock.unix_timestamp,
+        )?;
+        let (fee, fee_dust, waived_fee) = if fee_treatment.waives_fee() {
+            (0, subscription.fee_dust, fee)
+        } else {
+            (fee, fee_dust, 0)
+        };
+
+        // A fee the user bears is taken on top of the charge, within the same
+        // per-payment and lifetime caps
+        let fee_bearer = subscription.fee_bearer;
+        let outflow = fee_bearer.user_outflow(charge, fee).ok_or(ErrorCode::Overflow)?;
+        let merchant_amount = fee_bearer
+            .merchant_amount(charge, fee)
+            .ok_or(ErrorCode::InsufficientAmount)?;
+        require!(
+            outflow <= subscription.max_per_transaction,
+            ErrorCode::ExceedsTransactionCap
+        );
+        let new_total = limits::total_after_payment(subscription.total_paid, outflow)
+            .map_err(ErrorCode::from)?;
+        require!(
+            new_total <= subscription.lifetime_cap,
+            ErrorCode::ExceedsLifetimeCap
+        );
+
+        Ok(DuePayment {
+            charge,
+            fee,
+            outflow,
+            merchant_amount,
+            fee_treatment,
+            waived_fee,
+            fee_dust,
+            proration_adjustment: 0,
+            carried_proration: subscription.pending_proration,
+            payment_outflow: outflow,
+            new_total,
+            new_volume,
+            forecast_entry: subscription.forecast_entry(),
+            schedule_now: subscription.schedule_unit.now(clock),
+            trial_ending: false,
+        })
+    }
+
+    /// Record a collection that could not be made and back the cycle off
+    ///
+    /// Delinquency is recorded instead of reverting, so keepers stop
+    /// retrying an empty wallet every block. Out of retries, the merchant's
+    /// retry policy pauses or cancels the subscription.
+    pub fn fail(
+        &mut self,
+        due: &DuePayment,
+        failure: ErrorCode,
+        delegation_covers: bool,
+        clock: &Clock,
+    ) -> Result<()> {
+        let retry_policy = MerchantPolicy::retry_policy_of(self.merchant_policy)?;
+        let subscription = &mut *self.subscription;
+        subscription.retry_count = subscription.retry_count.saturating_add(1);
+        subscription.last_failure_at = clock.unix_timestamp;
+        self.platform.failed_tx_count = self.platform.failed_tx_count.saturating_add(1);
+
+        // Out of retries: the merchant's policy pauses or cancels
+        if retry_policy.is_delinquent(subscription.retry_count) {
+            let failed_attempts = subscription.retry_count;
+            subscription.retry_count = 0;
+            subscription.next_retry_at = 0;
+            match retry_policy.delinquency_action {
+                DelinquencyAction::Pause => subscription.is_paused = true,
+                DelinquencyAction::Cancel => self.deactivate()?,
+            }
+            self.unschedule(due.forecast_entry, clock.unix_timestamp)?;
+
+            let subscription = &*self.subscription;
+            emit!(SubscriptionDelinquent {
+                schema_version: EVENT_SCHEMA_VERSION,
+                subscription: subscription.key(),
+                user: subscription.user,
+                merchant: subscription.merchant,
+                failed_attempts,
+                action: retry_policy.delinquency_action,
+                failure: failure.into(),
+                timestamp: clock.unix_timestamp,
+            });
+            return Ok(());
+        }
+
+        let unit = subscription.schedule_unit;
+        subscription.next_retry_at = due
+            .schedule_now
+            .checked_add(unit.span(retry_policy.retry_delay(subscription.retry_count)))
+            .ok_or(ErrorCode::Overflow)?;
+
+        emit!(PaymentRetryScheduled {
+            schema_version: EVENT_SCHEMA_VERSION,
+            subscription: subscription.key(),
+            retry_count: subscription.retry_count,
+            next_retry_at: subscription.next_retry_at,
+            delegation_covers,
+            timestamp: clock.unix_timestamp,
+            failure: failure.into(),
+        });
+
+        #[cfg(feature = "verbose-logs")]
+        msg!(
+            "⚠️ Payment not collectable, retry #{} at {}",
+            subscription.retry_count,
+            subscription.next_retry_at
+        );
+        Ok(())
+    }
+
+    /// Commit a collectable payment before any tokens move (CEI pattern)
+    ///
+    /// Sets the reentrancy guard, advances the schedule, releases the outflow
+    /// from the shared approval and updates the user's, merchant's and
+    /// platform's records. `finish` clears the guard.
+    pub fn record(&mut self, due: &DuePayment, now: i64) -> Result<()> {
+        let subscription = &mut *self.subscription;
+
+        // Set reentrancy guard
+        subscription.payment_in_progress = true;
+
+        // Update subscription state
+        let scheduled_from = subscription.next_cycle_base(due.schedule_now);
+        subscription.last_payment = now;
+        subscription.next_payment = schedule::next_due(
+            scheduled_from,
+            subscription.frequency_seconds,
+            subscription.billing_day,
+            subscription.billing_weekday,
+            subscription.utc_offset_seconds,
+        )
+        .map_err(ErrorCode::from)?;
+        subscription.payment_count = subscription
+            .payment_count
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+        subscription.snoozed = false; // re-arm snooze for the new cycle
+        subscription.pending_proration = due.carried_proration;
+        subscription.discount_payments_remaining =
+            subscription.discount_payments_remaining.saturating_sub(1);
+        subscription.retry_count = 0;
+        subscription.next_retry_at = 0;
+        self.record_charge(due, now)?;
+
+        // Move the index entry to the new due date; a full or wrong-day
+        // bucket (keeper clock skew at midnight) must not fail the payment
+        let subscription = &*self.subscription;
+        if let Some(due_bucket) = self.due_bucket.as_deref_mut() {
+            due_bucket.reschedule(&subscription.key(), &subscription.merchant, subscription.next_payment);
+        }
+        if let Some(next_due_bucket) = self.next_due_bucket.as_deref_mut() {
+            let _ = next_due_bucket.insert(subscription.key(), &subscription.merchant, subscription.next_payment);
+        }
+        RevenueForecast::update_in(self.revenue_forecast, |forecast| {
+            forecast.reschedule(due.forecast_entry, subscription.forecast_entry(), now)
+        })?;
+        Ok(())
+    }
+
+    /// Commit a usage charge before any tokens move
+    ///
+    /// Keeps the same records as a scheduled payment without touching the
+    /// schedule; the forecast is only brought current.
+    pub fn record_usage(&mut self, due: &DuePayment, now: i64) -> Result<()> {
+        self.record_charge(due, now)?;
+        RevenueForecast::update_in(self.revenue_forecast, |forecast| {
+            forecast.refresh(now);
+            Ok(())
+        })
+    }
+
+    /// Count a charge against the subscription's caps and in the user's,
+    /// merchant's and platform's records
+    fn record_charge(&mut self, due: &DuePayment, now: i64) -> Result<()> {
+        let subscription = &mut *self.subscription;
+        subscription.total_paid = due.new_total;
+        subscription.fee_dust = due.fee_dust;
+
+        // The subscription's remaining allowance shrinks whichever source pays
+        self.user_delegate.release(due.outflow);
+
+        UserStats::update_in(self.user_stats, |stats| stats.record_payment(due.outflow, now))?;
+        MerchantVolume::update_in(self.merchant_volume, |volume| volume.record(due.charge, due.fee, now))?;
+        MerchantReport::update_in(self.merchant_report, |report| report.record(due.charge, due.fee, now))?;
+
+        // Update platform stats
+        let platform = &mut *self.platform;
+        platform.total_volume_24h = due.new_volume;
+        platform.total_transactions = platform
+            .total_transactions
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+        if due.fee_treatment == FeeTreatment::Exempt {
+            platform.record_fee_exemption(due.waived_fee);
+        }
+        Ok(())
+    }
+
+    /// Book what the merchant received in its statement and refund exposure
+    pub fn record_settlement(&mut self, due: &DuePayment, merchant_amount: u64, now: i64) -> Result<()> {
+        MerchantStatement::update_in(self.merchant_statement, |statement| {
+            statement.record(due.charge, due.fee, merchant_amount, now)
+        })?;
+        RefundLiability::update_in(self.refund_liability, |liability| liability.record(merchant_amount, now))?;
+        Ok(())
+    }
+
+    /// Clear the reentrancy guard once the transfers are done and publish the payment
+    pub fn finish(
+        &mut self,
+        due: &DuePayment,
+        merchant_received: u64,
+        remaining_delegation: u64,
+        batched: bool,
+        settlement_mint: Pubkey,
+        now: i64,
+    ) -> Result<()> {
+        let subscription = &mut *self.subscription;
+
+        // Clear reentrancy guard
+        subscription.payment_in_progress = false;
+
+        emit!(PaymentExecuted {
+            schema_version: EVENT_SCHEMA_VERSION,
+            subscription: subscription.key(),
+            amount: due.charge,
+            proration_adjustment: due.proration_adjustment,
+            fee: due.fee,
+            merchant_received,
+            payment_count: subscription.payment_count,
+            remaining_lifetime_allowance: limits::remaining_allowance(
+                subscription.lifetime_cap,
+                subscription.total_paid,
+            ),
+            payments_remaining: limits::payments_remaining(
+                subscription.lifetime_cap,
+                subscription.total_paid,
+                due.payment_outflow,
+            ),
+            remaining_delegation,
+            batched,
+            timestamp: now,
+            settlement_mint,
+            promotional: due.fee_treatment.is_promotional(),
+            gross_amount: due.outflow,
+            fee_bearer: subscription.fee_bearer,
+        });
+        if due.trial_ending {
+            emit!(TrialEnded {
+                schema_version: EVENT_SCHEMA_VERSION,
+                subscription: subscription.key(),
+                user: subscription.user,
+                merchant: subscription.merchant,
+                trial_ends_at: subscription.trial_ends_at,
+                first_payment: due.charge,
+                timestamp: now,
+            });
+        }
+        Ok(())
+    }
+
+    /// Cancel a subscription whose payment went uncollected past the window
+    fn auto_cancel(&mut self, forecast_entry: Option<(u64, i64)>, now: i64) -> Result<()> {
+        self.deactivate()?;
+        self.unschedule(forecast_entry, now)?;
+
+        let subscription = &*self.subscription;
+        emit!(SubscriptionAutoCancelled {
+            schema_version: EVENT_SCHEMA_VERSION,
+            subscription: subscription.key(),
+            user: subscription.user,
+            merchant: subscription.merchant,
+            missed_payment: subscription.next_payment,
+            timestamp: now,
+        });
+
+        #[cfg(feature = "verbose-logs")]
+        msg!("Payment window closed, subscription cancelled");
+        Ok(())
+    }
+
+    /// Move past cycles the missed-payment policy no longer collects
+    ///
+    /// Returns whether any were skipped, which ends the execution.
+    fn skip_missed_cycles(
+        &mut self,
+        forecast_entry: Option<(u64, i64)>,
+        schedule_now: i64,
+        now: i64,
+    ) -> Result<bool> {
+        let subscription = &mut *self.subscription;
+        let unit = subscription.schedule_unit;
+        let (next_payment, cycles_skipped) = match subscription.missed_payment_policy {
+            MissedPaymentPolicy::Accumulate { max_cycles } => schedule::cap_backlog(
+                schedule_now,
+                subscription.next_payment,
+                max_cycles,
+                subscription.frequency_seconds,
+                subscription.billing_day,
+                subscription.utc_offset_seconds,
+            ),
+            MissedPaymentPolicy::Skip | MissedPaymentPolicy::AutoCancel => {
+                schedule::skip_expired_cycles(
+                    schedule_now,
+                    subscription.next_payment,
+                    unit.span(self.platform.collection_window_seconds),
+                    subscription.frequency_seconds,
+                    subscription.billing_day,
+                    subscription.utc_offset_seconds,
+                )
+            }
+        }
+        .map_err(ErrorCode::from)?;
+        if cycles_skipped == 0 {
+            return Ok(false);
+        }
+
+        let missed_payment = subscription.next_payment;
+        subscription.next_payment = next_payment;
+        subscription.snoozed = false;
+        subscription.retry_count = 0;
+        subscription.next_retry_at = 0;
+
+        let subscription = &*self.subscription;
+        if let Some(due_bucket) = self.due_bucket.as_deref_mut() {
+            due_bucket.reschedule(&subscription.key(), &subscription.merchant, next_payment);
+        }
+        if let Some(next_due_bucket) = self.next_due_bucket.as_deref_mut() {
+            let _ = next_due_bucket.insert(subscription.key(), &subscription.merchant, next_payment);
+        }
+        RevenueForecast::update_in(self.revenue_forecast, |forecast| {
+            forecast.reschedule(forecast_entry, subscription.forecast_entry(), now)
+        })?;
+
+        emit!(PaymentCycleSkipped {
+            schema_version: EVENT_SCHEMA_VERSION,
+            subscription: subscription.key(),
+            missed_payment,
+            cycles_skipped,
+            next_payment,
+            timestamp: now,
+            utc_offset_seconds: subscription.utc_offset_seconds,
+        });
+
+        #[cfg(feature = "verbose-logs")]
+        msg!("Payment window closed, skipped {} cycle(s)", cycles_skipped);
+        Ok(true)
+    }
+
+    /// Cancel the subscription, giving back its allowance and merchant slot
+    fn deactivate(&mut self) -> Result<()> {
+        self.subscription.is_active = false;
+        self.user_delegate.remove_subscription(self.subscription);
+        self.platform.total_subscriptions = self.platform.total_subscriptions.saturating_sub(1);
+        MerchantPolicy::release_slot(self.merchant_policy)?;
+        UserStats::update_in(self.user_stats, |stats| {
+            stats.subscription_removed();
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    /// Drop a subscription that stopped billing from the due index and forecast
+    fn unschedule(&mut self, forecast_entry: Option<(u64, i64)>, now: i64) -> Result<()> {
+        if let Some(due_bucket) = self.due_bucket.as_deref_mut() {
+            due_bucket.remove(&self.subscription.key());
+        }
+        RevenueForecast::update_in(self.revenue_forecast, |forecast| forecast.reschedule(forecast_entry, None, now))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+/// Token the platform accepts for payments (`[b"accepted_mint", mint]`)
+///
+/// Subscriptions can only be created for listed, enabled mints. Unlisting is
+/// done by disabling the entry; existing subscriptions keep executing.
+#[account]
+pub struct AcceptedMint {
+    /// Listed token
+    pub mint: Pubkey,                   // 32
+
+    /// Decimals of `mint`, recorded when listed
+    pub decimals: u8,                   // 1
+
+    /// Pyth USD price account for the mint (default = none)
+    pub price_feed: Pubkey,             // 32
+
+    /// Smallest subscription amount billed in the mint, in base units
+    pub min_amount: u64,                // 8
+
+    /// Whether new subscriptions may use the mint
+    pub enabled: bool,                  // 1
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Extra padding for future upgrades
+    pub reserved: [u8; 32],             // 32
+}
+
+impl AcceptedMint {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // mint
+        1 +                              // decimals
+        32 +                             // price_feed
+        8 +                              // min_amount
+        1 +                              // enabled
+        1 +                              // bump
+        32;                              // reserved
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use lutrii_common::{AdminAuditAction, AdminAuditTrail};
+
+/// Ring buffer of the platform's admin and governance actions
+///
+/// Opened once by `open_admin_audit_log`. Admin instructions take it as
+/// an optional account and append an entry whenever it is passed, giving
+/// an on-chain operational history that does not depend on transaction
+/// logs being retained.
+///
+/// PDA: `[b"admin_audit_log"]`
+#[account]
+pub struct AdminAuditLog {
+    pub trail: AdminAuditTrail,         // 2 + 8 + 41 * 32
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+}
+
+impl AdminAuditLog {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        AdminAuditTrail::SPACE +         // trail
+        1;                               // bump
+}
+
+/// Append `action` by `actor` to the audit log, if one was passed
+pub fn record_admin_action(
+    audit_log: Option<&mut Box<Account<AdminAuditLog>>>,
+    actor: Pubkey,
+    action: AdminAuditAction,
+) -> Result<()> {
+    if let Some(audit_log) = audit_log {
+        audit_log.trail.record(actor, action, Clock::get()?.unix_timestamp);
+    }
+    Ok(())
+}
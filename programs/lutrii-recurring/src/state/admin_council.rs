@@ -0,0 +1,220 @@
+use anchor_lang::prelude::*;
+use lutrii_common::AdminAuditAction;
+use crate::state::{ConfigChange, FeeDustPolicy, FeeRounding};
+use crate::MAX_PAUSE_REASON_LEN;
+
+/// Most members an admin council may have (approvals are a 16-bit mask)
+pub const MAX_COUNCIL_MEMBERS: usize = 10;
+
+/// How long a council proposal can gather approvals and be executed (7 days)
+pub const COUNCIL_PROPOSAL_TTL_SECONDS: i64 = 7 * 86_400;
+
+/// M-of-N council over the platform's emergency pause and fees
+///
+/// Created by `create_admin_council`. From then on `emergency_pause`,
+/// `emergency_unpause`, the fee instructions and timelocked config changes
+/// refuse the single admin key; the same changes run through
+/// `CouncilProposal`s that `threshold` members approved. Fee exemptions,
+/// merchant fee schedules and fee holidays are refused outright. Other
+/// admin instructions keep using the platform authority. Membership is
+/// fixed at creation.
+///
+/// PDA: `[b"admin_council"]`
+#[account]
+pub struct AdminCouncil {
+    /// Keys allowed to propose and approve
+    pub members: Vec<Pubkey>,           // 4 + 32 * 10
+
+    /// Approvals a proposal needs before it executes
+    pub threshold: u8,                  // 1
+
+    /// Proposals created so far; the next proposal's id
+    pub proposal_count: u64,            // 8
+
+    /// When the council was created
+    pub created_at: i64,                // 8
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Extra padding for future upgrades
+    pub reserved: [u8; 32],             // 32
+}
+
+impl AdminCouncil {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        4 + 32 * MAX_COUNCIL_MEMBERS +   // members
+        1 +                              // threshold
+        8 +                              // proposal_count
+        8 +                              // created_at
+        1 +                              // bump
+        32;                              // reserved
+
+    /// Whether `members` and `threshold` make a usable council: 1 to
+    /// `MAX_COUNCIL_MEMBERS` distinct keys and a threshold they can reach
+    pub fn is_valid(members: &[Pubkey], threshold: u8) -> bool {
+        let distinct = members
+            .iter()
+            .enumerate()
+            .all(|(i, member)| !members[..i].contains(member));
+        !members.is_empty()
+            && members.len() <= MAX_COUNCIL_MEMBERS
+            && distinct
+            && threshold >= 1
+            && threshold as usize <= members.len()
+    }
+
+    /// Position of `key` among the members
+    pub fn member_index(&self, key: &Pubkey) -> Option<usize> {
+        self.members.iter().position(|member| member == key)
+    }
+}
+
+/// Change a council proposal makes once approved
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub enum CouncilAction {
+    /// `emergency_pause` with this reason
+    EmergencyPause { reason: String },
+    /// `emergency_unpause`
+    EmergencyUnpause,
+    /// `set_fee_policy`
+    SetFeePolicy {
+        fee_rounding: FeeRounding,
+        fee_dust_policy: FeeDustPolicy,
+        fee_dust_threshold: u64,
+    },
+    /// `set_executor_reward`
+    SetExecutorReward { reward_bps: u16, flat_reward: u64 },
+    /// `set_first_subscription_waiver`
+    SetFirstSubscriptionWaiver { free_payments: u8 },
+    /// `schedule_config_change` or `update_platform_params`; fee wallets it
+    /// rotates to are passed to `execute_council_action`
+    ScheduleConfigChange { change: ConfigChange },
+}
+
+impl CouncilAction {
+    /// Serialized size of the largest action
+    pub const MAX_SPACE: usize = 1 + if ConfigChange::SPACE > 4 + MAX_PAUSE_REASON_LEN {
+        ConfigChange::SPACE
+    } else {
+        4 + MAX_PAUSE_REASON_LEN
+    };
+
+    /// Entry the action leaves in the admin audit log
+    pub fn audit_action(&self) -> AdminAuditAction {
+        match self {
+            CouncilAction::EmergencyPause { .. } => AdminAuditAction::EmergencyPause,
+            CouncilAction::EmergencyUnpause => AdminAuditAction::EmergencyUnpause,
+            CouncilAction::SetFeePolicy { .. }
+            | CouncilAction::SetExecutorReward { .. }
+            | CouncilAction::SetFirstSubscriptionWaiver { .. } => AdminAuditAction::FeeChange,
+            CouncilAction::ScheduleConfigChange { .. } => AdminAuditAction::ConfigUpdate,
+        }
+    }
+}
+
+/// One council proposal and its approvals
+///
+/// PDA: `[b"council_proposal", proposal_id]`
+#[account]
+pub struct CouncilProposal {
+    /// Sequence number from `AdminCouncil.proposal_count`
+    pub proposal_id: u64,               // 8
+
+    /// Member who proposed it
+    pub proposer: Pubkey,               // 32
+
+    /// Change to make
+    pub action: CouncilAction,          // CouncilAction::MAX_SPACE
+
+    /// Members who approved, one bit per member index
+    pub approvals: u16,                 // 2
+
+    /// When the proposal was created
+    pub created_at: i64,                // 8
+
+    /// Approvals and execution are refused from this time on
+    pub expires_at: i64,                // 8
+
+    /// Whether the action has run
+    pub executed: bool,                 // 1
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+}
+
+impl CouncilProposal {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        8 +                              // proposal_id
+        32 +                             // proposer
+        CouncilAction::MAX_SPACE +       // action
+        2 +                              // approvals
+        8 +                              // created_at
+        8 +                              // expires_at
+        1 +                              // executed
+        1;                               // bump
+
+    /// Record the approval of the member at `index`; false if already counted
+    pub fn approve(&mut self, index: usize) -> bool {
+        let bit = 1u16 << index;
+        let fresh = self.approvals & bit == 0;
+        self.approvals |= bit;
+        fresh
+    }
+
+    /// Members who approved so far
+    pub fn approval_count(&self) -> u8 {
+        self.approvals.count_ones() as u8
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_council_membership_rules() {
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert!(AdminCouncil::is_valid(&[a, b], 2));
+        assert!(!AdminCouncil::is_valid(&[a, b], 3));
+        assert!(!AdminCouncil::is_valid(&[a, b], 0));
+        assert!(!AdminCouncil::is_valid(&[a, a], 1));
+        assert!(!AdminCouncil::is_valid(&[], 1));
+        assert!(!AdminCouncil::is_valid(&[a; MAX_COUNCIL_MEMBERS + 1], 1));
+    }
+
+    #[test]
+    fn test_approvals_count_each_member_once() {
+        let mut proposal = CouncilProposal {
+            proposal_id: 0,
+            proposer: Pubkey::new_unique(),
+            action: CouncilAction::EmergencyUnpause,
+            approvals: 0,
+            created_at: 0,
+            expires_at: 0,
+            executed: false,
+            bump: 255,
+        };
+        assert!(proposal.approve(0));
+        assert!(proposal.approve(9));
+        assert!(!proposal.approve(0));
+        assert_eq!(proposal.approval_count(), 2);
+
+        let reason = "x".repeat(MAX_PAUSE_REASON_LEN);
+        let pause = CouncilAction::EmergencyPause { reason };
+        assert!(pause.try_to_vec().unwrap().len() <= CouncilAction::MAX_SPACE);
+        let change = ConfigChange {
+            fee_basis_points: Some(0),
+            min_fee: Some(0),
+            max_fee: Some(0),
+            daily_volume_limit: Some(0),
+            fee_wallet_usdc: Some(Pubkey::new_unique()),
+            fee_wallet_usd1: Some(Pubkey::new_unique()),
+            config_change_delay_seconds: Some(0),
+        };
+        let largest = CouncilAction::ScheduleConfigChange { change };
+        assert_eq!(largest.try_to_vec().unwrap().len(), CouncilAction::MAX_SPACE);
+    }
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+/// Merchant-managed block on a single subscriber wallet
+///
+/// One PDA per (merchant, user) pair (`[b"blocklist", merchant, user]`).
+/// While it exists the user cannot subscribe to the merchant and payments on
+/// their existing subscription are refused until it is cancelled.
+#[account]
+pub struct BlocklistEntry {
+    /// Merchant PDA (lutrii-merchant-registry) that created the block
+    pub merchant: Pubkey,               // 32
+
+    /// Blocked subscriber wallet
+    pub user: Pubkey,                   // 32
+
+    /// Unix timestamp the block was added
+    pub blocked_at: i64,                // 8
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+}
+
+impl BlocklistEntry {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // merchant
+        32 +                             // user
+        8 +                              // blocked_at
+        1;                               // bump
+}
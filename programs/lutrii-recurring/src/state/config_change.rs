@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::{
+    PlatformState, MAX_CONFIG_CHANGE_DELAY_SECONDS, MAX_FEE_BASIS_POINTS,
+    MIN_CONFIG_CHANGE_DELAY_SECONDS, MIN_FEE_BASIS_POINTS,
+};
+
+/// Platform parameters a timelocked config change may set
+///
+/// `None` leaves a parameter as it is.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ConfigChange {
+    /// New platform fee in basis points
+    pub fee_basis_points: Option<u16>,
+    /// New smallest platform fee in token units
+    pub min_fee: Option<u64>,
+    /// New largest platform fee in token units
+    pub max_fee: Option<u64>,
+    /// New daily volume limit
+    pub daily_volume_limit: Option<u64>,
+    /// New USDC fee wallet
+    pub fee_wallet_usdc: Option<Pubkey>,
+    /// New USD1 fee wallet
+    pub fee_wallet_usd1: Option<Pubkey>,
+    /// New delay for later config changes
+    pub config_change_delay_seconds: Option<i64>,
+}
+
+impl ConfigChange {
+    /// Serialized size
+    pub const SPACE: usize = 3 + 9 + 9 + 9 + 33 + 33 + 9;
+
+    /// Whether the change sets nothing
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether the change touches the platform fee parameters
+    pub fn changes_fee_params(&self) -> bool {
+        self.fee_basis_points.is_some() || self.min_fee.is_some() || self.max_fee.is_some()
+    }
+
+    /// Require the change to leave `platform` with valid parameters
+    ///
+    /// Fee basis points stay within the platform bounds, `min_fee` may not
+    /// exceed `max_fee` and the fee dust threshold stays at or below
+    /// `max_fee`.
+    pub fn validate(&self, platform: &PlatformState) -> Result<()> {
+        require!(!self.is_empty(), ErrorCode::NoUpdateProvided);
+        if let Some(fee_basis_points) = self.fee_basis_points {
+            require!(fee_basis_points >= MIN_FEE_BASIS_POINTS, ErrorCode::FeeTooLow);
+            require!(fee_basis_points <= MAX_FEE_BASIS_POINTS, ErrorCode::FeeTooHigh);
+        }
+        let min_fee = self.min_fee.unwrap_or(platform.min_fee);
+        let max_fee = self.max_fee.unwrap_or(platform.max_fee);
+        require!(min_fee <= max_fee, ErrorCode::InvalidFeeSchedule);
+        require!(
+            platform.fee_dust_threshold <= max_fee,
+            ErrorCode::InvalidFeeDustThreshold
+        );
+        if let Some(limit) = self.daily_volume_limit {
+            require!(limit > 0, ErrorCode::InvalidDailyVolumeLimit);
+        }
+        if let Some(delay) = self.config_change_delay_seconds {
+            require!(
+                (MIN_CONFIG_CHANGE_DELAY_SECONDS..=MAX_CONFIG_CHANGE_DELAY_SECONDS).contains(&delay),
+                ErrorCode::InvalidConfigChangeDelay
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Platform config change waiting out its timelock
+///
+/// Written by `schedule_config_change`, which replaces any change still
+/// pending and restarts the clock; anyone may apply it with
+/// `execute_config_change` from `executable_at` on.
+///
+/// PDA: `[b"pending_config_change"]`
+#[account]
+pub struct PendingConfigChange {
+    /// Parameters to set
+    pub change: ConfigChange,           // ConfigChange::SPACE
+
+    /// Whether a change is waiting to be executed
+    pub pending: bool,                  // 1
+
+    /// When the change was scheduled
+    pub scheduled_at: i64,              // 8
+
+    /// Earliest time the change may be executed
+    pub executable_at: i64,             // 8
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Extra padding for future upgrades
+    pub reserved: [u8; 32],             // 32
+}
+
+impl PendingConfigChange {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        ConfigChange::SPACE +            // change
+        1 +                              // pending
+        8 +                              // scheduled_at
+        8 +                              // executable_at
+        1 +                              // bump
+        32;                              // reserved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_change_space_fits_every_field() {
+        let change = ConfigChange {
+            fee_basis_points: Some(MAX_FEE_BASIS_POINTS),
+            min_fee: Some(0),
+            max_fee: Some(u64::MAX),
+            daily_volume_limit: Some(1),
+            fee_wallet_usdc: Some(Pubkey::new_unique()),
+            fee_wallet_usd1: Some(Pubkey::new_unique()),
+            config_change_delay_seconds: Some(MIN_CONFIG_CHANGE_DELAY_SECONDS),
+        };
+        assert!(change.changes_fee_params());
+        assert_eq!(change.try_to_vec().unwrap().len(), ConfigChange::SPACE);
+        assert!(ConfigChange::default().is_empty());
+    }
+}
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+/// Compliance denylist entry for a single wallet
+///
+/// One PDA per wallet (`[b"denylist", wallet]`). The account existing is the
+/// denial: create and execute paths require the entry for the user and the
+/// merchant wallet to be uninitialized, so lookups are O(1) with no capacity limit.
+#[account]
+pub struct DenylistEntry {
+    /// Denied wallet
+    pub wallet: Pubkey,                 // 32
+
+    /// Admin who added the entry
+    pub added_by: Pubkey,               // 32
+
+    /// Unix timestamp the entry was added
+    pub added_at: i64,                  // 8
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+}
+
+impl DenylistEntry {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // wallet
+        32 +                             // added_by
+        8 +                              // added_at
+        1;                               // bump
+}
@@ -0,0 +1,129 @@
+use anchor_lang::prelude::*;
+use lutrii_core::SECONDS_PER_DAY;
+use crate::errors::ErrorCode;
+
+/// Subscriptions one bucket can index
+pub const MAX_BUCKET_SUBSCRIPTIONS: usize = 64;
+
+/// Per-merchant index of subscriptions due on one day
+///
+/// Lets keepers fetch a merchant's due payments with a handful of PDA reads
+/// instead of scanning every subscription. Buckets are opened by anyone
+/// (usually the keeper) and kept current by the instructions that move
+/// `next_payment`, when the client passes them. The index is best-effort:
+/// keepers must still check each subscription it lists. Slot-scheduled
+/// subscriptions have no unix day and are never indexed.
+///
+/// PDA: `[b"due_bucket", merchant, day.to_le_bytes()]`
+#[account]
+pub struct DueBucket {
+    /// Merchant PDA (lutrii-merchant-registry)
+    pub merchant: Pubkey,               // 32
+
+    /// Unix day (`next_payment / 86400`) the bucket covers
+    pub day: i64,                       // 8
+
+    /// Account refunded the rent when the bucket is closed
+    pub payer: Pubkey,                  // 32
+
+    /// Subscriptions whose next payment falls on `day`
+    pub subscriptions: Vec<Pubkey>,     // 4 + 64 * 32
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+}
+
+impl DueBucket {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // merchant
+        8 +                              // day
+        32 +                             // payer
+        4 + 32 * MAX_BUCKET_SUBSCRIPTIONS + // subscriptions
+        1;                               // bump
+
+    /// Unix day containing `timestamp`
+    pub fn day_of(timestamp: i64) -> i64 {
+        timestamp.div_euclid(SECONDS_PER_DAY)
+    }
+
+    /// Whether this is `merchant`'s bucket for a payment due at `next_payment`
+    pub fn covers(&self, merchant: &Pubkey, next_payment: i64) -> bool {
+        self.merchant == *merchant && self.day == Self::day_of(next_payment)
+    }
+
+    /// Index `subscription`, due at `next_payment`; a no-op if already listed
+    pub fn insert(&mut self, subscription: Pubkey, merchant: &Pubkey, next_payment: i64) -> Result<()> {
+        require!(self.covers(merchant, next_payment), ErrorCode::InvalidDueBucket);
+        if self.subscriptions.contains(&subscription) {
+            return Ok(());
+        }
+        require!(
+            self.subscriptions.len() < MAX_BUCKET_SUBSCRIPTIONS,
+            ErrorCode::DueBucketFull
+        );
+        self.subscriptions.push(subscription);
+        Ok(())
+    }
+
+    /// Keep `subscription` listed only while `next_payment` still falls in this bucket
+    pub fn reschedule(&mut self, subscription: &Pubkey, merchant: &Pubkey, next_payment: i64) {
+        if !self.covers(merchant, next_payment) {
+            self.remove(subscription);
+        }
+    }
+
+    /// Drop `subscription` from the index if listed
+    pub fn remove(&mut self, subscription: &Pubkey) {
+        if let Some(index) = self.subscriptions.iter().position(|s| s == subscription) {
+            self.subscriptions.swap_remove(index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = SECONDS_PER_DAY;
+
+    fn bucket(merchant: Pubkey, day: i64) -> DueBucket {
+        DueBucket {
+            merchant,
+            day,
+            payer: Pubkey::new_unique(),
+            subscriptions: Vec::new(),
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn test_due_bucket_len() {
+        assert_eq!(DueBucket::LEN, 8 + 32 + 8 + 32 + 4 + 32 * 64 + 1);
+    }
+
+    #[test]
+    fn test_insert_checks_day_and_capacity() {
+        let merchant = Pubkey::new_unique();
+        let start = 1_700_000_000;
+        let mut bucket = bucket(merchant, DueBucket::day_of(start));
+
+        let subscription = Pubkey::new_unique();
+        bucket.insert(subscription, &merchant, start).unwrap();
+        bucket.insert(subscription, &merchant, start).unwrap();
+        assert_eq!(bucket.subscriptions, vec![subscription]);
+
+        assert!(bucket.insert(Pubkey::new_unique(), &merchant, start + DAY).is_err());
+        assert!(bucket.insert(Pubkey::new_unique(), &Pubkey::new_unique(), start).is_err());
+
+        for _ in 1..MAX_BUCKET_SUBSCRIPTIONS {
+            bucket.insert(Pubkey::new_unique(), &merchant, start).unwrap();
+        }
+        assert!(bucket.insert(Pubkey::new_unique(), &merchant, start).is_err());
+
+        bucket.remove(&subscription);
+        bucket.remove(&subscription);
+        assert_eq!(bucket.subscriptions.len(), MAX_BUCKET_SUBSCRIPTIONS - 1);
+        assert!(!bucket.subscriptions.contains(&subscription));
+    }
+}
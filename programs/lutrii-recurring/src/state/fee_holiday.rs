@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+
+/// Promotional windows the platform can schedule at once
+pub const MAX_FEE_HOLIDAYS: usize = 4;
+
+/// Window during which recurring payments carry no platform fee
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeHoliday {
+    /// Unix time the window opens
+    pub start: i64,
+    /// Unix time the window closes (exclusive)
+    pub end: i64,
+    /// Only payments in this mint qualify (default = every mint)
+    pub mint: Pubkey,
+}
+
+impl FeeHoliday {
+    /// Serialized size
+    pub const LEN: usize = 8 + 8 + 32;
+
+    /// Whether a payment in `mint` at `now` falls in the window
+    pub fn covers(&self, mint: &Pubkey, now: i64) -> bool {
+        (self.start..self.end).contains(&now)
+            && (self.mint == Pubkey::default() || self.mint == *mint)
+    }
+}
+
+/// Scheduled promotional zero-fee windows (`[b"fee_holidays"]`)
+///
+/// Every execution passes this account, scheduled or not. Payments a
+/// window covers charge no platform fee and are marked promotional in
+/// their event.
+#[account]
+pub struct FeeHolidays {
+    /// Scheduled windows; only the first `holiday_count` are used
+    pub holidays: [FeeHoliday; MAX_FEE_HOLIDAYS], // 192
+
+    /// Number of scheduled windows
+    pub holiday_count: u8,              // 1
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Extra padding for future upgrades
+    pub reserved: [u8; 32],             // 32
+}
+
+impl FeeHolidays {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        FeeHoliday::LEN * MAX_FEE_HOLIDAYS + // holidays
+        1 +                              // holiday_count
+        1 +                              // bump
+        32;                              // reserved
+
+    /// Whether a payment in `mint` at `now` falls in a scheduled window
+    pub fn covers(&self, mint: &Pubkey, now: i64) -> bool {
+        self.holidays[..self.holiday_count as usize]
+            .iter()
+            .any(|holiday| holiday.covers(mint, now))
+    }
+
+    /// Whether `info`, the fee holidays PDA, has a window covering a payment
+    /// in `mint` at `now`
+    pub fn cover_of(info: &AccountInfo, mint: &Pubkey, now: i64) -> Result<bool> {
+        if info.data_is_empty() {
+            return Ok(false);
+        }
+        Ok(Self::try_deserialize(&mut &info.try_borrow_data()?[..])?.covers(mint, now))
+    }
+}
@@ -0,0 +1,132 @@
+use anchor_lang::prelude::*;
+use lutrii_common::Subscription;
+use lutrii_core::fee;
+use crate::state::{FeeHolidays, MerchantPolicy};
+
+/// How the platform fee is rounded to whole base units
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FeeRounding {
+    /// Round down; fractions of a base unit stay with the merchant
+    #[default]
+    Floor,
+    /// Round to the nearest base unit, halves up
+    HalfUp,
+}
+
+impl From<FeeRounding> for fee::Rounding {
+    fn from(rounding: FeeRounding) -> Self {
+        match rounding {
+            FeeRounding::Floor => Self::Floor,
+            FeeRounding::HalfUp => Self::HalfUp,
+        }
+    }
+}
+
+/// What happens to a recurring fee below the platform's dust threshold
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FeeDustPolicy {
+    /// Not charged at all
+    #[default]
+    Waive,
+    /// Carried on the subscription and charged once it reaches the threshold
+    Accumulate,
+}
+
+impl From<FeeDustPolicy> for fee::DustPolicy {
+    fn from(policy: FeeDustPolicy) -> Self {
+        match policy {
+            FeeDustPolicy::Waive => Self::Waive,
+            FeeDustPolicy::Accumulate => Self::Accumulate,
+        }
+    }
+}
+
+/// Fee parameters payments are charged under
+///
+/// The platform's own live on `PlatformState`; a merchant may have a
+/// negotiated schedule on its `MerchantPolicy` that overrides them.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeSchedule {
+    pub fee_basis_points: u16,
+    pub min_fee: u64,
+    pub max_fee: u64,
+}
+
+impl FeeSchedule {
+    pub const LEN: usize = 2 + 8 + 8;
+}
+
+/// How a charge's platform fee is treated
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FeeTreatment {
+    /// The platform fee is charged
+    #[default]
+    Charged,
+    /// The subscription or its merchant is fee-exempt
+    Exempt,
+    /// A promotional fee holiday covers the payment
+    Promotional,
+    /// One of the free payments on the user's first subscription
+    FirstSubscription,
+}
+
+impl FeeTreatment {
+    /// Treatment of a `charge` on `subscription` settling in `mint` at `now`
+    ///
+    /// `merchant_policy` is the merchant's policy PDA and `fee_holidays` the
+    /// platform's promotional windows PDA; either may be uninitialized.
+    /// `first_subscription` is set when the user's first-subscription waiver
+    /// covers this payment.
+    pub fn of(
+        subscription: &Subscription,
+        charge: u64,
+        merchant_policy: &UncheckedAccount,
+        fee_holidays: &UncheckedAccount,
+        first_subscription: bool,
+        mint: &Pubkey,
+        now: i64,
+    ) -> Result<Self> {
+        if charge == 0 {
+            return Ok(Self::Charged);
+        }
+        let fee_exempt = subscription.fee_exempt || MerchantPolicy::fee_exempt_of(merchant_policy)?;
+        Self::for_charge(charge, fee_exempt, fee_holidays, first_subscription, mint, now)
+    }
+
+    /// Treatment of a `charge` settling in `mint` at `now`, for charges
+    /// priced without their subscription at hand
+    ///
+    /// `fee_exempt` is set when the subscription or its merchant is exempt.
+    pub fn for_charge(
+        charge: u64,
+        fee_exempt: bool,
+        fee_holidays: &AccountInfo,
+        first_subscription: bool,
+        mint: &Pubkey,
+        now: i64,
+    ) -> Result<Self> {
+        if charge == 0 {
+            return Ok(Self::Charged);
+        }
+        if fee_exempt {
+            return Ok(Self::Exempt);
+        }
+        if FeeHolidays::cover_of(fee_holidays, mint, now)? {
+            return Ok(Self::Promotional);
+        }
+        if first_subscription {
+            return Ok(Self::FirstSubscription);
+        }
+        Ok(Self::Charged)
+    }
+
+    /// Whether the platform fee is waived
+    pub fn waives_fee(&self) -> bool {
+        *self != Self::Charged
+    }
+
+    /// Whether the fee is waived by a platform promotion
+    pub fn is_promotional(&self) -> bool {
+        matches!(self, Self::Promotional | Self::FirstSubscription)
+    }
+}
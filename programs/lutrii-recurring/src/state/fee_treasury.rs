@@ -0,0 +1,249 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+
+/// Recipients a fee treasury can split between
+pub const MAX_FEE_RECIPIENTS: usize = 3;
+
+/// Volume thresholds a fee treasury's rebate schedule can have
+pub const MAX_REBATE_TIERS: usize = 3;
+
+/// Share of swept fees paid to one token account
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeShare {
+    /// Token account receiving the share
+    pub recipient: Pubkey,
+    /// Share of each sweep in basis points
+    pub basis_points: u16,
+}
+
+/// Fee rebate earned by merchants processing at least `min_volume` in a period
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RebateTier {
+    /// Volume (token units) a merchant must process in the period
+    pub min_volume: u64,
+    /// Share of the period's platform fees paid back, in basis points
+    pub rebate_bps: u16,
+}
+
+/// Per-mint platform fee treasury
+///
+/// While enabled, executions pay the platform fee into `vault` (owned by
+/// this PDA) instead of the fee wallet. The permissionless `sweep_fees`
+/// crank distributes the vault balance according to `shares` at most once
+/// per `sweep_interval`. Merchant fee rebates (`claim_rebate`) are paid out
+/// of the same vault, from fees accrued since the last sweep.
+///
+/// PDA: `[b"fee_treasury", mint]`; vault: `[b"fee_treasury_vault", fee_treasury]`
+#[account]
+pub struct FeeTreasury {
+    /// Mint collected by this treasury
+    pub mint: Pubkey,                   // 32
+
+    /// Token account holding accrued fees
+    pub vault: Pubkey,                  // 32
+
+    /// Distribution split; only the first `share_count` entries are used
+    pub shares: [FeeShare; MAX_FEE_RECIPIENTS], // 34 * 3
+
+    /// Number of configured shares
+    pub share_count: u8,                // 1
+
+    /// Minimum seconds between sweeps
+    pub sweep_interval: i64,            // 8
+
+    /// Whether executions should accrue fees here
+    pub enabled: bool,                  // 1
+
+    /// Accrued since the last sweep
+    pub accrued: u64,                   // 8
+
+    /// Total distributed by sweeps
+    pub total_distributed: u64,         // 8
+
+    /// Timestamp of the last sweep
+    pub last_sweep: i64,                // 8
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Vault token account bump
+    pub vault_bump: u8,                 // 1
+
+    /// Rebate schedule, ascending; only the first `rebate_tier_count` entries are used
+    pub rebate_tiers: [RebateTier; MAX_REBATE_TIERS], // 10 * 3
+
+    /// Number of configured rebate tiers (0 = no rebates)
+    pub rebate_tier_count: u8,          // 1
+
+    /// Extra padding for future upgrades
+    pub reserved: [u8; 1],              // 1
+}
+
+impl FeeTreasury {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // mint
+        32 +                             // vault
+        (32 + 2) * MAX_FEE_RECIPIENTS +  // shares
+        1 +                              // share_count
+        8 +                              // sweep_interval
+        1 +                              // enabled
+        8 +                              // accrued
+        8 +                              // total_distributed
+        8 +                              // last_sweep
+        1 +                              // bump
+        1 +                              // vault_bump
+        (8 + 2) * MAX_REBATE_TIERS +     // rebate_tiers
+        1 +                              // rebate_tier_count
+        1;                               // reserved
+
+    /// Validate a split: 1-3 distinct recipients summing to 100%
+    pub fn validate_shares(shares: &[FeeShare]) -> Result<()> {
+        require!(
+            !shares.is_empty() && shares.len() <= MAX_FEE_RECIPIENTS,
+            ErrorCode::InvalidFeeSplit
+        );
+        let mut total: u32 = 0;
+        for (i, share) in shares.iter().enumerate() {
+            require!(
+                share.recipient != Pubkey::default() && share.basis_points > 0,
+                ErrorCode::InvalidFeeSplit
+            );
+            require!(
+                !shares[..i].iter().any(|s| s.recipient == share.recipient),
+                ErrorCode::InvalidFeeSplit
+            );
+            total += share.basis_points as u32;
+        }
+        require!(total == 10_000, ErrorCode::InvalidFeeSplit);
+        Ok(())
+    }
+
+    pub fn active_shares(&self) -> &[FeeShare] {
+        &self.shares[..self.share_count as usize]
+    }
+
+    /// Split `amount` by the configured shares; rounding dust goes to the last recipient
+    pub fn split(&self, amount: u64) -> Vec<u64> {
+        let shares = self.active_shares();
+        let mut amounts: Vec<u64> = shares
+            .iter()
+            .map(|share| (amount as u128 * share.basis_points as u128 / 10_000) as u64)
+            .collect();
+        let distributed: u64 = amounts.iter().sum();
+        if let Some(last) = amounts.last_mut() {
+            *last += amount - distributed;
+        }
+        amounts
+    }
+
+    /// Validate a rebate schedule: up to 3 tiers, ascending in volume and rebate
+    pub fn validate_rebate_tiers(tiers: &[RebateTier]) -> Result<()> {
+        require!(tiers.len() <= MAX_REBATE_TIERS, ErrorCode::InvalidRebateTiers);
+        for (i, tier) in tiers.iter().enumerate() {
+            require!(
+                tier.rebate_bps > 0 && tier.rebate_bps <= 10_000,
+                ErrorCode::InvalidRebateTiers
+            );
+            if let Some(previous) = i.checked_sub(1).map(|p| tiers[p]) {
+                require!(
+                    tier.min_volume > previous.min_volume && tier.rebate_bps > previous.rebate_bps,
+                    ErrorCode::InvalidRebateTiers
+                );
+            }
+        }
+        Ok(())
+    }
+
+    pub fn active_rebate_tiers(&self) -> &[RebateTier] {
+        &self.rebate_tiers[..self.rebate_tier_count as usize]
+    }
+
+    /// Rebate owed on `fees` for a period with `volume` processed; the highest tier reached applies
+    pub fn rebate(&self, volume: u64, fees: u64) -> (u16, u64) {
+        let rebate_bps = self
+            .active_rebate_tiers()
+            .iter()
+            .rev()
+            .find(|tier| volume >= tier.min_volume)
+            .map_or(0, |tier| tier.rebate_bps);
+        (rebate_bps, (fees as u128 * rebate_bps as u128 / 10_000) as u64)
+    }
+
+    /// Whether a sweep is allowed at `now`
+    pub fn sweep_due(&self, now: i64) -> bool {
+        now >= self.last_sweep.saturating_add(self.sweep_interval)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn share(basis_points: u16) -> FeeShare {
+        FeeShare {
+            recipient: Pubkey::new_unique(),
+            basis_points,
+        }
+    }
+
+    #[test]
+    fn test_validate_shares() {
+        assert!(FeeTreasury::validate_shares(&[share(6_000), share(3_000), share(1_000)]).is_ok());
+        assert!(FeeTreasury::validate_shares(&[share(10_000)]).is_ok());
+        assert!(FeeTreasury::validate_shares(&[]).is_err());
+        assert!(FeeTreasury::validate_shares(&[share(6_000), share(3_000)]).is_err());
+
+        let duplicate = share(5_000);
+        assert!(FeeTreasury::validate_shares(&[duplicate, duplicate]).is_err());
+    }
+
+    #[test]
+    fn test_split_gives_dust_to_last_recipient() {
+        let mut shares = [FeeShare::default(); MAX_FEE_RECIPIENTS];
+        shares[0] = share(6_000);
+        shares[1] = share(3_000);
+        shares[2] = share(1_000);
+        let treasury = FeeTreasury {
+            mint: Pubkey::new_unique(),
+            vault: Pubkey::new_unique(),
+            shares,
+            share_count: 3,
+            sweep_interval: 86_400,
+            enabled: true,
+            accrued: 0,
+            total_distributed: 0,
+            last_sweep: 0,
+            bump: 255,
+            vault_bump: 255,
+            rebate_tiers: [RebateTier::default(); MAX_REBATE_TIERS],
+            rebate_tier_count: 0,
+            reserved: [0; 1],
+        };
+
+        assert_eq!(treasury.split(1_000), vec![600, 300, 100]);
+        assert_eq!(treasury.split(7), vec![4, 2, 1]);
+        assert_eq!(treasury.split(7).iter().sum::<u64>(), 7);
+    }
+
+    #[test]
+    fn test_rebate_uses_highest_tier_reached() {
+        let tier = |min_volume, rebate_bps| RebateTier { min_volume, rebate_bps };
+        let tiers = [tier(1_000, 500), tier(10_000, 1_000)];
+        assert!(FeeTreasury::validate_rebate_tiers(&tiers).is_ok());
+        assert!(FeeTreasury::validate_rebate_tiers(&[tier(10_000, 500), tier(1_000, 1_000)]).is_err());
+        assert!(FeeTreasury::validate_rebate_tiers(&[tier(1_000, 1_000), tier(10_000, 500)]).is_err());
+        assert!(FeeTreasury::validate_rebate_tiers(&[tier(1_000, 10_001)]).is_err());
+
+        let mut treasury = FeeTreasury::try_deserialize_unchecked(
+            &mut &[0u8; FeeTreasury::LEN][..],
+        )
+        .unwrap();
+        treasury.rebate_tiers[..2].copy_from_slice(&tiers);
+        treasury.rebate_tier_count = 2;
+
+        assert_eq!(treasury.rebate(999, 100), (0, 0));
+        assert_eq!(treasury.rebate(1_000, 100), (500, 5));
+        assert_eq!(treasury.rebate(50_000, 100), (1_000, 10));
+    }
+}
@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+
+/// Seed SPL Governance derives a governance's native treasury from
+pub const NATIVE_TREASURY_SEED: &[u8] = b"native-treasury";
+
+/// Byte offset of `realm` in an SPL Governance governance account (after
+/// the account type)
+pub const GOVERNANCE_REALM_OFFSET: usize = 1;
+
+/// Byte offset of `governance` in an SPL Governance proposal account
+pub const PROPOSAL_GOVERNANCE_OFFSET: usize = 1;
+
+/// Byte offset of `state` in a proposal (after the governing token mint)
+pub const PROPOSAL_STATE_OFFSET: usize = 65;
+
+/// Proposal states in which its transactions may execute: `Succeeded`,
+/// `Executing` and `ExecutingWithErrors`
+pub const EXECUTABLE_PROPOSAL_STATES: [u8; 3] = [3, 4, 8];
+
+/// Governance mode for the platform
+///
+/// Created by `enable_governance`, which hands `PlatformState.authority` to
+/// the native treasury of an SPL Governance governance. Admin instructions
+/// (fee changes, pause policy, fee splits) then run from passed proposals,
+/// with the treasury signing and paying rent. Because a vote takes days,
+/// `pause_guardian` may still trigger an emergency pause on its own;
+/// resuming requires a proposal.
+///
+/// PDA: `[b"governance"]`
+#[account]
+pub struct GovernanceConfig {
+    /// SPL Governance program instance the realm lives in
+    pub governance_program: Pubkey,     // 32
+
+    /// Realm the governance belongs to
+    pub realm: Pubkey,                  // 32
+
+    /// Governance account whose proposals administer the platform
+    pub governance: Pubkey,             // 32
+
+    /// Native treasury PDA of `governance`, the platform authority
+    pub native_treasury: Pubkey,        // 32
+
+    /// Key allowed to pause payments without a vote (default = none)
+    pub pause_guardian: Pubkey,         // 32
+
+    /// When governance mode was enabled
+    pub enabled_at: i64,                // 8
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Most a single proposal may spend from fee treasuries (0 = no spending)
+    pub max_spend_per_proposal: u64,    // 8
+
+    /// Extra padding for future upgrades
+    pub reserved: [u8; 24],             // 24
+}
+
+impl GovernanceConfig {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // governance_program
+        32 +                             // realm
+        32 +                             // governance
+        32 +                             // native_treasury
+        32 +                             // pause_guardian
+        8 +                              // enabled_at
+        1 +                              // bump
+        8 +                              // max_spend_per_proposal
+        24;                              // reserved
+
+    /// Native treasury of `governance` under `governance_program`
+    pub fn native_treasury_of(governance: &Pubkey, governance_program: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[NATIVE_TREASURY_SEED, governance.as_ref()], governance_program).0
+    }
+
+    /// Require `proposal` to be a proposal of this governance that is
+    /// currently executing its transactions
+    pub fn check_executing_proposal(&self, proposal: &AccountInfo) -> Result<()> {
+        require_keys_eq!(*proposal.owner, self.governance_program, ErrorCode::InvalidProposal);
+        let data = proposal.try_borrow_data()?;
+        let governance = data
+            .get(PROPOSAL_GOVERNANCE_OFFSET..PROPOSAL_GOVERNANCE_OFFSET + 32)
+            .ok_or(ErrorCode::InvalidProposal)?;
+        require!(governance == self.governance.as_ref(), ErrorCode::InvalidProposal);
+        let state = *data.get(PROPOSAL_STATE_OFFSET).ok_or(ErrorCode::InvalidProposal)?;
+        require!(
+            EXECUTABLE_PROPOSAL_STATES.contains(&state),
+            ErrorCode::InvalidProposal
+        );
+        Ok(())
+    }
+
+    /// Whether `key` may pause payments without a vote
+    pub fn is_pause_guardian(&self, key: &Pubkey) -> bool {
+        self.pause_guardian != Pubkey::default() && self.pause_guardian == *key
+    }
+}
@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+
+/// Lifecycle of an invoice: Open -> Paid, or Open -> Void
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvoiceStatus {
+    Open,
+    Paid,
+    Void,
+}
+
+/// One billed item; descriptions live off-chain and are committed by hash
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LineItem {
+    /// SHA-256 of the off-chain description
+    pub description_hash: [u8; 32],     // 32
+    pub quantity: u32,                  // 4
+    pub unit_price: u64,                // 8
+}
+
+impl LineItem {
+    pub const LEN: usize = 32 + 4 + 8;
+
+    /// `quantity * unit_price`, or None on overflow
+    pub fn amount(&self) -> Option<u64> {
+        self.unit_price.checked_mul(self.quantity as u64)
+    }
+}
+
+/// Itemized bill from a merchant to a user
+///
+/// One PDA per (merchant, invoice id) pair (`[b"invoice", merchant, invoice_id]`).
+/// Paid directly by the user, or collected through the shared user delegate
+/// when linked to one of the user's subscriptions with the same merchant.
+#[account]
+pub struct Invoice {
+    /// Merchant PDA (lutrii-merchant-registry)
+    pub merchant: Pubkey,               // 32
+
+    /// Billed wallet
+    pub user: Pubkey,                   // 32
+
+    /// Merchant-chosen identifier, part of the PDA seeds
+    pub invoice_id: u64,                // 8
+
+    /// Subscription the invoice bills alongside (default = standalone)
+    pub subscription: Pubkey,           // 32
+
+    /// Merchant settlement account receiving payment
+    pub merchant_token_account: Pubkey, // 32
+
+    /// Payment token mint
+    pub mint: Pubkey,                   // 32
+
+    /// Billed items (1..=MAX_LINE_ITEMS)
+    pub line_items: Vec<LineItem>,      // 4 + 10 * 44
+
+    /// Sum of line item amounts
+    pub total: u64,                     // 8
+
+    pub status: InvoiceStatus,          // 1
+
+    /// Unix timestamp the invoice was issued
+    pub created_at: i64,                // 8
+
+    /// Unix timestamp the invoice was paid (0 until paid)
+    pub paid_at: i64,                   // 8
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+}
+
+impl Invoice {
+    pub const MAX_LINE_ITEMS: usize = 10;
+
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // merchant
+        32 +                             // user
+        8 +                              // invoice_id
+        32 +                             // subscription
+        32 +                             // merchant_token_account
+        32 +                             // mint
+        4 + Self::MAX_LINE_ITEMS * LineItem::LEN + // line_items
+        8 +                              // total
+        1 +                              // status
+        8 +                              // created_at
+        8 +                              // paid_at
+        1;                               // bump
+
+    /// Sum of line item amounts, or None on overflow
+    pub fn compute_total(line_items: &[LineItem]) -> Option<u64> {
+        line_items
+            .iter()
+            .try_fold(0u64, |total, item| total.checked_add(item.amount()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(quantity: u32, unit_price: u64) -> LineItem {
+        LineItem {
+            description_hash: [0; 32],
+            quantity,
+            unit_price,
+        }
+    }
+
+    #[test]
+    fn test_compute_total() {
+        assert_eq!(Invoice::compute_total(&[item(2, 500), item(1, 250)]), Some(1_250));
+        assert_eq!(Invoice::compute_total(&[item(2, u64::MAX)]), None);
+        assert_eq!(Invoice::compute_total(&[item(1, u64::MAX), item(1, 1)]), None);
+    }
+}
@@ -0,0 +1,232 @@
+use anchor_lang::prelude::*;
+use lutrii_common::FeeBearer;
+use lutrii_core::{schedule, SECONDS_PER_DAY};
+use crate::state::FeeSchedule;
+
+/// Per-merchant subscription policy, managed by the merchant owner
+///
+/// Lives in this program (`[b"merchant_policy", merchant]`) because the
+/// registry's Merchant account has no spare space. Created on demand by the
+/// first subscription or by the merchant configuring it; defaults impose no
+/// restrictions.
+#[account]
+pub struct MerchantPolicy {
+    /// Merchant PDA (lutrii-merchant-registry)
+    pub merchant: Pubkey,               // 32
+
+    /// Only wallets with an AllowlistEntry may subscribe
+    pub allowlist_only: bool,           // 1
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Maximum concurrently active subscriptions (0 = unlimited)
+    pub max_active_subscribers: u32,    // 4
+
+    /// Subscriptions currently holding a slot
+    pub active_subscribers: u32,        // 4
+
+    /// Days a subscriber may defer a due payment, once per cycle (0 = disabled)
+    pub max_snooze_days: u8,            // 1
+
+    /// Fixed billing date new subscriptions align to, repeating every
+    /// subscription period (0 = bill from signup)
+    pub billing_anchor: i64,            // 8
+
+    /// What swap-settled payments do when no swap route is available
+    pub swap_fallback: SwapFallback,    // 1
+
+    /// Payments to the merchant carry no platform fee (set by the admin)
+    pub fee_exempt: bool,               // 1
+
+    /// Who pays the platform fee on new subscriptions
+    pub fee_bearer: FeeBearer,          // 1
+
+    /// Whether `fee_schedule` overrides the platform's (set by the admin)
+    pub custom_fee: bool,               // 1
+
+    /// Negotiated fee schedule for payments to the merchant
+    pub fee_schedule: FeeSchedule,      // 18
+
+    /// How failed collections back off and when they give up
+    pub retry_policy: RetryPolicy,      // 6
+
+    /// Extra padding for future upgrades
+    pub reserved: [u8; 19],             // 19
+}
+
+impl MerchantPolicy {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // merchant
+        1 +                              // allowlist_only
+        1 +                              // bump
+        4 +                              // max_active_subscribers
+        4 +                              // active_subscribers
+        1 +                              // max_snooze_days
+        8 +                              // billing_anchor
+        1 +                              // swap_fallback
+        1 +                              // fee_exempt
+        1 +                              // fee_bearer
+        1 +                              // custom_fee
+        FeeSchedule::LEN +               // fee_schedule
+        RetryPolicy::LEN +               // retry_policy
+        19;                              // reserved
+
+    /// Upper bound merchants may set for `max_snooze_days`
+    pub const MAX_SNOOZE_DAYS: u8 = 30;
+
+    /// Whether another subscription fits under the cap
+    pub fn has_capacity(&self) -> bool {
+        self.max_active_subscribers == 0 || self.active_subscribers < self.max_active_subscribers
+    }
+
+    /// Free the slot held by a subscription that is no longer active
+    ///
+    /// `info` must be constrained to the merchant's policy PDA. Subscriptions
+    /// created before the merchant had a policy never took a slot, so a
+    /// missing account is a no-op and the counter saturates at zero.
+    pub fn release_slot(info: &AccountInfo) -> Result<()> {
+        if info.data_is_empty() {
+            return Ok(());
+        }
+        let mut policy = Self::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        policy.active_subscribers = policy.active_subscribers.saturating_sub(1);
+        policy.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+    }
+
+    /// Swap fallback configured in `info`, the merchant's policy PDA
+    ///
+    /// Merchants without a policy keep the default.
+    pub fn swap_fallback_of(info: &AccountInfo) -> Result<SwapFallback> {
+        if info.data_is_empty() {
+            return Ok(SwapFallback::default());
+        }
+        Ok(Self::try_deserialize(&mut &info.try_borrow_data()?[..])?.swap_fallback)
+    }
+
+    /// Negotiated fee schedule, if the admin attached one
+    pub fn custom_fee_schedule(&self) -> Option<FeeSchedule> {
+        self.custom_fee.then_some(self.fee_schedule)
+    }
+
+    /// Negotiated fee schedule in `info`, the merchant's policy PDA
+    ///
+    /// Merchants without a policy pay the platform defaults.
+    pub fn fee_schedule_of(info: &AccountInfo) -> Result<Option<FeeSchedule>> {
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+        Ok(Self::try_deserialize(&mut &info.try_borrow_data()?[..])?.custom_fee_schedule())
+    }
+
+    /// Retry policy configured in `info`, the merchant's policy PDA
+    ///
+    /// Merchants without a policy keep the default.
+    pub fn retry_policy_of(info: &AccountInfo) -> Result<RetryPolicy> {
+        if info.data_is_empty() {
+            return Ok(RetryPolicy::default());
+        }
+        Ok(Self::try_deserialize(&mut &info.try_borrow_data()?[..])?.retry_policy)
+    }
+
+    /// Whether `info`, the merchant's policy PDA, marks the merchant fee-exempt
+    pub fn fee_exempt_of(info: &AccountInfo) -> Result<bool> {
+        if info.data_is_empty() {
+            return Ok(false);
+        }
+        Ok(Self::try_deserialize(&mut &info.try_borrow_data()?[..])?.fee_exempt)
+    }
+}
+
+/// How a swap-settled payment is handled when the keeper finds no swap route
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SwapFallback {
+    /// Refuse the execution; the payment stays due until a route exists
+    #[default]
+    Fail,
+    /// Pay the merchant in the subscriber's token at the oracle price
+    SettleInPaymentToken,
+    /// Back the cycle off like a failed collection and retry later
+    Delinquent,
+}
+
+/// How a merchant's failed collections are retried
+///
+/// The default backs off from `RETRY_BASE_DELAY_SECONDS` and retries until
+/// the subscriber pays or cancels.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Consecutive failures that make a subscription delinquent (0 = never)
+    pub max_failed_attempts: u8,
+    /// What happens to a delinquent subscription
+    pub delinquency_action: DelinquencyAction,
+    /// Delay before the first retry, doubling after each failure
+    /// (0 = platform default)
+    pub base_delay_seconds: u32,
+}
+
+impl RetryPolicy {
+    pub const LEN: usize = 1 + 1 + 4;
+
+    /// Shortest and longest `base_delay_seconds` merchants may set
+    pub const MIN_BASE_DELAY_SECONDS: u32 = schedule::MIN_RETRY_BASE_DELAY_SECONDS as u32;
+    pub const MAX_BASE_DELAY_SECONDS: u32 = 7 * SECONDS_PER_DAY as u32;
+
+    /// Whether the policy is within bounds
+    pub fn is_valid(&self) -> bool {
+        self.base_delay_seconds == 0
+            || (Self::MIN_BASE_DELAY_SECONDS..=Self::MAX_BASE_DELAY_SECONDS)
+                .contains(&self.base_delay_seconds)
+    }
+
+    /// Backoff after `retry_count` consecutive failures, in seconds
+    pub fn retry_delay(&self, retry_count: u8) -> i64 {
+        match self.base_delay_seconds {
+            0 => schedule::retry_delay(retry_count),
+            base => schedule::backoff_delay(base as i64, retry_count),
+        }
+    }
+
+    /// Whether `failed_attempts` consecutive failures make a subscription delinquent
+    pub fn is_delinquent(&self, failed_attempts: u8) -> bool {
+        self.max_failed_attempts != 0 && failed_attempts >= self.max_failed_attempts
+    }
+}
+
+/// What happens to a subscription once it runs out of retries
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DelinquencyAction {
+    /// Pause it; the subscriber resumes once funded
+    #[default]
+    Pause,
+    /// Cancel it and release its allowance
+    Cancel,
+}
+
+/// Wallet pre-approved to subscribe to an allowlist-only merchant
+///
+/// One PDA per (merchant, user) pair (`[b"allowlist", merchant, user]`).
+#[account]
+pub struct AllowlistEntry {
+    /// Merchant PDA (lutrii-merchant-registry)
+    pub merchant: Pubkey,               // 32
+
+    /// Approved subscriber wallet
+    pub user: Pubkey,                   // 32
+
+    /// Unix timestamp the wallet was approved
+    pub added_at: i64,                  // 8
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+}
+
+impl AllowlistEntry {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // merchant
+        32 +                             // user
+        8 +                              // added_at
+        1;                               // bump
+}
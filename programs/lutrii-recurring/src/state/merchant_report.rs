@@ -0,0 +1,138 @@
+use anchor_lang::prelude::*;
+use lutrii_core::schedule;
+use crate::errors::ErrorCode;
+
+/// A merchant's payments and platform fees in one mint over a UTC calendar year
+///
+/// Opened by anyone (usually the merchant) with `open_merchant_report`;
+/// payments and invoices in the year record their gross amount and fee,
+/// so year-end reporting (1099-K style) can be read straight from chain.
+/// Totals are also kept per calendar month.
+///
+/// PDA: `[b"merchant_report", merchant, mint, year (u16 LE)]`
+#[account]
+pub struct MerchantReport {
+    /// Merchant PDA (lutrii-merchant-registry)
+    pub merchant: Pubkey,               // 32
+
+    /// Mint the payments were made in
+    pub mint: Pubkey,                   // 32
+
+    /// UTC calendar year covered
+    pub year: u16,                      // 2
+
+    /// Gross amount of the year's payments, before platform fees
+    pub volume: u64,                    // 8
+
+    /// Platform fees charged on those payments
+    pub fees: u64,                      // 8
+
+    /// Number of payments recorded
+    pub payment_count: u32,             // 4
+
+    /// `volume` by month, January first
+    pub monthly_volume: [u64; 12],      // 96
+
+    /// `fees` by month, January first
+    pub monthly_fees: [u64; 12],        // 96
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Extra padding for future upgrades
+    pub reserved: [u8; 32],             // 32
+}
+
+impl MerchantReport {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // merchant
+        32 +                             // mint
+        2 +                              // year
+        8 +                              // volume
+        8 +                              // fees
+        4 +                              // payment_count
+        96 +                             // monthly_volume
+        96 +                             // monthly_fees
+        1 +                              // bump
+        32;                              // reserved
+
+    /// Year seed of the report covering `now`
+    pub fn year_seed(now: i64) -> [u8; 2] {
+        (schedule::utc_year_month(now).0 as u16).to_le_bytes()
+    }
+
+    /// Apply `update` to `info`, the merchant's report for the current year PDA
+    ///
+    /// Payments only count once the year's report is opened, so a missing
+    /// account is a no-op.
+    pub fn update_in(info: &AccountInfo, update: impl FnOnce(&mut Self) -> Result<()>) -> Result<()> {
+        if info.data_is_empty() {
+            return Ok(());
+        }
+        let mut report = Self::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        update(&mut report)?;
+        report.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+    }
+
+    /// Record a payment of gross `amount` and the platform `fee` charged on it
+    pub fn record(&mut self, amount: u64, fee: u64, now: i64) -> Result<()> {
+        let (year, month) = schedule::utc_year_month(now);
+        require!(year == i64::from(self.year), ErrorCode::ReportingYearMismatch);
+        let month = (month - 1) as usize;
+
+        self.volume = self.volume.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        self.fees = self.fees.checked_add(fee).ok_or(ErrorCode::Overflow)?;
+        self.payment_count = self.payment_count.checked_add(1).ok_or(ErrorCode::Overflow)?;
+        self.monthly_volume[month] = self.monthly_volume[month]
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        self.monthly_fees[month] = self.monthly_fees[month]
+            .checked_add(fee)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-01-31T12:00:00Z and 2024-02-29T00:00:00Z
+    const JAN_31_NOON: i64 = 1_706_702_400;
+    const FEB_29: i64 = 1_709_164_800;
+
+    fn report(year: u16) -> MerchantReport {
+        MerchantReport {
+            merchant: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            year,
+            volume: 0,
+            fees: 0,
+            payment_count: 0,
+            monthly_volume: [0; 12],
+            monthly_fees: [0; 12],
+            bump: 255,
+            reserved: [0; 32],
+        }
+    }
+
+    #[test]
+    fn test_record_accrues_year_and_month() {
+        let mut report = report(2024);
+        report.record(1_000, 10, JAN_31_NOON).unwrap();
+        report.record(500, 5, FEB_29).unwrap();
+        report.record(200, 0, FEB_29 + 1).unwrap();
+
+        assert_eq!((report.volume, report.fees, report.payment_count), (1_700, 15, 3));
+        assert_eq!(report.monthly_volume[..3], [1_000, 700, 0]);
+        assert_eq!(report.monthly_fees[..3], [10, 5, 0]);
+    }
+
+    #[test]
+    fn test_record_rejects_other_years() {
+        let mut report = report(2023);
+        assert!(report.record(1_000, 10, JAN_31_NOON).is_err());
+        assert_eq!(report.payment_count, 0);
+    }
+}
@@ -0,0 +1,169 @@
+use anchor_lang::prelude::*;
+use lutrii_core::schedule;
+use crate::errors::ErrorCode;
+
+/// A merchant's books in one mint for a UTC calendar month
+///
+/// Opened ahead of the month by a crank (or the merchant) with
+/// `open_merchant_statement`; payments and invoices in the month accrue
+/// into it. Once the month is over anyone can `close_merchant_statement`,
+/// after which it never changes.
+///
+/// PDA: `[b"merchant_statement", merchant, mint, year (u16 LE), month]`
+#[account]
+pub struct MerchantStatement {
+    /// Merchant PDA (lutrii-merchant-registry)
+    pub merchant: Pubkey,               // 32
+
+    /// Mint the payments were made in
+    pub mint: Pubkey,                   // 32
+
+    /// UTC calendar year of the period
+    pub year: u16,                      // 2
+
+    /// UTC calendar month of the period (1-12)
+    pub month: u8,                      // 1
+
+    /// Number of payments collected
+    pub payment_count: u32,             // 4
+
+    /// Gross amount of those payments, before platform fees
+    pub gross_volume: u64,              // 8
+
+    /// Amounts returned to users
+    pub refunds: u64,                   // 8
+
+    /// Platform fees charged
+    pub fees: u64,                      // 8
+
+    /// What the merchant was paid after fees and refunds
+    pub net_settlement: u64,            // 8
+
+    /// Whether the books are closed
+    pub closed: bool,                   // 1
+
+    /// When the books were closed
+    pub closed_at: i64,                 // 8
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Extra padding for future upgrades
+    pub reserved: [u8; 32],             // 32
+}
+
+impl MerchantStatement {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // merchant
+        32 +                             // mint
+        2 +                              // year
+        1 +                              // month
+        4 +                              // payment_count
+        8 +                              // gross_volume
+        8 +                              // refunds
+        8 +                              // fees
+        8 +                              // net_settlement
+        1 +                              // closed
+        8 +                              // closed_at
+        1 +                              // bump
+        32;                              // reserved
+
+    /// Month seed of the statement covering `now`
+    pub fn month_seed(now: i64) -> [u8; 1] {
+        [schedule::utc_year_month(now).1 as u8]
+    }
+
+    /// Apply `update` to `info`, the merchant's statement for the current month PDA
+    ///
+    /// Payments only count once the month's statement is opened, so a
+    /// missing account is a no-op.
+    pub fn update_in(info: &AccountInfo, update: impl FnOnce(&mut Self) -> Result<()>) -> Result<()> {
+        if info.data_is_empty() {
+            return Ok(());
+        }
+        let mut statement = Self::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        update(&mut statement)?;
+        statement.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+    }
+
+    /// Start and exclusive end of the period
+    pub fn period_bounds(&self) -> Result<(i64, i64)> {
+        schedule::utc_month_bounds(i64::from(self.year), u32::from(self.month))
+            .map_err(|_| error!(ErrorCode::InvalidStatementPeriod))
+    }
+
+    /// Record a payment of gross `amount`, its platform `fee` and what the
+    /// merchant received
+    pub fn record(&mut self, amount: u64, fee: u64, merchant_amount: u64, now: i64) -> Result<()> {
+        require!(!self.closed, ErrorCode::StatementClosed);
+        let (start, end) = self.period_bounds()?;
+        require!(now >= start && now < end, ErrorCode::StatementPeriodMismatch);
+
+        self.payment_count = self.payment_count.checked_add(1).ok_or(ErrorCode::Overflow)?;
+        self.gross_volume = self.gross_volume.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        self.fees = self.fees.checked_add(fee).ok_or(ErrorCode::Overflow)?;
+        self.net_settlement = self
+            .net_settlement
+            .checked_add(merchant_amount)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    /// Close the books once the period is over
+    pub fn close_books(&mut self, now: i64) -> Result<()> {
+        require!(!self.closed, ErrorCode::StatementClosed);
+        let (_, end) = self.period_bounds()?;
+        require!(now >= end, ErrorCode::StatementPeriodOpen);
+        self.closed = true;
+        self.closed_at = now;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-02-29T00:00:00Z and 2024-03-01T00:00:00Z
+    const FEB_29: i64 = 1_709_164_800;
+    const MAR_1: i64 = 1_709_251_200;
+
+    fn statement() -> MerchantStatement {
+        MerchantStatement {
+            merchant: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            year: 2024,
+            month: 2,
+            payment_count: 0,
+            gross_volume: 0,
+            refunds: 0,
+            fees: 0,
+            net_settlement: 0,
+            closed: false,
+            closed_at: 0,
+            bump: 255,
+            reserved: [0; 32],
+        }
+    }
+
+    #[test]
+    fn test_books_close_once_the_month_ends() {
+        let mut statement = statement();
+        statement.record(1_000, 10, 990, FEB_29).unwrap();
+        statement.record(500, 5, 500, MAR_1 - 1).unwrap();
+        assert!(statement.record(100, 1, 99, MAR_1).is_err());
+        assert!(statement.close_books(MAR_1 - 1).is_err());
+
+        statement.close_books(MAR_1).unwrap();
+        assert_eq!(
+            (statement.payment_count, statement.gross_volume, statement.fees, statement.net_settlement),
+            (2, 1_500, 15, 1_490)
+        );
+
+        // Closed books stay as they are
+        assert!(statement.record(100, 1, 99, FEB_29).is_err());
+        assert!(statement.close_books(MAR_1 + 1).is_err());
+        assert_eq!(statement.closed_at, MAR_1);
+    }
+}
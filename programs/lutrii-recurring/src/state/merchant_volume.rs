@@ -0,0 +1,176 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::state::SECONDS_PER_MONTH;
+
+/// A merchant's processed volume and platform fees in one mint, per rebate period
+///
+/// Opened by anyone (usually the merchant) with `open_merchant_volume`;
+/// executions then record their charge and fee. Rebate periods are
+/// 30-day windows counted from the unix epoch. Only the current and the most
+/// recently finished period are kept, so a period's rebate must be claimed
+/// before the next one finishes.
+///
+/// PDA: `[b"merchant_volume", merchant, mint]`
+#[account]
+pub struct MerchantVolume {
+    /// Merchant PDA (lutrii-merchant-registry)
+    pub merchant: Pubkey,               // 32
+
+    /// Mint the volume was paid in
+    pub mint: Pubkey,                   // 32
+
+    /// Period `volume` and `fees` accrue to
+    pub period: i64,                    // 8
+
+    /// Charges collected in `period`
+    pub volume: u64,                    // 8
+
+    /// Platform fees taken from those charges
+    pub fees: u64,                      // 8
+
+    /// Most recently finished period
+    pub closed_period: i64,             // 8
+
+    /// Charges collected in `closed_period`
+    pub closed_volume: u64,             // 8
+
+    /// Platform fees taken in `closed_period`
+    pub closed_fees: u64,               // 8
+
+    /// Last period a rebate was claimed for
+    pub claimed_period: i64,            // 8
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Extra padding for future upgrades
+    pub reserved: [u8; 32],             // 32
+}
+
+impl MerchantVolume {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // merchant
+        32 +                             // mint
+        8 +                              // period
+        8 +                              // volume
+        8 +                              // fees
+        8 +                              // closed_period
+        8 +                              // closed_volume
+        8 +                              // closed_fees
+        8 +                              // claimed_period
+        1 +                              // bump
+        32;                              // reserved
+
+    /// Rebate period containing `timestamp`
+    pub fn period_of(timestamp: i64) -> i64 {
+        timestamp.div_euclid(SECONDS_PER_MONTH)
+    }
+
+    /// Apply `update` to `info`, the merchant's volume PDA
+    ///
+    /// Volume only counts once the merchant opens tracking, so a missing
+    /// account is a no-op.
+    pub fn update_in(info: &AccountInfo, update: impl FnOnce(&mut Self) -> Result<()>) -> Result<()> {
+        if info.data_is_empty() {
+            return Ok(());
+        }
+        let mut volume = Self::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        update(&mut volume)?;
+        volume.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+    }
+
+    /// Start counting at `now`, with nothing claimable yet
+    pub fn open(&mut self, merchant: Pubkey, mint: Pubkey, now: i64, bump: u8) {
+        let period = Self::period_of(now);
+        self.merchant = merchant;
+        self.mint = mint;
+        self.period = period;
+        self.volume = 0;
+        self.fees = 0;
+        self.closed_period = period - 1;
+        self.closed_volume = 0;
+        self.closed_fees = 0;
+        self.claimed_period = period - 1;
+        self.bump = bump;
+    }
+
+    /// Close the current period if `now` has moved past it
+    pub fn roll(&mut self, now: i64) {
+        let period = Self::period_of(now);
+        if period > self.period {
+            self.closed_period = self.period;
+            self.closed_volume = self.volume;
+            self.closed_fees = self.fees;
+            self.period = period;
+            self.volume = 0;
+            self.fees = 0;
+        }
+    }
+
+    /// Record a collected `charge` and the platform `fee` taken from it
+    pub fn record(&mut self, charge: u64, fee: u64, now: i64) -> Result<()> {
+        self.roll(now);
+        self.volume = self.volume.checked_add(charge).ok_or(ErrorCode::Overflow)?;
+        self.fees = self.fees.checked_add(fee).ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    /// Whether the finished period is still waiting for its rebate claim
+    pub fn rebate_claimable(&self) -> bool {
+        self.closed_period > self.claimed_period
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONTH: i64 = SECONDS_PER_MONTH;
+
+    fn volume(now: i64) -> MerchantVolume {
+        let mut volume = MerchantVolume {
+            merchant: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            period: 0,
+            volume: 0,
+            fees: 0,
+            closed_period: 0,
+            closed_volume: 0,
+            closed_fees: 0,
+            claimed_period: 0,
+            bump: 255,
+            reserved: [0; 32],
+        };
+        let (merchant, mint) = (volume.merchant, volume.mint);
+        volume.open(merchant, mint, now, 255);
+        volume
+    }
+
+    #[test]
+    fn test_nothing_claimable_when_opened() {
+        let volume = volume(10 * MONTH + 5);
+        assert_eq!(volume.period, 10);
+        assert!(!volume.rebate_claimable());
+    }
+
+    #[test]
+    fn test_roll_closes_the_period_once() {
+        let mut volume = volume(10 * MONTH);
+        volume.record(1_000, 10, 10 * MONTH + 1).unwrap();
+        volume.record(500, 5, 11 * MONTH - 1).unwrap();
+        assert!(!volume.rebate_claimable());
+
+        // Payments in a later period close the earlier one
+        volume.record(200, 2, 11 * MONTH).unwrap();
+        assert!(volume.rebate_claimable());
+        assert_eq!((volume.closed_period, volume.closed_volume, volume.closed_fees), (10, 1_500, 15));
+        assert_eq!((volume.period, volume.volume, volume.fees), (11, 200, 2));
+
+        // A quiet stretch keeps the last active period claimable
+        volume.roll(14 * MONTH);
+        assert_eq!((volume.closed_period, volume.closed_volume), (11, 200));
+        volume.roll(14 * MONTH + 1);
+        assert_eq!(volume.closed_period, 11);
+    }
+}
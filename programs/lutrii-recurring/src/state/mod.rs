@@ -1,3 +1,65 @@
 pub mod platform_config;
+pub mod denylist;
+pub mod blocklist;
+pub mod merchant_policy;
+pub mod user_delegate;
+pub mod plan;
+pub mod invoice;
+pub mod user_stats;
+pub mod settlement;
+pub mod fee_treasury;
+pub mod yield_config;
+pub mod swap_asset;
+pub mod due_bucket;
+pub mod offer;
+pub mod merchant_volume;
+pub mod merchant_report;
+pub mod merchant_statement;
+pub mod refund_liability;
+pub mod revenue_forecast;
+pub mod governance;
+pub mod treasury_spend;
+pub mod admin_audit_log;
+pub mod swap_config;
+pub mod accepted_mint;
+pub mod fee_policy;
+pub mod fee_holiday;
+pub mod subscription_counter;
+pub mod admin_council;
+pub mod config_change;
+pub mod subscription_escrow;
+pub mod usage_meter;
+pub mod promo;
 
 pub use platform_config::*;
+pub use denylist::*;
+pub use blocklist::*;
+pub use merchant_policy::*;
+pub use user_delegate::*;
+pub use plan::*;
+pub use invoice::*;
+pub use user_stats::*;
+pub use settlement::*;
+pub use fee_treasury::*;
+pub use yield_config::*;
+pub use swap_asset::*;
+pub use due_bucket::*;
+pub use offer::*;
+pub use merchant_volume::*;
+pub use merchant_report::*;
+pub use merchant_statement::*;
+pub use refund_liability::*;
+pub use revenue_forecast::*;
+pub use governance::*;
+pub use treasury_spend::*;
+pub use admin_audit_log::*;
+pub use swap_config::*;
+pub use accepted_mint::*;
+pub use fee_policy::*;
+pub use fee_holiday::*;
+pub use subscription_counter::*;
+pub use admin_council::*;
+pub use config_change::*;
+pub use subscription_escrow::*;
+pub use usage_meter::*;
+pub use promo::*;
@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+use lutrii_common::MAX_MERCHANT_NAME_LEN;
+use crate::errors::ErrorCode;
+use crate::{MAX_FREQUENCY_SECONDS, MIN_FREQUENCY_SECONDS};
+
+/// Subscription terms fixed by a merchant offer
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct OfferTerms {
+    /// Amount charged per billing cycle
+    pub amount: u64,                    // 8
+
+    /// Billing cycle length
+    pub frequency_seconds: i64,         // 8
+
+    /// Per-transaction safety cap
+    pub max_per_transaction: u64,       // 8
+
+    /// Lifetime safety cap, approved to the user delegate on acceptance
+    pub lifetime_cap: u64,              // 8
+
+    /// Display name recorded on the subscription
+    pub merchant_name: String,          // 4 + 32
+}
+
+impl OfferTerms {
+    pub const LEN: usize = 8 + 8 + 8 + 8 + 4 + MAX_MERCHANT_NAME_LEN;
+
+    /// Same checks create_subscription applies to its arguments
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.frequency_seconds >= MIN_FREQUENCY_SECONDS,
+            ErrorCode::FrequencyTooShort
+        );
+        require!(
+            self.frequency_seconds <= MAX_FREQUENCY_SECONDS,
+            ErrorCode::FrequencyTooLong
+        );
+        require!(
+            !self.merchant_name.is_empty() && self.merchant_name.len() <= MAX_MERCHANT_NAME_LEN,
+            ErrorCode::InvalidMerchantName
+        );
+        require!(self.amount > 0, ErrorCode::AmountTooLow);
+        require!(
+            self.amount <= self.max_per_transaction,
+            ErrorCode::ExceedsTransactionCap
+        );
+        require!(self.amount <= self.lifetime_cap, ErrorCode::ExceedsLifetimeCap);
+        Ok(())
+    }
+}
+
+/// Merchant-prepared subscription awaiting the user's signature
+///
+/// Accepting creates the subscription with exactly these terms, so a
+/// tampered checkout client cannot change the amount or frequency the user
+/// signs for. Offers are either:
+/// - targeted: one PDA per (merchant, user) pair (`[b"offer", merchant, user]`),
+///   accepted once
+/// - published: open to any wallet (`user` is the default pubkey) with
+///   limited inventory (`[b"offer", merchant, offer_id.to_le_bytes()]`)
+///
+/// The offer closes to the merchant owner once its inventory is used up.
+#[account]
+pub struct SubscriptionOffer {
+    /// Merchant PDA (lutrii-merchant-registry)
+    pub merchant: Pubkey,               // 32
+
+    /// Wallet the offer is addressed to (default = published to anyone)
+    pub user: Pubkey,                   // 32
+
+    /// Merchant settlement account the subscription pays into
+    pub merchant_token_account: Pubkey, // 32
+
+    pub terms: OfferTerms,              // 68
+
+    /// Unix timestamp after which the offer can no longer be accepted
+    pub expires_at: i64,                // 8
+
+    /// Unix timestamp the offer was created
+    pub created_at: i64,                // 8
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Merchant-chosen identifier of a published offer (0 when targeted)
+    pub offer_id: u64,                  // 8
+
+    /// Acceptances the offer allows
+    pub max_acceptances: u32,           // 4
+
+    /// Acceptances so far
+    pub acceptances: u32,               // 4
+}
+
+impl SubscriptionOffer {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // merchant
+        32 +                             // user
+        32 +                             // merchant_token_account
+        OfferTerms::LEN +                // terms
+        8 +                              // expires_at
+        8 +                              // created_at
+        1 +                              // bump
+        8 +                              // offer_id
+        4 +                              // max_acceptances
+        4;                               // acceptances
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        now >= self.expires_at
+    }
+
+    pub fn is_published(&self) -> bool {
+        self.user == Pubkey::default()
+    }
+
+    /// Whether `user` may accept the offer
+    pub fn is_open_to(&self, user: &Pubkey) -> bool {
+        self.is_published() || self.user == *user
+    }
+
+    /// Acceptances left
+    pub fn remaining(&self) -> u32 {
+        self.max_acceptances.saturating_sub(self.acceptances)
+    }
+}
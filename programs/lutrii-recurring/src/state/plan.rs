@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use lutrii_common::MAX_MERCHANT_NAME_LEN;
+
+/// Merchant-defined subscription plan
+///
+/// One PDA per (merchant, plan id) pair (`[b"plan", merchant, plan_id]`).
+/// Subscriptions record the plan they are bound to so users can move between
+/// a merchant's plans without losing their payment history. Payments of a
+/// bound subscription charge the plan's current `price`, so `update_plan`
+/// reprices every subscriber at once within the 10% price variance window;
+/// larger changes still need each user's `accept_amount_change`.
+#[account]
+pub struct Plan {
+    /// Merchant PDA (lutrii-merchant-registry)
+    pub merchant: Pubkey,               // 32
+
+    /// Merchant-chosen identifier, part of the PDA seeds
+    pub plan_id: u64,                   // 8
+
+    /// Display name
+    pub name: String,                   // 4 + 32
+
+    /// Price per billing cycle
+    pub price: u64,                     // 8
+
+    /// Billing cycle length
+    pub frequency_seconds: i64,         // 8
+
+    /// Whether subscriptions may move onto this plan
+    pub is_active: bool,                // 1
+
+    /// Unix timestamp the plan was created
+    pub created_at: i64,                // 8
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Mint the plan is billed in (default = any accepted mint)
+    pub mint: Pubkey,                   // 32
+
+    /// Free trial before the first payment of new subscribers (0 = none)
+    pub trial_period_seconds: i64,      // 8
+
+    /// Extra padding for future upgrades
+    pub reserved: [u8; 24],             // 24
+}
+
+impl Plan {
+    pub const MAX_NAME_LEN: usize = MAX_MERCHANT_NAME_LEN;
+
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // merchant
+        8 +                              // plan_id
+        4 + Self::MAX_NAME_LEN +         // name
+        8 +                              // price
+        8 +                              // frequency_seconds
+        1 +                              // is_active
+        8 +                              // created_at
+        1 +                              // bump
+        32 +                             // mint
+        8 +                              // trial_period_seconds
+        24;                              // reserved
+
+    /// Whether subscriptions to the plan may be billed in `mint`
+    pub fn accepts_mint(&self, mint: &Pubkey) -> bool {
+        self.mint == Pubkey::default() || self.mint == *mint
+    }
+}
@@ -4,6 +4,9 @@ use anchor_lang::prelude::*;
 ///
 /// Phase 1: Single fee wallet per stablecoin (USDC + USD1)
 /// Phase 3: Automated fee splitting to operations/LP/marketing wallets
+///
+/// Legacy: fee wallets now live on `PlatformState` (layout v2). This account
+/// is only read by `migrate_platform_state`, which merges and closes it.
 #[account]
 pub struct PlatformConfig {
     /// Admin authority (can update config)
@@ -0,0 +1,152 @@
+use anchor_lang::prelude::*;
+use lutrii_core::BASIS_POINTS_DIVISOR;
+use crate::errors::ErrorCode;
+
+/// Longest promo code, in bytes (it is a PDA seed)
+pub const MAX_PROMO_CODE_LEN: usize = 16;
+
+/// What a promo code takes off each discounted payment
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PromoDiscount {
+    /// Share of the subscription amount, in basis points
+    Percent { basis_points: u16 },
+    /// Fixed token units, at most the subscription amount
+    Fixed { amount: u64 },
+}
+
+impl PromoDiscount {
+    pub const SPACE: usize = 1 + 8;
+
+    /// Whether the discount takes something off without exceeding 100%
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            Self::Percent { basis_points } => {
+                basis_points > 0 && u128::from(basis_points) <= BASIS_POINTS_DIVISOR
+            }
+            Self::Fixed { amount } => amount > 0,
+        }
+    }
+
+    /// Discount on a payment of `amount`, rounded down
+    pub fn discount_on(&self, amount: u64) -> u64 {
+        match *self {
+            Self::Percent { basis_points } => {
+                (amount as u128 * basis_points as u128 / BASIS_POINTS_DIVISOR) as u64
+            }
+            Self::Fixed { amount: off } => off.min(amount),
+        }
+    }
+}
+
+/// Merchant promo code discounting new subscribers' first payments
+///
+/// Passed to `create_subscription`, it records the discount on the new
+/// subscription, which takes it off its first `discounted_payments`
+/// payments. Percent discounts are priced on the amount at signup.
+///
+/// PDA: `[b"promo", merchant, code]`
+#[account]
+pub struct Promo {
+    /// Merchant PDA (lutrii-merchant-registry)
+    pub merchant: Pubkey,               // 32
+
+    /// Code subscribers enter, part of the PDA seeds
+    pub code: String,                   // 4 + 16
+
+    /// Discount per payment
+    pub discount: PromoDiscount,        // 9
+
+    /// Payments each redemption discounts
+    pub discounted_payments: u16,       // 2
+
+    /// Most subscriptions that may redeem the code (0 = unlimited)
+    pub max_redemptions: u32,           // 4
+
+    /// Subscriptions that redeemed the code
+    pub redemptions: u32,               // 4
+
+    /// Unix time the code stops being accepted (0 = never)
+    pub expires_at: i64,                // 8
+
+    /// Unix timestamp the promo was created
+    pub created_at: i64,                // 8
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Extra padding for future upgrades
+    pub reserved: [u8; 32],             // 32
+}
+
+impl Promo {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // merchant
+        4 + MAX_PROMO_CODE_LEN +         // code
+        PromoDiscount::SPACE +           // discount
+        2 +                              // discounted_payments
+        4 +                              // max_redemptions
+        4 +                              // redemptions
+        8 +                              // expires_at
+        8 +                              // created_at
+        1 +                              // bump
+        32;                              // reserved
+
+    /// Redeem the code for a subscription of `amount` at `now`
+    ///
+    /// Returns the discount per payment and how many payments it covers.
+    pub fn redeem(&mut self, amount: u64, now: i64) -> Result<(u64, u16)> {
+        require!(
+            self.expires_at == 0 || now < self.expires_at,
+            ErrorCode::PromoExpired
+        );
+        require!(
+            self.max_redemptions == 0 || self.redemptions < self.max_redemptions,
+            ErrorCode::PromoExhausted
+        );
+        self.redemptions = self.redemptions.checked_add(1).ok_or(ErrorCode::Overflow)?;
+        Ok((self.discount.discount_on(amount), self.discounted_payments))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(discount: PromoDiscount, max_redemptions: u32, expires_at: i64) -> Promo {
+        Promo {
+            merchant: Pubkey::new_unique(),
+            code: "WELCOME".to_string(),
+            discount,
+            discounted_payments: 3,
+            max_redemptions,
+            redemptions: 0,
+            expires_at,
+            created_at: 0,
+            bump: 255,
+            reserved: [0; 32],
+        }
+    }
+
+    #[test]
+    fn test_discounts() {
+        let half = PromoDiscount::Percent { basis_points: 5_000 };
+        assert_eq!(half.discount_on(1_001), 500);
+        assert_eq!(PromoDiscount::Fixed { amount: 300 }.discount_on(200), 200);
+        assert!(!PromoDiscount::Percent { basis_points: 10_001 }.is_valid());
+        assert!(!PromoDiscount::Fixed { amount: 0 }.is_valid());
+    }
+
+    #[test]
+    fn test_redemptions_limited_and_expire() {
+        let mut promo = sample(PromoDiscount::Fixed { amount: 100 }, 2, 1_000);
+        assert_eq!(promo.redeem(500, 10).unwrap(), (100, 3));
+        promo.redeem(500, 20).unwrap();
+        assert!(promo.redeem(500, 30).is_err());
+        assert_eq!(promo.redemptions, 2);
+
+        let mut promo = sample(PromoDiscount::Fixed { amount: 100 }, 0, 1_000);
+        assert!(promo.redeem(500, 1_000).is_err());
+        assert_eq!(promo.redemptions, 0);
+    }
+}
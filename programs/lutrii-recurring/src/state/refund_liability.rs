@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+use lutrii_core::{BASIS_POINTS_DIVISOR, SECONDS_PER_DAY};
+use crate::errors::ErrorCode;
+
+/// Longest refund window tracked, in days
+pub const MAX_REFUND_WINDOW_DAYS: u16 = 31;
+
+/// A merchant's refundable exposure in one mint
+///
+/// Configured by the admin with `configure_refund_liability`; payments
+/// then record what the merchant received into a bucket for the UTC day. Payments stay refundable for `window_days`, and sweeps of the
+/// merchant's settlement vault hold back `reserve_bps` of that exposure.
+///
+/// PDA: `[b"refund_liability", merchant, mint]`
+#[account]
+pub struct RefundLiability {
+    /// Merchant PDA (lutrii-merchant-registry)
+    pub merchant: Pubkey,               // 32
+
+    /// Mint the payments were made in
+    pub mint: Pubkey,                   // 32
+
+    /// Days a payment stays refundable (1 to `MAX_REFUND_WINDOW_DAYS`)
+    pub window_days: u16,               // 2
+
+    /// Share of the outstanding exposure kept in the settlement vault
+    pub reserve_bps: u16,               // 2
+
+    /// Unix day of the newest bucket
+    pub day: i64,                       // 8
+
+    /// Amounts received per unix day, indexed by day modulo the window cap
+    pub buckets: [u64; MAX_REFUND_WINDOW_DAYS as usize], // 248
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Extra padding for future upgrades
+    pub reserved: [u8; 32],             // 32
+}
+
+impl RefundLiability {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // merchant
+        32 +                             // mint
+        2 +                              // window_days
+        2 +                              // reserve_bps
+        8 +                              // day
+        8 * MAX_REFUND_WINDOW_DAYS as usize + // buckets
+        1 +                              // bump
+        32;                              // reserved
+
+    fn slot(day: i64) -> usize {
+        day.rem_euclid(i64::from(MAX_REFUND_WINDOW_DAYS)) as usize
+    }
+
+    /// Apply `update` to `info`, the merchant's refund liability PDA
+    ///
+    /// Exposure is only tracked once the admin configures it, so a missing
+    /// account is a no-op.
+    pub fn update_in(info: &AccountInfo, update: impl FnOnce(&mut Self) -> Result<()>) -> Result<()> {
+        if info.data_is_empty() {
+            return Ok(());
+        }
+        let mut liability = Self::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        update(&mut liability)?;
+        liability.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+    }
+
+    /// Record `amount` received by the merchant at `now`
+    pub fn record(&mut self, amount: u64, now: i64) -> Result<()> {
+        let today = now.div_euclid(SECONDS_PER_DAY);
+        if today > self.day {
+            // Clear the buckets of the days skipped since the last payment
+            let elapsed = (today - self.day).min(i64::from(MAX_REFUND_WINDOW_DAYS));
+            for day in today - elapsed + 1..=today {
+                self.buckets[Self::slot(day)] = 0;
+            }
+            self.day = today;
+        }
+        let slot = Self::slot(self.day);
+        self.buckets[slot] = self.buckets[slot].checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    /// Amount received within the refund window ending at `now`
+    pub fn outstanding(&self, now: i64) -> u64 {
+        let today = now.div_euclid(SECONDS_PER_DAY);
+        let oldest = today - i64::from(self.window_days) + 1;
+        let tracked = self.day - i64::from(MAX_REFUND_WINDOW_DAYS) + 1;
+        (oldest.max(tracked)..=today.min(self.day))
+            .map(|day| self.buckets[Self::slot(day)])
+            .fold(0u64, u64::saturating_add)
+    }
+
+    /// Settlement vault balance held back from sweeps at `now`
+    pub fn required_reserve(&self, now: i64) -> u64 {
+        (u128::from(self.outstanding(now)) * u128::from(self.reserve_bps) / BASIS_POINTS_DIVISOR) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = SECONDS_PER_DAY;
+
+    fn liability(window_days: u16) -> RefundLiability {
+        RefundLiability {
+            merchant: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            window_days,
+            reserve_bps: 5_000,
+            day: 0,
+            buckets: [0; MAX_REFUND_WINDOW_DAYS as usize],
+            bump: 255,
+            reserved: [0; 32],
+        }
+    }
+
+    #[test]
+    fn test_exposure_rolls_off_after_the_window() {
+        let mut liability = liability(7);
+        liability.record(1_000, 100 * DAY).unwrap();
+        liability.record(500, 103 * DAY + 1).unwrap();
+        assert_eq!(liability.outstanding(103 * DAY), 1_500);
+        assert_eq!(liability.required_reserve(103 * DAY), 750);
+
+        // Day 100 leaves the window on day 107, day 103 on day 110
+        assert_eq!(liability.outstanding(106 * DAY), 1_500);
+        assert_eq!(liability.outstanding(107 * DAY), 500);
+        assert_eq!(liability.outstanding(110 * DAY), 0);
+    }
+
+    #[test]
+    fn test_stale_buckets_are_cleared_on_reuse() {
+        let mut liability = liability(MAX_REFUND_WINDOW_DAYS);
+        liability.record(1_000, 100 * DAY).unwrap();
+
+        // Day 131 reuses day 100's bucket
+        liability.record(200, 131 * DAY).unwrap();
+        assert_eq!(liability.outstanding(131 * DAY), 200);
+
+        // A long gap clears everything
+        liability.record(300, 400 * DAY).unwrap();
+        assert_eq!(liability.outstanding(400 * DAY), 300);
+    }
+}
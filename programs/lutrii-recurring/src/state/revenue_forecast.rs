@@ -0,0 +1,196 @@
+use anchor_lang::prelude::*;
+use lutrii_core::SECONDS_PER_DAY;
+use crate::errors::ErrorCode;
+
+/// Days ahead the forecast tracks due payments (a year-long cycle plus a snooze)
+pub const FORECAST_RING_DAYS: usize = 400;
+
+/// Days of due payments counted in `projected_revenue`
+pub const FORECAST_WINDOW_DAYS: i64 = 30;
+
+/// A merchant's upcoming revenue in one mint
+///
+/// Holds the next payment of each active subscription in a bucket for its
+/// UTC due day. Payments always move their subscription's entry; creating,
+/// cancelling, pausing or rescheduling a subscription moves it when the
+/// account is passed along, so like `DueBucket` it is best-effort:
+/// subscriptions that change without it, or predate it, are missing until
+/// their next payment. Slot-scheduled
+/// subscriptions are not counted.
+///
+/// `projected_revenue` is the sum due within `FORECAST_WINDOW_DAYS`, as of
+/// `projected_at`, for financing programs to read; `refresh_revenue_forecast`
+/// brings it current between updates.
+///
+/// PDA: `[b"revenue_forecast", merchant, mint]`
+#[account]
+pub struct RevenueForecast {
+    /// Merchant PDA (lutrii-merchant-registry)
+    pub merchant: Pubkey,               // 32
+
+    /// Mint the payments are taken in
+    pub mint: Pubkey,                   // 32
+
+    /// Payments due within the window as of `projected_at`
+    pub projected_revenue: u64,         // 8
+
+    /// When `projected_revenue` was computed
+    pub projected_at: i64,              // 8
+
+    /// Unix day of the oldest bucket
+    pub day: i64,                       // 8
+
+    /// Amounts due per unix day, indexed by day modulo the ring size
+    /// (`FORECAST_RING_DAYS` long; kept on the heap, too large for the stack)
+    pub buckets: Vec<u64>,              // 4 + 8 * 400
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Extra padding for future upgrades
+    pub reserved: [u8; 32],             // 32
+}
+
+impl RevenueForecast {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // merchant
+        32 +                             // mint
+        8 +                              // projected_revenue
+        8 +                              // projected_at
+        8 +                              // day
+        4 + 8 * FORECAST_RING_DAYS +     // buckets
+        1 +                              // bump
+        32;                              // reserved
+
+    fn slot(day: i64) -> usize {
+        day.rem_euclid(FORECAST_RING_DAYS as i64) as usize
+    }
+
+    /// Bucket of the payment due at `due_at`, if it falls within the ring
+    fn tracked_slot(&self, due_at: i64) -> Option<usize> {
+        let day = due_at.div_euclid(SECONDS_PER_DAY);
+        (day >= self.day && day < self.day + FORECAST_RING_DAYS as i64).then(|| Self::slot(day))
+    }
+
+    /// Apply `update` to `info`, the merchant's revenue forecast PDA
+    ///
+    /// Payments are only forecast once the forecast is opened, so a missing
+    /// account is a no-op.
+    pub fn update_in(info: &AccountInfo, update: impl FnOnce(&mut Self) -> Result<()>) -> Result<()> {
+        if info.data_is_empty() {
+            return Ok(());
+        }
+        let mut forecast = Self::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        update(&mut forecast)?;
+        forecast.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+    }
+
+    /// Drop the buckets of days before `now`, freeing them for later days
+    fn roll(&mut self, now: i64) {
+        let today = now.div_euclid(SECONDS_PER_DAY);
+        if today > self.day {
+            let elapsed = (today - self.day).min(FORECAST_RING_DAYS as i64);
+            for day in self.day..self.day + elapsed {
+                self.buckets[Self::slot(day)] = 0;
+            }
+            self.day = today;
+        }
+    }
+
+    /// Payments due within the window starting at `now`
+    pub fn forecast(&self, now: i64) -> u64 {
+        let today = now.div_euclid(SECONDS_PER_DAY).max(self.day);
+        let end = (today + FORECAST_WINDOW_DAYS).min(self.day + FORECAST_RING_DAYS as i64);
+        (today..end)
+            .map(|day| self.buckets[Self::slot(day)])
+            .fold(0u64, u64::saturating_add)
+    }
+
+    /// Recompute `projected_revenue` at `now`
+    pub fn refresh(&mut self, now: i64) {
+        self.roll(now);
+        self.projected_revenue = self.forecast(now);
+        self.projected_at = now;
+    }
+
+    /// Move a subscription's entry from `before` to `after`
+    ///
+    /// Entries are `(amount, next_payment)` from `Subscription::forecast_entry`.
+    /// Payments already overdue or beyond the ring are left out.
+    pub fn reschedule(&mut self, before: Option<(u64, i64)>, after: Option<(u64, i64)>, now: i64) -> Result<()> {
+        self.roll(now);
+        if before != after {
+            if let Some(slot) = before.and_then(|(_, due_at)| self.tracked_slot(due_at)) {
+                self.buckets[slot] = self.buckets[slot].saturating_sub(before.map_or(0, |(amount, _)| amount));
+            }
+            if let Some((amount, due_at)) = after {
+                if let Some(slot) = self.tracked_slot(due_at) {
+                    self.buckets[slot] = self.buckets[slot].checked_add(amount).ok_or(ErrorCode::Overflow)?;
+                }
+            }
+        }
+        self.projected_revenue = self.forecast(now);
+        self.projected_at = now;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = SECONDS_PER_DAY;
+
+    fn forecast() -> RevenueForecast {
+        RevenueForecast {
+            merchant: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            projected_revenue: 0,
+            projected_at: 0,
+            day: 0,
+            buckets: vec![0; FORECAST_RING_DAYS],
+            bump: 255,
+            reserved: [0; 32],
+        }
+    }
+
+    #[test]
+    fn test_forecast_counts_the_next_thirty_days() {
+        let mut forecast = forecast();
+        let now = 100 * DAY;
+        forecast.reschedule(None, Some((1_000, now + 5 * DAY)), now).unwrap();
+        forecast.reschedule(None, Some((500, now + 40 * DAY)), now).unwrap();
+        assert_eq!(forecast.projected_revenue, 1_000);
+
+        // Eleven days on the later payment is in the window, the earlier overdue
+        assert_eq!(forecast.forecast(now + 11 * DAY), 500);
+
+        // Paying moves the entry a cycle forward
+        forecast
+            .reschedule(Some((1_000, now + 5 * DAY)), Some((1_000, now + 35 * DAY)), now + 5 * DAY)
+            .unwrap();
+        assert_eq!(forecast.projected_revenue, 0);
+        forecast.refresh(now + 11 * DAY);
+        assert_eq!(forecast.projected_revenue, 1_500);
+
+        // Cancelling removes it
+        forecast.reschedule(Some((500, now + 40 * DAY)), None, now + 11 * DAY).unwrap();
+        assert_eq!(forecast.projected_revenue, 1_000);
+    }
+
+    #[test]
+    fn test_past_days_are_cleared_on_reuse() {
+        let mut forecast = forecast();
+        forecast.reschedule(None, Some((1_000, 100 * DAY)), 100 * DAY).unwrap();
+
+        // Day 500 reuses day 100's bucket once the ring has moved past it
+        forecast.reschedule(None, Some((200, 500 * DAY)), 101 * DAY).unwrap();
+        forecast.refresh(490 * DAY);
+        assert_eq!(forecast.projected_revenue, 200);
+
+        // Overdue payments are not tracked
+        forecast.reschedule(None, Some((300, 480 * DAY)), 490 * DAY).unwrap();
+        assert_eq!(forecast.projected_revenue, 200);
+    }
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+/// Batched settlement for one merchant and mint
+///
+/// While enabled, executions route the merchant leg into `vault` (a token
+/// account owned by this PDA) instead of transferring to the merchant
+/// directly. A permissionless `sweep_settlement` later moves the whole vault
+/// balance to `destination` in a single transfer.
+///
+/// PDA: `[b"settlement", merchant, mint]`; vault: `[b"settlement_vault", settlement]`
+#[account]
+pub struct SettlementVault {
+    /// Merchant account (registry PDA)
+    pub merchant: Pubkey,               // 32
+
+    /// Mint settled through this vault
+    pub mint: Pubkey,                   // 32
+
+    /// Token account holding accrued merchant funds
+    pub vault: Pubkey,                  // 32
+
+    /// Merchant token account receiving sweeps
+    pub destination: Pubkey,            // 32
+
+    /// Whether executions should accrue into the vault
+    pub enabled: bool,                  // 1
+
+    /// Accrued since the last sweep
+    pub accrued: u64,                   // 8
+
+    /// Total moved to the merchant by sweeps
+    pub total_swept: u64,               // 8
+
+    /// Timestamp of the last sweep
+    pub last_sweep: i64,                // 8
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Vault token account bump
+    pub vault_bump: u8,                 // 1
+
+    /// Principal currently deposited in a lending protocol
+    pub deployed: u64,                  // 8
+
+    /// Yield credited to the merchant by lending withdrawals
+    pub yield_earned: u64,              // 8
+
+    /// Extra padding for future upgrades
+    pub reserved: [u8; 16],             // 16
+}
+
+impl SettlementVault {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // merchant
+        32 +                             // mint
+        32 +                             // vault
+        32 +                             // destination
+        1 +                              // enabled
+        8 +                              // accrued
+        8 +                              // total_swept
+        8 +                              // last_sweep
+        1 +                              // bump
+        1 +                              // vault_bump
+        8 +                              // deployed
+        8 +                              // yield_earned
+        16;                              // reserved
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+/// Nonce allocator for a user's subscriptions to one merchant
+///
+/// Created by the pair's first `create_subscription` (or by
+/// `open_subscription_counter` for pairs subscribed before it existed).
+/// Each new subscription takes `next_nonce` into its PDA seeds and bumps
+/// it, so a user may hold several subscriptions with one merchant and two
+/// signups never race for the same address. Nonces are not reused after a
+/// subscription closes.
+///
+/// PDA: `[b"subscription_counter", user, merchant]`
+#[account]
+pub struct SubscriptionCounter {
+    /// Subscribing wallet
+    pub user: Pubkey,                   // 32
+
+    /// Merchant PDA (lutrii-merchant-registry)
+    pub merchant: Pubkey,               // 32
+
+    /// Nonce the pair's next subscription is created with
+    pub next_nonce: u32,                // 4
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Extra padding for future upgrades
+    pub reserved: [u8; 32],             // 32
+}
+
+impl SubscriptionCounter {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // user
+        32 +                             // merchant
+        4 +                              // next_nonce
+        1 +                              // bump
+        32;                              // reserved
+}
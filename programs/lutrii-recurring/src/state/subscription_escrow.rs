@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+/// Prepaid funds for one subscription
+///
+/// `deposit_escrow` tops up `vault`, a token account owned by this PDA, in
+/// the subscription's mint, and marks the subscription escrow-funded.
+/// Executions and invoice collections must then pass the escrow and take
+/// the payment from the vault instead of the user's token account, so
+/// spending the wallet balance or clobbering the delegation cannot make a
+/// collection fail; an empty vault is recorded as a failed attempt like any
+/// other.
+/// The user can withdraw the balance at any time, including after the
+/// subscription is closed.
+///
+/// PDA: `[b"escrow", subscription]`; vault: `[b"escrow_vault", escrow]`
+#[account]
+pub struct SubscriptionEscrow {
+    /// Subscription the funds are prepaid for
+    pub subscription: Pubkey,           // 32
+
+    /// Subscription owner, the only one who may deposit or withdraw
+    pub user: Pubkey,                   // 32
+
+    /// Mint the subscription is paid in
+    pub mint: Pubkey,                   // 32
+
+    /// Token account holding the prepaid funds
+    pub vault: Pubkey,                  // 32
+
+    /// Total deposited by the user
+    pub deposited: u64,                 // 8
+
+    /// Total paid out to executions
+    pub spent: u64,                     // 8
+
+    /// Total withdrawn back by the user
+    pub withdrawn: u64,                 // 8
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Vault token account bump
+    pub vault_bump: u8,                 // 1
+
+    /// Extra padding for future upgrades
+    pub reserved: [u8; 32],             // 32
+}
+
+impl SubscriptionEscrow {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // subscription
+        32 +                             // user
+        32 +                             // mint
+        32 +                             // vault
+        8 +                              // deposited
+        8 +                              // spent
+        8 +                              // withdrawn
+        1 +                              // bump
+        1 +                              // vault_bump
+        32;                              // reserved
+}
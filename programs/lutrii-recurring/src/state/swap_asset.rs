@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+use lutrii_core::oracle::{self, OraclePrice};
+use crate::errors::ErrorCode;
+
+/// Slippage allowance cap for swap assets (10%)
+pub const MAX_SWAP_SLIPPAGE_BPS: u16 = 1_000;
+
+/// Oracle confidence interval cap for swap assets (5%)
+pub const MAX_SWAP_CONFIDENCE_BPS: u16 = 500;
+
+/// Confidence bound for assets configured before it was settable (2%)
+pub const DEFAULT_SWAP_CONFIDENCE_BPS: u16 = 200;
+
+/// Token users may pay with while merchants settle in a stablecoin
+///
+/// Executions sell the asset (e.g. mSOL, jitoSOL) for the merchant's
+/// settlement mint through Jupiter. `price_feed` bounds how much of the
+/// asset a payment may spend: the oracle conversion of the charge plus
+/// `max_slippage_bps`. Prices whose confidence interval exceeds
+/// `max_confidence_bps` are refused rather than trusted.
+///
+/// PDA: `[b"swap_asset", mint]`
+#[account]
+pub struct SwapAsset {
+    /// Token users pay with
+    pub mint: Pubkey,                   // 32
+
+    /// Pyth price account for `mint` in USD
+    pub price_feed: Pubkey,             // 32
+
+    /// Slippage allowed over the oracle price
+    pub max_slippage_bps: u16,          // 2
+
+    /// Oldest oracle price accepted, in seconds
+    pub max_price_age: i64,             // 8
+
+    /// Whether new subscriptions and executions may use the asset
+    pub enabled: bool,                  // 1
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Widest oracle confidence interval accepted, as bps of the price
+    /// (0 = `DEFAULT_SWAP_CONFIDENCE_BPS`)
+    pub max_confidence_bps: u16,        // 2
+
+    /// Extra padding for future upgrades
+    pub reserved: [u8; 30],             // 30
+}
+
+impl SwapAsset {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // mint
+        32 +                             // price_feed
+        2 +                              // max_slippage_bps
+        8 +                              // max_price_age
+        1 +                              // enabled
+        1 +                              // bump
+        2 +                              // max_confidence_bps
+        30;                              // reserved
+
+    /// Read the oracle price, rejecting the wrong feed, a stale price or
+    /// one too uncertain to bound a swap
+    pub fn price(&self, price_feed: &AccountInfo, now: i64) -> Result<OraclePrice> {
+        require_keys_eq!(price_feed.key(), self.price_feed, ErrorCode::InvalidPriceFeed);
+        let price = oracle::parse_pyth_price(&price_feed.try_borrow_data()?)
+            .map_err(ErrorCode::from)?;
+        require!(
+            now.saturating_sub(price.publish_time) <= self.max_price_age,
+            ErrorCode::StaleOraclePrice
+        );
+        require!(
+            price.confidence_within(self.confidence_bound()),
+            ErrorCode::OraclePriceUncertain
+        );
+        Ok(price)
+    }
+
+    /// Widest confidence interval accepted, in bps of the price
+    pub fn confidence_bound(&self) -> u16 {
+        match self.max_confidence_bps {
+            0 => DEFAULT_SWAP_CONFIDENCE_BPS,
+            bps => bps,
+        }
+    }
+
+    /// Most of the asset that may be sold for `amount_out` of the settlement mint
+    ///
+    /// `max_slippage_bps` is the allowance over the oracle price, at most
+    /// the asset's own (see `Subscription::swap_slippage_bps`).
+    pub fn max_input(
+        &self,
+        price: &OraclePrice,
+        amount_out: u64,
+        input_decimals: u8,
+        output_decimals: u8,
+        max_slippage_bps: u16,
+    ) -> Result<u64> {
+        Ok(oracle::max_input_for_output(
+            amount_out,
+            price,
+            input_decimals,
+            output_decimals,
+            max_slippage_bps.min(self.max_slippage_bps),
+        )
+        .map_err(ErrorCode::from)?)
+    }
+
+    /// Least settlement mint output acceptable for selling `amount_in` of the asset
+    ///
+    /// Derived on-chain from the oracle, so a route cannot be sandwiched
+    /// into spending more than it returns.
+    pub fn min_output(
+        &self,
+        price: &OraclePrice,
+        amount_in: u64,
+        input_decimals: u8,
+        output_decimals: u8,
+        max_slippage_bps: u16,
+    ) -> Result<u64> {
+        Ok(oracle::min_output_for_input(
+            amount_in,
+            price,
+            input_decimals,
+            output_decimals,
+            max_slippage_bps.min(self.max_slippage_bps),
+        )
+        .map_err(ErrorCode::from)?)
+    }
+}
@@ -0,0 +1,163 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{token, token_2022};
+use crate::errors::ErrorCode;
+
+/// Swap programs executions may route through
+pub const MAX_SWAP_PROGRAMS: usize = 4;
+
+/// Mints a swap route may not pass through
+pub const MAX_BLOCKED_MINTS: usize = 8;
+
+/// Size of an SPL token account; Token-2022 accounts with extensions are longer
+const TOKEN_ACCOUNT_LEN: usize = 165;
+
+/// Platform-wide swap routing rules (`[b"swap_config"]`)
+///
+/// `execute_payment_with_swap` only invokes allowlisted swap programs and
+/// inspects the route's accounts before forwarding it: no blocked mint may
+/// appear, either directly or as the mint of an intermediate token account,
+/// and the only account the user delegate owns must be the swap output.
+#[account]
+pub struct SwapConfig {
+    /// Allowlisted swap programs; only the first `swap_program_count` are used
+    pub swap_programs: [Pubkey; MAX_SWAP_PROGRAMS], // 128
+
+    /// Number of allowlisted programs
+    pub swap_program_count: u8,         // 1
+
+    /// Disallowed intermediate mints; only the first `blocked_mint_count` are used
+    pub blocked_mints: [Pubkey; MAX_BLOCKED_MINTS], // 256
+
+    /// Number of blocked mints
+    pub blocked_mint_count: u8,         // 1
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Extra padding for future upgrades
+    pub reserved: [u8; 32],             // 32
+}
+
+impl SwapConfig {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 * MAX_SWAP_PROGRAMS +         // swap_programs
+        1 +                              // swap_program_count
+        32 * MAX_BLOCKED_MINTS +         // blocked_mints
+        1 +                              // blocked_mint_count
+        1 +                              // bump
+        32;                              // reserved
+
+    pub fn is_allowed(&self, program: &Pubkey) -> bool {
+        self.swap_programs[..self.swap_program_count as usize].contains(program)
+    }
+
+    pub fn is_blocked(&self, mint: &Pubkey) -> bool {
+        self.blocked_mints[..self.blocked_mint_count as usize].contains(mint)
+    }
+
+    /// Validate a route's accounts before forwarding it
+    ///
+    /// The route must spend from `source` into `destination`, must not touch
+    /// a blocked mint, and may not include other token accounts owned by
+    /// `authority` (the user delegate signing the swap).
+    pub fn check_route(
+        &self,
+        accounts: &[AccountInfo],
+        source: &Pubkey,
+        destination: &Pubkey,
+        authority: &Pubkey,
+    ) -> Result<()> {
+        let keys = || accounts.iter().map(|account| account.key);
+        require!(
+            keys().any(|key| key == source) && keys().any(|key| key == destination),
+            ErrorCode::InvalidSwapRoute
+        );
+
+        for account in accounts {
+            require!(!self.is_blocked(account.key), ErrorCode::DisallowedRouteMint);
+            if let Some((mint, owner)) = token_account_mint_and_owner(account)? {
+                require!(!self.is_blocked(&mint), ErrorCode::DisallowedRouteMint);
+                require!(
+                    owner != *authority || account.key == destination,
+                    ErrorCode::InvalidSwapRoute
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Mint and owner of `account` when it is an SPL Token or Token-2022 token account
+fn token_account_mint_and_owner(account: &AccountInfo) -> Result<Option<(Pubkey, Pubkey)>> {
+    if *account.owner != token::ID && *account.owner != token_2022::ID {
+        return Ok(None);
+    }
+    let data = account.try_borrow_data()?;
+    // Token-2022 tags extended accounts with their type right after the base layout
+    let is_token_account = data.len() == TOKEN_ACCOUNT_LEN
+        || (data.len() > TOKEN_ACCOUNT_LEN && data[TOKEN_ACCOUNT_LEN] == 2);
+    if !is_token_account {
+        return Ok(None);
+    }
+    let mint = Pubkey::try_from(&data[..32]).map_err(|_| ErrorCode::InvalidAccountData)?;
+    let owner = Pubkey::try_from(&data[32..64]).map_err(|_| ErrorCode::InvalidAccountData)?;
+    Ok(Some((mint, owner)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token_account(mint: &Pubkey, owner: &Pubkey) -> Vec<u8> {
+        let mut data = vec![0u8; TOKEN_ACCOUNT_LEN];
+        data[..32].copy_from_slice(mint.as_ref());
+        data[32..64].copy_from_slice(owner.as_ref());
+        data
+    }
+
+    #[test]
+    fn test_route_rejects_blocked_mints_and_foreign_delegate_accounts() {
+        let (usdc, blocked, delegate) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+        let mut config = SwapConfig {
+            swap_programs: [Pubkey::default(); MAX_SWAP_PROGRAMS],
+            swap_program_count: 1,
+            blocked_mints: [Pubkey::default(); MAX_BLOCKED_MINTS],
+            blocked_mint_count: 1,
+            bump: 255,
+            reserved: [0; 32],
+        };
+        config.swap_programs[0] = Pubkey::new_unique();
+        config.blocked_mints[0] = blocked;
+        assert!(config.is_allowed(&config.swap_programs[0]));
+        assert!(!config.is_allowed(&Pubkey::default()));
+
+        let keys: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let (source, destination, hop, other) = (keys[0], keys[1], keys[2], keys[3]);
+        let mut lamports = [0u64; 4];
+        let mut data = [
+            token_account(&usdc, &Pubkey::new_unique()),
+            token_account(&usdc, &delegate),
+            token_account(&usdc, &Pubkey::new_unique()),
+            token_account(&usdc, &delegate),
+        ];
+        let [l0, l1, l2, l3] = &mut lamports;
+        let [d0, d1, d2, d3] = &mut data;
+        let mut infos = vec![
+            AccountInfo::new(&source, false, true, l0, d0, &token::ID, false, 0),
+            AccountInfo::new(&destination, false, true, l1, d1, &token::ID, false, 0),
+            AccountInfo::new(&hop, false, true, l2, d2, &token::ID, false, 0),
+        ];
+        assert!(config.check_route(&infos, &source, &destination, &delegate).is_ok());
+        assert!(config.check_route(&infos[..2], &source, &hop, &delegate).is_err());
+
+        // An intermediate hop through a blocked mint
+        infos[2].try_borrow_mut_data().unwrap()[..32].copy_from_slice(blocked.as_ref());
+        assert!(config.check_route(&infos, &source, &destination, &delegate).is_err());
+        infos[2].try_borrow_mut_data().unwrap()[..32].copy_from_slice(usdc.as_ref());
+
+        // Another account owned by the delegate
+        infos.push(AccountInfo::new(&other, false, true, l3, d3, &token::ID, false, 0));
+        assert!(config.check_route(&infos, &source, &destination, &delegate).is_err());
+    }
+}
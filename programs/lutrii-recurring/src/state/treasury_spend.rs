@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+
+/// Spend log of one governance proposal against the fee treasuries
+///
+/// Created by the proposal's first `withdraw_treasury` or
+/// `treasury_buyback`; every spend it executes is added here and held to
+/// `GovernanceConfig.max_spend_per_proposal` in total.
+///
+/// PDA: `[b"treasury_spend", proposal]`
+#[account]
+pub struct TreasurySpend {
+    /// SPL Governance proposal that authorized the spending
+    pub proposal: Pubkey,               // 32
+
+    /// Withdrawn to recipients
+    pub withdrawn: u64,                 // 8
+
+    /// Spent on buybacks
+    pub bought_back: u64,               // 8
+
+    /// Tokens the buybacks returned
+    pub buyback_received: u64,          // 8
+
+    /// Number of spends executed
+    pub spend_count: u16,               // 2
+
+    /// Timestamp of the latest spend
+    pub last_spend_at: i64,             // 8
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Extra padding for future upgrades
+    pub reserved: [u8; 32],             // 32
+}
+
+impl TreasurySpend {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // proposal
+        8 +                              // withdrawn
+        8 +                              // bought_back
+        8 +                              // buyback_received
+        2 +                              // spend_count
+        8 +                              // last_spend_at
+        1 +                              // bump
+        32;                              // reserved
+
+    /// Everything the proposal has spent
+    pub fn total_spent(&self) -> Result<u64> {
+        self.withdrawn
+            .checked_add(self.bought_back)
+            .ok_or_else(|| error!(ErrorCode::Overflow))
+    }
+
+    /// Count a spend of `amount`, keeping the proposal within `cap`
+    pub fn record_spend(&mut self, amount: u64, cap: u64, now: i64) -> Result<()> {
+        let total = self.total_spent()?.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        require!(total <= cap, ErrorCode::ProposalSpendCapExceeded);
+        self.spend_count = self.spend_count.checked_add(1).ok_or(ErrorCode::Overflow)?;
+        self.last_spend_at = now;
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+
+/// Usage a merchant may bill on one subscription per billing period
+///
+/// Set up by the user with `configure_usage_cap`; `execute_metered_payment`
+/// then lets the merchant charge actual usage, each charge within the
+/// subscription's `max_per_transaction` and all charges in a period within
+/// `period_cap`. Periods are `frequency_seconds` long on the subscription's
+/// schedule clock, counted from when the meter was created.
+///
+/// PDA: `[b"usage_meter", subscription]`
+#[account]
+pub struct UsageMeter {
+    /// Subscription the usage is billed against
+    pub subscription: Pubkey,           // 32
+
+    /// Most the merchant may bill per period (0 = metered billing off)
+    pub period_cap: u64,                // 8
+
+    /// Start of the current period
+    pub period_start: i64,              // 8
+
+    /// Billed in the current period
+    pub period_usage: u64,              // 8
+
+    /// Billed over the meter's lifetime
+    pub total_usage: u64,               // 8
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Extra padding for future upgrades
+    pub reserved: [u8; 32],             // 32
+}
+
+impl UsageMeter {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // subscription
+        8 +                              // period_cap
+        8 +                              // period_start
+        8 +                              // period_usage
+        8 +                              // total_usage
+        1 +                              // bump
+        32;                              // reserved
+
+    /// Start a new period once `now` is past the current one
+    fn roll(&mut self, period: i64, now: i64) {
+        if period > 0 && now >= self.period_start.saturating_add(period) {
+            let elapsed = (now - self.period_start) / period;
+            self.period_start = self.period_start.saturating_add(elapsed.saturating_mul(period));
+            self.period_usage = 0;
+        }
+    }
+
+    /// Bill `amount` of usage at `now`, keeping the period within its cap
+    pub fn record(&mut self, amount: u64, period: i64, now: i64) -> Result<()> {
+        self.roll(period, now);
+        let usage = self.period_usage.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        require!(usage <= self.period_cap, ErrorCode::UsageCapExceeded);
+        self.period_usage = usage;
+        self.total_usage = self.total_usage.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86_400;
+
+    fn meter(period_cap: u64) -> UsageMeter {
+        UsageMeter {
+            subscription: Pubkey::new_unique(),
+            period_cap,
+            period_start: 0,
+            period_usage: 0,
+            total_usage: 0,
+            bump: 255,
+            reserved: [0; 32],
+        }
+    }
+
+    #[test]
+    fn test_usage_meter_len() {
+        assert_eq!(UsageMeter::LEN, 8 + 32 + 8 + 8 + 8 + 8 + 1 + 32);
+    }
+
+    #[test]
+    fn test_usage_capped_per_period() {
+        let mut meter = meter(1_000);
+        meter.record(600, 30 * DAY, DAY).unwrap();
+        meter.record(400, 30 * DAY, 2 * DAY).unwrap();
+        assert!(meter.record(1, 30 * DAY, 3 * DAY).is_err());
+        assert_eq!(meter.period_usage, 1_000);
+
+        // The next period starts on the period boundary with a fresh cap
+        meter.record(700, 30 * DAY, 65 * DAY).unwrap();
+        assert_eq!(meter.period_start, 60 * DAY);
+        assert_eq!(meter.period_usage, 700);
+        assert_eq!(meter.total_usage, 1_700);
+    }
+
+    #[test]
+    fn test_zero_cap_bills_nothing() {
+        let mut meter = meter(0);
+        assert!(meter.record(1, DAY, 0).is_err());
+        assert_eq!(meter.total_usage, 0);
+    }
+}
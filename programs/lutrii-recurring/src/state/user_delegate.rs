@@ -0,0 +1,262 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token_2022::{approve, revoke, Approve, Revoke};
+use anchor_spl::token_interface::TokenAccount;
+use lutrii_common::Subscription;
+use lutrii_core::limits;
+use crate::errors::ErrorCode;
+
+/// Shared delegate for every subscription drawing from one token account
+///
+/// SPL token accounts hold a single delegate, so per-subscription delegates
+/// overwrite each other. Instead one PDA per user token account
+/// (`[b"user_delegate", user_token_account]`) is approved for the sum of its
+/// subscriptions' remaining lifetime allowances; each subscription's own
+/// `lifetime_cap - total_paid` bounds what it may draw.
+///
+/// Allowances are denominated in the merchants' settlement mint. When the
+/// token account pays through swaps, `approval_rate` converts them into the
+/// token account's own units.
+#[account]
+pub struct UserDelegate {
+    /// Token account owner
+    pub user: Pubkey,                   // 32
+
+    /// Token account this PDA is delegate of
+    pub token_account: Pubkey,          // 32
+
+    /// Sum of remaining lifetime allowances of active subscriptions
+    pub outstanding_allowance: u64,     // 8
+
+    /// Active subscriptions drawing from the token account
+    pub active_subscriptions: u32,      // 4
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Token account units approved per `RATE_SCALE` units of allowance;
+    /// 0 when subscriptions settle in the token account's own mint
+    pub approval_rate: u64,             // 8
+
+    /// Extra padding for future upgrades
+    pub reserved: [u8; 24],             // 24
+}
+
+impl UserDelegate {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // user
+        32 +                             // token_account
+        8 +                              // outstanding_allowance
+        4 +                              // active_subscriptions
+        1 +                              // bump
+        8 +                              // approval_rate
+        24;                              // reserved
+
+    /// Fixed-point scale of `approval_rate`
+    pub const RATE_SCALE: u64 = 1_000_000_000;
+
+    /// Add a subscription's allowance to the shared approval
+    pub fn reserve(&mut self, allowance: u64) -> Result<()> {
+        self.outstanding_allowance = self
+            .outstanding_allowance
+            .checked_add(allowance)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    /// Remove allowance that was spent or given back
+    pub fn release(&mut self, allowance: u64) {
+        self.outstanding_allowance = self.outstanding_allowance.saturating_sub(allowance);
+    }
+
+    /// Drop a cancelled subscription and its remaining allowance
+    ///
+    /// Trials awaiting `convert_trial` never reserved anything and are skipped.
+    pub fn remove_subscription(&mut self, subscription: &Subscription) {
+        if subscription.delegation_deferred {
+            return;
+        }
+        self.release(limits::remaining_allowance(
+            subscription.lifetime_cap,
+            subscription.total_paid,
+        ));
+        self.active_subscriptions = self.active_subscriptions.saturating_sub(1);
+    }
+
+    /// Token account units to approve for `outstanding_allowance`
+    pub fn approval_amount(&self) -> Result<u64> {
+        self.to_token_units(self.outstanding_allowance)
+    }
+
+    /// Convert an allowance amount into token account units at `approval_rate`
+    pub fn to_token_units(&self, allowance: u64) -> Result<u64> {
+        if self.approval_rate == 0 {
+            return Ok(allowance);
+        }
+        let amount = (allowance as u128)
+            .checked_mul(self.approval_rate as u128)
+            .ok_or(ErrorCode::Overflow)?
+            .div_ceil(Self::RATE_SCALE as u128);
+        Ok(u64::try_from(amount).unwrap_or(u64::MAX))
+    }
+
+    /// Set the token account approval to `outstanding_allowance`
+    ///
+    /// Re-approving the full outstanding amount also repairs a delegation
+    /// clobbered by another dApp. Revokes once nothing is outstanding.
+    pub fn sync_approval<'info>(
+        &self,
+        delegate: AccountInfo<'info>,
+        token_account: AccountInfo<'info>,
+        owner: AccountInfo<'info>,
+        token_program: AccountInfo<'info>,
+    ) -> Result<()> {
+        if self.outstanding_allowance == 0 {
+            return revoke(CpiContext::new(
+                token_program,
+                Revoke {
+                    source: token_account,
+                    authority: owner,
+                },
+            ));
+        }
+
+        approve(
+            CpiContext::new(
+                token_program,
+                Approve {
+                    to: token_account,
+                    delegate,
+                    authority: owner,
+                },
+            ),
+            self.approval_amount()?,
+        )
+    }
+}
+
+/// Check `delegate` can move `amount` out of `token_account`
+///
+/// Run before delegated token CPIs so callers can branch on the specific
+/// cause instead of an opaque token program error.
+pub fn check_spendable(
+    token_account: &TokenAccount,
+    delegate: &Pubkey,
+    amount: u64,
+) -> std::result::Result<(), ErrorCode> {
+    if token_account.is_frozen() {
+        return Err(ErrorCode::TokenAccountFrozen);
+    }
+    if token_account.delegate != COption::Some(*delegate) {
+        return Err(ErrorCode::DelegateMismatch);
+    }
+    if token_account.delegated_amount < amount {
+        return Err(ErrorCode::DelegatedAmountInsufficient);
+    }
+    if token_account.amount < amount {
+        return Err(ErrorCode::UserBalanceInsufficient);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_delegate_len() {
+        assert_eq!(UserDelegate::LEN, 8 + 32 + 32 + 8 + 4 + 1 + 8 + 24);
+    }
+
+    #[test]
+    fn test_reserve_and_release() {
+        let mut delegate = UserDelegate {
+            user: Pubkey::new_unique(),
+            token_account: Pubkey::new_unique(),
+            outstanding_allowance: 0,
+            active_subscriptions: 0,
+            bump: 255,
+            approval_rate: 0,
+            reserved: [0; 24],
+        };
+
+        delegate.reserve(100).unwrap();
+        delegate.reserve(50).unwrap();
+        assert_eq!(delegate.outstanding_allowance, 150);
+
+        delegate.release(40);
+        assert_eq!(delegate.outstanding_allowance, 110);
+
+        // Spending never underflows the pool
+        delegate.release(500);
+        assert_eq!(delegate.outstanding_allowance, 0);
+
+        delegate.outstanding_allowance = u64::MAX;
+        assert!(delegate.reserve(1).is_err());
+    }
+
+    #[test]
+    fn test_swap_approval_amount() {
+        let mut delegate = UserDelegate {
+            user: Pubkey::new_unique(),
+            token_account: Pubkey::new_unique(),
+            outstanding_allowance: 150_000_000,
+            active_subscriptions: 1,
+            bump: 255,
+            approval_rate: 0,
+            reserved: [0; 24],
+        };
+        assert_eq!(delegate.approval_amount().unwrap(), 150_000_000);
+
+        // 150 USDC of allowance paid in an LST worth $150 (9 decimals) plus 1%
+        delegate.approval_rate = 6_733_333_334;
+        assert_eq!(delegate.approval_amount().unwrap(), 1_010_000_001);
+    }
+
+    #[test]
+    fn test_check_spendable_reports_cause() {
+        use anchor_lang::solana_program::program_pack::Pack;
+        use anchor_spl::token_2022::spl_token_2022::state::{Account, AccountState};
+
+        let delegate = Pubkey::new_unique();
+        let account = |state, delegate, delegated_amount, amount| {
+            let mut data = vec![0u8; Account::LEN];
+            Account::pack(
+                Account {
+                    mint: Pubkey::new_unique(),
+                    owner: Pubkey::new_unique(),
+                    amount,
+                    delegate,
+                    state,
+                    delegated_amount,
+                    ..Default::default()
+                },
+                &mut data,
+            )
+            .unwrap();
+            TokenAccount::try_deserialize_unchecked(&mut data.as_slice()).unwrap()
+        };
+        let ok = AccountState::Initialized;
+
+        assert!(
+            check_spendable(&account(ok, COption::Some(delegate), 100, 100), &delegate, 100).is_ok()
+        );
+        assert!(matches!(
+            check_spendable(&account(AccountState::Frozen, COption::Some(delegate), 100, 100), &delegate, 100),
+            Err(ErrorCode::TokenAccountFrozen)
+        ));
+        assert!(matches!(
+            check_spendable(&account(ok, COption::Some(Pubkey::new_unique()), 100, 100), &delegate, 100),
+            Err(ErrorCode::DelegateMismatch)
+        ));
+        assert!(matches!(
+            check_spendable(&account(ok, COption::Some(delegate), 99, 100), &delegate, 100),
+            Err(ErrorCode::DelegatedAmountInsufficient)
+        ));
+        assert!(matches!(
+            check_spendable(&account(ok, COption::Some(delegate), 100, 99), &delegate, 100),
+            Err(ErrorCode::UserBalanceInsufficient)
+        ));
+    }
+}
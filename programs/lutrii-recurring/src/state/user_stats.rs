@@ -0,0 +1,283 @@
+use anchor_lang::prelude::*;
+use lutrii_core::SECONDS_PER_DAY;
+use crate::errors::ErrorCode;
+
+/// Days tracked by the rolling spend window
+pub const SPEND_WINDOW_DAYS: usize = 30;
+
+/// Length of a budgeting month
+pub const SECONDS_PER_MONTH: i64 = SPEND_WINDOW_DAYS as i64 * SECONDS_PER_DAY;
+
+/// Optional per-user spending analytics (`[b"user_stats", user]`)
+///
+/// Created by the user so budgeting UIs can read spend straight from chain
+/// state. Payment executions always pass it, so neither the spend totals
+/// nor the fee waiver depend on the executor; create_subscription and
+/// cancel_subscription take it when it exists. Months are fixed 30-day periods starting at
+/// account creation. The first subscription it records is the one the
+/// platform's first-subscription fee waiver applies to.
+#[account]
+pub struct UserStats {
+    /// Wallet the statistics belong to
+    pub user: Pubkey,                   // 32
+
+    /// Active subscriptions across all merchants
+    pub active_subscriptions: u32,      // 4
+
+    /// Payments recorded since creation
+    pub payment_count: u64,             // 8
+
+    /// Total spent since creation
+    pub lifetime_spend: u64,            // 8
+
+    /// Start of the current month
+    pub month_start: i64,               // 8
+
+    /// Spent in the current month
+    pub month_spend: u64,               // 8
+
+    /// Spent in the previous month
+    pub previous_month_spend: u64,      // 8
+
+    /// Daily spend ring buffer indexed by `day % SPEND_WINDOW_DAYS`
+    pub daily_spend: [u64; SPEND_WINDOW_DAYS], // 240
+
+    /// Day number (unix days) of the newest ring buffer entry
+    pub last_spend_day: i64,            // 8
+
+    /// Creation timestamp
+    pub created_at: i64,                // 8
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Subscription eligible for the first-subscription fee waiver
+    pub first_subscription: Pubkey,     // 32
+}
+
+impl UserStats {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 +                             // user
+        4 +                              // active_subscriptions
+        8 +                              // payment_count
+        8 +                              // lifetime_spend
+        8 +                              // month_start
+        8 +                              // month_spend
+        8 +                              // previous_month_spend
+        8 * SPEND_WINDOW_DAYS +          // daily_spend
+        8 +                              // last_spend_day
+        8 +                              // created_at
+        1 +                              // bump
+        32;                              // first_subscription
+
+    /// Record a collected payment of `amount` at `now`
+    pub fn record_payment(&mut self, amount: u64, now: i64) -> Result<()> {
+        self.roll_month(now);
+        self.roll_days(now);
+
+        let slot = &mut self.daily_spend[Self::slot(now)];
+        *slot = slot.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+        self.month_spend = self
+            .month_spend
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        self.lifetime_spend = self
+            .lifetime_spend
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        self.payment_count = self
+            .payment_count
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    /// Spend over the 30 days ending at `now`
+    pub fn last_30_days_spend(&self, now: i64) -> u64 {
+        let today = now.div_euclid(SECONDS_PER_DAY);
+        let oldest = today - SPEND_WINDOW_DAYS as i64 + 1;
+        let newest = today.min(self.last_spend_day);
+        let first = oldest.max(self.last_spend_day - SPEND_WINDOW_DAYS as i64 + 1);
+
+        (first..=newest)
+            .map(|day| self.daily_spend[day.rem_euclid(SPEND_WINDOW_DAYS as i64) as usize])
+            .fold(0u64, u64::saturating_add)
+    }
+
+    /// Record `subscription` as the user's first if they never had one
+    ///
+    /// Call before `subscription_added`; returns whether it was recorded.
+    pub fn claim_first_subscription(&mut self, subscription: Pubkey) -> bool {
+        let first = self.first_subscription == Pubkey::default()
+            && self.active_subscriptions == 0
+            && self.payment_count == 0;
+        if first {
+            self.first_subscription = subscription;
+        }
+        first
+    }
+
+    /// Whether payment number `payment_count` (zero-based) of `subscription`
+    /// is among the `free_payments` of the first-subscription waiver
+    pub fn first_subscription_fee_waived(
+        &self,
+        subscription: &Pubkey,
+        payment_count: u32,
+        free_payments: u8,
+    ) -> bool {
+        *subscription == self.first_subscription && payment_count < u32::from(free_payments)
+    }
+
+    /// Whether the waiver in `info`, the user's stats PDA, covers payment
+    /// number `payment_count` of `subscription`
+    ///
+    /// Users who never opened their stats never claimed the waiver.
+    pub fn first_subscription_fee_waived_in(
+        info: &AccountInfo,
+        subscription: &Pubkey,
+        payment_count: u32,
+        free_payments: u8,
+    ) -> Result<bool> {
+        if info.data_is_empty() {
+            return Ok(false);
+        }
+        let stats = Self::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        Ok(stats.first_subscription_fee_waived(subscription, payment_count, free_payments))
+    }
+
+    /// Apply `update` to `info`, the user's stats PDA
+    ///
+    /// Spend is only tracked once the user opens their stats, so a missing
+    /// account is a no-op.
+    pub fn update_in(info: &AccountInfo, update: impl FnOnce(&mut Self) -> Result<()>) -> Result<()> {
+        if info.data_is_empty() {
+            return Ok(());
+        }
+        let mut stats = Self::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        update(&mut stats)?;
+        stats.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+    }
+
+    pub fn subscription_added(&mut self) -> Result<()> {
+        self.active_subscriptions = self
+            .active_subscriptions
+            .checked_add(1)
+            .ok_or(ErrorCode::Overflow)?;
+        Ok(())
+    }
+
+    pub fn subscription_removed(&mut self) {
+        self.active_subscriptions = self.active_subscriptions.saturating_sub(1);
+    }
+
+    /// Advance `month_start` to the month containing `now`
+    fn roll_month(&mut self, now: i64) {
+        if now < self.month_start + SECONDS_PER_MONTH {
+            return;
+        }
+        let months = (now - self.month_start) / SECONDS_PER_MONTH;
+        self.previous_month_spend = if months == 1 { self.month_spend } else { 0 };
+        self.month_spend = 0;
+        self.month_start += months * SECONDS_PER_MONTH;
+    }
+
+    /// Clear ring buffer entries for days that passed since the last payment
+    fn roll_days(&mut self, now: i64) {
+        let today = now.div_euclid(SECONDS_PER_DAY);
+        if today <= self.last_spend_day {
+            return;
+        }
+        let stale = (today - self.last_spend_day).min(SPEND_WINDOW_DAYS as i64);
+        for day in (today - stale + 1)..=today {
+            self.daily_spend[day.rem_euclid(SPEND_WINDOW_DAYS as i64) as usize] = 0;
+        }
+        self.last_spend_day = today;
+    }
+
+    fn slot(now: i64) -> usize {
+        now.div_euclid(SECONDS_PER_DAY)
+            .rem_euclid(SPEND_WINDOW_DAYS as i64) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = SECONDS_PER_DAY;
+
+    fn stats(now: i64) -> UserStats {
+        UserStats {
+            user: Pubkey::new_unique(),
+            active_subscriptions: 0,
+            payment_count: 0,
+            lifetime_spend: 0,
+            month_start: now,
+            month_spend: 0,
+            previous_month_spend: 0,
+            daily_spend: [0; SPEND_WINDOW_DAYS],
+            last_spend_day: now / DAY,
+            created_at: now,
+            bump: 255,
+            first_subscription: Pubkey::default(),
+        }
+    }
+
+    #[test]
+    fn test_user_stats_len() {
+        assert_eq!(UserStats::LEN, 8 + 32 + 4 + 8 * 5 + 240 + 8 + 8 + 1 + 32);
+    }
+
+    #[test]
+    fn test_rolling_window_drops_old_days() {
+        let start = 1_700_000_000;
+        let mut stats = stats(start);
+
+        stats.record_payment(10, start).unwrap();
+        stats.record_payment(5, start + 10 * DAY).unwrap();
+        assert_eq!(stats.last_30_days_spend(start + 10 * DAY), 15);
+
+        // The first payment leaves the window after 30 days
+        assert_eq!(stats.last_30_days_spend(start + 30 * DAY), 5);
+        stats.record_payment(7, start + 31 * DAY).unwrap();
+        assert_eq!(stats.last_30_days_spend(start + 31 * DAY), 12);
+        assert_eq!(stats.last_30_days_spend(start + 100 * DAY), 0);
+        assert_eq!(stats.lifetime_spend, 22);
+        assert_eq!(stats.payment_count, 3);
+    }
+
+    #[test]
+    fn test_month_rollover() {
+        let start = 1_700_000_000;
+        let mut stats = stats(start);
+
+        stats.record_payment(10, start + DAY).unwrap();
+        stats.record_payment(20, start + SECONDS_PER_MONTH).unwrap();
+        assert_eq!(stats.previous_month_spend, 10);
+        assert_eq!(stats.month_spend, 20);
+
+        // A skipped month leaves nothing to carry
+        stats.record_payment(5, start + 3 * SECONDS_PER_MONTH).unwrap();
+        assert_eq!(stats.previous_month_spend, 0);
+        assert_eq!(stats.month_spend, 5);
+        assert_eq!(stats.month_start, start + 3 * SECONDS_PER_MONTH);
+    }
+
+    #[test]
+    fn test_first_subscription_waiver() {
+        let mut stats = stats(1_700_000_000);
+        let first = Pubkey::new_unique();
+        let second = Pubkey::new_unique();
+
+        assert!(stats.claim_first_subscription(first));
+        stats.subscription_added().unwrap();
+        assert!(!stats.claim_first_subscription(second));
+
+        assert!(stats.first_subscription_fee_waived(&first, 0, 2));
+        assert!(stats.first_subscription_fee_waived(&first, 1, 2));
+        assert!(!stats.first_subscription_fee_waived(&first, 2, 2));
+        assert!(!stats.first_subscription_fee_waived(&first, 0, 0));
+        assert!(!stats.first_subscription_fee_waived(&second, 0, 2));
+    }
+}
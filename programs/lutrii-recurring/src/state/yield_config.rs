@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+
+/// Lending programs the platform may deposit held funds into
+pub const MAX_LENDING_PROGRAMS: usize = 4;
+
+/// Platform-wide yield settings for held balances (`[b"yield_config"]`)
+///
+/// Held balances (settlement vaults today) can be deployed into an
+/// allowlisted lending protocol and withdrawn before settlement. Yield on
+/// withdrawal is split: `merchant_yield_bps` stays with the merchant, the
+/// rest goes to the platform fee wallet.
+#[account]
+pub struct YieldConfig {
+    /// Allowlisted lending programs; only the first `lending_program_count` are used
+    pub lending_programs: [Pubkey; MAX_LENDING_PROGRAMS], // 128
+
+    /// Number of allowlisted programs
+    pub lending_program_count: u8,      // 1
+
+    /// Share of yield attributed to the merchant (basis points)
+    pub merchant_yield_bps: u16,        // 2
+
+    /// Whether new deposits are allowed (withdrawals always are)
+    pub enabled: bool,                  // 1
+
+    /// PDA bump
+    pub bump: u8,                       // 1
+
+    /// Extra padding for future upgrades
+    pub reserved: [u8; 32],             // 32
+}
+
+impl YieldConfig {
+    /// Total space required for account
+    pub const LEN: usize = 8 +          // discriminator
+        32 * MAX_LENDING_PROGRAMS +      // lending_programs
+        1 +                              // lending_program_count
+        2 +                              // merchant_yield_bps
+        1 +                              // enabled
+        1 +                              // bump
+        32;                              // reserved
+
+    pub fn is_allowed(&self, program: &Pubkey) -> bool {
+        self.lending_programs[..self.lending_program_count as usize].contains(program)
+    }
+
+    /// Split `yield_amount` into (merchant, platform) shares
+    pub fn split_yield(&self, yield_amount: u64) -> (u64, u64) {
+        let merchant = (yield_amount as u128 * self.merchant_yield_bps as u128 / 10_000) as u64;
+        (merchant, yield_amount - merchant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allowlist_and_split() {
+        let allowed = Pubkey::new_unique();
+        let mut lending_programs = [Pubkey::default(); MAX_LENDING_PROGRAMS];
+        lending_programs[0] = allowed;
+        let config = YieldConfig {
+            lending_programs,
+            lending_program_count: 1,
+            merchant_yield_bps: 8_000,
+            enabled: true,
+            bump: 255,
+            reserved: [0; 32],
+        };
+
+        assert!(config.is_allowed(&allowed));
+        assert!(!config.is_allowed(&Pubkey::default()));
+        assert_eq!(config.split_yield(1_001), (800, 201));
+    }
+}
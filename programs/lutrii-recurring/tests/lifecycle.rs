@@ -0,0 +1,5951 @@
+//! Lutrii Recurring - Subscription Lifecycle Integration Tests
+//!
+//! Runs both programs inside solana-program-test against the compiled SBF
+//! binaries. Build them first so the loader can find the .so files:
+//!
+//! ```bash
+//! anchor build
+//! SBF_OUT_DIR=$(pwd)/target/deploy cargo test -p lutrii-recurring --test lifecycle
+//! ```
+//!
+//! Covers:
+//! - create → multiple executions → pause/resume → cancel → close
+//! - cancel_and_close revokes and reclaims rent in one instruction
+//! - Admin force-cancel stops executions; only the admin may use it
+//! - emergency_revoke_all cancels and revokes a user's subscriptions at once
+//! - Due-date buckets follow the payment schedule
+//! - Merchant offers create subscriptions on exactly the offered terms
+//! - Published offers sell out after their inventory and close themselves
+//! - Trials approve nothing until convert_trial starts billing
+//! - A trial period on create_subscription defers the first payment only
+//! - charge_immediately collects the first payment at creation
+//! - Setup fees are collected once and count against the lifetime cap
+//! - Merchant billing anchors prorate the first period at signup
+//! - Billing days keep monthly payments on one calendar day
+//! - Slot schedules fall due by slot height, not wall-clock time
+//! - Subscriptions settle in the merchant's chosen settlement currency
+//! - Subscriptions are only created for listed, enabled mints, at or above
+//!   the mint's minimum amount
+//! - Platform fees only payable to the configured fee wallets
+//! - Fee-exempt subscriptions skip the platform fee
+//! - Scheduled fee holidays waive the fee and mark payments promotional
+//! - A user's first subscription pays no fee on its first payments
+//! - Merchants may charge the platform fee on top and net the full amount;
+//!   caps and the delegation cover the user's gross outflow
+//! - Negotiated per-merchant fee schedules override the platform's fee rate,
+//!   and executions cannot leave the merchant's policy out
+//! - Merchant reporting totals accrue a calendar year's volume and fees
+//! - Monthly merchant statements close their books once the month ends,
+//!   publishing the period's settlement summary
+//! - Settlement sweeps hold back a reserve sized by recent refundable payments
+//! - Revenue forecasts follow subscriptions' next payments over 30 days
+//! - verify_active_subscription answers whether a wallet is paid up
+//! - Governance mode hands the authority to a realm's treasury, leaving the
+//!   pause guardian able to pause without a vote
+//! - Pausing one token program leaves payments through the other running
+//! - Subscriptions on an older layout are refused until migrated
+//! - execute_payment stays within its compute unit budget
+//! - Compliance denylist blocks payments to denied wallets
+//! - Merchant blocklist refuses payments from blocked subscribers
+//! - Per-merchant active subscriber cap
+//! - Delegation health checks detect a revoked approval; refresh restores it
+//! - Execution claims reserve a due payment for the claiming keeper
+//! - A priority reserve holds daily volume back from standard merchants
+//! - Cycles left uncollected past the collection window are skipped
+//! - Missed-payment policies accumulate a bounded backlog or auto-cancel
+//! - Merchant retry policies set the backoff and pause or cancel after
+//!   repeated failed collections
+//! - Subscriptions on one token account share a single delegate
+//! - High-volume merchants claim a fee rebate once per finished period
+//! - Swap slippage caps are set by the subscriber or the merchant only
+//! - The swap config bounds its program allowlist and blocked mints
+//! - Snoozing defers a due payment within the merchant's limit
+//! - Plan changes keep payment history and prorate the paid cycle
+//! - Invoices paid directly or collected through a subscription delegation
+//! - Review eligibility enforced by lutrii-merchant-registry against live
+//!   lutrii-recurring subscription accounts, under admin-tuned thresholds
+//! - Merchant tags keep per-tag index PDAs in step on add and remove
+//! - Once an admin council exists, pauses and config changes need its
+//!   members' approvals
+//! - Scheduled config changes wait out the timelock before applying
+//! - Platform fee parameters change within bounds, behind the timelock
+//! - Every payment is recorded in the merchant's registry stats by CPI
+//! - migrate_token_account moves a subscription's delegation to a new
+//!   token account, revoking the old one
+//! - Prepaid escrow pays executions once the delegation is revoked, cannot
+//!   be left out once funded, and unspent funds can be withdrawn
+//! - Merchants bill metered usage within the user's per-period usage cap,
+//!   under the same fee exemptions and fee bearer as scheduled payments
+//! - Promo codes discount a new subscription's first payments
+
+use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorDeserialize, InstructionData, ToAccountMetas};
+use lutrii_core::{proration, schedule};
+use lutrii_merchant_registry::{Merchant, VerificationTier};
+use lutrii_recurring::{
+    CreateOptions, DueBucket, ErrorCode, MissedPaymentPolicy, OfferTerms, SubscriptionOffer, Invoice, InvoiceStatus, LineItem, MerchantPolicy, MerchantReport, MerchantStatement, RefundLiability, RevenueForecast, PlatformState, ScheduleUnit,
+    Subscription, SwapConfig, FeeHoliday, FeeBearer, FeeSchedule, SwapFallback, FeeShare, ForceCancelReason, RebateTier, SettlementVault, UserDelegate, UserStats,
+    AccessStatus, DelinquencyAction, RetryPolicy, SubscriptionCounter, SubscriptionEscrow, AdminAuditAction, AdminAuditLog, AdminCouncil, CouncilAction, CouncilProposal, ConfigChange, PendingConfigChange, GovernanceConfig, TreasurySpend, SubscriptionAccess, UsageMeter, MerchantVolume, Promo, PromoDiscount, EXECUTE_PAYMENT_COMPUTE_UNITS,
+};
+use solana_program_test::{BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction, system_program,
+    transaction::{Transaction, TransactionError},
+};
+use spl_token::state::{Account as TokenAccount, Mint};
+
+const DECIMALS: u8 = 6;
+const USDC: u64 = 1_000_000;
+const DAY: i64 = 86_400;
+const FEE_BASIS_POINTS: u16 = 250; // 2.5%
+const DAILY_VOLUME_LIMIT: u64 = 1_000_000 * USDC;
+
+// ============================================================================
+// Harness
+// ============================================================================
+
+struct Harness {
+    ctx: ProgramTestContext,
+    mint: Pubkey,
+    merchant_owner: Keypair,
+    merchant: Pubkey,
+    merchant_token_account: Pubkey,
+    platform_fee_account: Pubkey,
+}
+
+struct UserFixture {
+    keypair: Keypair,
+    token_account: Pubkey,
+    subscription: Pubkey,
+}
+
+impl Harness {
+    /// Boot both programs, a verified merchant and an initialized platform
+    async fn new() -> Self {
+        let mut program_test = ProgramTest::default();
+        program_test.prefer_bpf(true);
+        program_test.add_program("lutrii_recurring", lutrii_recurring::ID, None);
+        program_test.add_program(
+            "lutrii_merchant_registry",
+            lutrii_merchant_registry::ID,
+            None,
+        );
+
+        let ctx = program_test.start_with_context().await;
+        let mut harness = Self {
+            ctx,
+            mint: Pubkey::default(),
+            merchant_owner: Keypair::new(),
+            merchant: Pubkey::default(),
+            merchant_token_account: Pubkey::default(),
+            platform_fee_account: Pubkey::default(),
+        };
+
+        harness.mint = harness.create_mint().await;
+
+        // Registry + verified merchant (payer is the registry admin)
+        let admin = harness.ctx.payer.pubkey();
+        harness
+            .process(
+                ix(
+                    lutrii_merchant_registry::ID,
+                    lutrii_merchant_registry::accounts::InitializeRegistry {
+                        registry_state: registry_state(),
+                        authority: admin,
+                        system_program: system_program::ID,
+                    },
+                    lutrii_merchant_registry::instruction::InitializeRegistry {},
+                ),
+                &[],
+            )
+            .await
+            .unwrap();
+
+        let owner = harness.merchant_owner.insecure_clone();
+        harness.merchant = harness.register_merchant(&owner).await;
+
+        // Platform
+        harness
+            .process(
+                ix(
+                    lutrii_recurring::ID,
+                    lutrii_recurring::accounts::InitializePlatform {
+                        platform_state: platform_state(),
+                        authority: admin,
+                        system_program: system_program::ID,
+                    },
+                    lutrii_recurring::instruction::InitializePlatform {
+                        daily_volume_limit: DAILY_VOLUME_LIMIT,
+                        fee_basis_points: FEE_BASIS_POINTS,
+                    },
+                ),
+                &[],
+            )
+            .await
+            .unwrap();
+
+        harness.merchant_token_account = harness.create_token_account(&owner.pubkey()).await;
+        harness.platform_fee_account = harness.create_token_account(&admin).await;
+
+        // Single test mint stands in for both settlement stablecoins
+        harness
+            .process(
+                ix(
+                    lutrii_recurring::ID,
+                    lutrii_recurring::accounts::InitializeConfig {
+                        platform_state: platform_state(),
+                        authority: admin,
+                        fee_wallet_usdc: harness.platform_fee_account,
+                        fee_wallet_usd1: harness.platform_fee_account,
+                        usdc_mint: harness.mint,
+                        usd1_mint: harness.mint,
+                        token_program: spl_token::id(),
+                    },
+                    lutrii_recurring::instruction::InitializeConfig {},
+                ),
+                &[],
+            )
+            .await
+            .unwrap();
+        harness.configure_accepted_mint(1, true).await.unwrap();
+        harness
+    }
+
+    /// List the test mint for payments with a minimum subscription amount
+    async fn configure_accepted_mint(
+        &mut self,
+        min_amount: u64,
+        enabled: bool,
+    ) -> Result<(), BanksClientError> {
+        let configure = ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::ConfigureAcceptedMint {
+                platform_state: platform_state(),
+                accepted_mint: accepted_mint_pda(&self.mint),
+                authority: self.ctx.payer.pubkey(),
+                mint: self.mint,
+                system_program: system_program::ID,
+            },
+            lutrii_recurring::instruction::ConfigureAcceptedMint {
+                price_feed: Pubkey::default(),
+                min_amount,
+                enabled,
+            },
+        );
+        self.process(configure, &[]).await
+    }
+
+    /// Fund a user, mint them USDC and create a subscription to the merchant
+    async fn subscribe(&mut self, amount: u64, frequency_seconds: i64) -> UserFixture {
+        self.try_subscribe(amount, frequency_seconds).await.unwrap()
+    }
+
+    async fn try_subscribe(
+        &mut self,
+        amount: u64,
+        frequency_seconds: i64,
+    ) -> Result<UserFixture, BanksClientError> {
+        let keypair = Keypair::new();
+        self.fund(&keypair.pubkey()).await;
+        let token_account = self.create_token_account(&keypair.pubkey()).await;
+        self.mint_to(&token_account, 100 * USDC).await;
+
+        let merchant = self.merchant;
+        let merchant_owner = self.merchant_owner.pubkey();
+        let merchant_token_account = self.merchant_token_account;
+        let subscription = self
+            .subscribe_with(
+                &keypair,
+                token_account,
+                merchant,
+                &merchant_owner,
+                merchant_token_account,
+                amount,
+                frequency_seconds,
+            )
+            .await?;
+
+        Ok(UserFixture {
+            keypair,
+            token_account,
+            subscription,
+        })
+    }
+
+    /// Subscribe an existing wallet and token account to any merchant
+    #[allow(clippy::too_many_arguments)]
+    async fn subscribe_with(
+        &mut self,
+        user: &Keypair,
+        token_account: Pubkey,
+        merchant: Pubkey,
+        merchant_owner: &Pubkey,
+        merchant_token_account: Pubkey,
+        amount: u64,
+        frequency_seconds: i64,
+    ) -> Result<Pubkey, BanksClientError> {
+        let accounts = self
+            .create_subscription_accounts(
+                &user.pubkey(),
+                token_account,
+                merchant,
+                merchant_owner,
+                merchant_token_account,
+                frequency_seconds,
+            )
+            .await;
+        let subscription = accounts.subscription;
+        self.process(
+            ix(
+                lutrii_recurring::ID,
+                accounts,
+                lutrii_recurring::instruction::CreateSubscription {
+                    amount,
+                    frequency_seconds,
+                    max_per_transaction: 2 * amount,
+                    lifetime_cap: 50 * amount,
+                    merchant_name: "Lutrii Test".to_string(),
+                    options: CreateOptions::default(),
+                },
+            ),
+            &[user],
+        )
+        .await?;
+
+        Ok(subscription)
+    }
+
+    /// create_subscription accounts, passing optional accounts that exist
+    async fn create_subscription_accounts(
+        &mut self,
+        user: &Pubkey,
+        token_account: Pubkey,
+        merchant: Pubkey,
+        merchant_owner: &Pubkey,
+        merchant_token_account: Pubkey,
+        frequency_seconds: i64,
+    ) -> lutrii_recurring::accounts::CreateSubscription {
+        let user_stats = self.existing(user_stats_pda(user)).await;
+        let clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        let first_payment = clock.unix_timestamp + frequency_seconds;
+        let next_due_bucket = self
+            .existing(due_bucket_pda(&merchant, DueBucket::day_of(first_payment)))
+            .await;
+        let revenue_forecast = self.existing(revenue_forecast_pda(&merchant, &self.mint)).await;
+        // Further subscriptions of the pair take the counter's next nonce
+        let counter = subscription_counter_pda(user, &merchant);
+        let nonce = match self.account(&counter).await {
+            Some(_) => self.anchor_account::<SubscriptionCounter>(&counter).await.next_nonce,
+            None => 0,
+        };
+        lutrii_recurring::accounts::CreateSubscription {
+            subscription_counter: counter,
+            subscription: lutrii_common::subscription_address_with_nonce(user, &merchant, nonce),
+            platform_state: platform_state(),
+            user: *user,
+            merchant,
+            user_token_account: token_account,
+            merchant_token_account,
+            user_denylist_entry: denylist_entry(user),
+            merchant_denylist_entry: denylist_entry(merchant_owner),
+            blocklist_entry: blocklist_entry(&merchant, user),
+            merchant_policy: merchant_policy(&merchant),
+            allowlist_entry: allowlist_entry(&merchant, user),
+            user_delegate: user_delegate(&token_account),
+            mint: self.mint,
+            accepted_mint: accepted_mint_pda(&self.mint),
+            token_program: spl_token::id(),
+            system_program: system_program::ID,
+            user_stats,
+            swap_asset: None,
+            price_feed: None,
+            settlement_mint: None,
+            settlement_accepted_mint: None,
+            next_due_bucket,
+            revenue_forecast,
+            platform_fee_account: None,
+            promo: None,
+            fee_holidays: fee_holidays_pda(),
+            merchant_registry_program: lutrii_merchant_registry::ID,
+            instructions: solana_sdk::sysvar::instructions::ID,
+        }
+    }
+
+    /// Offer `terms` to a funded `user` for a day; returns the offer and its accept instruction
+    async fn offer(&mut self, user: &Keypair, terms: OfferTerms) -> (Pubkey, Instruction) {
+        let clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        let offer = Pubkey::find_program_address(
+            &[b"offer", self.merchant.as_ref(), user.pubkey().as_ref()],
+            &lutrii_recurring::ID,
+        )
+        .0;
+        let owner = self.merchant_owner.insecure_clone();
+        let frequency_seconds = terms.frequency_seconds;
+        self.process(
+            ix(
+                lutrii_recurring::ID,
+                lutrii_recurring::accounts::CreateOffer {
+                    offer,
+                    merchant: self.merchant,
+                    merchant_token_account: self.merchant_token_account,
+                    owner: owner.pubkey(),
+                    system_program: system_program::ID,
+                },
+                lutrii_recurring::instruction::CreateOffer {
+                    user: user.pubkey(),
+                    terms,
+                    expires_at: clock.unix_timestamp + DAY,
+                },
+            ),
+            &[&owner],
+        )
+        .await
+        .unwrap();
+
+        let accept = self.accept_offer_ix(user, offer, frequency_seconds).await;
+        (offer, accept)
+    }
+
+    /// Fund `user` and build its accept_offer instruction
+    async fn accept_offer_ix(
+        &mut self,
+        user: &Keypair,
+        offer: Pubkey,
+        frequency_seconds: i64,
+    ) -> Instruction {
+        self.fund(&user.pubkey()).await;
+        let token_account = self.create_token_account(&user.pubkey()).await;
+        self.mint_to(&token_account, 100 * USDC).await;
+
+        let owner = self.merchant_owner.pubkey();
+        let (merchant, merchant_token_account) = (self.merchant, self.merchant_token_account);
+        let create = self
+            .create_subscription_accounts(
+                &user.pubkey(),
+                token_account,
+                merchant,
+                &owner,
+                merchant_token_account,
+                frequency_seconds,
+            )
+            .await;
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::AcceptOffer {
+                offer,
+                merchant_owner: owner,
+                create,
+            },
+            lutrii_recurring::instruction::AcceptOffer {},
+        )
+    }
+
+    /// Create a daily plan for the harness merchant
+    async fn create_plan(&mut self, plan_id: u64, price: u64) -> Pubkey {
+        let owner = self.merchant_owner.insecure_clone();
+        let plan = plan_pda(&self.merchant, plan_id);
+        self.process(
+            ix(
+                lutrii_recurring::ID,
+                lutrii_recurring::accounts::CreatePlan {
+                    plan,
+                    merchant: self.merchant,
+                    owner: owner.pubkey(),
+                    system_program: system_program::ID,
+                },
+                lutrii_recurring::instruction::CreatePlan {
+                    plan_id,
+                    name: format!("Plan {}", plan_id),
+                    price,
+                    frequency_seconds: DAY,
+                    mint: Pubkey::default(),
+                    trial_period_seconds: 0,
+                },
+            ),
+            &[&owner],
+        )
+        .await
+        .unwrap();
+        plan
+    }
+
+    /// Apply for and approve a Verified merchant owned by `owner`
+    async fn register_merchant(&mut self, owner: &Keypair) -> Pubkey {
+        let admin = self.ctx.payer.pubkey();
+        self.fund(&owner.pubkey()).await;
+        let merchant = merchant_pda(&owner.pubkey());
+        self.process(
+            ix(
+                lutrii_merchant_registry::ID,
+                lutrii_merchant_registry::accounts::ApplyForVerification {
+                    merchant,
+                    registry_state: registry_state(),
+                    owner: owner.pubkey(),
+                    system_program: system_program::ID,
+                },
+                lutrii_merchant_registry::instruction::ApplyForVerification {
+                    business_name: "Lutrii Test Merchant".to_string(),
+                    webhook_url: "https://example.com/webhook".to_string(),
+                    category: "software".to_string(),
+                },
+            ),
+            &[owner],
+        )
+        .await
+        .unwrap();
+        self.process(
+            ix(
+                lutrii_merchant_registry::ID,
+                lutrii_merchant_registry::accounts::AdminMerchantAction {
+                    merchant,
+                    registry_state: registry_state(),
+                    authority: admin,
+                    audit_log: None,
+                },
+                lutrii_merchant_registry::instruction::ApproveMerchant {
+                    tier: VerificationTier::Verified,
+                },
+            ),
+            &[],
+        )
+        .await
+        .unwrap();
+        merchant
+    }
+
+    async fn execute_payment(&mut self, user: &UserFixture) -> Result<(), BanksClientError> {
+        let instruction = self.execute_payment_ix(user).await;
+        self.process(instruction, &[]).await
+    }
+
+    async fn execute_payment_ix(&mut self, user: &UserFixture) -> Instruction {
+        let settlement = self.existing(settlement_pda(&self.merchant, &self.mint)).await;
+        let settlement_vault = settlement.map(|s| settlement_vault_pda(&s));
+        let fee_treasury = self.existing(fee_treasury_pda(&self.mint)).await;
+        let fee_treasury_vault = fee_treasury.map(|t| fee_treasury_vault_pda(&t));
+        let escrow = self.existing(escrow_pda(&user.subscription)).await;
+        let escrow_vault = escrow.map(|e| escrow_vault_pda(&e));
+        // Due-date buckets the keeper would pass: the current and the next due day
+        let subscription: Subscription = self.anchor_account(&user.subscription).await;
+        let clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        let (year, month) = schedule::utc_year_month(clock.unix_timestamp);
+        let (year, month) = (year as u16, month as u8);
+        let next_payment = schedule::next_due(
+            subscription.next_cycle_base(clock.unix_timestamp),
+            subscription.frequency_seconds,
+            subscription.billing_day,
+            subscription.billing_weekday,
+            subscription.utc_offset_seconds,
+        )
+        .unwrap();
+        let due_bucket = self
+            .existing(due_bucket_pda(&self.merchant, DueBucket::day_of(subscription.next_payment)))
+            .await;
+        let next_due_bucket = self
+            .existing(due_bucket_pda(&self.merchant, DueBucket::day_of(next_payment)))
+            .await;
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::ExecutePayment {
+                subscription: user.subscription,
+                platform_state: platform_state(),
+                user: user.keypair.pubkey(),
+                user_token_account: user.token_account,
+                user_delegate: user_delegate(&user.token_account),
+                merchant_token_account: self.merchant_token_account,
+                platform_fee_account: self.platform_fee_account,
+                user_denylist_entry: denylist_entry(&user.keypair.pubkey()),
+                merchant_denylist_entry: denylist_entry(&self.merchant_owner.pubkey()),
+                blocklist_entry: blocklist_entry(&self.merchant, &user.keypair.pubkey()),
+                mint: self.mint,
+                token_program: spl_token::id(),
+                user_stats: user_stats_pda(&user.keypair.pubkey()),
+                settlement,
+                settlement_vault,
+                fee_treasury,
+                fee_treasury_vault,
+                due_bucket,
+                next_due_bucket,
+                merchant_policy: merchant_policy(&self.merchant),
+                claimant: None,
+                merchant: self.merchant,
+                merchant_registry_program: lutrii_merchant_registry::ID,
+                instructions: solana_sdk::sysvar::instructions::ID,
+                merchant_volume: merchant_volume_pda(&self.merchant, &self.mint),
+                fee_holidays: fee_holidays_pda(),
+                merchant_report: merchant_report_pda(&self.merchant, &self.mint, year),
+                merchant_statement: merchant_statement_pda(&self.merchant, &self.mint, year, month),
+                refund_liability: refund_liability_pda(&self.merchant, &self.mint),
+                revenue_forecast: revenue_forecast_pda(&self.merchant, &self.mint),
+                executor: None,
+                executor_token_account: None,
+                plan: (subscription.plan != Pubkey::default()).then_some(subscription.plan),
+                escrow,
+                escrow_vault,
+            },
+            lutrii_recurring::instruction::ExecutePayment {},
+        )
+    }
+
+    /// Bill `amount` of metered usage to `user`, signed by the merchant owner `owner`
+    async fn metered_payment_ix(&mut self, user: &UserFixture, owner: Pubkey, amount: u64) -> Instruction {
+        let (year, month) = self.reporting_period().await;
+        let escrow = self.existing(escrow_pda(&user.subscription)).await;
+        let escrow_vault = escrow.map(|e| escrow_vault_pda(&e));
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::ExecuteMeteredPayment {
+                subscription: user.subscription,
+                usage_meter: usage_meter_pda(&user.subscription),
+                platform_state: platform_state(),
+                user_token_account: user.token_account,
+                user_delegate: user_delegate(&user.token_account),
+                merchant_token_account: self.merchant_token_account,
+                platform_fee_account: self.platform_fee_account,
+                user_denylist_entry: denylist_entry(&user.keypair.pubkey()),
+                merchant_denylist_entry: denylist_entry(&self.merchant_owner.pubkey()),
+                blocklist_entry: blocklist_entry(&self.merchant, &user.keypair.pubkey()),
+                merchant: merchant_pda(&owner),
+                owner,
+                mint: self.mint,
+                token_program: spl_token::id(),
+                merchant_policy: merchant_policy(&self.merchant),
+                fee_holidays: fee_holidays_pda(),
+                merchant_report: merchant_report_pda(&self.merchant, &self.mint, year),
+                merchant_statement: merchant_statement_pda(&self.merchant, &self.mint, year, month),
+                refund_liability: refund_liability_pda(&self.merchant, &self.mint),
+                user_stats: user_stats_pda(&user.keypair.pubkey()),
+                merchant_volume: merchant_volume_pda(&self.merchant, &self.mint),
+                revenue_forecast: revenue_forecast_pda(&self.merchant, &self.mint),
+                merchant_registry_program: lutrii_merchant_registry::ID,
+                instructions: solana_sdk::sysvar::instructions::ID,
+                escrow,
+                escrow_vault,
+            },
+            lutrii_recurring::instruction::ExecuteMeteredPayment { amount },
+        )
+    }
+
+    /// Let the merchant bill `user` up to `period_cap` of usage per period
+    async fn configure_usage_cap(&mut self, user: &UserFixture, period_cap: u64) {
+        let configure = ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::ConfigureUsageCap {
+                subscription: user.subscription,
+                usage_meter: usage_meter_pda(&user.subscription),
+                user: user.keypair.pubkey(),
+                system_program: system_program::ID,
+            },
+            lutrii_recurring::instruction::ConfigureUsageCap { period_cap },
+        );
+        self.process(configure, &[&user.keypair]).await.unwrap();
+    }
+
+    async fn modify(
+        &mut self,
+        user: &UserFixture,
+        data: impl InstructionData,
+    ) -> Result<(), BanksClientError> {
+        let revenue_forecast = self.existing(revenue_forecast_pda(&self.merchant, &self.mint)).await;
+        self.process(
+            ix(
+                lutrii_recurring::ID,
+                lutrii_recurring::accounts::ModifySubscription {
+                    subscription: user.subscription,
+                    platform_state: platform_state(),
+                    user: user.keypair.pubkey(),
+                    due_bucket: None,
+                    next_due_bucket: None,
+                    revenue_forecast,
+                },
+                data,
+            ),
+            &[&user.keypair],
+        )
+        .await
+    }
+
+    async fn review_ix(&mut self, user: &UserFixture, rating: u8) -> Instruction {
+        let subscription: Subscription = self.anchor_account(&user.subscription).await;
+        ix(
+            lutrii_merchant_registry::ID,
+            lutrii_merchant_registry::accounts::SubmitReview {
+                review: review_pda(&user.subscription, &subscription.plan),
+                merchant: self.merchant,
+                subscription: user.subscription,
+                registry_state: registry_state(),
+                reviewer: user.keypair.pubkey(),
+                system_program: system_program::ID,
+            },
+            lutrii_merchant_registry::instruction::SubmitReview {
+                rating,
+                comment: "Reliable service".to_string(),
+            },
+        )
+    }
+
+    async fn submit_review(&mut self, user: &UserFixture, rating: u8) -> Result<(), BanksClientError> {
+        let review = self.review_ix(user, rating).await;
+        self.process(review, &[&user.keypair]).await
+    }
+
+    // ------------------------------------------------------------------------
+    // Chain helpers
+    // ------------------------------------------------------------------------
+
+    async fn process(
+        &mut self,
+        instruction: Instruction,
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        // Fresh blockhash so repeated identical instructions are not deduplicated
+        let blockhash = self.ctx.get_new_latest_blockhash().await.unwrap();
+        let mut all_signers: Vec<&Keypair> = vec![&self.ctx.payer];
+        all_signers.extend_from_slice(signers);
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.ctx.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.ctx.banks_client.process_transaction(tx).await
+    }
+
+    /// Compute units `instruction` consumes, from a simulation
+    async fn compute_units(&mut self, instruction: Instruction) -> u64 {
+        let blockhash = self.ctx.get_new_latest_blockhash().await.unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.ctx.payer.pubkey()),
+            &[&self.ctx.payer],
+            blockhash,
+        );
+        let simulation = self.ctx.banks_client.simulate_transaction(tx).await.unwrap();
+        simulation.result.unwrap().unwrap();
+        simulation.simulation_details.unwrap().units_consumed
+    }
+
+    /// `verify_active_subscription` answer for `user`'s first subscription to
+    /// the merchant, from a simulation
+    async fn verify_access(&mut self, user: &Pubkey, plan: Option<Pubkey>) -> SubscriptionAccess {
+        self.verify_access_with_nonce(user, 0, plan).await
+    }
+
+    /// `verify_active_subscription` answer for `user`'s subscription to the
+    /// merchant created with `nonce`, from a simulation
+    async fn verify_access_with_nonce(
+        &mut self,
+        user: &Pubkey,
+        nonce: u32,
+        plan: Option<Pubkey>,
+    ) -> SubscriptionAccess {
+        let instruction = lutrii_common::verify_active_subscription_ix(user, &self.merchant, nonce, plan);
+        let blockhash = self.ctx.get_new_latest_blockhash().await.unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&self.ctx.payer.pubkey()),
+            &[&self.ctx.payer],
+            blockhash,
+        );
+        let simulation = self.ctx.banks_client.simulate_transaction(tx).await.unwrap();
+        simulation.result.unwrap().unwrap();
+        // Return data comes back without its trailing zero bytes
+        let mut data = simulation
+            .simulation_details
+            .unwrap()
+            .return_data
+            .map(|return_data| return_data.data)
+            .unwrap_or_default();
+        data.resize(SubscriptionAccess::SPACE, 0);
+        SubscriptionAccess::deserialize(&mut &data[..]).unwrap()
+    }
+
+    /// Move the cluster clock forward by `seconds`
+    async fn warp_forward(&mut self, seconds: i64) {
+        let mut clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        self.ctx.warp_to_slot(clock.slot + 1).unwrap();
+        clock.slot += 1;
+        clock.unix_timestamp += seconds;
+        self.ctx.set_sysvar(&clock);
+    }
+
+    /// `address` if an account exists there (for optional accounts)
+    /// UTC year and month the merchant's report and statement are kept for now
+    async fn reporting_period(&mut self) -> (u16, u8) {
+        let clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        let (year, month) = schedule::utc_year_month(clock.unix_timestamp);
+        (year as u16, month as u8)
+    }
+
+    async fn existing(&mut self, address: Pubkey) -> Option<Pubkey> {
+        self.account(&address).await.map(|_| address)
+    }
+
+    async fn account(&mut self, address: &Pubkey) -> Option<Account> {
+        self.ctx.banks_client.get_account(*address).await.unwrap()
+    }
+
+    async fn anchor_account<T: AccountDeserialize>(&mut self, address: &Pubkey) -> T {
+        let account = self.account(address).await.expect("account missing");
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    async fn token_account(&mut self, address: &Pubkey) -> TokenAccount {
+        let account = self.account(address).await.expect("token account missing");
+        TokenAccount::unpack(&account.data).unwrap()
+    }
+
+    async fn fund(&mut self, to: &Pubkey) {
+        let payer = self.ctx.payer.pubkey();
+        self.process(
+            system_instruction::transfer(&payer, to, 1_000_000_000),
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+
+    async fn create_mint(&mut self) -> Pubkey {
+        let mint = Keypair::new();
+        let payer = self.ctx.payer.pubkey();
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+
+        let blockhash = self.ctx.get_new_latest_blockhash().await.unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &[
+                system_instruction::create_account(
+                    &payer,
+                    &mint.pubkey(),
+                    rent.minimum_balance(Mint::LEN),
+                    Mint::LEN as u64,
+                    &spl_token::id(),
+                ),
+                spl_token::instruction::initialize_mint2(
+                    &spl_token::id(),
+                    &mint.pubkey(),
+                    &payer,
+                    None,
+                    DECIMALS,
+                )
+                .unwrap(),
+            ],
+            Some(&payer),
+            &[&self.ctx.payer, &mint],
+            blockhash,
+        );
+        self.ctx.banks_client.process_transaction(tx).await.unwrap();
+        mint.pubkey()
+    }
+
+    async fn create_token_account(&mut self, owner: &Pubkey) -> Pubkey {
+        let account = Keypair::new();
+        let payer = self.ctx.payer.pubkey();
+        let rent = self.ctx.banks_client.get_rent().await.unwrap();
+
+        let blockhash = self.ctx.get_new_latest_blockhash().await.unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &[
+                system_instruction::create_account(
+                    &payer,
+                    &account.pubkey(),
+                    rent.minimum_balance(TokenAccount::LEN),
+                    TokenAccount::LEN as u64,
+                    &spl_token::id(),
+                ),
+                spl_token::instruction::initialize_account3(
+                    &spl_token::id(),
+                    &account.pubkey(),
+                    &self.mint,
+                    owner,
+                )
+                .unwrap(),
+            ],
+            Some(&payer),
+            &[&self.ctx.payer, &account],
+            blockhash,
+        );
+        self.ctx.banks_client.process_transaction(tx).await.unwrap();
+        account.pubkey()
+    }
+
+    async fn mint_to(&mut self, account: &Pubkey, amount: u64) {
+        let payer = self.ctx.payer.pubkey();
+        self.process(
+            spl_token::instruction::mint_to(&spl_token::id(), &self.mint, account, &payer, &[], amount)
+                .unwrap(),
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+}
+
+fn ix(program_id: Pubkey, accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+fn platform_state() -> Pubkey {
+    Pubkey::find_program_address(&[b"platform"], &lutrii_recurring::ID).0
+}
+
+fn registry_state() -> Pubkey {
+    Pubkey::find_program_address(&[b"registry"], &lutrii_merchant_registry::ID).0
+}
+
+fn merchant_pda(owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"merchant", owner.as_ref()], &lutrii_merchant_registry::ID).0
+}
+
+fn denylist_entry(wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"denylist", wallet.as_ref()], &lutrii_recurring::ID).0
+}
+
+fn blocklist_entry(merchant: &Pubkey, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"blocklist", merchant.as_ref(), user.as_ref()],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
+
+fn merchant_policy(merchant: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"merchant_policy", merchant.as_ref()], &lutrii_recurring::ID).0
+}
+
+fn allowlist_entry(merchant: &Pubkey, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"allowlist", merchant.as_ref(), user.as_ref()],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
+
+fn user_delegate(token_account: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"user_delegate", token_account.as_ref()],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
+
+fn user_stats_pda(user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"user_stats", user.as_ref()], &lutrii_recurring::ID).0
+}
+
+fn due_bucket_pda(merchant: &Pubkey, day: i64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"due_bucket", merchant.as_ref(), &day.to_le_bytes()],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
+
+fn settlement_pda(merchant: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"settlement", merchant.as_ref(), mint.as_ref()],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
+
+fn settlement_vault_pda(settlement: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"settlement_vault", settlement.as_ref()],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
+
+fn fee_treasury_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"fee_treasury", mint.as_ref()], &lutrii_recurring::ID).0
+}
+
+fn fee_treasury_vault_pda(treasury: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"fee_treasury_vault", treasury.as_ref()],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
+
+fn accepted_mint_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"accepted_mint", mint.as_ref()], &lutrii_recurring::ID).0
+}
+
+fn fee_holidays_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"fee_holidays"], &lutrii_recurring::ID).0
+}
+
+fn usage_meter_pda(subscription: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"usage_meter", subscription.as_ref()], &lutrii_recurring::ID).0
+}
+
+fn pending_config_change_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"pending_config_change"], &lutrii_recurring::ID).0
+}
+
+fn merchant_volume_pda(merchant: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"merchant_volume", merchant.as_ref(), mint.as_ref()],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
+
+fn merchant_report_pda(merchant: &Pubkey, mint: &Pubkey, year: u16) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"merchant_report", merchant.as_ref(), mint.as_ref(), &year.to_le_bytes()],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
+
+fn merchant_statement_pda(merchant: &Pubkey, mint: &Pubkey, year: u16, month: u8) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"merchant_statement",
+            merchant.as_ref(),
+            mint.as_ref(),
+            &year.to_le_bytes(),
+            &[month],
+        ],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
+
+fn revenue_forecast_pda(merchant: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"revenue_forecast", merchant.as_ref(), mint.as_ref()],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
+
+fn escrow_pda(subscription: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"escrow", subscription.as_ref()], &lutrii_recurring::ID).0
+}
+
+fn escrow_vault_pda(escrow: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"escrow_vault", escrow.as_ref()], &lutrii_recurring::ID).0
+}
+
+fn refund_liability_pda(merchant: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"refund_liability", merchant.as_ref(), mint.as_ref()],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
+
+fn event_authority() -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], &lutrii_recurring::ID).0
+}
+
+fn plan_pda(merchant: &Pubkey, plan_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"plan", merchant.as_ref(), &plan_id.to_le_bytes()],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
+
+fn invoice_pda(merchant: &Pubkey, invoice_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"invoice", merchant.as_ref(), &invoice_id.to_le_bytes()],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
+
+fn subscription_counter_pda(user: &Pubkey, merchant: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"subscription_counter", user.as_ref(), merchant.as_ref()],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
+
+fn subscription_pda(user: &Pubkey, merchant: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"subscription", user.as_ref(), merchant.as_ref()],
+        &lutrii_recurring::ID,
+    )
+    .0
+}
+
+fn tag_index_pda(tag: &str) -> Pubkey {
+    Pubkey::find_program_address(&[b"tag_index", tag.as_bytes()], &lutrii_merchant_registry::ID).0
+}
+
+fn review_pda(subscription: &Pubkey, plan: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"review", subscription.as_ref(), plan.as_ref()],
+        &lutrii_merchant_registry::ID,
+    )
+    .0
+}
+
+fn assert_custom_error(result: Result<(), BanksClientError>, expected: u32) {
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(code),
+        ))) => assert_eq!(code, expected, "unexpected error code"),
+        other => panic!("expected custom error {}, got {:?}", expected, other),
+    }
+}
+
+/// Put `with` where `instruction` passes `key`, as a client that left the
+/// account out or forged it would
+fn substitute_account(instruction: &mut Instruction, key: &Pubkey, with: Pubkey) {
+    let meta = instruction
+        .accounts
+        .iter_mut()
+        .find(|meta| meta.pubkey == *key)
+        .expect("account not passed");
+    meta.pubkey = with;
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[tokio::test]
+async fn test_full_subscription_lifecycle() {
+    let mut h = Harness::new().await;
+    let amount = USDC;
+    let fee = amount * FEE_BASIS_POINTS as u64 / 10_000;
+    let user = h.subscribe(amount, DAY).await;
+
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert!(subscription.is_active);
+    assert_eq!(subscription.payment_count, 0);
+    let delegated = h.token_account(&user.token_account).await;
+    assert_eq!(
+        Option::<Pubkey>::from(delegated.delegate),
+        Some(user_delegate(&user.token_account))
+    );
+    assert_eq!(delegated.delegated_amount, 50 * amount);
+
+    // Not due yet
+    assert_custom_error(
+        h.execute_payment(&user).await,
+        u32::from(ErrorCode::PaymentNotDue),
+    );
+
+    // Three consecutive cycles
+    for cycle in 1..=3u32 {
+        h.warp_forward(DAY).await;
+        h.execute_payment(&user).await.unwrap();
+
+        let subscription: Subscription = h.anchor_account(&user.subscription).await;
+        assert_eq!(subscription.payment_count, cycle);
+        assert_eq!(subscription.total_paid, amount * cycle as u64);
+        assert!(!subscription.payment_in_progress);
+
+        // Same cycle cannot be charged twice
+        assert_custom_error(
+            h.execute_payment(&user).await,
+            u32::from(ErrorCode::PaymentNotDue),
+        );
+    }
+
+    let merchant_balance = h.token_account(&h.merchant_token_account.clone()).await.amount;
+    assert_eq!(merchant_balance, 3 * (amount - fee));
+    let fee_balance = h.token_account(&h.platform_fee_account.clone()).await.amount;
+    assert_eq!(fee_balance, 3 * fee);
+    let user_balance = h.token_account(&user.token_account).await.amount;
+    assert_eq!(user_balance, 100 * USDC - 3 * amount);
+
+    let platform: PlatformState = h.anchor_account(&platform_state()).await;
+    assert_eq!(platform.total_transactions, 3);
+    assert_eq!(platform.total_subscriptions, 1);
+
+    // Pause blocks execution even once due
+    h.modify(&user, lutrii_recurring::instruction::PauseSubscription {})
+        .await
+        .unwrap();
+    h.warp_forward(DAY).await;
+    assert_custom_error(
+        h.execute_payment(&user).await,
+        u32::from(ErrorCode::SubscriptionPaused),
+    );
+
+    // Resume reschedules one period out from now
+    h.modify(&user, lutrii_recurring::instruction::ResumeSubscription {})
+        .await
+        .unwrap();
+    assert_custom_error(
+        h.execute_payment(&user).await,
+        u32::from(ErrorCode::PaymentNotDue),
+    );
+    h.warp_forward(DAY).await;
+    h.execute_payment(&user).await.unwrap();
+
+    // Close is refused while active
+    let close = || {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::CloseSubscription {
+                subscription: user.subscription,
+                user: user.keypair.pubkey(),
+            },
+            lutrii_recurring::instruction::CloseSubscription {},
+        )
+    };
+    assert_custom_error(
+        h.process(close(), &[&user.keypair]).await,
+        u32::from(ErrorCode::SubscriptionStillActive),
+    );
+
+    // Cancel revokes the delegation
+    h.process(
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::CancelSubscription {
+                subscription: user.subscription,
+                platform_state: platform_state(),
+                merchant_policy: merchant_policy(&h.merchant),
+                user_token_account: user.token_account,
+                user_delegate: user_delegate(&user.token_account),
+                user: user.keypair.pubkey(),
+                token_program: spl_token::id(),
+                user_stats: None,
+                due_bucket: None,
+                revenue_forecast: None,
+            },
+            lutrii_recurring::instruction::CancelSubscription {},
+        ),
+        &[&user.keypair],
+    )
+    .await
+    .unwrap();
+
+    let revoked = h.token_account(&user.token_account).await;
+    assert_eq!(Option::<Pubkey>::from(revoked.delegate), None);
+    h.warp_forward(DAY).await;
+    assert_custom_error(
+        h.execute_payment(&user).await,
+        u32::from(ErrorCode::SubscriptionInactive),
+    );
+
+    let platform: PlatformState = h.anchor_account(&platform_state()).await;
+    assert_eq!(platform.total_subscriptions, 0);
+
+    // Close returns rent to the user
+    h.process(close(), &[&user.keypair]).await.unwrap();
+    assert!(h.account(&user.subscription).await.is_none());
+}
+
+#[tokio::test]
+async fn test_cancel_and_close_reclaims_rent() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    let rent = h.account(&user.subscription).await.unwrap().lamports;
+    let balance_before = h.account(&user.keypair.pubkey()).await.unwrap().lamports;
+
+    h.process(
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::CancelAndClose {
+                subscription: user.subscription,
+                platform_state: platform_state(),
+                merchant_policy: merchant_policy(&h.merchant),
+                user_token_account: user.token_account,
+                user_delegate: user_delegate(&user.token_account),
+                user: user.keypair.pubkey(),
+                token_program: spl_token::id(),
+                user_stats: None,
+                due_bucket: None,
+                revenue_forecast: None,
+            },
+            lutrii_recurring::instruction::CancelAndClose {},
+        ),
+        &[&user.keypair],
+    )
+    .await
+    .unwrap();
+
+    assert!(h.account(&user.subscription).await.is_none());
+    let balance_after = h.account(&user.keypair.pubkey()).await.unwrap().lamports;
+    assert_eq!(balance_after, balance_before + rent);
+
+    let revoked = h.token_account(&user.token_account).await;
+    assert_eq!(Option::<Pubkey>::from(revoked.delegate), None);
+    let platform: PlatformState = h.anchor_account(&platform_state()).await;
+    assert_eq!(platform.total_subscriptions, 0);
+    let policy: MerchantPolicy = h.anchor_account(&merchant_policy(&h.merchant)).await;
+    assert_eq!(policy.active_subscribers, 0);
+}
+
+#[tokio::test]
+async fn test_admin_force_cancel_stops_executions() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    let policy = merchant_policy(&h.merchant);
+    let force_cancel = |authority: Pubkey| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::ForceCancelSubscription {
+                platform_state: platform_state(),
+                subscription: user.subscription,
+                user_delegate: user_delegate(&user.token_account),
+                merchant_policy: policy,
+                authority,
+                user_stats: None,
+                due_bucket: None,
+                revenue_forecast: None,
+            },
+            lutrii_recurring::instruction::ForceCancelSubscription {
+                reason: ForceCancelReason::LegalOrder,
+            },
+        )
+    };
+
+    // Users cannot force-cancel (e.g. to dodge a cancellation event)
+    assert_custom_error(
+        h.process(force_cancel(user.keypair.pubkey()), &[&user.keypair]).await,
+        u32::from(ErrorCode::UnauthorizedAdmin),
+    );
+
+    let admin = h.ctx.payer.pubkey();
+    h.process(force_cancel(admin), &[]).await.unwrap();
+
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert!(!subscription.is_active);
+    assert!(subscription.force_cancelled);
+    let delegate: UserDelegate = h.anchor_account(&user_delegate(&user.token_account)).await;
+    assert_eq!(delegate.outstanding_allowance, 0);
+    assert_eq!(delegate.active_subscriptions, 0);
+
+    h.warp_forward(DAY).await;
+    assert_custom_error(
+        h.execute_payment(&user).await,
+        u32::from(ErrorCode::SubscriptionInactive),
+    );
+}
+
+#[tokio::test]
+async fn test_emergency_revoke_all_cancels_and_revokes() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    let delegate = user_delegate(&user.token_account);
+    let mut revoke_all = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::EmergencyRevokeAll {
+            platform_state: platform_state(),
+            user: user.keypair.pubkey(),
+            token_program: spl_token::id(),
+            user_stats: None,
+        },
+        lutrii_recurring::instruction::EmergencyRevokeAll {},
+    );
+    revoke_all.accounts.extend([
+        AccountMeta::new(user.subscription, false),
+        AccountMeta::new(merchant_policy(&h.merchant), false),
+        AccountMeta::new(delegate, false),
+        AccountMeta::new(user.token_account, false),
+    ]);
+
+    // Someone else cannot trip the panic button on the user's behalf
+    let attacker = Keypair::new();
+    let mut hijacked = revoke_all.clone();
+    hijacked.accounts[1] = AccountMeta::new_readonly(attacker.pubkey(), true);
+    assert_custom_error(
+        h.process(hijacked, &[&attacker]).await,
+        u32::from(ErrorCode::InvalidSubscriptionAccount),
+    );
+
+    h.process(revoke_all, &[&user.keypair]).await.unwrap();
+
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert!(!subscription.is_active);
+    let revoked = h.token_account(&user.token_account).await;
+    assert_eq!(Option::<Pubkey>::from(revoked.delegate), None);
+    let delegate: UserDelegate = h.anchor_account(&delegate).await;
+    assert_eq!(delegate.outstanding_allowance, 0);
+    assert_eq!(delegate.active_subscriptions, 0);
+    let platform: PlatformState = h.anchor_account(&platform_state()).await;
+    assert_eq!(platform.total_subscriptions, 0);
+    let policy: MerchantPolicy = h.anchor_account(&merchant_policy(&h.merchant)).await;
+    assert_eq!(policy.active_subscribers, 0);
+}
+
+#[tokio::test]
+async fn test_due_buckets_follow_the_payment_schedule() {
+    let mut h = Harness::new().await;
+    let clock: Clock = h.ctx.banks_client.get_sysvar().await.unwrap();
+    let today = DueBucket::day_of(clock.unix_timestamp);
+    let merchant = h.merchant;
+    let payer = h.ctx.payer.pubkey();
+    let open = |day: i64| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::OpenDueBucket {
+                due_bucket: due_bucket_pda(&merchant, day),
+                merchant,
+                payer,
+                system_program: system_program::ID,
+            },
+            lutrii_recurring::instruction::OpenDueBucket { day },
+        )
+    };
+    let close = |day: i64| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::CloseDueBucket {
+                due_bucket: due_bucket_pda(&merchant, day),
+                payer,
+            },
+            lutrii_recurring::instruction::CloseDueBucket {},
+        )
+    };
+
+    // Buckets for past days would never be read
+    assert_custom_error(
+        h.process(open(today - 1), &[]).await,
+        u32::from(ErrorCode::InvalidDueBucket),
+    );
+    h.process(open(today + 1), &[]).await.unwrap();
+    h.process(open(today + 2), &[]).await.unwrap();
+
+    let user = h.subscribe(USDC, DAY).await;
+    let first: DueBucket = h.anchor_account(&due_bucket_pda(&merchant, today + 1)).await;
+    assert_eq!(first.subscriptions, vec![user.subscription]);
+
+    // Executing moves the subscription to its next due day
+    h.warp_forward(DAY).await;
+    h.execute_payment(&user).await.unwrap();
+    let first: DueBucket = h.anchor_account(&due_bucket_pda(&merchant, today + 1)).await;
+    assert!(first.subscriptions.is_empty());
+    let second: DueBucket = h.anchor_account(&due_bucket_pda(&merchant, today + 2)).await;
+    assert_eq!(second.subscriptions, vec![user.subscription]);
+
+    // Only empty buckets for past days can be closed
+    assert_custom_error(
+        h.process(close(today + 2), &[]).await,
+        u32::from(ErrorCode::DueBucketInUse),
+    );
+    h.warp_forward(DAY).await;
+    h.process(close(today + 1), &[]).await.unwrap();
+    assert!(h.account(&due_bucket_pda(&merchant, today + 1)).await.is_none());
+}
+
+#[tokio::test]
+async fn test_offer_accepted_on_merchant_terms() {
+    let mut h = Harness::new().await;
+    let terms = OfferTerms {
+        amount: 3 * USDC,
+        frequency_seconds: 7 * DAY,
+        max_per_transaction: 3 * USDC,
+        lifetime_cap: 36 * USDC,
+        merchant_name: "Lutrii Weekly".to_string(),
+    };
+
+    let user = Keypair::new();
+    let (offer, accept) = h.offer(&user, terms.clone()).await;
+    h.process(accept, &[&user]).await.unwrap();
+
+    // The subscription carries the offered terms, not client-chosen ones
+    let subscription: Subscription = h
+        .anchor_account(&subscription_pda(&user.pubkey(), &h.merchant))
+        .await;
+    assert_eq!(subscription.amount, terms.amount);
+    assert_eq!(subscription.frequency_seconds, terms.frequency_seconds);
+    assert_eq!(subscription.lifetime_cap, terms.lifetime_cap);
+    assert_eq!(subscription.merchant_name, terms.merchant_name);
+    assert!(h.account(&offer).await.is_none());
+
+    // Targeted offers are only open to their user
+    let late = Keypair::new();
+    let (late_offer, accept) = h.offer(&late, terms.clone()).await;
+    let intruder = Keypair::new();
+    let stolen = h
+        .accept_offer_ix(&intruder, late_offer, terms.frequency_seconds)
+        .await;
+    assert_custom_error(
+        h.process(stolen, &[&intruder]).await,
+        u32::from(ErrorCode::UnauthorizedUser),
+    );
+
+    // A lapsed offer cannot be accepted
+    h.warp_forward(2 * DAY).await;
+    assert_custom_error(
+        h.process(accept, &[&late]).await,
+        u32::from(ErrorCode::OfferExpired),
+    );
+}
+
+#[tokio::test]
+async fn test_published_offer_closes_when_sold_out() {
+    let mut h = Harness::new().await;
+    let clock: Clock = h.ctx.banks_client.get_sysvar().await.unwrap();
+    let offer_id: u64 = 7;
+    let offer = Pubkey::find_program_address(
+        &[b"offer", h.merchant.as_ref(), &offer_id.to_le_bytes()],
+        &lutrii_recurring::ID,
+    )
+    .0;
+    let owner = h.merchant_owner.insecure_clone();
+    h.process(
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::PublishOffer {
+                offer,
+                merchant: h.merchant,
+                merchant_token_account: h.merchant_token_account,
+                owner: owner.pubkey(),
+                system_program: system_program::ID,
+            },
+            lutrii_recurring::instruction::PublishOffer {
+                offer_id,
+                terms: OfferTerms {
+                    amount: 5 * USDC,
+                    frequency_seconds: 30 * DAY,
+                    max_per_transaction: 5 * USDC,
+                    lifetime_cap: 60 * USDC,
+                    merchant_name: "Lutrii Launch".to_string(),
+                },
+                expires_at: clock.unix_timestamp + 7 * DAY,
+                max_acceptances: 2,
+            },
+        ),
+        &[&owner],
+    )
+    .await
+    .unwrap();
+
+    // Any wallet can take one unit of inventory
+    let first = Keypair::new();
+    let accept = h.accept_offer_ix(&first, offer, 30 * DAY).await;
+    h.process(accept, &[&first]).await.unwrap();
+    let published: SubscriptionOffer = h.anchor_account(&offer).await;
+    assert_eq!(published.acceptances, 1);
+    assert_eq!(published.remaining(), 1);
+
+    // The last unit closes the offer and refunds the merchant owner
+    let owner_balance = h.account(&owner.pubkey()).await.unwrap().lamports;
+    let rent = h.account(&offer).await.unwrap().lamports;
+    let second = Keypair::new();
+    let accept = h.accept_offer_ix(&second, offer, 30 * DAY).await;
+    h.process(accept, &[&second]).await.unwrap();
+    assert!(h.account(&offer).await.is_none());
+    let refunded = h.account(&owner.pubkey()).await.unwrap().lamports;
+    assert_eq!(refunded, owner_balance + rent);
+
+    let subscription: Subscription = h
+        .anchor_account(&subscription_pda(&second.pubkey(), &h.merchant))
+        .await;
+    assert_eq!(subscription.amount, 5 * USDC);
+}
+
+#[tokio::test]
+async fn test_trial_converts_before_billing() {
+    let mut h = Harness::new().await;
+
+    // An unfunded wallet signs up without approving anything
+    let keypair = Keypair::new();
+    h.fund(&keypair.pubkey()).await;
+    let token_account = h.create_token_account(&keypair.pubkey()).await;
+    let owner = h.merchant_owner.pubkey();
+    let (merchant, merchant_token_account) = (h.merchant, h.merchant_token_account);
+    let accounts = h
+        .create_subscription_accounts(
+            &keypair.pubkey(),
+            token_account,
+            merchant,
+            &owner,
+            merchant_token_account,
+            7 * DAY,
+        )
+        .await;
+    h.process(
+        ix(
+            lutrii_recurring::ID,
+            accounts,
+            lutrii_recurring::instruction::CreateTrialSubscription {
+                amount: 5 * USDC,
+                frequency_seconds: 30 * DAY,
+                max_per_transaction: 5 * USDC,
+                lifetime_cap: 60 * USDC,
+                merchant_name: "Lutrii Trial".to_string(),
+                trial_seconds: 7 * DAY,
+            },
+        ),
+        &[&keypair],
+    )
+    .await
+    .unwrap();
+
+    let user = UserFixture {
+        subscription: subscription_pda(&keypair.pubkey(), &merchant),
+        keypair,
+        token_account,
+    };
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert!(subscription.delegation_deferred);
+    assert_eq!(subscription.trial_ends_at, subscription.next_payment);
+    assert!(h.token_account(&token_account).await.delegate.is_none());
+
+    // Nothing is collectable until the user converts
+    h.warp_forward(7 * DAY).await;
+    assert_custom_error(
+        h.execute_payment(&user).await,
+        u32::from(ErrorCode::TrialNotConverted),
+    );
+
+    // Converting approves the lifetime allowance; billing starts right away
+    h.mint_to(&token_account, 100 * USDC).await;
+    let convert = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::ConvertTrial {
+            subscription: user.subscription,
+            user_token_account: token_account,
+            user_delegate: user_delegate(&token_account),
+            user: user.keypair.pubkey(),
+            token_program: spl_token::id(),
+        },
+        lutrii_recurring::instruction::ConvertTrial {},
+    );
+    h.process(convert.clone(), &[&user.keypair]).await.unwrap();
+    assert_eq!(h.token_account(&token_account).await.delegated_amount, 60 * USDC);
+
+    h.execute_payment(&user).await.unwrap();
+    assert_eq!(h.token_account(&token_account).await.amount, 95 * USDC);
+    assert_custom_error(
+        h.process(convert, &[&user.keypair]).await,
+        u32::from(ErrorCode::NoTrialToConvert),
+    );
+}
+
+#[tokio::test]
+async fn test_trial_period_defers_first_payment() {
+    let mut h = Harness::new().await;
+
+    let keypair = Keypair::new();
+    h.fund(&keypair.pubkey()).await;
+    let token_account = h.create_token_account(&keypair.pubkey()).await;
+    h.mint_to(&token_account, 100 * USDC).await;
+    let owner = h.merchant_owner.pubkey();
+    let (merchant, merchant_token_account) = (h.merchant, h.merchant_token_account);
+    let accounts = h
+        .create_subscription_accounts(
+            &keypair.pubkey(),
+            token_account,
+            merchant,
+            &owner,
+            merchant_token_account,
+            14 * DAY,
+        )
+        .await;
+    let create = |charge_immediately: bool| lutrii_recurring::instruction::CreateSubscription {
+        amount: 5 * USDC,
+        frequency_seconds: 30 * DAY,
+        max_per_transaction: 5 * USDC,
+        lifetime_cap: 60 * USDC,
+        merchant_name: "Lutrii Trial".to_string(),
+        options: CreateOptions {
+            charge_immediately,
+            trial_seconds: 14 * DAY,
+            ..Default::default()
+        },
+    };
+    let charging = ix(lutrii_recurring::ID, accounts, create(true));
+    let deferring = Instruction {
+        data: create(false).data(),
+        ..charging.clone()
+    };
+    let without_trial = Instruction {
+        data: lutrii_recurring::instruction::CreateSubscription {
+            options: CreateOptions {
+                defer_delegation: true,
+                ..Default::default()
+            },
+            ..create(false)
+        }
+        .data(),
+        ..charging.clone()
+    };
+
+    // A trial has nothing to charge up front, and only trials defer the approval
+    assert_custom_error(
+        h.process(charging, &[&keypair]).await,
+        u32::from(ErrorCode::InvalidTrialPeriod),
+    );
+    assert_custom_error(
+        h.process(without_trial, &[&keypair]).await,
+        u32::from(ErrorCode::InvalidTrialPeriod),
+    );
+    h.process(deferring, &[&keypair]).await.unwrap();
+
+    // The allowance is approved at signup, unlike a deferred trial
+    let user = UserFixture {
+        subscription: subscription_pda(&keypair.pubkey(), &merchant),
+        keypair,
+        token_account,
+    };
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert!(!subscription.delegation_deferred);
+    assert_eq!(subscription.trial_ends_at, subscription.next_payment);
+    assert_eq!(h.token_account(&token_account).await.delegated_amount, 60 * USDC);
+    let clock: Clock = h.ctx.banks_client.get_sysvar().await.unwrap();
+    assert!(subscription.is_in_trial(clock.unix_timestamp + 13 * DAY));
+
+    h.warp_forward(7 * DAY).await;
+    assert_custom_error(
+        h.execute_payment(&user).await,
+        u32::from(ErrorCode::PaymentNotDue),
+    );
+
+    // Billing starts without another signature when the trial ends
+    h.warp_forward(7 * DAY).await;
+    h.execute_payment(&user).await.unwrap();
+    assert_eq!(h.token_account(&token_account).await.amount, 95 * USDC);
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert!(!subscription.is_in_trial(subscription.last_payment));
+    assert_eq!(subscription.payment_count, 1);
+}
+
+#[tokio::test]
+async fn test_charge_immediately_collects_first_payment() {
+    let mut h = Harness::new().await;
+    let amount = USDC;
+    let fee = amount * FEE_BASIS_POINTS as u64 / 10_000;
+
+    let keypair = Keypair::new();
+    h.fund(&keypair.pubkey()).await;
+    let token_account = h.create_token_account(&keypair.pubkey()).await;
+    h.mint_to(&token_account, 100 * USDC).await;
+    let owner = h.merchant_owner.pubkey();
+    let (merchant, merchant_token_account) = (h.merchant, h.merchant_token_account);
+    let mut accounts = h
+        .create_subscription_accounts(
+            &keypair.pubkey(),
+            token_account,
+            merchant,
+            &owner,
+            merchant_token_account,
+            DAY,
+        )
+        .await;
+    accounts.platform_fee_account = Some(h.platform_fee_account);
+    let registry_before: Merchant = h.anchor_account(&merchant).await;
+    h.process(
+        ix(
+            lutrii_recurring::ID,
+            accounts,
+            lutrii_recurring::instruction::CreateSubscription {
+                amount,
+                frequency_seconds: DAY,
+                max_per_transaction: amount,
+                lifetime_cap: 12 * amount,
+                merchant_name: "Lutrii Now".to_string(),
+                options: CreateOptions {
+                    charge_immediately: true,
+                    ..Default::default()
+                },
+            },
+        ),
+        &[&keypair],
+    )
+    .await
+    .unwrap();
+
+    // Paid at creation, renewing one period later
+    let user = UserFixture {
+        subscription: subscription_pda(&keypair.pubkey(), &merchant),
+        keypair,
+        token_account,
+    };
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.payment_count, 1);
+    assert_eq!(subscription.total_paid, amount);
+    assert_eq!(subscription.last_payment, subscription.created_at);
+    assert_eq!(subscription.next_payment, subscription.last_payment + DAY);
+
+    let user_account = h.token_account(&token_account).await;
+    assert_eq!(user_account.amount, 100 * USDC - amount);
+    assert_eq!(user_account.delegated_amount, 11 * amount);
+    assert_eq!(h.token_account(&merchant_token_account).await.amount, amount - fee);
+    assert_eq!(h.token_account(&h.platform_fee_account.clone()).await.amount, fee);
+
+    // The up-front charge counts in the merchant's registry stats
+    let registry: Merchant = h.anchor_account(&merchant).await;
+    assert_eq!(registry.total_volume, registry_before.total_volume + amount);
+    assert_eq!(registry.total_transactions, registry_before.total_transactions + 1);
+
+    assert_custom_error(
+        h.execute_payment(&user).await,
+        u32::from(ErrorCode::PaymentNotDue),
+    );
+    h.warp_forward(DAY).await;
+    h.execute_payment(&user).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_setup_fee_counts_against_lifetime_cap() {
+    let mut h = Harness::new().await;
+    let setup_fee = 3 * USDC;
+    let fee = setup_fee * FEE_BASIS_POINTS as u64 / 10_000;
+
+    let keypair = Keypair::new();
+    h.fund(&keypair.pubkey()).await;
+    let token_account = h.create_token_account(&keypair.pubkey()).await;
+    h.mint_to(&token_account, 100 * USDC).await;
+    let owner = h.merchant_owner.pubkey();
+    let (merchant, merchant_token_account) = (h.merchant, h.merchant_token_account);
+    let create = || lutrii_recurring::instruction::CreateSubscription {
+        amount: USDC,
+        frequency_seconds: DAY,
+        max_per_transaction: USDC,
+        lifetime_cap: 12 * USDC,
+        merchant_name: "Lutrii Setup".to_string(),
+        options: CreateOptions {
+            setup_fee,
+            ..Default::default()
+        },
+    };
+
+    // The fee wallet is required to collect anything up front
+    for platform_fee_account in [None, Some(h.platform_fee_account)] {
+        let mut accounts = h
+            .create_subscription_accounts(
+                &keypair.pubkey(),
+                token_account,
+                merchant,
+                &owner,
+                merchant_token_account,
+                DAY,
+            )
+            .await;
+        accounts.platform_fee_account = platform_fee_account;
+        let result = h
+            .process(ix(lutrii_recurring::ID, accounts, create()), &[&keypair])
+            .await;
+        if platform_fee_account.is_none() {
+            assert_custom_error(result, u32::from(ErrorCode::InvalidFeeWallet));
+        } else {
+            result.unwrap();
+        }
+    }
+
+    // Charged once, not a payment, and only the rest of the cap is approved
+    let subscription: Subscription = h
+        .anchor_account(&subscription_pda(&keypair.pubkey(), &merchant))
+        .await;
+    assert_eq!(subscription.setup_fee, setup_fee);
+    assert_eq!(subscription.total_paid, setup_fee);
+    assert_eq!(subscription.payment_count, 0);
+    assert_eq!(subscription.next_payment, subscription.created_at + DAY);
+
+    let user_account = h.token_account(&token_account).await;
+    assert_eq!(user_account.amount, 100 * USDC - setup_fee);
+    assert_eq!(user_account.delegated_amount, 9 * USDC);
+    assert_eq!(h.token_account(&merchant_token_account).await.amount, setup_fee - fee);
+}
+
+#[tokio::test]
+async fn test_billing_anchor_prorates_first_period() {
+    let mut h = Harness::new().await;
+    let clock: Clock = h.ctx.banks_client.get_sysvar().await.unwrap();
+    let anchor = clock.unix_timestamp + 10 * DAY;
+    let owner = h.merchant_owner.insecure_clone();
+    let configure = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::ConfigureMerchantPolicy {
+            merchant_policy: merchant_policy(&h.merchant),
+            merchant: h.merchant,
+            owner: owner.pubkey(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::ConfigureMerchantPolicy {
+            allowlist_only: false,
+            max_active_subscribers: 0,
+            max_snooze_days: 0,
+            billing_anchor: anchor,
+            swap_fallback: SwapFallback::Fail,
+            fee_bearer: FeeBearer::Merchant,
+        },
+    );
+    h.process(configure, &[&owner]).await.unwrap();
+
+    let keypair = Keypair::new();
+    h.fund(&keypair.pubkey()).await;
+    let token_account = h.create_token_account(&keypair.pubkey()).await;
+    h.mint_to(&token_account, 100 * USDC).await;
+    let (merchant, merchant_token_account) = (h.merchant, h.merchant_token_account);
+    let mut accounts = h
+        .create_subscription_accounts(
+            &keypair.pubkey(),
+            token_account,
+            merchant,
+            &owner.pubkey(),
+            merchant_token_account,
+            10 * DAY,
+        )
+        .await;
+    accounts.platform_fee_account = Some(h.platform_fee_account);
+    h.process(
+        ix(
+            lutrii_recurring::ID,
+            accounts,
+            lutrii_recurring::instruction::CreateSubscription {
+                amount: 3 * USDC,
+                frequency_seconds: 30 * DAY,
+                max_per_transaction: 3 * USDC,
+                lifetime_cap: 30 * USDC,
+                merchant_name: "Lutrii Anchored".to_string(),
+                options: CreateOptions::default(),
+            },
+        ),
+        &[&keypair],
+    )
+    .await
+    .unwrap();
+
+    // Ten of thirty days are charged now; full periods start at the anchor
+    let subscription: Subscription = h
+        .anchor_account(&subscription_pda(&keypair.pubkey(), &merchant))
+        .await;
+    assert_eq!(subscription.next_payment, anchor);
+    assert_eq!(subscription.total_paid, USDC);
+    assert_eq!(subscription.payment_count, 1);
+
+    let user_account = h.token_account(&token_account).await;
+    assert_eq!(user_account.amount, 99 * USDC);
+    assert_eq!(user_account.delegated_amount, 29 * USDC);
+}
+
+#[tokio::test]
+async fn test_billing_day_lands_on_the_same_day_each_month() {
+    const NEW_YORK: i32 = -5 * 3_600;
+    let mut h = Harness::new().await;
+    let amount = 3 * USDC;
+    let clock: Clock = h.ctx.banks_client.get_sysvar().await.unwrap();
+    let (previous, first_due) =
+        schedule::billing_day_bounds(clock.unix_timestamp, 31, NEW_YORK).unwrap();
+
+    let keypair = Keypair::new();
+    h.fund(&keypair.pubkey()).await;
+    let token_account = h.create_token_account(&keypair.pubkey()).await;
+    h.mint_to(&token_account, 100 * USDC).await;
+    let owner = h.merchant_owner.pubkey();
+    let (merchant, merchant_token_account) = (h.merchant, h.merchant_token_account);
+    let mut accounts = h
+        .create_subscription_accounts(
+            &keypair.pubkey(),
+            token_account,
+            merchant,
+            &owner,
+            merchant_token_account,
+            first_due - clock.unix_timestamp,
+        )
+        .await;
+    accounts.platform_fee_account = Some(h.platform_fee_account);
+    h.process(
+        ix(
+            lutrii_recurring::ID,
+            accounts,
+            lutrii_recurring::instruction::CreateSubscription {
+                amount,
+                frequency_seconds: 30 * DAY,
+                max_per_transaction: amount,
+                lifetime_cap: 12 * amount,
+                merchant_name: "Lutrii Monthly".to_string(),
+                options: CreateOptions {
+                    billing_day: 31,
+                    utc_offset_seconds: NEW_YORK,
+                    ..Default::default()
+                },
+            },
+        ),
+        &[&keypair],
+    )
+    .await
+    .unwrap();
+
+    // The partial month to the first billing day is charged at signup
+    let user = UserFixture {
+        subscription: subscription_pda(&keypair.pubkey(), &merchant),
+        keypair,
+        token_account,
+    };
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    let prorated =
+        proration::prorate(amount, first_due - clock.unix_timestamp, first_due - previous).unwrap();
+    assert_eq!(subscription.billing_day, 31);
+    assert_eq!((first_due + NEW_YORK as i64) % DAY, 0);
+    assert_eq!(subscription.next_payment, first_due);
+    assert_eq!(subscription.total_paid, prorated);
+
+    // A late execution still schedules the following billing day at local
+    // midnight, clamped to the end of shorter months
+    h.warp_forward(first_due - clock.unix_timestamp + DAY).await;
+    h.execute_payment(&user).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    let (_, second_due) = schedule::billing_day_bounds(first_due, 31, NEW_YORK).unwrap();
+    assert_eq!(subscription.next_payment, second_due);
+    assert_eq!(subscription.total_paid, prorated + amount);
+
+    // Billing days are only for monthly subscriptions
+    let weekly = Keypair::new();
+    h.fund(&weekly.pubkey()).await;
+    let token_account = h.create_token_account(&weekly.pubkey()).await;
+    let accounts = h
+        .create_subscription_accounts(
+            &weekly.pubkey(),
+            token_account,
+            merchant,
+            &owner,
+            merchant_token_account,
+            7 * DAY,
+        )
+        .await;
+    assert_custom_error(
+        h.process(
+            ix(
+                lutrii_recurring::ID,
+                accounts,
+                lutrii_recurring::instruction::CreateSubscription {
+                    amount,
+                    frequency_seconds: 7 * DAY,
+                    max_per_transaction: amount,
+                    lifetime_cap: 12 * amount,
+                    merchant_name: "Lutrii Weekly".to_string(),
+                    options: CreateOptions {
+                        billing_day: 1,
+                        ..Default::default()
+                    },
+                },
+            ),
+            &[&weekly],
+        )
+        .await,
+        u32::from(ErrorCode::InvalidBillingDay),
+    );
+}
+
+#[tokio::test]
+async fn test_billing_weekday_lands_on_the_same_weekday() {
+    const MONDAY: u8 = 1;
+    let mut h = Harness::new().await;
+    let amount = 7 * USDC;
+    let clock: Clock = h.ctx.banks_client.get_sysvar().await.unwrap();
+    let first_due = schedule::next_weekday(clock.unix_timestamp, MONDAY, NEW_YORK).unwrap();
+
+    let keypair = Keypair::new();
+    h.fund(&keypair.pubkey()).await;
+    let token_account = h.create_token_account(&keypair.pubkey()).await;
+    h.mint_to(&token_account, 100 * USDC).await;
+    let owner = h.merchant_owner.pubkey();
+    let (merchant, merchant_token_account) = (h.merchant, h.merchant_token_account);
+    let mut accounts = h
+        .create_subscription_accounts(
+            &keypair.pubkey(),
+            token_account,
+            merchant,
+            &owner,
+            merchant_token_account,
+            first_due - clock.unix_timestamp,
+        )
+        .await;
+    accounts.platform_fee_account = Some(h.platform_fee_account);
+    let create = |frequency_seconds: i64, options: CreateOptions| lutrii_recurring::instruction::CreateSubscription {
+        amount,
+        frequency_seconds,
+        max_per_transaction: amount,
+        lifetime_cap: 12 * amount,
+        merchant_name: "Lutrii Fortnightly".to_string(),
+        options,
+    };
+    let weekday = |billing_weekday: u8| CreateOptions {
+        billing_weekday,
+        utc_offset_seconds: NEW_YORK,
+        ..Default::default()
+    };
+    let fortnightly = ix(lutrii_recurring::ID, accounts, create(14 * DAY, weekday(MONDAY)));
+    let with = |data: lutrii_recurring::instruction::CreateSubscription| Instruction {
+        data: data.data(),
+        ..fortnightly.clone()
+    };
+
+    // Only whole-week schedules without a billing day or trial take a weekday
+    let ten_days = with(create(10 * DAY, weekday(MONDAY)));
+    let eighth_day = with(create(14 * DAY, weekday(8)));
+    let with_billing_day = with(create(14 * DAY, CreateOptions { billing_day: 1, ..weekday(MONDAY) }));
+    let with_trial = with(create(14 * DAY, CreateOptions { trial_seconds: 7 * DAY, ..weekday(MONDAY) }));
+    for (bad, error) in [
+        (ten_days, ErrorCode::InvalidBillingWeekday),
+        (eighth_day, ErrorCode::InvalidBillingWeekday),
+        (with_billing_day, ErrorCode::InvalidBillingDay),
+        (with_trial, ErrorCode::InvalidTrialPeriod),
+    ] {
+        assert_custom_error(h.process(bad, &[&keypair]).await, u32::from(error));
+    }
+
+    // The partial period to the first Monday is charged at signup
+    h.process(fortnightly, &[&keypair]).await.unwrap();
+    let user = UserFixture {
+        subscription: subscription_pda(&keypair.pubkey(), &merchant),
+        keypair,
+        token_account,
+    };
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    let prorated = proration::prorate(amount, first_due - clock.unix_timestamp, 14 * DAY).unwrap();
+    assert_eq!(subscription.billing_weekday, MONDAY);
+    // Local midnight on a Monday; 1970-01-01 was a Thursday
+    assert_eq!((first_due + NEW_YORK as i64) % DAY, 0);
+    assert_eq!(((first_due + NEW_YORK as i64) / DAY + 3) % 7, 0);
+    assert_eq!(subscription.next_payment, first_due);
+    assert_eq!(subscription.total_paid, prorated);
+
+    // A late execution still schedules the Monday two weeks on
+    h.warp_forward(first_due - clock.unix_timestamp + DAY).await;
+    h.execute_payment(&user).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.next_payment, first_due + 14 * DAY);
+    assert_eq!(subscription.total_paid, prorated + amount);
+}
+
+#[tokio::test]
+async fn test_slot_schedule_follows_slots_not_wall_clock() {
+    const HOUR_IN_SLOTS: i64 = 9_000;
+    let mut h = Harness::new().await;
+    let keypair = Keypair::new();
+    h.fund(&keypair.pubkey()).await;
+    let token_account = h.create_token_account(&keypair.pubkey()).await;
+    h.mint_to(&token_account, 100 * USDC).await;
+    let owner = h.merchant_owner.pubkey();
+    let (merchant, merchant_token_account) = (h.merchant, h.merchant_token_account);
+    let mut accounts = h
+        .create_subscription_accounts(
+            &keypair.pubkey(),
+            token_account,
+            merchant,
+            &owner,
+            merchant_token_account,
+            DAY,
+        )
+        .await;
+    // Due buckets index unix days; slot schedules are never listed
+    accounts.next_due_bucket = None;
+    h.process(
+        ix(
+            lutrii_recurring::ID,
+            accounts,
+            lutrii_recurring::instruction::CreateSubscription {
+                amount: USDC,
+                frequency_seconds: HOUR_IN_SLOTS,
+                max_per_transaction: USDC,
+                lifetime_cap: 12 * USDC,
+                merchant_name: "Lutrii Epochs".to_string(),
+                options: CreateOptions {
+                    schedule_unit: ScheduleUnit::Slots,
+                    ..Default::default()
+                },
+            },
+        ),
+        &[&keypair],
+    )
+    .await
+    .unwrap();
+    let user = UserFixture {
+        subscription: subscription_pda(&keypair.pubkey(), &merchant),
+        keypair,
+        token_account,
+    };
+    let clock: Clock = h.ctx.banks_client.get_sysvar().await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.schedule_unit, ScheduleUnit::Slots);
+    assert!(subscription.next_payment > clock.slot as i64);
+    assert!(subscription.next_payment <= clock.slot as i64 + HOUR_IN_SLOTS);
+
+    // Wall-clock time alone does not make a slot schedule due
+    h.warp_forward(2 * DAY).await;
+    assert_custom_error(
+        h.execute_payment(&user).await,
+        u32::from(ErrorCode::PaymentNotDue),
+    );
+
+    h.ctx.warp_to_slot(subscription.next_payment as u64).unwrap();
+    h.execute_payment(&user).await.unwrap();
+    let clock: Clock = h.ctx.banks_client.get_sysvar().await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.payment_count, 1);
+    assert_eq!(subscription.next_payment, clock.slot as i64 + HOUR_IN_SLOTS);
+}
+
+#[tokio::test]
+async fn test_subscriptions_settle_in_merchant_currency() {
+    async fn set_settlement_token(h: &mut Harness, settlement_token: Pubkey) {
+        let merchant = h.merchant;
+        let mut data: Merchant = h.anchor_account(&merchant).await;
+        data.settlement_token = settlement_token;
+        let mut bytes = Vec::new();
+        data.try_serialize(&mut bytes).unwrap();
+        let mut account = h.account(&merchant).await.unwrap();
+        account.data[..bytes.len()].copy_from_slice(&bytes);
+        h.ctx.set_account(&merchant, &account.into());
+    }
+
+    let mut h = Harness::new().await;
+
+    // A merchant settling in another stablecoin cannot be paid into this mint
+    set_settlement_token(&mut h, Pubkey::new_unique()).await;
+    assert_custom_error(
+        h.try_subscribe(USDC, DAY).await.map(|_| ()),
+        u32::from(ErrorCode::SettlementCurrencyMismatch),
+    );
+
+    let mint = h.mint;
+    set_settlement_token(&mut h, mint).await;
+    let user = h.subscribe(USDC, DAY).await;
+    h.warp_forward(DAY).await;
+    h.execute_payment(&user).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_execute_payment_rejects_unconfigured_fee_account() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    h.warp_forward(DAY).await;
+
+    // A cranker cannot redirect the platform fee to their own token account
+    let cranker = Keypair::new();
+    h.platform_fee_account = h.create_token_account(&cranker.pubkey()).await;
+    assert_custom_error(
+        h.execute_payment(&user).await,
+        u32::from(ErrorCode::InvalidFeeWallet),
+    );
+
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.payment_count, 0);
+}
+
+#[tokio::test]
+async fn test_token_program_pause_is_isolated() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    let set_pause = |token_program: Pubkey, paused: bool| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::AdminAction {
+                platform_state: platform_state(),
+                authority: h.ctx.payer.pubkey(),
+                audit_log: None,
+            },
+            lutrii_recurring::instruction::SetTokenProgramPause {
+                token_program,
+                paused,
+            },
+        )
+    };
+    let pause_2022 = set_pause(anchor_spl::token_2022::ID, true);
+    let pause_spl = set_pause(spl_token::id(), true);
+    let resume_spl = set_pause(spl_token::id(), false);
+    h.warp_forward(DAY).await;
+
+    // A Token-2022 incident leaves legacy SPL Token payments running
+    h.process(pause_2022, &[]).await.unwrap();
+    let platform: PlatformState = h.anchor_account(&platform_state()).await;
+    assert!(platform.token_2022_paused && !platform.spl_token_paused);
+
+    h.process(pause_spl, &[]).await.unwrap();
+    assert_custom_error(
+        h.execute_payment(&user).await,
+        u32::from(ErrorCode::TokenProgramPaused),
+    );
+
+    h.process(resume_spl, &[]).await.unwrap();
+    h.execute_payment(&user).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.payment_count, 1);
+}
+
+#[tokio::test]
+async fn test_stale_layout_refused_until_migrated() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    let mut subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.layout_version, Subscription::CURRENT_VERSION);
+
+    // Rewind the stamp as if the account predated layout versioning
+    subscription.layout_version = 0;
+    let mut data = Vec::new();
+    subscription.try_serialize(&mut data).unwrap();
+    let mut account = h.account(&user.subscription).await.unwrap();
+    account.data[..data.len()].copy_from_slice(&data);
+    h.ctx.set_account(&user.subscription, &account.into());
+
+    h.warp_forward(DAY).await;
+    assert_custom_error(
+        h.execute_payment(&user).await,
+        u32::from(ErrorCode::SubscriptionMigrationRequired),
+    );
+
+    let migrate = || {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::MigrateSubscription {
+                subscription: user.subscription,
+                escrow: escrow_pda(&user.subscription),
+                payer: h.ctx.payer.pubkey(),
+                system_program: system_program::id(),
+            },
+            lutrii_recurring::instruction::MigrateSubscription {},
+        )
+    };
+    let (first, second) = (migrate(), migrate());
+    h.process(first, &[]).await.unwrap();
+    assert_custom_error(
+        h.process(second, &[]).await,
+        u32::from(ErrorCode::AlreadyMigrated),
+    );
+
+    h.execute_payment(&user).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.layout_version, Subscription::CURRENT_VERSION);
+    assert_eq!(subscription.payment_count, 1);
+}
+
+#[tokio::test]
+async fn test_execute_payment_within_compute_budget() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    h.warp_forward(DAY).await;
+
+    // Keepers request this budget per payment, so exceeding it fails batches
+    let instruction = h.execute_payment_ix(&user).await;
+    let units = h.compute_units(instruction).await;
+    // Run with --nocapture to read the figure recorded next to the budget
+    println!("execute_payment used {} of {} compute units", units, EXECUTE_PAYMENT_COMPUTE_UNITS);
+    assert!(
+        units <= EXECUTE_PAYMENT_COMPUTE_UNITS as u64,
+        "execute_payment used {} compute units",
+        units
+    );
+}
+
+#[tokio::test]
+async fn test_denylisted_merchant_cannot_receive_payments() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    h.warp_forward(DAY).await;
+
+    let admin = h.ctx.payer.pubkey();
+    let merchant_wallet = h.merchant_owner.pubkey();
+    let deny = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::AddToDenylist {
+            denylist_entry: denylist_entry(&merchant_wallet),
+            platform_state: platform_state(),
+            authority: admin,
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::AddToDenylist {
+            wallet: merchant_wallet,
+        },
+    );
+    h.process(deny, &[]).await.unwrap();
+
+    assert_custom_error(
+        h.execute_payment(&user).await,
+        u32::from(ErrorCode::WalletDenylisted),
+    );
+
+    // Lifting the entry restores collection
+    let lift = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::RemoveFromDenylist {
+            denylist_entry: denylist_entry(&merchant_wallet),
+            platform_state: platform_state(),
+            authority: admin,
+        },
+        lutrii_recurring::instruction::RemoveFromDenylist {},
+    );
+    h.process(lift, &[]).await.unwrap();
+    h.execute_payment(&user).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_blocked_user_payments_refused_until_cancelled() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    h.warp_forward(DAY).await;
+
+    let owner = h.merchant_owner.insecure_clone();
+    let block = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::BlockUser {
+            blocklist_entry: blocklist_entry(&h.merchant, &user.keypair.pubkey()),
+            merchant: h.merchant,
+            owner: owner.pubkey(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::BlockUser {
+            user: user.keypair.pubkey(),
+        },
+    );
+    h.process(block, &[&owner]).await.unwrap();
+
+    assert_custom_error(
+        h.execute_payment(&user).await,
+        u32::from(ErrorCode::UserBlockedByMerchant),
+    );
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.payment_count, 0);
+}
+
+#[tokio::test]
+async fn test_merchant_subscriber_cap() {
+    let mut h = Harness::new().await;
+    let owner = h.merchant_owner.insecure_clone();
+    let configure = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::ConfigureMerchantPolicy {
+            merchant_policy: merchant_policy(&h.merchant),
+            merchant: h.merchant,
+            owner: owner.pubkey(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::ConfigureMerchantPolicy {
+            allowlist_only: false,
+            max_active_subscribers: 1,
+            max_snooze_days: 0,
+            billing_anchor: 0,
+            swap_fallback: SwapFallback::Fail,
+            fee_bearer: FeeBearer::Merchant,
+        },
+    );
+    h.process(configure, &[&owner]).await.unwrap();
+
+    let first = h.subscribe(USDC, DAY).await;
+    assert_custom_error(
+        h.try_subscribe(USDC, DAY).await.map(|_| ()),
+        u32::from(ErrorCode::MerchantAtCapacity),
+    );
+
+    // Cancelling frees the slot
+    let cancel = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::CancelSubscription {
+            subscription: first.subscription,
+            platform_state: platform_state(),
+            merchant_policy: merchant_policy(&h.merchant),
+            user_token_account: first.token_account,
+            user_delegate: user_delegate(&first.token_account),
+            user: first.keypair.pubkey(),
+            token_program: spl_token::id(),
+            user_stats: None,
+            due_bucket: None,
+            revenue_forecast: None,
+        },
+        lutrii_recurring::instruction::CancelSubscription {},
+    );
+    h.process(cancel, &[&first.keypair]).await.unwrap();
+    h.subscribe(USDC, DAY).await;
+
+    let policy: MerchantPolicy = h.anchor_account(&merchant_policy(&h.merchant)).await;
+    assert_eq!(policy.active_subscribers, 1);
+}
+
+#[tokio::test]
+async fn test_delegation_check_and_refresh() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+
+    let check = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::CheckDelegation {
+            subscription: user.subscription,
+            platform_state: platform_state(),
+            user_token_account: user.token_account,
+            user_delegate: user_delegate(&user.token_account),
+            merchant_policy: merchant_policy(&h.merchant),
+        },
+        lutrii_recurring::instruction::CheckDelegation {},
+    );
+    h.process(check.clone(), &[]).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert!(subscription.delegation_healthy);
+
+    // Another dApp (or the user) clears the delegate
+    let revoke = spl_token::instruction::revoke(
+        &spl_token::id(),
+        &user.token_account,
+        &user.keypair.pubkey(),
+        &[],
+    )
+    .unwrap();
+    h.process(revoke, &[&user.keypair]).await.unwrap();
+
+    h.process(check.clone(), &[]).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert!(!subscription.delegation_healthy);
+
+    // Refresh restores the remaining lifetime allowance without touching caps
+    let refresh = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::RefreshDelegation {
+            subscription: user.subscription,
+            user_token_account: user.token_account,
+            user_delegate: user_delegate(&user.token_account),
+            user: user.keypair.pubkey(),
+            token_program: spl_token::id(),
+            swap_asset: None,
+            price_feed: None,
+            mint: None,
+            settlement_mint: None,
+            merchant_token_account: None,
+        },
+        lutrii_recurring::instruction::RefreshDelegation {},
+    );
+    h.process(refresh, &[&user.keypair]).await.unwrap();
+    h.process(check, &[]).await.unwrap();
+
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert!(subscription.delegation_healthy);
+    assert_eq!(subscription.lifetime_cap, 50 * USDC);
+    let token = h.token_account(&user.token_account).await;
+    assert_eq!(token.delegated_amount, subscription.lifetime_cap - subscription.total_paid);
+}
+
+#[tokio::test]
+async fn test_failed_collection_backs_off_until_retry_time() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+
+    let revoke = spl_token::instruction::revoke(
+        &spl_token::id(),
+        &user.token_account,
+        &user.keypair.pubkey(),
+        &[],
+    )
+    .unwrap();
+    h.process(revoke, &[&user.keypair]).await.unwrap();
+
+    // The failed attempt is recorded rather than reverted
+    h.warp_forward(DAY).await;
+    h.execute_payment(&user).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.retry_count, 1);
+    assert_eq!(subscription.payment_count, 0);
+    assert!(subscription.next_retry_at > subscription.next_payment);
+
+    // Keepers cannot retry before the backoff elapses
+    assert!(h.execute_payment(&user).await.is_err());
+
+    // Still delinquent: the next delay doubles
+    h.warp_forward(3_600).await;
+    h.execute_payment(&user).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.retry_count, 2);
+
+    let refresh = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::RefreshDelegation {
+            subscription: user.subscription,
+            user_token_account: user.token_account,
+            user_delegate: user_delegate(&user.token_account),
+            user: user.keypair.pubkey(),
+            token_program: spl_token::id(),
+            swap_asset: None,
+            price_feed: None,
+            mint: None,
+            settlement_mint: None,
+            merchant_token_account: None,
+        },
+        lutrii_recurring::instruction::RefreshDelegation {},
+    );
+    h.process(refresh, &[&user.keypair]).await.unwrap();
+
+    h.warp_forward(2 * 3_600).await;
+    h.execute_payment(&user).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.payment_count, 1);
+    assert_eq!(subscription.retry_count, 0);
+    assert_eq!(subscription.next_retry_at, 0);
+}
+
+#[tokio::test]
+async fn test_retry_policy_makes_subscriptions_delinquent() {
+    let mut h = Harness::new().await;
+    let owner = h.merchant_owner.insecure_clone();
+    let merchant = h.merchant;
+    let set_retry_policy = |retry_policy: RetryPolicy| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::SetRetryPolicy {
+                merchant_policy: merchant_policy(&merchant),
+                merchant,
+                owner: owner.pubkey(),
+                system_program: system_program::ID,
+            },
+            lutrii_recurring::instruction::SetRetryPolicy { retry_policy },
+        )
+    };
+    let pause_after_two = set_retry_policy(RetryPolicy {
+        max_failed_attempts: 2,
+        delinquency_action: DelinquencyAction::Pause,
+        base_delay_seconds: 900,
+    });
+    let cancel_after_one = set_retry_policy(RetryPolicy {
+        max_failed_attempts: 1,
+        delinquency_action: DelinquencyAction::Cancel,
+        base_delay_seconds: 0,
+    });
+    let too_fast = set_retry_policy(RetryPolicy {
+        max_failed_attempts: 1,
+        delinquency_action: DelinquencyAction::Pause,
+        base_delay_seconds: 60,
+    });
+    assert_custom_error(
+        h.process(too_fast, &[&owner]).await,
+        u32::from(ErrorCode::InvalidRetryPolicy),
+    );
+    h.process(pause_after_two, &[&owner]).await.unwrap();
+
+    let user = h.subscribe(USDC, DAY).await;
+    let revoke = spl_token::instruction::revoke(
+        &spl_token::id(),
+        &user.token_account,
+        &user.keypair.pubkey(),
+        &[],
+    )
+    .unwrap();
+    h.process(revoke, &[&user.keypair]).await.unwrap();
+
+    // The first failure retries after the merchant's 15 minute base delay
+    h.warp_forward(DAY).await;
+    h.execute_payment(&user).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.retry_count, 1);
+    assert_eq!(subscription.next_retry_at, subscription.last_failure_at + 900);
+
+    // The second pauses the subscription; leaving the policy out to retry
+    // under the default policy is refused
+    h.warp_forward(900).await;
+    let policy = merchant_policy(&merchant);
+    let mut without_policy = h.execute_payment_ix(&user).await;
+    substitute_account(&mut without_policy, &policy, lutrii_recurring::ID);
+    assert_custom_error(
+        h.process(without_policy, &[]).await,
+        anchor_lang::error::ErrorCode::ConstraintSeeds as u32,
+    );
+    h.execute_payment(&user).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert!(subscription.is_paused);
+    assert!(subscription.is_active);
+    assert_eq!(subscription.retry_count, 0);
+
+    // Cancelling merchants end the subscription outright
+    h.process(cancel_after_one, &[&owner]).await.unwrap();
+    let second = h.subscribe(USDC, DAY).await;
+    let revoke = spl_token::instruction::revoke(
+        &spl_token::id(),
+        &second.token_account,
+        &second.keypair.pubkey(),
+        &[],
+    )
+    .unwrap();
+    h.process(revoke, &[&second.keypair]).await.unwrap();
+    h.warp_forward(DAY).await;
+    h.execute_payment(&second).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&second.subscription).await;
+    assert!(!subscription.is_active);
+    assert!(subscription.last_failure_at > 0);
+}
+
+#[tokio::test]
+async fn test_execution_claim_reserves_payment_for_claimant() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    let (first, second) = (Keypair::new(), Keypair::new());
+    let claim = |keeper: &Keypair| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::ClaimExecution {
+                subscription: user.subscription,
+                platform_state: platform_state(),
+                keeper: keeper.pubkey(),
+            },
+            lutrii_recurring::instruction::ClaimExecution {},
+        )
+    };
+
+    // Only due payments can be claimed
+    assert_custom_error(
+        h.process(claim(&first), &[&first]).await,
+        u32::from(ErrorCode::PaymentNotDue),
+    );
+
+    h.warp_forward(DAY).await;
+    h.process(claim(&first), &[&first]).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.execution_claimant, first.pubkey());
+
+    // Neither a competing claim nor an unclaimed execution gets through
+    assert_custom_error(
+        h.process(claim(&second), &[&second]).await,
+        u32::from(ErrorCode::ExecutionClaimed),
+    );
+    assert_custom_error(
+        h.execute_payment(&user).await,
+        u32::from(ErrorCode::ExecutionClaimed),
+    );
+
+    // The claimant executes, which spends the claim
+    let mut execute = h.execute_payment_ix(&user).await;
+    *execute.accounts.last_mut().unwrap() = AccountMeta::new_readonly(first.pubkey(), true);
+    h.process(execute, &[&first]).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.payment_count, 1);
+    assert_eq!(subscription.claim_expires_slot, 0);
+}
+
+#[tokio::test]
+async fn test_priority_reserve_holds_volume_back_from_standard_merchants() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    let set_reserve = |authority: Pubkey, priority_reserve_bps: u16| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::AdminAction {
+                platform_state: platform_state(),
+                authority,
+                audit_log: None,
+            },
+            lutrii_recurring::instruction::SetPriorityReserve { priority_reserve_bps },
+        )
+    };
+    let admin = h.ctx.payer.pubkey();
+
+    // The whole limit is reserved, and the test merchant is not Community tier
+    h.process(set_reserve(admin, 10_000), &[]).await.unwrap();
+    h.warp_forward(DAY).await;
+    assert_custom_error(
+        h.execute_payment(&user).await,
+        u32::from(ErrorCode::VelocityExceeded),
+    );
+
+    h.process(set_reserve(admin, 0), &[]).await.unwrap();
+    h.execute_payment(&user).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.payment_count, 1);
+}
+
+#[tokio::test]
+async fn test_uncollected_cycle_skipped_after_window() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    let set_window = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::AdminAction {
+            platform_state: platform_state(),
+            authority: h.ctx.payer.pubkey(),
+            audit_log: None,
+        },
+        lutrii_recurring::instruction::SetCollectionWindow {
+            collection_window_seconds: 3_600,
+        },
+    );
+    h.process(set_window, &[]).await.unwrap();
+    let missed: Subscription = h.anchor_account(&user.subscription).await;
+
+    // The keeper was down for a whole cycle: the missed one is skipped, not charged
+    h.warp_forward(2 * DAY).await;
+    h.execute_payment(&user).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.payment_count, 0);
+    assert_eq!(subscription.total_paid, 0);
+    assert_eq!(subscription.next_payment, missed.next_payment + DAY);
+
+    // The current cycle is still inside its window and collects once
+    h.execute_payment(&user).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.payment_count, 1);
+    assert_eq!(subscription.total_paid, USDC);
+}
+
+#[tokio::test]
+async fn test_missed_payment_policy_accumulates_or_cancels() {
+    let mut h = Harness::new().await;
+    let set_window = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::AdminAction {
+            platform_state: platform_state(),
+            authority: h.ctx.payer.pubkey(),
+            audit_log: None,
+        },
+        lutrii_recurring::instruction::SetCollectionWindow {
+            collection_window_seconds: 3_600,
+        },
+    );
+    h.process(set_window, &[]).await.unwrap();
+
+    let accumulating = h.subscribe(USDC, DAY).await;
+    let cancelling = h.subscribe(USDC, DAY).await;
+    for (user, policy) in [
+        (&accumulating, MissedPaymentPolicy::Accumulate { max_cycles: 2 }),
+        (&cancelling, MissedPaymentPolicy::AutoCancel),
+    ] {
+        let set_policy = ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::SetMissedPaymentPolicy {
+                subscription: user.subscription,
+                user: user.keypair.pubkey(),
+            },
+            lutrii_recurring::instruction::SetMissedPaymentPolicy { policy },
+        );
+        h.process(set_policy, &[&user.keypair]).await.unwrap();
+    }
+    let missed: Subscription = h.anchor_account(&accumulating.subscription).await;
+
+    // Three cycles are due: the oldest is skipped, two are caught up one by one
+    h.warp_forward(3 * DAY).await;
+    h.execute_payment(&accumulating).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&accumulating.subscription).await;
+    assert_eq!(subscription.payment_count, 0);
+    assert_eq!(subscription.next_payment, missed.next_payment + DAY);
+
+    h.execute_payment(&accumulating).await.unwrap();
+    h.execute_payment(&accumulating).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&accumulating.subscription).await;
+    assert_eq!(subscription.payment_count, 2);
+    assert_eq!(subscription.total_paid, 2 * USDC);
+    assert_eq!(subscription.next_payment, missed.next_payment + 3 * DAY);
+    assert_custom_error(
+        h.execute_payment(&accumulating).await,
+        u32::from(ErrorCode::PaymentNotDue),
+    );
+
+    // The closed window cancels the other subscription instead of charging it
+    h.execute_payment(&cancelling).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&cancelling.subscription).await;
+    assert!(!subscription.is_active);
+    assert_eq!(subscription.total_paid, 0);
+    let delegate: UserDelegate = h.anchor_account(&user_delegate(&cancelling.token_account)).await;
+    assert_eq!(delegate.active_subscriptions, 0);
+}
+
+#[tokio::test]
+async fn test_subscriptions_share_user_delegate() {
+    let mut h = Harness::new().await;
+    let first = h.subscribe(USDC, DAY).await;
+
+    // Second merchant, same user token account
+    let other_owner = Keypair::new();
+    let other_merchant = h.register_merchant(&other_owner).await;
+    let other_token_account = h.create_token_account(&other_owner.pubkey()).await;
+    let second = h
+        .subscribe_with(
+            &first.keypair,
+            first.token_account,
+            other_merchant,
+            &other_owner.pubkey(),
+            other_token_account,
+            2 * USDC,
+            DAY,
+        )
+        .await
+        .unwrap();
+
+    let token = h.token_account(&first.token_account).await;
+    assert_eq!(
+        Option::<Pubkey>::from(token.delegate),
+        Some(user_delegate(&first.token_account))
+    );
+    assert_eq!(token.delegated_amount, 50 * USDC + 100 * USDC);
+
+    // The second approval did not break the first subscription
+    h.warp_forward(DAY).await;
+    h.execute_payment(&first).await.unwrap();
+
+    // Cancelling one subscription keeps the other's allowance approved
+    let cancel = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::CancelSubscription {
+            subscription: second,
+            platform_state: platform_state(),
+            merchant_policy: merchant_policy(&other_merchant),
+            user_token_account: first.token_account,
+            user_delegate: user_delegate(&first.token_account),
+            user: first.keypair.pubkey(),
+            token_program: spl_token::id(),
+            user_stats: None,
+            due_bucket: None,
+            revenue_forecast: None,
+        },
+        lutrii_recurring::instruction::CancelSubscription {},
+    );
+    h.process(cancel, &[&first.keypair]).await.unwrap();
+
+    let token = h.token_account(&first.token_account).await;
+    assert_eq!(token.delegated_amount, 49 * USDC);
+    let delegate: UserDelegate = h.anchor_account(&user_delegate(&first.token_account)).await;
+    assert_eq!(delegate.outstanding_allowance, 49 * USDC);
+    assert_eq!(delegate.active_subscriptions, 1);
+}
+
+#[tokio::test]
+async fn test_user_stats_track_spend_and_active_subscriptions() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    let stats = user_stats_pda(&user.keypair.pubkey());
+
+    // Existing subscriptions seed the active count
+    let mut init = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::InitUserStats {
+            user_stats: stats,
+            user: user.keypair.pubkey(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::InitUserStats {},
+    );
+    init.accounts
+        .push(AccountMeta::new_readonly(user.subscription, false));
+    h.process(init, &[&user.keypair]).await.unwrap();
+
+    let user_stats: UserStats = h.anchor_account(&stats).await;
+    assert_eq!(user_stats.active_subscriptions, 1);
+
+    h.warp_forward(DAY).await;
+    h.execute_payment(&user).await.unwrap();
+    h.warp_forward(DAY).await;
+    h.execute_payment(&user).await.unwrap();
+
+    let clock: Clock = h.ctx.banks_client.get_sysvar().await.unwrap();
+    let user_stats: UserStats = h.anchor_account(&stats).await;
+    assert_eq!(user_stats.payment_count, 2);
+    assert_eq!(user_stats.lifetime_spend, 2 * USDC);
+    assert_eq!(user_stats.month_spend, 2 * USDC);
+    assert_eq!(user_stats.last_30_days_spend(clock.unix_timestamp), 2 * USDC);
+
+    let cancel = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::CancelSubscription {
+            subscription: user.subscription,
+            platform_state: platform_state(),
+            merchant_policy: merchant_policy(&h.merchant),
+            user_token_account: user.token_account,
+            user_delegate: user_delegate(&user.token_account),
+            user: user.keypair.pubkey(),
+            token_program: spl_token::id(),
+            user_stats: Some(stats),
+            due_bucket: None,
+            revenue_forecast: None,
+        },
+        lutrii_recurring::instruction::CancelSubscription {},
+    );
+    h.process(cancel, &[&user.keypair]).await.unwrap();
+
+    let user_stats: UserStats = h.anchor_account(&stats).await;
+    assert_eq!(user_stats.active_subscriptions, 0);
+}
+
+#[tokio::test]
+async fn test_batched_settlement_accrues_then_sweeps() {
+    let mut h = Harness::new().await;
+    let settlement = settlement_pda(&h.merchant, &h.mint);
+    let vault = settlement_vault_pda(&settlement);
+
+    let configure = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::ConfigureSettlement {
+            settlement,
+            vault,
+            destination: h.merchant_token_account,
+            merchant: h.merchant,
+            owner: h.merchant_owner.pubkey(),
+            mint: h.mint,
+            token_program: spl_token::id(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::ConfigureSettlement { enabled: true },
+    );
+    let owner = h.merchant_owner.insecure_clone();
+    h.process(configure, &[&owner]).await.unwrap();
+
+    let first = h.subscribe(USDC, DAY).await;
+    let second = h.subscribe(2 * USDC, DAY).await;
+    h.warp_forward(DAY).await;
+    h.execute_payment(&first).await.unwrap();
+    h.execute_payment(&second).await.unwrap();
+
+    // Merchant legs accrue in the vault, not the merchant account
+    let before = h.token_account(&h.merchant_token_account).await.amount;
+    let accrued = h.token_account(&vault).await.amount;
+    let state: SettlementVault = h.anchor_account(&settlement).await;
+    assert!(accrued > 0);
+    assert_eq!(state.accrued, accrued);
+
+    let sweep = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::SweepSettlement {
+            settlement,
+            vault,
+            destination: h.merchant_token_account,
+            mint: h.mint,
+            token_program: spl_token::id(),
+            refund_liability: None,
+        },
+        lutrii_recurring::instruction::SweepSettlement {},
+    );
+    h.process(sweep.clone(), &[]).await.unwrap();
+
+    assert_eq!(h.token_account(&vault).await.amount, 0);
+    assert_eq!(
+        h.token_account(&h.merchant_token_account).await.amount,
+        before + accrued
+    );
+    let state: SettlementVault = h.anchor_account(&settlement).await;
+    assert_eq!(state.accrued, 0);
+    assert_eq!(state.total_swept, accrued);
+
+    // Nothing left to move
+    assert!(h.process(sweep, &[]).await.is_err());
+}
+
+#[tokio::test]
+async fn test_verify_active_subscription_reports_access() {
+    let mut h = Harness::new().await;
+    let stranger = Pubkey::new_unique();
+    let access = h.verify_access(&stranger, None).await;
+    assert_eq!(access.status, AccessStatus::NotSubscribed);
+    assert!(!access.has_access);
+
+    let user = h.subscribe(USDC, 7 * DAY).await;
+    let wallet = user.keypair.pubkey();
+    let access = h.verify_access(&wallet, None).await;
+    assert_eq!(access.status, AccessStatus::Active);
+    assert!(access.has_access);
+    assert_eq!(access.subscription, user.subscription);
+    let access = h.verify_access(&wallet, Some(Pubkey::new_unique())).await;
+    assert_eq!(access.status, AccessStatus::PlanMismatch);
+
+    // Access lapses while a payment is outstanding and returns once collected
+    h.warp_forward(7 * DAY).await;
+    let due = h.verify_access(&wallet, None).await;
+    assert_eq!(due.status, AccessStatus::PaymentDue);
+    assert!(!due.has_access);
+    h.execute_payment(&user).await.unwrap();
+    let access = h.verify_access(&wallet, None).await;
+    assert_eq!(access.status, AccessStatus::Active);
+    assert!(access.paid_through > due.paid_through);
+    assert_eq!(access.payment_count, 1);
+
+    h.modify(&user, lutrii_recurring::instruction::PauseSubscription {})
+        .await
+        .unwrap();
+    assert_eq!(h.verify_access(&wallet, None).await.status, AccessStatus::Paused);
+}
+
+#[tokio::test]
+async fn test_revenue_forecast_tracks_next_thirty_days() {
+    let mut h = Harness::new().await;
+    let forecast = revenue_forecast_pda(&h.merchant, &h.mint);
+    let open = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::OpenRevenueForecast {
+            revenue_forecast: forecast,
+            merchant: h.merchant,
+            mint: h.mint,
+            payer: h.ctx.payer.pubkey(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::OpenRevenueForecast {},
+    );
+    h.process(open, &[]).await.unwrap();
+
+    // Weekly payments fall inside the window, a 60-day cycle does not yet
+    let weekly = h.subscribe(USDC, 7 * DAY).await;
+    let _bimonthly = h.subscribe(2 * USDC, 60 * DAY).await;
+    let state: RevenueForecast = h.anchor_account(&forecast).await;
+    assert_eq!(state.projected_revenue, USDC);
+
+    // Pausing takes the payment out; resuming puts it back
+    h.modify(&weekly, lutrii_recurring::instruction::PauseSubscription {})
+        .await
+        .unwrap();
+    let state: RevenueForecast = h.anchor_account(&forecast).await;
+    assert_eq!(state.projected_revenue, 0);
+    h.modify(&weekly, lutrii_recurring::instruction::ResumeSubscription {})
+        .await
+        .unwrap();
+    let state: RevenueForecast = h.anchor_account(&forecast).await;
+    assert_eq!(state.projected_revenue, USDC);
+
+    // Paying moves the weekly payment a cycle on
+    h.warp_forward(7 * DAY).await;
+    h.execute_payment(&weekly).await.unwrap();
+    let state: RevenueForecast = h.anchor_account(&forecast).await;
+    assert_eq!(state.projected_revenue, USDC);
+
+    // As days pass the longer cycle enters the window and unpaid ones drop out
+    h.warp_forward(30 * DAY).await;
+    let refresh = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::RefreshRevenueForecast { revenue_forecast: forecast },
+        lutrii_recurring::instruction::RefreshRevenueForecast {},
+    );
+    h.process(refresh, &[]).await.unwrap();
+    let state: RevenueForecast = h.anchor_account(&forecast).await;
+    assert_eq!(state.projected_revenue, 2 * USDC);
+}
+
+#[tokio::test]
+async fn test_refund_liability_holds_reserve_in_settlement_vault() {
+    let mut h = Harness::new().await;
+    let settlement = settlement_pda(&h.merchant, &h.mint);
+    let vault = settlement_vault_pda(&settlement);
+    let liability = refund_liability_pda(&h.merchant, &h.mint);
+
+    let configure = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::ConfigureSettlement {
+            settlement,
+            vault,
+            destination: h.merchant_token_account,
+            merchant: h.merchant,
+            owner: h.merchant_owner.pubkey(),
+            mint: h.mint,
+            token_program: spl_token::id(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::ConfigureSettlement { enabled: true },
+    );
+    let owner = h.merchant_owner.insecure_clone();
+    h.process(configure, &[&owner]).await.unwrap();
+
+    let (merchant, mint, authority) = (h.merchant, h.mint, h.ctx.payer.pubkey());
+    let track = |window_days: u16, reserve_bps: u16| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::ConfigureRefundLiability {
+                platform_state: platform_state(),
+                refund_liability: liability,
+                merchant,
+                authority,
+                mint,
+                system_program: system_program::ID,
+            },
+            lutrii_recurring::instruction::ConfigureRefundLiability {
+                window_days,
+                reserve_bps,
+            },
+        )
+    };
+    assert_custom_error(
+        h.process(track(32, 5_000), &[]).await,
+        u32::from(ErrorCode::InvalidRefundLiability),
+    );
+    h.process(track(7, 5_000), &[]).await.unwrap();
+
+    let user = h.subscribe(USDC, 30 * DAY).await;
+    h.warp_forward(30 * DAY).await;
+    h.execute_payment(&user).await.unwrap();
+    let accrued = h.token_account(&vault).await.amount;
+    let liability_state: RefundLiability = h.anchor_account(&liability).await;
+    assert_eq!(liability_state.buckets.iter().sum::<u64>(), accrued);
+
+    let sweep = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::SweepSettlement {
+            settlement,
+            vault,
+            destination: h.merchant_token_account,
+            mint: h.mint,
+            token_program: spl_token::id(),
+            refund_liability: Some(liability),
+        },
+        lutrii_recurring::instruction::SweepSettlement {},
+    );
+
+    // Half of the still-refundable payment stays behind
+    h.process(sweep.clone(), &[]).await.unwrap();
+    let reserve = accrued / 2;
+    assert_eq!(h.token_account(&vault).await.amount, reserve);
+    assert_custom_error(
+        h.process(sweep.clone(), &[]).await,
+        u32::from(ErrorCode::NothingToSweep),
+    );
+
+    // Once the payment leaves the refund window the reserve is released
+    h.warp_forward(8 * DAY).await;
+    h.process(sweep, &[]).await.unwrap();
+    assert_eq!(h.token_account(&vault).await.amount, 0);
+    let state: SettlementVault = h.anchor_account(&settlement).await;
+    assert_eq!(state.total_swept, accrued);
+}
+
+#[tokio::test]
+async fn test_governance_mode_hands_authority_to_realm_treasury() {
+    let mut h = Harness::new().await;
+
+    // Stand-ins for an SPL Governance deployment: a program, a realm and a
+    // governance account whose data starts with its type and realm
+    let governance_program = Pubkey::new_unique();
+    let realm = Pubkey::new_unique();
+    let governance = Pubkey::new_unique();
+    let stub = |data: Vec<u8>, owner: Pubkey, executable: bool| Account {
+        lamports: 1_000_000_000,
+        data,
+        owner,
+        executable,
+        rent_epoch: 0,
+    };
+    h.ctx.set_account(
+        &governance_program,
+        &stub(vec![], solana_sdk::bpf_loader::id(), true).into(),
+    );
+    h.ctx.set_account(&realm, &stub(vec![16; 64], governance_program, false).into());
+    let mut governance_data = vec![18u8];
+    governance_data.extend_from_slice(Pubkey::new_unique().as_ref());
+    h.ctx.set_account(&governance, &stub(governance_data.clone(), governance_program, false).into());
+
+    let config = Pubkey::find_program_address(&[b"governance"], &lutrii_recurring::ID).0;
+    let guardian = Keypair::new();
+    let admin = h.ctx.payer.pubkey();
+    let enable = |governance: Pubkey| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::EnableGovernance {
+                platform_state: platform_state(),
+                governance_config: config,
+                governance,
+                realm,
+                governance_program,
+                authority: admin,
+                system_program: system_program::ID,
+                audit_log: None,
+            },
+            lutrii_recurring::instruction::EnableGovernance {
+                pause_guardian: guardian.pubkey(),
+            },
+        )
+    };
+
+    // The governance must belong to the realm
+    let wrong_realm = enable(governance);
+    assert_custom_error(
+        h.process(wrong_realm, &[]).await,
+        u32::from(ErrorCode::InvalidGovernance),
+    );
+    governance_data[1..33].copy_from_slice(realm.as_ref());
+    h.ctx.set_account(&governance, &stub(governance_data, governance_program, false).into());
+    let enable_ix = enable(governance);
+    h.process(enable_ix, &[]).await.unwrap();
+
+    let treasury = Pubkey::find_program_address(
+        &[b"native-treasury", governance.as_ref()],
+        &governance_program,
+    )
+    .0;
+    let platform: PlatformState = h.anchor_account(&platform_state()).await;
+    assert_eq!(platform.authority, treasury);
+    let state: GovernanceConfig = h.anchor_account(&config).await;
+    assert_eq!((state.realm, state.native_treasury), (realm, treasury));
+
+    // The former admin key no longer administers the platform
+    let pause = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::AdminAction {
+            platform_state: platform_state(),
+            authority: h.ctx.payer.pubkey(),
+            audit_log: None,
+        },
+        lutrii_recurring::instruction::EmergencyPause {
+            reason: "Incident".to_string(),
+        },
+    );
+    assert_custom_error(
+        h.process(pause, &[]).await,
+        u32::from(ErrorCode::UnauthorizedAdmin),
+    );
+
+    // Only the guardian can pause without a proposal
+    let guardian_pause = |guardian: Pubkey| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::GuardianPause {
+                platform_state: platform_state(),
+                governance_config: config,
+                guardian,
+                audit_log: None,
+            },
+            lutrii_recurring::instruction::GuardianPause {
+                reason: "Oracle exploit under investigation".to_string(),
+            },
+        )
+    };
+    let stranger = Keypair::new();
+    assert_custom_error(
+        h.process(guardian_pause(stranger.pubkey()), &[&stranger]).await,
+        u32::from(ErrorCode::UnauthorizedGuardian),
+    );
+    h.process(guardian_pause(guardian.pubkey()), &[&guardian]).await.unwrap();
+    let platform: PlatformState = h.anchor_account(&platform_state()).await;
+    assert!(platform.emergency_pause);
+    assert_eq!(platform.pause_reason(), "Oracle exploit under investigation");
+}
+
+#[tokio::test]
+async fn test_emergency_pause_lapses_unless_reactivated() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    let admin = h.ctx.payer.pubkey();
+    let pause = |reason: &str| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::AdminAction {
+                platform_state: platform_state(),
+                authority: admin,
+                audit_log: None,
+            },
+            lutrii_recurring::instruction::EmergencyPause {
+                reason: reason.to_string(),
+            },
+        )
+    };
+
+    // A pause has to say why
+    assert_custom_error(
+        h.process(pause(" "), &[]).await,
+        u32::from(ErrorCode::InvalidPauseReason),
+    );
+    h.process(pause("Suspicious keeper activity"), &[]).await.unwrap();
+    let platform: PlatformState = h.anchor_account(&platform_state()).await;
+    assert_eq!(platform.pause_reason(), "Suspicious keeper activity");
+
+    h.warp_forward(DAY).await;
+    assert_custom_error(
+        h.execute_payment(&user).await,
+        u32::from(ErrorCode::SystemPaused),
+    );
+
+    // Re-activating restarts the 72h clock
+    h.process(pause("Still investigating"), &[]).await.unwrap();
+    h.warp_forward(2 * DAY).await;
+    assert_custom_error(
+        h.execute_payment(&user).await,
+        u32::from(ErrorCode::SystemPaused),
+    );
+
+    // Without another activation the pause lapses on its own
+    h.warp_forward(DAY).await;
+    h.execute_payment(&user).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_admin_actions_recorded_in_audit_log() {
+    let mut h = Harness::new().await;
+    let admin = h.ctx.payer.pubkey();
+    let audit_log = Pubkey::find_program_address(&[b"admin_audit_log"], &lutrii_recurring::ID).0;
+    let open = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::OpenAdminAuditLog {
+            platform_state: platform_state(),
+            audit_log,
+            authority: admin,
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::OpenAdminAuditLog {},
+    );
+    h.process(open, &[]).await.unwrap();
+
+    let accounts = |audit_log: Option<Pubkey>| lutrii_recurring::accounts::AdminAction {
+        platform_state: platform_state(),
+        authority: admin,
+        audit_log,
+    };
+    let pause = |audit_log: Option<Pubkey>| {
+        ix(
+            lutrii_recurring::ID,
+            accounts(audit_log),
+            lutrii_recurring::instruction::EmergencyPause {
+                reason: "Key compromise drill".to_string(),
+            },
+        )
+    };
+    let unpause = ix(
+        lutrii_recurring::ID,
+        accounts(Some(audit_log)),
+        lutrii_recurring::instruction::EmergencyUnpause {},
+    );
+
+    h.process(pause(Some(audit_log)), &[]).await.unwrap();
+    h.warp_forward(60).await;
+    h.process(unpause, &[]).await.unwrap();
+    // Actions taken without the log are not recorded
+    h.process(pause(None), &[]).await.unwrap();
+
+    let log: AdminAuditLog = h.anchor_account(&audit_log).await;
+    assert_eq!(log.trail.total_actions, 2);
+    let entries: Vec<_> = log.trail.chronological().collect();
+    assert_eq!(
+        entries.iter().map(|entry| entry.action).collect::<Vec<_>>(),
+        vec![AdminAuditAction::EmergencyPause, AdminAuditAction::EmergencyUnpause]
+    );
+    assert!(entries.iter().all(|entry| entry.actor == admin));
+    assert_eq!(entries[1].timestamp - entries[0].timestamp, 60);
+}
+
+#[tokio::test]
+async fn test_treasury_withdrawal_capped_per_executing_proposal() {
+    let mut h = Harness::new().await;
+    let treasury = fee_treasury_pda(&h.mint);
+    let vault = fee_treasury_vault_pda(&treasury);
+    let admin = h.ctx.payer.pubkey();
+    let configure = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::ConfigureFeeTreasury {
+            platform_state: platform_state(),
+            fee_treasury: treasury,
+            vault,
+            authority: admin,
+            mint: h.mint,
+            token_program: spl_token::id(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::ConfigureFeeTreasury {
+            shares: vec![FeeShare {
+                recipient: h.platform_fee_account,
+                basis_points: 10_000,
+            }],
+            sweep_interval: DAY,
+            enabled: true,
+        },
+    );
+    h.process(configure, &[]).await.unwrap();
+    h.mint_to(&vault, 500 * USDC).await;
+
+    // Stand-ins for an SPL Governance deployment, as in the governance
+    // mode test
+    let governance_program = Pubkey::new_unique();
+    let realm = Pubkey::new_unique();
+    let governance = Pubkey::new_unique();
+    let stub = |data: Vec<u8>, owner: Pubkey, executable: bool| Account {
+        lamports: 1_000_000_000,
+        data,
+        owner,
+        executable,
+        rent_epoch: 0,
+    };
+    h.ctx.set_account(
+        &governance_program,
+        &stub(vec![], solana_sdk::bpf_loader::id(), true).into(),
+    );
+    h.ctx.set_account(&realm, &stub(vec![16; 64], governance_program, false).into());
+    let mut governance_data = vec![18u8];
+    governance_data.extend_from_slice(realm.as_ref());
+    h.ctx.set_account(&governance, &stub(governance_data, governance_program, false).into());
+
+    let config = Pubkey::find_program_address(&[b"governance"], &lutrii_recurring::ID).0;
+    let enable = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::EnableGovernance {
+            platform_state: platform_state(),
+            governance_config: config,
+            governance,
+            realm,
+            governance_program,
+            authority: admin,
+            system_program: system_program::ID,
+            audit_log: None,
+        },
+        lutrii_recurring::instruction::EnableGovernance {
+            pause_guardian: Pubkey::default(),
+        },
+    );
+    h.process(enable, &[]).await.unwrap();
+
+    // The native treasury only signs inside the governance program's
+    // execution CPI, so a local key stands in for it
+    let executor = Keypair::new();
+    h.fund(&executor.pubkey()).await;
+    for (address, offset) in [(platform_state(), 8), (config, 8 + 3 * 32)] {
+        let mut account = h.account(&address).await.unwrap();
+        account.data[offset..offset + 32].copy_from_slice(executor.pubkey().as_ref());
+        h.ctx.set_account(&address, &account.into());
+    }
+
+    let set_cap = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::SetTreasurySpendCap {
+            platform_state: platform_state(),
+            governance_config: config,
+            authority: executor.pubkey(),
+        },
+        lutrii_recurring::instruction::SetTreasurySpendCap {
+            max_spend_per_proposal: 100 * USDC,
+        },
+    );
+    h.process(set_cap, &[&executor]).await.unwrap();
+
+    // Proposal data: type, governance, governing token mint, state
+    let proposal = Pubkey::new_unique();
+    let set_proposal_state = |h: &mut Harness, state: u8| {
+        let mut data = vec![14u8];
+        data.extend_from_slice(governance.as_ref());
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.push(state);
+        h.ctx.set_account(&proposal, &stub(data, governance_program, false).into());
+    };
+    let recipient = h.create_token_account(&Pubkey::new_unique()).await;
+    let mint = h.mint;
+    let withdraw = |amount: u64| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::WithdrawTreasury {
+                platform_state: platform_state(),
+                governance_config: config,
+                proposal,
+                treasury_spend: Pubkey::find_program_address(
+                    &[b"treasury_spend", proposal.as_ref()],
+                    &lutrii_recurring::ID,
+                )
+                .0,
+                fee_treasury: treasury,
+                vault,
+                destination: recipient,
+                authority: executor.pubkey(),
+                mint,
+                token_program: spl_token::id(),
+                system_program: system_program::ID,
+            },
+            lutrii_recurring::instruction::WithdrawTreasury { amount },
+        )
+    };
+
+    // A proposal still being voted on cannot spend
+    set_proposal_state(&mut h, 2);
+    assert_custom_error(
+        h.process(withdraw(60 * USDC), &[&executor]).await,
+        u32::from(ErrorCode::InvalidProposal),
+    );
+
+    set_proposal_state(&mut h, 3);
+    h.process(withdraw(60 * USDC), &[&executor]).await.unwrap();
+    assert_eq!(h.token_account(&recipient).await.amount, 60 * USDC);
+    assert_eq!(h.token_account(&vault).await.amount, 440 * USDC);
+
+    // The proposal's spending is logged and held to the cap
+    assert_custom_error(
+        h.process(withdraw(50 * USDC), &[&executor]).await,
+        u32::from(ErrorCode::ProposalSpendCapExceeded),
+    );
+    h.process(withdraw(40 * USDC), &[&executor]).await.unwrap();
+    let spend_address = Pubkey::find_program_address(
+        &[b"treasury_spend", proposal.as_ref()],
+        &lutrii_recurring::ID,
+    )
+    .0;
+    let spend: TreasurySpend = h.anchor_account(&spend_address).await;
+    assert_eq!((spend.withdrawn, spend.spend_count), (100 * USDC, 2));
+}
+
+#[tokio::test]
+async fn test_fee_treasury_accrues_then_splits() {
+    let mut h = Harness::new().await;
+    let treasury = fee_treasury_pda(&h.mint);
+    let vault = fee_treasury_vault_pda(&treasury);
+    let operations = h.create_token_account(&Pubkey::new_unique()).await;
+    let marketing = h.create_token_account(&Pubkey::new_unique()).await;
+
+    let configure = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::ConfigureFeeTreasury {
+            platform_state: platform_state(),
+            fee_treasury: treasury,
+            vault,
+            authority: h.ctx.payer.pubkey(),
+            mint: h.mint,
+            token_program: spl_token::id(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::ConfigureFeeTreasury {
+            shares: vec![
+                FeeShare {
+                    recipient: operations,
+                    basis_points: 7_000,
+                },
+                FeeShare {
+                    recipient: marketing,
+                    basis_points: 3_000,
+                },
+            ],
+            sweep_interval: DAY,
+            enabled: true,
+        },
+    );
+    h.process(configure, &[]).await.unwrap();
+
+    let user = h.subscribe(100 * USDC, DAY).await;
+    let fee_wallet_before = h.token_account(&h.platform_fee_account).await.amount;
+    h.warp_forward(DAY).await;
+    h.execute_payment(&user).await.unwrap();
+
+    // The fee accrues in the treasury instead of the fee wallet
+    let accrued = h.token_account(&vault).await.amount;
+    assert!(accrued > 0);
+    assert_eq!(
+        h.token_account(&h.platform_fee_account).await.amount,
+        fee_wallet_before
+    );
+
+    let mut sweep = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::SweepFees {
+            fee_treasury: treasury,
+            vault,
+            mint: h.mint,
+            token_program: spl_token::id(),
+        },
+        lutrii_recurring::instruction::SweepFees {},
+    );
+    sweep.accounts.push(AccountMeta::new(operations, false));
+    sweep.accounts.push(AccountMeta::new(marketing, false));
+    h.process(sweep.clone(), &[]).await.unwrap();
+
+    let to_operations = h.token_account(&operations).await.amount;
+    let to_marketing = h.token_account(&marketing).await.amount;
+    assert_eq!(to_operations, accrued * 7 / 10);
+    assert_eq!(to_operations + to_marketing, accrued);
+    assert_eq!(h.token_account(&vault).await.amount, 0);
+
+    // One sweep per interval
+    assert_custom_error(
+        h.process(sweep, &[]).await,
+        u32::from(ErrorCode::SweepTooEarly),
+    );
+}
+
+#[tokio::test]
+async fn test_fee_rebate_claimed_once_per_period() {
+    let mut h = Harness::new().await;
+    let treasury = fee_treasury_pda(&h.mint);
+    let vault = fee_treasury_vault_pda(&treasury);
+    let volume = merchant_volume_pda(&h.merchant, &h.mint);
+
+    let configure = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::ConfigureFeeTreasury {
+            platform_state: platform_state(),
+            fee_treasury: treasury,
+            vault,
+            authority: h.ctx.payer.pubkey(),
+            mint: h.mint,
+            token_program: spl_token::id(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::ConfigureFeeTreasury {
+            shares: vec![FeeShare {
+                recipient: h.platform_fee_account,
+                basis_points: 10_000,
+            }],
+            sweep_interval: DAY,
+            enabled: true,
+        },
+    );
+    h.process(configure, &[]).await.unwrap();
+
+    let rebates = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::ConfigureRebates {
+            platform_state: platform_state(),
+            fee_treasury: treasury,
+            authority: h.ctx.payer.pubkey(),
+        },
+        lutrii_recurring::instruction::ConfigureRebates {
+            tiers: vec![
+                RebateTier {
+                    min_volume: 50 * USDC,
+                    rebate_bps: 5_000,
+                },
+                RebateTier {
+                    min_volume: 1_000 * USDC,
+                    rebate_bps: 8_000,
+                },
+            ],
+        },
+    );
+    h.process(rebates, &[]).await.unwrap();
+
+    let open = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::OpenMerchantVolume {
+            merchant_volume: volume,
+            merchant: h.merchant,
+            mint: h.mint,
+            payer: h.ctx.payer.pubkey(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::OpenMerchantVolume {},
+    );
+    h.process(open, &[]).await.unwrap();
+
+    let user = h.subscribe(100 * USDC, 60 * DAY).await;
+    h.warp_forward(60 * DAY).await;
+    h.execute_payment(&user).await.unwrap();
+    let fees = h.token_account(&vault).await.amount;
+
+    let claim = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::ClaimRebate {
+            merchant: h.merchant,
+            merchant_volume: volume,
+            fee_treasury: treasury,
+            vault,
+            destination: h.merchant_token_account,
+            owner: h.merchant_owner.pubkey(),
+            mint: h.mint,
+            token_program: spl_token::id(),
+        },
+        lutrii_recurring::instruction::ClaimRebate {},
+    );
+    let owner = h.merchant_owner.insecure_clone();
+
+    // The payment's period has not finished yet
+    assert_custom_error(
+        h.process(claim.clone(), &[&owner]).await,
+        u32::from(ErrorCode::NoRebateDue),
+    );
+
+    // Once it has, the first tier's share of its fees comes back
+    h.warp_forward(31 * DAY).await;
+    let before = h.token_account(&h.merchant_token_account).await.amount;
+    h.process(claim.clone(), &[&owner]).await.unwrap();
+    assert_eq!(
+        h.token_account(&h.merchant_token_account).await.amount - before,
+        fees / 2
+    );
+    assert_eq!(h.token_account(&vault).await.amount, fees - fees / 2);
+
+    // One claim per period
+    h.warp_forward(1).await;
+    assert_custom_error(
+        h.process(claim, &[&owner]).await,
+        u32::from(ErrorCode::NoRebateDue),
+    );
+}
+
+#[tokio::test]
+async fn test_swap_slippage_set_by_user_or_merchant() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    let set_slippage = |authority: Pubkey, merchant: Option<Pubkey>, max_slippage_bps: u16| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::SetSwapSlippage {
+                subscription: user.subscription,
+                authority,
+                merchant,
+            },
+            lutrii_recurring::instruction::SetSwapSlippage { max_slippage_bps },
+        )
+    };
+
+    let subscriber = user.keypair.insecure_clone();
+    h.process(set_slippage(subscriber.pubkey(), None, 50), &[&subscriber])
+        .await
+        .unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.max_slippage_bps, 50);
+    assert_eq!(subscription.swap_slippage_bps(300), 50);
+    assert_eq!(subscription.swap_slippage_bps(20), 20);
+
+    let owner = h.merchant_owner.insecure_clone();
+    h.process(
+        set_slippage(owner.pubkey(), Some(h.merchant), 25),
+        &[&owner],
+    )
+    .await
+    .unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.max_slippage_bps, 25);
+
+    // Anyone else, or a cap above the swap asset limit, is refused
+    let stranger = Keypair::new();
+    assert_custom_error(
+        h.process(set_slippage(stranger.pubkey(), Some(h.merchant), 10), &[&stranger])
+            .await,
+        u32::from(ErrorCode::UnauthorizedUser),
+    );
+    assert_custom_error(
+        h.process(set_slippage(subscriber.pubkey(), None, 1_001), &[&subscriber])
+            .await,
+        u32::from(ErrorCode::InvalidSlippage),
+    );
+}
+
+#[tokio::test]
+async fn test_swap_config_allowlists_programs_and_blocks_mints() {
+    let mut h = Harness::new().await;
+    let swap_config = Pubkey::find_program_address(&[b"swap_config"], &lutrii_recurring::ID).0;
+    let configure = |swap_programs: Vec<Pubkey>, blocked_mints: Vec<Pubkey>| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::ConfigureSwapConfig {
+                platform_state: platform_state(),
+                swap_config,
+                authority: h.ctx.payer.pubkey(),
+                system_program: system_program::ID,
+            },
+            lutrii_recurring::instruction::ConfigureSwapConfig {
+                swap_programs,
+                blocked_mints,
+            },
+        )
+    };
+
+    let blocked = Pubkey::new_unique();
+    let set = configure(vec![lutrii_recurring::jupiter::ID], vec![blocked]);
+    let too_many = configure(vec![Pubkey::new_unique(); 5], vec![]);
+    h.process(set, &[]).await.unwrap();
+
+    let config: SwapConfig = h.anchor_account(&swap_config).await;
+    assert!(config.is_allowed(&lutrii_recurring::jupiter::ID));
+    assert!(!config.is_allowed(&Pubkey::new_unique()));
+    assert!(config.is_blocked(&blocked));
+    assert!(!config.is_blocked(&h.mint));
+
+    assert_custom_error(
+        h.process(too_many, &[]).await,
+        u32::from(ErrorCode::InvalidSwapConfig),
+    );
+}
+
+#[tokio::test]
+async fn test_subscriptions_limited_to_accepted_mints() {
+    let mut h = Harness::new().await;
+
+    h.configure_accepted_mint(5 * USDC, true).await.unwrap();
+    assert_custom_error(
+        h.try_subscribe(USDC, DAY).await.map(|_| ()),
+        u32::from(ErrorCode::BelowMintMinimum),
+    );
+
+    // Disabled mints take no new subscriptions
+    h.configure_accepted_mint(5 * USDC, false).await.unwrap();
+    assert_custom_error(
+        h.try_subscribe(5 * USDC, DAY).await.map(|_| ()),
+        u32::from(ErrorCode::MintNotAccepted),
+    );
+
+    h.configure_accepted_mint(5 * USDC, true).await.unwrap();
+    let user = h.subscribe(5 * USDC, DAY).await;
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.mint, h.mint);
+
+    // Plan changes cannot drop the amount below the minimum either
+    let cheap = h.create_plan(1, USDC).await;
+    let change_plan = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::ChangePlan {
+            subscription: user.subscription,
+            new_plan: cheap,
+            merchant_token_account: h.merchant_token_account,
+            accepted_mint: accepted_mint_pda(&h.mint),
+            user: user.keypair.pubkey(),
+            revenue_forecast: None,
+        },
+        lutrii_recurring::instruction::ChangePlan {},
+    );
+    assert_custom_error(
+        h.process(change_plan, &[&user.keypair]).await,
+        u32::from(ErrorCode::BelowMintMinimum),
+    );
+    assert_custom_error(
+        h.configure_accepted_mint(0, true).await,
+        u32::from(ErrorCode::InvalidAcceptedMintConfig),
+    );
+}
+
+#[tokio::test]
+async fn test_fee_exempt_subscription_pays_no_platform_fee() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    let exempt = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::SetFeeExemption {
+            platform_state: platform_state(),
+            authority: h.ctx.payer.pubkey(),
+            subscription: Some(user.subscription),
+            merchant_policy: None,
+        },
+        lutrii_recurring::instruction::SetFeeExemption { fee_exempt: true },
+    );
+    h.process(exempt, &[]).await.unwrap();
+
+    let fee_account = h.platform_fee_account;
+    let merchant_account = h.merchant_token_account;
+    let fee_before = h.token_account(&fee_account).await.amount;
+    h.warp_forward(DAY).await;
+    h.execute_payment(&user).await.unwrap();
+
+    // The merchant nets the full amount and the waived fee is recorded
+    assert_eq!(h.token_account(&fee_account).await.amount, fee_before);
+    assert_eq!(h.token_account(&merchant_account).await.amount, USDC);
+    let platform: PlatformState = h.anchor_account(&platform_state()).await;
+    assert_eq!(platform.fee_exempt_payments, 1);
+    assert_eq!(
+        platform.fees_waived,
+        USDC * u64::from(FEE_BASIS_POINTS) / 10_000
+    );
+}
+
+#[tokio::test]
+async fn test_fee_exempt_merchant_pays_no_fee_on_invoices_or_upfront_charges() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    let owner = h.merchant_owner.insecure_clone();
+    let exempt = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::SetFeeExemption {
+            platform_state: platform_state(),
+            authority: h.ctx.payer.pubkey(),
+            subscription: None,
+            merchant_policy: Some(merchant_policy(&h.merchant)),
+        },
+        lutrii_recurring::instruction::SetFeeExemption { fee_exempt: true },
+    );
+    h.process(exempt, &[]).await.unwrap();
+
+    let issue = |invoice_id: u64, subscription: Option<Pubkey>| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::CreateInvoice {
+                invoice: invoice_pda(&h.merchant, invoice_id),
+                merchant: h.merchant,
+                subscription,
+                merchant_token_account: h.merchant_token_account,
+                owner: owner.pubkey(),
+                system_program: system_program::ID,
+            },
+            lutrii_recurring::instruction::CreateInvoice {
+                invoice_id,
+                user: user.keypair.pubkey(),
+                line_items: vec![LineItem {
+                    description_hash: [1; 32],
+                    quantity: 1,
+                    unit_price: USDC,
+                }],
+            },
+        )
+    };
+    let linked = issue(1, Some(user.subscription));
+    let standalone = issue(2, None);
+    h.process(linked, &[&owner]).await.unwrap();
+    h.process(standalone, &[&owner]).await.unwrap();
+
+    let (year, month) = h.reporting_period().await;
+    let pay = |invoice_id: u64, subscription: Option<Pubkey>| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::PayInvoice {
+                invoice: invoice_pda(&h.merchant, invoice_id),
+                platform_state: platform_state(),
+                subscription,
+                user: user.keypair.pubkey(),
+                merchant: h.merchant,
+                user_token_account: user.token_account,
+                merchant_token_account: h.merchant_token_account,
+                platform_fee_account: h.platform_fee_account,
+                mint: h.mint,
+                token_program: spl_token::id(),
+                merchant_policy: merchant_policy(&h.merchant),
+                fee_holidays: fee_holidays_pda(),
+                merchant_report: merchant_report_pda(&h.merchant, &h.mint, year),
+                merchant_statement: merchant_statement_pda(&h.merchant, &h.mint, year, month),
+                refund_liability: refund_liability_pda(&h.merchant, &h.mint),
+                merchant_registry_program: lutrii_merchant_registry::ID,
+                instructions: solana_sdk::sysvar::instructions::ID,
+            },
+            lutrii_recurring::instruction::PayInvoice {},
+        )
+    };
+    let collect_linked = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::CollectInvoice {
+            invoice: invoice_pda(&h.merchant, 1),
+            subscription: user.subscription,
+            platform_state: platform_state(),
+            user_token_account: user.token_account,
+            user_delegate: user_delegate(&user.token_account),
+            merchant_token_account: h.merchant_token_account,
+            platform_fee_account: h.platform_fee_account,
+            user_denylist_entry: denylist_entry(&user.keypair.pubkey()),
+            merchant_denylist_entry: denylist_entry(&h.merchant_owner.pubkey()),
+            blocklist_entry: blocklist_entry(&h.merchant, &user.keypair.pubkey()),
+            merchant: h.merchant,
+            mint: h.mint,
+            token_program: spl_token::id(),
+            merchant_policy: merchant_policy(&h.merchant),
+            fee_holidays: fee_holidays_pda(),
+            merchant_report: merchant_report_pda(&h.merchant, &h.mint, year),
+            merchant_statement: merchant_statement_pda(&h.merchant, &h.mint, year, month),
+            refund_liability: refund_liability_pda(&h.merchant, &h.mint),
+            escrow: None,
+            escrow_vault: None,
+            merchant_registry_program: lutrii_merchant_registry::ID,
+            instructions: solana_sdk::sysvar::instructions::ID,
+        },
+        lutrii_recurring::instruction::CollectInvoice {},
+    );
+    let pay_linked_alone = pay(1, None);
+    let pay_standalone = pay(2, None);
+
+    // A linked invoice is paid with its subscription, whose exemption applies
+    assert_custom_error(
+        h.process(pay_linked_alone, &[&user.keypair]).await,
+        u32::from(ErrorCode::InvoiceSubscriptionRequired),
+    );
+
+    let (fee_account, merchant_account) = (h.platform_fee_account, h.merchant_token_account);
+    let fee_before = h.token_account(&fee_account).await.amount;
+    h.process(collect_linked, &[]).await.unwrap();
+    h.process(pay_standalone, &[&user.keypair]).await.unwrap();
+    assert_eq!(h.token_account(&merchant_account).await.amount, 2 * USDC);
+    assert_eq!(h.token_account(&fee_account).await.amount, fee_before);
+
+    // Up-front charges at signup are exempt too
+    let keypair = Keypair::new();
+    h.fund(&keypair.pubkey()).await;
+    let token_account = h.create_token_account(&keypair.pubkey()).await;
+    h.mint_to(&token_account, 100 * USDC).await;
+    let owner = h.merchant_owner.pubkey();
+    let merchant = h.merchant;
+    let mut accounts = h
+        .create_subscription_accounts(&keypair.pubkey(), token_account, merchant, &owner, merchant_account, DAY)
+        .await;
+    accounts.platform_fee_account = Some(fee_account);
+    h.process(
+        ix(
+            lutrii_recurring::ID,
+            accounts,
+            lutrii_recurring::instruction::CreateSubscription {
+                amount: USDC,
+                frequency_seconds: DAY,
+                max_per_transaction: USDC,
+                lifetime_cap: 12 * USDC,
+                merchant_name: "Lutrii Now".to_string(),
+                options: CreateOptions {
+                    charge_immediately: true,
+                    ..Default::default()
+                },
+            },
+        ),
+        &[&keypair],
+    )
+    .await
+    .unwrap();
+    assert_eq!(h.token_account(&merchant_account).await.amount, 3 * USDC);
+    assert_eq!(h.token_account(&fee_account).await.amount, fee_before);
+
+    let fee = USDC * u64::from(FEE_BASIS_POINTS) / 10_000;
+    let platform: PlatformState = h.anchor_account(&platform_state()).await;
+    assert_eq!((platform.fee_exempt_payments, platform.fees_waived), (3, 3 * fee));
+}
+
+#[tokio::test]
+async fn test_fee_holiday_waives_platform_fee_inside_window() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    let clock: Clock = h.ctx.banks_client.get_sysvar().await.unwrap();
+    let schedule = |holidays: Vec<FeeHoliday>| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::ConfigureFeeHolidays {
+                platform_state: platform_state(),
+                fee_holidays: fee_holidays_pda(),
+                authority: h.ctx.payer.pubkey(),
+                system_program: system_program::ID,
+            },
+            lutrii_recurring::instruction::ConfigureFeeHolidays { holidays },
+        )
+    };
+
+    // Windows must end after they start
+    let inverted = FeeHoliday {
+        start: clock.unix_timestamp + DAY,
+        end: clock.unix_timestamp,
+        mint: Pubkey::default(),
+    };
+    assert_custom_error(
+        h.process(schedule(vec![inverted]), &[]).await,
+        u32::from(ErrorCode::InvalidFeeHoliday),
+    );
+
+    // A holiday for the billing mint covering the next payment
+    let holiday = FeeHoliday {
+        start: clock.unix_timestamp,
+        end: clock.unix_timestamp + DAY + DAY / 2,
+        mint: h.mint,
+    };
+    h.process(schedule(vec![holiday]), &[]).await.unwrap();
+
+    let fee_account = h.platform_fee_account;
+    let merchant_account = h.merchant_token_account;
+    let fee_before = h.token_account(&fee_account).await.amount;
+    h.warp_forward(DAY).await;
+    h.execute_payment(&user).await.unwrap();
+    assert_eq!(h.token_account(&fee_account).await.amount, fee_before);
+    assert_eq!(h.token_account(&merchant_account).await.amount, USDC);
+
+    // Promotional payments are not counted as fee exemptions
+    let platform: PlatformState = h.anchor_account(&platform_state()).await;
+    assert_eq!(platform.fee_exempt_payments, 0);
+
+    // Once the window closes the fee is charged again
+    h.warp_forward(DAY).await;
+    h.execute_payment(&user).await.unwrap();
+    assert_eq!(
+        h.token_account(&fee_account).await.amount,
+        fee_before + USDC * u64::from(FEE_BASIS_POINTS) / 10_000
+    );
+}
+
+#[tokio::test]
+async fn test_first_subscription_waiver_covers_first_payments() {
+    let mut h = Harness::new().await;
+    let waiver = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::AdminAction {
+            platform_state: platform_state(),
+            authority: h.ctx.payer.pubkey(),
+            audit_log: None,
+        },
+        lutrii_recurring::instruction::SetFirstSubscriptionWaiver { free_payments: 2 },
+    );
+    h.process(waiver, &[]).await.unwrap();
+
+    // The only subscription passed at init is recorded as the user's first
+    let user = h.subscribe(USDC, DAY).await;
+    let stats = user_stats_pda(&user.keypair.pubkey());
+    let mut init = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::InitUserStats {
+            user_stats: stats,
+            user: user.keypair.pubkey(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::InitUserStats {},
+    );
+    init.accounts
+        .push(AccountMeta::new_readonly(user.subscription, false));
+    h.process(init, &[&user.keypair]).await.unwrap();
+    let user_stats: UserStats = h.anchor_account(&stats).await;
+    assert_eq!(user_stats.first_subscription, user.subscription);
+
+    let fee_account = h.platform_fee_account;
+    let fee_before = h.token_account(&fee_account).await.amount;
+    for _ in 0..2 {
+        h.warp_forward(DAY).await;
+        h.execute_payment(&user).await.unwrap();
+    }
+    assert_eq!(h.token_account(&fee_account).await.amount, fee_before);
+
+    // The third payment is past the waiver
+    h.warp_forward(DAY).await;
+    h.execute_payment(&user).await.unwrap();
+    assert_eq!(
+        h.token_account(&fee_account).await.amount,
+        fee_before + USDC * u64::from(FEE_BASIS_POINTS) / 10_000
+    );
+}
+
+#[tokio::test]
+async fn test_user_bears_fee_on_top_of_payment() {
+    let mut h = Harness::new().await;
+    let owner = h.merchant_owner.insecure_clone();
+    let configure = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::ConfigureMerchantPolicy {
+            merchant_policy: merchant_policy(&h.merchant),
+            merchant: h.merchant,
+            owner: owner.pubkey(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::ConfigureMerchantPolicy {
+            allowlist_only: false,
+            max_active_subscribers: 0,
+            max_snooze_days: 0,
+            billing_anchor: 0,
+            swap_fallback: SwapFallback::Fail,
+            fee_bearer: FeeBearer::User,
+        },
+    );
+    h.process(configure, &[&owner]).await.unwrap();
+
+    let user = h.subscribe(USDC, DAY).await;
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.fee_bearer, FeeBearer::User);
+
+    let fee = USDC * u64::from(FEE_BASIS_POINTS) / 10_000;
+    let fee_account = h.platform_fee_account;
+    let merchant_account = h.merchant_token_account;
+    let fee_before = h.token_account(&fee_account).await.amount;
+    let user_before = h.token_account(&user.token_account).await.amount;
+    h.warp_forward(DAY).await;
+    h.execute_payment(&user).await.unwrap();
+
+    // The merchant nets the full amount; the user pays it plus the fee
+    assert_eq!(h.token_account(&merchant_account).await.amount, USDC);
+    assert_eq!(h.token_account(&fee_account).await.amount, fee_before + fee);
+    assert_eq!(
+        h.token_account(&user.token_account).await.amount,
+        user_before - USDC - fee
+    );
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.total_paid, USDC + fee);
+
+    // The delegation must keep covering the charge plus the fee on top
+    let check = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::CheckDelegation {
+            subscription: user.subscription,
+            platform_state: platform_state(),
+            user_token_account: user.token_account,
+            user_delegate: user_delegate(&user.token_account),
+            merchant_policy: merchant_policy(&h.merchant),
+        },
+        lutrii_recurring::instruction::CheckDelegation {},
+    );
+    h.process(check, &[]).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert!(subscription.delegation_healthy);
+}
+
+#[tokio::test]
+async fn test_merchant_fee_schedule_overrides_platform_fee() {
+    let mut h = Harness::new().await;
+    let owner = h.merchant_owner.insecure_clone();
+    let configure = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::ConfigureMerchantPolicy {
+            merchant_policy: merchant_policy(&h.merchant),
+            merchant: h.merchant,
+            owner: owner.pubkey(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::ConfigureMerchantPolicy {
+            allowlist_only: false,
+            max_active_subscribers: 0,
+            max_snooze_days: 0,
+            billing_anchor: 0,
+            swap_fallback: SwapFallback::Fail,
+            fee_bearer: FeeBearer::Merchant,
+        },
+    );
+    h.process(configure, &[&owner]).await.unwrap();
+
+    let authority = h.ctx.payer.pubkey();
+    let policy = merchant_policy(&h.merchant);
+    let schedule = |fee_schedule: Option<FeeSchedule>| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::SetMerchantFeeSchedule {
+                platform_state: platform_state(),
+                authority,
+                merchant_policy: policy,
+                audit_log: None,
+            },
+            lutrii_recurring::instruction::SetMerchantFeeSchedule { fee_schedule },
+        )
+    };
+    let inverted = FeeSchedule {
+        fee_basis_points: 100,
+        min_fee: 2,
+        max_fee: 1,
+    };
+    assert_custom_error(
+        h.process(schedule(Some(inverted)), &[]).await,
+        u32::from(ErrorCode::InvalidFeeSchedule),
+    );
+
+    // 1% with no floor, instead of the platform's rate
+    let negotiated = FeeSchedule {
+        fee_basis_points: 100,
+        min_fee: 0,
+        max_fee: USDC,
+    };
+    h.process(schedule(Some(negotiated)), &[]).await.unwrap();
+
+    let user = h.subscribe(USDC, DAY).await;
+    let fee_account = h.platform_fee_account;
+    let merchant_account = h.merchant_token_account;
+    let fee_before = h.token_account(&fee_account).await.amount;
+    h.warp_forward(DAY).await;
+
+    // Leaving the policy out cannot fall back to the platform's rate
+    let mut without_policy = h.execute_payment_ix(&user).await;
+    substitute_account(&mut without_policy, &policy, Pubkey::new_unique());
+    assert_custom_error(
+        h.process(without_policy, &[]).await,
+        anchor_lang::error::ErrorCode::ConstraintSeeds as u32,
+    );
+    h.execute_payment(&user).await.unwrap();
+    assert_eq!(
+        h.token_account(&fee_account).await.amount,
+        fee_before + USDC / 100
+    );
+    assert_eq!(
+        h.token_account(&merchant_account).await.amount,
+        USDC - USDC / 100
+    );
+
+    // Clearing the schedule restores the platform defaults
+    h.process(schedule(None), &[]).await.unwrap();
+    let policy: MerchantPolicy = h.anchor_account(&policy).await;
+    assert!(!policy.custom_fee);
+}
+
+#[tokio::test]
+async fn test_merchant_report_accrues_calendar_year_totals() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    h.warp_forward(DAY).await;
+
+    let clock: Clock = h.ctx.banks_client.get_sysvar().await.unwrap();
+    let (year, month) = schedule::utc_year_month(clock.unix_timestamp);
+    let year = year as u16;
+    let report = merchant_report_pda(&h.merchant, &h.mint, year);
+    let open = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::OpenMerchantReport {
+            merchant_report: report,
+            merchant: h.merchant,
+            mint: h.mint,
+            payer: h.ctx.payer.pubkey(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::OpenMerchantReport { year },
+    );
+    h.process(open, &[]).await.unwrap();
+
+    // Once opened, an execution cannot leave the year's report out
+    let mut without_report = h.execute_payment_ix(&user).await;
+    substitute_account(&mut without_report, &report, lutrii_recurring::ID);
+    assert_custom_error(
+        h.process(without_report, &[]).await,
+        anchor_lang::error::ErrorCode::ConstraintSeeds as u32,
+    );
+
+    let fee_account = h.platform_fee_account;
+    let fee_before = h.token_account(&fee_account).await.amount;
+    h.execute_payment(&user).await.unwrap();
+    let fee = h.token_account(&fee_account).await.amount - fee_before;
+    assert!(fee > 0);
+
+    let totals: MerchantReport = h.anchor_account(&report).await;
+    assert_eq!(totals.year, year);
+    assert_eq!((totals.volume, totals.fees, totals.payment_count), (USDC, fee, 1));
+    assert_eq!(totals.monthly_volume[month as usize - 1], USDC);
+    assert_eq!(totals.monthly_fees[month as usize - 1], fee);
+}
+
+#[tokio::test]
+async fn test_merchant_statement_closes_after_month_end() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    h.warp_forward(DAY).await;
+
+    let clock: Clock = h.ctx.banks_client.get_sysvar().await.unwrap();
+    let (year, month) = schedule::utc_year_month(clock.unix_timestamp);
+    let (year, month) = (year as u16, month as u8);
+    let statement = merchant_statement_pda(&h.merchant, &h.mint, year, month);
+    let open = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::OpenMerchantStatement {
+            merchant_statement: statement,
+            merchant: h.merchant,
+            mint: h.mint,
+            payer: h.ctx.payer.pubkey(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::OpenMerchantStatement { year, month },
+    );
+    h.process(open, &[]).await.unwrap();
+
+    let fee_account = h.platform_fee_account;
+    let fee_before = h.token_account(&fee_account).await.amount;
+    h.execute_payment(&user).await.unwrap();
+    let fee = h.token_account(&fee_account).await.amount - fee_before;
+
+    let close = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::CloseMerchantStatement {
+            merchant_statement: statement,
+            event_authority: event_authority(),
+            program: lutrii_recurring::ID,
+        },
+        lutrii_recurring::instruction::CloseMerchantStatement {},
+    );
+    assert_custom_error(
+        h.process(close.clone(), &[]).await,
+        u32::from(ErrorCode::StatementPeriodOpen),
+    );
+
+    let (_, month_end) = schedule::utc_month_bounds(i64::from(year), u32::from(month)).unwrap();
+    h.warp_forward(month_end - clock.unix_timestamp).await;
+    h.process(close.clone(), &[]).await.unwrap();
+
+    let books: MerchantStatement = h.anchor_account(&statement).await;
+    assert!(books.closed);
+    assert_eq!(books.payment_count, 1);
+    assert_eq!((books.gross_volume, books.fees, books.refunds), (USDC, fee, 0));
+    assert_eq!(books.net_settlement, USDC - fee);
+
+    // Closed books cannot be reopened or closed again
+    h.warp_forward(1).await;
+    assert_custom_error(
+        h.process(close, &[]).await,
+        u32::from(ErrorCode::StatementClosed),
+    );
+}
+
+#[tokio::test]
+async fn test_snooze_defers_due_payment_once_per_cycle() {
+    let mut h = Harness::new().await;
+    let owner = h.merchant_owner.insecure_clone();
+    let configure = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::ConfigureMerchantPolicy {
+            merchant_policy: merchant_policy(&h.merchant),
+            merchant: h.merchant,
+            owner: owner.pubkey(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::ConfigureMerchantPolicy {
+            allowlist_only: false,
+            max_active_subscribers: 0,
+            max_snooze_days: 3,
+            billing_anchor: 0,
+            swap_fallback: SwapFallback::Fail,
+            fee_bearer: FeeBearer::Merchant,
+        },
+    );
+    h.process(configure, &[&owner]).await.unwrap();
+
+    let user = h.subscribe(USDC, DAY).await;
+    let snooze = |days: u8| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::SnoozePayment {
+                subscription: user.subscription,
+                merchant_policy: merchant_policy(&h.merchant),
+                user: user.keypair.pubkey(),
+                due_bucket: None,
+                next_due_bucket: None,
+                revenue_forecast: None,
+            },
+            lutrii_recurring::instruction::SnoozePayment { days },
+        )
+    };
+    let too_long = snooze(4);
+    let two_days = snooze(2);
+
+    // Only due payments can be snoozed
+    assert_custom_error(
+        h.process(two_days.clone(), &[&user.keypair]).await,
+        u32::from(ErrorCode::PaymentNotDue),
+    );
+
+    h.warp_forward(DAY).await;
+    assert_custom_error(
+        h.process(too_long, &[&user.keypair]).await,
+        u32::from(ErrorCode::SnoozeLimitExceeded),
+    );
+    h.process(two_days, &[&user.keypair]).await.unwrap();
+    assert_custom_error(
+        h.execute_payment(&user).await,
+        u32::from(ErrorCode::PaymentNotDue),
+    );
+
+    h.warp_forward(2 * DAY).await;
+    h.execute_payment(&user).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert!(!subscription.snoozed);
+    assert_eq!(subscription.payment_count, 1);
+}
+
+#[tokio::test]
+async fn test_change_plan_prorates_and_keeps_history() {
+    let mut h = Harness::new().await;
+    let basic = h.create_plan(1, USDC).await;
+    let pro = h.create_plan(2, 3 * USDC / 2).await;
+    let user = h.subscribe(USDC, DAY).await;
+
+    let change_plan = |plan: Pubkey| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::ChangePlan {
+                subscription: user.subscription,
+                new_plan: plan,
+                merchant_token_account: h.merchant_token_account,
+                accepted_mint: accepted_mint_pda(&h.mint),
+                user: user.keypair.pubkey(),
+                revenue_forecast: None,
+            },
+            lutrii_recurring::instruction::ChangePlan {},
+        )
+    };
+    let to_basic = change_plan(basic);
+    let to_pro = change_plan(pro);
+
+    // Nothing paid yet, so no proration
+    h.process(to_basic.clone(), &[&user.keypair]).await.unwrap();
+    assert_custom_error(
+        h.process(to_basic, &[&user.keypair]).await,
+        u32::from(ErrorCode::SamePlan),
+    );
+    h.warp_forward(DAY).await;
+    h.execute_payment(&user).await.unwrap();
+
+    // Upgrade halfway through a paid cycle: owe half the price difference
+    h.warp_forward(DAY / 2).await;
+    h.process(to_pro, &[&user.keypair]).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.plan, pro);
+    assert_eq!(subscription.amount, 3 * USDC / 2);
+    assert_eq!(subscription.pending_proration, (USDC / 4) as i64);
+    assert_eq!(subscription.payment_count, 1);
+
+    h.warp_forward(DAY / 2).await;
+    h.execute_payment(&user).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.pending_proration, 0);
+    assert_eq!(subscription.payment_count, 2);
+    assert_eq!(subscription.total_paid, USDC + 3 * USDC / 2 + USDC / 4);
+}
+
+#[tokio::test]
+async fn test_invoices_paid_directly_or_collected_via_delegation() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    let owner = h.merchant_owner.insecure_clone();
+    let line_items = vec![
+        LineItem {
+            description_hash: [1; 32],
+            quantity: 3,
+            unit_price: USDC / 10,
+        },
+        LineItem {
+            description_hash: [2; 32],
+            quantity: 1,
+            unit_price: USDC / 2,
+        },
+    ];
+
+    let issue = |invoice_id: u64, subscription: Option<Pubkey>| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::CreateInvoice {
+                invoice: invoice_pda(&h.merchant, invoice_id),
+                merchant: h.merchant,
+                subscription,
+                merchant_token_account: h.merchant_token_account,
+                owner: owner.pubkey(),
+                system_program: system_program::ID,
+            },
+            lutrii_recurring::instruction::CreateInvoice {
+                invoice_id,
+                user: user.keypair.pubkey(),
+                line_items: line_items.clone(),
+            },
+        )
+    };
+    let linked = issue(1, Some(user.subscription));
+    let standalone = issue(2, None);
+    h.process(linked, &[&owner]).await.unwrap();
+    h.process(standalone, &[&owner]).await.unwrap();
+
+    let invoice: Invoice = h.anchor_account(&invoice_pda(&h.merchant, 1)).await;
+    assert_eq!(invoice.total, 8 * USDC / 10);
+    assert_eq!(invoice.status, InvoiceStatus::Open);
+
+    // Linked invoice: anyone can collect through the subscription delegation
+    let (year, month) = h.reporting_period().await;
+    let collect = |invoice_id: u64| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::CollectInvoice {
+                invoice: invoice_pda(&h.merchant, invoice_id),
+                subscription: user.subscription,
+                platform_state: platform_state(),
+                user_token_account: user.token_account,
+                user_delegate: user_delegate(&user.token_account),
+                merchant_token_account: h.merchant_token_account,
+                platform_fee_account: h.platform_fee_account,
+                user_denylist_entry: denylist_entry(&user.keypair.pubkey()),
+                merchant_denylist_entry: denylist_entry(&h.merchant_owner.pubkey()),
+                blocklist_entry: blocklist_entry(&h.merchant, &user.keypair.pubkey()),
+                merchant: h.merchant,
+                mint: h.mint,
+                token_program: spl_token::id(),
+                merchant_policy: merchant_policy(&h.merchant),
+                fee_holidays: fee_holidays_pda(),
+                merchant_report: merchant_report_pda(&h.merchant, &h.mint, year),
+                merchant_statement: merchant_statement_pda(&h.merchant, &h.mint, year, month),
+                refund_liability: refund_liability_pda(&h.merchant, &h.mint),
+                escrow: None,
+                escrow_vault: None,
+                merchant_registry_program: lutrii_merchant_registry::ID,
+                instructions: solana_sdk::sysvar::instructions::ID,
+            },
+            lutrii_recurring::instruction::CollectInvoice {},
+        )
+    };
+    let collect_linked = collect(1);
+    let collect_standalone = collect(2);
+
+    // Not while the merchant blocks the user
+    let entry = blocklist_entry(&h.merchant, &user.keypair.pubkey());
+    let block = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::BlockUser {
+            blocklist_entry: entry,
+            merchant: h.merchant,
+            owner: owner.pubkey(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::BlockUser {
+            user: user.keypair.pubkey(),
+        },
+    );
+    h.process(block, &[&owner]).await.unwrap();
+    assert_custom_error(
+        h.process(collect_linked.clone(), &[]).await,
+        u32::from(ErrorCode::UserBlockedByMerchant),
+    );
+    let unblock = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::UnblockUser {
+            blocklist_entry: entry,
+            merchant: h.merchant,
+            owner: owner.pubkey(),
+        },
+        lutrii_recurring::instruction::UnblockUser {},
+    );
+    h.process(unblock, &[&owner]).await.unwrap();
+
+    let registry_before: Merchant = h.anchor_account(&h.merchant.clone()).await;
+    h.process(collect_linked, &[]).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.total_paid, 8 * USDC / 10);
+
+    // Standalone invoice cannot be pulled, only paid by the user
+    assert_custom_error(
+        h.process(collect_standalone, &[]).await,
+        u32::from(ErrorCode::InvoiceNotLinked),
+    );
+    let pay = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::PayInvoice {
+            invoice: invoice_pda(&h.merchant, 2),
+            platform_state: platform_state(),
+            subscription: None,
+            user: user.keypair.pubkey(),
+            merchant: h.merchant,
+            user_token_account: user.token_account,
+            merchant_token_account: h.merchant_token_account,
+            platform_fee_account: h.platform_fee_account,
+            mint: h.mint,
+            token_program: spl_token::id(),
+            merchant_policy: merchant_policy(&h.merchant),
+            fee_holidays: fee_holidays_pda(),
+            merchant_report: merchant_report_pda(&h.merchant, &h.mint, year),
+            merchant_statement: merchant_statement_pda(&h.merchant, &h.mint, year, month),
+            refund_liability: refund_liability_pda(&h.merchant, &h.mint),
+            merchant_registry_program: lutrii_merchant_registry::ID,
+            instructions: solana_sdk::sysvar::instructions::ID,
+        },
+        lutrii_recurring::instruction::PayInvoice {},
+    );
+    // The payer cannot leave the merchant's policy out to pick the fee schedule
+    let mut without_policy = pay.clone();
+    substitute_account(&mut without_policy, &merchant_policy(&h.merchant), Pubkey::new_unique());
+    assert_custom_error(
+        h.process(without_policy, &[&user.keypair]).await,
+        anchor_lang::error::ErrorCode::ConstraintSeeds as u32,
+    );
+    h.process(pay.clone(), &[&user.keypair]).await.unwrap();
+    assert_custom_error(
+        h.process(pay, &[&user.keypair]).await,
+        u32::from(ErrorCode::InvoiceNotOpen),
+    );
+
+    let invoice: Invoice = h.anchor_account(&invoice_pda(&h.merchant, 2)).await;
+    assert_eq!(invoice.status, InvoiceStatus::Paid);
+    let merchant_balance = h.token_account(&h.merchant_token_account).await.amount;
+    let fee = 8 * USDC / 10 * FEE_BASIS_POINTS as u64 / 10_000;
+    assert_eq!(merchant_balance, 2 * (8 * USDC / 10 - fee));
+
+    // Both invoices count in the merchant's registry stats
+    let registry: Merchant = h.anchor_account(&h.merchant.clone()).await;
+    assert_eq!(registry.total_volume, registry_before.total_volume + 2 * (8 * USDC / 10));
+    assert_eq!(registry.total_transactions, registry_before.total_transactions + 2);
+}
+
+#[tokio::test]
+async fn test_review_requires_payment_history_and_age() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+
+    // Two payments: below the 3-payment threshold
+    for _ in 0..2 {
+        h.warp_forward(DAY).await;
+        h.execute_payment(&user).await.unwrap();
+    }
+    assert_custom_error(
+        h.submit_review(&user, 5).await,
+        u32::from(lutrii_merchant_registry::ErrorCode::InsufficientPaymentHistory),
+    );
+
+    // Third payment at day 3: history is enough but the subscription is too new
+    h.warp_forward(DAY).await;
+    h.execute_payment(&user).await.unwrap();
+    assert_custom_error(
+        h.submit_review(&user, 5).await,
+        u32::from(lutrii_merchant_registry::ErrorCode::SubscriptionTooNew),
+    );
+
+    // Eligibility comes from the subscription's merchant PDA; a subscription
+    // to one merchant cannot review another
+    h.warp_forward(4 * DAY).await;
+    let merchant = h.merchant;
+    let other_merchant = h.register_merchant(&Keypair::new()).await;
+    let mut elsewhere = h.review_ix(&user, 5).await;
+    substitute_account(&mut elsewhere, &merchant, other_merchant);
+    assert_custom_error(
+        h.process(elsewhere, &[&user.keypair]).await,
+        u32::from(lutrii_merchant_registry::ErrorCode::SubscriptionMismatch),
+    );
+
+    // Past 7 days the registry accepts the review
+    h.submit_review(&user, 5).await.unwrap();
+
+    let merchant: Merchant = h.anchor_account(&h.merchant.clone()).await;
+    assert_eq!(merchant.community_score, 20);
+    let review: lutrii_merchant_registry::Review =
+        h.anchor_account(&review_pda(&user.subscription, &Pubkey::default())).await;
+    assert_eq!(review.subscription, user.subscription);
+    assert_eq!(review.plan, Pubkey::default());
+}
+
+#[tokio::test]
+async fn test_review_thresholds_set_by_registry_admin() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC / 10, DAY).await;
+    h.warp_forward(DAY).await;
+    h.execute_payment(&user).await.unwrap();
+
+    let admin = h.ctx.payer.pubkey();
+    let update = |authority: Pubkey, min_payments: u32, min_total_paid: u64, min_subscription_age: i64| {
+        ix(
+            lutrii_merchant_registry::ID,
+            lutrii_merchant_registry::accounts::UpdateReviewThresholds {
+                registry_state: registry_state(),
+                authority,
+                audit_log: None,
+            },
+            lutrii_merchant_registry::instruction::UpdateReviewThresholds {
+                min_payments,
+                min_total_paid,
+                min_subscription_age,
+            },
+        )
+    };
+
+    // Only the admin, and never without a payment
+    let outsider = Keypair::new();
+    h.fund(&outsider.pubkey()).await;
+    assert_custom_error(
+        h.process(update(outsider.pubkey(), 1, 0, 0), &[&outsider]).await,
+        u32::from(lutrii_merchant_registry::ErrorCode::UnauthorizedAdmin),
+    );
+    assert_custom_error(
+        h.process(update(admin, 0, 0, 0), &[]).await,
+        u32::from(lutrii_merchant_registry::ErrorCode::InvalidReviewThresholds),
+    );
+
+    // One small payment a day ago fails the defaults but passes relaxed thresholds
+    assert_custom_error(
+        h.submit_review(&user, 5).await,
+        u32::from(lutrii_merchant_registry::ErrorCode::InsufficientPaymentHistory),
+    );
+    h.process(update(admin, 1, USDC / 10, DAY), &[]).await.unwrap();
+    let registry: lutrii_merchant_registry::RegistryState = h.anchor_account(&registry_state()).await;
+    assert_eq!(registry.min_review_payments, 1);
+    h.submit_review(&user, 5).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_review_rejected_for_low_total_paid() {
+    let mut h = Harness::new().await;
+    // 0.10 USDC per cycle never reaches the 1 USDC total in three payments
+    let user = h.subscribe(USDC / 10, DAY).await;
+
+    for _ in 0..3 {
+        h.warp_forward(DAY).await;
+        h.execute_payment(&user).await.unwrap();
+    }
+    h.warp_forward(7 * DAY).await;
+
+    assert_custom_error(
+        h.submit_review(&user, 4).await,
+        u32::from(lutrii_merchant_registry::ErrorCode::InsufficientTotalPaid),
+    );
+}
+
+#[tokio::test]
+async fn test_merchant_tags_maintain_tag_indexes() {
+    let mut h = Harness::new().await;
+    let owner = h.merchant_owner.pubkey();
+    let merchant = h.merchant;
+    let merchant_tags =
+        Pubkey::find_program_address(&[b"merchant_tags", merchant.as_ref()], &lutrii_merchant_registry::ID).0;
+    let add = |tag: &str| {
+        ix(
+            lutrii_merchant_registry::ID,
+            lutrii_merchant_registry::accounts::AddMerchantTag {
+                merchant,
+                merchant_tags,
+                tag_index: tag_index_pda(tag),
+                owner,
+                system_program: system_program::ID,
+            },
+            lutrii_merchant_registry::instruction::AddMerchantTag { tag: tag.to_string() },
+        )
+    };
+    let remove = |tag: &str| {
+        ix(
+            lutrii_merchant_registry::ID,
+            lutrii_merchant_registry::accounts::RemoveMerchantTag {
+                merchant,
+                merchant_tags,
+                tag_index: tag_index_pda(tag),
+                owner,
+            },
+            lutrii_merchant_registry::instruction::RemoveMerchantTag { tag: tag.to_string() },
+        )
+    };
+    let signer = h.merchant_owner.insecure_clone();
+
+    h.process(add("streaming"), &[&signer]).await.unwrap();
+    h.process(add("video"), &[&signer]).await.unwrap();
+    assert_custom_error(
+        h.process(add("video"), &[&signer]).await,
+        u32::from(lutrii_merchant_registry::ErrorCode::DuplicateTag),
+    );
+    assert_custom_error(
+        h.process(add("Video"), &[&signer]).await,
+        u32::from(lutrii_merchant_registry::ErrorCode::InvalidTag),
+    );
+
+    let tags: lutrii_merchant_registry::MerchantTags = h.anchor_account(&merchant_tags).await;
+    assert_eq!(tags.tags, vec!["streaming".to_string(), "video".to_string()]);
+    let index: lutrii_merchant_registry::TagIndex = h.anchor_account(&tag_index_pda("video")).await;
+    assert_eq!(index.merchants, vec![merchant]);
+
+    // Removing drops the merchant from the index but keeps its other tags
+    h.process(remove("video"), &[&signer]).await.unwrap();
+    let index: lutrii_merchant_registry::TagIndex = h.anchor_account(&tag_index_pda("video")).await;
+    assert!(index.merchants.is_empty());
+    let tags: lutrii_merchant_registry::MerchantTags = h.anchor_account(&merchant_tags).await;
+    assert_eq!(tags.tags, vec!["streaming".to_string()]);
+    assert_custom_error(
+        h.process(remove("video"), &[&signer]).await,
+        u32::from(lutrii_merchant_registry::ErrorCode::TagNotFound),
+    );
+}
+
+#[tokio::test]
+async fn test_executor_reward_paid_out_of_platform_fee() {
+    let mut h = Harness::new().await;
+    let set_reward = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::AdminAction {
+            platform_state: platform_state(),
+            authority: h.ctx.payer.pubkey(),
+            audit_log: None,
+        },
+        lutrii_recurring::instruction::SetExecutorReward {
+            reward_bps: 0,
+            flat_reward: 1_000,
+        },
+    );
+    h.process(set_reward, &[]).await.unwrap();
+
+    let user = h.subscribe(USDC, DAY).await;
+    let executor = Keypair::new();
+    let executor_token_account = h.create_token_account(&executor.pubkey()).await;
+    let with_executor = |mut instruction: Instruction, reward_account: Pubkey| {
+        let count = instruction.accounts.len();
+        instruction.accounts[count - 5] = AccountMeta::new_readonly(executor.pubkey(), true);
+        instruction.accounts[count - 4] = AccountMeta::new(reward_account, false);
+        instruction
+    };
+    h.warp_forward(DAY).await;
+
+    // The reward must go to a token account the executor owns
+    let foreign = with_executor(h.execute_payment_ix(&user).await, h.platform_fee_account);
+    assert_custom_error(
+        h.process(foreign, &[&executor]).await,
+        u32::from(ErrorCode::InvalidExecutorRewardAccount),
+    );
+
+    let fee_before = h.token_account(&h.platform_fee_account.clone()).await.amount;
+    let execute = with_executor(h.execute_payment_ix(&user).await, executor_token_account);
+    h.process(execute, &[&executor]).await.unwrap();
+
+    let reward = h.token_account(&executor_token_account).await.amount;
+    assert_eq!(reward, 1_000);
+    let fee_collected = h.token_account(&h.platform_fee_account.clone()).await.amount - fee_before;
+    let platform: PlatformState = h.anchor_account(&platform_state()).await;
+    assert_eq!(platform.executor_rewards_paid, reward);
+    assert_eq!(fee_collected + reward, USDC * FEE_BASIS_POINTS as u64 / 10_000);
+}
+
+#[tokio::test]
+async fn test_amount_change_needs_user_acceptance() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    let owner = h.merchant_owner.insecure_clone();
+    let propose = |new_amount: u64| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::ProposeAmountChange {
+                subscription: user.subscription,
+                merchant: h.merchant,
+                merchant_token_account: h.merchant_token_account,
+                accepted_mint: accepted_mint_pda(&h.mint),
+                owner: owner.pubkey(),
+            },
+            lutrii_recurring::instruction::ProposeAmountChange { new_amount },
+        )
+    };
+    let accept = |new_amount: u64| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::AcceptAmountChange {
+                subscription: user.subscription,
+                user: user.keypair.pubkey(),
+                revenue_forecast: None,
+            },
+            lutrii_recurring::instruction::AcceptAmountChange { new_amount },
+        )
+    };
+    let raise = propose(3 * USDC / 2);
+    let raise_again = propose(2 * USDC);
+    let accept_raise = accept(3 * USDC / 2);
+    let accept_raise_again = accept(2 * USDC);
+    h.warp_forward(DAY).await;
+    h.execute_payment(&user).await.unwrap();
+
+    // A 50% raise is beyond the variance window, so it waits for the user
+    h.process(raise, &[&owner]).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.amount, USDC);
+    assert_eq!(subscription.pending_amount, 3 * USDC / 2);
+    assert_custom_error(
+        h.process(accept_raise_again.clone(), &[&user.keypair]).await,
+        u32::from(ErrorCode::AmountChangeMismatch),
+    );
+
+    h.process(accept_raise, &[&user.keypair]).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.amount, 3 * USDC / 2);
+    assert_eq!(subscription.original_amount, 3 * USDC / 2);
+    assert_eq!(subscription.pending_amount, 0);
+
+    h.warp_forward(DAY).await;
+    h.execute_payment(&user).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.total_paid, USDC + 3 * USDC / 2);
+
+    // Proposals the user leaves unanswered lapse
+    h.process(raise_again, &[&owner]).await.unwrap();
+    h.warp_forward(lutrii_recurring::AMOUNT_CHANGE_WINDOW_SECONDS).await;
+    assert_custom_error(
+        h.process(accept_raise_again, &[&user.keypair]).await,
+        u32::from(ErrorCode::NoPendingAmountChange),
+    );
+}
+
+#[tokio::test]
+async fn test_user_holds_several_subscriptions_with_one_merchant() {
+    let mut h = Harness::new().await;
+    let first = h.subscribe(USDC, DAY).await;
+    assert_eq!(first.subscription, subscription_pda(&first.keypair.pubkey(), &h.merchant));
+
+    let merchant = h.merchant;
+    let merchant_owner = h.merchant_owner.pubkey();
+    let merchant_token_account = h.merchant_token_account;
+    let subscription = h
+        .subscribe_with(
+            &first.keypair,
+            first.token_account,
+            merchant,
+            &merchant_owner,
+            merchant_token_account,
+            2 * USDC,
+            7 * DAY,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        subscription,
+        lutrii_common::subscription_address_with_nonce(&first.keypair.pubkey(), &merchant, 1)
+    );
+    let second = UserFixture {
+        keypair: first.keypair.insecure_clone(),
+        token_account: first.token_account,
+        subscription,
+    };
+    let counter: SubscriptionCounter = h
+        .anchor_account(&subscription_counter_pda(&first.keypair.pubkey(), &merchant))
+        .await;
+    assert_eq!(counter.next_nonce, 2);
+
+    // Access is answered per subscription
+    let wallet = first.keypair.pubkey();
+    let access = h.verify_access_with_nonce(&wallet, 1, None).await;
+    assert_eq!(access.status, AccessStatus::Active);
+    assert_eq!(access.subscription, second.subscription);
+    let access = h.verify_access_with_nonce(&wallet, 2, None).await;
+    assert_eq!(access.status, AccessStatus::NotSubscribed);
+
+    // Each runs on its own schedule
+    h.warp_forward(DAY).await;
+    h.execute_payment(&first).await.unwrap();
+    assert_custom_error(
+        h.execute_payment(&second).await,
+        u32::from(ErrorCode::PaymentNotDue),
+    );
+    h.warp_forward(6 * DAY).await;
+    h.execute_payment(&second).await.unwrap();
+
+    let first: Subscription = h.anchor_account(&first.subscription).await;
+    let second: Subscription = h.anchor_account(&second.subscription).await;
+    assert_eq!((first.nonce, first.total_paid), (0, USDC));
+    assert_eq!((second.nonce, second.total_paid), (1, 2 * USDC));
+}
+
+#[tokio::test]
+async fn test_plan_subscribers_follow_plan_price_within_variance() {
+    let mut h = Harness::new().await;
+    let plan = h.create_plan(1, USDC).await;
+
+    let keypair = Keypair::new();
+    h.fund(&keypair.pubkey()).await;
+    let token_account = h.create_token_account(&keypair.pubkey()).await;
+    h.mint_to(&token_account, 100 * USDC).await;
+    let merchant = h.merchant;
+    let merchant_owner = h.merchant_owner.pubkey();
+    let merchant_token_account = h.merchant_token_account;
+    let create = h
+        .create_subscription_accounts(
+            &keypair.pubkey(),
+            token_account,
+            merchant,
+            &merchant_owner,
+            merchant_token_account,
+            DAY,
+        )
+        .await;
+    let subscription = create.subscription;
+    h.process(
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::CreateSubscriptionFromPlan { plan, create },
+            lutrii_recurring::instruction::CreateSubscriptionFromPlan {
+                max_per_transaction: 2 * USDC,
+                lifetime_cap: 50 * USDC,
+            },
+        ),
+        &[&keypair],
+    )
+    .await
+    .unwrap();
+    let user = UserFixture {
+        keypair,
+        token_account,
+        subscription,
+    };
+    let terms: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(terms.plan, plan);
+    assert_eq!((terms.amount, terms.frequency_seconds), (USDC, DAY));
+    assert_eq!(terms.merchant_name, "Plan 1");
+
+    let owner = h.merchant_owner.insecure_clone();
+    let update = |price: u64| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::UpdatePlan {
+                plan,
+                merchant,
+                owner: owner.pubkey(),
+            },
+            lutrii_recurring::instruction::UpdatePlan { price, is_active: true },
+        )
+    };
+    h.warp_forward(DAY).await;
+    h.execute_payment(&user).await.unwrap();
+
+    // A 5% increase reaches every subscriber on their next payment, and
+    // keepers cannot leave the plan out to bill the stale price
+    h.process(update(USDC * 105 / 100), &[&owner]).await.unwrap();
+    h.warp_forward(DAY).await;
+    let mut without_plan = h.execute_payment_ix(&user).await;
+    substitute_account(&mut without_plan, &plan, lutrii_recurring::ID);
+    assert_custom_error(
+        h.process(without_plan, &[]).await,
+        u32::from(ErrorCode::PlanRequired),
+    );
+    h.execute_payment(&user).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.amount, USDC * 105 / 100);
+    assert_eq!(subscription.total_paid, USDC + USDC * 105 / 100);
+
+    // Beyond the variance window the user must accept it first
+    h.process(update(3 * USDC / 2), &[&owner]).await.unwrap();
+    h.warp_forward(DAY).await;
+    assert_custom_error(
+        h.execute_payment(&user).await,
+        u32::from(ErrorCode::PriceVarianceExceeded),
+    );
+
+    // The plan sets the amount, so per-subscription amount changes are refused
+    let propose = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::ProposeAmountChange {
+            subscription: user.subscription,
+            merchant,
+            merchant_token_account,
+            accepted_mint: accepted_mint_pda(&h.mint),
+            owner: owner.pubkey(),
+        },
+        lutrii_recurring::instruction::ProposeAmountChange { new_amount: 2 * USDC },
+    );
+    assert_custom_error(
+        h.process(propose, &[&owner]).await,
+        u32::from(ErrorCode::PlanBoundAmount),
+    );
+    let accept = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::AcceptAmountChange {
+            subscription: user.subscription,
+            user: user.keypair.pubkey(),
+            revenue_forecast: None,
+        },
+        lutrii_recurring::instruction::AcceptAmountChange { new_amount: 2 * USDC },
+    );
+    assert_custom_error(
+        h.process(accept, &[&user.keypair]).await,
+        u32::from(ErrorCode::PlanBoundAmount),
+    );
+}
+
+#[tokio::test]
+async fn test_admin_council_approves_emergency_pause() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    let admin = h.ctx.payer.pubkey();
+    let second = Keypair::new();
+    let admin_council = Pubkey::find_program_address(&[b"admin_council"], &lutrii_recurring::ID).0;
+    let proposal = Pubkey::find_program_address(
+        &[b"council_proposal", 0u64.to_le_bytes().as_ref()],
+        &lutrii_recurring::ID,
+    )
+    .0;
+
+    let create = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::CreateAdminCouncil {
+            platform_state: platform_state(),
+            admin_council,
+            pending_config_change: pending_config_change_pda(),
+            authority: admin,
+            system_program: system_program::ID,
+            audit_log: None,
+        },
+        lutrii_recurring::instruction::CreateAdminCouncil {
+            members: vec![admin, second.pubkey()],
+            threshold: 2,
+        },
+    );
+    h.process(create, &[]).await.unwrap();
+    let council: AdminCouncil = h.anchor_account(&admin_council).await;
+    assert_eq!((council.members.len(), council.threshold), (2, 2));
+
+    // The authority alone can no longer pause
+    let single_key_pause = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::AdminAction {
+            platform_state: platform_state(),
+            authority: admin,
+            audit_log: None,
+        },
+        lutrii_recurring::instruction::EmergencyPause {
+            reason: "Single key".to_string(),
+        },
+    );
+    assert_custom_error(
+        h.process(single_key_pause, &[]).await,
+        u32::from(ErrorCode::CouncilApprovalRequired),
+    );
+
+    let propose = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::ProposeCouncilAction {
+            admin_council,
+            proposal,
+            member: admin,
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::ProposeCouncilAction {
+            action: CouncilAction::EmergencyPause {
+                reason: "Oracle incident".to_string(),
+            },
+        },
+    );
+    h.process(propose, &[]).await.unwrap();
+    let execute = || {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::ExecuteCouncilAction {
+                platform_state: platform_state(),
+                admin_council,
+                proposal,
+                pending_config_change: None,
+                new_fee_wallet_usdc: None,
+                new_fee_wallet_usd1: None,
+                audit_log: None,
+            },
+            lutrii_recurring::instruction::ExecuteCouncilAction {},
+        )
+    };
+
+    // One approval of two is not enough
+    assert_custom_error(
+        h.process(execute(), &[]).await,
+        u32::from(ErrorCode::CouncilThresholdNotMet),
+    );
+
+    let approve = |member: Pubkey| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::ApproveCouncilAction {
+                admin_council,
+                proposal,
+                member,
+            },
+            lutrii_recurring::instruction::ApproveCouncilAction {},
+        )
+    };
+    assert_custom_error(
+        h.process(approve(admin), &[]).await,
+        u32::from(ErrorCode::AlreadyApproved),
+    );
+    h.process(approve(second.pubkey()), &[&second]).await.unwrap();
+    let approved: CouncilProposal = h.anchor_account(&proposal).await;
+    assert_eq!(approved.approval_count(), 2);
+
+    h.process(execute(), &[]).await.unwrap();
+    let platform: PlatformState = h.anchor_account(&platform_state()).await;
+    assert_eq!(platform.pause_reason(), "Oracle incident");
+    h.warp_forward(DAY).await;
+    assert_custom_error(
+        h.execute_payment(&user).await,
+        u32::from(ErrorCode::SystemPaused),
+    );
+
+    // Each proposal runs once
+    assert_custom_error(
+        h.process(execute(), &[]).await,
+        u32::from(ErrorCode::CouncilProposalClosed),
+    );
+
+    // Timelocked config changes go through the council as well
+    let single_key_change = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::ScheduleConfigChange {
+            platform_state: platform_state(),
+            pending_config_change: pending_config_change_pda(),
+            authority: admin,
+            new_fee_wallet_usdc: None,
+            new_fee_wallet_usd1: None,
+            system_program: system_program::ID,
+            audit_log: None,
+        },
+        lutrii_recurring::instruction::ScheduleConfigChange {
+            fee_basis_points: Some(300),
+            daily_volume_limit: None,
+            config_change_delay_seconds: None,
+        },
+    );
+    assert_custom_error(
+        h.process(single_key_change, &[]).await,
+        u32::from(ErrorCode::CouncilApprovalRequired),
+    );
+
+    let change = ConfigChange {
+        fee_basis_points: Some(300),
+        ..ConfigChange::default()
+    };
+    let change_proposal = Pubkey::find_program_address(
+        &[b"council_proposal", 1u64.to_le_bytes().as_ref()],
+        &lutrii_recurring::ID,
+    )
+    .0;
+    let propose_change = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::ProposeCouncilAction {
+            admin_council,
+            proposal: change_proposal,
+            member: admin,
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::ProposeCouncilAction {
+            action: CouncilAction::ScheduleConfigChange { change },
+        },
+    );
+    h.process(propose_change, &[]).await.unwrap();
+    let approve_change = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::ApproveCouncilAction {
+            admin_council,
+            proposal: change_proposal,
+            member: second.pubkey(),
+        },
+        lutrii_recurring::instruction::ApproveCouncilAction {},
+    );
+    h.process(approve_change, &[&second]).await.unwrap();
+    let execute_change = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::ExecuteCouncilAction {
+            platform_state: platform_state(),
+            admin_council,
+            proposal: change_proposal,
+            pending_config_change: Some(pending_config_change_pda()),
+            new_fee_wallet_usdc: None,
+            new_fee_wallet_usd1: None,
+            audit_log: None,
+        },
+        lutrii_recurring::instruction::ExecuteCouncilAction {},
+    );
+    h.process(execute_change, &[]).await.unwrap();
+    let pending: PendingConfigChange = h.anchor_account(&pending_config_change_pda()).await;
+    assert!(pending.pending);
+    assert_eq!(pending.change, change);
+}
+
+#[tokio::test]
+async fn test_config_change_waits_out_timelock() {
+    let mut h = Harness::new().await;
+    let admin = h.ctx.payer.pubkey();
+    let pending_config_change =
+        Pubkey::find_program_address(&[b"pending_config_change"], &lutrii_recurring::ID).0;
+    let schedule = |fee_basis_points: u16| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::ScheduleConfigChange {
+                platform_state: platform_state(),
+                pending_config_change,
+                authority: admin,
+                new_fee_wallet_usdc: None,
+                new_fee_wallet_usd1: None,
+                system_program: system_program::ID,
+                audit_log: None,
+            },
+            lutrii_recurring::instruction::ScheduleConfigChange {
+                fee_basis_points: Some(fee_basis_points),
+                daily_volume_limit: Some(DAILY_VOLUME_LIMIT / 2),
+                config_change_delay_seconds: None,
+            },
+        )
+    };
+    let execute = || {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::ExecuteConfigChange {
+                platform_state: platform_state(),
+                pending_config_change,
+            },
+            lutrii_recurring::instruction::ExecuteConfigChange {},
+        )
+    };
+
+    assert_custom_error(
+        h.process(schedule(10_000), &[]).await,
+        u32::from(ErrorCode::FeeTooHigh),
+    );
+    h.process(schedule(300), &[]).await.unwrap();
+
+    // Nothing changes until the 48h delay has passed
+    h.warp_forward(47 * 3_600).await;
+    assert_custom_error(
+        h.process(execute(), &[]).await,
+        u32::from(ErrorCode::ConfigChangeTimelocked),
+    );
+    let platform: PlatformState = h.anchor_account(&platform_state()).await;
+    assert_eq!(platform.fee_basis_points, FEE_BASIS_POINTS);
+
+    h.warp_forward(3_600).await;
+    h.process(execute(), &[]).await.unwrap();
+    let platform: PlatformState = h.anchor_account(&platform_state()).await;
+    assert_eq!(platform.fee_basis_points, 300);
+    assert_eq!(platform.daily_volume_limit, DAILY_VOLUME_LIMIT / 2);
+
+    // A change applies once
+    assert_custom_error(
+        h.process(execute(), &[]).await,
+        u32::from(ErrorCode::NoPendingConfigChange),
+    );
+}
+
+#[tokio::test]
+async fn test_platform_params_update_behind_timelock() {
+    let mut h = Harness::new().await;
+    let admin = h.ctx.payer.pubkey();
+    let pending_config_change =
+        Pubkey::find_program_address(&[b"pending_config_change"], &lutrii_recurring::ID).0;
+    let update = |min_fee: u64, max_fee: u64| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::UpdatePlatformParams {
+                platform_state: platform_state(),
+                pending_config_change,
+                authority: admin,
+                system_program: system_program::ID,
+                audit_log: None,
+            },
+            lutrii_recurring::instruction::UpdatePlatformParams {
+                fee_basis_points: 100,
+                min_fee,
+                max_fee,
+            },
+        )
+    };
+
+    assert_custom_error(
+        h.process(update(USDC, USDC / 2), &[]).await,
+        u32::from(ErrorCode::InvalidFeeSchedule),
+    );
+    h.process(update(USDC / 100, USDC), &[]).await.unwrap();
+
+    // A second update cannot replace the change already scheduled
+    assert_custom_error(
+        h.process(update(USDC / 50, USDC), &[]).await,
+        u32::from(ErrorCode::ConfigChangePending),
+    );
+
+    h.warp_forward(2 * DAY).await;
+    h.process(
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::ExecuteConfigChange {
+                platform_state: platform_state(),
+                pending_config_change,
+            },
+            lutrii_recurring::instruction::ExecuteConfigChange {},
+        ),
+        &[],
+    )
+    .await
+    .unwrap();
+    let platform: PlatformState = h.anchor_account(&platform_state()).await;
+    assert_eq!(
+        (platform.fee_basis_points, platform.min_fee, platform.max_fee),
+        (100, USDC / 100, USDC)
+    );
+}
+
+#[tokio::test]
+async fn test_payments_recorded_in_merchant_registry_stats() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    let before: Merchant = h.anchor_account(&h.merchant.clone()).await;
+    for _ in 0..2 {
+        h.warp_forward(DAY).await;
+        h.execute_payment(&user).await.unwrap();
+    }
+
+    let merchant: Merchant = h.anchor_account(&h.merchant.clone()).await;
+    assert_eq!(merchant.total_transactions, before.total_transactions + 2);
+    assert_eq!(merchant.total_volume, before.total_volume + 2 * USDC);
+    assert_eq!(merchant.community_score, before.community_score + 20);
+
+    // Only lutrii-recurring can sign for its platform PDA
+    let direct = Instruction {
+        program_id: lutrii_merchant_registry::ID,
+        accounts: lutrii_merchant_registry::accounts::RecordTransaction {
+            merchant: h.merchant,
+            recurring_authority: platform_state(),
+            instructions: solana_sdk::sysvar::instructions::ID,
+        }
+        .to_account_metas(None),
+        data: lutrii_merchant_registry::instruction::RecordTransaction {
+            amount: 1_000 * USDC,
+            success: true,
+        }
+        .data(),
+    };
+    assert!(h.process(direct, &[]).await.is_err());
+}
+
+#[tokio::test]
+async fn test_migrate_token_account_moves_delegation() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    h.warp_forward(DAY).await;
+    h.execute_payment(&user).await.unwrap();
+
+    let new_token_account = h.create_token_account(&user.keypair.pubkey()).await;
+    h.mint_to(&new_token_account, 100 * USDC).await;
+    let migrate = |new_token_account: Pubkey| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::MigrateTokenAccount {
+                subscription: user.subscription,
+                old_token_account: user.token_account,
+                old_user_delegate: user_delegate(&user.token_account),
+                new_token_account,
+                new_user_delegate: user_delegate(&new_token_account),
+                user: user.keypair.pubkey(),
+                token_program: spl_token::id(),
+                system_program: system_program::ID,
+            },
+            lutrii_recurring::instruction::MigrateTokenAccount {},
+        )
+    };
+
+    // Only to a token account the user owns
+    let foreign = h.create_token_account(&Pubkey::new_unique()).await;
+    assert!(h.process(migrate(foreign), &[&user.keypair]).await.is_err());
+
+    h.process(migrate(new_token_account), &[&user.keypair]).await.unwrap();
+
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.user_token_account, new_token_account);
+    assert_eq!(subscription.payment_count, 1);
+    let old = h.token_account(&user.token_account).await;
+    assert!(old.delegate.is_none());
+    let new = h.token_account(&new_token_account).await;
+    assert_eq!(new.delegate, Some(user_delegate(&new_token_account)).into());
+    assert_eq!(new.delegated_amount, subscription.lifetime_cap - subscription.total_paid);
+
+    // Payments now draw from the new account
+    let migrated = UserFixture {
+        keypair: user.keypair.insecure_clone(),
+        token_account: new_token_account,
+        subscription: user.subscription,
+    };
+    h.warp_forward(DAY).await;
+    h.execute_payment(&migrated).await.unwrap();
+    assert_eq!(h.token_account(&new_token_account).await.amount, 99 * USDC);
+    assert_eq!(h.token_account(&user.token_account).await.amount, 99 * USDC);
+}
+
+#[tokio::test]
+async fn test_escrow_pays_without_delegation() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, DAY).await;
+    let escrow = escrow_pda(&user.subscription);
+    let vault = escrow_vault_pda(&escrow);
+
+    let deposit = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::DepositEscrow {
+            subscription: user.subscription,
+            escrow,
+            vault,
+            source: user.token_account,
+            user: user.keypair.pubkey(),
+            mint: h.mint,
+            token_program: spl_token::id(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::DepositEscrow { amount: 3 * USDC },
+    );
+    h.process(deposit, &[&user.keypair]).await.unwrap();
+    assert_eq!(h.token_account(&vault).await.amount, 3 * USDC);
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert!(subscription.escrow_funded);
+
+    // Spending the delegation no longer stops collection
+    let revoke = spl_token::instruction::revoke(
+        &spl_token::id(),
+        &user.token_account,
+        &user.keypair.pubkey(),
+        &[],
+    )
+    .unwrap();
+    h.process(revoke, &[&user.keypair]).await.unwrap();
+
+    h.warp_forward(DAY).await;
+
+    // Once prepaid, a keeper cannot leave the escrow out and charge the wallet
+    let mut without_escrow = h.execute_payment_ix(&user).await;
+    substitute_account(&mut without_escrow, &escrow, lutrii_recurring::ID);
+    substitute_account(&mut without_escrow, &vault, lutrii_recurring::ID);
+    assert_custom_error(
+        h.process(without_escrow, &[]).await,
+        u32::from(ErrorCode::EscrowRequired),
+    );
+
+    h.execute_payment(&user).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.payment_count, 1);
+    assert_eq!(subscription.retry_count, 0);
+    assert_eq!(h.token_account(&vault).await.amount, 2 * USDC);
+    assert_eq!(h.token_account(&user.token_account).await.amount, 97 * USDC);
+    let record: SubscriptionEscrow = h.anchor_account(&escrow).await;
+    assert_eq!(record.spent, USDC);
+
+    // The user takes the rest back, and no more than that
+    let mint = h.mint;
+    let withdraw = |amount: u64| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::WithdrawEscrow {
+                escrow,
+                vault,
+                destination: user.token_account,
+                user: user.keypair.pubkey(),
+                mint,
+                token_program: spl_token::id(),
+            },
+            lutrii_recurring::instruction::WithdrawEscrow { amount },
+        )
+    };
+    assert!(h.process(withdraw(3 * USDC), &[&user.keypair]).await.is_err());
+    h.process(withdraw(2 * USDC), &[&user.keypair]).await.unwrap();
+    assert_eq!(h.token_account(&vault).await.amount, 0);
+    assert_eq!(h.token_account(&user.token_account).await.amount, 99 * USDC);
+
+    // An empty escrow records a failed attempt rather than paying
+    h.warp_forward(DAY).await;
+    h.execute_payment(&user).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.payment_count, 1);
+    assert_eq!(subscription.retry_count, 1);
+}
+
+#[tokio::test]
+async fn test_metered_payments_within_usage_cap() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, 30 * DAY).await;
+    let owner = h.merchant_owner.insecure_clone();
+
+    // Nothing is billable until the user sets a cap
+    let uncapped = h.metered_payment_ix(&user, owner.pubkey(), USDC).await;
+    assert!(h.process(uncapped, &[&owner]).await.is_err());
+
+    h.configure_usage_cap(&user, 3 * USDC).await;
+
+    let charge = h.metered_payment_ix(&user, owner.pubkey(), 2 * USDC).await;
+    h.process(charge, &[&owner]).await.unwrap();
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.total_paid, 2 * USDC);
+    assert_eq!(subscription.payment_count, 0);
+    assert_eq!(h.token_account(&user.token_account).await.amount, 98 * USDC);
+
+    // Bounded by max_per_transaction and by the period's cap
+    let over_transaction_cap = h.metered_payment_ix(&user, owner.pubkey(), 3 * USDC).await;
+    assert!(h.process(over_transaction_cap, &[&owner]).await.is_err());
+    let over_period_cap = h.metered_payment_ix(&user, owner.pubkey(), 2 * USDC).await;
+    assert_custom_error(
+        h.process(over_period_cap, &[&owner]).await,
+        u32::from(ErrorCode::UsageCapExceeded),
+    );
+    let charge = h.metered_payment_ix(&user, owner.pubkey(), USDC).await;
+    h.process(charge, &[&owner]).await.unwrap();
+
+    // Only the subscription's merchant may bill it
+    let stranger = Keypair::new();
+    h.register_merchant(&stranger).await;
+    let foreign = h.metered_payment_ix(&user, stranger.pubkey(), USDC).await;
+    assert!(h.process(foreign, &[&stranger]).await.is_err());
+
+    // A new period brings a fresh cap
+    h.warp_forward(30 * DAY).await;
+    let charge = h.metered_payment_ix(&user, owner.pubkey(), 2 * USDC).await;
+    h.process(charge, &[&owner]).await.unwrap();
+    let meter: UsageMeter = h.anchor_account(&usage_meter_pda(&user.subscription)).await;
+    assert_eq!(meter.period_usage, 2 * USDC);
+    assert_eq!(meter.total_usage, 5 * USDC);
+}
+
+#[tokio::test]
+async fn test_metered_payments_keep_payment_records() {
+    let mut h = Harness::new().await;
+    let user = h.subscribe(USDC, 30 * DAY).await;
+    let owner = h.merchant_owner.insecure_clone();
+    let stats = user_stats_pda(&user.keypair.pubkey());
+    let volume = merchant_volume_pda(&h.merchant, &h.mint);
+
+    let mut init_stats = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::InitUserStats {
+            user_stats: stats,
+            user: user.keypair.pubkey(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::InitUserStats {},
+    );
+    init_stats
+        .accounts
+        .push(AccountMeta::new_readonly(user.subscription, false));
+    h.process(init_stats, &[&user.keypair]).await.unwrap();
+    let open_volume = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::OpenMerchantVolume {
+            merchant_volume: volume,
+            merchant: h.merchant,
+            mint: h.mint,
+            payer: h.ctx.payer.pubkey(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::OpenMerchantVolume {},
+    );
+    h.process(open_volume, &[]).await.unwrap();
+    h.configure_usage_cap(&user, 3 * USDC).await;
+
+    // Usage counts in the user's stats, the merchant's rebate volume and
+    // the registry, like a scheduled payment
+    let registry_before: Merchant = h.anchor_account(&h.merchant.clone()).await;
+    let charge = h.metered_payment_ix(&user, owner.pubkey(), 2 * USDC).await;
+    h.process(charge, &[&owner]).await.unwrap();
+    let fee = 2 * USDC * u64::from(FEE_BASIS_POINTS) / 10_000;
+    let user_stats: UserStats = h.anchor_account(&stats).await;
+    assert_eq!((user_stats.payment_count, user_stats.lifetime_spend), (1, 2 * USDC));
+    let merchant_volume: MerchantVolume = h.anchor_account(&volume).await;
+    assert_eq!((merchant_volume.volume, merchant_volume.fees), (2 * USDC, fee));
+    let registry: Merchant = h.anchor_account(&h.merchant.clone()).await;
+    assert_eq!(registry.total_volume, registry_before.total_volume + 2 * USDC);
+    assert_eq!(registry.total_transactions, registry_before.total_transactions + 1);
+
+    // Once prepaid, usage is drawn from the escrow and cannot bypass it
+    let escrow = escrow_pda(&user.subscription);
+    let vault = escrow_vault_pda(&escrow);
+    let deposit = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::DepositEscrow {
+            subscription: user.subscription,
+            escrow,
+            vault,
+            source: user.token_account,
+            user: user.keypair.pubkey(),
+            mint: h.mint,
+            token_program: spl_token::id(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::DepositEscrow { amount: 3 * USDC },
+    );
+    h.process(deposit, &[&user.keypair]).await.unwrap();
+    let mut without_escrow = h.metered_payment_ix(&user, owner.pubkey(), USDC).await;
+    substitute_account(&mut without_escrow, &escrow, lutrii_recurring::ID);
+    substitute_account(&mut without_escrow, &vault, lutrii_recurring::ID);
+    assert_custom_error(
+        h.process(without_escrow, &[&owner]).await,
+        u32::from(ErrorCode::EscrowRequired),
+    );
+    let charge = h.metered_payment_ix(&user, owner.pubkey(), USDC).await;
+    h.process(charge, &[&owner]).await.unwrap();
+    assert_eq!(h.token_account(&vault).await.amount, 2 * USDC);
+    assert_eq!(h.token_account(&user.token_account).await.amount, 95 * USDC);
+
+    // Users the merchant blocked cannot be billed for usage either
+    let block = ix(
+        lutrii_recurring::ID,
+        lutrii_recurring::accounts::BlockUser {
+            blocklist_entry: blocklist_entry(&h.merchant, &user.keypair.pubkey()),
+            merchant: h.merchant,
+            owner: owner.pubkey(),
+            system_program: system_program::ID,
+        },
+        lutrii_recurring::instruction::BlockUser {
+            user: user.keypair.pubkey(),
+        },
+    );
+    h.process(block, &[&owner]).await.unwrap();
+    let blocked = h.metered_payment_ix(&user, owner.pubkey(), USDC).await;
+    assert_custom_error(
+        h.process(blocked, &[&owner]).await,
+        u32::from(ErrorCode::UserBlockedByMerchant),
+    );
+}
+
+#[tokio::test]
+async fn test_metered_payments_follow_fee_treatment() {
+    let mut h = Harness::new().await;
+    let owner = h.merchant_owner.insecure_clone();
+    let configure_fee_bearer = |fee_bearer: FeeBearer| {
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::ConfigureMerchantPolicy {
+                merchant_policy: merchant_policy(&h.merchant),
+                merchant: h.merchant,
+                owner: owner.pubkey(),
+                system_program: system_program::ID,
+            },
+            lutrii_recurring::instruction::ConfigureMerchantPolicy {
+                allowlist_only: false,
+                max_active_subscribers: 0,
+                max_snooze_days: 0,
+                billing_anchor: 0,
+                swap_fallback: SwapFallback::Fail,
+                fee_bearer,
+            },
+        )
+    };
+    let to_user = configure_fee_bearer(FeeBearer::User);
+    let to_merchant = configure_fee_bearer(FeeBearer::Merchant);
+    h.process(to_user, &[&owner]).await.unwrap();
+    let bearer = h.subscribe(USDC, 30 * DAY).await;
+    h.process(to_merchant, &[&owner]).await.unwrap();
+    let exempt = h.subscribe(USDC, 30 * DAY).await;
+    h.process(
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::SetFeeExemption {
+                platform_state: platform_state(),
+                authority: h.ctx.payer.pubkey(),
+                subscription: Some(exempt.subscription),
+                merchant_policy: None,
+            },
+            lutrii_recurring::instruction::SetFeeExemption { fee_exempt: true },
+        ),
+        &[],
+    )
+    .await
+    .unwrap();
+    h.configure_usage_cap(&bearer, 3 * USDC).await;
+    h.configure_usage_cap(&exempt, 3 * USDC).await;
+
+    let fee = USDC * u64::from(FEE_BASIS_POINTS) / 10_000;
+    let (fee_account, merchant_account) = (h.platform_fee_account, h.merchant_token_account);
+
+    // The user bearing the fee pays it on top; the merchant nets the usage
+    let fee_before = h.token_account(&fee_account).await.amount;
+    let merchant_before = h.token_account(&merchant_account).await.amount;
+    let charge = h.metered_payment_ix(&bearer, owner.pubkey(), USDC).await;
+    h.process(charge, &[&owner]).await.unwrap();
+    assert_eq!(h.token_account(&bearer.token_account).await.amount, 100 * USDC - USDC - fee);
+    assert_eq!(h.token_account(&merchant_account).await.amount, merchant_before + USDC);
+    assert_eq!(h.token_account(&fee_account).await.amount, fee_before + fee);
+    let subscription: Subscription = h.anchor_account(&bearer.subscription).await;
+    assert_eq!(subscription.total_paid, USDC + fee);
+
+    // An exempt subscription pays no platform fee and the waiver is recorded
+    let fee_before = h.token_account(&fee_account).await.amount;
+    let merchant_before = h.token_account(&merchant_account).await.amount;
+    let charge = h.metered_payment_ix(&exempt, owner.pubkey(), USDC).await;
+    h.process(charge, &[&owner]).await.unwrap();
+    assert_eq!(h.token_account(&merchant_account).await.amount, merchant_before + USDC);
+    assert_eq!(h.token_account(&fee_account).await.amount, fee_before);
+    let platform: PlatformState = h.anchor_account(&platform_state()).await;
+    assert_eq!((platform.fee_exempt_payments, platform.fees_waived), (1, fee));
+}
+
+#[tokio::test]
+async fn test_promo_discounts_first_payments() {
+    let mut h = Harness::new().await;
+    let owner = h.merchant_owner.insecure_clone();
+    let (merchant, merchant_token_account) = (h.merchant, h.merchant_token_account);
+    let promo = Pubkey::find_program_address(
+        &[b"promo", merchant.as_ref(), b"WELCOME"],
+        &lutrii_recurring::ID,
+    )
+    .0;
+    h.process(
+        ix(
+            lutrii_recurring::ID,
+            lutrii_recurring::accounts::CreatePromo {
+                promo,
+                merchant,
+                owner: owner.pubkey(),
+                system_program: system_program::ID,
+            },
+            lutrii_recurring::instruction::CreatePromo {
+                code: "WELCOME".to_string(),
+                discount: PromoDiscount::Percent { basis_points: 5_000 },
+                discounted_payments: 2,
+                max_redemptions: 1,
+                expires_at: 0,
+            },
+        ),
+        &[&owner],
+    )
+    .await
+    .unwrap();
+
+    let keypair = Keypair::new();
+    h.fund(&keypair.pubkey()).await;
+    let token_account = h.create_token_account(&keypair.pubkey()).await;
+    h.mint_to(&token_account, 100 * USDC).await;
+    let mut accounts = h
+        .create_subscription_accounts(
+            &keypair.pubkey(),
+            token_account,
+            merchant,
+            &owner.pubkey(),
+            merchant_token_account,
+            DAY,
+        )
+        .await;
+    accounts.promo = Some(promo);
+    h.process(
+        ix(
+            lutrii_recurring::ID,
+            accounts,
+            lutrii_recurring::instruction::CreateSubscription {
+                amount: USDC,
+                frequency_seconds: DAY,
+                max_per_transaction: USDC,
+                lifetime_cap: 12 * USDC,
+                merchant_name: "Lutrii Promo".to_string(),
+                options: CreateOptions::default(),
+            },
+        ),
+        &[&keypair],
+    )
+    .await
+    .unwrap();
+
+    let user = UserFixture {
+        subscription: subscription_pda(&keypair.pubkey(), &merchant),
+        keypair,
+        token_account,
+    };
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.discount_amount, USDC / 2);
+    assert_eq!(subscription.discount_payments_remaining, 2);
+    let promo_account: Promo = h.anchor_account(&promo).await;
+    assert_eq!(promo_account.redemptions, 1);
+
+    // Two half-price payments, then the full amount
+    for expected in [USDC / 2, USDC / 2, USDC] {
+        let before = h.token_account(&token_account).await.amount;
+        h.warp_forward(DAY).await;
+        h.execute_payment(&user).await.unwrap();
+        assert_eq!(before - h.token_account(&token_account).await.amount, expected);
+    }
+    let subscription: Subscription = h.anchor_account(&user.subscription).await;
+    assert_eq!(subscription.discount_payments_remaining, 0);
+    assert_eq!(subscription.total_paid, 2 * USDC);
+}
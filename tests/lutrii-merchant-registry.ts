@@ -367,8 +367,8 @@ describe("lutrii-merchant-registry", () => {
       [review] = PublicKey.findProgramAddressSync(
         [
           Buffer.from(REVIEW_SEED),
-          reviewer.publicKey.toBuffer(),
-          merchant.toBuffer(),
+          mockSubscription.toBuffer(),
+          PublicKey.default.toBuffer(), // custom terms, no plan
         ],
         program.programId
       );
@@ -301,7 +301,11 @@ describe("lutrii-recurring", () => {
         undefined,
         TOKEN_2022_PROGRAM_ID
       );
-      assert.equal(userAccount.delegate?.toBase58(), subscription.toBase58());
+      const [userDelegate] = PublicKey.findProgramAddressSync(
+        [Buffer.from("user_delegate"), userTokenAccount.toBuffer()],
+        program.programId
+      );
+      assert.equal(userAccount.delegate?.toBase58(), userDelegate.toBase58());
       assert.equal(userAccount.delegatedAmount.toString(), lifetimeCap.toString());
     });
 